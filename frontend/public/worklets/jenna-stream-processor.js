@@ -0,0 +1,81 @@
+import './text-codec-polyfill.js'
+import { initSync, StreamProcessor } from '../wasm/jenna/jenna.js'
+
+const RENDER_QUANTUM = 128
+
+/**
+ * Resamples microphone audio to 16 kHz in wasm and posts fixed-size
+ * 16-bit PCM frames to the main thread.
+ *
+ * processorOptions:
+ * - wasmBytes: contents of jenna_bg.wasm (worklets cannot fetch)
+ * - outputRate, frameLength: passed to StreamProcessor
+ * - vadThresholdDb: enables voice activity detection when set
+ */
+class JennaStreamProcessor extends AudioWorkletProcessor {
+  constructor(options) {
+    super()
+    const { wasmBytes, outputRate, frameLength, vadThresholdDb } = options.processorOptions
+    initSync(wasmBytes)
+
+    this.stream = new StreamProcessor(sampleRate, outputRate, frameLength)
+    if (vadThresholdDb !== undefined) {
+      this.stream.setVad(vadThresholdDb)
+    }
+    this.mono = new Float32Array(RENDER_QUANTUM)
+    this.stopped = false
+
+    this.port.onmessage = (event) => {
+      switch (event.data.type) {
+        case 'clear':
+          this.stream.clear()
+          break
+        case 'vad':
+          this.stream.setVad(event.data.thresholdDb)
+          break
+        case 'stop':
+          this.stopped = true
+          break
+      }
+    }
+  }
+
+  process(inputs) {
+    if (this.stopped) {
+      this.stream.free()
+      return false
+    }
+
+    const channels = inputs[0]
+    if (!channels || channels.length === 0) {
+      return true
+    }
+
+    // Downmix to mono
+    this.mono.fill(0)
+    for (const channel of channels) {
+      for (let i = 0; i < RENDER_QUANTUM; i++) {
+        this.mono[i] += channel[i] / channels.length
+      }
+    }
+
+    let ready = this.stream.process(this.mono)
+    while (ready-- > 0) {
+      const pcm = this.stream.nextFramePcm16()
+      this.port.postMessage(
+        {
+          type: 'frame',
+          pcm,
+          level: this.stream.level,
+          peak: this.stream.peak,
+          isSpeech: this.stream.isSpeech,
+          dropped: this.stream.dropped,
+        },
+        [pcm.buffer],
+      )
+    }
+    return true
+  }
+}
+
+registerProcessor('jenna-stream-processor', JennaStreamProcessor)
@@ -0,0 +1,18 @@
+// AudioWorkletGlobalScope has no TextDecoder/TextEncoder, which the
+// wasm-bindgen glue creates when it loads. Only ASCII is needed there
+// (error messages), so a minimal stand-in is enough.
+if (typeof globalThis.TextDecoder === 'undefined') {
+  globalThis.TextDecoder = class {
+    decode(bytes) {
+      return bytes ? String.fromCharCode(...bytes) : ''
+    }
+  }
+}
+
+if (typeof globalThis.TextEncoder === 'undefined') {
+  globalThis.TextEncoder = class {
+    encode(text = '') {
+      return Uint8Array.from(text, (c) => c.charCodeAt(0) & 0xff)
+    }
+  }
+}
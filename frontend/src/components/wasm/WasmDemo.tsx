@@ -1,158 +1,188 @@
-import React, { useState, useEffect } from 'react'
-import { useWebAssemblyModule } from '@hooks/useWebAssembly'
-import { useWasmStore, useWasmActions } from '@store/wasmStore'
-import { readStringFromMemory, copyStringToMemory } from '@utils/wasmUtils'
+import React, { useState, useEffect, useRef, useCallback } from 'react'
+import { useJennaWasm } from '@hooks/useJennaWasm'
+import type { LevelMeter, SpectrumAnalyzer } from '@hooks/useJennaWasm'
 
 interface WasmDemoProps {
   className?: string
 }
 
+/** Samples analyzed per animation frame */
+const FRAME_SIZE = 2048
+
+/** Bars drawn in the spectrum display */
+const SPECTRUM_BARS = 48
+
+/** Lowest level shown by the meters and the spectrum */
+const FLOOR_DB = -90
+
+// Map a dB value onto 0..1 for drawing
+const normalizeDb = (db: number) => Math.min(1, Math.max(0, (db - FLOOR_DB) / -FLOOR_DB))
+
 const WasmDemo: React.FC<WasmDemoProps> = ({ className }) => {
-  // Example of using the useWebAssemblyModule hook directly
-  const { status: hookStatus, wasm, callFunction } = useWebAssemblyModule('/wasm/example.wasm')
-  
-  // Example of using the wasmStore
-  const { loadModule, callFunction: storeCallFunction } = useWasmActions()
-  const modules = useWasmStore(state => Object.values(state.modules))
-  
-  const [result, setResult] = useState<number | null>(null)
-  const [stringResult, setStringResult] = useState<string | null>(null)
-  const [input1, setInput1] = useState(5)
-  const [input2, setInput2] = useState(3)
-  const [textInput, setTextInput] = useState('')
-  
-  // Load a module using the store when the component mounts
-  useEffect(() => {
-    loadModule('example', '/wasm/example.wasm', {
-      name: 'Example Module',
-      description: 'A simple example WebAssembly module'
+  const { status, jenna, error } = useJennaWasm()
+
+  const [running, setRunning] = useState(false)
+  const [level, setLevel] = useState({ rms: FLOOR_DB, peak: FLOOR_DB })
+  const [micError, setMicError] = useState<string | null>(null)
+
+  const canvasRef = useRef<HTMLCanvasElement>(null)
+  const waveformRef = useRef<HTMLCanvasElement>(null)
+  const cleanupRef = useRef<(() => void) | null>(null)
+
+  const stop = useCallback(() => {
+    cleanupRef.current?.()
+    cleanupRef.current = null
+    setRunning(false)
+  }, [])
+
+  // Release the microphone and wasm objects when unmounting
+  useEffect(() => stop, [stop])
+
+  const drawSpectrum = (bands: Float32Array) => {
+    const canvas = canvasRef.current
+    const context = canvas?.getContext('2d')
+    if (!canvas || !context) {
+      return
+    }
+
+    context.clearRect(0, 0, canvas.width, canvas.height)
+    context.fillStyle = '#3b82f6'
+    const barWidth = canvas.width / bands.length
+    bands.forEach((db, bar) => {
+      const height = normalizeDb(db) * canvas.height
+      context.fillRect(bar * barWidth + 1, canvas.height - height, barWidth - 2, height)
     })
-  }, [loadModule])
-  
-  // Example of calling a simple add function from WebAssembly
-  const handleCalculate = () => {
-    // Using the hook
-    if (hookStatus === 'ready' && wasm) {
-      const hookResult = callFunction<number>('add', input1, input2)
-      setResult(hookResult || null)
+  }
+
+  // `peaks` holds a min/max pair per canvas column
+  const drawWaveform = (peaks: Float32Array) => {
+    const canvas = waveformRef.current
+    const context = canvas?.getContext('2d')
+    if (!canvas || !context) {
+      return
     }
-    
-    // Using the store
-    const storeResult = storeCallFunction<number>('example', 'add', input1, input2)
-    if (storeResult !== undefined) {
-      setResult(storeResult)
+
+    context.clearRect(0, 0, canvas.width, canvas.height)
+    context.fillStyle = '#10b981'
+    const middle = canvas.height / 2
+    for (let column = 0; column < peaks.length / 2; column++) {
+      const top = middle - peaks[column * 2 + 1] * middle
+      const bottom = middle - peaks[column * 2] * middle
+      context.fillRect(column, top, 1, Math.max(1, bottom - top))
     }
   }
-  
-  // Example of passing a string to WebAssembly and getting a string back
-  const handleProcessString = () => {
-    if (hookStatus === 'ready' && wasm && wasm.exports) {
-      // Get memory view
-      const memory = new Uint8Array((wasm.exports.memory as WebAssembly.Memory).buffer)
-      
-      // Allocate memory for the input string
-      const ptr = (wasm.exports.allocate as Function)(textInput.length + 1)
-      
-      // Copy the string to WebAssembly memory
-      copyStringToMemory(memory, textInput, ptr)
-      
-      // Call the WebAssembly function
-      const resultPtr = (wasm.exports.processString as Function)(ptr, textInput.length)
-      
-      // Read the result string from WebAssembly memory
-      const result = readStringFromMemory(memory, resultPtr)
-      setStringResult(result)
-      
-      // Free the allocated memory
-      (wasm.exports.deallocate as Function)(ptr)
-      (wasm.exports.deallocate as Function)(resultPtr)
+
+  const start = async () => {
+    if (!jenna) {
+      return
+    }
+    setMicError(null)
+
+    let stream: MediaStream
+    try {
+      stream = await navigator.mediaDevices.getUserMedia({ audio: true })
+    } catch (err) {
+      setMicError(err instanceof Error ? err.message : String(err))
+      return
     }
+
+    const audioContext = new AudioContext()
+    const source = audioContext.createMediaStreamSource(stream)
+    const analyser = audioContext.createAnalyser()
+    analyser.fftSize = FRAME_SIZE
+    source.connect(analyser)
+
+    const meter: LevelMeter = new jenna.LevelMeter(audioContext.sampleRate)
+    const spectrumAnalyzer: SpectrumAnalyzer = new jenna.SpectrumAnalyzer(audioContext.sampleRate)
+    const frame = new Float32Array(FRAME_SIZE)
+    let lastTime = audioContext.currentTime
+    let animation = 0
+
+    const render = () => {
+      analyser.getFloatTimeDomainData(frame)
+      // Only meter the samples that arrived since the last animation frame
+      const now = audioContext.currentTime
+      const fresh = Math.min(FRAME_SIZE, Math.round((now - lastTime) * audioContext.sampleRate))
+      lastTime = now
+      if (fresh > 0) {
+        meter.process(frame.subarray(FRAME_SIZE - fresh))
+        setLevel({ rms: meter.rms, peak: meter.peak })
+      }
+      drawSpectrum(spectrumAnalyzer.bands(frame, SPECTRUM_BARS, undefined, undefined, FLOOR_DB))
+      if (waveformRef.current) {
+        drawWaveform(jenna.waveformPeaks(frame, waveformRef.current.width))
+      }
+      animation = requestAnimationFrame(render)
+    }
+    animation = requestAnimationFrame(render)
+
+    cleanupRef.current = () => {
+      cancelAnimationFrame(animation)
+      stream.getTracks().forEach(track => track.stop())
+      audioContext.close()
+      meter.free()
+      spectrumAnalyzer.free()
+    }
+    setRunning(true)
   }
-  
+
   return (
     <div className={`p-4 bg-white dark:bg-gray-800 rounded-lg shadow ${className}`}>
-      <h2 className="text-xl font-semibold mb-4">WebAssembly Demo</h2>
-      
+      <h2 className="text-xl font-semibold mb-4">WebAssembly Audio Demo</h2>
+
       <div className="mb-6">
         <h3 className="text-lg font-medium mb-2">Module Status</h3>
         <div className="grid grid-cols-2 gap-4">
           <div>
-            <p className="text-sm font-medium text-gray-500 dark:text-gray-400">Hook Status:</p>
-            <p className={`font-mono ${hookStatus === 'ready' ? 'text-green-500' : hookStatus === 'error' ? 'text-red-500' : 'text-yellow-500'}`}>
-              {hookStatus}
+            <p className="text-sm font-medium text-gray-500 dark:text-gray-400">Status:</p>
+            <p className={`font-mono ${status === 'ready' ? 'text-green-500' : status === 'error' ? 'text-red-500' : 'text-yellow-500'}`}>
+              {status}
             </p>
           </div>
           <div>
-            <p className="text-sm font-medium text-gray-500 dark:text-gray-400">Store Modules:</p>
-            <p className="font-mono">{modules.length}</p>
+            <p className="text-sm font-medium text-gray-500 dark:text-gray-400">Version:</p>
+            <p className="font-mono">{jenna ? jenna.version() : '-'}</p>
           </div>
         </div>
+        {error && <p className="mt-2 text-sm text-red-500">{error.message}</p>}
       </div>
-      
-      <div className="mb-6">
-        <h3 className="text-lg font-medium mb-2">Simple Calculation</h3>
-        <div className="flex items-center space-x-2 mb-4">
-          <input
-            type="number"
-            value={input1}
-            onChange={(e) => setInput1(parseInt(e.target.value) || 0)}
-            className="w-16 p-2 border rounded dark:bg-gray-700 dark:border-gray-600"
-          />
-          <span>+</span>
-          <input
-            type="number"
-            value={input2}
-            onChange={(e) => setInput2(parseInt(e.target.value) || 0)}
-            className="w-16 p-2 border rounded dark:bg-gray-700 dark:border-gray-600"
-          />
-          <button
-            onClick={handleCalculate}
-            disabled={hookStatus !== 'ready'}
-            className="px-4 py-2 bg-blue-500 text-white rounded disabled:opacity-50"
-          >
-            Calculate
-          </button>
-        </div>
-        {result !== null && (
-          <div className="mt-2">
-            <p className="text-sm font-medium text-gray-500 dark:text-gray-400">Result:</p>
-            <p className="font-mono text-lg">{result}</p>
-          </div>
-        )}
-      </div>
-      
+
       <div className="mb-6">
-        <h3 className="text-lg font-medium mb-2">String Processing</h3>
-        <div className="flex flex-col space-y-2 mb-4">
-          <input
-            type="text"
-            value={textInput}
-            onChange={(e) => setTextInput(e.target.value)}
-            placeholder="Enter text to process"
-            className="p-2 border rounded dark:bg-gray-700 dark:border-gray-600"
-          />
-          <button
-            onClick={handleProcessString}
-            disabled={hookStatus !== 'ready'}
-            className="px-4 py-2 bg-blue-500 text-white rounded disabled:opacity-50"
-          >
-            Process String
-          </button>
+        <h3 className="text-lg font-medium mb-2">Microphone</h3>
+        <button
+          onClick={running ? stop : start}
+          disabled={status !== 'ready'}
+          className="px-4 py-2 bg-blue-500 text-white rounded disabled:opacity-50 mb-4"
+        >
+          {running ? 'Stop' : 'Start'}
+        </button>
+        {micError && <p className="text-sm text-red-500 mb-2">{micError}</p>}
+
+        <div className="space-y-2 mb-4">
+          {(['rms', 'peak'] as const).map(key => (
+            <div key={key} className="flex items-center space-x-2">
+              <span className="w-12 text-sm font-medium text-gray-500 dark:text-gray-400 uppercase">{key}</span>
+              <div className="flex-1 h-3 bg-gray-200 dark:bg-gray-700 rounded">
+                <div
+                  className={`h-3 rounded ${level[key] > -3 ? 'bg-red-500' : 'bg-green-500'}`}
+                  style={{ width: `${normalizeDb(level[key]) * 100}%` }}
+                />
+              </div>
+              <span className="w-20 text-right font-mono text-sm">{level[key].toFixed(1)} dB</span>
+            </div>
+          ))}
         </div>
-        {stringResult && (
-          <div className="mt-2">
-            <p className="text-sm font-medium text-gray-500 dark:text-gray-400">Result:</p>
-            <p className="font-mono">{stringResult}</p>
-          </div>
-        )}
+
+        <canvas ref={waveformRef} width={480} height={60} className="w-full h-16 bg-gray-100 dark:bg-gray-900 rounded mb-2" />
+        <canvas ref={canvasRef} width={480} height={120} className="w-full h-32 bg-gray-100 dark:bg-gray-900 rounded" />
       </div>
-      
+
       <div className="text-sm text-gray-500 dark:text-gray-400 mt-4">
-        <p>Note: This demo requires WebAssembly modules to be available at the specified paths.</p>
-        <p>Check the browser console for any errors loading the modules.</p>
+        <p>Note: This demo requires the audio module, built with <code>npm run wasm:build</code>.</p>
+        <p>Audio is analyzed locally and never leaves the browser.</p>
       </div>
     </div>
   )
 }
 
-export default WasmDemo
\ No newline at end of file
+export default WasmDemo
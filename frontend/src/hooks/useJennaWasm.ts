@@ -0,0 +1,190 @@
+import { useEffect, useState } from 'react'
+import type { ClientMessage, MessageEncoding, ServerMessage } from '@types/index'
+
+type JennaWasmStatus = 'loading' | 'ready' | 'error'
+
+/**
+ * Object backed by wasm memory; call `free()` once it is no longer needed
+ */
+interface WasmObject {
+  free(): void
+}
+
+export interface SpectrumAnalyzer extends WasmObject {
+  readonly sampleRate: number
+  magnitudes(frame: Float32Array): Float32Array
+  decibels(frame: Float32Array, floorDb?: number): Float32Array
+  bands(frame: Float32Array, count: number, minFrequency?: number, maxFrequency?: number, floorDb?: number): Float32Array
+  binFrequency(bin: number, frameLength: number): number
+  setWindow(window: string): void
+}
+
+export interface Filter extends WasmObject {
+  readonly numSections: number
+  process(samples: Float32Array): void
+  reset(): void
+}
+
+export interface Resampler extends WasmObject {
+  readonly inputRate: number
+  readonly outputRate: number
+  process(samples: Float32Array): Float32Array
+}
+
+export interface LevelMeter extends WasmObject {
+  readonly rms: number
+  readonly peak: number
+  process(samples: Float32Array): number
+  reset(): void
+}
+
+export interface StreamProcessor extends WasmObject {
+  readonly available: number
+  readonly dropped: number
+  readonly outputRate: number
+  readonly frameLength: number
+  readonly level: number
+  readonly peak: number
+  readonly isSpeech: boolean
+  process(input: Float32Array): number
+  nextFrame(): Float32Array | undefined
+  nextFramePcm16(): Int16Array | undefined
+  setVad(thresholdDb?: number): void
+  clear(): void
+}
+
+export interface VoiceActivityDetector extends WasmObject {
+  readonly isSpeech: boolean
+  readonly noiseFloor: number | undefined
+  process(frame: Int16Array): boolean
+  reset(): void
+}
+
+export type SpeechBoundary = 'start' | 'end'
+
+export interface SpeechSegmenter extends WasmObject {
+  readonly inUtterance: boolean
+  readonly isSpeech: boolean
+  readonly sampleRate: number
+  process(frame: Int16Array): SpeechBoundary | undefined
+  takeAudio(): Int16Array
+  reset(): void
+}
+
+export interface OpusEncoder extends WasmObject {
+  readonly sampleRate: number
+  readonly frameLength: number
+  readonly dropped: number
+  encode(pcm: Int16Array): number
+  nextPacket(): Uint8Array | undefined
+  setBitrate(bitrate: number): void
+  reset(): void
+}
+
+export interface WakeWordDetector extends WasmObject {
+  readonly keywords: string[]
+  readonly sampleRate: number
+  readonly score: number
+  addKeyword(name: string, classifier: Uint8Array, sensitivity?: number): number
+  process(pcm: Int16Array): string | undefined
+  reset(): void
+}
+
+/**
+ * Exports of the audio module built from `frontend/wasm`
+ */
+export interface JennaWasm {
+  version(): string
+  getWindow(window: string, length: number): Float32Array
+  waveformPeaks(samples: Float32Array, columns: number): Float32Array
+  SpectrumAnalyzer: new (sampleRate: number, window?: string) => SpectrumAnalyzer
+  Filter: new (kind: string, band: string, order: number, cutoffs: Float64Array, sampleRate: number, rippleDb?: number) => Filter
+  firFilter(samples: Float32Array, kernel: Float32Array): Float32Array
+  designFir(numTaps: number, frequencies: Float32Array, gains: Float32Array, sampleRate: number, window?: string): Float32Array
+  Resampler: new (inputRate: number, outputRate: number) => Resampler
+  resample(samples: Float32Array, inputRate: number, outputRate: number): Float32Array
+  toPcm16(samples: Float32Array): Int16Array
+  LevelMeter: new (sampleRate: number) => LevelMeter
+  rmsDbfs(samples: Float32Array): number
+  peakDbfs(samples: Float32Array): number
+  encodeMessage(message: ClientMessage | ServerMessage, encoding?: MessageEncoding): Uint8Array
+  decodeMessage(data: Uint8Array, encoding?: MessageEncoding): ClientMessage | ServerMessage
+  normalizeText(text: string, language?: string): string
+  isSsml(text: string): boolean
+  validateSsml(text: string): string | undefined
+  StreamProcessor: new (inputRate: number, outputRate?: number, frameLength?: number) => StreamProcessor
+  VoiceActivityDetector: new (thresholdDb?: number) => VoiceActivityDetector
+  SpeechSegmenter: new (sampleRate: number, thresholdDb?: number, silenceTimeout?: number, maxUtterance?: number, preRoll?: number) => SpeechSegmenter
+  /** Missing unless the module is built with the opus feature */
+  OpusEncoder?: new (sampleRate?: number, bitrate?: number) => OpusEncoder
+  /** Missing when the module is built without the wake-word feature */
+  WakeWordDetector?: new (melspectrogram: Uint8Array, embedding: Uint8Array) => WakeWordDetector
+}
+
+/**
+ * URL of the wasm-bindgen glue written by `npm run wasm:build`
+ */
+export const JENNA_WASM_URL = '/wasm/jenna/jenna.js'
+
+let modulePromise: Promise<JennaWasm> | null = null
+
+/**
+ * Load and initialize the audio module once, sharing it between callers
+ */
+export function loadJennaWasm(): Promise<JennaWasm> {
+  if (!modulePromise) {
+    modulePromise = import(/* @vite-ignore */ JENNA_WASM_URL)
+      .then(async (module) => {
+        await module.default()
+        return module as unknown as JennaWasm
+      })
+      .catch((err) => {
+        // Allow a later retry, e.g. after the module has been built
+        modulePromise = null
+        throw err
+      })
+  }
+  return modulePromise
+}
+
+/**
+ * Custom hook giving access to the wasm audio module
+ *
+ * @example
+ * ```tsx
+ * const { status, jenna } = useJennaWasm()
+ *
+ * if (status === 'ready' && jenna) {
+ *   const level = jenna.rmsDbfs(samples)
+ * }
+ * ```
+ */
+export function useJennaWasm() {
+  const [status, setStatus] = useState<JennaWasmStatus>('loading')
+  const [jenna, setJenna] = useState<JennaWasm | null>(null)
+  const [error, setError] = useState<Error | null>(null)
+
+  useEffect(() => {
+    let cancelled = false
+
+    loadJennaWasm()
+      .then((module) => {
+        if (!cancelled) {
+          setJenna(module)
+          setStatus('ready')
+        }
+      })
+      .catch((err) => {
+        if (!cancelled) {
+          setError(err instanceof Error ? err : new Error(String(err)))
+          setStatus('error')
+        }
+      })
+
+    return () => {
+      cancelled = true
+    }
+  }, [])
+
+  return { status, jenna, error }
+}
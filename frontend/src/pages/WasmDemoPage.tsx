@@ -14,9 +14,9 @@ const WasmDemoPage: React.FC = () => {
         </p>
         
         <p className="mb-4">
-          The WebAssembly module used in this demo is a simple example that provides basic
-          mathematical operations and string processing functions. It is written in Rust and
-          compiled to WebAssembly using wasm-pack.
+          The WebAssembly module used in this demo is the audio module in <code>frontend/wasm</code>.
+          It compiles the same FFT, filtering, resampling and level-metering code used by the native
+          engines, so the web client can preprocess microphone audio before streaming it to the server.
         </p>
         
         <p className="text-sm text-gray-500">
@@ -49,8 +49,8 @@ const WasmDemoPage: React.FC = () => {
           </ol>
           
           <p>
-            The demo above uses both the custom hooks and the Zustand store to load and interact with the WebAssembly module.
-            It demonstrates how to call functions in the WebAssembly module and how to pass data between JavaScript and WebAssembly.
+            The demo above loads the audio module with the <code>useJennaWasm</code> hook and feeds it microphone
+            frames as typed arrays to drive the level meter and spectrum display.
           </p>
           
           <p>
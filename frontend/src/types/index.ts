@@ -467,6 +467,47 @@ export interface WebSocketConfig {
   timeout: number
 }
 
+// ============================================================================
+// Voice Protocol Types
+// ============================================================================
+
+// Messages of the audio streaming server, mirroring `rust_modules/core/src/protocol.rs`.
+// Validate them with the wasm module's `encodeMessage` / `decodeMessage`.
+
+export type AudioCodec = 'pcm' | 'opus'
+
+export type ClientMessage =
+  | { type: 'start'; sample_rate?: number; channels?: number; codec?: AudioCodec }
+  | { type: 'stop' }
+  | { type: 'trigger' }
+  | { type: 'playback'; playing: boolean }
+  | { type: 'say'; text: string }
+  | { type: 'ping' }
+
+export type PipelineEventKind = 'wake' | 'speech_start' | 'partial' | 'final' | 'timeout' | 'error'
+
+export type ServerMessage =
+  | { type: 'ready'; client: number; sample_rate: number; frame_length: number; tts: boolean }
+  | { type: 'started' }
+  | { type: 'stopped' }
+  | { type: 'pong' }
+  | {
+      type: 'event'
+      kind: PipelineEventKind
+      text: string | null
+      keyword: string | null
+      confidence: number | null
+      timestamp: number
+    }
+  | { type: 'state'; previous: string; state: string; reason: string; timestamp: number }
+  | { type: 'interrupt' }
+  | { type: 'tts_start'; text: string; sample_rate: number; channels: number; format: string }
+  | { type: 'tts_chunk'; samples: number[] }
+  | { type: 'tts_end' }
+  | { type: 'error'; message: string }
+
+export type MessageEncoding = 'json' | 'msgpack'
+
 // ============================================================================
 // API Types
 // ============================================================================
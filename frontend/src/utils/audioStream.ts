@@ -0,0 +1,119 @@
+/**
+ * Microphone streaming through the wasm StreamProcessor in an AudioWorklet
+ */
+
+const WORKLET_URL = '/worklets/jenna-stream-processor.js'
+const WASM_BYTES_URL = '/wasm/jenna/jenna_bg.wasm'
+
+export interface StreamFrame {
+  /** 16-bit mono PCM at the output rate */
+  pcm: Int16Array
+  /** Displayed RMS input level in dBFS */
+  level: number
+  /** Held peak input level in dBFS */
+  peak: number
+  /** Whether the frame was classified as speech (always false without VAD) */
+  isSpeech: boolean
+  /** Frames dropped so far because the main thread fell behind */
+  dropped: number
+}
+
+export interface AudioStreamOptions {
+  /** Output sample rate, 16000 by default */
+  outputRate?: number
+  /** Samples per frame at the output rate, 320 (20 ms) by default */
+  frameLength?: number
+  /** Enable voice activity detection with this threshold above the noise floor */
+  vadThresholdDb?: number
+  /** Called for every frame */
+  onFrame: (frame: StreamFrame) => void
+}
+
+export interface AudioStream {
+  node: AudioWorkletNode
+  /** Enable VAD with a threshold in dB, or disable it with undefined */
+  setVad: (thresholdDb?: number) => void
+  /** Drop buffered audio */
+  clear: () => void
+  /** Disconnect and release the processor */
+  stop: () => void
+}
+
+let wasmBytes: Promise<ArrayBuffer> | null = null
+
+/**
+ * Fetch the wasm binary once; worklets cannot fetch it themselves
+ */
+function loadWasmBytes(): Promise<ArrayBuffer> {
+  if (!wasmBytes) {
+    wasmBytes = fetch(WASM_BYTES_URL)
+      .then((response) => {
+        if (!response.ok) {
+          throw new Error(`Failed to fetch WebAssembly module: ${response.statusText}`)
+        }
+        return response.arrayBuffer()
+      })
+      .catch((err) => {
+        wasmBytes = null
+        throw err
+      })
+  }
+  return wasmBytes
+}
+
+const registeredContexts = new WeakSet<BaseAudioContext>()
+
+/**
+ * Connect `source` to a wasm stream processor and receive fixed-size PCM frames
+ *
+ * @example
+ * ```ts
+ * const context = new AudioContext()
+ * const source = context.createMediaStreamSource(await navigator.mediaDevices.getUserMedia({ audio: true }))
+ * const stream = await createAudioStream(context, source, {
+ *   vadThresholdDb: 9,
+ *   onFrame: ({ pcm, isSpeech }) => isSpeech && socket.send(pcm),
+ * })
+ * ```
+ */
+export async function createAudioStream(
+  context: AudioContext,
+  source: AudioNode,
+  options: AudioStreamOptions,
+): Promise<AudioStream> {
+  const bytes = await loadWasmBytes()
+  if (!registeredContexts.has(context)) {
+    await context.audioWorklet.addModule(WORKLET_URL)
+    registeredContexts.add(context)
+  }
+
+  const node = new AudioWorkletNode(context, 'jenna-stream-processor', {
+    numberOfInputs: 1,
+    numberOfOutputs: 0,
+    processorOptions: {
+      // Each processor compiles its own copy
+      wasmBytes: bytes.slice(0),
+      outputRate: options.outputRate,
+      frameLength: options.frameLength,
+      vadThresholdDb: options.vadThresholdDb,
+    },
+  })
+  node.port.onmessage = (event) => {
+    if (event.data.type === 'frame') {
+      const { pcm, level, peak, isSpeech, dropped } = event.data
+      options.onFrame({ pcm, level, peak, isSpeech, dropped })
+    }
+  }
+  source.connect(node)
+
+  return {
+    node,
+    setVad: (thresholdDb) => node.port.postMessage({ type: 'vad', thresholdDb }),
+    clear: () => node.port.postMessage({ type: 'clear' }),
+    stop: () => {
+      node.port.postMessage({ type: 'stop' })
+      source.disconnect(node)
+      node.port.onmessage = null
+    },
+  }
+}
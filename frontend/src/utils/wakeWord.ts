@@ -0,0 +1,58 @@
+/**
+ * In-browser wake word detection with the wasm openWakeWord backend
+ */
+import type { JennaWasm, WakeWordDetector } from '@hooks/useJennaWasm'
+
+/**
+ * Default location of the shared openWakeWord models
+ */
+export const WAKE_WORD_MODEL_URL = '/models/openwakeword'
+
+export interface WakeWordKeyword {
+  /** Name reported when the keyword fires */
+  name: string
+  /** URL of the keyword classifier (.onnx) */
+  url: string
+  /** 0.0 to 1.0, 0.5 by default */
+  sensitivity?: number
+}
+
+async function fetchModel(url: string): Promise<Uint8Array> {
+  const response = await fetch(url)
+  if (!response.ok) {
+    throw new Error(`Failed to fetch wake word model ${url}: ${response.statusText}`)
+  }
+  return new Uint8Array(await response.arrayBuffer())
+}
+
+/**
+ * Fetch the models and build a detector
+ *
+ * `modelUrl` must serve `melspectrogram.onnx` and `embedding_model.onnx`.
+ * Feed the detector 16 kHz 16-bit frames, e.g. from `createAudioStream`,
+ * and only connect to the server once `process` returns a keyword.
+ */
+export async function createWakeWordDetector(
+  jenna: JennaWasm,
+  keywords: WakeWordKeyword[],
+  modelUrl: string = WAKE_WORD_MODEL_URL,
+): Promise<WakeWordDetector> {
+  if (!jenna.WakeWordDetector) {
+    throw new Error('The wasm module was built without wake word support')
+  }
+
+  const [melspectrogram, embedding, ...classifiers] = await Promise.all([
+    fetchModel(`${modelUrl}/melspectrogram.onnx`),
+    fetchModel(`${modelUrl}/embedding_model.onnx`),
+    ...keywords.map(keyword => fetchModel(keyword.url)),
+  ])
+
+  const detector = new jenna.WakeWordDetector(melspectrogram, embedding)
+  try {
+    keywords.forEach((keyword, index) => detector.addKeyword(keyword.name, classifiers[index], keyword.sensitivity))
+  } catch (err) {
+    detector.free()
+    throw err
+  }
+  return detector
+}
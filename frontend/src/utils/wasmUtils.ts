@@ -45,70 +45,6 @@ export function createMemoryView<T extends TypedArray>(memory: WebAssembly.Memor
   return new type(memory.buffer)
 }
 
-/**
- * Copy a string to WebAssembly memory
- * 
- * @param memory - Typed array view of WebAssembly memory
- * @param str - String to copy
- * @param offset - Offset in memory to start copying
- * @returns The next offset after the string
- */
-export function copyStringToMemory(memory: Uint8Array, str: string, offset: number): number {
-  const encoder = new TextEncoder()
-  const bytes = encoder.encode(str)
-  memory.set(bytes, offset)
-  // Add null terminator
-  memory[offset + bytes.length] = 0
-  return offset + bytes.length + 1
-}
-
-/**
- * Read a null-terminated string from WebAssembly memory
- * 
- * @param memory - Typed array view of WebAssembly memory
- * @param offset - Offset in memory to start reading
- * @returns The string read from memory
- */
-export function readStringFromMemory(memory: Uint8Array, offset: number): string {
-  let end = offset
-  while (memory[end] !== 0) {
-    end++
-  }
-  
-  const bytes = memory.slice(offset, end)
-  const decoder = new TextDecoder()
-  return decoder.decode(bytes)
-}
-
-/**
- * Allocate memory in a WebAssembly module
- * 
- * @param exports - Exports from a WebAssembly instance
- * @param size - Size of memory to allocate in bytes
- * @returns Pointer to the allocated memory
- */
-export function allocateMemory(exports: any, size: number): number {
-  if (typeof exports.malloc !== 'function') {
-    throw new Error('WebAssembly module does not export a malloc function')
-  }
-  
-  return exports.malloc(size)
-}
-
-/**
- * Free memory in a WebAssembly module
- * 
- * @param exports - Exports from a WebAssembly instance
- * @param pointer - Pointer to the memory to free
- */
-export function freeMemory(exports: any, pointer: number): void {
-  if (typeof exports.free !== 'function') {
-    throw new Error('WebAssembly module does not export a free function')
-  }
-  
-  exports.free(pointer)
-}
-
 // Type definitions for typed arrays
 type TypedArray = 
   | Int8Array 
@@ -0,0 +1,20 @@
+const { execSync } = require('child_process');
+const path = require('path');
+
+// wasm-pack writes the module and its JavaScript bindings straight into
+// public/wasm/jenna, where the web client loads them at runtime
+const outDir = path.resolve(__dirname, '../public/wasm/jenna');
+
+// Extra arguments go to cargo, e.g. `npm run wasm:build -- --features opus`
+const cargoArgs = process.argv.slice(2).join(' ');
+
+console.log('Building WebAssembly module...');
+try {
+  execSync(`wasm-pack build --release --target web --out-name jenna --out-dir "${outDir}"${cargoArgs ? ` -- ${cargoArgs}` : ''}`, { stdio: 'inherit' });
+  console.log(`WebAssembly module written to ${outDir}`);
+} catch (error) {
+  console.error('Failed to build WebAssembly module:', error);
+  process.exit(1);
+}
+
+console.log('Done!');
@@ -0,0 +1,218 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::dsp::{self, WindowType};
+use jenna_core::iir::{self, design_sos, BandType, FilterFamily};
+
+use crate::to_error;
+
+fn parse_window(window: Option<String>, default: WindowType) -> Result<WindowType, JsError> {
+    match window {
+        Some(name) => WindowType::from_name(&name).map_err(to_error),
+        None => Ok(default),
+    }
+}
+
+/// Window coefficients for "hann", "hamming", "blackman", "flattop" or "rectangular"
+#[wasm_bindgen(js_name = getWindow)]
+pub fn get_window(window: &str, length: usize) -> Result<Vec<f32>, JsError> {
+    Ok(WindowType::from_name(window).map_err(to_error)?.coefficients(length))
+}
+
+/// Minimum and maximum of each of `columns` equal slices of `samples`, interleaved
+/// 
+/// The result holds `2 * columns` values (`[min0, max0, min1, max1, ...]`),
+/// enough to draw a waveform one canvas column per slice. Columns past the
+/// end of a short buffer are zero.
+#[wasm_bindgen(js_name = waveformPeaks)]
+pub fn waveform_peaks(samples: &[f32], columns: usize) -> Vec<f32> {
+    let mut peaks = vec![0.0; columns * 2];
+    if samples.is_empty() || columns == 0 {
+        return peaks;
+    }
+    
+    for (column, peak) in peaks.chunks_mut(2).enumerate() {
+        let start = column * samples.len() / columns;
+        let end = ((column + 1) * samples.len() / columns).max(start + 1).min(samples.len());
+        if start >= end {
+            continue;
+        }
+        let slice = &samples[start..end];
+        peak[0] = slice.iter().cloned().fold(f32::INFINITY, f32::min);
+        peak[1] = slice.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+    }
+    peaks
+}
+
+/// Magnitude spectrum of microphone frames, for visualization
+/// 
+/// Plans and window coefficients are cached per frame size, so feeding
+/// frames of a constant size allocates nothing after the first call.
+#[wasm_bindgen]
+pub struct SpectrumAnalyzer {
+    processor: dsp::FftProcessor,
+    sample_rate: f32,
+}
+
+#[wasm_bindgen]
+impl SpectrumAnalyzer {
+    /// Create an analyzer; the window defaults to Hann
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32, window: Option<String>) -> Result<SpectrumAnalyzer, JsError> {
+        if sample_rate <= 0.0 {
+            return Err(JsError::new("Sample rate must be positive"));
+        }
+        Ok(SpectrumAnalyzer {
+            processor: dsp::FftProcessor::new(parse_window(window, WindowType::Hann)?),
+            sample_rate,
+        })
+    }
+    
+    #[wasm_bindgen(getter, js_name = sampleRate)]
+    pub fn sample_rate(&self) -> f32 {
+        self.sample_rate
+    }
+    
+    /// Magnitudes of the `n / 2 + 1` bins of a frame
+    pub fn magnitudes(&mut self, frame: &[f32]) -> Result<Vec<f32>, JsError> {
+        self.processor.magnitudes(frame).map_err(to_error)
+    }
+    
+    /// Bin levels in dB relative to a full-scale sine, floored at `floor_db` (-100 by default)
+    pub fn decibels(&mut self, frame: &[f32], floor_db: Option<f32>) -> Result<Vec<f32>, JsError> {
+        let floor_db = floor_db.unwrap_or(-100.0);
+        let scale = 2.0 / frame.len().max(1) as f32;
+        Ok(self
+            .magnitudes(frame)?
+            .into_iter()
+            .map(|m| {
+                let amplitude = m * scale;
+                if amplitude > 0.0 { (20.0 * amplitude.log10()).max(floor_db) } else { floor_db }
+            })
+            .collect())
+    }
+    
+    /// Levels of `count` log-spaced bands for drawing a spectrum, in dB
+    /// 
+    /// Bands span `min_frequency` (50 Hz by default) to `max_frequency`
+    /// (Nyquist by default). Each band takes the loudest bin inside it;
+    /// bands narrower than a bin are interpolated from the neighbouring bins.
+    pub fn bands(
+        &mut self,
+        frame: &[f32],
+        count: usize,
+        min_frequency: Option<f32>,
+        max_frequency: Option<f32>,
+        floor_db: Option<f32>,
+    ) -> Result<Vec<f32>, JsError> {
+        let nyquist = self.sample_rate / 2.0;
+        let min_frequency = min_frequency.unwrap_or(50.0);
+        let max_frequency = max_frequency.unwrap_or(nyquist).min(nyquist);
+        if min_frequency <= 0.0 || min_frequency >= max_frequency {
+            return Err(JsError::new("Band range must be above 0 Hz and below the maximum frequency"));
+        }
+        
+        let floor_db = floor_db.unwrap_or(-100.0);
+        if frame.is_empty() {
+            return Ok(vec![floor_db; count]);
+        }
+        let decibels = self.decibels(frame, Some(floor_db))?;
+        let bin_width = self.sample_rate / frame.len().max(1) as f32;
+        let ratio = max_frequency / min_frequency;
+        let edge = |band: usize| min_frequency * ratio.powf(band as f32 / count as f32) / bin_width;
+        
+        Ok((0..count)
+            .map(|band| {
+                let (low, high) = (edge(band), edge(band + 1));
+                let first = low.ceil() as usize;
+                let last = (high.ceil() as usize).min(decibels.len());
+                if first < last {
+                    return decibels[first..last].iter().cloned().fold(floor_db, f32::max);
+                }
+                // No bin inside the band: interpolate at its centre
+                let centre = (low * high).sqrt();
+                let below = (centre.floor() as usize).min(decibels.len() - 1);
+                let above = (below + 1).min(decibels.len() - 1);
+                let t = centre - below as f32;
+                decibels[below] + (decibels[above] - decibels[below]) * t
+            })
+            .collect())
+    }
+    
+    /// Centre frequency in Hz of a bin for frames of `frame_length` samples
+    #[wasm_bindgen(js_name = binFrequency)]
+    pub fn bin_frequency(&self, bin: usize, frame_length: usize) -> f32 {
+        bin as f32 * self.sample_rate / frame_length.max(1) as f32
+    }
+    
+    /// Change the window applied before transforming
+    #[wasm_bindgen(js_name = setWindow)]
+    pub fn set_window(&mut self, window: &str) -> Result<(), JsError> {
+        self.processor.set_window(WindowType::from_name(window).map_err(to_error)?);
+        Ok(())
+    }
+}
+
+/// Streaming IIR filter made of cascaded second-order sections
+#[wasm_bindgen]
+pub struct Filter {
+    inner: iir::SosFilter,
+}
+
+#[wasm_bindgen]
+impl Filter {
+    /// Design a filter
+    /// 
+    /// `kind` is "butterworth" or "chebyshev1" and `band` is "lowpass",
+    /// "highpass", "bandpass" or "bandstop". `cutoffs` holds one frequency
+    /// (Hz) for lowpass/highpass and two for bandpass/bandstop.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        kind: &str,
+        band: &str,
+        order: usize,
+        cutoffs: &[f64],
+        sample_rate: f64,
+        ripple_db: Option<f64>,
+    ) -> Result<Filter, JsError> {
+        let family = FilterFamily::from_name(kind, ripple_db.unwrap_or(1.0)).map_err(to_error)?;
+        let band = BandType::from_name(band).map_err(to_error)?;
+        let sections = design_sos(family, band, order, cutoffs, sample_rate).map_err(to_error)?;
+        Ok(Filter { inner: iir::SosFilter::from_sections(sections).map_err(to_error)? })
+    }
+    
+    /// Filter a block in place, continuing from the previous block
+    pub fn process(&mut self, samples: &mut [f32]) {
+        self.inner.process_in_place(samples);
+    }
+    
+    /// Clear the filter state
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+    
+    #[wasm_bindgen(getter, js_name = numSections)]
+    pub fn num_sections(&self) -> usize {
+        self.inner.num_sections()
+    }
+}
+
+/// Filter a buffer with an FIR kernel; the output has the same length as the input
+#[wasm_bindgen(js_name = firFilter)]
+pub fn fir_filter(samples: &[f32], kernel: &[f32]) -> Vec<f32> {
+    dsp::fir_filter(samples, kernel)
+}
+
+/// Design a linear-phase FIR kernel from a piecewise-linear response
+/// 
+/// `frequencies` (Hz) must run from 0 to the Nyquist frequency with matching
+/// linear `gains`. The kernel is tapered with a Hamming window by default.
+#[wasm_bindgen(js_name = designFir)]
+pub fn design_fir(
+    num_taps: usize,
+    frequencies: &[f32],
+    gains: &[f32],
+    sample_rate: f32,
+    window: Option<String>,
+) -> Result<Vec<f32>, JsError> {
+    let window = parse_window(window, WindowType::Hamming)?;
+    dsp::design_fir_kernel(num_taps, frequencies, gains, sample_rate, window).map_err(to_error)
+}
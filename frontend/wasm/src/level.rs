@@ -0,0 +1,80 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::level::{self, SILENCE_DBFS};
+
+/// How long a new peak is held before it starts to fall
+const PEAK_HOLD_SECONDS: f32 = 1.0;
+
+/// Fall rate of the displayed levels
+const RELEASE_DB_PER_SECOND: f32 = 20.0;
+
+/// Level meter with instant attack, a slow release and peak hold
+/// 
+/// Meant for driving a microphone level display: feed every captured
+/// block and read `rms` and `peak` when rendering.
+#[wasm_bindgen]
+pub struct LevelMeter {
+    sample_rate: f32,
+    rms: f32,
+    peak: f32,
+    hold: f32,
+}
+
+#[wasm_bindgen]
+impl LevelMeter {
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: f32) -> Result<LevelMeter, JsError> {
+        if sample_rate <= 0.0 {
+            return Err(JsError::new("Sample rate must be positive"));
+        }
+        Ok(LevelMeter { sample_rate, rms: SILENCE_DBFS, peak: SILENCE_DBFS, hold: 0.0 })
+    }
+    
+    /// Meter a block, returning the displayed RMS level in dBFS
+    pub fn process(&mut self, samples: &[f32]) -> f32 {
+        let elapsed = samples.len() as f32 / self.sample_rate;
+        let fall = RELEASE_DB_PER_SECOND * elapsed;
+        
+        self.rms = level::rms_dbfs(samples).max(self.rms - fall);
+        
+        let peak = level::peak_dbfs(samples);
+        if peak >= self.peak {
+            self.peak = peak;
+            self.hold = PEAK_HOLD_SECONDS;
+        } else if self.hold > 0.0 {
+            self.hold -= elapsed;
+        } else {
+            self.peak = peak.max(self.peak - fall);
+        }
+        self.rms
+    }
+    
+    /// Displayed RMS level in dBFS
+    #[wasm_bindgen(getter)]
+    pub fn rms(&self) -> f32 {
+        self.rms
+    }
+    
+    /// Held peak level in dBFS
+    #[wasm_bindgen(getter)]
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+    
+    pub fn reset(&mut self) {
+        self.rms = SILENCE_DBFS;
+        self.peak = SILENCE_DBFS;
+        self.hold = 0.0;
+    }
+}
+
+/// RMS level of a block in dBFS
+#[wasm_bindgen(js_name = rmsDbfs)]
+pub fn rms_dbfs(samples: &[f32]) -> f32 {
+    level::rms_dbfs(samples)
+}
+
+/// Peak level of a block in dBFS
+#[wasm_bindgen(js_name = peakDbfs)]
+pub fn peak_dbfs(samples: &[f32]) -> f32 {
+    level::peak_dbfs(samples)
+}
@@ -0,0 +1,29 @@
+use wasm_bindgen::prelude::*;
+
+mod dsp;
+mod level;
+#[cfg(feature = "opus")]
+mod opus;
+mod protocol;
+mod resample;
+mod stream;
+mod text;
+mod vad;
+#[cfg(feature = "wake-word")]
+mod wake_word;
+
+#[wasm_bindgen(start)]
+pub fn start() {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
+/// Version of the wasm module
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+pub fn to_error(message: String) -> JsError {
+    JsError::new(&message)
+}
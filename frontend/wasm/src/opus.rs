@@ -0,0 +1,104 @@
+use wasm_bindgen::prelude::*;
+use opus::{Application, Bitrate, Channels, Encoder};
+use std::collections::VecDeque;
+
+/// Packet duration; 20 ms is the usual choice for speech
+const FRAME_MS: u32 = 20;
+
+/// Size limit recommended by the Opus documentation
+const MAX_PACKET: usize = 4000;
+
+/// Packets buffered before the oldest are dropped, about one second
+const MAX_QUEUED_PACKETS: usize = 50;
+
+/// Opus encoder for the `"codec": "opus"` WebSocket stream
+/// 
+/// Encodes 16-bit mono audio (16 kHz by default) into 20 ms packets, one
+/// per binary message, which the server's Opus decoder reads directly.
+/// `encode` accepts blocks of any size and returns the number of packets
+/// ready; read them with `nextPacket`.
+#[wasm_bindgen]
+pub struct OpusEncoder {
+    encoder: Encoder,
+    sample_rate: u32,
+    frame_length: usize,
+    pending: Vec<i16>,
+    packets: VecDeque<Vec<u8>>,
+    dropped: u32,
+}
+
+#[wasm_bindgen]
+impl OpusEncoder {
+    /// Create an encoder; `sample_rate` must be 8000, 12000, 16000, 24000 or 48000
+    #[wasm_bindgen(constructor)]
+    pub fn new(sample_rate: Option<u32>, bitrate: Option<i32>) -> Result<OpusEncoder, JsError> {
+        let sample_rate = sample_rate.unwrap_or(16000);
+        let mut encoder = Encoder::new(sample_rate, Channels::Mono, Application::Voip)?;
+        if let Some(bitrate) = bitrate {
+            encoder.set_bitrate(Bitrate::Bits(bitrate))?;
+        }
+        let frame_length = (sample_rate * FRAME_MS / 1000) as usize;
+        
+        Ok(OpusEncoder {
+            encoder,
+            sample_rate,
+            frame_length,
+            pending: Vec::with_capacity(frame_length * 2),
+            packets: VecDeque::with_capacity(MAX_QUEUED_PACKETS),
+            dropped: 0,
+        })
+    }
+    
+    /// Feed audio, returning the number of packets ready to read
+    pub fn encode(&mut self, pcm: &[i16]) -> Result<usize, JsError> {
+        self.pending.extend_from_slice(pcm);
+        while self.pending.len() >= self.frame_length {
+            let frame: Vec<i16> = self.pending.drain(..self.frame_length).collect();
+            let packet = self.encoder.encode_vec(&frame, MAX_PACKET)?;
+            if self.packets.len() == MAX_QUEUED_PACKETS {
+                self.packets.pop_front();
+                self.dropped += 1;
+            }
+            self.packets.push_back(packet);
+        }
+        Ok(self.packets.len())
+    }
+    
+    /// Take the oldest encoded packet
+    #[wasm_bindgen(js_name = nextPacket)]
+    pub fn next_packet(&mut self) -> Option<Vec<u8>> {
+        self.packets.pop_front()
+    }
+    
+    /// Change the target bitrate in bits per second
+    #[wasm_bindgen(js_name = setBitrate)]
+    pub fn set_bitrate(&mut self, bitrate: i32) -> Result<(), JsError> {
+        self.encoder.set_bitrate(Bitrate::Bits(bitrate))?;
+        Ok(())
+    }
+    
+    #[wasm_bindgen(getter, js_name = sampleRate)]
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    
+    /// Samples per packet
+    #[wasm_bindgen(getter, js_name = frameLength)]
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+    
+    /// Packets discarded because the queue was full
+    #[wasm_bindgen(getter)]
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+    
+    /// Drop buffered audio and packets and reset the encoder, e.g. between streams
+    pub fn reset(&mut self) -> Result<(), JsError> {
+        self.encoder.reset_state()?;
+        self.pending.clear();
+        self.packets.clear();
+        Ok(())
+    }
+}
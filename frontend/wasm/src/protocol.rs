@@ -0,0 +1,33 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::protocol::{Encoding, Message};
+use serde::Serialize;
+
+use crate::to_error;
+
+fn parse_encoding(encoding: Option<String>) -> Result<Encoding, JsError> {
+    match encoding {
+        Some(name) => Encoding::from_name(&name).map_err(to_error),
+        None => Ok(Encoding::Json),
+    }
+}
+
+/// Validate a client or server message object and encode it for the wire
+/// 
+/// `encoding` is "json" (the default) or "msgpack". The schema is the one
+/// the server uses, so a malformed message fails here rather than on the
+/// server.
+#[wasm_bindgen(js_name = encodeMessage)]
+pub fn encode_message(message: JsValue, encoding: Option<String>) -> Result<Vec<u8>, JsError> {
+    let encoding = parse_encoding(encoding)?;
+    let value = serde_wasm_bindgen::from_value(message).map_err(|err| to_error(err.to_string()))?;
+    Ok(Message::from_value(value)?.encode(encoding)?)
+}
+
+/// Decode and validate a message, returning it as a plain object
+#[wasm_bindgen(js_name = decodeMessage)]
+pub fn decode_message(data: &[u8], encoding: Option<String>) -> Result<JsValue, JsError> {
+    let message = Message::decode(data, parse_encoding(encoding)?)?;
+    message
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|err| to_error(err.to_string()))
+}
@@ -0,0 +1,51 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::resample::{resample_mono, MonoResampler};
+
+/// Streaming mono resampler, e.g. from the AudioContext rate to 16 kHz
+#[wasm_bindgen]
+pub struct Resampler {
+    inner: MonoResampler,
+    input_rate: u32,
+    output_rate: u32,
+}
+
+#[wasm_bindgen]
+impl Resampler {
+    #[wasm_bindgen(constructor)]
+    pub fn new(input_rate: u32, output_rate: u32) -> Result<Resampler, JsError> {
+        if input_rate == 0 || output_rate == 0 {
+            return Err(JsError::new("Sample rates must be positive"));
+        }
+        Ok(Resampler { inner: MonoResampler::new(input_rate, output_rate)?, input_rate, output_rate })
+    }
+    
+    #[wasm_bindgen(getter, js_name = inputRate)]
+    pub fn input_rate(&self) -> u32 {
+        self.input_rate
+    }
+    
+    #[wasm_bindgen(getter, js_name = outputRate)]
+    pub fn output_rate(&self) -> u32 {
+        self.output_rate
+    }
+    
+    /// Resample the next block of a stream; output lags input by the filter delay
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<f32>, JsError> {
+        Ok(self.inner.push(samples)?)
+    }
+}
+
+/// Resample a complete mono signal
+#[wasm_bindgen]
+pub fn resample(samples: &[f32], input_rate: u32, output_rate: u32) -> Result<Vec<f32>, JsError> {
+    if input_rate == 0 || output_rate == 0 {
+        return Err(JsError::new("Sample rates must be positive"));
+    }
+    Ok(resample_mono(samples, input_rate, output_rate)?)
+}
+
+/// Convert float samples to 16-bit PCM, the format the server expects
+#[wasm_bindgen(js_name = toPcm16)]
+pub fn to_pcm16(samples: &[f32]) -> Vec<i16> {
+    samples.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect()
+}
@@ -0,0 +1,163 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::resample::MonoResampler;
+use jenna_core::vad::VoiceActivityDetector;
+use std::collections::VecDeque;
+
+use crate::level::LevelMeter;
+use crate::resample::to_pcm16;
+
+/// Samples per AudioWorklet render quantum
+pub const RENDER_QUANTUM: usize = 128;
+
+/// Rate the server's speech engines expect
+const DEFAULT_OUTPUT_RATE: u32 = 16000;
+
+/// Default frame length, 20 ms at 16 kHz
+const DEFAULT_FRAME_LENGTH: usize = 320;
+
+/// Frames buffered before the oldest are dropped, about one second by default
+const MAX_QUEUED_FRAMES: usize = 50;
+
+/// Streaming front end for an AudioWorkletProcessor
+/// 
+/// Each call to `process` takes one 128-sample render quantum at the
+/// AudioContext rate. Audio is resampled (16 kHz by default) and cut into
+/// fixed-size frames held in a bounded queue, which the worklet drains with
+/// `nextFrame` or `nextFramePcm16` and posts to the main thread. The input
+/// level is always metered; voice activity is classified per frame once
+/// enabled with `setVad`.
+#[wasm_bindgen]
+pub struct StreamProcessor {
+    resampler: MonoResampler,
+    output_rate: u32,
+    frame_length: usize,
+    pending: Vec<f32>,
+    frames: VecDeque<Vec<f32>>,
+    dropped: u32,
+    meter: LevelMeter,
+    vad: Option<VoiceActivityDetector>,
+    is_speech: bool,
+}
+
+#[wasm_bindgen]
+impl StreamProcessor {
+    /// Create a processor for audio at `input_rate` (the AudioContext rate)
+    #[wasm_bindgen(constructor)]
+    pub fn new(input_rate: u32, output_rate: Option<u32>, frame_length: Option<usize>) -> Result<StreamProcessor, JsError> {
+        let output_rate = output_rate.unwrap_or(DEFAULT_OUTPUT_RATE);
+        let frame_length = frame_length.unwrap_or(DEFAULT_FRAME_LENGTH);
+        if input_rate == 0 || output_rate == 0 {
+            return Err(JsError::new("Sample rates must be positive"));
+        }
+        if frame_length == 0 {
+            return Err(JsError::new("Frame length must be positive"));
+        }
+        
+        Ok(StreamProcessor {
+            resampler: MonoResampler::new(input_rate, output_rate)?,
+            output_rate,
+            frame_length,
+            pending: Vec::with_capacity(frame_length * 2),
+            frames: VecDeque::with_capacity(MAX_QUEUED_FRAMES),
+            dropped: 0,
+            meter: LevelMeter::new(input_rate as f32)?,
+            vad: None,
+            is_speech: false,
+        })
+    }
+    
+    /// Feed one render quantum, returning the number of frames ready to read
+    pub fn process(&mut self, input: &[f32]) -> Result<usize, JsError> {
+        if input.len() != RENDER_QUANTUM {
+            return Err(JsError::new(&format!(
+                "Expected {} samples per render quantum, got {}", RENDER_QUANTUM, input.len()
+            )));
+        }
+        
+        self.meter.process(input);
+        let resampled = self.resampler.push(input)?;
+        self.pending.extend_from_slice(&resampled);
+        
+        while self.pending.len() >= self.frame_length {
+            let frame: Vec<f32> = self.pending.drain(..self.frame_length).collect();
+            if let Some(vad) = self.vad.as_mut() {
+                self.is_speech = vad.process(&to_pcm16(&frame));
+            }
+            if self.frames.len() == MAX_QUEUED_FRAMES {
+                self.frames.pop_front();
+                self.dropped += 1;
+            }
+            self.frames.push_back(frame);
+        }
+        Ok(self.frames.len())
+    }
+    
+    /// Take the oldest complete frame as float samples
+    #[wasm_bindgen(js_name = nextFrame)]
+    pub fn next_frame(&mut self) -> Option<Vec<f32>> {
+        self.frames.pop_front()
+    }
+    
+    /// Take the oldest complete frame as 16-bit PCM, ready to stream to the server
+    #[wasm_bindgen(js_name = nextFramePcm16)]
+    pub fn next_frame_pcm16(&mut self) -> Option<Vec<i16>> {
+        self.frames.pop_front().map(|frame| to_pcm16(&frame))
+    }
+    
+    /// Number of complete frames waiting to be read
+    #[wasm_bindgen(getter)]
+    pub fn available(&self) -> usize {
+        self.frames.len()
+    }
+    
+    /// Frames discarded because the queue was full
+    #[wasm_bindgen(getter)]
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+    
+    #[wasm_bindgen(getter, js_name = outputRate)]
+    pub fn output_rate(&self) -> u32 {
+        self.output_rate
+    }
+    
+    #[wasm_bindgen(getter, js_name = frameLength)]
+    pub fn frame_length(&self) -> usize {
+        self.frame_length
+    }
+    
+    /// Displayed RMS input level in dBFS
+    #[wasm_bindgen(getter)]
+    pub fn level(&self) -> f32 {
+        self.meter.rms()
+    }
+    
+    /// Held peak input level in dBFS
+    #[wasm_bindgen(getter)]
+    pub fn peak(&self) -> f32 {
+        self.meter.peak()
+    }
+    
+    /// Whether the most recent frame was classified as speech; false while the VAD is off
+    #[wasm_bindgen(getter, js_name = isSpeech)]
+    pub fn is_speech(&self) -> bool {
+        self.is_speech
+    }
+    
+    /// Enable voice activity detection with a threshold in dB above the noise floor, or disable it
+    #[wasm_bindgen(js_name = setVad)]
+    pub fn set_vad(&mut self, threshold_db: Option<f32>) {
+        self.vad = threshold_db.map(VoiceActivityDetector::new);
+        self.is_speech = false;
+    }
+    
+    /// Discard queued and partial frames, keeping the configuration
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.frames.clear();
+        if let Some(vad) = self.vad.as_mut() {
+            vad.reset();
+        }
+        self.is_speech = false;
+    }
+}
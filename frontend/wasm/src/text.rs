@@ -0,0 +1,27 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::normalize::TextNormalizer;
+use jenna_core::ssml;
+
+/// Rewrite numbers, dates, times and symbols as words, as the TTS engine does
+/// 
+/// `language` is a tag such as "en_US" (the default) or "en-GB"; only
+/// English is supported.
+#[wasm_bindgen(js_name = normalizeText)]
+pub fn normalize_text(text: &str, language: Option<String>) -> Result<String, JsError> {
+    let language = language.unwrap_or_else(|| "en_US".to_string());
+    let normalizer = TextNormalizer::for_language(&language)
+        .ok_or_else(|| JsError::new(&format!("Text normalization is not supported for '{}'", language)))?;
+    Ok(normalizer.normalize(text))
+}
+
+/// Whether text is an SSML document rather than plain text
+#[wasm_bindgen(js_name = isSsml)]
+pub fn is_ssml(text: &str) -> bool {
+    ssml::is_ssml(text)
+}
+
+/// Check SSML before sending it to the server, returning the parse error if any
+#[wasm_bindgen(js_name = validateSsml)]
+pub fn validate_ssml(text: &str) -> Option<String> {
+    ssml::parse_ssml(text).err()
+}
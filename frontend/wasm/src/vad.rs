@@ -0,0 +1,187 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::vad;
+use std::collections::VecDeque;
+
+/// Threshold above the noise floor used by the native pipeline
+const DEFAULT_THRESHOLD_DB: f32 = 12.0;
+
+/// Trailing silence that ends an utterance, matching the native `silence_timeout`
+const DEFAULT_SILENCE_TIMEOUT: f32 = 0.8;
+
+/// Longest utterance before it is cut off, matching the native `max_utterance`
+const DEFAULT_MAX_UTTERANCE: f32 = 15.0;
+
+/// Audio kept from before speech is detected, so word onsets are not clipped
+const DEFAULT_PRE_ROLL: f32 = 0.3;
+
+/// Energy-based voice activity detector, the same one the native pipeline uses
+/// 
+/// A frame counts as speech when it is `thresholdDb` above an adaptive
+/// noise floor. Feed it consecutive 16-bit frames of a fixed size (e.g.
+/// 20 ms); speech is reported after a few loud frames and held for a few
+/// quiet ones.
+#[wasm_bindgen]
+pub struct VoiceActivityDetector {
+    inner: vad::VoiceActivityDetector,
+}
+
+#[wasm_bindgen]
+impl VoiceActivityDetector {
+    #[wasm_bindgen(constructor)]
+    pub fn new(threshold_db: Option<f32>) -> VoiceActivityDetector {
+        VoiceActivityDetector {
+            inner: vad::VoiceActivityDetector::new(threshold_db.unwrap_or(DEFAULT_THRESHOLD_DB)),
+        }
+    }
+    
+    /// Classify a frame, returning whether speech is in progress
+    pub fn process(&mut self, frame: &[i16]) -> bool {
+        self.inner.process(frame)
+    }
+    
+    #[wasm_bindgen(getter, js_name = isSpeech)]
+    pub fn is_speech(&self) -> bool {
+        self.inner.is_speech()
+    }
+    
+    /// Tracked background level in dBFS, undefined before the first frame
+    #[wasm_bindgen(getter, js_name = noiseFloor)]
+    pub fn noise_floor(&self) -> Option<f32> {
+        self.inner.noise_floor()
+    }
+    
+    /// Forget the current speech state, keeping the noise floor
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// Cuts a continuous stream into utterances so only speech is sent to the server
+/// 
+/// `process` classifies each frame and returns "start" when speech begins
+/// and "end" once `silenceTimeout` seconds of silence follow it (or the
+/// utterance reaches `maxUtterance` seconds). The audio of the utterance,
+/// including `preRoll` seconds from before the start, is collected until
+/// read with `takeAudio`: read it after every frame to stream speech as it
+/// happens, or only on "end" to send whole utterances.
+#[wasm_bindgen]
+pub struct SpeechSegmenter {
+    vad: vad::VoiceActivityDetector,
+    sample_rate: u32,
+    silence_samples: usize,
+    max_samples: usize,
+    pre_roll_samples: usize,
+    pre_roll: VecDeque<i16>,
+    audio: Vec<i16>,
+    in_utterance: bool,
+    utterance_samples: usize,
+    silent_samples: usize,
+}
+
+#[wasm_bindgen]
+impl SpeechSegmenter {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        sample_rate: u32,
+        threshold_db: Option<f32>,
+        silence_timeout: Option<f32>,
+        max_utterance: Option<f32>,
+        pre_roll: Option<f32>,
+    ) -> Result<SpeechSegmenter, JsError> {
+        let silence_timeout = silence_timeout.unwrap_or(DEFAULT_SILENCE_TIMEOUT);
+        let max_utterance = max_utterance.unwrap_or(DEFAULT_MAX_UTTERANCE);
+        let pre_roll = pre_roll.unwrap_or(DEFAULT_PRE_ROLL);
+        if sample_rate == 0 {
+            return Err(JsError::new("Sample rate must be positive"));
+        }
+        if silence_timeout <= 0.0 || max_utterance <= 0.0 {
+            return Err(JsError::new("Timeouts must be positive"));
+        }
+        if pre_roll < 0.0 {
+            return Err(JsError::new("Pre-roll must not be negative"));
+        }
+        
+        let samples_for = |seconds: f32| (seconds * sample_rate as f32).ceil() as usize;
+        Ok(SpeechSegmenter {
+            vad: vad::VoiceActivityDetector::new(threshold_db.unwrap_or(DEFAULT_THRESHOLD_DB)),
+            sample_rate,
+            silence_samples: samples_for(silence_timeout),
+            max_samples: samples_for(max_utterance),
+            pre_roll_samples: samples_for(pre_roll),
+            pre_roll: VecDeque::with_capacity(samples_for(pre_roll)),
+            audio: Vec::new(),
+            in_utterance: false,
+            utterance_samples: 0,
+            silent_samples: 0,
+        })
+    }
+    
+    /// Feed one frame, returning "start" or "end" at utterance boundaries
+    pub fn process(&mut self, frame: &[i16]) -> Option<String> {
+        let speaking = self.vad.process(frame);
+        
+        if !self.in_utterance {
+            if !speaking {
+                self.pre_roll.extend(frame.iter().copied());
+                let excess = self.pre_roll.len().saturating_sub(self.pre_roll_samples);
+                self.pre_roll.drain(..excess);
+                return None;
+            }
+            
+            self.in_utterance = true;
+            self.utterance_samples = 0;
+            self.silent_samples = 0;
+            self.audio.extend(self.pre_roll.drain(..));
+            self.audio.extend_from_slice(frame);
+            return Some("start".to_string());
+        }
+        
+        self.audio.extend_from_slice(frame);
+        self.utterance_samples += frame.len();
+        if speaking {
+            self.silent_samples = 0;
+        } else {
+            self.silent_samples += frame.len();
+        }
+        
+        if self.silent_samples >= self.silence_samples || self.utterance_samples >= self.max_samples {
+            self.in_utterance = false;
+            self.vad.reset();
+            return Some("end".to_string());
+        }
+        None
+    }
+    
+    /// Take the utterance audio collected since the last call
+    #[wasm_bindgen(js_name = takeAudio)]
+    pub fn take_audio(&mut self) -> Vec<i16> {
+        std::mem::take(&mut self.audio)
+    }
+    
+    /// Whether an utterance is in progress
+    #[wasm_bindgen(getter, js_name = inUtterance)]
+    pub fn in_utterance(&self) -> bool {
+        self.in_utterance
+    }
+    
+    /// Whether the most recent frame was classified as speech
+    #[wasm_bindgen(getter, js_name = isSpeech)]
+    pub fn is_speech(&self) -> bool {
+        self.vad.is_speech()
+    }
+    
+    #[wasm_bindgen(getter, js_name = sampleRate)]
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    
+    /// Abandon the current utterance and drop collected audio, keeping the noise floor
+    pub fn reset(&mut self) {
+        self.vad.reset();
+        self.pre_roll.clear();
+        self.audio.clear();
+        self.in_utterance = false;
+        self.utterance_samples = 0;
+        self.silent_samples = 0;
+    }
+}
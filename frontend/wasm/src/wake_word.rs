@@ -0,0 +1,101 @@
+use wasm_bindgen::prelude::*;
+use jenna_core::wake_word::{OpenWakeWordEngine, WakeWordEngine};
+
+use crate::to_error;
+
+/// Steps ignored after a detection so one utterance fires once (2 s of 80 ms steps)
+const REFRACTORY_STEPS: u32 = 25;
+
+/// openWakeWord detector running entirely in the browser
+/// 
+/// Load the shared `melspectrogram.onnx` and `embedding_model.onnx` models
+/// and add one classifier per keyword, then feed 16 kHz 16-bit mono audio
+/// in blocks of any size (e.g. the frames of a `StreamProcessor`). Only
+/// after a keyword fires does the client need to talk to the server.
+#[wasm_bindgen]
+pub struct WakeWordDetector {
+    engine: OpenWakeWordEngine,
+    keywords: Vec<String>,
+    pending: Vec<i16>,
+    score: f32,
+    refractory: u32,
+}
+
+#[wasm_bindgen]
+impl WakeWordDetector {
+    /// Create a detector from the contents of the shared model files
+    #[wasm_bindgen(constructor)]
+    pub fn new(melspectrogram: &[u8], embedding: &[u8]) -> Result<WakeWordDetector, JsError> {
+        let engine = OpenWakeWordEngine::from_bytes(melspectrogram, embedding).map_err(to_error)?;
+        let frame_length = engine.frame_length();
+        Ok(WakeWordDetector {
+            engine,
+            keywords: Vec::new(),
+            pending: Vec::with_capacity(frame_length * 2),
+            score: 0.0,
+            refractory: 0,
+        })
+    }
+    
+    /// Add a keyword classifier; higher sensitivity (0.0 to 1.0, default 0.5) fires more readily
+    #[wasm_bindgen(js_name = addKeyword)]
+    pub fn add_keyword(&mut self, name: String, classifier: &[u8], sensitivity: Option<f32>) -> Result<usize, JsError> {
+        let sensitivity = sensitivity.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&sensitivity) {
+            return Err(JsError::new("Sensitivity must be between 0.0 and 1.0"));
+        }
+        let index = self.engine.add_keyword_bytes(classifier, sensitivity).map_err(to_error)?;
+        self.keywords.push(name);
+        Ok(index)
+    }
+    
+    /// Names of the loaded keywords, in index order
+    #[wasm_bindgen(getter)]
+    pub fn keywords(&self) -> Vec<JsValue> {
+        self.keywords.iter().map(|name| JsValue::from_str(name)).collect()
+    }
+    
+    /// Sample rate the detector expects
+    #[wasm_bindgen(getter, js_name = sampleRate)]
+    pub fn sample_rate(&self) -> u32 {
+        self.engine.sample_rate()
+    }
+    
+    /// Highest keyword score of the most recent step
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> f32 {
+        self.score
+    }
+    
+    /// Feed audio, returning the name of the keyword that fired, if any
+    pub fn process(&mut self, pcm: &[i16]) -> Result<Option<String>, JsError> {
+        if self.engine.num_keywords() == 0 {
+            return Err(JsError::new("Add at least one keyword before processing audio"));
+        }
+        
+        self.pending.extend_from_slice(pcm);
+        let frame_length = self.engine.frame_length();
+        let mut detected = None;
+        while self.pending.len() >= frame_length {
+            let frame: Vec<i16> = self.pending.drain(..frame_length).collect();
+            let output = self.engine.process(&frame).map_err(to_error)?;
+            self.score = output.score.unwrap_or(0.0);
+            
+            if self.refractory > 0 {
+                self.refractory -= 1;
+                continue;
+            }
+            if let Some(index) = output.keyword {
+                self.refractory = REFRACTORY_STEPS;
+                detected = Some(self.keywords[index].clone());
+            }
+        }
+        Ok(detected)
+    }
+    
+    /// Drop buffered audio, e.g. when the microphone restarts
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.refractory = 0;
+    }
+}
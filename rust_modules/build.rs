@@ -0,0 +1,17 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Server side of the speech service; clients generate their own stubs
+    tonic_build::configure()
+        .build_client(false)
+        .compile(&["../proto/speech.proto"], &["../proto"])?;
+    println!("cargo:rerun-if-changed=../proto/speech.proto");
+    
+    // C header for the engines exposed in src/capi.rs
+    #[cfg(feature = "capi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR")?;
+        cbindgen::generate(&crate_dir)?.write_to_file(format!("{}/include/jenna.h", crate_dir));
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+    Ok(())
+}
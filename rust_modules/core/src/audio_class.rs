@@ -0,0 +1,322 @@
+use std::collections::VecDeque;
+
+use crate::dsp::{FftProcessor, WindowType};
+use crate::level::frame_dbfs;
+
+/// Seconds of audio the level and flatness statistics cover
+const CONTEXT_SECS: f32 = 1.0;
+
+/// Average level below which the stream is silent, in dBFS
+const SILENCE_DBFS: f32 = -50.0;
+
+/// Frames this far below the loudest one in the context count as pauses, in dB
+const PAUSE_DEPTH_DB: f32 = 10.0;
+
+/// Fraction of pause frames speech shows (gaps between words and syllables)
+const SPEECH_PAUSE_RATIO: f32 = 0.15;
+
+/// Level deviation speech shows across the context, in dB
+const SPEECH_MODULATION_DB: f32 = 4.0;
+
+/// Spectral flatness above which steady sound is noise rather than music
+const NOISE_FLATNESS: f32 = 0.3;
+
+/// Band the spectral flatness is measured over, in Hz
+const FLATNESS_BAND: (f32, f32) = (100.0, 4000.0);
+
+/// Seconds a new class has to hold before it is reported
+const SWITCH_SECS: f32 = 0.3;
+
+/// Seconds of speech needed before someone counts as present
+const PRESENCE_ONSET_SECS: f32 = 0.5;
+
+/// Seconds of history `SpeechActivity` keeps (one day)
+const ACTIVITY_HISTORY_SECS: f64 = 24.0 * 3600.0;
+
+/// What a stretch of audio mostly contains
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioClass {
+    Silence,
+    Speech,
+    Music,
+    Noise,
+}
+
+impl AudioClass {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioClass::Silence => "silence",
+            AudioClass::Speech => "speech",
+            AudioClass::Music => "music",
+            AudioClass::Noise => "noise",
+        }
+    }
+}
+
+/// Per-frame features kept for the context window
+struct FrameFeatures {
+    seconds: f32,
+    level: f32,
+    flatness: f32,
+}
+
+/// Labels a stream frame by frame as silence, speech, music or noise
+/// 
+/// The decision uses about a second of context. Speech is strongly
+/// modulated at the syllable rate, so its level swings and drops into short
+/// pauses; music and noise are steadier, and the spectrum tells them apart
+/// (noise is flat, music is tonal). A new class is only reported once it
+/// has held for a moment, so single frames don't flip the label.
+pub struct AudioClassifier {
+    sample_rate: u32,
+    fft: FftProcessor,
+    context: VecDeque<FrameFeatures>,
+    context_secs: f32,
+    class: AudioClass,
+    candidate: AudioClass,
+    candidate_secs: f32,
+}
+
+impl AudioClassifier {
+    pub fn new(sample_rate: u32) -> Self {
+        AudioClassifier {
+            sample_rate: sample_rate.max(1),
+            fft: FftProcessor::new(WindowType::Hann),
+            context: VecDeque::new(),
+            context_secs: 0.0,
+            class: AudioClass::Silence,
+            candidate: AudioClass::Silence,
+            candidate_secs: 0.0,
+        }
+    }
+    
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    
+    /// Class reported for the latest frame
+    pub fn class(&self) -> AudioClass {
+        self.class
+    }
+    
+    /// Add a frame and return the current class
+    pub fn process(&mut self, frame: &[i16]) -> AudioClass {
+        if frame.is_empty() {
+            return self.class;
+        }
+        
+        let seconds = frame.len() as f32 / self.sample_rate as f32;
+        let features = FrameFeatures { seconds, level: frame_dbfs(frame), flatness: self.flatness(frame) };
+        self.context_secs += seconds;
+        self.context.push_back(features);
+        while self.context.len() > 1 && self.context_secs - self.context[0].seconds >= CONTEXT_SECS {
+            if let Some(oldest) = self.context.pop_front() {
+                self.context_secs -= oldest.seconds;
+            }
+        }
+        
+        let decided = self.decide();
+        if decided == self.class {
+            self.candidate = decided;
+            self.candidate_secs = 0.0;
+        } else {
+            if decided != self.candidate {
+                self.candidate = decided;
+                self.candidate_secs = 0.0;
+            }
+            self.candidate_secs += seconds;
+            if self.candidate_secs >= SWITCH_SECS {
+                self.class = decided;
+                self.candidate_secs = 0.0;
+            }
+        }
+        self.class
+    }
+    
+    /// Classify the context window as it stands
+    fn decide(&self) -> AudioClass {
+        let count = self.context.len() as f32;
+        let weight = |features: &FrameFeatures| features.seconds / self.context_secs;
+        let mean_level: f32 = self.context.iter().map(|f| f.level * weight(f)).sum();
+        if mean_level < SILENCE_DBFS {
+            return AudioClass::Silence;
+        }
+        
+        let deviation = self.context.iter()
+            .map(|f| (f.level - mean_level).powi(2) * weight(f))
+            .sum::<f32>()
+            .sqrt();
+        let loudest = self.context.iter().map(|f| f.level).fold(f32::MIN, f32::max);
+        let pauses = self.context.iter().filter(|f| f.level < loudest - PAUSE_DEPTH_DB).count() as f32 / count;
+        if pauses >= SPEECH_PAUSE_RATIO && deviation >= SPEECH_MODULATION_DB {
+            return AudioClass::Speech;
+        }
+        
+        let flatness: f32 = self.context.iter().map(|f| f.flatness * weight(f)).sum();
+        if flatness >= NOISE_FLATNESS {
+            AudioClass::Noise
+        } else {
+            AudioClass::Music
+        }
+    }
+    
+    /// Spectral flatness (geometric over arithmetic mean of the power) within `FLATNESS_BAND`
+    fn flatness(&mut self, frame: &[i16]) -> f32 {
+        let samples: Vec<f32> = frame.iter().map(|&s| s as f32 / 32768.0).collect();
+        let magnitudes = match self.fft.magnitudes(&samples) {
+            Ok(magnitudes) => magnitudes,
+            Err(_) => return 0.0,
+        };
+        
+        let bin_width = self.sample_rate as f32 / frame.len() as f32;
+        let (low, high) = FLATNESS_BAND;
+        let first = ((low / bin_width).ceil() as usize).max(1);
+        let last = ((high / bin_width).floor() as usize).min(magnitudes.len().saturating_sub(1));
+        if last <= first {
+            return 0.0;
+        }
+        
+        let band = &magnitudes[first..=last];
+        let powers = band.iter().map(|m| m * m + 1e-12);
+        let log_mean = powers.clone().map(f32::ln).sum::<f32>() / band.len() as f32;
+        let mean = powers.sum::<f32>() / band.len() as f32;
+        (log_mean.exp() / mean).clamp(0.0, 1.0)
+    }
+    
+    /// Forget the context and go back to silence
+    pub fn reset(&mut self) {
+        self.context.clear();
+        self.context_secs = 0.0;
+        self.class = AudioClass::Silence;
+        self.candidate = AudioClass::Silence;
+        self.candidate_secs = 0.0;
+    }
+}
+
+/// Turns speech classifications into an "is someone talking?" signal
+/// 
+/// Someone becomes present after half a second of speech and stays present
+/// until `timeout` seconds pass without any, so the signal doesn't drop
+/// between sentences.
+pub struct PresenceTracker {
+    timeout: f32,
+    speech_secs: f32,
+    quiet_secs: f32,
+    present: bool,
+}
+
+impl PresenceTracker {
+    pub fn new(timeout: f32) -> Self {
+        PresenceTracker { timeout: timeout.max(0.0), speech_secs: 0.0, quiet_secs: 0.0, present: false }
+    }
+    
+    pub fn is_present(&self) -> bool {
+        self.present
+    }
+    
+    pub fn timeout(&self) -> f32 {
+        self.timeout
+    }
+    
+    pub fn set_timeout(&mut self, timeout: f32) {
+        self.timeout = timeout.max(0.0);
+    }
+    
+    /// Account for `seconds` of audio of the given class, returning the new state if it changed
+    pub fn update(&mut self, class: AudioClass, seconds: f32) -> Option<bool> {
+        if class == AudioClass::Speech {
+            self.speech_secs += seconds;
+            self.quiet_secs = 0.0;
+        } else {
+            self.speech_secs = 0.0;
+            self.quiet_secs += seconds;
+        }
+        
+        let present = if self.present {
+            self.quiet_secs < self.timeout
+        } else {
+            self.speech_secs >= PRESENCE_ONSET_SECS
+        };
+        if present == self.present {
+            return None;
+        }
+        self.present = present;
+        Some(present)
+    }
+    
+    pub fn reset(&mut self) {
+        self.speech_secs = 0.0;
+        self.quiet_secs = 0.0;
+        self.present = false;
+    }
+}
+
+/// Speech time per minute over the last day, for presence statistics
+/// 
+/// Only how much speech there was and when is kept, never any audio, so
+/// "someone is talking in the kitchen" can serve as a home automation
+/// sensor. Times are Unix timestamps in seconds.
+#[derive(Default)]
+pub struct SpeechActivity {
+    /// (minute since the Unix epoch, seconds of speech in it), oldest first
+    minutes: VecDeque<(i64, f32)>,
+    last_speech: Option<f64>,
+}
+
+impl SpeechActivity {
+    pub fn new() -> Self {
+        SpeechActivity::default()
+    }
+    
+    /// Account for `seconds` of audio ending at `now`, speech or not
+    pub fn record(&mut self, now: f64, seconds: f32, speaking: bool) {
+        if speaking {
+            let minute = (now / 60.0).floor() as i64;
+            match self.minutes.back_mut() {
+                Some((last, total)) if *last == minute => *total += seconds,
+                _ => self.minutes.push_back((minute, seconds)),
+            }
+            self.last_speech = Some(now);
+        }
+        while let Some(&(minute, _)) = self.minutes.front() {
+            if (minute as f64 + 1.0) * 60.0 > now - ACTIVITY_HISTORY_SECS {
+                break;
+            }
+            self.minutes.pop_front();
+        }
+    }
+    
+    /// When speech was last heard
+    pub fn last_speech(&self) -> Option<f64> {
+        self.last_speech
+    }
+    
+    /// Minutes of speech in the `window` seconds up to `now`, to the nearest whole minute of history
+    pub fn speech_minutes(&self, now: f64, window: f64) -> f32 {
+        let first = ((now - window) / 60.0).floor() as i64;
+        self.minutes.iter().filter(|&&(minute, _)| minute >= first).map(|&(_, seconds)| seconds).sum::<f32>() / 60.0
+    }
+    
+    /// Minutes of speech in each of the last `hours` clock hours as (hour start, minutes), oldest first
+    /// 
+    /// The last entry is the current hour so far.
+    pub fn hourly(&self, now: f64, hours: usize) -> Vec<(f64, f32)> {
+        let current = (now / 3600.0).floor() as i64;
+        let mut totals: Vec<(f64, f32)> = (0..hours as i64)
+            .rev()
+            .map(|back| ((current - back) as f64 * 3600.0, 0.0))
+            .collect();
+        for &(minute, seconds) in &self.minutes {
+            let back = current - minute.div_euclid(60);
+            if (0..hours as i64).contains(&back) {
+                totals[hours - 1 - back as usize].1 += seconds / 60.0;
+            }
+        }
+        totals
+    }
+    
+    pub fn reset(&mut self) {
+        self.minutes.clear();
+        self.last_speech = None;
+    }
+}
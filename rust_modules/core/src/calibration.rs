@@ -0,0 +1,107 @@
+use crate::level::frame_dbfs;
+
+/// Speech level assumed when nobody spoke during calibration (normal voice at arm's length)
+const NOMINAL_SPEECH_DBFS: f32 = -26.0;
+
+/// How far above the noise floor a frame must be to count as speech
+const SPEECH_MARGIN_DB: f32 = 15.0;
+
+/// Fraction of frames that must be speech before its level is measured
+const MIN_SPEECH_FRACTION: f32 = 0.05;
+
+/// Lowest and highest VAD thresholds recommended
+const VAD_THRESHOLD_RANGE: (f32, f32) = (6.0, 20.0);
+
+/// Lowest and highest wake word sensitivities recommended
+const SENSITIVITY_RANGE: (f32, f32) = (0.3, 0.8);
+
+/// Value at `fraction` (0.0 to 1.0) of sorted levels
+fn percentile(sorted: &[f32], fraction: f32) -> f32 {
+    let index = ((sorted.len() - 1) as f32 * fraction).round() as usize;
+    sorted[index]
+}
+
+/// Settings recommended for the measured environment
+#[derive(Clone, Copy, Debug)]
+pub struct Calibration {
+    /// Median frame level, in dBFS
+    pub noise_floor_db: f32,
+    /// Spread of the ambient level (90th minus 10th percentile), in dB
+    pub noise_spread_db: f32,
+    /// Level of the speech heard during calibration, or the nominal level if there was none
+    pub speech_level_db: f32,
+    pub speech_detected: bool,
+    /// Expected speech-to-noise ratio, in dB
+    pub snr_db: f32,
+    pub vad_threshold_db: f32,
+    pub sensitivity: f32,
+}
+
+/// Measures ambient noise frame by frame and recommends VAD and wake word settings
+/// 
+/// The VAD threshold has to clear the ambient level's fluctuations while
+/// staying well below the speech level. Sensitivity rises as the SNR
+/// falls, so a keyword spoken over noise still fires, and is pulled back
+/// when the noise fluctuates (speech-like noise such as a TV causes most
+/// false accepts).
+pub struct NoiseCalibrator {
+    levels: Vec<f32>,
+}
+
+impl NoiseCalibrator {
+    pub fn new() -> Self {
+        NoiseCalibrator { levels: Vec::new() }
+    }
+    
+    pub fn process(&mut self, frame: &[i16]) {
+        self.levels.push(frame_dbfs(frame));
+    }
+    
+    pub fn frames(&self) -> usize {
+        self.levels.len()
+    }
+    
+    /// Recommend settings from the frames seen so far, or None without audio
+    pub fn finish(&self) -> Option<Calibration> {
+        if self.levels.is_empty() {
+            return None;
+        }
+        
+        let mut sorted = self.levels.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let noise_floor_db = percentile(&sorted, 0.5);
+        let noise_spread_db = percentile(&sorted, 0.9) - percentile(&sorted, 0.1);
+        
+        let speech: Vec<f32> = sorted.iter().cloned().filter(|&level| level > noise_floor_db + SPEECH_MARGIN_DB).collect();
+        let speech_detected = speech.len() as f32 >= sorted.len() as f32 * MIN_SPEECH_FRACTION;
+        let speech_level_db = if speech_detected { percentile(&speech, 0.5) } else { NOMINAL_SPEECH_DBFS };
+        let snr_db = speech_level_db - noise_floor_db;
+        
+        // Clear the noise fluctuations, but leave at least 6 dB of headroom below speech
+        let (min_threshold, max_threshold) = VAD_THRESHOLD_RANGE;
+        let vad_threshold_db = (noise_spread_db + 6.0)
+            .min(snr_db - 6.0)
+            .clamp(min_threshold, max_threshold);
+        
+        let low_snr = ((30.0 - snr_db) / 20.0).clamp(0.0, 1.0);
+        let fluctuation = ((noise_spread_db - 6.0) / 12.0).clamp(0.0, 1.0);
+        let (min_sensitivity, max_sensitivity) = SENSITIVITY_RANGE;
+        let sensitivity = (0.5 + 0.2 * low_snr - 0.1 * fluctuation).clamp(min_sensitivity, max_sensitivity);
+        
+        Some(Calibration {
+            noise_floor_db,
+            noise_spread_db,
+            speech_level_db,
+            speech_detected,
+            snr_db,
+            vad_threshold_db,
+            sensitivity,
+        })
+    }
+}
+
+impl Default for NoiseCalibrator {
+    fn default() -> Self {
+        NoiseCalibrator::new()
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use rustfft::num_complex::Complex64;
+
+/// STFT frame length, in seconds (rounded up to a power of two in samples)
+const FRAME_SECS: f32 = 0.032;
+
+/// Frames overlap by 75%
+const OVERLAP: usize = 4;
+
+/// Frames skipped before the prediction starts, keeping the direct sound and early reflections
+const PREDICTION_DELAY: usize = 3;
+
+/// Past frames the late reverberation is predicted from
+const PREDICTION_TAPS: usize = 10;
+
+/// RLS forgetting factor per frame; closer to 1 adapts slower but steadier
+const FORGETTING: f64 = 0.998;
+
+/// Smoothing of the per-bin power estimate between frames
+const POWER_SMOOTHING: f64 = 0.5;
+
+/// Floor of the per-bin power estimate, so silence doesn't dominate the weighting
+const POWER_FLOOR: f64 = 1e-6;
+
+/// Starting diagonal of the inverse correlation matrix
+const INITIAL_INVERSE: f64 = 100.0;
+
+/// Prediction state of one frequency bin
+struct BinState {
+    /// Inverse of the weighted correlation of the delayed frames, row major
+    inverse: Vec<Complex64>,
+    filter: Vec<Complex64>,
+    power: f64,
+}
+
+impl BinState {
+    fn new() -> Self {
+        let mut inverse = vec![Complex64::new(0.0, 0.0); PREDICTION_TAPS * PREDICTION_TAPS];
+        for tap in 0..PREDICTION_TAPS {
+            inverse[tap * PREDICTION_TAPS + tap] = Complex64::new(INITIAL_INVERSE, 0.0);
+        }
+        BinState { inverse, filter: vec![Complex64::new(0.0, 0.0); PREDICTION_TAPS], power: 0.0 }
+    }
+    
+    /// Remove the predicted reverberation from `current` given the delayed frames `past`
+    fn process(&mut self, current: Complex64, past: &[Complex64]) -> Complex64 {
+        let taps = PREDICTION_TAPS;
+        self.power = (POWER_SMOOTHING * self.power + (1.0 - POWER_SMOOTHING) * current.norm_sqr()).max(POWER_FLOOR);
+        
+        let predicted: Complex64 = self.filter.iter().zip(past).map(|(g, x)| g.conj() * *x).sum();
+        let output = current - predicted;
+        
+        // Recursive least squares, each frame weighted by the inverse of its power
+        let gain_numerator: Vec<Complex64> = (0..taps)
+            .map(|row| (0..taps).map(|col| self.inverse[row * taps + col] * past[col]).sum())
+            .collect();
+        let denominator = FORGETTING * self.power
+            + past.iter().zip(&gain_numerator).map(|(x, p)| x.conj() * *p).sum::<Complex64>().re;
+        if denominator <= f64::EPSILON {
+            return output;
+        }
+        let gain: Vec<Complex64> = gain_numerator.iter().map(|p| *p / denominator).collect();
+        
+        // past^H P, which is (P past)^H since P stays Hermitian
+        for (row, k) in gain.iter().enumerate() {
+            for (entry, p) in self.inverse[row * taps..(row + 1) * taps].iter_mut().zip(&gain_numerator) {
+                *entry = (*entry - *k * p.conj()) / FORGETTING;
+            }
+        }
+        for (g, k) in self.filter.iter_mut().zip(&gain) {
+            *g += *k * output.conj();
+        }
+        output
+    }
+}
+
+/// Removes late reverberation from a far-field microphone stream
+/// 
+/// Online single-channel weighted prediction error (WPE): in each STFT bin
+/// the reverberant tail is predicted from frames a few tens of
+/// milliseconds in the past and subtracted, with the predictor adapted by
+/// recursive least squares. The direct sound and early reflections fall
+/// inside the prediction delay and are kept, so speech stays natural while
+/// the smearing that hurts wake word and recognition accuracy across a
+/// room is reduced.
+/// 
+/// Processing is in place with a fixed latency of one frame (32 ms). The
+/// cost grows with the square of the prediction taps in every bin, which is
+/// noticeable on small devices.
+pub struct Dereverberator {
+    frame_length: usize,
+    hop: usize,
+    forward: Arc<dyn RealToComplex<f64>>,
+    inverse: Arc<dyn ComplexToReal<f64>>,
+    window: Vec<f64>,
+    input: Vec<f64>,
+    overlap: Vec<f64>,
+    output: VecDeque<f32>,
+    /// Recent spectra, newest first
+    history: VecDeque<Vec<Complex64>>,
+    bins: Vec<BinState>,
+}
+
+impl Dereverberator {
+    pub fn new(sample_rate: u32) -> Self {
+        let frame_length = ((FRAME_SECS * sample_rate as f32) as usize).next_power_of_two().max(OVERLAP * 2);
+        let hop = frame_length / OVERLAP;
+        let mut planner = RealFftPlanner::<f64>::new();
+        // Square-root Hann for both analysis and synthesis, which together sum to a constant
+        let window = (0..frame_length)
+            .map(|n| (0.5 - 0.5 * (2.0 * PI * n as f64 / frame_length as f64).cos()).sqrt())
+            .collect();
+        let mut dereverberator = Dereverberator {
+            frame_length,
+            hop,
+            forward: planner.plan_fft_forward(frame_length),
+            inverse: planner.plan_fft_inverse(frame_length),
+            window,
+            input: Vec::with_capacity(frame_length * 2),
+            overlap: vec![0.0; frame_length],
+            output: VecDeque::new(),
+            history: VecDeque::with_capacity(PREDICTION_DELAY + PREDICTION_TAPS),
+            bins: Vec::new(),
+        };
+        dereverberator.reset();
+        dereverberator
+    }
+    
+    /// Delay between a sample going in and coming out, in samples
+    pub fn latency(&self) -> usize {
+        self.frame_length
+    }
+    
+    /// Dereverberate a block of samples in place
+    pub fn process(&mut self, block: &mut [f32]) {
+        for sample in block.iter_mut() {
+            self.input.push(*sample as f64);
+            if self.input.len() == self.frame_length {
+                self.process_frame();
+                self.input.drain(..self.hop);
+            }
+            *sample = self.output.pop_front().unwrap_or(0.0);
+        }
+    }
+    
+    fn process_frame(&mut self) {
+        let mut frame: Vec<f64> = self.input.iter().zip(&self.window).map(|(x, w)| x * w).collect();
+        let mut spectrum = self.forward.make_output_vec();
+        if self.forward.process(&mut frame, &mut spectrum).is_err() {
+            return;
+        }
+        
+        let mut cleaned = spectrum.clone();
+        if self.history.len() == PREDICTION_DELAY + PREDICTION_TAPS {
+            let mut past = vec![Complex64::new(0.0, 0.0); PREDICTION_TAPS];
+            for (bin, state) in self.bins.iter_mut().enumerate() {
+                for (tap, value) in past.iter_mut().enumerate() {
+                    *value = self.history[PREDICTION_DELAY + tap][bin];
+                }
+                cleaned[bin] = state.process(spectrum[bin], &past);
+            }
+        }
+        self.history.push_front(spectrum);
+        self.history.truncate(PREDICTION_DELAY + PREDICTION_TAPS);
+        
+        // The inverse transform needs real DC and Nyquist bins
+        let last = cleaned.len() - 1;
+        cleaned[0].im = 0.0;
+        cleaned[last].im = 0.0;
+        let mut time = self.inverse.make_output_vec();
+        if self.inverse.process(&mut cleaned, &mut time).is_err() {
+            return;
+        }
+        
+        // Unnormalized inverse FFT, and the squared windows overlap to OVERLAP / 2
+        let scale = 2.0 / (self.frame_length * OVERLAP) as f64;
+        for ((acc, x), w) in self.overlap.iter_mut().zip(&time).zip(&self.window) {
+            *acc += x * w * scale;
+        }
+        self.output.extend(self.overlap.drain(..self.hop).map(|x| x as f32));
+        self.overlap.resize(self.frame_length, 0.0);
+    }
+    
+    /// Forget the adapted predictors and buffered audio
+    pub fn reset(&mut self) {
+        self.input.clear();
+        // Start a frame's worth of zeros in so the output never runs dry
+        self.input.resize(self.frame_length - self.hop, 0.0);
+        self.overlap.iter_mut().for_each(|x| *x = 0.0);
+        self.output.clear();
+        self.output.resize(self.hop, 0.0);
+        self.history.clear();
+        self.bins = (0..=self.frame_length / 2).map(|_| BinState::new()).collect();
+    }
+}
@@ -0,0 +1,503 @@
+use rustfft::{FftPlanner, num_complex::Complex32};
+use realfft::{RealFftPlanner, RealToComplex};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Window functions that can be applied to a frame before transforming
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowType {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    FlatTop,
+}
+
+impl WindowType {
+    /// Parse a window name such as "hann" or "flattop"
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "rectangular" | "none" | "boxcar" => Ok(WindowType::Rectangular),
+            "hann" | "hanning" => Ok(WindowType::Hann),
+            "hamming" => Ok(WindowType::Hamming),
+            "blackman" => Ok(WindowType::Blackman),
+            "flattop" | "flat_top" | "flat-top" => Ok(WindowType::FlatTop),
+            _ => Err(format!("Unknown window type: {}", name))
+        }
+    }
+
+    /// Compute the window coefficients for a frame of the given length
+    pub fn coefficients(&self, len: usize) -> Vec<f32> {
+        if len == 0 {
+            return Vec::new();
+        }
+        if len == 1 {
+            return vec![1.0];
+        }
+
+        // Symmetric cosine-sum windows: w[n] = sum_k (-1)^k a_k cos(2 pi k n / (N - 1))
+        let terms: &[f64] = match self {
+            WindowType::Rectangular => return vec![1.0; len],
+            WindowType::Hann => &[0.5, 0.5],
+            WindowType::Hamming => &[0.54, 0.46],
+            WindowType::Blackman => &[0.42, 0.5, 0.08],
+            WindowType::FlatTop => &[0.21557895, 0.41663158, 0.277263158, 0.083578947, 0.006947368],
+        };
+
+        let denom = (len - 1) as f64;
+        (0..len)
+            .map(|n| {
+                let phase = 2.0 * std::f64::consts::PI * n as f64 / denom;
+                terms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, &a)| {
+                        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+                        sign * a * (phase * k as f64).cos()
+                    })
+                    .sum::<f64>() as f32
+            })
+            .collect()
+    }
+}
+
+/// Multiply a frame in-place by the given window coefficients
+pub fn apply_window(frame: &mut [f32], coefficients: &[f32]) {
+    for (sample, &w) in frame.iter_mut().zip(coefficients.iter()) {
+        *sample *= w;
+    }
+}
+
+/// Compute FFT magnitudes for a single frame
+pub fn fft_magnitudes(planner: &mut FftPlanner<f32>, frame: &[f32]) -> Vec<f32> {
+    let mut complex_input: Vec<Complex32> = frame
+        .iter()
+        .map(|&x| Complex32::new(x, 0.0))
+        .collect();
+
+    let fft = planner.plan_fft_forward(complex_input.len());
+    fft.process(&mut complex_input);
+
+    complex_input
+        .iter()
+        .map(|c| (c.re * c.re + c.im * c.im).sqrt())
+        .collect()
+}
+
+/// A plan for one frame size and the buffers it transforms in
+struct SizePlan {
+    plan: Arc<dyn RealToComplex<f32>>,
+    input: Vec<f32>,
+    spectrum: Vec<Complex32>,
+    scratch: Vec<Complex32>,
+}
+
+/// FFT processor that caches real-to-complex plans and window coefficients per frame size
+/// 
+/// Creating an FFT plan is expensive compared to transforming a short frame,
+/// so the processor keeps one plan (and scratch buffers) for every frame size
+/// it has seen. Input is real-valued, so only the `n / 2 + 1` non-redundant
+/// bins are computed and returned.
+pub struct FftProcessor {
+    planner: RealFftPlanner<f32>,
+    plans: HashMap<usize, SizePlan>,
+    windows: HashMap<usize, Vec<f32>>,
+    window: WindowType,
+}
+
+impl FftProcessor {
+    pub fn new(window: WindowType) -> Self {
+        FftProcessor {
+            planner: RealFftPlanner::new(),
+            plans: HashMap::new(),
+            windows: HashMap::new(),
+            window,
+        }
+    }
+    
+    /// Compute the magnitude spectrum of a single frame
+    pub fn magnitudes(&mut self, frame: &[f32]) -> Result<Vec<f32>, String> {
+        if frame.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let size = frame.len();
+        let planner = &mut self.planner;
+        let cached = self.plans.entry(size).or_insert_with(|| {
+            let plan = planner.plan_fft_forward(size);
+            SizePlan {
+                input: plan.make_input_vec(),
+                spectrum: plan.make_output_vec(),
+                scratch: plan.make_scratch_vec(),
+                plan,
+            }
+        });
+        cached.input.copy_from_slice(frame);
+        
+        if self.window != WindowType::Rectangular {
+            let window = self.window;
+            let coefficients = self.windows
+                .entry(size)
+                .or_insert_with(|| window.coefficients(size));
+            apply_window(&mut cached.input, coefficients);
+        }
+        
+        cached.plan
+            .process_with_scratch(&mut cached.input, &mut cached.spectrum, &mut cached.scratch)
+            .map_err(|err| format!("FFT error: {}", err))?;
+        
+        Ok(cached.spectrum.iter().map(|c| c.norm()).collect())
+    }
+    
+    /// Set the window applied before transforming
+    pub fn set_window(&mut self, window: WindowType) {
+        self.window = window;
+        self.windows.clear();
+    }
+    
+    /// Get the frame sizes that currently have a cached plan
+    pub fn cached_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = self.plans.keys().cloned().collect();
+        sizes.sort();
+        sizes
+    }
+    
+    /// Drop all cached plans and window coefficients
+    pub fn clear_cache(&mut self) {
+        self.planner = RealFftPlanner::new();
+        self.plans.clear();
+        self.windows.clear();
+    }
+}
+
+/// Cutoff and Q parameters shared by the simple filters
+#[derive(Clone, Copy, Debug)]
+pub struct FilterParams {
+    pub cutoff_low: Option<f32>,
+    pub cutoff_high: Option<f32>,
+    pub q_factor: f32,
+}
+
+impl Default for FilterParams {
+    fn default() -> Self {
+        FilterParams { cutoff_low: None, cutoff_high: None, q_factor: 1.0 }
+    }
+}
+
+pub fn validate_filter_type(filter_type: &str) -> Result<(), String> {
+    if !matches!(filter_type, "lowpass" | "highpass" | "bandpass" | "notch") {
+        return Err(format!("Unknown filter type: {}", filter_type));
+    }
+    Ok(())
+}
+
+/// Apply one of the simple filters to a buffer; the type must already be validated
+pub fn filter_buffer(buffer: &[f32], filter_type: &str, params: &FilterParams) -> Vec<f32> {
+    let q_factor = params.q_factor;
+    match filter_type {
+        "lowpass" => apply_lowpass_filter(buffer, params.cutoff_high.unwrap_or(1000.0), q_factor),
+        "highpass" => apply_highpass_filter(buffer, params.cutoff_low.unwrap_or(500.0), q_factor),
+        "bandpass" => apply_bandpass_filter(
+            buffer, 
+            params.cutoff_low.unwrap_or(500.0), 
+            params.cutoff_high.unwrap_or(2000.0), 
+            q_factor
+        ),
+        "notch" => apply_notch_filter(
+            buffer, 
+            params.cutoff_low.unwrap_or(500.0), 
+            params.cutoff_high.unwrap_or(2000.0), 
+            q_factor
+        ),
+        _ => unreachable!("filter type validated by the caller"),
+    }
+}
+
+// Simple implementation of a low-pass filter
+fn apply_lowpass_filter(buffer: &[f32], _cutoff: f32, _q: f32) -> Vec<f32> {
+    // This is a simple first-order low-pass filter
+    // In a real implementation, you would use more sophisticated filters
+    let alpha = 0.1; // Simplified coefficient based on cutoff
+    let mut output = vec![0.0; buffer.len()];
+    
+    if buffer.is_empty() {
+        return output;
+    }
+    
+    output[0] = buffer[0];
+    for i in 1..buffer.len() {
+        output[i] = output[i-1] + alpha * (buffer[i] - output[i-1]);
+    }
+    
+    output
+}
+
+// Simple implementation of a high-pass filter
+fn apply_highpass_filter(buffer: &[f32], _cutoff: f32, _q: f32) -> Vec<f32> {
+    // This is a simple first-order high-pass filter
+    let alpha = 0.9; // Simplified coefficient based on cutoff
+    let mut output = vec![0.0; buffer.len()];
+    
+    if buffer.is_empty() {
+        return output;
+    }
+    
+    output[0] = buffer[0];
+    for i in 1..buffer.len() {
+        output[i] = alpha * (output[i-1] + buffer[i] - buffer[i-1]);
+    }
+    
+    output
+}
+
+// Simple implementation of a band-pass filter
+fn apply_bandpass_filter(buffer: &[f32], low_cutoff: f32, high_cutoff: f32, q: f32) -> Vec<f32> {
+    // Apply low-pass and high-pass in sequence
+    let highpassed = apply_highpass_filter(buffer, low_cutoff, q);
+    apply_lowpass_filter(&highpassed, high_cutoff, q)
+}
+
+// Simple implementation of a notch filter
+fn apply_notch_filter(buffer: &[f32], low_cutoff: f32, high_cutoff: f32, q: f32) -> Vec<f32> {
+    // A notch filter can be implemented as the original signal minus the band-pass
+    let bandpassed = apply_bandpass_filter(buffer, low_cutoff, high_cutoff, q);
+    
+    buffer.iter()
+        .zip(bandpassed.iter())
+        .map(|(&original, &filtered)| original - filtered)
+        .collect()
+}
+/// Kernels longer than this are applied with FFT-based overlap-save convolution
+const FFT_CONVOLUTION_THRESHOLD: usize = 64;
+
+// Direct-form FIR filtering, output has the same length as the input
+fn fir_direct(buffer: &[f32], kernel: &[f32]) -> Vec<f32> {
+    let mut output = vec![0.0; buffer.len()];
+    for (n, out) in output.iter_mut().enumerate() {
+        let taps = kernel.len().min(n + 1);
+        let mut acc = 0.0;
+        for k in 0..taps {
+            acc += kernel[k] * buffer[n - k];
+        }
+        *out = acc;
+    }
+    output
+}
+
+// FFT-based overlap-save FIR filtering, output has the same length as the input
+fn fir_overlap_save(buffer: &[f32], kernel: &[f32]) -> Vec<f32> {
+    let taps = kernel.len();
+    let block = (2 * taps).next_power_of_two();
+    let step = block - taps + 1;
+    
+    let mut planner = RealFftPlanner::<f32>::new();
+    let forward = planner.plan_fft_forward(block);
+    let inverse = planner.plan_fft_inverse(block);
+    
+    // Spectrum of the zero-padded kernel
+    let mut padded_kernel = forward.make_input_vec();
+    padded_kernel[..taps].copy_from_slice(kernel);
+    let mut kernel_spectrum = forward.make_output_vec();
+    forward.process(&mut padded_kernel, &mut kernel_spectrum)
+        .expect("FFT buffer sizes are derived from the plan");
+    
+    let mut output = Vec::with_capacity(buffer.len());
+    let mut segment = forward.make_input_vec();
+    let mut spectrum = forward.make_output_vec();
+    let mut time = inverse.make_output_vec();
+    let scale = 1.0 / block as f32;
+    
+    let mut pos = 0;
+    while pos < buffer.len() {
+        // Each block carries the previous `taps - 1` samples as history
+        for (i, sample) in segment.iter_mut().enumerate() {
+            let index = pos as isize + i as isize - (taps as isize - 1);
+            *sample = if index >= 0 && (index as usize) < buffer.len() { buffer[index as usize] } else { 0.0 };
+        }
+        
+        forward.process(&mut segment, &mut spectrum)
+            .expect("FFT buffer sizes are derived from the plan");
+        for (bin, h) in spectrum.iter_mut().zip(kernel_spectrum.iter()) {
+            *bin *= h;
+        }
+        // DC and Nyquist bins of a real signal have no imaginary part
+        spectrum[0].im = 0.0;
+        let last = spectrum.len() - 1;
+        spectrum[last].im = 0.0;
+        
+        inverse.process(&mut spectrum, &mut time)
+            .expect("FFT buffer sizes are derived from the plan");
+        
+        let valid = step.min(buffer.len() - pos);
+        output.extend(time[taps - 1..taps - 1 + valid].iter().map(|&x| x * scale));
+        pos += step;
+    }
+    
+    output
+}
+
+/// Filter a buffer with an FIR kernel, choosing direct or FFT convolution by kernel length
+pub fn fir_filter(buffer: &[f32], kernel: &[f32]) -> Vec<f32> {
+    if buffer.is_empty() || kernel.is_empty() {
+        return vec![0.0; buffer.len()];
+    }
+    
+    if kernel.len() > FFT_CONVOLUTION_THRESHOLD {
+        fir_overlap_save(buffer, kernel)
+    } else {
+        fir_direct(buffer, kernel)
+    }
+}
+
+/// Design a linear-phase FIR kernel from a piecewise-linear frequency response
+/// 
+/// `frequencies` (Hz, ascending, from 0 to Nyquist) and `gains` describe the
+/// desired magnitude response. The kernel is obtained by frequency sampling
+/// and then tapered with the given window.
+pub fn design_fir_kernel(
+    num_taps: usize,
+    frequencies: &[f32],
+    gains: &[f32],
+    sample_rate: f32,
+    window: WindowType,
+) -> Result<Vec<f32>, String> {
+    if num_taps == 0 {
+        return Err("Number of taps must be greater than zero".to_string());
+    }
+    if frequencies.len() != gains.len() || frequencies.len() < 2 {
+        return Err("Frequencies and gains must have the same length of at least 2".to_string());
+    }
+    if frequencies.windows(2).any(|w| w[1] < w[0]) {
+        return Err("Frequencies must be in ascending order".to_string());
+    }
+    let nyquist = sample_rate / 2.0;
+    if frequencies[0] != 0.0 || (frequencies[frequencies.len() - 1] - nyquist).abs() > 1e-3 {
+        return Err("Frequencies must start at 0 and end at the Nyquist frequency".to_string());
+    }
+    
+    // Dense frequency grid, large enough to avoid time-domain aliasing
+    let n_fft = (2 * num_taps).next_power_of_two().max(512);
+    let bins = n_fft / 2 + 1;
+    let delay = (num_taps - 1) as f32 / 2.0;
+    
+    let mut spectrum: Vec<Complex32> = (0..bins)
+        .map(|bin| {
+            let freq = bin as f32 * nyquist / (bins - 1) as f32;
+            let segment = frequencies.windows(2).position(|w| freq <= w[1]).unwrap_or(frequencies.len() - 2);
+            let (f0, f1) = (frequencies[segment], frequencies[segment + 1]);
+            let (g0, g1) = (gains[segment], gains[segment + 1]);
+            let gain = if f1 > f0 { g0 + (g1 - g0) * (freq - f0) / (f1 - f0) } else { g1 };
+            
+            // Linear phase centres the impulse response in the kernel
+            let phase = -std::f32::consts::PI * bin as f32 * delay * 2.0 / n_fft as f32;
+            Complex32::from_polar(gain, phase)
+        })
+        .collect();
+    spectrum[0].im = 0.0;
+    spectrum[bins - 1].im = 0.0;
+    
+    let mut planner = RealFftPlanner::<f32>::new();
+    let inverse = planner.plan_fft_inverse(n_fft);
+    let mut impulse = inverse.make_output_vec();
+    inverse.process(&mut spectrum, &mut impulse).map_err(|err| err.to_string())?;
+    
+    let coefficients = window.coefficients(num_taps);
+    Ok(impulse[..num_taps]
+        .iter()
+        .zip(coefficients.iter())
+        .map(|(&h, &w)| h * w / n_fft as f32)
+        .collect())
+}
+
+/// Full cross-correlation of `a` and `b` computed via the FFT
+/// 
+/// The result has `a.len() + b.len() - 1` entries ordered by lag, from
+/// `-(b.len() - 1)` to `a.len() - 1`. Entry at lag `k` is `sum_n a[n + k] * b[n]`.
+/// With `phat` the cross-spectrum is whitened (GCC-PHAT), which sharpens the
+/// peak for reverberant multi-microphone signals.
+pub fn cross_correlation(a: &[f32], b: &[f32], phat: bool) -> Vec<f32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    
+    let full_len = a.len() + b.len() - 1;
+    let n = full_len.next_power_of_two();
+    
+    let mut planner = RealFftPlanner::<f32>::new();
+    let forward = planner.plan_fft_forward(n);
+    let inverse = planner.plan_fft_inverse(n);
+    
+    let mut padded_a = forward.make_input_vec();
+    padded_a[..a.len()].copy_from_slice(a);
+    let mut padded_b = forward.make_input_vec();
+    padded_b[..b.len()].copy_from_slice(b);
+    
+    let mut spectrum_a = forward.make_output_vec();
+    let mut spectrum_b = forward.make_output_vec();
+    forward.process(&mut padded_a, &mut spectrum_a)
+        .expect("FFT buffer sizes are derived from the plan");
+    forward.process(&mut padded_b, &mut spectrum_b)
+        .expect("FFT buffer sizes are derived from the plan");
+    
+    let mut cross: Vec<Complex32> = spectrum_a
+        .iter()
+        .zip(spectrum_b.iter())
+        .map(|(x, y)| {
+            let product = x * y.conj();
+            if phat {
+                let magnitude = product.norm();
+                if magnitude > f32::EPSILON { product / magnitude } else { Complex32::new(0.0, 0.0) }
+            } else {
+                product
+            }
+        })
+        .collect();
+    cross[0].im = 0.0;
+    let last = cross.len() - 1;
+    cross[last].im = 0.0;
+    
+    let mut circular = inverse.make_output_vec();
+    inverse.process(&mut cross, &mut circular)
+        .expect("FFT buffer sizes are derived from the plan");
+    
+    // Reorder the circular result so negative lags come first
+    let scale = 1.0 / n as f32;
+    let negative = b.len() - 1;
+    let mut result = Vec::with_capacity(full_len);
+    result.extend(circular[n - negative..].iter().map(|&x| x * scale));
+    result.extend(circular[..a.len()].iter().map(|&x| x * scale));
+    result
+}
+
+/// Estimate the delay of `a` relative to `b` in samples
+/// 
+/// Returns the sub-sample lag of the cross-correlation peak and the peak value.
+/// A positive lag means `a` lags behind `b`.
+pub fn delay_in_samples(a: &[f32], b: &[f32], max_lag: Option<usize>, phat: bool) -> Option<(f32, f32)> {
+    let correlation = cross_correlation(a, b, phat);
+    if correlation.is_empty() {
+        return None;
+    }
+    
+    let zero = b.len() - 1;
+    let lo = max_lag.map(|m| zero.saturating_sub(m)).unwrap_or(0);
+    let hi = max_lag.map(|m| (zero + m).min(correlation.len() - 1)).unwrap_or(correlation.len() - 1);
+    
+    let (index, &peak) = correlation[lo..=hi]
+        .iter()
+        .enumerate()
+        .max_by(|x, y| x.1.abs().partial_cmp(&y.1.abs()).unwrap_or(std::cmp::Ordering::Equal))?;
+    let index = index + lo;
+    
+    // Parabolic interpolation around the peak
+    let mut offset = 0.0;
+    if index > 0 && index + 1 < correlation.len() {
+        let (y0, y1, y2) = (correlation[index - 1], correlation[index], correlation[index + 1]);
+        let denom = y0 - 2.0 * y1 + y2;
+        if denom.abs() > f32::EPSILON {
+            offset = 0.5 * (y0 - y2) / denom;
+        }
+    }
+    
+    Some((index as f32 - zero as f32 + offset, peak))
+}
@@ -0,0 +1,108 @@
+/// Adaptation step of the NLMS filter (0 to 2; smaller is slower but steadier)
+const STEP_SIZE: f32 = 0.3;
+
+/// Regularization added to the reference energy so quiet passages don't blow up the update
+const ENERGY_FLOOR: f64 = 1e-3;
+
+/// Reference peak below which there is no echo worth modelling
+const SILENT_REFERENCE: f32 = 1e-4;
+
+/// Near-end level, relative to the recent reference peak, that means someone is talking (Geigel test)
+const DOUBLE_TALK_RATIO: f32 = 0.6;
+
+/// Samples adaptation stays frozen after double talk was last seen
+const DOUBLE_TALK_HOLD: usize = 480;
+
+/// Removes the assistant's own playback from the microphone signal
+/// 
+/// A normalized LMS filter models the path from the speaker to the
+/// microphone and subtracts its estimate of the echo. Adaptation is frozen
+/// while the microphone is louder than the echo could be (Geigel double-talk
+/// detection), so the user talking over playback doesn't pull the filter
+/// off course. The reference must run at the microphone's rate and lead the
+/// echo by less than the filter's tail length.
+pub struct EchoCanceller {
+    weights: Vec<f32>,
+    /// Reference history stored twice so the newest `tail` samples are always contiguous
+    history: Vec<f32>,
+    position: usize,
+    energy: f64,
+    /// Decaying peak of the reference over roughly the tail length
+    reference_peak: f32,
+    peak_decay: f32,
+    hold: usize,
+}
+
+impl EchoCanceller {
+    /// Create a canceller modelling an echo path `tail_length` samples long
+    pub fn new(tail_length: usize) -> Self {
+        let tail_length = tail_length.max(1);
+        EchoCanceller {
+            weights: vec![0.0; tail_length],
+            history: vec![0.0; tail_length * 2],
+            position: 0,
+            energy: 0.0,
+            reference_peak: 0.0,
+            // Fall by 60 dB over one tail length
+            peak_decay: 0.001f32.powf(1.0 / tail_length as f32),
+            hold: 0,
+        }
+    }
+    
+    pub fn tail_length(&self) -> usize {
+        self.weights.len()
+    }
+    
+    /// Whether the last sample was treated as the near end talking over playback
+    pub fn double_talk(&self) -> bool {
+        self.hold > 0
+    }
+    
+    /// Cancel the echo of `reference` (what was played) from `mic` in place
+    /// 
+    /// Missing reference samples are treated as silence.
+    pub fn process(&mut self, mic: &mut [f32], reference: &[f32]) {
+        let tail = self.weights.len();
+        for (index, sample) in mic.iter_mut().enumerate() {
+            let x = reference.get(index).copied().unwrap_or(0.0);
+            
+            self.position = (self.position + tail - 1) % tail;
+            let oldest = self.history[self.position];
+            self.history[self.position] = x;
+            self.history[self.position + tail] = x;
+            self.energy = (self.energy + (x as f64).powi(2) - (oldest as f64).powi(2)).max(0.0);
+            self.reference_peak = (self.reference_peak * self.peak_decay).max(x.abs());
+            
+            if self.reference_peak < SILENT_REFERENCE {
+                self.hold = self.hold.saturating_sub(1);
+                continue;
+            }
+            
+            let window = &self.history[self.position..self.position + tail];
+            let estimate: f32 = self.weights.iter().zip(window).map(|(w, x)| w * x).sum();
+            let error = *sample - estimate;
+            
+            if sample.abs() > DOUBLE_TALK_RATIO * self.reference_peak {
+                self.hold = DOUBLE_TALK_HOLD;
+            } else if self.hold > 0 {
+                self.hold -= 1;
+            } else {
+                let scale = (STEP_SIZE as f64 * error as f64 / (self.energy + ENERGY_FLOOR)) as f32;
+                for (weight, x) in self.weights.iter_mut().zip(window) {
+                    *weight += scale * x;
+                }
+            }
+            *sample = error;
+        }
+    }
+    
+    /// Forget the learned echo path and the reference history
+    pub fn reset(&mut self) {
+        self.weights.fill(0.0);
+        self.history.fill(0.0);
+        self.position = 0;
+        self.energy = 0.0;
+        self.reference_peak = 0.0;
+        self.hold = 0;
+    }
+}
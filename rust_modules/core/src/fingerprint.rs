@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use crate::dsp::{FftProcessor, WindowType};
+
+/// Sample rate audio is fingerprinted at; resample to this first
+pub const FINGERPRINT_RATE: u32 = 8000;
+
+/// Spectrogram frame length in samples
+const FRAME_LENGTH: usize = 1024;
+
+/// Spectrogram hop in samples (32 ms at 8 kHz)
+const HOP_LENGTH: usize = 256;
+
+/// Upper bin edges of the bands a peak is picked from, low bands narrower like hearing
+const BAND_EDGES: [usize; 6] = [10, 20, 40, 80, 160, FRAME_LENGTH / 2];
+
+/// Magnitude (natural log) below which a band peak is too quiet to be reliable
+const PEAK_FLOOR: f32 = -8.0;
+
+/// Peaks each anchor is paired with
+const FAN_OUT: usize = 5;
+
+/// Furthest a paired peak may follow its anchor, in frames (fits the hash's 6 bits)
+const TARGET_SPAN: u32 = 63;
+
+/// Seconds between spectrogram frames, the unit of landmark times
+pub const FRAME_SECONDS: f64 = HOP_LENGTH as f64 / FINGERPRINT_RATE as f64;
+
+/// A hashed pair of spectral peaks and the frame of the first one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Landmark {
+    pub hash: u32,
+    pub frame: u32,
+}
+
+/// A prominent point of the spectrogram
+#[derive(Clone, Copy, Debug)]
+struct Peak {
+    frame: u32,
+    bin: u32,
+}
+
+/// Pack two peak frequencies and the frames between them into a hash
+fn landmark_hash(anchor_bin: u32, target_bin: u32, delta: u32) -> u32 {
+    ((anchor_bin & 0x1ff) << 15) | ((target_bin & 0x1ff) << 6) | (delta & 0x3f)
+}
+
+/// Pick the constellation of spectral peaks
+/// 
+/// Each frame contributes the strongest bin of every band that stands out
+/// from the frame's other band maxima, so loud low frequencies don't crowd
+/// out the rest of the spectrum.
+fn spectral_peaks(samples: &[f32]) -> Vec<Peak> {
+    let mut fft = FftProcessor::new(WindowType::Hann);
+    let mut peaks = Vec::new();
+    if samples.len() < FRAME_LENGTH {
+        return peaks;
+    }
+    
+    for (frame, start) in (0..=samples.len() - FRAME_LENGTH).step_by(HOP_LENGTH).enumerate() {
+        let magnitudes = match fft.magnitudes(&samples[start..start + FRAME_LENGTH]) {
+            Ok(magnitudes) => magnitudes,
+            Err(_) => continue,
+        };
+        
+        let mut band_peaks = Vec::with_capacity(BAND_EDGES.len());
+        let mut low = 1;
+        for &high in BAND_EDGES.iter() {
+            let best = (low..high.min(magnitudes.len()))
+                .map(|bin| (bin, (magnitudes[bin] + 1e-9).ln()))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            if let Some(best) = best {
+                band_peaks.push(best);
+            }
+            low = high;
+        }
+        if band_peaks.is_empty() {
+            continue;
+        }
+        
+        let mean = band_peaks.iter().map(|&(_, level)| level).sum::<f32>() / band_peaks.len() as f32;
+        peaks.extend(
+            band_peaks
+                .into_iter()
+                .filter(|&(_, level)| level >= mean && level > PEAK_FLOOR)
+                .map(|(bin, _)| Peak { frame: frame as u32, bin: bin as u32 }),
+        );
+    }
+    peaks
+}
+
+/// Compute the landmarks of mono audio at `FINGERPRINT_RATE`
+/// 
+/// Every peak is paired with the next few peaks after it, and each pair is
+/// hashed from the two frequencies and their distance in time. These
+/// survive noise, compression and level changes well, and a recording
+/// matches a track when many of its hashes line up at one time offset.
+pub fn fingerprint(samples: &[f32]) -> Vec<Landmark> {
+    let peaks = spectral_peaks(samples);
+    let mut landmarks = Vec::new();
+    for (index, anchor) in peaks.iter().enumerate() {
+        let targets = peaks[index + 1..]
+            .iter()
+            .filter(|target| target.frame > anchor.frame)
+            .take_while(|target| target.frame - anchor.frame <= TARGET_SPAN)
+            .take(FAN_OUT);
+        for target in targets {
+            landmarks.push(Landmark {
+                hash: landmark_hash(anchor.bin, target.bin, target.frame - anchor.frame),
+                frame: anchor.frame,
+            });
+        }
+    }
+    landmarks
+}
+
+/// Landmarks of one track lining up at one time offset
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alignment {
+    pub track: i64,
+    /// Frame of the track the query starts at
+    pub offset: i64,
+    /// Matching landmarks at this offset
+    pub count: usize,
+}
+
+/// Find the best time offset per track from matching hashes
+/// 
+/// `hits` are (track, track frame, query frame) for every stored landmark
+/// sharing a hash with the query. Returns one alignment per track, most
+/// matches first.
+pub fn align(hits: impl IntoIterator<Item = (i64, u32, u32)>) -> Vec<Alignment> {
+    let mut counts: HashMap<(i64, i64), usize> = HashMap::new();
+    for (track, track_frame, query_frame) in hits {
+        *counts.entry((track, track_frame as i64 - query_frame as i64)).or_insert(0) += 1;
+    }
+    
+    let mut best: HashMap<i64, Alignment> = HashMap::new();
+    for ((track, offset), count) in counts {
+        let entry = best.entry(track).or_insert(Alignment { track, offset, count: 0 });
+        if count > entry.count || (count == entry.count && offset < entry.offset) {
+            *entry = Alignment { track, offset, count };
+        }
+    }
+    
+    let mut alignments: Vec<Alignment> = best.into_values().collect();
+    alignments.sort_by(|a, b| b.count.cmp(&a.count).then(a.track.cmp(&b.track)));
+    alignments
+}
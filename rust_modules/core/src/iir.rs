@@ -0,0 +1,389 @@
+use rustfft::num_complex::Complex64;
+use std::f64::consts::PI;
+
+/// A second-order section as `[b0, b1, b2, a0, a1, a2]` with `a0 == 1`
+pub type Section = [f64; 6];
+
+/// Filter families supported by the designer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterFamily {
+    Butterworth,
+    /// Chebyshev type I with the given passband ripple in dB
+    Chebyshev1(f64),
+}
+
+impl FilterFamily {
+    /// Parse a family name; the passband ripple only applies to Chebyshev filters
+    pub fn from_name(name: &str, ripple_db: f64) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "butterworth" | "butter" => Ok(FilterFamily::Butterworth),
+            "chebyshev1" | "chebyshev" | "cheby1" => {
+                if ripple_db <= 0.0 {
+                    return Err("Passband ripple must be greater than zero".to_string());
+                }
+                Ok(FilterFamily::Chebyshev1(ripple_db))
+            }
+            _ => Err(format!("Unknown filter kind: {}", name))
+        }
+    }
+}
+
+/// Band type of a designed filter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BandType {
+    Lowpass,
+    Highpass,
+    Bandpass,
+    Bandstop,
+}
+
+impl BandType {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "lowpass" => Ok(BandType::Lowpass),
+            "highpass" => Ok(BandType::Highpass),
+            "bandpass" => Ok(BandType::Bandpass),
+            "bandstop" | "notch" => Ok(BandType::Bandstop),
+            _ => Err(format!("Unknown band type: {}", name))
+        }
+    }
+}
+
+/// Zeros, poles and gain of a filter
+struct Zpk {
+    zeros: Vec<Complex64>,
+    poles: Vec<Complex64>,
+    gain: f64,
+}
+
+fn product(values: &[Complex64]) -> Complex64 {
+    values.iter().fold(Complex64::new(1.0, 0.0), |acc, &v| acc * v)
+}
+
+/// Analog lowpass prototype with a cutoff of 1 rad/s
+fn prototype(family: FilterFamily, order: usize) -> Zpk {
+    let n = order as f64;
+    match family {
+        FilterFamily::Butterworth => {
+            let poles = (0..order)
+                .map(|k| {
+                    let theta = PI * (2.0 * k as f64 + n + 1.0) / (2.0 * n);
+                    Complex64::from_polar(1.0, theta)
+                })
+                .collect();
+            Zpk { zeros: Vec::new(), poles, gain: 1.0 }
+        }
+        FilterFamily::Chebyshev1(ripple_db) => {
+            let eps = (10f64.powf(ripple_db / 10.0) - 1.0).sqrt();
+            let mu = (1.0 / eps).asinh() / n;
+            let poles: Vec<Complex64> = (0..order)
+                .map(|k| {
+                    let theta = PI * (2.0 * k as f64 + 1.0) / (2.0 * n);
+                    Complex64::new(-mu.sinh() * theta.sin(), mu.cosh() * theta.cos())
+                })
+                .collect();
+            let mut gain = product(&poles.iter().map(|p| -p).collect::<Vec<_>>()).re;
+            if order.is_multiple_of(2) {
+                gain /= (1.0 + eps * eps).sqrt();
+            }
+            Zpk { zeros: Vec::new(), poles, gain }
+        }
+    }
+}
+
+/// Transform the prototype into the requested band at the given analog frequencies
+fn transform(zpk: Zpk, band: BandType, w1: f64, w2: f64) -> Zpk {
+    let degree = zpk.poles.len() - zpk.zeros.len();
+    match band {
+        BandType::Lowpass => Zpk {
+            zeros: zpk.zeros.iter().map(|z| z * w1).collect(),
+            poles: zpk.poles.iter().map(|p| p * w1).collect(),
+            gain: zpk.gain * w1.powi(degree as i32),
+        },
+        BandType::Highpass => {
+            let gain = zpk.gain
+                * (product(&zpk.zeros.iter().map(|z| -z).collect::<Vec<_>>())
+                    / product(&zpk.poles.iter().map(|p| -p).collect::<Vec<_>>())).re;
+            let mut zeros: Vec<Complex64> = zpk.zeros.iter().map(|z| w1 / *z).collect();
+            zeros.extend(std::iter::repeat_n(Complex64::new(0.0, 0.0), degree));
+            Zpk { zeros, poles: zpk.poles.iter().map(|p| w1 / *p).collect(), gain }
+        }
+        BandType::Bandpass => {
+            let bw = w2 - w1;
+            let wo = (w1 * w2).sqrt();
+            let split = |values: &[Complex64]| -> Vec<Complex64> {
+                let scaled: Vec<Complex64> = values.iter().map(|v| v * bw / 2.0).collect();
+                let mut out: Vec<Complex64> = scaled.iter().map(|v| v + (v * v - wo * wo).sqrt()).collect();
+                out.extend(scaled.iter().map(|v| v - (v * v - wo * wo).sqrt()));
+                out
+            };
+            let mut zeros = split(&zpk.zeros);
+            zeros.extend(std::iter::repeat_n(Complex64::new(0.0, 0.0), degree));
+            Zpk { zeros, poles: split(&zpk.poles), gain: zpk.gain * bw.powi(degree as i32) }
+        }
+        BandType::Bandstop => {
+            let bw = w2 - w1;
+            let wo = (w1 * w2).sqrt();
+            let gain = zpk.gain
+                * (product(&zpk.zeros.iter().map(|z| -z).collect::<Vec<_>>())
+                    / product(&zpk.poles.iter().map(|p| -p).collect::<Vec<_>>())).re;
+            let split = |values: &[Complex64]| -> Vec<Complex64> {
+                let inverted: Vec<Complex64> = values.iter().map(|v| (bw / 2.0) / *v).collect();
+                let mut out: Vec<Complex64> = inverted.iter().map(|v| v + (v * v - wo * wo).sqrt()).collect();
+                out.extend(inverted.iter().map(|v| v - (v * v - wo * wo).sqrt()));
+                out
+            };
+            let mut zeros = split(&zpk.zeros);
+            zeros.extend(std::iter::repeat_n(Complex64::new(0.0, wo), degree));
+            zeros.extend(std::iter::repeat_n(Complex64::new(0.0, -wo), degree));
+            Zpk { zeros, poles: split(&zpk.poles), gain }
+        }
+    }
+}
+
+/// Map an analog filter to the z-plane with the bilinear transform
+fn bilinear(zpk: Zpk, sample_rate: f64) -> Zpk {
+    let fs2 = Complex64::new(2.0 * sample_rate, 0.0);
+    let degree = zpk.poles.len() - zpk.zeros.len();
+    
+    let gain = zpk.gain
+        * (product(&zpk.zeros.iter().map(|z| fs2 - z).collect::<Vec<_>>())
+            / product(&zpk.poles.iter().map(|p| fs2 - p).collect::<Vec<_>>())).re;
+    let mut zeros: Vec<Complex64> = zpk.zeros.iter().map(|z| (fs2 + z) / (fs2 - z)).collect();
+    zeros.extend(std::iter::repeat_n(Complex64::new(-1.0, 0.0), degree));
+    let poles = zpk.poles.iter().map(|p| (fs2 + p) / (fs2 - p)).collect();
+    
+    Zpk { zeros, poles, gain }
+}
+
+/// Group roots into conjugate pairs and pairs of real roots
+fn pair_roots(roots: &[Complex64]) -> Vec<(Complex64, Option<Complex64>)> {
+    const TOLERANCE: f64 = 1e-9;
+    
+    let mut complex: Vec<Complex64> = roots.iter().cloned().filter(|r| r.im > TOLERANCE).collect();
+    let mut real: Vec<f64> = roots.iter().filter(|r| r.im.abs() <= TOLERANCE).map(|r| r.re).collect();
+    
+    // Roots closest to the unit circle go last for better numerical behaviour
+    complex.sort_by(|a, b| a.norm().partial_cmp(&b.norm()).unwrap_or(std::cmp::Ordering::Equal));
+    real.sort_by(|a, b| a.abs().partial_cmp(&b.abs()).unwrap_or(std::cmp::Ordering::Equal));
+    
+    let mut pairs: Vec<(Complex64, Option<Complex64>)> = Vec::new();
+    for chunk in real.chunks(2) {
+        let first = Complex64::new(chunk[0], 0.0);
+        pairs.push((first, chunk.get(1).map(|&r| Complex64::new(r, 0.0))));
+    }
+    for root in complex {
+        pairs.push((root, Some(root.conj())));
+    }
+    pairs
+}
+
+fn section_polynomial(pair: Option<&(Complex64, Option<Complex64>)>) -> [f64; 3] {
+    match pair {
+        Some((first, Some(second))) => [1.0, -(first + second).re, (first * second).re],
+        Some((first, None)) => [1.0, -first.re, 0.0],
+        None => [1.0, 0.0, 0.0],
+    }
+}
+
+/// Convert a digital zpk filter into second-order sections
+fn zpk_to_sos(zpk: Zpk) -> Vec<Section> {
+    let pole_pairs = pair_roots(&zpk.poles);
+    let zero_pairs = pair_roots(&zpk.zeros);
+    let count = pole_pairs.len().max(zero_pairs.len());
+    
+    (0..count)
+        .map(|i| {
+            let b = section_polynomial(zero_pairs.get(i));
+            let a = section_polynomial(pole_pairs.get(i));
+            let k = if i == 0 { zpk.gain } else { 1.0 };
+            [b[0] * k, b[1] * k, b[2] * k, a[0], a[1], a[2]]
+        })
+        .collect()
+}
+
+/// Design a digital IIR filter as second-order sections
+/// 
+/// `cutoffs` holds one frequency (Hz) for lowpass/highpass and two for
+/// bandpass/bandstop. Band-pass and band-stop filters have `2 * order` poles.
+pub fn design_sos(
+    family: FilterFamily,
+    band: BandType,
+    order: usize,
+    cutoffs: &[f64],
+    sample_rate: f64,
+) -> Result<Vec<Section>, String> {
+    if order == 0 {
+        return Err("Filter order must be greater than zero".to_string());
+    }
+    let expected = match band {
+        BandType::Lowpass | BandType::Highpass => 1,
+        BandType::Bandpass | BandType::Bandstop => 2,
+    };
+    if cutoffs.len() != expected {
+        return Err(format!("Expected {} cutoff frequencies, got {}", expected, cutoffs.len()));
+    }
+    let nyquist = sample_rate / 2.0;
+    if cutoffs.iter().any(|&f| f <= 0.0 || f >= nyquist) {
+        return Err(format!("Cutoff frequencies must be between 0 and {} Hz", nyquist));
+    }
+    if expected == 2 && cutoffs[0] >= cutoffs[1] {
+        return Err("Lower cutoff must be below the upper cutoff".to_string());
+    }
+    
+    // Pre-warp the cutoffs so the digital filter hits them exactly
+    let warp = |f: f64| 2.0 * sample_rate * (PI * f / sample_rate).tan();
+    let w1 = warp(cutoffs[0]);
+    let w2 = if expected == 2 { warp(cutoffs[1]) } else { w1 };
+    
+    let analog = transform(prototype(family, order), band, w1, w2);
+    Ok(zpk_to_sos(bilinear(analog, sample_rate)))
+}
+
+/// Streaming filter made of cascaded second-order sections
+/// 
+/// Uses the transposed direct form II and keeps its state between calls,
+/// so audio can be filtered frame by frame without discontinuities.
+pub struct SosFilter {
+    sections: Vec<Section>,
+    state: Vec<[f64; 2]>,
+}
+
+impl SosFilter {
+    /// Build a filter from sections, normalising each one by `a0`
+    pub fn from_sections(sections: Vec<Section>) -> Result<Self, String> {
+        let mut normalized = Vec::with_capacity(sections.len());
+        for section in sections {
+            let a0 = section[3];
+            if a0 == 0.0 {
+                return Err("Section coefficient a0 must be non-zero".to_string());
+            }
+            normalized.push([
+                section[0] / a0, section[1] / a0, section[2] / a0,
+                1.0, section[4] / a0, section[5] / a0,
+            ]);
+        }
+        
+        let state = vec![[0.0; 2]; normalized.len()];
+        Ok(SosFilter { sections: normalized, state })
+    }
+    
+    /// Filter a single sample through all sections
+    #[inline]
+    pub fn process_sample(&mut self, sample: f32) -> f32 {
+        let mut x = sample as f64;
+        for (s, state) in self.sections.iter().zip(self.state.iter_mut()) {
+            let y = s[0] * x + state[0];
+            state[0] = s[1] * x - s[4] * y + state[1];
+            state[1] = s[2] * x - s[5] * y;
+            x = y;
+        }
+        x as f32
+    }
+    
+    /// Filter a buffer in place
+    pub fn process_in_place(&mut self, buffer: &mut [f32]) {
+        for sample in buffer.iter_mut() {
+            *sample = self.process_sample(*sample);
+        }
+    }    
+    /// Replace the coefficients, keeping the state if the section count is unchanged
+    pub fn set_sections(&mut self, sections: Vec<Section>) -> Result<(), String> {
+        let filter = SosFilter::from_sections(sections)?;
+        if filter.sections.len() == self.sections.len() {
+            self.sections = filter.sections;
+        } else {
+            *self = filter;
+        }
+        Ok(())
+    }
+    
+    /// Clear the filter state
+    pub fn reset(&mut self) {
+        for state in self.state.iter_mut() {
+            *state = [0.0; 2];
+        }
+    }
+    
+    /// Get the number of second-order sections
+    pub fn num_sections(&self) -> usize {
+        self.sections.len()
+    }
+}
+
+/// Shape of an equalizer band
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BandShape {
+    Peaking,
+    LowShelf,
+    HighShelf,
+}
+
+impl BandShape {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "peaking" | "peak" | "bell" => Ok(BandShape::Peaking),
+            "lowshelf" | "low_shelf" => Ok(BandShape::LowShelf),
+            "highshelf" | "high_shelf" => Ok(BandShape::HighShelf),
+            _ => Err(format!("Unknown band shape: {}", name))
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BandShape::Peaking => "peaking",
+            BandShape::LowShelf => "lowshelf",
+            BandShape::HighShelf => "highshelf",
+        }
+    }
+}
+
+/// Parameters of a single equalizer band
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BandParams {
+    pub shape: BandShape,
+    pub frequency: f64,
+    pub gain_db: f64,
+    pub q: f64,
+}
+
+impl BandParams {
+    /// Biquad coefficients from the RBJ audio EQ cookbook
+    pub fn coefficients(&self, sample_rate: f64) -> Section {
+        let a = 10f64.powf(self.gain_db / 40.0);
+        let w0 = 2.0 * PI * self.frequency / sample_rate;
+        let (sin_w0, cos_w0) = w0.sin_cos();
+        let alpha = sin_w0 / (2.0 * self.q);
+        
+        let [b0, b1, b2, a0, a1, a2] = match self.shape {
+            BandShape::Peaking => [
+                1.0 + alpha * a, -2.0 * cos_w0, 1.0 - alpha * a,
+                1.0 + alpha / a, -2.0 * cos_w0, 1.0 - alpha / a,
+            ],
+            BandShape::LowShelf => {
+                let sq = 2.0 * a.sqrt() * alpha;
+                [
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 + sq),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) - (a - 1.0) * cos_w0 - sq),
+                    (a + 1.0) + (a - 1.0) * cos_w0 + sq,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    (a + 1.0) + (a - 1.0) * cos_w0 - sq,
+                ]
+            }
+            BandShape::HighShelf => {
+                let sq = 2.0 * a.sqrt() * alpha;
+                [
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 + sq),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0),
+                    a * ((a + 1.0) + (a - 1.0) * cos_w0 - sq),
+                    (a + 1.0) - (a - 1.0) * cos_w0 + sq,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos_w0),
+                    (a + 1.0) - (a - 1.0) * cos_w0 - sq,
+                ]
+            }
+        };
+        
+        [b0 / a0, b1 / a0, b2 / a0, 1.0, a1 / a0, a2 / a0]
+    }
+}
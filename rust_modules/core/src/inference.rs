@@ -0,0 +1,173 @@
+use tract_onnx::prelude::*;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::time::SystemTime;
+
+/// An ONNX model optimized for fixed input facts and ready to run
+pub type OnnxPlan = TypedRunnableModel<TypedModel>;
+
+/// A model loaded through `load_shared`, kept while anything still uses it
+struct CacheEntry {
+    path: PathBuf,
+    shape: Vec<usize>,
+    /// Modification time of the file when it was loaded, so a replaced model is reloaded
+    modified: Option<SystemTime>,
+    plan: Weak<OnnxPlan>,
+}
+
+/// A model in the shared cache, for reporting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedModel {
+    pub path: PathBuf,
+    pub shape: Vec<usize>,
+    /// Engines holding the model
+    pub users: usize,
+}
+
+static CACHE: Mutex<Vec<CacheEntry>> = Mutex::new(Vec::new());
+
+/// How many model runs may execute at once, and how many are
+struct RunLimit {
+    max: Option<usize>,
+    running: usize,
+}
+
+static LIMIT: Mutex<RunLimit> = Mutex::new(RunLimit { max: None, running: 0 });
+
+static SLOT_FREED: Condvar = Condvar::new();
+
+/// A slot in the run limit, given back when dropped
+struct RunSlot;
+
+impl RunSlot {
+    fn acquire() -> Self {
+        let mut limit = LIMIT.lock().unwrap();
+        while limit.max.is_some_and(|max| limit.running >= max) {
+            limit = SLOT_FREED.wait(limit).unwrap();
+        }
+        limit.running += 1;
+        RunSlot
+    }
+}
+
+impl Drop for RunSlot {
+    fn drop(&mut self) {
+        LIMIT.lock().unwrap().running -= 1;
+        SLOT_FREED.notify_one();
+    }
+}
+
+/// Run at most `max` models at once across the process, or lift the limit with None
+/// 
+/// tract runs each model on the calling thread, so this caps the cores
+/// neural models take from audio and recognition. Callers over the limit
+/// wait for a slot.
+pub fn set_max_concurrent_runs(max: Option<usize>) -> Result<(), String> {
+    if max == Some(0) {
+        return Err("At least one concurrent run must be allowed".to_string());
+    }
+    LIMIT.lock().unwrap().max = max;
+    // Waiters re-check against the new limit
+    SLOT_FREED.notify_all();
+    Ok(())
+}
+
+/// The concurrent run limit and the number of runs executing now
+pub fn run_limit() -> (Option<usize>, usize) {
+    let limit = LIMIT.lock().unwrap();
+    (limit.max, limit.running)
+}
+
+fn into_plan(model: InferenceModel, shape: &[usize]) -> TractResult<OnnxPlan> {
+    model
+        .with_input_fact(0, f32::fact(shape).into())?
+        .into_optimized()?
+        .into_runnable()
+}
+
+/// Load a model whose single f32 input has a fixed `shape`
+pub fn load_onnx(path: &Path, shape: &[usize]) -> Result<OnnxPlan, String> {
+    tract_onnx::onnx()
+        .model_for_path(path)
+        .and_then(|model| into_plan(model, shape))
+        .map_err(|err| format!("Failed to load {}: {}", path.display(), err))
+}
+
+/// Load a model from memory, for clients without a filesystem such as browsers
+pub fn load_onnx_bytes(bytes: &[u8], shape: &[usize]) -> Result<OnnxPlan, String> {
+    tract_onnx::onnx()
+        .model_for_read(&mut Cursor::new(bytes))
+        .and_then(|model| into_plan(model, shape))
+        .map_err(|err| format!("Failed to load model: {}", err))
+}
+
+/// Load a model like `load_onnx`, sharing it with every other user of the same file and shape
+/// 
+/// Engines in several zones (or several detectors on one model) then hold
+/// one copy of the weights. The cache only keeps weak references, so a
+/// model is freed once its last user drops it, and a file that changed on
+/// disk since it was loaded is loaded afresh.
+pub fn load_shared(path: &Path, shape: &[usize]) -> Result<Arc<OnnxPlan>, String> {
+    let path = fs::canonicalize(path).map_err(|err| format!("Failed to load {}: {}", path.display(), err))?;
+    let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+    let find = |cache: &[CacheEntry]| {
+        cache
+            .iter()
+            .find(|entry| entry.path == path && entry.shape == shape && entry.modified == modified)
+            .and_then(|entry| entry.plan.upgrade())
+    };
+    if let Some(plan) = find(&CACHE.lock().unwrap()) {
+        return Ok(plan);
+    }
+    
+    // Loading can take seconds, so other models aren't held up meanwhile
+    let plan = Arc::new(load_onnx(&path, shape)?);
+    let mut cache = CACHE.lock().unwrap();
+    // Another thread may have loaded the same model in the meantime
+    if let Some(existing) = find(&cache) {
+        return Ok(existing);
+    }
+    cache.retain(|entry| entry.plan.strong_count() > 0);
+    cache.push(CacheEntry { path, shape: shape.to_vec(), modified, plan: Arc::downgrade(&plan) });
+    Ok(plan)
+}
+
+/// Models in the shared cache that are still in use
+pub fn cached_models() -> Vec<CachedModel> {
+    CACHE
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|entry| entry.plan.strong_count() > 0)
+        .map(|entry| CachedModel { path: entry.path.clone(), shape: entry.shape.clone(), users: entry.plan.strong_count() })
+        .collect()
+}
+
+/// Build an input tensor of `shape` from row-major `data`
+pub fn tensor<T: Datum + Copy>(shape: &[usize], data: &[T]) -> Result<Tensor, String> {
+    Tensor::from_shape(shape, data).map_err(|err| err.to_string())
+}
+
+/// Copy output `index` out as a flat row-major vector
+pub fn output<T: Datum + Copy>(outputs: &[TValue], index: usize) -> Result<Vec<T>, String> {
+    let value = outputs
+        .get(index)
+        .ok_or_else(|| format!("Model has no output {}; it returned {}", index, outputs.len()))?;
+    let view = value.to_array_view::<T>().map_err(|err| err.to_string())?;
+    Ok(view.iter().copied().collect())
+}
+
+/// Run a model, within the concurrent run limit
+pub fn run(plan: &OnnxPlan, inputs: TVec<TValue>) -> Result<TVec<TValue>, String> {
+    let _slot = RunSlot::acquire();
+    plan.run(inputs).map_err(|err| err.to_string())
+}
+
+/// Run a model with a single f32 input and return its first output
+pub fn run_onnx(plan: &OnnxPlan, shape: &[usize], data: Vec<f32>) -> Result<Vec<f32>, String> {
+    let input = tensor(shape, &data)?;
+    let outputs = run(plan, tvec!(input.into()))?;
+    output(&outputs, 0)
+}
@@ -0,0 +1,205 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Lowercase words separated by single spaces, with a space at the start
+/// 
+/// Punctuation and other separators become word breaks, so "Stop!" and
+/// "stop," both read as " stop".
+pub fn normalize_words(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len() + 1);
+    normalized.push(' ');
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() || ch == '\'' {
+            normalized.push(ch);
+        } else if !normalized.ends_with(' ') {
+            normalized.push(' ');
+        }
+    }
+    normalized
+}
+
+/// Pattern a phrase is matched by: its words, each preceded and followed by a space
+fn phrase_pattern(phrase: &str) -> String {
+    format!("{} ", normalize_words(phrase).trim_end())
+}
+
+/// Aho-Corasick automaton matching many patterns in one pass over the text
+struct Automaton {
+    goto: Vec<HashMap<char, usize>>,
+    fail: Vec<usize>,
+    /// Patterns ending at each state, including those reached through fail links
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Automaton {
+    fn new<'a>(patterns: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut automaton = Automaton { goto: vec![HashMap::new()], fail: vec![0], outputs: vec![Vec::new()] };
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            let mut state = 0;
+            for ch in pattern.chars() {
+                state = match automaton.goto[state].get(&ch) {
+                    Some(&next) => next,
+                    None => {
+                        automaton.goto.push(HashMap::new());
+                        automaton.fail.push(0);
+                        automaton.outputs.push(Vec::new());
+                        let next = automaton.goto.len() - 1;
+                        automaton.goto[state].insert(ch, next);
+                        next
+                    }
+                };
+            }
+            automaton.outputs[state].push(index);
+        }
+        
+        // Breadth first, so every fail link points at an already finished state
+        let mut queue: VecDeque<usize> = automaton.goto[0].values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let edges: Vec<(char, usize)> = automaton.goto[state].iter().map(|(&ch, &next)| (ch, next)).collect();
+            for (ch, next) in edges {
+                let mut fallback = automaton.fail[state];
+                while fallback != 0 && !automaton.goto[fallback].contains_key(&ch) {
+                    fallback = automaton.fail[fallback];
+                }
+                let fail = automaton.goto[fallback].get(&ch).copied().unwrap_or(0);
+                automaton.fail[next] = fail;
+                let inherited = automaton.outputs[fail].clone();
+                automaton.outputs[next].extend(inherited);
+                queue.push_back(next);
+            }
+        }
+        automaton
+    }
+    
+    fn step(&self, mut state: usize, ch: char) -> usize {
+        loop {
+            if let Some(&next) = self.goto[state].get(&ch) {
+                return next;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.fail[state];
+        }
+    }
+}
+
+/// Watches a growing transcript for trigger phrases
+/// 
+/// Recognizers revise their partial results as an utterance goes on, and
+/// each partial repeats everything said so far. Feeding every partial
+/// reports each phrase once per time it was said, as soon as it appears,
+/// without waiting for the final result. When a partial only extends the
+/// previous one, just the new text is scanned.
+/// 
+/// Phrases match whole words, ignoring case and punctuation. A phrase at
+/// the very end of a partial counts straight away, even though the
+/// recognizer could still extend its last word.
+pub struct KeywordSpotter {
+    phrases: Vec<String>,
+    automaton: Automaton,
+    /// Normalized text scanned so far and the automaton state after it
+    text: String,
+    state: usize,
+    /// Occurrences of each phrase in `text`, and how many have been reported this utterance
+    found: Vec<usize>,
+    reported: Vec<usize>,
+}
+
+impl KeywordSpotter {
+    pub fn new(phrases: impl IntoIterator<Item = String>) -> Self {
+        let mut spotter = KeywordSpotter {
+            phrases: Vec::new(),
+            automaton: Automaton::new(std::iter::empty()),
+            text: String::new(),
+            state: 0,
+            found: Vec::new(),
+            reported: Vec::new(),
+        };
+        for phrase in phrases {
+            spotter.add(&phrase);
+        }
+        spotter
+    }
+    
+    pub fn phrases(&self) -> &[String] {
+        &self.phrases
+    }
+    
+    /// The watched phrase `phrase` matches the same words as, if any
+    pub fn find(&self, phrase: &str) -> Option<&str> {
+        let pattern = phrase_pattern(phrase);
+        self.phrases.iter().find(|p| phrase_pattern(p) == pattern).map(String::as_str)
+    }
+    
+    /// Watch for `phrase`; returns false if it is blank or already watched
+    pub fn add(&mut self, phrase: &str) -> bool {
+        if phrase_pattern(phrase).trim().is_empty() || self.find(phrase).is_some() {
+            return false;
+        }
+        self.phrases.push(phrase.trim().to_string());
+        self.rebuild();
+        true
+    }
+    
+    /// Stop watching `phrase`; returns false if it wasn't watched
+    pub fn remove(&mut self, phrase: &str) -> bool {
+        let pattern = phrase_pattern(phrase);
+        let before = self.phrases.len();
+        self.phrases.retain(|p| phrase_pattern(p) != pattern);
+        if self.phrases.len() == before {
+            return false;
+        }
+        self.rebuild();
+        true
+    }
+    
+    fn rebuild(&mut self) {
+        let patterns: Vec<String> = self.phrases.iter().map(|p| phrase_pattern(p)).collect();
+        self.automaton = Automaton::new(patterns.iter().map(String::as_str));
+        self.reset();
+    }
+    
+    /// Scan the latest partial (or final) transcript, returning the phrases newly heard
+    pub fn feed(&mut self, transcript: &str) -> Vec<String> {
+        let normalized = normalize_words(transcript);
+        let normalized = normalized.trim_end();
+        let start = if normalized.starts_with(self.text.as_str()) && !self.text.is_empty() {
+            self.text.len()
+        } else {
+            // The recognizer revised earlier words; rescan, keeping what was already reported
+            self.state = 0;
+            self.found.iter_mut().for_each(|count| *count = 0);
+            0
+        };
+        for ch in normalized[start..].chars() {
+            self.state = self.automaton.step(self.state, ch);
+            for &index in &self.automaton.outputs[self.state] {
+                self.found[index] += 1;
+            }
+        }
+        self.text = normalized.to_string();
+        
+        // Treat the end of the transcript as a word break without committing to it
+        let mut pending = self.found.clone();
+        for &index in &self.automaton.outputs[self.automaton.step(self.state, ' ')] {
+            pending[index] += 1;
+        }
+        
+        let mut heard = Vec::new();
+        for (index, &count) in pending.iter().enumerate() {
+            if count > self.reported[index] {
+                self.reported[index] = count;
+                heard.push(self.phrases[index].clone());
+            }
+        }
+        heard
+    }
+    
+    /// Start a new utterance
+    pub fn reset(&mut self) {
+        self.text.clear();
+        self.state = 0;
+        self.found = vec![0; self.phrases.len()];
+        self.reported = vec![0; self.phrases.len()];
+    }
+}
@@ -0,0 +1,106 @@
+/// Floor used when converting silent frames to dBFS
+pub const SILENCE_DBFS: f32 = -96.0;
+
+/// Convert a linear amplitude (1.0 = full scale) to dBFS
+pub fn amplitude_to_dbfs(amplitude: f32) -> f32 {
+    if amplitude > 0.0 {
+        (20.0 * amplitude.log10()).max(SILENCE_DBFS)
+    } else {
+        SILENCE_DBFS
+    }
+}
+
+/// Compute the RMS level of float samples in dBFS
+pub fn rms_dbfs(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return SILENCE_DBFS;
+    }
+    
+    let sum: f64 = samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    amplitude_to_dbfs((sum / samples.len() as f64).sqrt() as f32)
+}
+
+/// Compute the peak level of float samples in dBFS
+pub fn peak_dbfs(samples: &[f32]) -> f32 {
+    amplitude_to_dbfs(samples.iter().fold(0.0f32, |peak, &s| peak.max(s.abs())))
+}
+
+/// Compute the RMS level of a 16-bit frame in dBFS
+pub fn frame_dbfs(frame: &[i16]) -> f32 {
+    if frame.is_empty() {
+        return SILENCE_DBFS;
+    }
+    
+    let sum: f64 = frame.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    let rms = (sum / frame.len() as f64).sqrt() / i16::MAX as f64;
+    if rms > 0.0 {
+        (20.0 * rms.log10() as f32).max(SILENCE_DBFS)
+    } else {
+        SILENCE_DBFS
+    }
+}
+
+/// Check an energy gate threshold: a finite level at or below full scale
+pub fn check_gate_threshold(threshold_dbfs: f32) -> Result<(), String> {
+    if !threshold_dbfs.is_finite() || threshold_dbfs > 0.0 {
+        return Err("Energy gate threshold must be a finite level <= 0 dBFS".to_string());
+    }
+    Ok(())
+}
+
+/// Time constant of a falling gain, in seconds
+const AGC_ATTACK_SECS: f32 = 0.05;
+
+/// Time constant of a rising gain, in seconds
+const AGC_RELEASE_SECS: f32 = 2.0;
+
+/// Adapts a gain so speech reaches a target level whatever the microphone's sensitivity
+/// 
+/// The level is measured per block; blocks below `gate_db` (silence and
+/// faint noise) leave the gain alone so the background isn't pumped up
+/// between words. The gain falls quickly when the input gets louder and
+/// rises slowly, and is ramped across each block so changes don't click.
+pub struct AutomaticGain {
+    sample_rate: u32,
+    target_db: f32,
+    max_gain_db: f32,
+    gate_db: f32,
+    gain_db: f32,
+}
+
+impl AutomaticGain {
+    pub fn new(sample_rate: u32, target_db: f32, max_gain_db: f32, gate_db: f32) -> Self {
+        AutomaticGain { sample_rate: sample_rate.max(1), target_db, max_gain_db: max_gain_db.abs(), gate_db, gain_db: 0.0 }
+    }
+    
+    /// Current gain in dB
+    pub fn gain_db(&self) -> f32 {
+        self.gain_db
+    }
+    
+    /// Apply the gain to a block in place, then adapt it to the block's level
+    pub fn process(&mut self, block: &mut [f32]) {
+        if block.is_empty() {
+            return;
+        }
+        let level = rms_dbfs(block);
+        let previous = self.gain_db;
+        if level > self.gate_db {
+            let wanted = (self.target_db - level).clamp(-self.max_gain_db, self.max_gain_db);
+            let seconds = block.len() as f32 / self.sample_rate as f32;
+            let time = if wanted < self.gain_db { AGC_ATTACK_SECS } else { AGC_RELEASE_SECS };
+            let coefficient = (-seconds / time).exp();
+            self.gain_db = coefficient * self.gain_db + (1.0 - coefficient) * wanted;
+        }
+        
+        let (start, end) = (10f32.powf(previous / 20.0), 10f32.powf(self.gain_db / 20.0));
+        let step = (end - start) / block.len() as f32;
+        for (n, sample) in block.iter_mut().enumerate() {
+            *sample = (*sample * (start + step * (n + 1) as f32)).clamp(-1.0, 1.0);
+        }
+    }
+    
+    pub fn reset(&mut self) {
+        self.gain_db = 0.0;
+    }
+}
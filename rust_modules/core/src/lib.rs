@@ -0,0 +1,21 @@
+pub mod audio_class;
+pub mod calibration;
+pub mod dereverb;
+pub mod dsp;
+pub mod echo;
+pub mod fingerprint;
+pub mod iir;
+#[cfg(feature = "inference")]
+pub mod inference;
+pub mod keywords;
+pub mod level;
+pub mod loudness;
+pub mod normalize;
+pub mod protocol;
+pub mod resample;
+pub mod ssml;
+pub mod stretch;
+pub mod testing;
+pub mod vad;
+#[cfg(feature = "wake-word")]
+pub mod wake_word;
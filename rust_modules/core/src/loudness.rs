@@ -0,0 +1,116 @@
+use std::f64::consts::PI;
+
+use crate::iir::{Section, SosFilter};
+
+/// Gating block length in seconds (ITU-R BS.1770)
+const BLOCK_SECS: f64 = 0.4;
+
+/// Step between gating blocks in seconds (75% overlap)
+const STEP_SECS: f64 = 0.1;
+
+/// Blocks quieter than this never count, in LUFS
+const ABSOLUTE_GATE: f64 = -70.0;
+
+/// Blocks this far below the absolute-gated loudness don't count, in LU
+const RELATIVE_GATE: f64 = -10.0;
+
+/// EBU R128 programme loudness target, in LUFS
+pub const EBU_R128_TARGET: f32 = -23.0;
+
+/// Highest sample peak normalization may raise audio to, in dBFS
+pub const DEFAULT_MAX_PEAK_DB: f32 = -1.0;
+
+/// K-weighting pre-filter: a high shelf modelling the head, then a high-pass
+/// 
+/// The coefficients are computed from the analog prototypes so any sample
+/// rate works, not just the 48 kHz the standard tabulates.
+fn k_weighting(sample_rate: u32) -> Vec<Section> {
+    let fs = sample_rate as f64;
+    
+    let (gain_db, q, fc) = (3.999843853973347, 0.7071752369554196, 1681.974450955533);
+    let a = 10f64.powf(gain_db / 40.0);
+    let w0 = 2.0 * PI * fc / fs;
+    let (cos, alpha) = (w0.cos(), w0.sin() / (2.0 * q));
+    let root = 2.0 * a.sqrt() * alpha;
+    let shelf = [
+        a * ((a + 1.0) + (a - 1.0) * cos + root),
+        -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+        a * ((a + 1.0) + (a - 1.0) * cos - root),
+        (a + 1.0) - (a - 1.0) * cos + root,
+        2.0 * ((a - 1.0) - (a + 1.0) * cos),
+        (a + 1.0) - (a - 1.0) * cos - root,
+    ];
+    
+    let (q, fc) = (0.5003270373238773, 38.13547087602444);
+    let w0 = 2.0 * PI * fc / fs;
+    let (cos, alpha) = (w0.cos(), w0.sin() / (2.0 * q));
+    let highpass = [
+        (1.0 + cos) / 2.0,
+        -(1.0 + cos),
+        (1.0 + cos) / 2.0,
+        1.0 + alpha,
+        -2.0 * cos,
+        1.0 - alpha,
+    ];
+    
+    vec![shelf, highpass]
+}
+
+fn lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.max(1e-20).log10()
+}
+
+/// Integrated loudness of mono audio in LUFS (ITU-R BS.1770 / EBU R128)
+/// 
+/// Audio shorter than one gating block, such as a notification chime, is
+/// measured as a single block. Returns None for silence.
+pub fn integrated_loudness(samples: &[f32], sample_rate: u32) -> Option<f64> {
+    if samples.is_empty() || sample_rate == 0 {
+        return None;
+    }
+    
+    let mut filter = SosFilter::from_sections(k_weighting(sample_rate)).ok()?;
+    let weighted: Vec<f64> = samples.iter().map(|&s| (filter.process_sample(s) as f64).powi(2)).collect();
+    
+    let block = ((BLOCK_SECS * sample_rate as f64) as usize).clamp(1, weighted.len());
+    let step = ((STEP_SECS * sample_rate as f64) as usize).max(1);
+    let blocks: Vec<f64> = (0..=weighted.len() - block)
+        .step_by(step)
+        .map(|start| weighted[start..start + block].iter().sum::<f64>() / block as f64)
+        .filter(|&power| lufs(power) > ABSOLUTE_GATE)
+        .collect();
+    if blocks.is_empty() {
+        return None;
+    }
+    
+    let threshold = lufs(blocks.iter().sum::<f64>() / blocks.len() as f64) + RELATIVE_GATE;
+    let gated: Vec<f64> = blocks.into_iter().filter(|&power| lufs(power) > threshold).collect();
+    if gated.is_empty() {
+        return None;
+    }
+    Some(lufs(gated.iter().sum::<f64>() / gated.len() as f64))
+}
+
+/// Linear gain that brings audio from `loudness` to `target` LUFS
+/// 
+/// The gain is lowered if it would push the sample peak above
+/// `max_peak_db`, so quiet but spiky sounds aren't clipped.
+pub fn normalization_gain(loudness: f64, target: f32, peak: f32, max_peak_db: f32) -> f32 {
+    let gain = 10f64.powf((target as f64 - loudness) / 20.0) as f32;
+    if peak > 0.0 {
+        gain.min(10f32.powf(max_peak_db / 20.0) / peak)
+    } else {
+        gain
+    }
+}
+
+/// Scale mono audio in place to `target` LUFS, returning the measured loudness
+/// 
+/// Silent audio is left alone and returns None.
+pub fn normalize_loudness(samples: &mut [f32], sample_rate: u32, target: f32, max_peak_db: f32) -> Option<f64> {
+    let loudness = integrated_loudness(samples, sample_rate)?;
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let gain = normalization_gain(loudness, target, peak, max_peak_db);
+    samples.iter_mut().for_each(|s| *s *= gain);
+    Some(loudness)
+}
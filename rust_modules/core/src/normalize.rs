@@ -0,0 +1,352 @@
+use crate::ssml::{date_to_words, number_to_words, ordinal_to_words, time_to_words, year_to_words};
+
+/// A currency symbol with its major and minor unit names (singular, plural)
+struct Currency {
+    symbol: &'static str,
+    major: (&'static str, &'static str),
+    minor: (&'static str, &'static str),
+}
+
+const CURRENCIES: [Currency; 7] = [
+    Currency { symbol: "$", major: ("dollar", "dollars"), minor: ("cent", "cents") },
+    Currency { symbol: "£", major: ("pound", "pounds"), minor: ("penny", "pence") },
+    Currency { symbol: "€", major: ("euro", "euros"), minor: ("cent", "cents") },
+    Currency { symbol: "₹", major: ("rupee", "rupees"), minor: ("paisa", "paise") },
+    Currency { symbol: "Rs.", major: ("rupee", "rupees"), minor: ("paisa", "paise") },
+    Currency { symbol: "Rs", major: ("rupee", "rupees"), minor: ("paisa", "paise") },
+    Currency { symbol: "¥", major: ("yen", "yen"), minor: ("sen", "sen") },
+];
+
+/// Unit abbreviations (singular, plural); single letters are matched case-sensitively
+const UNITS: [(&str, &str, &str); 32] = [
+    ("%", "percent", "percent"),
+    ("°C", "degree Celsius", "degrees Celsius"),
+    ("°F", "degree Fahrenheit", "degrees Fahrenheit"),
+    ("°", "degree", "degrees"),
+    ("km/h", "kilometer per hour", "kilometers per hour"),
+    ("kmh", "kilometer per hour", "kilometers per hour"),
+    ("mph", "mile per hour", "miles per hour"),
+    ("km", "kilometer", "kilometers"),
+    ("m", "meter", "meters"),
+    ("cm", "centimeter", "centimeters"),
+    ("mm", "millimeter", "millimeters"),
+    ("mi", "mile", "miles"),
+    ("ft", "foot", "feet"),
+    ("kg", "kilogram", "kilograms"),
+    ("g", "gram", "grams"),
+    ("mg", "milligram", "milligrams"),
+    ("lb", "pound", "pounds"),
+    ("lbs", "pound", "pounds"),
+    ("oz", "ounce", "ounces"),
+    ("ml", "milliliter", "milliliters"),
+    ("kb", "kilobyte", "kilobytes"),
+    ("mb", "megabyte", "megabytes"),
+    ("gb", "gigabyte", "gigabytes"),
+    ("tb", "terabyte", "terabytes"),
+    ("ms", "millisecond", "milliseconds"),
+    ("sec", "second", "seconds"),
+    ("min", "minute", "minutes"),
+    ("hr", "hour", "hours"),
+    ("hrs", "hour", "hours"),
+    ("kwh", "kilowatt hour", "kilowatt hours"),
+    ("hz", "hertz", "hertz"),
+    ("khz", "kilohertz", "kilohertz"),
+];
+
+/// Abbreviations expanded wherever they appear (with their trailing period)
+const ABBREVIATIONS: [(&str, &str); 16] = [
+    ("Dr.", "Doctor"),
+    ("Mr.", "Mister"),
+    ("Mrs.", "Missus"),
+    ("Ms.", "Miz"),
+    ("Prof.", "Professor"),
+    ("Jr.", "Junior"),
+    ("Sr.", "Senior"),
+    ("Mt.", "Mount"),
+    ("Ave.", "Avenue"),
+    ("Rd.", "Road"),
+    ("etc.", "et cetera"),
+    ("e.g.", "for example"),
+    ("i.e.", "that is"),
+    ("vs.", "versus"),
+    ("approx.", "approximately"),
+    ("dept.", "department"),
+];
+
+/// Scale suffixes for amounts such as "$5bn"
+const SCALES: [(&str, &str); 5] = [("k", "thousand"), ("m", "million"), ("M", "million"), ("bn", "billion"), ("b", "billion")];
+
+/// Rewrites numbers, dates, times, currencies, abbreviations and units as words
+/// 
+/// Rules are chosen per language tag. Only English is supported; for
+/// other languages espeak-ng's own number handling is left to do the work.
+/// Regional variants differ in date order ("12/05" is December fifth in
+/// en_US and the twelfth of May elsewhere).
+#[derive(Clone, Debug)]
+pub struct TextNormalizer {
+    language: String,
+    date_order: &'static str,
+}
+
+impl TextNormalizer {
+    /// Rules for a language tag such as "en_US" or "en-GB", or None if unsupported
+    pub fn for_language(tag: &str) -> Option<Self> {
+        let tag = tag.replace('-', "_");
+        let mut parts = tag.split('_');
+        let language = parts.next()?.to_ascii_lowercase();
+        let region = parts.next().map(|r| r.to_ascii_uppercase());
+        if language != "en" {
+            return None;
+        }
+        
+        let date_order = match region.as_deref() {
+            None | Some("US") | Some("PH") => "mdy",
+            _ => "dmy",
+        };
+        Some(TextNormalizer { language: tag, date_order })
+    }
+    
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+    
+    /// Normalize text, keeping line breaks (which end sentences) intact
+    pub fn normalize(&self, text: &str) -> String {
+        text.lines().map(|line| self.normalize_line(line)).collect::<Vec<_>>().join("\n")
+    }
+    
+    fn normalize_line(&self, line: &str) -> String {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let mut words = Vec::with_capacity(tokens.len());
+        let mut i = 0;
+        while i < tokens.len() {
+            let (consumed, expansion) = self.expand(&tokens, i);
+            words.push(expansion);
+            i += consumed;
+        }
+        words.join(" ")
+    }
+    
+    /// Expand the token at `i`, possibly together with the next one
+    /// 
+    /// Returns how many tokens were consumed and their expansion.
+    fn expand(&self, tokens: &[&str], i: usize) -> (usize, String) {
+        let token = tokens[i];
+        if let Some(expansion) = abbreviation(token) {
+            return (1, expansion);
+        }
+        
+        // "Rs. 500", "$ 20"
+        if let (Some(currency), Some(next)) = (CURRENCIES.iter().find(|c| c.symbol == token), tokens.get(i + 1)) {
+            let (_, amount, trail) = split_punctuation(next);
+            if let Some(words) = expand_amount(amount, currency) {
+                return (2, format!("{}{}", words, trail));
+            }
+        }
+        
+        let (lead, core, trail) = split_punctuation(token);
+        if let Some(next) = tokens.get(i + 1).filter(|_| trail.is_empty()) {
+            let (_, next_core, next_trail) = split_punctuation(next);
+            
+            // "3:30 pm"
+            if let Some(suffix) = meridiem(next_core) {
+                if let Some(words) = expand_time(&format!("{}{}", core, suffix)) {
+                    return (2, format!("{}{}{}", lead, words, next_trail));
+                }
+            }
+            // "10 km"
+            if let (Some(number), Some(unit)) = (number_words(core), unit_words(next_core, core == "1")) {
+                return (2, format!("{}{} {}{}", lead, number, unit, next_trail));
+            }
+        }
+        
+        (1, format!("{}{}{}", lead, self.expand_word(core), trail))
+    }
+    
+    fn expand_word(&self, core: &str) -> String {
+        if !core.chars().any(|c| c.is_ascii_digit()) {
+            return core.to_string();
+        }
+        
+        expand_time(core)
+            .or_else(|| self.expand_date(core))
+            .or_else(|| CURRENCIES.iter().find_map(|c| core.strip_prefix(c.symbol).and_then(|amount| expand_amount(amount, c))))
+            .or_else(|| expand_ordinal(core))
+            .or_else(|| number_words(core))
+            .or_else(|| expand_measurement(core))
+            .unwrap_or_else(|| core.to_string())
+    }
+    
+    /// "12/05", "12/05/2024", "12/05/24" or ISO "2024-12-05"
+    fn expand_date(&self, core: &str) -> Option<String> {
+        let bytes = core.as_bytes();
+        if bytes.len() == 10 && bytes[4] == b'-' && bytes[7] == b'-' {
+            return date_to_words(core, "ymd");
+        }
+        
+        let parts: Vec<&str> = core.split('/').collect();
+        let valid = (2..=3).contains(&parts.len())
+            && parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+            && parts[..2].iter().all(|p| p.len() <= 2)
+            && parts.get(2).map(|y| y.len() == 2 || y.len() == 4).unwrap_or(true);
+        if !valid {
+            return None;
+        }
+        
+        let day = parts[self.date_order.find('d')?].parse::<u32>().ok()?;
+        if !(1..=31).contains(&day) {
+            return None;
+        }
+        let mut fields: Vec<String> = parts.iter().map(|p| p.to_string()).collect();
+        if let Some(year) = fields.get_mut(2) {
+            if year.len() == 2 {
+                year.insert_str(0, "20");
+            }
+        }
+        date_to_words(&fields.join("/"), &self.date_order[..parts.len()])
+    }
+}
+
+/// Split leading and trailing punctuation off a token
+fn split_punctuation(token: &str) -> (&str, &str, &str) {
+    let is_lead = |c: char| matches!(c, '(' | '[' | '{' | '"' | '\'' | '“' | '‘');
+    let is_trail = |c: char| matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | ')' | ']' | '}' | '"' | '\'' | '”' | '’');
+    
+    let rest = token.trim_start_matches(is_lead);
+    let lead = &token[..token.len() - rest.len()];
+    let core = rest.trim_end_matches(is_trail);
+    let trail = &rest[core.len()..];
+    (lead, core, trail)
+}
+
+fn abbreviation(token: &str) -> Option<String> {
+    let stripped = token.trim_end_matches([',', ';', ':', '!', '?', ')', '"']);
+    let (_, expansion) = ABBREVIATIONS.iter().find(|(abbreviation, _)| *abbreviation == stripped)?;
+    Some(format!("{}{}", expansion, &token[stripped.len()..]))
+}
+
+fn meridiem(text: &str) -> Option<&'static str> {
+    match text.to_ascii_lowercase().as_str() {
+        "am" | "a.m" | "a.m." => Some("am"),
+        "pm" | "p.m" | "p.m." => Some("pm"),
+        _ => None,
+    }
+}
+
+/// "3:30pm", "3pm", "15:05"
+fn expand_time(core: &str) -> Option<String> {
+    let lower = core.to_ascii_lowercase();
+    let split = lower.find(|c: char| !c.is_ascii_digit() && c != ':').unwrap_or(lower.len());
+    let (clock, suffix) = lower.split_at(split);
+    let suffix = if suffix.is_empty() { None } else { Some(meridiem(suffix)?) };
+    
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    let is_clock = match clock.split_once(':') {
+        Some((hour, minute)) => is_digits(hour) && hour.len() <= 2 && is_digits(minute) && minute.len() == 2,
+        None => is_digits(clock) && clock.len() <= 2 && suffix.is_some(),
+    };
+    if !is_clock {
+        return None;
+    }
+    time_to_words(&format!("{}{}", clock, suffix.unwrap_or("")))
+}
+
+/// Parse "1,234.56" / "-3" into words; four-digit integers are read as years
+fn number_words(text: &str) -> Option<String> {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => ("minus ", rest),
+        None => ("", text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (integer, fraction) = match unsigned.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    
+    let groups: Vec<&str> = integer.split(',').collect();
+    let grouped = groups.len() > 1;
+    let valid_groups = groups.iter().enumerate().all(|(i, g)| {
+        !g.is_empty() && g.chars().all(|c| c.is_ascii_digit()) && (!grouped || if i == 0 { g.len() <= 3 } else { g.len() == 3 })
+    });
+    let valid_fraction = fraction.map(|f| !f.is_empty() && f.chars().all(|c| c.is_ascii_digit())).unwrap_or(true);
+    if !valid_groups || !valid_fraction {
+        return None;
+    }
+    
+    let digits: String = groups.concat();
+    let integer_words = if digits.len() > 1 && digits.starts_with('0') {
+        spell_digits(&digits)
+    } else {
+        let value: u64 = digits.parse().ok()?;
+        if !grouped && fraction.is_none() && sign.is_empty() && digits.len() == 4 && (1100..2100).contains(&value) {
+            year_to_words(value)
+        } else {
+            number_to_words(value)
+        }
+    };
+    
+    Some(match fraction {
+        Some(fraction) => format!("{}{} point {}", sign, integer_words, spell_digits(fraction)),
+        None => format!("{}{}", sign, integer_words),
+    })
+}
+
+fn spell_digits(digits: &str) -> String {
+    digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .map(|d| number_to_words(d as u64))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// "21st", "3rd"
+fn expand_ordinal(core: &str) -> Option<String> {
+    let split = core.find(|c: char| !c.is_ascii_digit())?;
+    let (digits, suffix) = core.split_at(split);
+    if digits.is_empty() || !matches!(suffix.to_ascii_lowercase().as_str(), "st" | "nd" | "rd" | "th") {
+        return None;
+    }
+    digits.parse().ok().map(ordinal_to_words)
+}
+
+fn unit_words(text: &str, singular: bool) -> Option<String> {
+    let (_, one, many) = UNITS.iter().find(|(unit, _, _)| {
+        if unit.chars().count() == 1 {
+            *unit == text
+        } else {
+            unit.eq_ignore_ascii_case(text)
+        }
+    })?;
+    let words = if singular { one } else { many };
+    Some(words.to_string())
+}
+
+/// "10km", "25°C", "50%"
+fn expand_measurement(core: &str) -> Option<String> {
+    let split = core.find(|c: char| !c.is_ascii_digit() && !matches!(c, '.' | ',' | '-' | '+'))?;
+    let (number, unit) = core.split_at(split);
+    let words = number_words(number)?;
+    Some(format!("{} {}", words, unit_words(unit, number == "1")?))
+}
+
+/// "5.50" -> "five dollars and fifty cents", "2.5m" -> "two point five million dollars"
+fn expand_amount(amount: &str, currency: &Currency) -> Option<String> {
+    if let Some((number, scale)) = SCALES.iter().find_map(|(suffix, scale)| amount.strip_suffix(suffix).map(|n| (n, scale))) {
+        return Some(format!("{} {} {}", number_words(number)?, scale, currency.major.1));
+    }
+    
+    let (integer, cents) = match amount.split_once('.') {
+        Some((integer, cents)) if cents.len() == 2 => (integer, cents.parse::<u64>().ok()?),
+        Some(_) => return None,
+        None => (amount, 0),
+    };
+    let whole: u64 = integer.replace(',', "").parse().ok()?;
+    
+    let major = format!("{} {}", number_to_words(whole), if whole == 1 { currency.major.0 } else { currency.major.1 });
+    let minor = format!("{} {}", number_to_words(cents), if cents == 1 { currency.minor.0 } else { currency.minor.1 });
+    Some(match (whole, cents) {
+        (_, 0) => major,
+        (0, _) => minor,
+        _ => format!("{} and {}", major, minor),
+    })
+}
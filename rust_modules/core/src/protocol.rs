@@ -0,0 +1,182 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ProtocolError {
+    #[error("Invalid JSON message: {0}")]
+    Json(#[from] serde_json::Error),
+    
+    #[error("Failed to encode MessagePack message: {0}")]
+    Encode(#[from] rmp_serde::encode::Error),
+    
+    #[error("Invalid MessagePack message: {0}")]
+    Decode(#[from] rmp_serde::decode::Error),
+    
+    #[error("Message has no \"type\" field")]
+    MissingType,
+}
+
+/// Wire format of protocol messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Json,
+    MessagePack,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Ok(Encoding::Json),
+            "msgpack" | "messagepack" => Ok(Encoding::MessagePack),
+            _ => Err(format!("Unknown encoding '{}'; expected 'json' or 'msgpack'", name)),
+        }
+    }
+}
+
+/// Encoding of the binary audio messages a client sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Codec {
+    /// Interleaved signed 16-bit little-endian PCM
+    #[default]
+    Pcm,
+    /// One raw Opus packet per message
+    Opus,
+}
+
+/// Messages a client sends to the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Start streaming audio; binary messages that follow are fed to the pipeline
+    Start {
+        #[serde(default = "default_sample_rate")]
+        sample_rate: u32,
+        #[serde(default = "default_channels")]
+        channels: u32,
+        #[serde(default)]
+        codec: Codec,
+    },
+    /// Stop streaming audio and release the engines
+    Stop,
+    /// Start a turn without the wake word
+    Trigger,
+    /// The client started or finished playing audio
+    Playback { playing: bool },
+    /// Synthesize `text` and stream it back
+    Say { text: String },
+    Ping,
+}
+
+impl ClientMessage {
+    /// Values of the "type" field that belong to client messages
+    pub const TYPES: [&'static str; 6] = ["start", "stop", "trigger", "playback", "say", "ping"];
+}
+
+fn default_sample_rate() -> u32 {
+    16000
+}
+
+fn default_channels() -> u32 {
+    1
+}
+
+/// Messages the server sends to a client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Greeting with the audio format the engines expect
+    Ready {
+        client: u64,
+        sample_rate: u32,
+        frame_length: usize,
+        /// Whether `say` is available
+        tts: bool,
+    },
+    Started,
+    Stopped,
+    Pong,
+    /// A pipeline event
+    /// 
+    /// `kind` is "wake" (with `keyword` and `confidence`), "speech_start",
+    /// "partial" or "final" (with the transcript in `text`), "timeout" or
+    /// "error" (with the message in `text`).
+    Event {
+        kind: String,
+        text: Option<String>,
+        keyword: Option<String>,
+        confidence: Option<f32>,
+        timestamp: f64,
+    },
+    /// An assistant state transition
+    State {
+        previous: String,
+        state: String,
+        reason: String,
+        timestamp: f64,
+    },
+    /// The wake word interrupted the client's playback; stop playing
+    Interrupt,
+    /// Synthesized speech follows as binary PCM messages or `tts_chunk` messages
+    TtsStart {
+        text: String,
+        sample_rate: u32,
+        channels: u32,
+        format: String,
+    },
+    /// A block of synthesized speech, for transports without binary messages
+    TtsChunk { samples: Vec<i16> },
+    TtsEnd,
+    Error { message: String },
+}
+
+/// Any protocol message, told apart by its "type" field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Message {
+    Client(ClientMessage),
+    Server(ServerMessage),
+}
+
+impl Message {
+    /// Parse a message from its JSON object form
+    pub fn from_value(value: Value) -> Result<Self, ProtocolError> {
+        let kind = value.get("type").and_then(Value::as_str).ok_or(ProtocolError::MissingType)?;
+        if ClientMessage::TYPES.contains(&kind) {
+            Ok(Message::Client(serde_json::from_value(value)?))
+        } else {
+            Ok(Message::Server(serde_json::from_value(value)?))
+        }
+    }
+    
+    /// JSON object form of the message
+    pub fn to_value(&self) -> Value {
+        // Protocol messages only contain strings, numbers and lists
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+    
+    pub fn encode(&self, encoding: Encoding) -> Result<Vec<u8>, ProtocolError> {
+        encode(self, encoding)
+    }
+    
+    pub fn decode(data: &[u8], encoding: Encoding) -> Result<Self, ProtocolError> {
+        Message::from_value(decode(data, encoding)?)
+    }
+}
+
+/// Serialize a message; MessagePack messages are maps with named fields like the JSON ones
+pub fn encode<T: Serialize>(message: &T, encoding: Encoding) -> Result<Vec<u8>, ProtocolError> {
+    Ok(match encoding {
+        Encoding::Json => serde_json::to_vec(message)?,
+        Encoding::MessagePack => rmp_serde::to_vec_named(message)?,
+    })
+}
+
+pub fn decode<T: DeserializeOwned>(data: &[u8], encoding: Encoding) -> Result<T, ProtocolError> {
+    Ok(match encoding {
+        Encoding::Json => serde_json::from_slice(data)?,
+        Encoding::MessagePack => rmp_serde::from_slice(data)?,
+    })
+}
@@ -0,0 +1,87 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("Audio format error: {0}")]
+pub struct ResampleError(pub String);
+
+/// Streaming mono resampler that accepts arbitrarily sized input blocks
+pub struct MonoResampler {
+    resampler: Option<rubato::FftFixedIn<f32>>,
+    pending: Vec<f32>,
+}
+
+impl MonoResampler {
+    /// Chunk size fed to the FFT resampler
+    pub const CHUNK_SIZE: usize = 1024;
+    
+    pub fn new(input_rate: u32, output_rate: u32) -> Result<Self, ResampleError> {
+        let resampler = if input_rate == output_rate {
+            None
+        } else {
+            Some(
+                rubato::FftFixedIn::<f32>::new(input_rate as usize, output_rate as usize, Self::CHUNK_SIZE, 2, 1)
+                    .map_err(|err| ResampleError(err.to_string()))?,
+            )
+        };
+        
+        Ok(MonoResampler { resampler, pending: Vec::new() })
+    }
+    
+    /// Input samples consumed by the next resampling step
+    pub fn input_frames_next(&self) -> usize {
+        use rubato::Resampler;
+        
+        self.resampler.as_ref().map_or(0, |resampler| resampler.input_frames_next())
+    }
+    
+    /// Output samples the resampler emits before the first input sample appears
+    pub fn output_delay(&self) -> usize {
+        use rubato::Resampler;
+        
+        self.resampler.as_ref().map_or(0, |resampler| resampler.output_delay())
+    }
+    
+    /// Push input samples and return all output samples that are ready
+    pub fn push(&mut self, samples: &[f32]) -> Result<Vec<f32>, ResampleError> {
+        use rubato::Resampler;
+        
+        let resampler = match self.resampler.as_mut() {
+            Some(resampler) => resampler,
+            None => return Ok(samples.to_vec()),
+        };
+        
+        self.pending.extend_from_slice(samples);
+        let mut output = Vec::new();
+        loop {
+            let needed = resampler.input_frames_next();
+            if self.pending.len() < needed {
+                break;
+            }
+            let chunk: Vec<f32> = self.pending.drain(..needed).collect();
+            let resampled = resampler
+                .process(&[chunk], None)
+                .map_err(|err| ResampleError(err.to_string()))?;
+            output.extend_from_slice(&resampled[0]);
+        }
+        Ok(output)
+    }
+}
+
+/// Resample a complete mono signal, flushing the resampler tail
+pub fn resample_mono(samples: &[f32], input_rate: u32, output_rate: u32) -> Result<Vec<f32>, ResampleError> {
+    let mut resampler = MonoResampler::new(input_rate, output_rate)?;
+    let mut output = resampler.push(samples)?;
+    if input_rate != output_rate {
+        // Flush with silence until the delayed output covers the whole input
+        let delay = resampler.output_delay();
+        let expected = (samples.len() as u64 * output_rate as u64 / input_rate as u64) as usize;
+        while output.len() < delay + expected {
+            let padding = resampler.input_frames_next();
+            output.extend(resampler.push(&vec![0.0; padding])?);
+        }
+        // Drop the delay so the output lines up with the input, then trim the padding
+        output.drain(..delay);
+        output.truncate(expected);
+    }
+    Ok(output)
+}
@@ -0,0 +1,445 @@
+/// Prosody applied to a run of text, as multipliers of the voice defaults
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Prosody {
+    /// Speaking rate (2.0 is twice as fast)
+    pub rate: f32,
+    /// Pitch (1.059 is one semitone up)
+    pub pitch: f32,
+    /// Linear gain
+    pub volume: f32,
+}
+
+impl Default for Prosody {
+    fn default() -> Self {
+        Prosody { rate: 1.0, pitch: 1.0, volume: 1.0 }
+    }
+}
+
+impl Prosody {
+    /// Apply `other` on top of this prosody
+    pub fn combine(self, other: Prosody) -> Prosody {
+        Prosody {
+            rate: self.rate * other.rate,
+            pitch: self.pitch * other.pitch,
+            volume: self.volume * other.volume,
+        }
+    }
+}
+
+/// A piece of parsed SSML: text to speak, or a pause
+#[derive(Clone, Debug, PartialEq)]
+pub enum SsmlSegment {
+    Text { text: String, prosody: Prosody },
+    Break(f32),
+}
+
+/// Rate, pitch and gain applied by `<emphasis level="...">`
+fn emphasis_prosody(level: &str) -> (f32, f32, f32) {
+    match level {
+        "strong" => (0.8, 1.08, 1.4),
+        "reduced" => (1.1, 0.97, 0.8),
+        "none" => (1.0, 1.0, 1.0),
+        _ => (0.9, 1.04, 1.2),
+    }
+}
+
+/// Check whether input should be treated as SSML
+pub fn is_ssml(text: &str) -> bool {
+    text.trim_start().starts_with("<speak")
+}
+
+/// Parse a percentage ("+20%", "80%") relative to 1.0
+fn parse_percent(value: &str, relative: bool) -> Option<f32> {
+    let number: f32 = value.trim_end_matches('%').parse().ok()?;
+    Some(if relative { 1.0 + number / 100.0 } else { number / 100.0 })
+}
+
+/// Parse a prosody rate: keyword, percentage or plain multiplier
+fn parse_rate(value: &str) -> Option<f32> {
+    match value {
+        "x-slow" => Some(0.6),
+        "slow" => Some(0.8),
+        "medium" | "default" => Some(1.0),
+        "fast" => Some(1.25),
+        "x-fast" => Some(1.5),
+        v if v.ends_with('%') => parse_percent(v, v.starts_with('+') || v.starts_with('-')),
+        v => v.parse().ok(),
+    }
+}
+
+/// Parse a prosody pitch: keyword, relative percentage or semitones ("+2st")
+fn parse_pitch(value: &str) -> Option<f32> {
+    match value {
+        "x-low" => Some(0.8),
+        "low" => Some(0.9),
+        "medium" | "default" => Some(1.0),
+        "high" => Some(1.1),
+        "x-high" => Some(1.2),
+        v if v.ends_with("st") => v.trim_end_matches("st").parse::<f32>().ok().map(|st| 2f32.powf(st / 12.0)),
+        v if v.ends_with('%') => parse_percent(v, true),
+        _ => None,
+    }
+}
+
+/// Parse a prosody volume: keyword or relative decibels ("+6dB")
+fn parse_volume(value: &str) -> Option<f32> {
+    match value {
+        "silent" => Some(0.0),
+        "x-soft" => Some(0.4),
+        "soft" => Some(0.7),
+        "medium" | "default" => Some(1.0),
+        "loud" => Some(1.4),
+        "x-loud" => Some(1.8),
+        v if v.to_ascii_lowercase().ends_with("db") => {
+            v[..v.len() - 2].parse::<f32>().ok().map(|db| 10f32.powf(db / 20.0))
+        }
+        _ => None,
+    }
+}
+
+/// Parse a break: `time="500ms"`/`"1.5s"` or a strength keyword
+fn parse_break(attributes: &[(String, String)]) -> f32 {
+    if let Some(time) = attribute(attributes, "time") {
+        if let Some(ms) = time.strip_suffix("ms") {
+            return ms.parse::<f32>().unwrap_or(0.0) / 1000.0;
+        }
+        if let Some(s) = time.strip_suffix('s') {
+            return s.parse().unwrap_or(0.0);
+        }
+    }
+    
+    match attribute(attributes, "strength").unwrap_or("medium") {
+        "none" | "x-weak" => 0.0,
+        "weak" => 0.1,
+        "strong" => 0.5,
+        "x-strong" => 1.0,
+        _ => 0.25,
+    }
+}
+
+fn attribute<'a>(attributes: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Split a tag body into its name and attributes
+fn parse_tag(body: &str) -> (String, Vec<(String, String)>) {
+    let body = body.trim().trim_end_matches('/').trim();
+    let (name, mut rest) = match body.find(char::is_whitespace) {
+        Some(index) => (&body[..index], &body[index..]),
+        None => (body, ""),
+    };
+    
+    let mut attributes = Vec::new();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().to_string();
+        let after = rest[eq + 1..].trim_start();
+        let quote = match after.chars().next() {
+            Some(q) if q == '"' || q == '\'' => q,
+            _ => break,
+        };
+        let value_end = match after[1..].find(quote) {
+            Some(end) => end + 1,
+            None => break,
+        };
+        attributes.push((key, decode_entities(&after[1..value_end])));
+        rest = &after[value_end + 1..];
+    }
+    (name.to_ascii_lowercase(), attributes)
+}
+
+/// Parse the supported SSML subset into segments
+/// 
+/// Supports `<speak>`, `<break>`, `<emphasis>`, `<prosody rate pitch
+/// volume>`, `<say-as interpret-as>` and `<s>`/`<p>` (which add a short
+/// pause). Unknown tags are ignored but their text is kept.
+pub fn parse_ssml(input: &str) -> Result<Vec<SsmlSegment>, String> {
+    let mut segments = Vec::new();
+    let mut stack: Vec<(String, Prosody)> = Vec::new();
+    let mut say_as: Option<(String, Option<String>)> = None;
+    let mut prosody = Prosody::default();
+    let mut rest = input;
+    
+    let push_text = |segments: &mut Vec<SsmlSegment>, text: String, prosody: Prosody| {
+        if text.trim().is_empty() {
+            return;
+        }
+        match segments.last_mut() {
+            Some(SsmlSegment::Text { text: last, prosody: last_prosody }) if *last_prosody == prosody => {
+                last.push(' ');
+                last.push_str(text.trim());
+            }
+            _ => segments.push(SsmlSegment::Text { text: text.trim().to_string(), prosody }),
+        }
+    };
+    
+    while !rest.is_empty() {
+        let (text, after) = match rest.find('<') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, ""),
+        };
+        
+        if !text.is_empty() {
+            let text = decode_entities(text);
+            let text = match &say_as {
+                Some((kind, format)) => interpret_as(&text, kind, format.as_deref()),
+                None => text,
+            };
+            push_text(&mut segments, text, prosody);
+        }
+        if after.is_empty() {
+            break;
+        }
+        
+        let end = after.find('>').ok_or_else(|| "Unterminated SSML tag".to_string())?;
+        let body = &after[1..end];
+        rest = &after[end + 1..];
+        
+        if body.starts_with('?') || body.starts_with('!') {
+            continue;
+        }
+        
+        if let Some(closing) = body.strip_prefix('/') {
+            let name = closing.trim().to_ascii_lowercase();
+            if name == "say-as" {
+                say_as = None;
+                continue;
+            }
+            match stack.pop() {
+                Some((open, previous)) if open == name => prosody = previous,
+                Some((open, _)) => return Err(format!("Mismatched SSML tags: <{}> closed by </{}>", open, name)),
+                None => return Err(format!("Unexpected closing tag </{}>", name)),
+            }
+            if name == "s" || name == "p" {
+                segments.push(SsmlSegment::Break(if name == "p" { 0.4 } else { 0.2 }));
+            }
+            continue;
+        }
+        
+        let self_closing = body.trim_end().ends_with('/');
+        let (name, attributes) = parse_tag(body);
+        match name.as_str() {
+            "break" => {
+                segments.push(SsmlSegment::Break(parse_break(&attributes)));
+                continue;
+            }
+            "say-as" => {
+                let kind = attribute(&attributes, "interpret-as").unwrap_or("").to_ascii_lowercase();
+                let format = attribute(&attributes, "format").map(|f| f.to_string());
+                say_as = Some((kind, format));
+                continue;
+            }
+            _ => {}
+        }
+        if self_closing {
+            continue;
+        }
+        
+        stack.push((name.clone(), prosody));
+        match name.as_str() {
+            "prosody" => {
+                if let Some(rate) = attribute(&attributes, "rate").and_then(parse_rate) {
+                    prosody.rate *= rate.clamp(0.25, 4.0);
+                }
+                if let Some(pitch) = attribute(&attributes, "pitch").and_then(parse_pitch) {
+                    prosody.pitch *= pitch.clamp(0.5, 2.0);
+                }
+                if let Some(volume) = attribute(&attributes, "volume").and_then(parse_volume) {
+                    prosody.volume *= volume.clamp(0.0, 4.0);
+                }
+            }
+            "emphasis" => {
+                let (rate, pitch, volume) = emphasis_prosody(attribute(&attributes, "level").unwrap_or("moderate"));
+                prosody.rate *= rate;
+                prosody.pitch *= pitch;
+                prosody.volume *= volume;
+            }
+            _ => {}
+        }
+    }
+    
+    if let Some((open, _)) = stack.iter().rev().find(|(name, _)| name != "speak") {
+        return Err(format!("Unclosed SSML tag <{}>", open));
+    }
+    Ok(segments)
+}
+
+pub const ONES: [&str; 20] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+];
+
+pub const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+
+pub const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Spell out a non-negative integer in English words
+pub fn number_to_words(n: u64) -> String {
+    if n < 20 {
+        return ONES[n as usize].to_string();
+    }
+    if n < 100 {
+        let tens = TENS[(n / 10) as usize];
+        return if n.is_multiple_of(10) { tens.to_string() } else { format!("{} {}", tens, ONES[(n % 10) as usize]) };
+    }
+    if n < 1000 {
+        let hundreds = format!("{} hundred", ONES[(n / 100) as usize]);
+        return if n.is_multiple_of(100) { hundreds } else { format!("{} {}", hundreds, number_to_words(n % 100)) };
+    }
+    
+    const SCALES: [(u64, &str); 4] = [
+        (1_000_000_000_000, "trillion"),
+        (1_000_000_000, "billion"),
+        (1_000_000, "million"),
+        (1_000, "thousand"),
+    ];
+    for (scale, name) in SCALES {
+        if n >= scale {
+            let head = format!("{} {}", number_to_words(n / scale), name);
+            return if n.is_multiple_of(scale) { head } else { format!("{} {}", head, number_to_words(n % scale)) };
+        }
+    }
+    unreachable!()
+}
+
+/// Spell out an ordinal ("twenty first")
+pub fn ordinal_to_words(n: u64) -> String {
+    let words = number_to_words(n);
+    let (head, last) = match words.rfind(' ') {
+        Some(index) => (&words[..=index], &words[index + 1..]),
+        None => ("", words.as_str()),
+    };
+    
+    let last = match last {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        w if w.ends_with('y') => format!("{}ieth", &w[..w.len() - 1]),
+        w => format!("{}th", w),
+    };
+    format!("{}{}", head, last)
+}
+
+/// Spell out a year the way it is usually spoken ("nineteen eighty four")
+pub fn year_to_words(year: u64) -> String {
+    if (1100..2000).contains(&year) || (2010..10000).contains(&year) {
+        let (century, rest) = (year / 100, year % 100);
+        match rest {
+            0 => format!("{} hundred", number_to_words(century)),
+            1..=9 => format!("{} oh {}", number_to_words(century), number_to_words(rest)),
+            _ => format!("{} {}", number_to_words(century), number_to_words(rest)),
+        }
+    } else {
+        number_to_words(year)
+    }
+}
+
+/// Spell each character separately
+fn spell_out(text: &str) -> String {
+    text.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c.to_digit(10) {
+            Some(d) => ONES[d as usize].to_string(),
+            None => c.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Speak a date given `format` (e.g. "mdy", "dmy", "ymd", "md")
+pub fn date_to_words(text: &str, format: &str) -> Option<String> {
+    let parts: Vec<u64> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse().ok())
+        .collect::<Option<_>>()?;
+    if parts.len() != format.len() {
+        return None;
+    }
+    
+    let (mut day, mut month, mut year) = (None, None, None);
+    for (field, value) in format.chars().zip(parts) {
+        match field {
+            'd' => day = Some(value),
+            'm' => month = Some(value),
+            'y' => year = Some(value),
+            _ => return None,
+        }
+    }
+    
+    let mut words = Vec::new();
+    if let Some(month) = month {
+        words.push(MONTHS.get((month as usize).checked_sub(1)?)?.to_string());
+    }
+    if let Some(day) = day {
+        words.push(ordinal_to_words(day));
+    }
+    if let Some(year) = year {
+        words.push(year_to_words(year));
+    }
+    Some(words.join(" "))
+}
+
+/// Speak a clock time such as "3:30pm" or "15:05"
+pub fn time_to_words(text: &str) -> Option<String> {
+    let lower = text.trim().to_ascii_lowercase();
+    let (clock, suffix) = if let Some(clock) = lower.strip_suffix("am").or_else(|| lower.strip_suffix("a.m.")) {
+        (clock.trim(), Some("AM"))
+    } else if let Some(clock) = lower.strip_suffix("pm").or_else(|| lower.strip_suffix("p.m.")) {
+        (clock.trim(), Some("PM"))
+    } else {
+        (lower.as_str(), None)
+    };
+    
+    let mut parts = clock.split(':');
+    let hour: u64 = parts.next()?.trim().parse().ok()?;
+    let minute: u64 = parts.next().map(|m| m.trim().parse().ok()).unwrap_or(Some(0))?;
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+    
+    let minutes = match minute {
+        0 if suffix.is_some() => String::new(),
+        0 => " hundred".to_string(),
+        1..=9 => format!(" oh {}", number_to_words(minute)),
+        _ => format!(" {}", number_to_words(minute)),
+    };
+    let hour_words = match (hour, minute, suffix) {
+        (0, 0, None) => return Some("midnight".to_string()),
+        (0, _, None) => "zero".to_string(),
+        _ => number_to_words(hour),
+    };
+    Some(match suffix {
+        Some(suffix) => format!("{}{} {}", hour_words, minutes, suffix),
+        None => format!("{}{}", hour_words, minutes),
+    })
+}
+
+/// Expand text according to a `<say-as interpret-as>` type
+fn interpret_as(text: &str, kind: &str, format: Option<&str>) -> String {
+    let digits: String = text.chars().filter(|c| c.is_ascii_digit()).collect();
+    let expanded = match kind {
+        "characters" | "spell-out" | "verbatim" => Some(spell_out(text)),
+        "digits" | "telephone" => Some(spell_out(&digits)),
+        "cardinal" | "number" => digits.parse().ok().map(number_to_words),
+        "ordinal" => digits.parse().ok().map(ordinal_to_words),
+        "date" => date_to_words(text, format.unwrap_or("mdy")),
+        "time" => time_to_words(text),
+        _ => None,
+    };
+    expanded.unwrap_or_else(|| text.to_string())
+}
@@ -0,0 +1,112 @@
+use std::f32::consts::PI;
+
+use crate::resample::{resample_mono, ResampleError};
+
+/// Length of the overlap-add frames, in seconds
+const FRAME_SECS: f32 = 0.04;
+
+/// How far a frame may move from its nominal position to line up, in seconds
+const TOLERANCE_SECS: f32 = 0.01;
+
+/// Slowest and fastest speeds accepted, so frames still overlap sensibly
+pub const MIN_SPEED: f32 = 0.25;
+pub const MAX_SPEED: f32 = 4.0;
+
+/// Largest pitch shift accepted either way, in semitones
+pub const MAX_SEMITONES: f32 = 24.0;
+
+/// Similarity of `candidate` to `target`, normalized by the candidate's energy
+fn similarity(candidate: &[f32], target: &[f32]) -> f32 {
+    let (mut dot, mut energy) = (0.0f32, 0.0f32);
+    for (&c, &t) in candidate.iter().zip(target) {
+        dot += c * t;
+        energy += c * c;
+    }
+    if energy > 0.0 {
+        dot / energy.sqrt()
+    } else {
+        0.0
+    }
+}
+
+/// Change the duration of mono audio without changing its pitch
+/// 
+/// A `speed` of 1.5 plays 1.5 times faster, so the output is two thirds as
+/// long. Uses WSOLA: Hann-windowed frames are taken from the input at the
+/// new rate and overlap-added at the original hop, each one nudged within
+/// a few milliseconds to the position whose waveform best continues the
+/// previous frame, so voices don't pick up the phasey echo of plain
+/// overlap-add.
+pub fn time_stretch(samples: &[f32], speed: f32, sample_rate: u32) -> Vec<f32> {
+    let speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+    let output_len = (samples.len() as f64 / speed as f64).round() as usize;
+    if samples.is_empty() || (speed - 1.0).abs() < 1e-6 {
+        return samples.to_vec();
+    }
+    
+    let hop = ((FRAME_SECS * sample_rate as f32 / 2.0) as usize).max(1);
+    let frame = hop * 2;
+    let tolerance = (TOLERANCE_SECS * sample_rate as f32) as usize;
+    let window: Vec<f32> = (0..frame).map(|n| 0.5 - 0.5 * (2.0 * PI * n as f32 / frame as f32).cos()).collect();
+    let last_start = samples.len().saturating_sub(frame);
+    
+    let mut output = vec![0.0f32; output_len + frame];
+    let mut weight = vec![0.0f32; output_len + frame];
+    let mut previous = 0usize;
+    let mut out_pos = 0usize;
+    let mut index = 0usize;
+    while out_pos < output_len {
+        let nominal = ((index as f64 * hop as f64 * speed as f64).round() as usize).min(last_start);
+        let start = if index == 0 {
+            0
+        } else {
+            // The previous frame's own continuation is what this one should overlap with
+            let natural = (previous + hop).min(samples.len());
+            let target = &samples[natural..(natural + hop).min(samples.len())];
+            let low = nominal.saturating_sub(tolerance);
+            let high = (nominal + tolerance).min(last_start);
+            let mut best = (nominal, f32::MIN);
+            for candidate in low..=high {
+                let score = similarity(&samples[candidate..(candidate + hop).min(samples.len())], target);
+                if score > best.1 {
+                    best = (candidate, score);
+                }
+            }
+            best.0
+        };
+        
+        for (n, &w) in window.iter().enumerate() {
+            if let Some(&sample) = samples.get(start + n) {
+                output[out_pos + n] += sample * w;
+                weight[out_pos + n] += w;
+            }
+        }
+        previous = start;
+        out_pos += hop;
+        index += 1;
+    }
+    
+    output.truncate(output_len);
+    for (sample, &w) in output.iter_mut().zip(&weight) {
+        *sample = if w > 1e-6 { *sample / w } else { 0.0 };
+    }
+    output
+}
+
+/// Shift the pitch of mono audio by `semitones` without changing its duration
+/// 
+/// The audio is time-stretched by the pitch ratio and then resampled back
+/// to its original length, which scales every frequency by that ratio.
+pub fn pitch_shift(samples: &[f32], semitones: f32, sample_rate: u32) -> Result<Vec<f32>, ResampleError> {
+    let semitones = semitones.clamp(-MAX_SEMITONES, MAX_SEMITONES);
+    if samples.is_empty() || semitones.abs() < 1e-3 {
+        return Ok(samples.to_vec());
+    }
+    
+    let ratio = 2f32.powf(semitones / 12.0);
+    let stretched = time_stretch(samples, 1.0 / ratio, sample_rate);
+    let source_rate = (sample_rate as f32 * ratio).round() as u32;
+    let mut shifted = resample_mono(&stretched, source_rate, sample_rate)?;
+    shifted.resize(samples.len(), 0.0);
+    Ok(shifted)
+}
@@ -0,0 +1,137 @@
+use std::f64::consts::PI;
+
+/// Syllable rate the speech-shaped noise is modulated at, in Hz
+const SYLLABLE_RATE: f64 = 4.0;
+
+/// Corners of the speech band the speech-shaped noise is limited to, in Hz
+const SPEECH_BAND: (f64, f64) = (100.0, 4000.0);
+
+/// Small deterministic PRNG (xorshift32), so generated noise is the same on every run
+pub struct TestRng(u32);
+
+impl TestRng {
+    pub fn new(seed: u32) -> Self {
+        // Zero is the one state xorshift never leaves
+        TestRng(if seed == 0 { 0x9e37_79b9 } else { seed })
+    }
+    
+    /// Next value, uniform in -1.0 to 1.0
+    pub fn next_f32(&mut self) -> f32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+/// Samples in `duration` seconds, treating negative durations as empty
+fn sample_count(duration: f64, sample_rate: u32) -> usize {
+    (duration.max(0.0) * sample_rate as f64).round() as usize
+}
+
+/// Scale a signal so its peak is `amplitude`
+fn normalize_peak(samples: &mut [f32], amplitude: f32) {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        let gain = amplitude / peak;
+        samples.iter_mut().for_each(|s| *s *= gain);
+    }
+}
+
+/// Coefficient of a one-pole lowpass with the given corner
+fn one_pole(corner: f64, sample_rate: u32) -> f32 {
+    (-2.0 * PI * corner / sample_rate as f64).exp() as f32
+}
+
+/// All-zero samples
+pub fn silence(duration: f64, sample_rate: u32) -> Vec<f32> {
+    vec![0.0; sample_count(duration, sample_rate)]
+}
+
+/// A steady tone with peak `amplitude`
+pub fn sine(frequency: f64, duration: f64, sample_rate: u32, amplitude: f32) -> Vec<f32> {
+    (0..sample_count(duration, sample_rate))
+        .map(|n| amplitude * (2.0 * PI * frequency * n as f64 / sample_rate as f64).sin() as f32)
+        .collect()
+}
+
+/// A tone gliding from `start_hz` to `end_hz` over `duration` seconds
+/// 
+/// A logarithmic sweep spends the same time on every octave, which suits
+/// measuring filters; a linear one the same time on every hertz. The
+/// phase is integrated exactly, so the sweep has no clicks.
+pub fn sine_sweep(start_hz: f64, end_hz: f64, duration: f64, sample_rate: u32, amplitude: f32, logarithmic: bool) -> Vec<f32> {
+    let total = duration.max(f64::EPSILON);
+    let ratio = end_hz / start_hz;
+    let exponential = logarithmic && start_hz > 0.0 && end_hz > 0.0 && (ratio - 1.0).abs() > 1e-9;
+    
+    (0..sample_count(duration, sample_rate))
+        .map(|n| {
+            let t = n as f64 / sample_rate as f64;
+            let cycles = if exponential {
+                start_hz * total / ratio.ln() * (ratio.powf(t / total) - 1.0)
+            } else {
+                start_hz * t + (end_hz - start_hz) * t * t / (2.0 * total)
+            };
+            amplitude * (2.0 * PI * cycles).sin() as f32
+        })
+        .collect()
+}
+
+/// Uniform white noise with peak `amplitude`
+pub fn white_noise(duration: f64, sample_rate: u32, amplitude: f32, seed: u32) -> Vec<f32> {
+    let mut rng = TestRng::new(seed);
+    (0..sample_count(duration, sample_rate)).map(|_| amplitude * rng.next_f32()).collect()
+}
+
+/// Pink (1/f, -3 dB per octave) noise with peak `amplitude`
+/// 
+/// White noise through Paul Kellet's pinking filter, accurate to about
+/// 0.05 dB above a few tens of hertz.
+pub fn pink_noise(duration: f64, sample_rate: u32, amplitude: f32, seed: u32) -> Vec<f32> {
+    let mut rng = TestRng::new(seed);
+    let mut b = [0.0f32; 7];
+    let mut samples: Vec<f32> = (0..sample_count(duration, sample_rate))
+        .map(|_| {
+            let white = rng.next_f32();
+            b[0] = 0.99886 * b[0] + white * 0.0555179;
+            b[1] = 0.99332 * b[1] + white * 0.0750759;
+            b[2] = 0.96900 * b[2] + white * 0.153852;
+            b[3] = 0.86650 * b[3] + white * 0.3104856;
+            b[4] = 0.55000 * b[4] + white * 0.5329522;
+            b[5] = -0.7616 * b[5] - white * 0.0168980;
+            let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+            b[6] = white * 0.115926;
+            pink
+        })
+        .collect();
+    normalize_peak(&mut samples, amplitude);
+    samples
+}
+
+/// Noise with the rough spectrum and rhythm of speech, with peak `amplitude`
+/// 
+/// Pink noise limited to the speech band and modulated at the syllable
+/// rate, dropping to silence between "syllables". Energy-based detectors
+/// (VAD, endpointing) treat it like talking, so it stands in for recorded
+/// speech where the words don't matter.
+pub fn speech_shaped_noise(duration: f64, sample_rate: u32, amplitude: f32, seed: u32) -> Vec<f32> {
+    let mut samples = pink_noise(duration, sample_rate, 1.0, seed);
+    let (low, high) = SPEECH_BAND;
+    let highpass = one_pole(low, sample_rate);
+    let lowpass = one_pole(high.min(sample_rate as f64 * 0.45), sample_rate);
+    let (mut low_state, mut high_state) = (0.0f32, 0.0f32);
+    
+    for (n, sample) in samples.iter_mut().enumerate() {
+        // Remove the rumble below the band, then roll off above it
+        low_state = highpass * low_state + (1.0 - highpass) * *sample;
+        high_state = lowpass * high_state + (1.0 - lowpass) * (*sample - low_state);
+        let t = n as f64 / sample_rate as f64;
+        let envelope = (0.5 - 0.5 * (2.0 * PI * SYLLABLE_RATE * t).cos()) as f32;
+        *sample = high_state * envelope * envelope;
+    }
+    normalize_peak(&mut samples, amplitude);
+    samples
+}
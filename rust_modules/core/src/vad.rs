@@ -0,0 +1,78 @@
+use crate::level::frame_dbfs;
+
+/// Consecutive loud frames needed before speech is reported
+const ONSET_FRAMES: u32 = 3;
+
+/// Quiet frames tolerated before speech is reported as ended
+const HANGOVER_FRAMES: u32 = 8;
+
+/// How quickly the noise floor rises towards the level outside speech (per frame)
+const FLOOR_RISE: f32 = 0.02;
+
+/// Energy-based voice activity detector with an adaptive noise floor
+/// 
+/// A frame counts as speech when it is `threshold_db` above the tracked
+/// noise floor. The floor drops straight to quieter frames and rises slowly
+/// otherwise, so it settles on the background level.
+pub struct VoiceActivityDetector {
+    threshold_db: f32,
+    noise_floor: Option<f32>,
+    onset: u32,
+    hangover: u32,
+    is_speech: bool,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(threshold_db: f32) -> Self {
+        VoiceActivityDetector {
+            threshold_db,
+            noise_floor: None,
+            onset: 0,
+            hangover: 0,
+            is_speech: false,
+        }
+    }
+    
+    /// Classify a frame, returning whether speech is in progress
+    pub fn process(&mut self, frame: &[i16]) -> bool {
+        let level = frame_dbfs(frame);
+        let floor = *self.noise_floor.get_or_insert(level);
+        
+        if level > floor + self.threshold_db {
+            self.onset += 1;
+            if self.onset >= ONSET_FRAMES {
+                self.is_speech = true;
+                self.hangover = HANGOVER_FRAMES;
+            }
+        } else {
+            self.onset = 0;
+            if self.hangover > 0 {
+                self.hangover -= 1;
+            } else {
+                self.is_speech = false;
+            }
+        }
+        
+        if level < floor {
+            self.noise_floor = Some(level);
+        } else if !self.is_speech {
+            self.noise_floor = Some(floor + FLOOR_RISE * (level - floor));
+        }
+        self.is_speech
+    }
+    
+    pub fn is_speech(&self) -> bool {
+        self.is_speech
+    }
+    
+    pub fn noise_floor(&self) -> Option<f32> {
+        self.noise_floor
+    }
+    
+    /// Forget the current speech state, keeping the noise floor
+    pub fn reset(&mut self) {
+        self.onset = 0;
+        self.hangover = 0;
+        self.is_speech = false;
+    }
+}
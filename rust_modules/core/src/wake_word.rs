@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::inference::{load_onnx_bytes, load_shared, run_onnx, OnnxPlan};
+
+/// Output of a single engine call
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EngineOutput {
+    /// Index of the keyword that fired, if any
+    pub keyword: Option<usize>,
+    /// Highest keyword score for this frame, for engines that expose one
+    pub score: Option<f32>,
+}
+
+/// A wake word engine backend
+/// 
+/// Engines consume fixed-size frames of 16-bit mono audio and report which
+/// keyword (if any) fired, so every backend can be driven the same way.
+pub trait WakeWordEngine: Send {
+    /// Number of samples the engine expects per call to `process`
+    fn frame_length(&self) -> usize;
+    
+    /// Sample rate the engine expects
+    fn sample_rate(&self) -> u32;
+    
+    /// Process one frame of audio
+    fn process(&mut self, frame: &[i16]) -> Result<EngineOutput, String>;
+    
+    /// Short name of the backend
+    fn name(&self) -> &'static str;
+}
+
+/// Samples per openWakeWord step (80 ms at 16 kHz)
+const OWW_FRAME_LENGTH: usize = 1280;
+
+/// Extra samples of context needed by the mel-spectrogram model
+const OWW_MEL_CONTEXT: usize = 480;
+
+/// Mel frames produced per step
+const OWW_MEL_FRAMES_PER_STEP: usize = 8;
+
+/// Mel bins per frame
+const OWW_MEL_BINS: usize = 32;
+
+/// Mel frames consumed by the embedding model
+const OWW_EMBEDDING_WINDOW: usize = 76;
+
+/// Embedding size produced by the embedding model
+const OWW_EMBEDDING_SIZE: usize = 96;
+
+/// Embeddings consumed by each keyword classifier
+const OWW_CLASSIFIER_WINDOW: usize = 16;
+
+/// Steps after a detection in which no keyword fires (about a second), so
+/// one utterance is one detection, as with Porcupine
+const OWW_REFRACTORY_STEPS: usize = 13;
+
+/// Input shape of the mel-spectrogram model
+const OWW_MELSPECTROGRAM_SHAPE: [usize; 2] = [1, OWW_FRAME_LENGTH + OWW_MEL_CONTEXT];
+
+/// Input shape of the embedding model
+const OWW_EMBEDDING_SHAPE: [usize; 4] = [1, OWW_EMBEDDING_WINDOW, OWW_MEL_BINS, 1];
+
+/// Input shape of each keyword classifier
+const OWW_CLASSIFIER_SHAPE: [usize; 3] = [1, OWW_CLASSIFIER_WINDOW, OWW_EMBEDDING_SIZE];
+
+/// Open-source openWakeWord-style backend running ONNX models with tract
+/// 
+/// Audio is converted to a mel spectrogram, embedded by a shared speech
+/// embedding model, and each keyword classifier scores the most recent
+/// window of embeddings. `model_dir` must contain `melspectrogram.onnx` and
+/// `embedding_model.onnx`; each keyword is a classifier `.onnx` file.
+pub struct OpenWakeWordEngine {
+    melspectrogram: Arc<OnnxPlan>,
+    embedding: Arc<OnnxPlan>,
+    classifiers: Vec<Arc<OnnxPlan>>,
+    thresholds: Vec<f32>,
+    audio: VecDeque<f32>,
+    mel: VecDeque<[f32; OWW_MEL_BINS]>,
+    embeddings: VecDeque<Vec<f32>>,
+    /// Steps left in the hold-off after the last detection
+    refractory: usize,
+}
+
+impl OpenWakeWordEngine {
+    pub fn new(model_dir: &Path, keyword_paths: &[String], sensitivities: &[f32]) -> Result<Self, String> {
+        let mut engine = OpenWakeWordEngine::with_models(
+            load_shared(&model_dir.join("melspectrogram.onnx"), &OWW_MELSPECTROGRAM_SHAPE)?,
+            load_shared(&model_dir.join("embedding_model.onnx"), &OWW_EMBEDDING_SHAPE)?,
+        );
+        for (path, &sensitivity) in keyword_paths.iter().zip(sensitivities.iter()) {
+            engine.add_classifier(load_shared(Path::new(path), &OWW_CLASSIFIER_SHAPE)?, sensitivity);
+        }
+        Ok(engine)
+    }
+    
+    /// Create an engine without keywords from the shared models' file contents
+    pub fn from_bytes(melspectrogram: &[u8], embedding: &[u8]) -> Result<Self, String> {
+        Ok(OpenWakeWordEngine::with_models(
+            Arc::new(load_onnx_bytes(melspectrogram, &OWW_MELSPECTROGRAM_SHAPE)?),
+            Arc::new(load_onnx_bytes(embedding, &OWW_EMBEDDING_SHAPE)?),
+        ))
+    }
+    
+    /// Add a keyword classifier from its file contents, returning its index
+    pub fn add_keyword_bytes(&mut self, classifier: &[u8], sensitivity: f32) -> Result<usize, String> {
+        self.add_classifier(Arc::new(load_onnx_bytes(classifier, &OWW_CLASSIFIER_SHAPE)?), sensitivity);
+        Ok(self.classifiers.len() - 1)
+    }
+    
+    pub fn num_keywords(&self) -> usize {
+        self.classifiers.len()
+    }
+    
+    fn with_models(melspectrogram: Arc<OnnxPlan>, embedding: Arc<OnnxPlan>) -> Self {
+        OpenWakeWordEngine {
+            melspectrogram,
+            embedding,
+            classifiers: Vec::new(),
+            thresholds: Vec::new(),
+            audio: VecDeque::from(vec![0.0; OWW_FRAME_LENGTH + OWW_MEL_CONTEXT]),
+            mel: VecDeque::with_capacity(OWW_EMBEDDING_WINDOW + OWW_MEL_FRAMES_PER_STEP),
+            embeddings: VecDeque::with_capacity(OWW_CLASSIFIER_WINDOW + 1),
+            refractory: 0,
+        }
+    }
+    
+    fn add_classifier(&mut self, classifier: Arc<OnnxPlan>, sensitivity: f32) {
+        self.classifiers.push(classifier);
+        // Higher sensitivity means a lower score threshold
+        self.thresholds.push(1.0 - sensitivity);
+    }
+}
+
+impl WakeWordEngine for OpenWakeWordEngine {
+    fn frame_length(&self) -> usize {
+        OWW_FRAME_LENGTH
+    }
+    
+    fn sample_rate(&self) -> u32 {
+        16000
+    }
+    
+    fn process(&mut self, frame: &[i16]) -> Result<EngineOutput, String> {
+        // The models are trained on raw int16 values stored as floats
+        self.audio.extend(frame.iter().map(|&s| s as f32));
+        while self.audio.len() > OWW_FRAME_LENGTH + OWW_MEL_CONTEXT {
+            self.audio.pop_front();
+        }
+        
+        let mel = run_onnx(
+            &self.melspectrogram,
+            &OWW_MELSPECTROGRAM_SHAPE,
+            self.audio.iter().cloned().collect(),
+        )?;
+        let frames: Vec<&[f32]> = mel.chunks(OWW_MEL_BINS).collect();
+        for bins in frames.iter().skip(frames.len().saturating_sub(OWW_MEL_FRAMES_PER_STEP)) {
+            let mut scaled = [0.0f32; OWW_MEL_BINS];
+            for (out, &value) in scaled.iter_mut().zip(bins.iter()) {
+                *out = value / 10.0 + 2.0;
+            }
+            self.mel.push_back(scaled);
+        }
+        while self.mel.len() > OWW_EMBEDDING_WINDOW {
+            self.mel.pop_front();
+        }
+        if self.mel.len() < OWW_EMBEDDING_WINDOW {
+            return Ok(EngineOutput::default());
+        }
+        
+        let window: Vec<f32> = self.mel.iter().flat_map(|frame| frame.iter().cloned()).collect();
+        let embedding = run_onnx(&self.embedding, &OWW_EMBEDDING_SHAPE, window)?;
+        self.embeddings.push_back(embedding);
+        while self.embeddings.len() > OWW_CLASSIFIER_WINDOW {
+            self.embeddings.pop_front();
+        }
+        if self.embeddings.len() < OWW_CLASSIFIER_WINDOW {
+            return Ok(EngineOutput::default());
+        }
+        
+        let features: Vec<f32> = self.embeddings.iter().flat_map(|e| e.iter().cloned()).collect();
+        let mut output = EngineOutput::default();
+        let mut best_margin = f32::MIN;
+        for (index, classifier) in self.classifiers.iter().enumerate() {
+            let score = run_onnx(classifier, &OWW_CLASSIFIER_SHAPE, features.clone())?
+                .first()
+                .cloned()
+                .unwrap_or(0.0);
+            output.score = Some(output.score.map_or(score, |s: f32| s.max(score)));
+            
+            let margin = score - self.thresholds[index];
+            if margin >= 0.0 && margin > best_margin {
+                best_margin = margin;
+                output.keyword = Some(index);
+            }
+        }
+        
+        // The score stays above threshold for several steps of one utterance;
+        // held-off steps report nothing, so they don't count as near misses either
+        if self.refractory > 0 {
+            self.refractory -= 1;
+            return Ok(EngineOutput::default());
+        }
+        if output.keyword.is_some() {
+            self.refractory = OWW_REFRACTORY_STEPS;
+        }
+        Ok(output)
+    }
+    
+    fn name(&self) -> &'static str {
+        "openwakeword"
+    }
+}
+
+/// Derive a keyword name from a keyword file name, e.g. "jenna_en_linux_v2_2_0.ppn" -> "jenna"
+pub fn keyword_name_from_path(path: &str) -> String {
+    let stem = Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+    
+    // Porcupine keyword files carry a "_<lang>_<platform>_v<version>" suffix
+    const PLATFORMS: &[&str] = &["linux", "mac", "windows", "raspberry-pi", "jetson", "android", "ios", "wasm", "beaglebone"];
+    let parts: Vec<&str> = stem.split('_').collect();
+    let suffix = (1..parts.len().saturating_sub(1)).find(|&i| {
+        parts[i].len() == 2 && parts[i].chars().all(|c| c.is_ascii_lowercase()) && PLATFORMS.contains(&parts[i + 1])
+    });
+    
+    match suffix {
+        Some(index) => parts[..index].join("_"),
+        None => stem,
+    }
+}
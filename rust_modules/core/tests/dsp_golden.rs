@@ -0,0 +1,85 @@
+// Golden reference outputs of the signal processing code
+//
+// Each case compares against `tests/golden/<name>.json`. After an intended
+// change in output, or to add a case, run with `JENNA_BLESS=1` to record the
+// references again and review the diff.
+
+use std::fs;
+use std::path::PathBuf;
+
+use jenna_core::dsp::{design_fir_kernel, filter_buffer, FftProcessor, FilterParams, WindowType};
+use jenna_core::iir::{design_sos, BandType, FilterFamily, SosFilter};
+use jenna_core::resample::resample_mono;
+use jenna_core::testing::{sine_sweep, white_noise};
+
+/// Allowed difference, absolute plus relative to the reference value
+const ABSOLUTE_TOLERANCE: f32 = 1e-5;
+const RELATIVE_TOLERANCE: f32 = 1e-4;
+
+fn check_golden(name: &str, output: &[f32]) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "golden", &format!("{}.json", name)].iter().collect();
+    if std::env::var_os("JENNA_BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serde_json::to_string_pretty(output).unwrap()).unwrap();
+        eprintln!("Recorded golden output {}", path.display());
+        return;
+    }
+    
+    let contents = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("{}: no reference at {} ({}); run with JENNA_BLESS=1 to record it", name, path.display(), err));
+    let reference: Vec<f32> = serde_json::from_str(&contents).unwrap();
+    assert_eq!(output.len(), reference.len(), "{}: length changed", name);
+    for (index, (&got, &want)) in output.iter().zip(reference.iter()).enumerate() {
+        let limit = ABSOLUTE_TOLERANCE + RELATIVE_TOLERANCE * want.abs();
+        assert!((got - want).abs() <= limit, "{}: sample {} is {}, expected {}", name, index, got, want);
+    }
+}
+
+fn impulse_response(family: FilterFamily, band: BandType, order: usize, cutoffs: &[f64], length: usize) -> Vec<f32> {
+    let sections = design_sos(family, band, order, cutoffs, 16000.0).unwrap();
+    let mut filter = SosFilter::from_sections(sections).unwrap();
+    let mut response = vec![0.0f32; length];
+    response[0] = 1.0;
+    filter.process_in_place(&mut response);
+    response
+}
+
+#[test]
+fn butterworth_lowpass() {
+    let response = impulse_response(FilterFamily::Butterworth, BandType::Lowpass, 4, &[1000.0], 256);
+    check_golden("butterworth_lowpass_impulse", &response);
+}
+
+#[test]
+fn chebyshev_bandpass() {
+    let response = impulse_response(FilterFamily::Chebyshev1(1.0), BandType::Bandpass, 3, &[300.0, 3400.0], 256);
+    check_golden("chebyshev_bandpass_impulse", &response);
+}
+
+#[test]
+fn simple_filters() {
+    let noise = white_noise(0.064, 16000, 0.5, 7);
+    let params = FilterParams { cutoff_low: Some(500.0), cutoff_high: Some(2000.0), q_factor: 0.707 };
+    for filter_type in ["lowpass", "highpass", "bandpass", "notch"] {
+        check_golden(&format!("simple_{}", filter_type), &filter_buffer(&noise, filter_type, &params));
+    }
+}
+
+#[test]
+fn fir_design() {
+    let kernel = design_fir_kernel(63, &[0.0, 3000.0, 3500.0, 8000.0], &[1.0, 1.0, 0.0, 0.0], 16000.0, WindowType::Hamming).unwrap();
+    check_golden("fir_lowpass_kernel", &kernel);
+}
+
+#[test]
+fn fft_magnitudes() {
+    let sweep = sine_sweep(100.0, 7000.0, 0.032, 16000, 0.5, true);
+    let magnitudes = FftProcessor::new(WindowType::Hann).magnitudes(&sweep).unwrap();
+    check_golden("fft_hann_sweep", &magnitudes);
+}
+
+#[test]
+fn resampling() {
+    let sweep = sine_sweep(50.0, 20000.0, 0.1, 48000, 0.5, true);
+    check_golden("resample_48k_16k_sweep", &resample_mono(&sweep, 48000, 16000).unwrap());
+}
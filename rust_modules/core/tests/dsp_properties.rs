@@ -0,0 +1,191 @@
+// Invariants of the DSP building blocks that must hold for any input, so
+// an implementation can be swapped out without re-deriving expected values
+
+use jenna_core::dsp::{cross_correlation, fir_filter, FftProcessor, WindowType};
+use jenna_core::iir::{design_sos, BandType, FilterFamily, SosFilter};
+use jenna_core::resample::{resample_mono, MonoResampler};
+use proptest::prelude::*;
+use realfft::RealFftPlanner;
+
+const SAMPLE_RATES: &[u32] = &[8000, 11025, 16000, 22050, 44100, 48000];
+
+fn signal(max_len: usize) -> impl Strategy<Value = Vec<f32>> {
+    prop::collection::vec(-1.0f32..1.0, 1..max_len)
+}
+
+/// Direct convolution, truncated to the input length like `fir_filter`
+fn convolve(buffer: &[f32], kernel: &[f32]) -> Vec<f32> {
+    (0..buffer.len())
+        .map(|n| {
+            kernel
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k <= n)
+                .map(|(k, &h)| h as f64 * buffer[n - k] as f64)
+                .sum::<f64>() as f32
+        })
+        .collect()
+}
+
+fn tolerance(reference: &[f32]) -> f32 {
+    1e-4 * reference.iter().fold(1.0f32, |peak, x| peak.max(x.abs())) * (reference.len() as f32).sqrt()
+}
+
+proptest! {
+    /// The energy of a frame equals the energy of its spectrum
+    #[test]
+    fn fft_preserves_energy(frame in signal(1024)) {
+        let mut fft = FftProcessor::new(WindowType::Rectangular);
+        let magnitudes = fft.magnitudes(&frame).unwrap();
+        let n = frame.len();
+        prop_assert_eq!(magnitudes.len(), n / 2 + 1);
+        
+        let time: f64 = frame.iter().map(|&x| (x as f64).powi(2)).sum();
+        // Bins other than DC (and Nyquist for even sizes) stand for their mirror image too
+        let frequency: f64 = magnitudes
+            .iter()
+            .enumerate()
+            .map(|(bin, &m)| {
+                let mirrored = bin != 0 && !(n % 2 == 0 && bin == n / 2);
+                (m as f64).powi(2) * if mirrored { 2.0 } else { 1.0 }
+            })
+            .sum::<f64>()
+            / n as f64;
+        prop_assert!((time - frequency).abs() <= 1e-3 * time.max(1.0), "{} vs {}", time, frequency);
+    }
+    
+    /// An inverse FFT of the forward transform gives back the frame
+    #[test]
+    fn fft_round_trips(frame in signal(2048)) {
+        let n = frame.len();
+        let mut planner = RealFftPlanner::<f32>::new();
+        let (forward, inverse) = (planner.plan_fft_forward(n), planner.plan_fft_inverse(n));
+        let mut input = frame.clone();
+        let mut spectrum = forward.make_output_vec();
+        forward.process(&mut input, &mut spectrum).unwrap();
+        prop_assert_eq!(spectrum.len(), n / 2 + 1);
+        
+        // The inverse needs real DC and Nyquist bins, and is unnormalized
+        let last = spectrum.len() - 1;
+        spectrum[0].im = 0.0;
+        if n % 2 == 0 {
+            spectrum[last].im = 0.0;
+        }
+        let mut output = inverse.make_output_vec();
+        inverse.process(&mut spectrum, &mut output).unwrap();
+        let limit = tolerance(&frame);
+        for (got, want) in output.iter().map(|x| x / n as f32).zip(frame.iter()) {
+            prop_assert!((got - want).abs() <= limit, "{} vs {}", got, want);
+        }
+    }
+    
+    /// FFT convolution (forward, multiply, inverse) matches direct convolution
+    #[test]
+    fn fft_convolution_round_trips(buffer in signal(2048), kernel in prop::collection::vec(-1.0f32..1.0, 65..300)) {
+        let expected = convolve(&buffer, &kernel);
+        let filtered = fir_filter(&buffer, &kernel);
+        prop_assert_eq!(filtered.len(), buffer.len());
+        let limit = tolerance(&expected);
+        for (got, want) in filtered.iter().zip(expected.iter()) {
+            prop_assert!((got - want).abs() <= limit, "{} vs {}", got, want);
+        }
+    }
+    
+    /// FFT cross-correlation matches the sum it is defined by
+    #[test]
+    fn cross_correlation_matches_definition(a in signal(300), b in signal(300)) {
+        let correlation = cross_correlation(&a, &b, false);
+        prop_assert_eq!(correlation.len(), a.len() + b.len() - 1);
+        
+        let offset = b.len() as i64 - 1;
+        let expected: Vec<f32> = (0..correlation.len() as i64)
+            .map(|index| {
+                let lag = index - offset;
+                (0..b.len() as i64)
+                    .filter(|&n| n + lag >= 0 && n + lag < a.len() as i64)
+                    .map(|n| a[(n + lag) as usize] as f64 * b[n as usize] as f64)
+                    .sum::<f64>() as f32
+            })
+            .collect();
+        let limit = tolerance(&expected);
+        for (got, want) in correlation.iter().zip(expected.iter()) {
+            prop_assert!((got - want).abs() <= limit, "{} vs {}", got, want);
+        }
+    }
+    
+    /// Windows are symmetric and peak at no more than 1
+    #[test]
+    fn windows_are_symmetric(len in 1usize..2048, index in 0usize..5) {
+        let window = [WindowType::Rectangular, WindowType::Hann, WindowType::Hamming, WindowType::Blackman, WindowType::FlatTop][index];
+        let coefficients = window.coefficients(len);
+        prop_assert_eq!(coefficients.len(), len);
+        for i in 0..len / 2 {
+            prop_assert!((coefficients[i] - coefficients[len - 1 - i]).abs() < 1e-5);
+        }
+        prop_assert!(coefficients.iter().all(|&w| w <= 1.0 + 1e-5));
+    }
+    
+    /// Every designed filter has its poles inside the unit circle and a decaying impulse response
+    #[test]
+    fn designed_filters_are_stable(
+        order in 1usize..9,
+        chebyshev in any::<bool>(),
+        band_index in 0usize..4,
+        rate_index in 0usize..SAMPLE_RATES.len(),
+        low in 0.01f64..0.2,
+        width in 0.02f64..0.25,
+    ) {
+        let sample_rate = SAMPLE_RATES[rate_index] as f64;
+        let family = if chebyshev { FilterFamily::Chebyshev1(1.0) } else { FilterFamily::Butterworth };
+        let band = [BandType::Lowpass, BandType::Highpass, BandType::Bandpass, BandType::Bandstop][band_index];
+        let cutoffs = match band {
+            BandType::Lowpass | BandType::Highpass => vec![low * sample_rate],
+            BandType::Bandpass | BandType::Bandstop => vec![low * sample_rate, (low + width) * sample_rate],
+        };
+        let sections = design_sos(family, band, order, &cutoffs, sample_rate).unwrap();
+        
+        for section in &sections {
+            // Stability triangle of a normalized second-order denominator
+            let (a1, a2) = (section[4] / section[3], section[5] / section[3]);
+            prop_assert!(a2.abs() < 1.0 && a1.abs() < 1.0 + a2, "unstable section {:?}", section);
+        }
+        
+        let mut filter = SosFilter::from_sections(sections).unwrap();
+        let mut response = vec![0.0f32; sample_rate as usize * 2];
+        response[0] = 1.0;
+        filter.process_in_place(&mut response);
+        prop_assert!(response.iter().all(|x| x.is_finite()));
+        let tail = response[response.len() - 100..].iter().fold(0.0f32, |peak, x| peak.max(x.abs()));
+        prop_assert!(tail < 1e-3, "impulse response still at {} after two seconds", tail);
+    }
+    
+    /// A complete resampled signal lasts as long as the input
+    #[test]
+    fn resampling_preserves_duration(
+        samples in prop::collection::vec(-1.0f32..1.0, 0..20000),
+        input_index in 0usize..SAMPLE_RATES.len(),
+        output_index in 0usize..SAMPLE_RATES.len(),
+    ) {
+        let (input_rate, output_rate) = (SAMPLE_RATES[input_index], SAMPLE_RATES[output_index]);
+        let output = resample_mono(&samples, input_rate, output_rate).unwrap();
+        let expected = samples.len() as u64 * output_rate as u64 / input_rate as u64;
+        prop_assert_eq!(output.len() as u64, expected);
+    }
+    
+    /// Streaming never produces more than the input's duration, however it is split
+    #[test]
+    fn streaming_resampler_never_runs_ahead(
+        blocks in prop::collection::vec(1usize..3000, 1..20),
+        input_index in 0usize..SAMPLE_RATES.len(),
+        output_index in 0usize..SAMPLE_RATES.len(),
+    ) {
+        let (input_rate, output_rate) = (SAMPLE_RATES[input_index], SAMPLE_RATES[output_index]);
+        let mut resampler = MonoResampler::new(input_rate, output_rate).unwrap();
+        let (mut consumed, mut produced) = (0u64, 0u64);
+        for block in blocks {
+            produced += resampler.push(&vec![0.25; block]).unwrap().len() as u64;
+            consumed += block as u64;
+            prop_assert!(produced <= consumed * output_rate as u64 / input_rate as u64 + 1);
+        }
+    }
+}
@@ -0,0 +1,18 @@
+// Validation of user-supplied levels, which reach the audio path unchecked otherwise
+
+use jenna_core::level::check_gate_threshold;
+
+#[test]
+fn gate_threshold_accepts_levels_up_to_full_scale() {
+    for threshold in [0.0, -0.0, -40.0, -96.0, f32::MIN] {
+        assert!(check_gate_threshold(threshold).is_ok(), "{} rejected", threshold);
+    }
+}
+
+#[test]
+fn gate_threshold_rejects_positive_and_non_finite_levels() {
+    // A NaN threshold would compare false against every level and close the gate for good
+    for threshold in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, 0.5, 12.0] {
+        assert!(check_gate_threshold(threshold).is_err(), "{} accepted", threshold);
+    }
+}
@@ -0,0 +1,240 @@
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use jenna_rust::engines;
+
+fn to_error(message: String) -> Error {
+    Error::new(Status::GenericFailure, message)
+}
+
+fn sensitivities(count: usize, sensitivities: Option<Vec<f64>>) -> Vec<f32> {
+    match sensitivities {
+        Some(values) => values.into_iter().map(|s| s as f32).collect(),
+        None => vec![0.5; count],
+    }
+}
+
+/// Version of the engines library
+#[napi]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Streaming mono resampler
+#[napi]
+pub struct Resampler {
+    inner: engines::Resampler,
+}
+
+#[napi]
+impl Resampler {
+    #[napi(constructor, catch_unwind)]
+    pub fn new(input_rate: u32, output_rate: u32) -> Result<Self> {
+        Ok(Resampler { inner: engines::Resampler::new(input_rate, output_rate).map_err(to_error)? })
+    }
+    
+    /// Resample the next block of a stream
+    #[napi(catch_unwind)]
+    pub fn process(&mut self, samples: Float32Array) -> Result<Float32Array> {
+        Ok(Float32Array::new(self.inner.process(&samples).map_err(to_error)?))
+    }
+}
+
+/// Wake word detector fed one frame of `frameLength` samples at a time
+#[napi]
+pub struct WakeWordDetector {
+    inner: engines::WakeWord,
+}
+
+#[napi]
+impl WakeWordDetector {
+    /// Porcupine with keyword (.ppn) files
+    #[napi(factory, catch_unwind)]
+    pub fn porcupine(
+        access_key: String,
+        keyword_paths: Vec<String>,
+        sensitivities: Option<Vec<f64>>,
+        model_path: Option<String>,
+    ) -> Result<Self> {
+        let sensitivities = self::sensitivities(keyword_paths.len(), sensitivities);
+        let inner = engines::WakeWord::porcupine(&access_key, keyword_paths, &sensitivities, model_path.as_deref())
+            .map_err(to_error)?;
+        Ok(WakeWordDetector { inner })
+    }
+    
+    /// openWakeWord with a model directory and one classifier (.onnx) per keyword
+    #[napi(factory, catch_unwind)]
+    pub fn open_wake_word(model_dir: String, keyword_paths: Vec<String>, sensitivities: Option<Vec<f64>>) -> Result<Self> {
+        let sensitivities = self::sensitivities(keyword_paths.len(), sensitivities);
+        let inner = engines::WakeWord::openwakeword(Path::new(&model_dir), keyword_paths, &sensitivities).map_err(to_error)?;
+        Ok(WakeWordDetector { inner })
+    }
+    
+    #[napi(getter)]
+    pub fn frame_length(&self) -> u32 {
+        self.inner.frame_length() as u32
+    }
+    
+    #[napi(getter)]
+    pub fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+    
+    /// Process one frame, returning the index of the keyword that fired or null
+    #[napi(catch_unwind)]
+    pub fn process(&mut self, frame: Int16Array) -> Result<Option<u32>> {
+        Ok(self.inner.process(&frame).map_err(to_error)?.map(|index| index as u32))
+    }
+}
+
+#[napi(object)]
+pub struct Word {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+    pub confidence: f64,
+}
+
+#[napi(object)]
+pub struct RecognitionResult {
+    pub text: String,
+    pub is_final: bool,
+    pub confidence: Option<f64>,
+    pub words: Vec<Word>,
+}
+
+impl From<engines::RecognitionResult> for RecognitionResult {
+    fn from(result: engines::RecognitionResult) -> Self {
+        RecognitionResult {
+            text: result.text,
+            is_final: result.is_final,
+            confidence: result.confidence.map(f64::from),
+            words: result
+                .words
+                .into_iter()
+                .map(|word| Word {
+                    word: word.word,
+                    start: word.start as f64,
+                    end: word.end as f64,
+                    confidence: word.confidence as f64,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[napi(object)]
+pub struct RecognizerOptions {
+    /// "vosk" (default) or "whisper"
+    pub engine: Option<String>,
+    /// Input sample rate, 16000 by default
+    pub sample_rate: Option<u32>,
+    /// Whisper language code; detected when omitted
+    pub language: Option<String>,
+    /// Include per-word timings in final results
+    pub words: Option<bool>,
+}
+
+/// Streaming speech recognizer
+#[napi]
+pub struct SpeechRecognizer {
+    inner: engines::Recognizer,
+}
+
+#[napi]
+impl SpeechRecognizer {
+    #[napi(constructor, catch_unwind)]
+    pub fn new(model_path: String, options: Option<RecognizerOptions>) -> Result<Self> {
+        let options = options.unwrap_or(RecognizerOptions { engine: None, sample_rate: None, language: None, words: None });
+        let mut inner = engines::Recognizer::new(
+            options.engine.as_deref().unwrap_or("vosk"),
+            &model_path,
+            options.sample_rate.unwrap_or(16000),
+            options.language,
+        )
+        .map_err(to_error)?;
+        inner.set_words(options.words.unwrap_or(false), false);
+        Ok(SpeechRecognizer { inner })
+    }
+    
+    #[napi(getter)]
+    pub fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+    
+    /// Feed 16-bit mono audio, returning a partial or final result when one is available
+    #[napi(catch_unwind)]
+    pub fn accept(&mut self, pcm: Int16Array) -> Result<Option<RecognitionResult>> {
+        Ok(self.inner.accept(&pcm).map_err(to_error)?.map(RecognitionResult::from))
+    }
+    
+    /// End the current utterance and return its final result
+    #[napi(catch_unwind)]
+    pub fn finish(&mut self) -> Result<Option<RecognitionResult>> {
+        Ok(self.inner.finish().map_err(to_error)?.map(RecognitionResult::from))
+    }
+    
+    /// Discard the utterance in progress
+    #[napi(catch_unwind)]
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// Synthesis on the libuv thread pool, so the event loop keeps running
+pub struct SynthesizeTask {
+    synthesizer: Arc<Mutex<engines::Synthesizer>>,
+    text: String,
+}
+
+impl Task for SynthesizeTask {
+    type Output = Vec<i16>;
+    type JsValue = Int16Array;
+    
+    fn compute(&mut self) -> Result<Self::Output> {
+        // A panic on the thread pool would take the whole Node process down
+        let (synthesizer, text) = (&self.synthesizer, &self.text);
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            synthesizer.lock().unwrap_or_else(PoisonError::into_inner).synthesize(text)
+        }))
+        .map_err(|_| to_error("Synthesis panicked".to_string()))?
+        .map_err(to_error)
+    }
+    
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(Int16Array::new(output))
+    }
+}
+
+/// Piper text-to-speech voice
+#[napi]
+pub struct TextToSpeech {
+    synthesizer: Arc<Mutex<engines::Synthesizer>>,
+    sample_rate: u32,
+}
+
+#[napi]
+impl TextToSpeech {
+    /// Load a voice file, or the voice named `voice` from a directory of voices
+    #[napi(constructor, catch_unwind)]
+    pub fn new(model_path: String, voice: Option<String>) -> Result<Self> {
+        let synthesizer = engines::Synthesizer::new(Path::new(&model_path), voice.as_deref()).map_err(to_error)?;
+        let sample_rate = synthesizer.sample_rate();
+        Ok(TextToSpeech { synthesizer: Arc::new(Mutex::new(synthesizer)), sample_rate })
+    }
+    
+    /// Native sample rate of the voice
+    #[napi(getter)]
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    
+    /// Synthesize text or SSML, resolving to 16-bit mono PCM at `sampleRate`
+    #[napi(ts_return_type = "Promise<Int16Array>")]
+    pub fn synthesize(&self, text: String) -> AsyncTask<SynthesizeTask> {
+        AsyncTask::new(SynthesizeTask { synthesizer: self.synthesizer.clone(), text })
+    }
+}
@@ -0,0 +1,126 @@
+use pyo3::prelude::*;
+use std::path::Path;
+
+/// Where a model's inference runs
+/// 
+/// Whisper can offload to a GPU when the module is built with the matching
+/// cargo feature ("cuda", "metal" or "opencl"). The tract models (Piper,
+/// wake word, speaker and sound event models) always run on the CPU.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Accelerator {
+    Cpu,
+    Cuda,
+    Metal,
+    OpenCl,
+}
+
+/// GPU backends tried by "auto", fastest first
+const GPU_ORDER: [Accelerator; 3] = [Accelerator::Cuda, Accelerator::Metal, Accelerator::OpenCl];
+
+/// Names accepted for an accelerator preference
+pub const ACCELERATOR_NAMES: &[&str] = &["auto", "cpu", "cuda", "metal", "opencl", "directml"];
+
+impl Accelerator {
+    pub fn name(self) -> &'static str {
+        match self {
+            Accelerator::Cpu => "cpu",
+            Accelerator::Cuda => "cuda",
+            Accelerator::Metal => "metal",
+            Accelerator::OpenCl => "opencl",
+        }
+    }
+    
+    /// Whether this build can run Whisper on it
+    pub fn compiled(self) -> bool {
+        match self {
+            Accelerator::Cpu => true,
+            Accelerator::Cuda => cfg!(feature = "cuda"),
+            Accelerator::Metal => cfg!(feature = "metal"),
+            Accelerator::OpenCl => cfg!(feature = "opencl"),
+        }
+    }
+    
+    /// Whether a device for it appears to be installed, judged from its driver files
+    pub fn device_present(self) -> bool {
+        match self {
+            Accelerator::Cpu => true,
+            Accelerator::Cuda => {
+                if cfg!(windows) {
+                    system_library("nvcuda.dll")
+                } else {
+                    Path::new("/proc/driver/nvidia/version").exists()
+                }
+            }
+            // Every Mac that runs a current macOS has a Metal GPU
+            Accelerator::Metal => cfg!(target_os = "macos"),
+            Accelerator::OpenCl => {
+                if cfg!(windows) {
+                    system_library("OpenCL.dll")
+                } else if cfg!(target_os = "macos") {
+                    true
+                } else {
+                    Path::new("/etc/OpenCL/vendors").read_dir().map_or(false, |mut vendors| vendors.next().is_some())
+                }
+            }
+        }
+    }
+    
+    /// Whether Whisper can run on it here
+    pub fn usable(self) -> bool {
+        self.compiled() && self.device_present()
+    }
+}
+
+fn system_library(name: &str) -> bool {
+    std::env::var_os("SystemRoot").map_or(false, |root| Path::new(&root).join("System32").join(name).exists())
+}
+
+/// Check that an accelerator preference is one `ACCELERATOR_NAMES` lists
+pub fn check_preference(preference: &str) -> Result<(), String> {
+    if ACCELERATOR_NAMES.contains(&preference.to_ascii_lowercase().as_str()) {
+        Ok(())
+    } else {
+        Err(format!("Unknown accelerator '{}'; expected one of {}", preference, ACCELERATOR_NAMES.join(", ")))
+    }
+}
+
+/// Pick where Whisper runs for a preference, falling back to the CPU
+/// 
+/// "auto" takes the first usable GPU backend. A named backend this build
+/// or machine can't use is logged and replaced by the CPU rather than
+/// failing the load. whisper.cpp has no DirectML backend, so "directml"
+/// always falls back; on Windows, "cuda" or "opencl" reach the GPU.
+pub fn select_for_whisper(preference: &str) -> Result<Accelerator, String> {
+    check_preference(preference)?;
+    let wanted = match preference.to_ascii_lowercase().as_str() {
+        "auto" => return Ok(GPU_ORDER.into_iter().find(|gpu| gpu.usable()).unwrap_or(Accelerator::Cpu)),
+        "cpu" => return Ok(Accelerator::Cpu),
+        "cuda" => Accelerator::Cuda,
+        "metal" => Accelerator::Metal,
+        "opencl" => Accelerator::OpenCl,
+        _ => {
+            tracing::warn!("Whisper has no DirectML backend; running on the CPU");
+            return Ok(Accelerator::Cpu);
+        }
+    };
+    if !wanted.compiled() {
+        tracing::warn!(accelerator = wanted.name(), "Built without this accelerator; running Whisper on the CPU");
+        Ok(Accelerator::Cpu)
+    } else if !wanted.device_present() {
+        tracing::warn!(accelerator = wanted.name(), "No device found for this accelerator; running Whisper on the CPU");
+        Ok(Accelerator::Cpu)
+    } else {
+        Ok(wanted)
+    }
+}
+
+/// Accelerators Whisper can use in this build on this machine, "cpu" always last
+#[pyfunction]
+pub fn available_accelerators() -> Vec<&'static str> {
+    GPU_ORDER
+        .into_iter()
+        .filter(|gpu| gpu.usable())
+        .chain([Accelerator::Cpu])
+        .map(Accelerator::name)
+        .collect()
+}
@@ -0,0 +1,466 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::events::{self, Event, EventData};
+use crate::lifecycle::{self, Resource};
+use crate::panics;
+use crate::wake_word::unix_time;
+
+/// Longest the timer thread sleeps when no deadline is pending
+const TIMER_IDLE_WAIT: Duration = Duration::from_millis(500);
+
+/// Stage of a voice interaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Idle,
+    WakeDetected,
+    Listening,
+    Thinking,
+    Speaking,
+}
+
+impl Phase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Idle => "idle",
+            Phase::WakeDetected => "wake_detected",
+            Phase::Listening => "listening",
+            Phase::Thinking => "thinking",
+            Phase::Speaking => "speaking",
+        }
+    }
+}
+
+/// Time limits for each stage
+#[derive(Clone, Copy, Debug)]
+struct Timeouts {
+    /// From the wake word to the start of speech
+    listen: Duration,
+    /// From the start of speech to its end
+    max_utterance: Duration,
+    /// After speaking, how long speech is accepted without the wake word (zero disables)
+    follow_up: Duration,
+    /// From the end of speech to the start of the response
+    thinking: Duration,
+}
+
+/// A transition between two phases
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct StateChange {
+    #[pyo3(get)]
+    pub previous: String,
+    #[pyo3(get)]
+    pub state: String,
+    /// Why the transition happened, e.g. "wake_word", "listen_timeout"
+    #[pyo3(get)]
+    pub reason: String,
+    /// Wall-clock time of the transition, in seconds since the Unix epoch
+    #[pyo3(get)]
+    pub timestamp: f64,
+}
+
+#[pymethods]
+impl StateChange {
+    fn __repr__(&self) -> String {
+        format!(
+            "StateChange(previous={:?}, state={:?}, reason={:?}, timestamp={:.3})",
+            self.previous, self.state, self.reason, self.timestamp
+        )
+    }
+}
+
+/// The transition rules; every method returns the change it made, if any
+struct Machine {
+    phase: Phase,
+    entered: Instant,
+    /// Whether the current WakeDetected phase is a follow-up window
+    follow_up: bool,
+    timeouts: Timeouts,
+}
+
+impl Machine {
+    fn transition(&mut self, next: Phase, reason: &str) -> Option<StateChange> {
+        let change = StateChange {
+            previous: self.phase.as_str().to_string(),
+            state: next.as_str().to_string(),
+            reason: reason.to_string(),
+            timestamp: unix_time(),
+        };
+        self.phase = next;
+        self.entered = Instant::now();
+        self.follow_up = false;
+        Some(change)
+    }
+    
+    fn wake(&mut self, reason: &str) -> Option<StateChange> {
+        match self.phase {
+            Phase::Idle | Phase::Thinking | Phase::Speaking => self.transition(Phase::WakeDetected, reason),
+            _ => None,
+        }
+    }
+    
+    fn speech_started(&mut self) -> Option<StateChange> {
+        match self.phase {
+            Phase::WakeDetected => self.transition(Phase::Listening, "speech_start"),
+            _ => None,
+        }
+    }
+    
+    fn speech_ended(&mut self, reason: &str) -> Option<StateChange> {
+        match self.phase {
+            Phase::WakeDetected | Phase::Listening => self.transition(Phase::Thinking, reason),
+            _ => None,
+        }
+    }
+    
+    fn speaking_started(&mut self) -> Option<StateChange> {
+        match self.phase {
+            Phase::Speaking => None,
+            _ => self.transition(Phase::Speaking, "playback_start"),
+        }
+    }
+    
+    fn speaking_finished(&mut self) -> Option<StateChange> {
+        if self.phase != Phase::Speaking {
+            return None;
+        }
+        if self.timeouts.follow_up.is_zero() {
+            return self.transition(Phase::Idle, "playback_end");
+        }
+        let change = self.transition(Phase::WakeDetected, "follow_up");
+        self.follow_up = true;
+        change
+    }
+    
+    fn cancel(&mut self, reason: &str) -> Option<StateChange> {
+        match self.phase {
+            Phase::Idle => None,
+            _ => self.transition(Phase::Idle, reason),
+        }
+    }
+    
+    /// When the current phase times out, if it can
+    fn deadline(&self) -> Option<Instant> {
+        let limit = match self.phase {
+            Phase::WakeDetected if self.follow_up => self.timeouts.follow_up,
+            Phase::WakeDetected => self.timeouts.listen,
+            Phase::Listening => self.timeouts.max_utterance,
+            Phase::Thinking => self.timeouts.thinking,
+            Phase::Idle | Phase::Speaking => return None,
+        };
+        Some(self.entered + limit)
+    }
+    
+    /// Apply the timeout for the current phase if it has passed
+    fn tick(&mut self, now: Instant) -> Option<StateChange> {
+        if self.deadline()? > now {
+            return None;
+        }
+        match self.phase {
+            Phase::WakeDetected if self.follow_up => self.transition(Phase::Idle, "follow_up_expired"),
+            Phase::WakeDetected => self.transition(Phase::Idle, "listen_timeout"),
+            Phase::Listening => self.transition(Phase::Thinking, "max_utterance"),
+            Phase::Thinking => self.transition(Phase::Idle, "thinking_timeout"),
+            Phase::Idle | Phase::Speaking => None,
+        }
+    }
+}
+
+/// State machine shared between `AssistantState`, its timer thread and the pipeline
+pub struct Assistant {
+    machine: Mutex<Machine>,
+    changed: Condvar,
+    callback: Mutex<Option<PyObject>>,
+    stop: AtomicBool,
+    timer: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Resource for Assistant {
+    /// Stop the timer thread; transitions still work but timeouts no longer fire
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.changed.notify_all();
+        let timer = self.timer.lock().unwrap().take();
+        lifecycle::join_threads(timer);
+    }
+}
+
+impl Assistant {
+    pub fn phase(&self) -> Phase {
+        self.machine.lock().unwrap().phase
+    }
+    
+    /// Run a transition and report it, returning whether the phase changed
+    fn apply(&self, f: impl FnOnce(&mut Machine) -> Option<StateChange>) -> bool {
+        let change = f(&mut self.machine.lock().unwrap());
+        self.changed.notify_all();
+        match change {
+            Some(change) => {
+                self.notify(change);
+                true
+            }
+            None => false,
+        }
+    }
+    
+    /// Post a change to the event bus and the callback; never called with the machine locked
+    fn notify(&self, change: StateChange) {
+        tracing::debug!("Assistant {} -> {} ({})", change.previous, change.state, change.reason);
+        events::post(Event::new("assistant.state", "assistant", EventData::State(change.clone())));
+        
+        let has_callback = self.callback.lock().unwrap().is_some();
+        if has_callback {
+            Python::with_gil(|py| {
+                let callback = self.callback.lock().unwrap().as_ref().map(|c| c.clone_ref(py));
+                if let Some(callback) = callback {
+                    if let Err(err) = callback.call1(py, (change,)) {
+                        err.print(py);
+                    }
+                }
+            });
+        }
+    }
+    
+    pub fn wake(&self, reason: &str) -> bool {
+        self.apply(|m| m.wake(reason))
+    }
+    
+    pub fn speech_started(&self) -> bool {
+        self.apply(|m| m.speech_started())
+    }
+    
+    pub fn speech_ended(&self, reason: &str) -> bool {
+        self.apply(|m| m.speech_ended(reason))
+    }
+    
+    pub fn speaking_started(&self) -> bool {
+        self.apply(|m| m.speaking_started())
+    }
+    
+    pub fn speaking_finished(&self) -> bool {
+        self.apply(|m| m.speaking_finished())
+    }
+    
+    pub fn cancel(&self, reason: &str) -> bool {
+        self.apply(|m| m.cancel(reason))
+    }
+}
+
+/// Apply timeouts as their deadlines pass, independent of the Python thread
+fn run_timer(assistant: &Assistant) {
+    let mut machine = assistant.machine.lock().unwrap();
+    while !assistant.stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        if let Some(change) = machine.tick(now) {
+            drop(machine);
+            assistant.notify(change);
+            machine = assistant.machine.lock().unwrap();
+            continue;
+        }
+        
+        let wait = machine
+            .deadline()
+            .map(|deadline| deadline.saturating_duration_since(now))
+            .unwrap_or(TIMER_IDLE_WAIT)
+            .min(TIMER_IDLE_WAIT);
+        machine = assistant.changed.wait_timeout(machine, wait).unwrap().0;
+    }
+}
+
+fn check_timeout(name: &str, seconds: f64, allow_zero: bool) -> PyResult<Duration> {
+    if seconds < 0.0 || (seconds == 0.0 && !allow_zero) || !seconds.is_finite() {
+        return Err(PyValueError::new_err(format!("{} must be positive", name)));
+    }
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| PyValueError::new_err(format!("{} is too large", name)))
+}
+
+/// Assistant state machine: idle → wake_detected → listening → thinking → speaking
+/// 
+/// Timeouts are enforced on a Rust thread, so a slow or paused Python
+/// thread can't stretch them: no speech within `listen_timeout` returns to
+/// idle, speech longer than `max_utterance` moves on to thinking, and no
+/// response within `thinking_timeout` returns to idle. After speaking, the
+/// machine waits `follow_up_window` seconds for speech without the wake
+/// word (0 goes straight back to idle).
+/// 
+/// Every transition calls `callback(change)` (see `set_callback`) and posts
+/// an "assistant.state" event on the event bus. Pass the state to
+/// `VoicePipeline` to have the pipeline drive it; the transition methods
+/// return whether the transition was valid from the current state.
+#[pyclass]
+pub struct AssistantState {
+    assistant: Arc<Assistant>,
+}
+
+impl AssistantState {
+    pub fn shared(&self) -> Arc<Assistant> {
+        self.assistant.clone()
+    }
+}
+
+#[pymethods]
+impl AssistantState {
+    #[new]
+    #[pyo3(signature = (listen_timeout=5.0, max_utterance=15.0, follow_up_window=0.0, thinking_timeout=10.0))]
+    fn new(listen_timeout: f64, max_utterance: f64, follow_up_window: f64, thinking_timeout: f64) -> PyResult<Self> {
+        let timeouts = Timeouts {
+            listen: check_timeout("listen_timeout", listen_timeout, false)?,
+            max_utterance: check_timeout("max_utterance", max_utterance, false)?,
+            follow_up: check_timeout("follow_up_window", follow_up_window, true)?,
+            thinking: check_timeout("thinking_timeout", thinking_timeout, false)?,
+        };
+        let assistant = Arc::new(Assistant {
+            machine: Mutex::new(Machine { phase: Phase::Idle, entered: Instant::now(), follow_up: false, timeouts }),
+            changed: Condvar::new(),
+            callback: Mutex::new(None),
+            stop: AtomicBool::new(false),
+            timer: Mutex::new(None),
+        });
+        
+        let timer_assistant = assistant.clone();
+        let timer = thread::Builder::new()
+            .name("jenna-assistant".to_string())
+            .spawn(move || {
+                panics::supervise(
+                    "Assistant timer",
+                    || {
+                        run_timer(&timer_assistant);
+                        Ok(())
+                    },
+                    |_, restart| {
+                        timer_assistant.machine.clear_poison();
+                        timer_assistant.callback.clear_poison();
+                        restart.map_or(false, |delay| panics::wait_unless_stopped(&timer_assistant.stop, delay))
+                    },
+                );
+            })
+            .map_err(|err| PyValueError::new_err(format!("Failed to start assistant timer: {}", err)))?;
+        *assistant.timer.lock().unwrap() = Some(timer);
+        lifecycle::register(&assistant);
+        
+        Ok(AssistantState { assistant })
+    }
+    
+    /// Call `callback(change)` with a `StateChange` on every transition (None removes it)
+    /// 
+    /// The callback may run on the pipeline or timer thread.
+    #[pyo3(signature = (callback=None))]
+    fn set_callback(&self, py: Python, callback: Option<PyObject>) -> PyResult<()> {
+        if let Some(callback) = &callback {
+            if !callback.as_ref(py).is_callable() {
+                return Err(PyValueError::new_err("Callback must be callable"));
+            }
+        }
+        *self.assistant.callback.lock().unwrap() = callback;
+        Ok(())
+    }
+    
+    /// Get the current state name
+    fn get_state(&self) -> &'static str {
+        self.assistant.phase().as_str()
+    }
+    
+    /// Seconds spent in the current state
+    fn time_in_state(&self) -> f64 {
+        self.assistant.machine.lock().unwrap().entered.elapsed().as_secs_f64()
+    }
+    
+    /// Check whether the machine is waiting for follow-up speech after a response
+    fn is_follow_up(&self) -> bool {
+        let machine = self.assistant.machine.lock().unwrap();
+        machine.phase == Phase::WakeDetected && machine.follow_up
+    }
+    
+    /// idle/thinking/speaking → wake_detected
+    #[pyo3(signature = (reason="manual"))]
+    fn wake(&self, py: Python, reason: &str) -> bool {
+        py.allow_threads(|| self.assistant.wake(reason))
+    }
+    
+    /// wake_detected → listening
+    fn speech_started(&self, py: Python) -> bool {
+        py.allow_threads(|| self.assistant.speech_started())
+    }
+    
+    /// wake_detected/listening → thinking
+    #[pyo3(signature = (reason="speech_end"))]
+    fn speech_ended(&self, py: Python, reason: &str) -> bool {
+        py.allow_threads(|| self.assistant.speech_ended(reason))
+    }
+    
+    /// Any state → speaking
+    fn speaking_started(&self, py: Python) -> bool {
+        py.allow_threads(|| self.assistant.speaking_started())
+    }
+    
+    /// speaking → wake_detected (follow-up window) or idle
+    fn speaking_finished(&self, py: Python) -> bool {
+        py.allow_threads(|| self.assistant.speaking_finished())
+    }
+    
+    /// Any state → idle
+    #[pyo3(signature = (reason="cancelled"))]
+    fn cancel(&self, py: Python, reason: &str) -> bool {
+        py.allow_threads(|| self.assistant.cancel(reason))
+    }
+    
+    /// Change timeouts; None keeps the current value
+    #[pyo3(signature = (listen_timeout=None, max_utterance=None, follow_up_window=None, thinking_timeout=None))]
+    fn set_timeouts(
+        &self,
+        listen_timeout: Option<f64>,
+        max_utterance: Option<f64>,
+        follow_up_window: Option<f64>,
+        thinking_timeout: Option<f64>,
+    ) -> PyResult<()> {
+        let mut machine = self.assistant.machine.lock().unwrap();
+        let mut timeouts = machine.timeouts;
+        if let Some(seconds) = listen_timeout {
+            timeouts.listen = check_timeout("listen_timeout", seconds, false)?;
+        }
+        if let Some(seconds) = max_utterance {
+            timeouts.max_utterance = check_timeout("max_utterance", seconds, false)?;
+        }
+        if let Some(seconds) = follow_up_window {
+            timeouts.follow_up = check_timeout("follow_up_window", seconds, true)?;
+        }
+        if let Some(seconds) = thinking_timeout {
+            timeouts.thinking = check_timeout("thinking_timeout", seconds, false)?;
+        }
+        machine.timeouts = timeouts;
+        self.assistant.changed.notify_all();
+        Ok(())
+    }
+    
+    /// Get the timeouts in seconds
+    fn get_timeouts(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let timeouts = self.assistant.machine.lock().unwrap().timeouts;
+        let dict = PyDict::new(py);
+        dict.set_item("listen_timeout", timeouts.listen.as_secs_f64())?;
+        dict.set_item("max_utterance", timeouts.max_utterance.as_secs_f64())?;
+        dict.set_item("follow_up_window", timeouts.follow_up.as_secs_f64())?;
+        dict.set_item("thinking_timeout", timeouts.thinking.as_secs_f64())?;
+        Ok(dict.into())
+    }
+    
+    /// Stop the timer thread; safe to call more than once
+    fn release(&mut self, py: Python) -> PyResult<()> {
+        let assistant = &self.assistant;
+        py.allow_threads(|| assistant.shutdown());
+        Ok(())
+    }
+}
+
+impl Drop for AssistantState {
+    fn drop(&mut self) {
+        self.assistant.stop.store(true, Ordering::Relaxed);
+        self.assistant.changed.notify_all();
+    }
+}
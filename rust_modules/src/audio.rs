@@ -1,10 +1,22 @@
 use pyo3::prelude::*;
-use pyo3::exceptions::PyValueError;
-use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ndarray::{Array1, ArrayView1};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
 use std::sync::{Arc, Mutex};
 use std::collections::VecDeque;
+use std::panic::AssertUnwindSafe;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use jenna_core::resample;
+
+use crate::errors::{DeviceError, JennaError};
+use crate::events::{self, Event, EventData};
+use crate::panics;
+use crate::tuning;
+
+pub use jenna_core::resample::MonoResampler;
 
 #[derive(Error, Debug)]
 pub enum AudioError {
@@ -16,6 +28,133 @@ pub enum AudioError {
     
     #[error("Audio format error: {0}")]
     FormatError(String),
+    
+    #[error("Microphone access denied by the operating system: {0}")]
+    PermissionDenied(String),
+    
+    #[error("Audio device is in use by another application: {0}")]
+    DeviceBusy(String),
+}
+
+impl From<resample::ResampleError> for AudioError {
+    fn from(err: resample::ResampleError) -> AudioError {
+        AudioError::FormatError(err.0)
+    }
+}
+
+impl From<AudioError> for PyErr {
+    fn from(err: AudioError) -> PyErr {
+        match err {
+            AudioError::FormatError(_) => JennaError::new_err(err.to_string()),
+            _ => DeviceError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// Classify a backend error message into a specific audio error
+/// 
+/// Backends report permission and exclusive-mode failures as generic errors,
+/// so the well-known WASAPI, CoreAudio and ALSA codes are matched by text.
+pub fn classify_stream_error(message: String) -> AudioError {
+    let lower = message.to_lowercase();
+    
+    const DENIED: &[&str] = &["access denied", "access is denied", "0x80070005", "permission denied", "not permitted", "eperm", "eacces"];
+    // Only codes that mean busy; looser words like "in use" appear in unrelated messages.
+    // "device or resource busy" is how EBUSY is spelled out.
+    const BUSY: &[&str] = &["audclnt_e_device_in_use", "0x8889000a", "device or resource busy", "ebusy", "!hog"];
+    
+    if DENIED.iter().any(|pattern| lower.contains(pattern)) {
+        AudioError::PermissionDenied(message)
+    } else if BUSY.iter().any(|pattern| lower.contains(pattern)) {
+        AudioError::DeviceBusy(message)
+    } else {
+        AudioError::StreamError(message)
+    }
+}
+
+/// How long the microphone probe listens on macOS before judging the input
+const PROBE_LISTEN: Duration = Duration::from_millis(300);
+
+/// Briefly open an input stream on the device to verify that capture is allowed
+/// 
+/// The stream is closed immediately, so the OS privacy indicator only flashes.
+/// macOS doesn't fail a stream when it denies the microphone; it delivers
+/// digital silence instead. There the probe listens briefly, and input that
+/// is exactly zero throughout is reported as a (likely) denial.
+pub fn probe_input_device(device: &cpal::Device) -> Result<(), AudioError> {
+    let config = device
+        .default_input_config()
+        .map_err(|err| classify_stream_error(err.to_string()))?;
+    let channels = config.channels() as usize;
+    let callbacks = Arc::new(AtomicU64::new(0));
+    let heard = Arc::new(AtomicBool::new(false));
+    let (sink_callbacks, sink_heard) = (callbacks.clone(), heard.clone());
+    
+    let stream = device
+        .build_input_stream_raw(
+            &config.config(),
+            config.sample_format(),
+            move |data: &cpal::Data, _: &cpal::InputCallbackInfo| {
+                let _ = panics::catch(
+                    "microphone probe callback",
+                    AssertUnwindSafe(|| {
+                        sink_callbacks.fetch_add(1, Ordering::Relaxed);
+                        if !sink_heard.load(Ordering::Relaxed) && data_to_mono(data, channels).iter().any(|&s| s != 0.0) {
+                            sink_heard.store(true, Ordering::Relaxed);
+                        }
+                    }),
+                );
+            },
+            |err| tracing::warn!("Microphone probe stream error: {}", err),
+            None,
+        )
+        .map_err(|err| classify_stream_error(err.to_string()))?;
+    stream.play().map_err(|err| classify_stream_error(err.to_string()))?;
+    let listen = cfg!(target_os = "macos");
+    if listen {
+        thread::sleep(PROBE_LISTEN);
+    }
+    drop(stream);
+    
+    // A real microphone always picks up some noise, even in a quiet room
+    if listen && callbacks.load(Ordering::Relaxed) > 0 && !heard.load(Ordering::Relaxed) {
+        return Err(AudioError::PermissionDenied(
+            "the microphone delivered only digital silence, which is how macOS answers when access is denied; \
+             check System Settings > Privacy & Security > Microphone"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Find an input device by name, or the default input device
+pub fn find_input_device(device_name: Option<&str>) -> Result<cpal::Device, AudioError> {
+    let host = cpal::default_host();
+    match device_name {
+        Some(name) => host
+            .input_devices()
+            .map_err(|err| AudioError::DeviceError(err.to_string()))?
+            .find(|device| device.name().map(|n| n == name).unwrap_or(false))
+            .ok_or_else(|| AudioError::DeviceError(format!("Input device not found: {}", name))),
+        None => host
+            .default_input_device()
+            .ok_or_else(|| AudioError::DeviceError("No default input device available".to_string())),
+    }
+}
+
+/// Find an output device by name, or the default output device
+pub fn find_output_device(device_name: Option<&str>) -> Result<cpal::Device, AudioError> {
+    let host = cpal::default_host();
+    match device_name {
+        Some(name) => host
+            .output_devices()
+            .map_err(|err| AudioError::DeviceError(err.to_string()))?
+            .find(|device| device.name().map(|n| n == name).unwrap_or(false))
+            .ok_or_else(|| AudioError::DeviceError(format!("Output device not found: {}", name))),
+        None => host
+            .default_output_device()
+            .ok_or_else(|| AudioError::DeviceError("No default output device available".to_string())),
+    }
 }
 
 /// A structure to hold audio buffer data
@@ -108,7 +247,7 @@ pub fn get_input_devices() -> PyResult<Vec<String>> {
                 .collect();
             Ok(device_names)
         },
-        Err(err) => Err(PyValueError::new_err(format!("Failed to get input devices: {}", err)))
+        Err(err) => Err(DeviceError::new_err(format!("Failed to get input devices: {}", err)))
     }
 }
 
@@ -123,6 +262,755 @@ pub fn get_output_devices() -> PyResult<Vec<String>> {
                 .collect();
             Ok(device_names)
         },
-        Err(err) => Err(PyValueError::new_err(format!("Failed to get output devices: {}", err)))
+        Err(err) => Err(DeviceError::new_err(format!("Failed to get output devices: {}", err)))
+    }
+}
+
+/// Check whether the microphone can currently be opened
+/// 
+/// Returns one of "granted", "denied" (OS privacy settings block access),
+/// "busy" (another application holds the device in exclusive mode),
+/// "no_device" or "error". Call again after the user changes settings.
+#[pyfunction]
+#[pyo3(signature = (device_name=None))]
+pub fn check_microphone_access(py: Python, device_name: Option<String>) -> PyResult<String> {
+    let status = py.allow_threads(|| {
+        let device = match find_input_device(device_name.as_deref()) {
+            Ok(device) => device,
+            Err(_) => return "no_device",
+        };
+        
+        match probe_input_device(&device) {
+            Ok(()) => "granted",
+            Err(AudioError::PermissionDenied(_)) => "denied",
+            Err(AudioError::DeviceBusy(_)) => "busy",
+            Err(err) => {
+                tracing::warn!("Microphone probe failed: {}", err);
+                "error"
+            }
+        }
+    });
+    
+    Ok(status.to_string())
+}
+
+/// Open the microphone briefly and raise a specific error if access fails
+/// 
+/// Raises `DeviceError`; the message says whether the OS denied access or
+/// the device is held by another application.
+#[pyfunction]
+#[pyo3(signature = (device_name=None))]
+pub fn ensure_microphone_access(py: Python, device_name: Option<String>) -> PyResult<()> {
+    py.allow_threads(|| {
+        let device = find_input_device(device_name.as_deref())?;
+        probe_input_device(&device)
+    })?;
+    Ok(())
+}
+
+/// Convert an interleaved capture buffer of any supported sample format to mono f32
+pub fn data_to_mono(data: &cpal::Data, channels: usize) -> Vec<f32> {
+    let channels = channels.max(1);
+    let interleaved: Vec<f32> = if let Some(samples) = data.as_slice::<f32>() {
+        samples.to_vec()
+    } else if let Some(samples) = data.as_slice::<i16>() {
+        samples.iter().map(|&s| s as f32 / 32768.0).collect()
+    } else if let Some(samples) = data.as_slice::<u16>() {
+        samples.iter().map(|&s| (s as f32 - 32768.0) / 32768.0).collect()
+    } else if let Some(samples) = data.as_slice::<i32>() {
+        samples.iter().map(|&s| s as f32 / 2147483648.0).collect()
+    } else {
+        return Vec::new();
+    };
+    
+    if channels == 1 {
+        return interleaved;
+    }
+    interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Level reported for silence, and while metering is off
+const SILENT_LEVEL_DB: f32 = -120.0;
+
+/// Software mute applied inside the capture callback
+/// 
+/// While muted, blocks are dropped before they are handed to the channel,
+/// so nothing downstream (wake word, recognizer, recordings, the recent
+/// audio buffer) can see them whatever the hardware mute state is. With
+/// metering on, the callback still measures each block's level, and only
+/// the level leaves the callback.
+pub struct PrivacyGate {
+    muted: AtomicBool,
+    metering: AtomicBool,
+    /// Level of the last captured block in dBFS, as f32 bits
+    level: AtomicU32,
+}
+
+impl PrivacyGate {
+    pub fn new() -> Self {
+        PrivacyGate {
+            muted: AtomicBool::new(false),
+            metering: AtomicBool::new(true),
+            level: AtomicU32::new(SILENT_LEVEL_DB.to_bits()),
+        }
+    }
+    
+    pub fn is_muted(&self) -> bool {
+        self.muted.load(Ordering::SeqCst)
+    }
+    
+    /// Mute or unmute; returns whether the mute state changed
+    pub fn set(&self, muted: bool, metering: bool) -> bool {
+        self.metering.store(metering, Ordering::SeqCst);
+        if !metering {
+            self.level.store(SILENT_LEVEL_DB.to_bits(), Ordering::Relaxed);
+        }
+        self.muted.swap(muted, Ordering::SeqCst) != muted
+    }
+    
+    /// Level of the last captured block in dBFS, or None while metering is off
+    pub fn level_db(&self) -> Option<f32> {
+        self.metering
+            .load(Ordering::Relaxed)
+            .then(|| f32::from_bits(self.level.load(Ordering::Relaxed)))
+    }
+    
+    /// Meter a captured block and decide whether it may leave the callback
+    fn admit(&self, block: &[f32]) -> bool {
+        if self.metering.load(Ordering::Relaxed) && !block.is_empty() {
+            let rms = (block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32).sqrt();
+            let level = if rms > 0.0 { (20.0 * rms.log10()).max(SILENT_LEVEL_DB) } else { SILENT_LEVEL_DB };
+            self.level.store(level.to_bits(), Ordering::Relaxed);
+        }
+        !self.muted.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for PrivacyGate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Open an input stream that delivers mono f32 blocks over a channel
+/// 
+/// Returns the stream (which must be kept alive on the calling thread) and
+/// the device sample rate. Blocks are dropped if the receiver falls behind,
+/// and never sent while `privacy` is muted. `callbacks` is incremented on
+/// every callback, for the stall watchdog. If the callback panics it goes
+/// quiet instead of unwinding into the audio backend, so the watchdog
+/// reports a stall and, if allowed, reopens the stream. The device's block
+/// size from the applied `DeviceConfigStore` is used if it accepts it.
+fn open_mono_capture(
+    device_name: Option<&str>,
+    sender: SyncSender<Vec<f32>>,
+    callbacks: Arc<AtomicU64>,
+    privacy: Option<Arc<PrivacyGate>>,
+) -> Result<(cpal::Stream, u32), AudioError> {
+    let device = find_input_device(device_name)?;
+    let supported = device
+        .default_input_config()
+        .map_err(|err| classify_stream_error(err.to_string()))?;
+    let channels = supported.channels() as usize;
+    let sample_rate = supported.sample_rate().0;
+    let mut config = supported.config();
+    // A block size tuned for this device applies while it still runs at the tuned rate
+    let preferred = device.name().ok().and_then(|name| tuning::preferred_config(&name));
+    if let Some(preferred) = preferred.filter(|preferred| preferred.sample_rate == sample_rate) {
+        config.buffer_size = cpal::BufferSize::Fixed(preferred.block_size);
+    }
+    
+    let format = supported.sample_format();
+    let stream = match build_mono_capture(&device, &config, format, channels, sender.clone(), callbacks.clone(), privacy.clone()) {
+        Err(err) if matches!(config.buffer_size, cpal::BufferSize::Fixed(_)) => {
+            tracing::warn!("Preferred capture block size rejected ({}); using the device default", err);
+            config.buffer_size = cpal::BufferSize::Default;
+            build_mono_capture(&device, &config, format, channels, sender, callbacks, privacy)?
+        }
+        stream => stream?,
+    };
+    stream.play().map_err(|err| classify_stream_error(err.to_string()))?;
+    
+    Ok((stream, sample_rate))
+}
+
+/// Build the stream behind `open_mono_capture`
+fn build_mono_capture(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    sample_format: cpal::SampleFormat,
+    channels: usize,
+    sender: SyncSender<Vec<f32>>,
+    callbacks: Arc<AtomicU64>,
+    privacy: Option<Arc<PrivacyGate>>,
+) -> Result<cpal::Stream, AudioError> {
+    let mut failed = false;
+    device
+        .build_input_stream_raw(
+            config,
+            sample_format,
+            move |data: &cpal::Data, _: &cpal::InputCallbackInfo| {
+                if failed {
+                    return;
+                }
+                let outcome = panics::catch(
+                    "capture callback",
+                    AssertUnwindSafe(|| {
+                        callbacks.fetch_add(1, Ordering::Relaxed);
+                        let block = data_to_mono(data, channels);
+                        if privacy.as_ref().map_or(false, |privacy| !privacy.admit(&block)) {
+                            return;
+                        }
+                        let _ = sender.try_send(block);
+                    }),
+                );
+                failed = outcome.is_err();
+            },
+            |err| {
+                tracing::warn!("Capture stream error: {}", err);
+                events::post(Event::new("audio.error", "capture", EventData::Text(err.to_string())));
+            },
+            None,
+        )
+        .map_err(|err| classify_stream_error(err.to_string()))
+}
+
+/// Blocks buffered between the capture callback and its consumer
+const CAPTURE_BACKLOG: usize = 64;
+
+/// What a capture does when its callback stops delivering audio
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StallPolicy {
+    /// Time without a callback that counts as a stall; None disables the watchdog
+    pub timeout: Option<Duration>,
+    /// Reopen the stream on a stall instead of only reporting it
+    pub restart: bool,
+}
+
+impl StallPolicy {
+    /// Policy from a timeout in seconds (0 disables the watchdog)
+    pub fn new(timeout_s: f32, restart: bool) -> Result<Self, String> {
+        let timeout = Duration::try_from_secs_f32(timeout_s)
+            .map_err(|_| "Stall timeout is out of range; expected a non-negative number of seconds".to_string())?;
+        let timeout = (!timeout.is_zero()).then_some(timeout);
+        Ok(StallPolicy { timeout, restart })
+    }
+}
+
+/// Thread that notices when a capture callback goes quiet
+/// 
+/// Posts "audio.stalled" once per stall and "audio.recovered" when
+/// callbacks resume. With a restarting policy it also raises `restart`
+/// every `timeout` while the stall lasts, so a failed reopen is retried.
+struct Watchdog {
+    restart: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    fn spawn(callbacks: Arc<AtomicU64>, timeout: Duration, restart_on_stall: bool, device: String) -> Result<Self, AudioError> {
+        let restart = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_restart, thread_stop) = (restart.clone(), stop.clone());
+        let poll = (timeout / 4).clamp(Duration::from_millis(10), Duration::from_millis(250));
+        
+        let handle = thread::Builder::new()
+            .name("jenna-watchdog".to_string())
+            .spawn(move || {
+                let mut last_count = callbacks.load(Ordering::Relaxed);
+                let mut last_change = Instant::now();
+                let mut stalled = false;
+                while !thread_stop.load(Ordering::Relaxed) {
+                    thread::sleep(poll);
+                    let count = callbacks.load(Ordering::Relaxed);
+                    if count != last_count {
+                        last_count = count;
+                        last_change = Instant::now();
+                        if stalled {
+                            stalled = false;
+                            tracing::info!(device = %device, "Capture stream recovered");
+                            events::post(Event::new("audio.recovered", "capture", EventData::Text(device.clone())));
+                        }
+                    } else if last_change.elapsed() >= timeout {
+                        if !stalled {
+                            stalled = true;
+                            tracing::warn!(device = %device, "No audio from the capture stream for {:?}", timeout);
+                            events::post(Event::new("audio.stalled", "capture", EventData::Text(device.clone())));
+                        }
+                        if restart_on_stall {
+                            thread_restart.store(true, Ordering::Relaxed);
+                        }
+                        // Measure the next period from here, so a restart gets a full timeout
+                        last_change = Instant::now();
+                    }
+                }
+            })
+            .map_err(|err| AudioError::StreamError(format!("Failed to start capture watchdog: {}", err)))?;
+        
+        Ok(Watchdog { restart, stop, handle: Some(handle) })
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Result of waiting for captured audio
+pub enum Captured {
+    /// A block of mono samples at the target rate (possibly empty while the resampler fills)
+    Audio(Vec<f32>),
+    /// Nothing arrived within the wait
+    Idle,
+    /// The stream closed for good
+    Closed,
+}
+
+/// Mono capture resampled to a fixed rate and supervised by a stall watchdog
+/// 
+/// Some drivers stop invoking the capture callback without reporting an
+/// error (device sleep, driver hiccups), which would leave the assistant
+/// deaf until restarted. The watchdog reports such stalls on the event bus
+/// and, if the policy allows, the stream is reopened on the capture thread
+/// (cpal streams must stay on the thread that opened them).
+pub struct CaptureSource {
+    device_name: Option<String>,
+    target_rate: u32,
+    stream: Option<cpal::Stream>,
+    receiver: mpsc::Receiver<Vec<f32>>,
+    resampler: MonoResampler,
+    callbacks: Arc<AtomicU64>,
+    watchdog: Option<Watchdog>,
+    privacy: Option<Arc<PrivacyGate>>,
+}
+
+impl CaptureSource {
+    /// Open a capture stream that yields audio at `target_rate`
+    pub fn open(device_name: Option<String>, target_rate: u32, policy: StallPolicy) -> Result<Self, AudioError> {
+        Self::open_gated(device_name, target_rate, policy, None)
     }
-}
\ No newline at end of file
+    
+    /// Open a capture stream whose callback drops every block while `privacy` is muted
+    pub fn open_gated(
+        device_name: Option<String>,
+        target_rate: u32,
+        policy: StallPolicy,
+        privacy: Option<Arc<PrivacyGate>>,
+    ) -> Result<Self, AudioError> {
+        let callbacks = Arc::new(AtomicU64::new(0));
+        let (stream, receiver, resampler) = Self::connect(device_name.as_deref(), target_rate, &callbacks, &privacy)?;
+        let watchdog = match policy.timeout {
+            Some(timeout) => {
+                let device = device_name.clone().unwrap_or_else(|| "default".to_string());
+                Some(Watchdog::spawn(callbacks.clone(), timeout, policy.restart, device)?)
+            }
+            None => None,
+        };
+        
+        Ok(CaptureSource {
+            device_name,
+            target_rate,
+            stream: Some(stream),
+            receiver,
+            resampler,
+            callbacks,
+            watchdog,
+            privacy,
+        })
+    }
+    
+    fn connect(
+        device_name: Option<&str>,
+        target_rate: u32,
+        callbacks: &Arc<AtomicU64>,
+        privacy: &Option<Arc<PrivacyGate>>,
+    ) -> Result<(cpal::Stream, mpsc::Receiver<Vec<f32>>, MonoResampler), AudioError> {
+        let (sender, receiver) = mpsc::sync_channel::<Vec<f32>>(CAPTURE_BACKLOG);
+        let (stream, device_rate) = open_mono_capture(device_name, sender, callbacks.clone(), privacy.clone())?;
+        let resampler = MonoResampler::new(device_rate, target_rate)?;
+        Ok((stream, receiver, resampler))
+    }
+    
+    /// Close the stream and open it again; on failure the watchdog asks again later
+    fn reopen(&mut self) {
+        // Release the device before asking for it again
+        self.stream = None;
+        match Self::connect(self.device_name.as_deref(), self.target_rate, &self.callbacks, &self.privacy) {
+            Ok((stream, receiver, resampler)) => {
+                tracing::info!(device = ?self.device_name, "Capture stream restarted");
+                self.stream = Some(stream);
+                self.receiver = receiver;
+                self.resampler = resampler;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to restart capture stream: {}", err);
+                events::post(Event::new("audio.error", "capture", EventData::Text(err.to_string())));
+            }
+        }
+    }
+    
+    /// Wait up to `timeout` for the next block of audio
+    pub fn recv(&mut self, timeout: Duration) -> Result<Captured, AudioError> {
+        if self.watchdog.as_ref().map_or(false, |w| w.restart.swap(false, Ordering::Relaxed)) {
+            self.reopen();
+        }
+        if self.stream.is_none() {
+            // Waiting for the next restart attempt
+            thread::sleep(timeout);
+            return Ok(Captured::Idle);
+        }
+        
+        match self.receiver.recv_timeout(timeout) {
+            Ok(block) => Ok(Captured::Audio(self.resampler.push(&block)?)),
+            Err(RecvTimeoutError::Timeout) => Ok(Captured::Idle),
+            Err(RecvTimeoutError::Disconnected) => Ok(Captured::Closed),
+        }
+    }
+}
+
+/// Most played samples kept for the echo canceller before the oldest are dropped
+const REFERENCE_LIMIT: usize = 96000;
+
+/// A short sound decoded once and kept ready for playback
+/// 
+/// The decoded samples are resampled to the output rate on first use and
+/// the result kept, so playing it again costs no more than a copy of the
+/// pointer.
+pub struct Earcon {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    at_output: Option<(u32, Arc<[f32]>)>,
+}
+
+impl Earcon {
+    pub fn new(samples: Vec<f32>, sample_rate: u32) -> Self {
+        Earcon { samples, sample_rate, at_output: None }
+    }
+    
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate as f64
+    }
+    
+    /// The sound at `output_rate`, resampled on the first call for that rate
+    pub fn at_rate(&mut self, output_rate: u32) -> Result<Arc<[f32]>, AudioError> {
+        match &self.at_output {
+            Some((rate, samples)) if *rate == output_rate => Ok(samples.clone()),
+            _ => {
+                let samples: Arc<[f32]> = resample_mono(&self.samples, self.sample_rate, output_rate)?.into();
+                self.at_output = Some((output_rate, samples.clone()));
+                Ok(samples)
+            }
+        }
+    }
+}
+
+/// An earcon waiting for, or partway through, playback
+struct ScheduledEarcon {
+    samples: Arc<[f32]>,
+    /// Output frame the first sample lands on
+    start: u64,
+    gain: f32,
+    /// Gain applied to the queued audio while the earcon plays, None to layer over it
+    duck: Option<f32>,
+}
+
+impl ScheduledEarcon {
+    fn end(&self) -> u64 {
+        self.start + self.samples.len() as u64
+    }
+}
+
+/// State the output callback shares with the code driving playback
+/// 
+/// The gain ducks playback without dropping it. With `keep_reference`,
+/// every sample handed to the device (silence included, so the stream
+/// keeps the device clock) is kept for echo cancellation. Earcons are
+/// mixed over the queued audio at the output frame they were scheduled
+/// for, counted by `clock`.
+pub struct PlaybackMonitor {
+    /// Linear gain as f32 bits
+    gain: AtomicU32,
+    keep_reference: bool,
+    reference: Mutex<VecDeque<f32>>,
+    /// Output frames handed to the device so far
+    clock: AtomicU64,
+    earcons: Mutex<Vec<ScheduledEarcon>>,
+    /// Set when the output callback panicked and went silent
+    failed: AtomicBool,
+}
+
+impl PlaybackMonitor {
+    pub fn new(keep_reference: bool) -> Self {
+        PlaybackMonitor {
+            gain: AtomicU32::new(1.0f32.to_bits()),
+            keep_reference,
+            reference: Mutex::new(VecDeque::new()),
+            clock: AtomicU64::new(0),
+            earcons: Mutex::new(Vec::new()),
+            failed: AtomicBool::new(false),
+        }
+    }
+    
+    /// Check whether the output callback panicked; the stream only plays silence after that
+    pub fn callback_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+    
+    /// Output frames played so far; an earcon scheduled at this frame starts with the next callback
+    pub fn clock(&self) -> u64 {
+        self.clock.load(Ordering::Relaxed)
+    }
+    
+    /// Mix `samples` (at the output rate) into playback from output frame `start`
+    /// 
+    /// With `duck`, the queued audio is scaled by it for as long as the
+    /// earcon plays; otherwise the earcon is layered over it.
+    pub fn schedule_earcon(&self, samples: Arc<[f32]>, start: u64, gain: f32, duck: Option<f32>) {
+        self.earcons.lock().unwrap().push(ScheduledEarcon { samples, start, gain: gain.max(0.0), duck });
+    }
+    
+    /// Check whether an earcon is playing or waiting to start
+    pub fn earcons_pending(&self) -> bool {
+        !self.earcons.lock().unwrap().is_empty()
+    }
+    
+    pub fn clear_earcons(&self) {
+        self.earcons.lock().unwrap().clear();
+    }
+    
+    pub fn gain(&self) -> f32 {
+        f32::from_bits(self.gain.load(Ordering::Relaxed))
+    }
+    
+    pub fn set_gain(&self, gain: f32) {
+        self.gain.store(gain.max(0.0).to_bits(), Ordering::Relaxed);
+    }
+    
+    /// Take the samples played since the last call, at the device rate
+    pub fn take_reference(&self) -> Vec<f32> {
+        self.reference.lock().unwrap().drain(..).collect()
+    }
+    
+    pub fn clear_reference(&self) {
+        self.reference.lock().unwrap().clear();
+    }
+}
+
+/// Sum of the earcons sounding at output frame `at`, and the gain they leave the queued audio
+fn mix_earcons(earcons: &[ScheduledEarcon], at: u64) -> (f32, f32) {
+    let mut mixed = 0.0;
+    let mut duck = 1.0f32;
+    for earcon in earcons.iter().filter(|earcon| earcon.start <= at) {
+        if let Some(sample) = earcon.samples.get((at - earcon.start) as usize) {
+            mixed += sample * earcon.gain;
+            if let Some(gain) = earcon.duck {
+                duck = duck.min(gain);
+            }
+        }
+    }
+    (mixed, duck)
+}
+
+/// Fill an interleaved playback buffer from a mono queue, padding with silence
+/// 
+/// A monitor's earcons are mixed in at the frames they were scheduled for,
+/// and the mix is what the echo reference keeps.
+pub fn fill_from_mono(data: &mut cpal::Data, channels: usize, queue: &Mutex<VecDeque<f32>>, monitor: Option<&PlaybackMonitor>) {
+    let channels = channels.max(1);
+    let gain = monitor.map_or(1.0, PlaybackMonitor::gain);
+    let mut reference = monitor.filter(|monitor| monitor.keep_reference).map(|monitor| monitor.reference.lock().unwrap());
+    let mut earcons = monitor.map(|monitor| monitor.earcons.lock().unwrap());
+    let mut clock = monitor.map_or(0, PlaybackMonitor::clock);
+    let mut queue = queue.lock().unwrap();
+    let mut next = || {
+        let (mixed, duck) = match earcons.as_deref() {
+            Some(earcons) if !earcons.is_empty() => mix_earcons(earcons, clock),
+            _ => (0.0, 1.0),
+        };
+        clock += 1;
+        let sample = (queue.pop_front().unwrap_or(0.0) * gain * duck + mixed).clamp(-1.0, 1.0);
+        if let Some(reference) = reference.as_mut() {
+            reference.push_back(sample);
+        }
+        sample
+    };
+    
+    if let Some(samples) = data.as_slice_mut::<f32>() {
+        for frame in samples.chunks_mut(channels) {
+            frame.fill(next());
+        }
+    } else if let Some(samples) = data.as_slice_mut::<i16>() {
+        for frame in samples.chunks_mut(channels) {
+            frame.fill((next() * 32767.0) as i16);
+        }
+    } else if let Some(samples) = data.as_slice_mut::<u16>() {
+        for frame in samples.chunks_mut(channels) {
+            frame.fill((next() * 32767.0 + 32768.0) as u16);
+        }
+    } else if let Some(samples) = data.as_slice_mut::<i32>() {
+        for frame in samples.chunks_mut(channels) {
+            frame.fill((next() as f64 * 2147483647.0) as i32);
+        }
+    }
+    
+    if let Some(reference) = reference.as_mut() {
+        let excess = reference.len().saturating_sub(REFERENCE_LIMIT);
+        reference.drain(..excess);
+    }
+    if let Some(monitor) = monitor {
+        monitor.clock.store(clock, Ordering::Relaxed);
+    }
+    if let Some(earcons) = earcons.as_mut() {
+        earcons.retain(|earcon| earcon.end() > clock);
+    }
+}
+
+/// Write silence to an interleaved playback buffer
+fn fill_silence(data: &mut cpal::Data) {
+    if let Some(samples) = data.as_slice_mut::<f32>() {
+        samples.fill(0.0);
+    } else if let Some(samples) = data.as_slice_mut::<i16>() {
+        samples.fill(0);
+    } else if let Some(samples) = data.as_slice_mut::<u16>() {
+        samples.fill(32768);
+    } else if let Some(samples) = data.as_slice_mut::<i32>() {
+        samples.fill(0);
+    }
+}
+
+/// Open an output stream that plays mono f32 samples pushed onto `queue`
+/// 
+/// Returns the stream (which must be kept alive on the calling thread) and
+/// the device sample rate; samples must already be at that rate. A
+/// `monitor` applies its gain and receives the played samples. If the
+/// callback panics, the stream plays silence from then on and the monitor
+/// reports it, so the owner can reopen the stream.
+pub fn open_mono_playback(
+    device_name: Option<&str>,
+    queue: Arc<Mutex<VecDeque<f32>>>,
+    monitor: Option<Arc<PlaybackMonitor>>,
+) -> Result<(cpal::Stream, u32), AudioError> {
+    let device = find_output_device(device_name)?;
+    let config = device
+        .default_output_config()
+        .map_err(|err| classify_stream_error(err.to_string()))?;
+    let channels = config.channels() as usize;
+    let sample_rate = config.sample_rate().0;
+    let mut failed = false;
+    if let Some(monitor) = &monitor {
+        monitor.failed.store(false, Ordering::Relaxed);
+    }
+    
+    let stream = device
+        .build_output_stream_raw(
+            &config.config(),
+            config.sample_format(),
+            move |data: &mut cpal::Data, _: &cpal::OutputCallbackInfo| {
+                if !failed {
+                    let outcome = panics::catch(
+                        "playback callback",
+                        AssertUnwindSafe(|| fill_from_mono(data, channels, &queue, monitor.as_deref())),
+                    );
+                    if outcome.is_err() {
+                        failed = true;
+                        // Whoever queues audio next shouldn't panic on the locks this callback held
+                        queue.clear_poison();
+                        if let Some(monitor) = &monitor {
+                            monitor.reference.clear_poison();
+                            monitor.earcons.clear_poison();
+                            monitor.failed.store(true, Ordering::Relaxed);
+                        }
+                    }
+                }
+                if failed {
+                    fill_silence(data);
+                }
+            },
+            |err| {
+                tracing::warn!("Playback stream error: {}", err);
+                events::post(Event::new("audio.error", "playback", EventData::Text(err.to_string())));
+            },
+            None,
+        )
+        .map_err(|err| classify_stream_error(err.to_string()))?;
+    stream.play().map_err(|err| classify_stream_error(err.to_string()))?;
+    
+    Ok((stream, sample_rate))
+}
+
+/// Read a WAV file as mono f32 samples, returning the samples and sample rate
+pub fn read_wav_mono(path: &std::path::Path) -> Result<(Vec<f32>, u32), AudioError> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|err| AudioError::FormatError(format!("{}: {}", path.display(), err)))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+    
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|err| AudioError::FormatError(err.to_string()))?,
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|err| AudioError::FormatError(err.to_string()))?
+        }
+    };
+    
+    let mono = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok((mono, spec.sample_rate))
+}
+
+/// Read a FLAC file as mono f32 samples, returning the samples and sample rate
+pub fn read_flac_mono(path: &std::path::Path) -> Result<(Vec<f32>, u32), AudioError> {
+    let mut reader = claxon::FlacReader::open(path)
+        .map_err(|err| AudioError::FormatError(format!("{}: {}", path.display(), err)))?;
+    let info = reader.streaminfo();
+    let channels = info.channels.max(1) as usize;
+    let scale = (1i64 << (info.bits_per_sample.max(1) - 1)) as f32;
+    
+    let interleaved: Vec<f32> = reader
+        .samples()
+        .map(|s| s.map(|s| s as f32 / scale))
+        .collect::<Result<_, _>>()
+        .map_err(|err| AudioError::FormatError(err.to_string()))?;
+    
+    let mono = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+    Ok((mono, info.sample_rate))
+}
+
+/// Read a WAV or FLAC file (chosen by extension) as mono f32 samples
+pub fn read_audio_file_mono(path: &std::path::Path) -> Result<(Vec<f32>, u32), AudioError> {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    
+    match extension.as_str() {
+        "wav" | "wave" => read_wav_mono(path),
+        "flac" => read_flac_mono(path),
+        _ => Err(AudioError::FormatError(format!("Unsupported audio file type: {}", path.display())))
+    }
+}
+
+/// Resample a complete mono signal, flushing the resampler tail
+pub fn resample_mono(samples: &[f32], input_rate: u32, output_rate: u32) -> Result<Vec<f32>, AudioError> {
+    Ok(resample::resample_mono(samples, input_rate, output_rate)?)
+}
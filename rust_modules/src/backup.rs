@@ -0,0 +1,282 @@
+use pyo3::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::errors::JennaError;
+
+/// Version of the backup archive layout written by this build
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Data directory used when none is given, the backend's default `data_dir`
+const DEFAULT_DATA_DIR: &str = "./data";
+
+/// Name of the manifest entry stored at the root of every archive
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Sections of the data directory that are included in a backup
+const BACKUP_SECTIONS: &[&str] = &[
+    "config",
+    "models/metadata",
+    "profiles",
+    "lexicons",
+    "memory",
+    "transcripts",
+];
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("Backup I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Backup manifest error: {0}")]
+    ManifestError(String),
+    
+    #[error("Backup integrity error: {0}")]
+    IntegrityError(String),
+}
+
+impl From<BackupError> for PyErr {
+    fn from(err: BackupError) -> PyErr {
+        JennaError::new_err(err.to_string())
+    }
+}
+
+/// A single file recorded in the backup manifest
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackupEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Manifest describing the contents of a backup archive
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: u64,
+    pub sections: Vec<String>,
+    pub files: Vec<BackupEntry>,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Recursively collect all files below `dir`
+/// 
+/// Symlinks are skipped, so a link back up the tree can't recurse forever.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), BackupError> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)?.filter_map(|entry| entry.ok()).collect();
+    entries.sort_by_key(|entry| entry.path());
+    
+    for entry in entries {
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// Convert a path relative to the data directory into a portable archive name
+fn archive_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Reject archive names that would escape the data directory when restored
+fn safe_relative_path(name: &str) -> Result<PathBuf, BackupError> {
+    let path = PathBuf::from(name);
+    if path.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(BackupError::IntegrityError(format!("Unsafe path in archive: {}", name)));
+    }
+    Ok(path)
+}
+
+fn append_file<W: std::io::Write>(builder: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<(), BackupError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// Snapshot the data directory into a compressed archive at `archive_path`
+pub fn create_backup(data_dir: &Path, archive_path: &Path) -> Result<BackupManifest, BackupError> {
+    if !data_dir.is_dir() {
+        return Err(BackupError::IoError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Data directory not found: {}", data_dir.display()),
+        )));
+    }
+    
+    let mut files = Vec::new();
+    for section in BACKUP_SECTIONS {
+        collect_files(&data_dir.join(section), &mut files)?;
+    }
+    
+    let mut contents = Vec::with_capacity(files.len());
+    let mut entries = Vec::with_capacity(files.len());
+    for path in files {
+        let relative = path.strip_prefix(data_dir).unwrap_or(&path);
+        let name = archive_name(relative);
+        let data = fs::read(&path)?;
+        entries.push(BackupEntry {
+            path: name.clone(),
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+        });
+        contents.push((name, data));
+    }
+    
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        sections: BACKUP_SECTIONS.iter().map(|s| s.to_string()).collect(),
+        files: entries,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|err| BackupError::ManifestError(err.to_string()))?;
+    
+    if let Some(parent) = archive_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    
+    // Write to a temporary file first so a failed backup never clobbers an existing archive
+    let tmp_path = archive_path.with_extension("partial");
+    {
+        let encoder = GzEncoder::new(File::create(&tmp_path)?, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        append_file(&mut builder, MANIFEST_NAME, &manifest_json)?;
+        for (name, data) in &contents {
+            append_file(&mut builder, name, data)?;
+        }
+        builder.into_inner()?.finish()?;
+    }
+    fs::rename(&tmp_path, archive_path)?;
+    
+    Ok(manifest)
+}
+
+/// Read and verify a backup archive, returning its manifest and file contents
+pub fn read_backup(archive_path: &Path) -> Result<(BackupManifest, Vec<(PathBuf, Vec<u8>)>), BackupError> {
+    let decoder = GzDecoder::new(File::open(archive_path)?);
+    let mut archive = tar::Archive::new(decoder);
+    
+    let mut manifest: Option<BackupManifest> = None;
+    let mut contents = std::collections::HashMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        
+        if name == MANIFEST_NAME {
+            manifest = Some(serde_json::from_slice(&data)
+                .map_err(|err| BackupError::ManifestError(err.to_string()))?);
+        } else {
+            contents.insert(name, data);
+        }
+    }
+    
+    let manifest = manifest.ok_or_else(|| BackupError::ManifestError("Archive has no manifest".to_string()))?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(BackupError::ManifestError(format!(
+            "Unsupported backup format version {} (this build supports up to {})",
+            manifest.format_version, BACKUP_FORMAT_VERSION
+        )));
+    }
+    
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for entry in &manifest.files {
+        let data = contents.remove(&entry.path).ok_or_else(|| {
+            BackupError::IntegrityError(format!("Missing file in archive: {}", entry.path))
+        })?;
+        if data.len() as u64 != entry.size || sha256_hex(&data) != entry.sha256 {
+            return Err(BackupError::IntegrityError(format!("Checksum mismatch for {}", entry.path)));
+        }
+        files.push((safe_relative_path(&entry.path)?, data));
+    }
+    
+    if let Some(extra) = contents.keys().next() {
+        return Err(BackupError::IntegrityError(format!("Unexpected file in archive: {}", extra)));
+    }
+    
+    Ok((manifest, files))
+}
+
+/// Restore a verified backup archive into the data directory
+pub fn restore_backup(archive_path: &Path, data_dir: &Path, overwrite: bool) -> Result<BackupManifest, BackupError> {
+    // Verify everything before touching the data directory
+    let (manifest, files) = read_backup(archive_path)?;
+    
+    if !overwrite {
+        if let Some((path, _)) = files.iter().find(|(path, _)| data_dir.join(path).exists()) {
+            return Err(BackupError::IoError(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("File already exists: {}", data_dir.join(path).display()),
+            )));
+        }
+    }
+    
+    for (path, data) in files {
+        let target = data_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, data)?;
+    }
+    
+    Ok(manifest)
+}
+
+/// Back up the Jenna data directory to a single archive
+/// 
+/// Config, model metadata, user profiles, lexicons, the memory index and
+/// transcripts are stored together with a versioned manifest of SHA-256
+/// checksums. `data_dir` defaults to "./data". Returns the number of files written.
+#[pyfunction]
+#[pyo3(signature = (path, data_dir=None))]
+pub fn backup(path: String, data_dir: Option<String>) -> PyResult<usize> {
+    let data_dir = data_dir.as_deref().unwrap_or(DEFAULT_DATA_DIR);
+    let manifest = create_backup(Path::new(data_dir), Path::new(&path))?;
+    tracing::info!(files = manifest.files.len(), archive = %path, "Created backup");
+    Ok(manifest.files.len())
+}
+
+/// Restore the Jenna data directory from an archive created by `backup`
+/// 
+/// The whole archive is verified before any file is written. Existing files
+/// are only replaced when `overwrite` is true. `data_dir` defaults to
+/// "./data", as for `backup`. Returns the number of files restored.
+#[pyfunction]
+#[pyo3(signature = (path, data_dir=None, overwrite=false))]
+pub fn restore(path: String, data_dir: Option<String>, overwrite: bool) -> PyResult<usize> {
+    let data_dir = data_dir.as_deref().unwrap_or(DEFAULT_DATA_DIR);
+    let manifest = restore_backup(Path::new(&path), Path::new(data_dir), overwrite)?;
+    tracing::info!(files = manifest.files.len(), archive = %path, "Restored backup");
+    Ok(manifest.files.len())
+}
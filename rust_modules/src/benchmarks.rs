@@ -0,0 +1,246 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use std::time::{Duration, Instant};
+
+use crate::audio::MonoResampler;
+use crate::errors::NotInitializedError;
+use crate::filters::{design_sos, BandType, FilterFamily, SosFilter};
+use crate::signal::{fir_filter, FftProcessor};
+use crate::soak::NoiseSource;
+use crate::speech::SpeechRecognizer;
+use crate::wake_word::WakeWordDetector;
+
+/// Sample rate the recognition benchmark feeds to `SttEngine::transcribe`
+const RECOGNITION_SAMPLE_RATE: u32 = 16000;
+
+/// Throughput of one benchmark
+struct BenchResult {
+    name: &'static str,
+    iterations: u64,
+    elapsed: Duration,
+    /// Seconds of audio processed per iteration, for the real-time factor
+    audio_per_iteration: f64,
+}
+
+impl BenchResult {
+    /// Convert to a dict with `name`, `iterations`, `seconds`, `per_second`,
+    /// `mean_us` and `realtime_factor` (audio seconds processed per second;
+    /// above 1 keeps up with live audio)
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let seconds = self.elapsed.as_secs_f64().max(f64::EPSILON);
+        let dict = PyDict::new(py);
+        dict.set_item("name", self.name)?;
+        dict.set_item("iterations", self.iterations)?;
+        dict.set_item("seconds", seconds)?;
+        dict.set_item("per_second", self.iterations as f64 / seconds)?;
+        dict.set_item("mean_us", seconds * 1e6 / self.iterations.max(1) as f64)?;
+        dict.set_item("realtime_factor", self.iterations as f64 * self.audio_per_iteration / seconds)?;
+        Ok(dict.into())
+    }
+}
+
+/// Run `step` repeatedly for at least `duration` (and at least once)
+fn measure(
+    name: &'static str,
+    duration: Duration,
+    audio_per_iteration: f64,
+    mut step: impl FnMut() -> Result<(), String>,
+) -> Result<BenchResult, String> {
+    // One untimed run to warm caches and build FFT plans
+    step()?;
+    
+    let start = Instant::now();
+    let mut iterations = 0u64;
+    while iterations == 0 || start.elapsed() < duration {
+        step()?;
+        iterations += 1;
+    }
+    Ok(BenchResult { name, iterations, elapsed: start.elapsed(), audio_per_iteration })
+}
+
+/// Noise at roughly -20 dBFS, loud enough to keep energy gates open
+fn noise(len: usize, seed: u32) -> Vec<f32> {
+    let mut source = NoiseSource(seed);
+    (0..len).map(|_| source.next() * 0.1).collect()
+}
+
+fn check_duration(seconds: f64) -> PyResult<Duration> {
+    if !seconds.is_finite() || seconds <= 0.0 {
+        return Err(PyValueError::new_err("Benchmark duration must be positive"));
+    }
+    Duration::try_from_secs_f64(seconds)
+        .map_err(|_| PyValueError::new_err("Benchmark duration is too large"))
+}
+
+fn bench_fft(size: usize, sample_rate: u32, duration: Duration) -> Result<BenchResult, String> {
+    let mut fft = FftProcessor::with_window("hann").map_err(|err| err.to_string())?;
+    let frame = noise(size, 0x1234_5678);
+    measure("fft", duration, size as f64 / sample_rate as f64, || {
+        fft.magnitudes(&frame).map(|_| ()).map_err(|err| err.to_string())
+    })
+}
+
+fn bench_filtering(kind: &str, block_size: usize, sample_rate: u32, duration: Duration) -> Result<BenchResult, String> {
+    let block = noise(block_size, 0x2345_6789);
+    let audio = block_size as f64 / sample_rate as f64;
+    match kind {
+        "iir" => {
+            let sections = design_sos(FilterFamily::Butterworth, BandType::Bandpass, 4, &[300.0, 3400.0], sample_rate as f64)?;
+            let mut filter = SosFilter::from_sections(sections)?;
+            let mut buffer = block.clone();
+            measure("iir_filter", duration, audio, || {
+                buffer.copy_from_slice(&block);
+                filter.process_in_place(&mut buffer);
+                Ok(())
+            })
+        }
+        "fir" => {
+            // 129-tap kernel, the size of a typical speech band-pass
+            let kernel: Vec<f32> = (0..129).map(|i| if i == 64 { 1.0 } else { 0.0 }).collect();
+            measure("fir_filter", duration, audio, || {
+                fir_filter(&block, &kernel);
+                Ok(())
+            })
+        }
+        other => Err(format!("Unknown filter kind '{}'; expected 'iir' or 'fir'", other)),
+    }
+}
+
+fn bench_resampling(input_rate: u32, output_rate: u32, block_size: usize, duration: Duration) -> Result<BenchResult, String> {
+    let mut resampler = MonoResampler::new(input_rate, output_rate).map_err(|err| err.to_string())?;
+    let block = noise(block_size, 0x3456_789A);
+    measure("resampling", duration, block_size as f64 / input_rate as f64, || {
+        resampler.push(&block).map(|_| ()).map_err(|err| err.to_string())
+    })
+}
+
+/// Time the FFT on frames of `size` samples
+/// 
+/// The real-time factor assumes non-overlapping frames at `sample_rate`.
+#[pyfunction]
+#[pyo3(signature = (size=512, sample_rate=16000, seconds=0.5))]
+pub fn fft(py: Python, size: usize, sample_rate: u32, seconds: f64) -> PyResult<Py<PyDict>> {
+    if size == 0 || sample_rate == 0 {
+        return Err(PyValueError::new_err("Size and sample rate must be positive"));
+    }
+    let duration = check_duration(seconds)?;
+    py.allow_threads(|| bench_fft(size, sample_rate, duration))
+        .map_err(PyValueError::new_err)?
+        .to_dict(py)
+}
+
+/// Time a 4th-order Butterworth band-pass (`kind="iir"`) or a 129-tap FIR filter (`kind="fir"`)
+#[pyfunction]
+#[pyo3(signature = (kind="iir", block_size=512, sample_rate=16000, seconds=0.5))]
+pub fn filtering(py: Python, kind: &str, block_size: usize, sample_rate: u32, seconds: f64) -> PyResult<Py<PyDict>> {
+    if block_size == 0 || sample_rate == 0 {
+        return Err(PyValueError::new_err("Block size and sample rate must be positive"));
+    }
+    let duration = check_duration(seconds)?;
+    let kind = kind.to_lowercase();
+    py.allow_threads(|| bench_filtering(&kind, block_size, sample_rate, duration))
+        .map_err(PyValueError::new_err)?
+        .to_dict(py)
+}
+
+/// Time streaming resampling, e.g. device capture to the engine rate
+#[pyfunction]
+#[pyo3(signature = (input_rate=48000, output_rate=16000, block_size=480, seconds=0.5))]
+pub fn resampling(py: Python, input_rate: u32, output_rate: u32, block_size: usize, seconds: f64) -> PyResult<Py<PyDict>> {
+    if input_rate == 0 || output_rate == 0 || block_size == 0 {
+        return Err(PyValueError::new_err("Sample rates and block size must be positive"));
+    }
+    let duration = check_duration(seconds)?;
+    py.allow_threads(|| bench_resampling(input_rate, output_rate, block_size, duration))
+        .map_err(PyValueError::new_err)?
+        .to_dict(py)
+}
+
+/// Time wake word processing with an initialized detector
+/// 
+/// Synthetic noise frames are fed through the detector's engine, which
+/// counts them in its statistics; don't run this on a detector that is
+/// listening or part of a running pipeline.
+#[pyfunction]
+#[pyo3(signature = (detector, seconds=0.5))]
+pub fn wake_word(py: Python, detector: PyRef<WakeWordDetector>, seconds: f64) -> PyResult<Py<PyDict>> {
+    let duration = check_duration(seconds)?;
+    let state = detector.shared_state();
+    let (frame_length, sample_rate) = state.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+    let frame: Vec<i16> = noise(frame_length, 0x4567_89AB).iter().map(|&s| (s * i16::MAX as f32) as i16).collect();
+    
+    py.allow_threads(|| {
+        measure("wake_word", duration, frame_length as f64 / sample_rate as f64, || {
+            state.lock().unwrap().process_frame(&frame).map(|_| ())
+        })
+    })
+    .map_err(PyValueError::new_err)?
+    .to_dict(py)
+}
+
+/// Time offline transcription of `audio_seconds` of audio with an initialized recognizer
+/// 
+/// Each iteration transcribes the whole clip, so this takes at least one
+/// full decode even when `seconds` is short. The clip is noise, which is
+/// enough to time Vosk and Whisper but not to judge accuracy.
+#[pyfunction]
+#[pyo3(signature = (recognizer, audio_seconds=5.0, seconds=2.0))]
+pub fn recognition(py: Python, recognizer: PyRef<SpeechRecognizer>, audio_seconds: f64, seconds: f64) -> PyResult<Py<PyDict>> {
+    let duration = check_duration(seconds)?;
+    if !audio_seconds.is_finite() || audio_seconds <= 0.0 {
+        return Err(PyValueError::new_err("Audio length must be positive"));
+    }
+    let (engine, _) = recognizer.shared_engine()?;
+    let audio = noise((audio_seconds * RECOGNITION_SAMPLE_RATE as f64) as usize, 0x5678_9ABC);
+    
+    py.allow_threads(|| {
+        measure("recognition", duration, audio_seconds, || {
+            let guard = engine.lock().unwrap();
+            let engine = guard.as_deref().ok_or_else(|| "Speech recognizer not initialized".to_string())?;
+            engine.transcribe(&audio).map(|_| ())
+        })
+    })
+    .map_err(PyValueError::new_err)?
+    .to_dict(py)
+}
+
+/// Run every benchmark and return the results keyed by name
+/// 
+/// The DSP benchmarks always run; wake word and recognition run when a
+/// detector or recognizer is passed. `cpu_count` is included to help pick
+/// model sizes and thread counts.
+#[pyfunction]
+#[pyo3(signature = (detector=None, recognizer=None, seconds=0.5))]
+pub fn run_all(
+    py: Python,
+    detector: Option<PyRef<WakeWordDetector>>,
+    recognizer: Option<PyRef<SpeechRecognizer>>,
+    seconds: f64,
+) -> PyResult<Py<PyDict>> {
+    let duration = check_duration(seconds)?;
+    let results = PyDict::new(py);
+    let dsp = py
+        .allow_threads(|| -> Result<Vec<BenchResult>, String> {
+            Ok(vec![
+                bench_fft(512, 16000, duration)?,
+                bench_filtering("iir", 512, 16000, duration)?,
+                bench_filtering("fir", 512, 16000, duration)?,
+                bench_resampling(48000, 16000, 480, duration)?,
+            ])
+        })
+        .map_err(PyValueError::new_err)?;
+    for result in dsp {
+        results.set_item(result.name, result.to_dict(py)?)?;
+    }
+    
+    if let Some(detector) = detector {
+        results.set_item("wake_word", wake_word(py, detector, seconds)?)?;
+    }
+    if let Some(recognizer) = recognizer {
+        results.set_item("recognition", recognition(py, recognizer, 5.0, seconds.max(2.0))?)?;
+    }
+    let cpus = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    results.set_item("cpu_count", cpus)?;
+    Ok(results.into())
+}
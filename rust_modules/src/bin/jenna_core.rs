@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(jenna_rust::cli::run(std::env::args().skip(1)));
+}
@@ -0,0 +1,535 @@
+use serde_json::json;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::ptr;
+
+use crate::engines::{RecognitionResult, Recognizer, Resampler, Synthesizer, WakeWord};
+use crate::panics;
+
+/// Returned by functions that succeeded
+pub const JENNA_OK: i32 = 0;
+
+/// Returned by functions that failed; see `jenna_last_error`
+pub const JENNA_ERROR: i32 = -1;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = RefCell::new(None);
+}
+
+fn set_error(message: impl Into<String>) {
+    // Interior NULs would truncate the message; replace them rather than lose it
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Record `err` and return the failure status
+fn fail(err: impl Into<String>) -> i32 {
+    set_error(err);
+    JENNA_ERROR
+}
+
+/// Run `f`, turning a panic into an error; unwinding into C would abort the host
+fn guarded<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    panics::install_hook();
+    panics::catch("C API call", AssertUnwindSafe(f)).and_then(|result| result)
+}
+
+/// Drop a handle made by `into_handle`, recording a panic in its destructor as an error
+unsafe fn release<T>(handle: *mut T) {
+    if handle.is_null() {
+        return;
+    }
+    if let Err(err) = guarded(|| {
+        drop(Box::from_raw(handle));
+        Ok(())
+    }) {
+        set_error(err);
+    }
+}
+
+/// Move `value` to the heap for C, or record the error and return NULL
+fn into_handle<T>(value: Result<T, String>) -> *mut T {
+    match value {
+        Ok(value) => Box::into_raw(Box::new(value)),
+        Err(err) => {
+            set_error(err);
+            ptr::null_mut()
+        }
+    }
+}
+
+unsafe fn opt_str<'a>(value: *const c_char, name: &str) -> Result<Option<&'a str>, String> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(value)
+        .to_str()
+        .map(Some)
+        .map_err(|_| format!("{} is not valid UTF-8", name))
+}
+
+unsafe fn req_str<'a>(value: *const c_char, name: &str) -> Result<&'a str, String> {
+    opt_str(value, name)?.ok_or_else(|| format!("{} must not be NULL", name))
+}
+
+/// View `len` elements at `data`, allowing NULL when `len` is 0
+unsafe fn slice<'a, T>(data: *const T, len: usize, name: &str) -> Result<&'a [T], String> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if data.is_null() {
+        return Err(format!("{} must not be NULL", name));
+    }
+    Ok(std::slice::from_raw_parts(data, len))
+}
+
+/// Hand a string to C; it must be released with `jenna_string_free`
+fn into_c_string(value: String) -> *mut c_char {
+    CString::new(value.replace('\0', " ")).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+fn result_json(result: &RecognitionResult) -> String {
+    let words: Vec<_> = result
+        .words
+        .iter()
+        .map(|word| json!({"word": word.word, "start": word.start, "end": word.end, "confidence": word.confidence}))
+        .collect();
+    json!({
+        "text": result.text,
+        "is_final": result.is_final,
+        "confidence": result.confidence,
+        "words": words,
+    })
+    .to_string()
+}
+
+/// Message for the last failed call on this thread, or NULL
+/// 
+/// The string stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn jenna_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |message| message.as_ptr()))
+}
+
+/// Version of the library as a static string
+#[no_mangle]
+pub extern "C" fn jenna_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr() as *const c_char
+}
+
+/// Release a string returned by this library
+/// 
+/// # Safety
+/// `value` must be NULL or a string returned by this library, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+/// Release samples returned by `jenna_tts_synthesize`
+/// 
+/// # Safety
+/// `samples` and `len` must be exactly as returned, and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_samples_free(samples: *mut i16, len: usize) {
+    if !samples.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(samples, len)));
+    }
+}
+
+/// Streaming mono resampler
+pub struct JennaResampler {
+    resampler: Resampler,
+    output: VecDeque<f32>,
+}
+
+/// Create a resampler from `input_rate` to `output_rate`
+#[no_mangle]
+pub extern "C" fn jenna_resampler_new(input_rate: u32, output_rate: u32) -> *mut JennaResampler {
+    into_handle(guarded(|| {
+        Resampler::new(input_rate, output_rate).map(|resampler| JennaResampler { resampler, output: VecDeque::new() })
+    }))
+}
+
+/// Feed `len` samples and take up to `capacity` resampled ones
+/// 
+/// Output that doesn't fit stays queued for the next call; call with
+/// `len` 0 to drain it. `written` receives the number of samples stored.
+/// 
+/// # Safety
+/// `resampler` must come from `jenna_resampler_new`; `input` must hold `len`
+/// samples and `output` room for `capacity`.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_resampler_process(
+    resampler: *mut JennaResampler,
+    input: *const f32,
+    len: usize,
+    output: *mut f32,
+    capacity: usize,
+    written: *mut usize,
+) -> i32 {
+    let resampler = match resampler.as_mut() {
+        Some(resampler) => resampler,
+        None => return fail("resampler must not be NULL"),
+    };
+    let input = match slice(input, len, "input") {
+        Ok(input) => input,
+        Err(err) => return fail(err),
+    };
+    if output.is_null() && capacity > 0 {
+        return fail("output must not be NULL");
+    }
+    if !input.is_empty() {
+        match guarded(|| resampler.resampler.process(input)) {
+            Ok(samples) => resampler.output.extend(samples),
+            Err(err) => return fail(err),
+        }
+    }
+    let count = capacity.min(resampler.output.len());
+    for (index, sample) in resampler.output.drain(..count).enumerate() {
+        *output.add(index) = sample;
+    }
+    if !written.is_null() {
+        *written = count;
+    }
+    JENNA_OK
+}
+
+/// Release a resampler
+/// 
+/// # Safety
+/// `resampler` must be NULL or come from `jenna_resampler_new`, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_resampler_free(resampler: *mut JennaResampler) {
+    release(resampler);
+}
+
+/// Wake word engine
+pub struct JennaWakeWord {
+    engine: WakeWord,
+}
+
+/// Collect `count` keyword paths and sensitivities (NULL sensitivities means 0.5 each)
+unsafe fn keyword_args(
+    keyword_paths: *const *const c_char,
+    sensitivities: *const f32,
+    count: usize,
+) -> Result<(Vec<String>, Vec<f32>), String> {
+    let paths = slice(keyword_paths, count, "keyword_paths")?
+        .iter()
+        .map(|&path| req_str(path, "keyword path").map(str::to_string))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sensitivities = if sensitivities.is_null() {
+        vec![0.5; count]
+    } else {
+        slice(sensitivities, count, "sensitivities")?.to_vec()
+    };
+    Ok((paths, sensitivities))
+}
+
+/// Create a Porcupine detector for `count` keyword files
+/// 
+/// `model_path` may be NULL for the default model and `sensitivities` NULL
+/// for 0.5 each.
+/// 
+/// # Safety
+/// Strings must be NUL-terminated; `keyword_paths` (and `sensitivities`, if
+/// not NULL) must hold `count` entries.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_wake_word_new_porcupine(
+    access_key: *const c_char,
+    model_path: *const c_char,
+    keyword_paths: *const *const c_char,
+    sensitivities: *const f32,
+    count: usize,
+) -> *mut JennaWakeWord {
+    into_handle(guarded(|| new_porcupine(access_key, model_path, keyword_paths, sensitivities, count)))
+}
+
+unsafe fn new_porcupine(
+    access_key: *const c_char,
+    model_path: *const c_char,
+    keyword_paths: *const *const c_char,
+    sensitivities: *const f32,
+    count: usize,
+) -> Result<JennaWakeWord, String> {
+    let access_key = req_str(access_key, "access_key")?;
+    let model_path = opt_str(model_path, "model_path")?;
+    let (paths, sensitivities) = keyword_args(keyword_paths, sensitivities, count)?;
+    let engine = WakeWord::porcupine(access_key, paths, &sensitivities, model_path)?;
+    Ok(JennaWakeWord { engine })
+}
+
+/// Create an openWakeWord detector from a model directory and `count` classifier files
+/// 
+/// # Safety
+/// As for `jenna_wake_word_new_porcupine`.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_wake_word_new_openwakeword(
+    model_dir: *const c_char,
+    keyword_paths: *const *const c_char,
+    sensitivities: *const f32,
+    count: usize,
+) -> *mut JennaWakeWord {
+    into_handle(guarded(|| new_openwakeword(model_dir, keyword_paths, sensitivities, count)))
+}
+
+unsafe fn new_openwakeword(
+    model_dir: *const c_char,
+    keyword_paths: *const *const c_char,
+    sensitivities: *const f32,
+    count: usize,
+) -> Result<JennaWakeWord, String> {
+    let model_dir = req_str(model_dir, "model_dir")?;
+    let (paths, sensitivities) = keyword_args(keyword_paths, sensitivities, count)?;
+    let engine = WakeWord::openwakeword(Path::new(model_dir), paths, &sensitivities)?;
+    Ok(JennaWakeWord { engine })
+}
+
+/// Samples per frame expected by `jenna_wake_word_process`, or 0 for NULL
+/// 
+/// # Safety
+/// `detector` must be NULL or come from a `jenna_wake_word_new_*` function.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_wake_word_frame_length(detector: *const JennaWakeWord) -> usize {
+    detector.as_ref().map_or(0, |detector| detector.engine.frame_length())
+}
+
+/// Sample rate expected by `jenna_wake_word_process`, or 0 for NULL
+/// 
+/// # Safety
+/// As for `jenna_wake_word_frame_length`.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_wake_word_sample_rate(detector: *const JennaWakeWord) -> u32 {
+    detector.as_ref().map_or(0, |detector| detector.engine.sample_rate())
+}
+
+/// Process one frame of 16-bit mono audio
+/// 
+/// `keyword` receives the index of the keyword that fired, or -1.
+/// 
+/// # Safety
+/// `detector` must come from a `jenna_wake_word_new_*` function and `frame`
+/// hold `len` samples.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_wake_word_process(
+    detector: *mut JennaWakeWord,
+    frame: *const i16,
+    len: usize,
+    keyword: *mut i32,
+) -> i32 {
+    let detector = match detector.as_mut() {
+        Some(detector) => detector,
+        None => return fail("detector must not be NULL"),
+    };
+    let frame = match slice(frame, len, "frame") {
+        Ok(frame) => frame,
+        Err(err) => return fail(err),
+    };
+    match guarded(|| detector.engine.process(frame)) {
+        Ok(index) => {
+            if !keyword.is_null() {
+                *keyword = index.map_or(-1, |index| index as i32);
+            }
+            JENNA_OK
+        }
+        Err(err) => fail(err),
+    }
+}
+
+/// Release a detector
+/// 
+/// # Safety
+/// `detector` must be NULL or come from a `jenna_wake_word_new_*` function, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_wake_word_free(detector: *mut JennaWakeWord) {
+    release(detector);
+}
+
+/// Speech recognizer
+pub struct JennaRecognizer {
+    engine: Recognizer,
+}
+
+/// Create a recognizer; `engine` is "vosk" or "whisper", `language` may be NULL
+/// 
+/// # Safety
+/// Strings must be NULL or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_recognizer_new(
+    engine: *const c_char,
+    model_path: *const c_char,
+    sample_rate: u32,
+    language: *const c_char,
+) -> *mut JennaRecognizer {
+    into_handle(guarded(|| new_recognizer(engine, model_path, sample_rate, language)))
+}
+
+unsafe fn new_recognizer(
+    engine: *const c_char,
+    model_path: *const c_char,
+    sample_rate: u32,
+    language: *const c_char,
+) -> Result<JennaRecognizer, String> {
+    let engine = req_str(engine, "engine")?;
+    let model_path = req_str(model_path, "model_path")?;
+    let language = opt_str(language, "language")?.map(str::to_string);
+    let engine = Recognizer::new(engine, model_path, sample_rate, language)?;
+    Ok(JennaRecognizer { engine })
+}
+
+/// Store `result` as JSON in `*out`, or NULL when there is none
+unsafe fn write_result(result: Result<Option<RecognitionResult>, String>, out: *mut *mut c_char) -> i32 {
+    match result {
+        Ok(result) => {
+            if !out.is_null() {
+                *out = result.map_or(ptr::null_mut(), |result| into_c_string(result_json(&result)));
+            }
+            JENNA_OK
+        }
+        Err(err) => fail(err),
+    }
+}
+
+/// Feed `len` samples; `*result` receives a JSON result or NULL
+/// 
+/// Results look like `{"text": ..., "is_final": ..., "confidence": ...,
+/// "words": [...]}` and must be released with `jenna_string_free`.
+/// 
+/// # Safety
+/// `recognizer` must come from `jenna_recognizer_new` and `pcm` hold `len` samples.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_recognizer_accept(
+    recognizer: *mut JennaRecognizer,
+    pcm: *const i16,
+    len: usize,
+    result: *mut *mut c_char,
+) -> i32 {
+    let recognizer = match recognizer.as_mut() {
+        Some(recognizer) => recognizer,
+        None => return fail("recognizer must not be NULL"),
+    };
+    let pcm = match slice(pcm, len, "pcm") {
+        Ok(pcm) => pcm,
+        Err(err) => return fail(err),
+    };
+    write_result(guarded(|| recognizer.engine.accept(pcm)), result)
+}
+
+/// End the current utterance; `*result` receives its final JSON result or NULL
+/// 
+/// # Safety
+/// As for `jenna_recognizer_accept`.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_recognizer_finish(recognizer: *mut JennaRecognizer, result: *mut *mut c_char) -> i32 {
+    match recognizer.as_mut() {
+        Some(recognizer) => write_result(guarded(|| recognizer.engine.finish()), result),
+        None => fail("recognizer must not be NULL"),
+    }
+}
+
+/// Discard the utterance in progress
+/// 
+/// # Safety
+/// `recognizer` must be NULL or come from `jenna_recognizer_new`.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_recognizer_reset(recognizer: *mut JennaRecognizer) {
+    if let Some(recognizer) = recognizer.as_mut() {
+        if let Err(err) = guarded(|| {
+            recognizer.engine.reset();
+            Ok(())
+        }) {
+            set_error(err);
+        }
+    }
+}
+
+/// Release a recognizer
+/// 
+/// # Safety
+/// `recognizer` must be NULL or come from `jenna_recognizer_new`, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_recognizer_free(recognizer: *mut JennaRecognizer) {
+    release(recognizer);
+}
+
+/// Piper text-to-speech voice
+pub struct JennaTts {
+    synthesizer: Synthesizer,
+}
+
+/// Load a voice file, or the voice named `voice` from a directory (NULL for "default")
+/// 
+/// # Safety
+/// Strings must be NULL or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_tts_new(model_path: *const c_char, voice: *const c_char) -> *mut JennaTts {
+    into_handle(guarded(|| new_tts(model_path, voice)))
+}
+
+unsafe fn new_tts(model_path: *const c_char, voice: *const c_char) -> Result<JennaTts, String> {
+    let model_path = req_str(model_path, "model_path")?;
+    let voice = opt_str(voice, "voice")?;
+    let synthesizer = Synthesizer::new(Path::new(model_path), voice)?;
+    Ok(JennaTts { synthesizer })
+}
+
+/// Native sample rate of the voice, or 0 for NULL
+/// 
+/// # Safety
+/// `tts` must be NULL or come from `jenna_tts_new`.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_tts_sample_rate(tts: *const JennaTts) -> u32 {
+    tts.as_ref().map_or(0, |tts| tts.synthesizer.sample_rate())
+}
+
+/// Synthesize text or SSML at the voice's native rate
+/// 
+/// `*samples` and `*len` receive 16-bit mono PCM, to be released with
+/// `jenna_samples_free`.
+/// 
+/// # Safety
+/// `tts` must come from `jenna_tts_new`, `text` be NUL-terminated and
+/// `samples` and `len` be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_tts_synthesize(
+    tts: *const JennaTts,
+    text: *const c_char,
+    samples: *mut *mut i16,
+    len: *mut usize,
+) -> i32 {
+    let tts = match tts.as_ref() {
+        Some(tts) => tts,
+        None => return fail("tts must not be NULL"),
+    };
+    if samples.is_null() || len.is_null() {
+        return fail("samples and len must not be NULL");
+    }
+    let text = match req_str(text, "text") {
+        Ok(text) => text,
+        Err(err) => return fail(err),
+    };
+    match guarded(|| tts.synthesizer.synthesize(text)) {
+        Ok(pcm) => {
+            let pcm = pcm.into_boxed_slice();
+            *len = pcm.len();
+            *samples = Box::into_raw(pcm) as *mut i16;
+            JENNA_OK
+        }
+        Err(err) => fail(err),
+    }
+}
+
+/// Release a voice
+/// 
+/// # Safety
+/// `tts` must be NULL or come from `jenna_tts_new`, not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn jenna_tts_free(tts: *mut JennaTts) {
+    release(tts);
+}
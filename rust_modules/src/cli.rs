@@ -0,0 +1,146 @@
+use serde_json::{json, Map, Value};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::config::JennaConfig;
+use crate::logging;
+use crate::panics;
+use crate::pipeline::{self, PipelineEvent};
+
+const USAGE: &str = "\
+Usage: jenna-core [OPTIONS]
+
+Run the capture -> wake word -> speech recognition pipeline and print one
+JSON object per event to stdout. Logs go to stderr.
+
+Options:
+  -c, --config PATH        Load settings from a .toml or .json config file
+  -d, --input-device NAME  Capture device, overriding the config
+      --log-level LEVEL    error, warning, info (default), debug or trace
+      --check              Validate the config and exit
+  -h, --help               Print this help
+  -V, --version            Print the version";
+
+/// Parsed command line
+#[derive(Debug, Default)]
+struct Options {
+    config: Option<PathBuf>,
+    input_device: Option<String>,
+    log_level: Option<String>,
+    check: bool,
+}
+
+enum Command {
+    Run(Options),
+    Help,
+    Version,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // Accept both "--flag value" and "--flag=value"
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = |name: &str| inline.clone().or_else(|| args.next()).ok_or_else(|| format!("{} needs a value", name));
+        match flag.as_str() {
+            "-c" | "--config" => options.config = Some(PathBuf::from(value("--config")?)),
+            "-d" | "--input-device" => options.input_device = Some(value("--input-device")?),
+            "--log-level" => options.log_level = Some(value("--log-level")?),
+            "--check" => options.check = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            _ => return Err(format!("Unknown argument: {}", flag)),
+        }
+    }
+    Ok(Command::Run(options))
+}
+
+/// One line of output for a pipeline event
+fn event_json(event: &PipelineEvent) -> Value {
+    let mut object = Map::new();
+    object.insert("type".to_string(), json!(event.kind));
+    object.insert("timestamp".to_string(), json!(event.timestamp));
+    if let Some(text) = &event.text {
+        object.insert("text".to_string(), json!(text));
+    }
+    if let Some(keyword) = &event.keyword {
+        object.insert("keyword".to_string(), json!(keyword));
+    }
+    if let Some(confidence) = event.confidence {
+        object.insert("confidence".to_string(), json!(confidence));
+    }
+    if let Some(result) = event.result.as_ref().filter(|result| !result.words.is_empty()) {
+        let words: Vec<Value> = result
+            .words
+            .iter()
+            .map(|word| json!({"word": word.word, "start": word.start, "end": word.end, "confidence": word.confidence}))
+            .collect();
+        object.insert("words".to_string(), Value::Array(words));
+    }
+    Value::Object(object)
+}
+
+fn run_pipeline(options: Options) -> Result<(), String> {
+    logging::init_stderr(options.log_level.as_deref().unwrap_or("info"))?;
+    panics::install_hook();
+    let mut config = match &options.config {
+        Some(path) => JennaConfig::from_file(path)?,
+        None => JennaConfig::default(),
+    };
+    if options.input_device.is_some() {
+        config.input_device = options.input_device;
+    }
+    // There is nothing to play without a TTS host
+    config.playback = false;
+    config.check()?;
+    if options.check {
+        eprintln!("Config OK");
+        return Ok(());
+    }
+    
+    let stop = Arc::new(AtomicBool::new(false));
+    let handler_stop = stop.clone();
+    ctrlc::set_handler(move || handler_stop.store(true, Ordering::Relaxed))
+        .map_err(|err| format!("Failed to install the signal handler: {}", err))?;
+    
+    let stdout = std::io::stdout();
+    pipeline::run_headless(config, &stop, |event| {
+        let mut out = stdout.lock();
+        // A closed stdout (e.g. `| head`) ends the run
+        if writeln!(out, "{}", event_json(event)).and_then(|_| out.flush()).is_err() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    })
+}
+
+/// Entry point of the `jenna-core` binary; returns the process exit code
+pub fn run(args: impl IntoIterator<Item = String>) -> i32 {
+    let result = match parse_args(args) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Ok(Command::Version) => {
+            println!("jenna-core {}", env!("CARGO_PKG_VERSION"));
+            return 0;
+        }
+        Ok(Command::Run(options)) => run_pipeline(options),
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return 2;
+        }
+    };
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Error: {}", err);
+            1
+        }
+    }
+}
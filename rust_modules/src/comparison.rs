@@ -0,0 +1,418 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyDict, PyList};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::audio::{read_audio_file_mono, resample_mono};
+use crate::errors::NotInitializedError;
+use crate::intent::tokenize;
+use crate::speech::{SharedEngine, SpeechRecognizer};
+use crate::wake_word::{DetectorState, WakeWordDetector};
+
+/// Sample rate `SttEngine::transcribe` expects
+const RECOGNITION_SAMPLE_RATE: u32 = 16000;
+
+/// Mean, 95th percentile and maximum of per-call latencies, in milliseconds
+fn latency_summary(seconds: &[f64]) -> (f64, f64, f64) {
+    if seconds.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+    let mut sorted: Vec<f64> = seconds.iter().map(|s| s * 1000.0).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+    let rank = (0.95 * (sorted.len() - 1) as f64).round() as usize;
+    (mean, sorted[rank], sorted[sorted.len() - 1])
+}
+
+/// Add latency and throughput figures to an engine's report
+fn add_timing(dict: &PyDict, latencies: &[f64], audio_seconds: f64) -> PyResult<()> {
+    let (mean, p95, max) = latency_summary(latencies);
+    let busy: f64 = latencies.iter().sum();
+    dict.set_item("mean_ms", mean)?;
+    dict.set_item("p95_ms", p95)?;
+    dict.set_item("max_ms", max)?;
+    dict.set_item("realtime_factor", audio_seconds / busy.max(f64::EPSILON))?;
+    Ok(())
+}
+
+fn file_label(path: &str) -> String {
+    Path::new(path).file_name().map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+/// A wake word one engine reported, on the comparison's clock
+#[derive(Clone, Debug)]
+struct Hit {
+    /// Seconds of audio fed to the comparison before the end of the frame that fired
+    time: f64,
+    keyword: String,
+    confidence: Option<f32>,
+    source: Option<String>,
+}
+
+/// One side of a wake word comparison
+struct WakeSide {
+    state: Arc<Mutex<DetectorState>>,
+    frame_length: usize,
+    /// Samples waiting for a full frame
+    pending: Vec<i16>,
+    /// Samples passed to the engine so far
+    consumed: u64,
+    hits: Vec<Hit>,
+    latencies: Vec<f64>,
+}
+
+impl WakeSide {
+    fn new(detector: &WakeWordDetector) -> PyResult<(Self, u32)> {
+        let state = detector.shared_state();
+        let (frame_length, sample_rate) = state.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+        let side = WakeSide { state, frame_length, pending: Vec::new(), consumed: 0, hits: Vec::new(), latencies: Vec::new() };
+        Ok((side, sample_rate))
+    }
+    
+    /// Run every complete frame in `samples` (plus what was left over) through the engine
+    fn feed(&mut self, samples: &[i16], sample_rate: u32, source: Option<&str>) -> Result<(), String> {
+        self.pending.extend_from_slice(samples);
+        let mut offset = 0;
+        while self.pending.len() - offset >= self.frame_length {
+            let frame = &self.pending[offset..offset + self.frame_length];
+            let started = Instant::now();
+            let detection = self.state.lock().unwrap().process_frame(frame)?;
+            self.latencies.push(started.elapsed().as_secs_f64());
+            offset += self.frame_length;
+            self.consumed += self.frame_length as u64;
+            if let Some(detection) = detection {
+                self.hits.push(Hit {
+                    time: self.consumed as f64 / sample_rate as f64,
+                    keyword: detection.keyword,
+                    confidence: detection.confidence,
+                    source: source.map(str::to_string),
+                });
+            }
+        }
+        self.pending.drain(..offset);
+        Ok(())
+    }
+    
+    fn clear(&mut self) {
+        self.pending.clear();
+        self.consumed = 0;
+        self.hits.clear();
+        self.latencies.clear();
+    }
+    
+    fn to_dict(&self, py: Python, sample_rate: u32) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("detections", self.hits.len())?;
+        dict.set_item("frames", self.latencies.len())?;
+        add_timing(dict, &self.latencies, self.consumed as f64 / sample_rate as f64)?;
+        Ok(dict.into())
+    }
+}
+
+fn hit_dict(py: Python, hit: &Hit) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("time", hit.time)?;
+    dict.set_item("keyword", &hit.keyword)?;
+    dict.set_item("confidence", hit.confidence)?;
+    dict.set_item("file", &hit.source)?;
+    Ok(dict.into())
+}
+
+/// Pair detections of the same keyword that lie within `tolerance` seconds of each other
+/// 
+/// Returns the number of pairs and the indices of the unpaired hits on each
+/// side. Hits are paired greedily in time order, which is exact as long as
+/// one engine doesn't fire twice within the tolerance.
+fn match_hits(a: &[Hit], b: &[Hit], tolerance: f64) -> (usize, Vec<usize>, Vec<usize>) {
+    let mut paired_b = vec![false; b.len()];
+    let mut only_a = Vec::new();
+    let mut agreed = 0;
+    for (index, hit) in a.iter().enumerate() {
+        let partner = (0..b.len()).find(|&other| {
+            !paired_b[other] && b[other].keyword == hit.keyword && (b[other].time - hit.time).abs() <= tolerance
+        });
+        match partner {
+            Some(other) => {
+                paired_b[other] = true;
+                agreed += 1;
+            }
+            None => only_a.push(index),
+        }
+    }
+    let only_b = (0..b.len()).filter(|&other| !paired_b[other]).collect();
+    (agreed, only_a, only_b)
+}
+
+/// Run two wake word detectors side by side on the same audio
+/// 
+/// Both detectors must be initialized for the same sample rate; each is fed
+/// in its own frame length, and detections are placed on a shared clock
+/// (seconds of audio passed to the comparison) so they can be paired.
+/// Frames go through the detectors' engines and count in their statistics,
+/// so don't compare detectors that are listening or part of a running
+/// pipeline.
+#[pyclass]
+pub struct WakeWordComparison {
+    a: WakeSide,
+    b: WakeSide,
+    sample_rate: u32,
+    /// Detections this far apart or closer, in seconds, count as the same wake word
+    tolerance: f64,
+}
+
+#[pymethods]
+impl WakeWordComparison {
+    #[new]
+    #[pyo3(signature = (a, b, tolerance=0.5))]
+    fn new(a: PyRef<WakeWordDetector>, b: PyRef<WakeWordDetector>, tolerance: f64) -> PyResult<Self> {
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            return Err(PyValueError::new_err("Tolerance must not be negative"));
+        }
+        let (a, rate_a) = WakeSide::new(&a)?;
+        let (b, rate_b) = WakeSide::new(&b)?;
+        if rate_a != rate_b {
+            return Err(PyValueError::new_err(format!(
+                "Detectors run at different sample rates ({} Hz and {} Hz)",
+                rate_a, rate_b
+            )));
+        }
+        Ok(WakeWordComparison { a, b, sample_rate: rate_a, tolerance })
+    }
+    
+    /// Sample rate both detectors expect
+    #[getter]
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    
+    /// Feed a block of live or pre-recorded audio, of any length, to both detectors
+    fn process(&mut self, py: Python, samples: Vec<i16>) -> PyResult<()> {
+        let rate = self.sample_rate;
+        let (a, b) = (&mut self.a, &mut self.b);
+        py.allow_threads(|| {
+            a.feed(&samples, rate, None)?;
+            b.feed(&samples, rate, None)
+        })
+        .map_err(PyValueError::new_err)
+    }
+    
+    /// Feed a WAV or FLAC file to both detectors, resampled to their rate
+    /// 
+    /// Files play back to back on the comparison's clock; divergent
+    /// detections name the file they came from.
+    fn process_file(&mut self, py: Python, path: &str) -> PyResult<()> {
+        let rate = self.sample_rate;
+        let source = file_label(path);
+        let (a, b) = (&mut self.a, &mut self.b);
+        py.allow_threads(|| -> PyResult<()> {
+            let (audio, file_rate) = read_audio_file_mono(Path::new(path))?;
+            let audio = resample_mono(&audio, file_rate, rate)?;
+            let samples: Vec<i16> = audio.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect();
+            a.feed(&samples, rate, Some(&source)).map_err(PyValueError::new_err)?;
+            b.feed(&samples, rate, Some(&source)).map_err(PyValueError::new_err)
+        })
+    }
+    
+    /// Agreement between the detectors and how each performed
+    /// 
+    /// Returns a dict with "agreed" (detections both engines made),
+    /// "only_a" and "only_b" (detections the other engine missed, each a
+    /// dict with "time", "keyword", "confidence" and "file"), "agreement"
+    /// (agreed over all distinct detections, 1.0 when neither fired) and "a"
+    /// and "b" with each engine's "detections", "frames", "mean_ms",
+    /// "p95_ms" and "max_ms" per frame, and "realtime_factor".
+    fn report(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let (agreed, only_a, only_b) = match_hits(&self.a.hits, &self.b.hits, self.tolerance);
+        let divergent = |hits: &[Hit], indices: &[usize]| -> PyResult<&PyList> {
+            let list = PyList::empty(py);
+            for &index in indices {
+                list.append(hit_dict(py, &hits[index])?)?;
+            }
+            Ok(list)
+        };
+        
+        let distinct = agreed + only_a.len() + only_b.len();
+        let report = PyDict::new(py);
+        report.set_item("seconds", self.a.consumed.max(self.b.consumed) as f64 / self.sample_rate as f64)?;
+        report.set_item("agreed", agreed)?;
+        report.set_item("agreement", if distinct == 0 { 1.0 } else { agreed as f64 / distinct as f64 })?;
+        report.set_item("only_a", divergent(&self.a.hits, &only_a)?)?;
+        report.set_item("only_b", divergent(&self.b.hits, &only_b)?)?;
+        report.set_item("a", self.a.to_dict(py, self.sample_rate)?)?;
+        report.set_item("b", self.b.to_dict(py, self.sample_rate)?)?;
+        Ok(report.into())
+    }
+    
+    /// Forget everything fed so far and start a fresh comparison
+    fn reset(&mut self) {
+        self.a.clear();
+        self.b.clear();
+    }
+}
+
+/// Word-level edit distance between two token lists
+fn word_distance(a: &[String], b: &[String]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, word_a) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, word_b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(word_a != word_b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Both engines' transcripts of one clip
+struct Transcription {
+    source: Option<String>,
+    audio_seconds: f64,
+    text_a: String,
+    text_b: String,
+    seconds_a: f64,
+    seconds_b: f64,
+    /// Words engine "a" heard, for the difference rate
+    words_a: usize,
+    distance: usize,
+}
+
+impl Transcription {
+    fn agrees(&self) -> bool {
+        self.distance == 0
+    }
+    
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("file", &self.source)?;
+        dict.set_item("seconds", self.audio_seconds)?;
+        dict.set_item("a", &self.text_a)?;
+        dict.set_item("b", &self.text_b)?;
+        dict.set_item("a_ms", self.seconds_a * 1000.0)?;
+        dict.set_item("b_ms", self.seconds_b * 1000.0)?;
+        dict.set_item("agree", self.agrees())?;
+        dict.set_item("word_distance", self.distance)?;
+        Ok(dict.into())
+    }
+}
+
+fn timed_transcribe(engine: &SharedEngine, audio: &[f32]) -> Result<(String, f64), String> {
+    let guard = engine.lock().unwrap();
+    let engine = guard.as_deref().ok_or_else(|| "Speech recognizer not initialized".to_string())?;
+    let started = Instant::now();
+    let result = engine.transcribe(audio)?;
+    Ok((result.text, started.elapsed().as_secs_f64()))
+}
+
+/// Transcribe the same clips with two speech recognizers and compare them
+/// 
+/// Clips are transcribed in full by each engine in turn, so latencies are
+/// for whole utterances rather than streaming. Transcripts are compared as
+/// words, ignoring case and punctuation.
+#[pyclass]
+pub struct RecognizerComparison {
+    a: SharedEngine,
+    b: SharedEngine,
+    name_a: &'static str,
+    name_b: &'static str,
+    items: Vec<Transcription>,
+}
+
+impl RecognizerComparison {
+    fn compare(&mut self, py: Python, audio: Vec<f32>, sample_rate: u32, source: Option<String>) -> PyResult<Py<PyDict>> {
+        let (a, b) = (&self.a, &self.b);
+        let item = py
+            .allow_threads(|| -> PyResult<Transcription> {
+                let audio = resample_mono(&audio, sample_rate, RECOGNITION_SAMPLE_RATE)?;
+                let (text_a, seconds_a) = timed_transcribe(a, &audio).map_err(PyValueError::new_err)?;
+                let (text_b, seconds_b) = timed_transcribe(b, &audio).map_err(PyValueError::new_err)?;
+                let (words_a, words_b) = (tokenize(&text_a), tokenize(&text_b));
+                Ok(Transcription {
+                    source,
+                    audio_seconds: audio.len() as f64 / RECOGNITION_SAMPLE_RATE as f64,
+                    distance: word_distance(&words_a, &words_b),
+                    words_a: words_a.len(),
+                    text_a,
+                    text_b,
+                    seconds_a,
+                    seconds_b,
+                })
+            })?;
+        let dict = item.to_dict(py);
+        self.items.push(item);
+        dict
+    }
+}
+
+#[pymethods]
+impl RecognizerComparison {
+    #[new]
+    fn new(a: PyRef<SpeechRecognizer>, b: PyRef<SpeechRecognizer>) -> PyResult<Self> {
+        let (a, _) = a.shared_engine()?;
+        let (b, _) = b.shared_engine()?;
+        let name = |engine: &SharedEngine| engine.lock().unwrap().as_deref().map_or("unknown", |engine| engine.name());
+        let (name_a, name_b) = (name(&a), name(&b));
+        Ok(RecognizerComparison { a, b, name_a, name_b, items: Vec::new() })
+    }
+    
+    /// Transcribe a clip of 16-bit audio with both engines
+    /// 
+    /// Returns the clip's entry as `report` lists it.
+    #[pyo3(signature = (samples, sample_rate=16000))]
+    fn process(&mut self, py: Python, samples: Vec<i16>, sample_rate: u32) -> PyResult<Py<PyDict>> {
+        if sample_rate == 0 {
+            return Err(PyValueError::new_err("Sample rate must be positive"));
+        }
+        let audio = samples.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
+        self.compare(py, audio, sample_rate, None)
+    }
+    
+    /// Transcribe a WAV or FLAC file with both engines
+    fn process_file(&mut self, py: Python, path: &str) -> PyResult<Py<PyDict>> {
+        let (audio, sample_rate) = py.allow_threads(|| read_audio_file_mono(Path::new(path)))?;
+        self.compare(py, audio, sample_rate, Some(file_label(path)))
+    }
+    
+    /// Agreement between the recognizers and how each performed
+    /// 
+    /// Returns a dict with "clips", "agreement" (fraction of clips with the
+    /// same words), "word_difference_rate" (word edits from "a" to "b" per
+    /// word of "a"), "divergent" (the entries of clips that differ, each
+    /// with "file", "seconds", "a", "b", "a_ms", "b_ms", "agree" and
+    /// "word_distance") and "a" and "b" with each engine's "engine",
+    /// "mean_ms", "p95_ms" and "max_ms" per clip, and "realtime_factor".
+    fn report(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let divergent = PyList::empty(py);
+        for item in self.items.iter().filter(|item| !item.agrees()) {
+            divergent.append(item.to_dict(py)?)?;
+        }
+        let agreed = self.items.iter().filter(|item| item.agrees()).count();
+        let distance: usize = self.items.iter().map(|item| item.distance).sum();
+        let words: usize = self.items.iter().map(|item| item.words_a).sum();
+        let audio_seconds: f64 = self.items.iter().map(|item| item.audio_seconds).sum();
+        
+        let engine = |name: &str, latencies: Vec<f64>| -> PyResult<Py<PyDict>> {
+            let dict = PyDict::new(py);
+            dict.set_item("engine", name)?;
+            add_timing(dict, &latencies, audio_seconds)?;
+            Ok(dict.into())
+        };
+        
+        let report = PyDict::new(py);
+        report.set_item("clips", self.items.len())?;
+        report.set_item("agreement", if self.items.is_empty() { 1.0 } else { agreed as f64 / self.items.len() as f64 })?;
+        report.set_item("word_difference_rate", distance as f64 / words.max(1) as f64)?;
+        report.set_item("divergent", divergent)?;
+        report.set_item("a", engine(self.name_a, self.items.iter().map(|item| item.seconds_a).collect())?)?;
+        report.set_item("b", engine(self.name_b, self.items.iter().map(|item| item.seconds_b).collect())?)?;
+        Ok(report.into())
+    }
+    
+    /// Forget the clips compared so far
+    fn reset(&mut self) {
+        self.items.clear();
+    }
+}
@@ -0,0 +1,397 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use jenna_core::loudness::EBU_R128_TARGET;
+
+use crate::accelerator;
+use crate::audio::StallPolicy;
+use crate::content_filter::FilterMode;
+use crate::dynamics::check_loudness_target;
+use crate::errors::JennaError;
+use crate::graph::{self, NodeSpec, ProcessingGraph};
+use crate::priority::{self, ThreadPolicy};
+use crate::speech::Acceptance;
+
+/// Serialization format, chosen by file extension (".toml", otherwise JSON)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ConfigFormat {
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Json,
+        }
+    }
+}
+
+/// Assistant configuration shared by the component constructors
+/// 
+/// Covers the audio devices, wake word engine, VAD and endpointing, speech
+/// recognition and text-to-speech. Every field is an attribute and has a
+/// default, so a config file only needs the settings that differ. Pass it
+/// to `WakeWordDetector.from_config`, `SpeechRecognizer.from_config`,
+/// `TextToSpeech.from_config` and `VoicePipeline(..., config=config)`.
+#[pyclass]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct JennaConfig {
+    /// Room or zone the pipeline serves, tagged on its events
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    /// Capture device name; None uses the default device
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_device: Option<String>,
+    /// Playback device name; None uses the default device
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output_device: Option<String>,
+    /// Whether the pipeline opens an output device for TTS playback
+    #[pyo3(get, set)]
+    pub playback: bool,
+    /// Seconds without capture callbacks before the stream counts as stalled (0 disables)
+    #[pyo3(get, set)]
+    pub stall_timeout: f32,
+    /// Reopen a stalled capture stream instead of only posting "audio.stalled"
+    #[pyo3(get, set)]
+    pub restart_on_stall: bool,
+    /// Remove room reverberation from the captured audio, for far-field microphones (CPU heavy)
+    #[pyo3(get, set)]
+    pub dereverberation: bool,
+    /// Processing graph run on the captured audio before detection, as `AudioGraph` nodes
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capture_graph: Vec<NodeSpec>,
+    
+    /// "porcupine" or "openwakeword"
+    #[pyo3(get, set)]
+    pub wake_engine: String,
+    /// Porcupine model file or openWakeWord model directory
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wake_model_path: Option<String>,
+    /// Keyword files (.ppn or .onnx); ignored when `keywords` is set
+    #[pyo3(get, set)]
+    pub keyword_paths: Vec<String>,
+    /// Built-in Porcupine keyword names
+    #[pyo3(get, set)]
+    pub keywords: Vec<String>,
+    /// Per-keyword sensitivities; empty uses `sensitivity` for every keyword
+    #[pyo3(get, set)]
+    pub sensitivities: Vec<f32>,
+    #[pyo3(get, set)]
+    pub sensitivity: f32,
+    /// Porcupine access key; None falls back to the PV_ACCESS_KEY environment variable
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_key: Option<String>,
+    
+    #[pyo3(get, set)]
+    pub vad_threshold_db: f32,
+    #[pyo3(get, set)]
+    pub silence_timeout: f32,
+    #[pyo3(get, set)]
+    pub no_speech_timeout: f32,
+    #[pyo3(get, set)]
+    pub max_utterance: f32,
+    
+    /// "vosk" or "whisper"
+    #[pyo3(get, set)]
+    pub stt_engine: String,
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_model_path: Option<String>,
+    #[pyo3(get, set)]
+    pub stt_sample_rate: u32,
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_language: Option<String>,
+    /// Content filter mode for transcripts ("partial", "full", "replace" or
+    /// "remove"); None disables filtering
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_filter: Option<String>,
+    /// Word list added to the built-in one, one word or phrase per line
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_filter_words: Option<String>,
+    /// Punctuate and recase final transcripts
+    #[pyo3(get, set)]
+    pub format_transcripts: bool,
+    /// Punctuation model directory for the transcript formatter; implies
+    /// `format_transcripts`
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub punctuation_model: Option<String>,
+    /// Final transcripts below this confidence are rejected as "unknown"
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_min_confidence: Option<f32>,
+    /// Final transcripts with fewer words are rejected as "unknown"
+    #[pyo3(get, set)]
+    pub stt_min_words: usize,
+    /// Final transcripts whose audio was more than this share non-speech are rejected as "unknown"
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stt_max_no_speech: Option<f32>,
+    
+    /// Voice file or directory of voices
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts_model_path: Option<String>,
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts_voice: Option<String>,
+    /// Output rate; None keeps the voice's native rate
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts_sample_rate: Option<u32>,
+    /// Loudness pipeline playback is normalized to, in LUFS (0 disables)
+    #[pyo3(get, set)]
+    pub playback_loudness: f32,
+    /// Read Whisper and Piper model files through a memory map
+    #[pyo3(get, set)]
+    pub memory_map_models: bool,
+    /// Where Whisper runs: "auto", "cpu", "cuda", "metal", "opencl" or
+    /// "directml", falling back to the CPU; Piper always runs on the CPU
+    #[pyo3(get, set)]
+    pub accelerator: String,
+    /// Priority of pipeline threads by stage ("capture", "playback",
+    /// "sound_events"): "normal", "high" or "realtime"
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub thread_priorities: HashMap<String, String>,
+    /// CPUs each pipeline thread is pinned to, by stage (Linux only)
+    #[pyo3(get, set)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub thread_cpus: HashMap<String, Vec<usize>>,
+}
+
+impl Default for JennaConfig {
+    fn default() -> Self {
+        JennaConfig {
+            zone: None,
+            input_device: None,
+            output_device: None,
+            playback: true,
+            stall_timeout: 2.0,
+            restart_on_stall: true,
+            dereverberation: false,
+            capture_graph: Vec::new(),
+            wake_engine: "porcupine".to_string(),
+            wake_model_path: None,
+            keyword_paths: Vec::new(),
+            keywords: Vec::new(),
+            sensitivities: Vec::new(),
+            sensitivity: 0.5,
+            access_key: None,
+            vad_threshold_db: 12.0,
+            silence_timeout: 0.8,
+            no_speech_timeout: 5.0,
+            max_utterance: 15.0,
+            stt_engine: "vosk".to_string(),
+            stt_model_path: None,
+            stt_sample_rate: 16000,
+            stt_language: None,
+            content_filter: None,
+            content_filter_words: None,
+            format_transcripts: false,
+            punctuation_model: None,
+            stt_min_confidence: None,
+            stt_min_words: 0,
+            stt_max_no_speech: None,
+            tts_model_path: None,
+            tts_voice: None,
+            tts_sample_rate: None,
+            playback_loudness: EBU_R128_TARGET,
+            memory_map_models: false,
+            accelerator: "auto".to_string(),
+            thread_priorities: HashMap::new(),
+            thread_cpus: HashMap::new(),
+        }
+    }
+}
+
+/// Check endpointing timeouts, in seconds: each must be finite and positive
+pub fn check_timeouts(timeouts: &[f32]) -> Result<(), String> {
+    if timeouts.iter().any(|&t| !t.is_finite() || t <= 0.0) {
+        return Err("Timeouts must be finite and positive".to_string());
+    }
+    Ok(())
+}
+
+impl JennaConfig {
+    /// Check the settings without touching any files or devices
+    pub fn check(&self) -> Result<(), String> {
+        if !matches!(self.wake_engine.to_ascii_lowercase().as_str(), "porcupine" | "openwakeword" | "oww" | "onnx") {
+            return Err(format!("Unknown wake word engine: {}", self.wake_engine));
+        }
+        if !matches!(self.stt_engine.to_ascii_lowercase().as_str(), "vosk" | "whisper") {
+            return Err(format!("Unknown speech engine '{}'; expected 'vosk' or 'whisper'", self.stt_engine));
+        }
+        if !self.sensitivities.is_empty() {
+            let count = if self.keywords.is_empty() { self.keyword_paths.len() } else { self.keywords.len() };
+            if self.sensitivities.len() != count {
+                return Err(format!("Got {} sensitivities for {} keywords", self.sensitivities.len(), count));
+            }
+        }
+        if self.sensitivities.iter().chain(std::iter::once(&self.sensitivity)).any(|s| !(0.0..=1.0).contains(s)) {
+            return Err("Sensitivities must be between 0.0 and 1.0".to_string());
+        }
+        check_timeouts(&[self.silence_timeout, self.no_speech_timeout, self.max_utterance])?;
+        if let Some(mode) = &self.content_filter {
+            FilterMode::from_name(mode)?;
+        }
+        Acceptance::new(self.stt_min_confidence, self.stt_min_words, self.stt_max_no_speech)?;
+        accelerator::check_preference(&self.accelerator)?;
+        StallPolicy::new(self.stall_timeout, self.restart_on_stall)?;
+        if self.stt_sample_rate == 0 || self.tts_sample_rate == Some(0) {
+            return Err("Sample rates must be positive".to_string());
+        }
+        if self.playback_loudness != 0.0 {
+            check_loudness_target(self.playback_loudness)?;
+        }
+        if !self.capture_graph.is_empty() {
+            ProcessingGraph::new(self.capture_graph.clone(), self.stt_sample_rate)?;
+        }
+        self.thread_policies()?;
+        Ok(())
+    }
+    
+    /// Scheduling policy of each pipeline stage named in `thread_priorities` or `thread_cpus`
+    pub fn thread_policies(&self) -> Result<HashMap<String, ThreadPolicy>, String> {
+        let mut policies = HashMap::new();
+        for stage in self.thread_priorities.keys().chain(self.thread_cpus.keys()) {
+            priority::check_stage(stage)?;
+            let level = self.thread_priorities.get(stage).map_or("normal", String::as_str);
+            let cpus = self.thread_cpus.get(stage).cloned().unwrap_or_default();
+            policies.insert(stage.clone(), ThreadPolicy::new(level, cpus)?);
+        }
+        Ok(policies)
+    }
+    
+    fn parse(data: &str, format: ConfigFormat) -> Result<Self, String> {
+        let config: JennaConfig = match format {
+            ConfigFormat::Toml => toml::from_str(data).map_err(|err| err.to_string())?,
+            ConfigFormat::Json => serde_json::from_str(data).map_err(|err| err.to_string())?,
+        };
+        config.check()?;
+        Ok(config)
+    }
+    
+    /// Read and validate a `.toml` or `.json` file
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        JennaConfig::parse(&data, ConfigFormat::for_path(path)).map_err(|err| format!("Invalid config {}: {}", path.display(), err))
+    }
+    
+    fn render(&self, format: ConfigFormat) -> Result<String, String> {
+        match format {
+            ConfigFormat::Toml => toml::to_string_pretty(self).map_err(|err| err.to_string()),
+            ConfigFormat::Json => serde_json::to_string_pretty(self).map_err(|err| err.to_string()),
+        }
+    }
+}
+
+#[pymethods]
+impl JennaConfig {
+    /// Create a configuration with default settings
+    #[new]
+    fn new() -> Self {
+        JennaConfig::default()
+    }
+    
+    /// Load a configuration from a `.toml` or `.json` file
+    /// 
+    /// Missing settings take their defaults. Raises `JennaError` if the file
+    /// can't be read and `ValueError` if it is malformed or invalid.
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        let path = Path::new(path);
+        let data = fs::read_to_string(path)
+            .map_err(|err| JennaError::new_err(format!("Failed to read {}: {}", path.display(), err)))?;
+        JennaConfig::parse(&data, ConfigFormat::for_path(path))
+            .map_err(|err| PyValueError::new_err(format!("Invalid config {}: {}", path.display(), err)))
+    }
+    
+    /// Save the configuration as TOML or JSON, chosen by the file extension
+    fn save(&self, path: &str) -> PyResult<()> {
+        let path = Path::new(path);
+        let data = self.render(ConfigFormat::for_path(path)).map_err(PyValueError::new_err)?;
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|err| JennaError::new_err(err.to_string()))?;
+        }
+        fs::write(path, data).map_err(|err| JennaError::new_err(format!("Failed to write {}: {}", path.display(), err)))
+    }
+    
+    /// Parse a configuration from a TOML string
+    #[staticmethod]
+    fn from_toml(data: &str) -> PyResult<Self> {
+        JennaConfig::parse(data, ConfigFormat::Toml).map_err(PyValueError::new_err)
+    }
+    
+    /// Parse a configuration from a JSON string
+    #[staticmethod]
+    fn from_json(data: &str) -> PyResult<Self> {
+        JennaConfig::parse(data, ConfigFormat::Json).map_err(PyValueError::new_err)
+    }
+    
+    /// Serialize the configuration as TOML
+    fn to_toml(&self) -> PyResult<String> {
+        self.render(ConfigFormat::Toml).map_err(PyValueError::new_err)
+    }
+    
+    /// Serialize the configuration as JSON
+    fn to_json(&self) -> PyResult<String> {
+        self.render(ConfigFormat::Json).map_err(PyValueError::new_err)
+    }
+    
+    /// Build a configuration from a dict with the same keys as the attributes
+    #[staticmethod]
+    fn from_dict(py: Python, data: &PyDict) -> PyResult<Self> {
+        let json: String = py.import("json")?.call_method1("dumps", (data,))?.extract()?;
+        JennaConfig::parse(&json, ConfigFormat::Json).map_err(PyValueError::new_err)
+    }
+    
+    /// Get the configuration as a dict (unset optional settings are omitted)
+    fn to_dict(&self, py: Python) -> PyResult<PyObject> {
+        let json = self.render(ConfigFormat::Json).map_err(PyValueError::new_err)?;
+        Ok(py.import("json")?.call_method1("loads", (json,))?.into())
+    }
+    
+    /// Capture processing graph nodes as a list of dicts (see `AudioGraph`)
+    #[getter]
+    fn capture_graph(&self, py: Python) -> PyResult<PyObject> {
+        graph::specs_to_py(py, &self.capture_graph)
+    }
+    
+    #[setter]
+    fn set_capture_graph(&mut self, py: Python, nodes: &PyAny) -> PyResult<()> {
+        self.capture_graph = graph::from_py(py, nodes)?;
+        Ok(())
+    }
+    
+    /// Raise `ValueError` if any setting is out of range
+    fn validate(&self) -> PyResult<()> {
+        self.check().map_err(PyValueError::new_err)
+    }
+    
+    fn copy(&self) -> Self {
+        self.clone()
+    }
+    
+    fn __repr__(&self) -> String {
+        format!(
+            "JennaConfig(wake_engine={:?}, stt_engine={:?}, stt_model_path={:?}, tts_model_path={:?})",
+            self.wake_engine, self.stt_engine, self.stt_model_path, self.tts_model_path
+        )
+    }
+}
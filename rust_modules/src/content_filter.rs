@@ -0,0 +1,361 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::speech::{RecognitionResult, WordInfo};
+
+/// Built-in English list; a trailing `*` matches any word starting with the rest
+const DEFAULT_WORDS: &[&str] = &[
+    "fuck*", "motherfuck*", "shit*", "bullshit", "bitch*", "bastard*", "asshole*", "arsehole*", "dick", "dickhead*",
+    "cunt*", "cock", "cocksucker*", "pussy", "twat*", "wanker*", "prick", "slut*", "whore*", "damn", "goddamn*",
+    "piss", "pissed", "bollocks", "jerk off", "son of a bitch",
+];
+
+/// Replacement used by `FilterMode::Replace` unless configured otherwise
+const DEFAULT_REPLACEMENT: &str = "[censored]";
+
+/// How a matched word or phrase is rewritten
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FilterMode {
+    /// Keep the first letter of each word: "f***"
+    Partial,
+    /// Replace every letter: "****"
+    Full,
+    /// Replace the whole phrase with the replacement text
+    Replace,
+    /// Drop the phrase
+    Remove,
+}
+
+impl FilterMode {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "partial" | "mask" => Ok(FilterMode::Partial),
+            "full" => Ok(FilterMode::Full),
+            "replace" => Ok(FilterMode::Replace),
+            "remove" => Ok(FilterMode::Remove),
+            other => Err(format!(
+                "Unknown filter mode '{}'; expected 'partial', 'full', 'replace' or 'remove'",
+                other
+            )),
+        }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterMode::Partial => "partial",
+            FilterMode::Full => "full",
+            FilterMode::Replace => "replace",
+            FilterMode::Remove => "remove",
+        }
+    }
+}
+
+/// A listed word or phrase
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    words: Vec<String>,
+    /// The last word matches as a prefix
+    prefix: bool,
+}
+
+impl Entry {
+    fn parse(phrase: &str) -> Option<Self> {
+        let phrase = phrase.trim().to_lowercase();
+        let (phrase, prefix) = match phrase.strip_suffix('*') {
+            Some(stem) => (stem.to_string(), true),
+            None => (phrase, false),
+        };
+        let words: Vec<String> = phrase.split_whitespace().map(core_word).filter(|w| !w.is_empty()).collect();
+        (!words.is_empty()).then(|| Entry { words, prefix })
+    }
+    
+    /// Whether the entry matches the words starting at `cores[0]`
+    fn matches(&self, cores: &[String], allowed: &HashSet<String>) -> bool {
+        if cores.len() < self.words.len() {
+            return false;
+        }
+        let last = self.words.len() - 1;
+        self.words.iter().zip(cores).enumerate().all(|(i, (word, core))| {
+            if i == last && self.prefix {
+                core.starts_with(word.as_str()) && !allowed.contains(core)
+            } else {
+                core == word
+            }
+        })
+    }
+}
+
+/// Lowercased word with surrounding punctuation removed
+fn core_word(token: &str) -> String {
+    token.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+/// Split a token into leading punctuation, the word and trailing punctuation
+fn split_token(token: &str) -> (&str, &str, &str) {
+    let start = token.find(|c: char| c.is_alphanumeric()).unwrap_or(token.len());
+    let end = token.rfind(|c: char| c.is_alphanumeric()).map_or(start, |i| i + token[i..].chars().next().unwrap().len_utf8());
+    (&token[..start], &token[start..end], &token[end..])
+}
+
+fn mask(word: &str, keep_first: bool) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, c)| if (keep_first && i == 0) || !c.is_alphanumeric() { c } else { '*' })
+        .collect()
+}
+
+/// Word list and masking rules for transcripts
+#[derive(Clone, Debug)]
+pub struct WordFilter {
+    entries: Vec<Entry>,
+    allowed: HashSet<String>,
+    mode: FilterMode,
+    replacement: String,
+}
+
+impl WordFilter {
+    pub fn new(mode: FilterMode, defaults: bool) -> Self {
+        let mut filter = WordFilter {
+            entries: Vec::new(),
+            allowed: HashSet::new(),
+            mode,
+            replacement: DEFAULT_REPLACEMENT.to_string(),
+        };
+        if defaults {
+            filter.add_words(DEFAULT_WORDS.iter().copied());
+        }
+        filter
+    }
+    
+    pub fn add_words<'a>(&mut self, phrases: impl IntoIterator<Item = &'a str>) -> usize {
+        let before = self.entries.len();
+        for entry in phrases.into_iter().filter_map(Entry::parse) {
+            if !self.entries.contains(&entry) {
+                self.entries.push(entry);
+            }
+        }
+        // Longest phrases first, so "son of a bitch" wins over "bitch"
+        self.entries.sort_by(|a, b| b.words.len().cmp(&a.words.len()));
+        self.entries.len() - before
+    }
+    
+    pub fn remove_words<'a>(&mut self, phrases: impl IntoIterator<Item = &'a str>) {
+        let removed: Vec<Entry> = phrases.into_iter().filter_map(Entry::parse).collect();
+        self.entries.retain(|entry| !removed.contains(entry));
+    }
+    
+    /// Load a word list: one word or phrase per line, `#` starts a comment
+    pub fn load(&mut self, path: &Path) -> Result<usize, String> {
+        let contents = fs::read_to_string(path).map_err(|err| format!("Cannot read word list {}: {}", path.display(), err))?;
+        Ok(self.add_words(contents.lines().map(|line| line.split('#').next().unwrap_or(""))))
+    }
+    
+    /// Matched spans as `(start, end)` word indices
+    fn spans(&self, cores: &[String]) -> Vec<(usize, usize)> {
+        let mut spans = Vec::new();
+        let mut i = 0;
+        while i < cores.len() {
+            match self.entries.iter().find(|entry| entry.matches(&cores[i..], &self.allowed)) {
+                Some(entry) => {
+                    spans.push((i, i + entry.words.len()));
+                    i += entry.words.len();
+                }
+                None => i += 1,
+            }
+        }
+        spans
+    }
+    
+    /// Whether any listed word or phrase appears in the text
+    pub fn contains(&self, text: &str) -> bool {
+        let cores: Vec<String> = text.split_whitespace().map(core_word).collect();
+        !self.spans(&cores).is_empty()
+    }
+    
+    /// Rewrite whitespace-separated tokens, keeping their punctuation
+    /// 
+    /// Returns None when nothing matched. `merge` is called with the span
+    /// of a phrase collapsed into one token in `Replace` mode, and of a
+    /// phrase dropped in `Remove` mode.
+    fn rewrite(&self, tokens: &[String], mut merge: impl FnMut(usize, usize)) -> Option<Vec<String>> {
+        let cores: Vec<String> = tokens.iter().map(|t| core_word(t)).collect();
+        let spans = self.spans(&cores);
+        if spans.is_empty() {
+            return None;
+        }
+        
+        let mut output = Vec::with_capacity(tokens.len());
+        let mut next = 0;
+        for (start, end) in spans {
+            output.extend_from_slice(&tokens[next..start]);
+            match self.mode {
+                FilterMode::Partial | FilterMode::Full => {
+                    for token in &tokens[start..end] {
+                        let (lead, word, trail) = split_token(token);
+                        output.push(format!("{}{}{}", lead, mask(word, self.mode == FilterMode::Partial), trail));
+                    }
+                }
+                FilterMode::Replace => {
+                    let (lead, _, _) = split_token(&tokens[start]);
+                    let (_, _, trail) = split_token(&tokens[end - 1]);
+                    output.push(format!("{}{}{}", lead, self.replacement, trail));
+                    merge(start, end);
+                }
+                FilterMode::Remove => {
+                    // Keep sentence punctuation that followed the phrase
+                    let (_, _, trail) = split_token(&tokens[end - 1]);
+                    if let Some(previous) = output.last_mut().filter(|_| !trail.is_empty()) {
+                        previous.push_str(trail);
+                    }
+                    merge(start, end);
+                }
+            }
+            next = end;
+        }
+        output.extend_from_slice(&tokens[next..]);
+        Some(output)
+    }
+    
+    /// Filter free text
+    pub fn apply(&self, text: &str) -> String {
+        let tokens: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+        match self.rewrite(&tokens, |_, _| {}) {
+            Some(tokens) => tokens.join(" "),
+            None => text.to_string(),
+        }
+    }
+    
+    /// Filter a recognition result's text and word list
+    /// 
+    /// Masked words keep their timings; a replaced phrase becomes one word
+    /// spanning the phrase and a removed phrase loses its words.
+    pub fn apply_result(&self, result: &mut RecognitionResult) {
+        result.text = self.apply(&result.text);
+        if result.words.is_empty() {
+            return;
+        }
+        
+        let tokens: Vec<String> = result.words.iter().map(|w| w.word.clone()).collect();
+        let mut merged = Vec::new();
+        let rewritten = match self.rewrite(&tokens, |start, end| merged.push((start, end))) {
+            Some(rewritten) => rewritten,
+            None => return,
+        };
+        
+        let mut words: Vec<WordInfo> = Vec::with_capacity(rewritten.len());
+        let mut text = rewritten.into_iter();
+        let mut i = 0;
+        while i < result.words.len() {
+            match merged.iter().find(|(start, _)| *start == i) {
+                Some(&(start, end)) => {
+                    if self.mode == FilterMode::Replace {
+                        let span = &result.words[start..end];
+                        words.push(WordInfo {
+                            word: text.next().unwrap_or_default(),
+                            start: span[0].start,
+                            end: span[span.len() - 1].end,
+                            confidence: span.iter().map(|w| w.confidence).fold(f32::INFINITY, f32::min),
+                        });
+                    }
+                    i = end;
+                }
+                None => {
+                    words.push(WordInfo { word: text.next().unwrap_or_default(), ..result.words[i].clone() });
+                    i += 1;
+                }
+            }
+        }
+        result.words = words;
+    }
+}
+
+/// Profanity and sensitive-content filter for transcripts
+/// 
+/// Holds a word list (a built-in English list unless `defaults=False`,
+/// plus any added words, phrases or files) and a mode: "partial" keeps the
+/// first letter ("f***"), "full" masks every letter, "replace" swaps the
+/// phrase for `replacement` and "remove" drops it. A trailing `*` on a
+/// listed word matches every word starting with it; `allow_words` exempts
+/// words such a prefix would catch. Pass it to
+/// `SpeechRecognizer.set_content_filter` to filter results before they
+/// reach Python, or call `apply` on any text.
+#[pyclass]
+#[derive(Clone)]
+pub struct ContentFilter {
+    pub filter: WordFilter,
+}
+
+#[pymethods]
+impl ContentFilter {
+    #[new]
+    #[pyo3(signature = (mode="partial", words=None, defaults=true, replacement=None))]
+    fn new(mode: &str, words: Option<Vec<String>>, defaults: bool, replacement: Option<String>) -> PyResult<Self> {
+        let mode = FilterMode::from_name(mode).map_err(PyValueError::new_err)?;
+        let mut filter = WordFilter::new(mode, defaults);
+        if let Some(words) = words {
+            filter.add_words(words.iter().map(String::as_str));
+        }
+        if let Some(replacement) = replacement {
+            filter.replacement = replacement;
+        }
+        Ok(ContentFilter { filter })
+    }
+    
+    /// Add words or phrases, returning how many were new
+    fn add_words(&mut self, words: Vec<String>) -> usize {
+        self.filter.add_words(words.iter().map(String::as_str))
+    }
+    
+    /// Remove words or phrases from the list
+    fn remove_words(&mut self, words: Vec<String>) {
+        self.filter.remove_words(words.iter().map(String::as_str));
+    }
+    
+    /// Never filter these words, even when a listed prefix matches them
+    fn allow_words(&mut self, words: Vec<String>) {
+        self.filter.allowed.extend(words.iter().map(|w| core_word(w)));
+    }
+    
+    /// Add words from a file (one per line, `#` comments), returning how many were new
+    fn load_word_list(&mut self, path: &str) -> PyResult<usize> {
+        self.filter.load(Path::new(path)).map_err(PyValueError::new_err)
+    }
+    
+    #[getter]
+    fn get_mode(&self) -> &'static str {
+        self.filter.mode.name()
+    }
+    
+    #[setter]
+    fn set_mode(&mut self, mode: &str) -> PyResult<()> {
+        self.filter.mode = FilterMode::from_name(mode).map_err(PyValueError::new_err)?;
+        Ok(())
+    }
+    
+    #[getter]
+    fn get_replacement(&self) -> String {
+        self.filter.replacement.clone()
+    }
+    
+    #[setter]
+    fn set_replacement(&mut self, replacement: String) {
+        self.filter.replacement = replacement;
+    }
+    
+    /// Filter a piece of text
+    fn apply(&self, text: &str) -> String {
+        self.filter.apply(text)
+    }
+    
+    /// Whether the text contains a listed word or phrase
+    fn contains(&self, text: &str) -> bool {
+        self.filter.contains(text)
+    }
+    
+    fn __len__(&self) -> usize {
+        self.filter.entries.len()
+    }
+}
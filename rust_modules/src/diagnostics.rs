@@ -0,0 +1,209 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use cpal::traits::{DeviceTrait, HostTrait};
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Severity of a diagnostic finding
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Ok,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Ok => "ok",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A single result of the startup probe
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub check: String,
+    pub severity: Severity,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl Finding {
+    fn ok(check: &str, message: String) -> Self {
+        Finding { check: check.to_string(), severity: Severity::Ok, message, suggestion: None }
+    }
+    
+    fn problem(check: &str, severity: Severity, message: String, suggestion: &str) -> Self {
+        Finding { check: check.to_string(), severity, message, suggestion: Some(suggestion.to_string()) }
+    }
+    
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("check", &self.check)?;
+        dict.set_item("severity", self.severity.as_str())?;
+        dict.set_item("message", &self.message)?;
+        dict.set_item("suggestion", &self.suggestion)?;
+        Ok(dict.into())
+    }
+}
+
+/// Native libraries probed at startup: (check id, candidate file names, severity if missing, suggestion)
+const NATIVE_LIBRARIES: &[(&str, &[&str], Severity, &str)] = &[
+    (
+        "library.porcupine",
+        &["libpv_porcupine.so", "libpv_porcupine.dylib", "libpv_porcupine.dll"],
+        Severity::Warning,
+        "Reinstall the Rust modules with scripts/build_rust_modules.py so the bundled Porcupine library is copied next to the extension",
+    ),
+    (
+        "library.onnxruntime",
+        &["libonnxruntime.so", "libonnxruntime.dylib", "onnxruntime.dll"],
+        Severity::Warning,
+        "Install onnxruntime (https://onnxruntime.ai) or set ORT_DYLIB_PATH to the onnxruntime shared library",
+    ),
+    (
+        "library.opus",
+        &["libopus.so", "libopus.so.0", "libopus.dylib", "libopus.0.dylib", "opus.dll"],
+        Severity::Warning,
+        "Install libopus (apt install libopus0, brew install opus) to enable compressed audio streaming",
+    ),
+];
+
+/// Directories searched for native libraries
+fn library_search_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    
+    // Next to the host executable (the Python interpreter, not this extension)
+    if let Ok(exe) = env::current_exe() {
+        if let Some(dir) = exe.parent() {
+            paths.push(dir.to_path_buf());
+        }
+    }
+    if let Ok(dir) = env::current_dir() {
+        paths.push(dir);
+    }
+    
+    for var in ["LD_LIBRARY_PATH", "DYLD_LIBRARY_PATH", "PATH"] {
+        if let Some(value) = env::var_os(var) {
+            paths.extend(env::split_paths(&value));
+        }
+    }
+    
+    for dir in ["/usr/lib", "/usr/local/lib", "/usr/lib/x86_64-linux-gnu", "/usr/lib/aarch64-linux-gnu", "/opt/homebrew/lib"] {
+        paths.push(PathBuf::from(dir));
+    }
+    
+    paths
+}
+
+/// Find the first existing library among the candidate file names
+pub fn find_library(candidates: &[&str], search_paths: &[PathBuf]) -> Option<PathBuf> {
+    search_paths
+        .iter()
+        .flat_map(|dir| candidates.iter().map(move |name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+fn check_libraries(findings: &mut Vec<Finding>) {
+    let search_paths = library_search_paths();
+    
+    for (check, candidates, severity, suggestion) in NATIVE_LIBRARIES {
+        match find_library(candidates, &search_paths) {
+            Some(path) => findings.push(Finding::ok(check, format!("Found {}", path.display()))),
+            None => findings.push(Finding::problem(
+                check,
+                *severity,
+                format!("None of {} could be found", candidates.join(", ")),
+                suggestion,
+            )),
+        }
+    }
+}
+
+/// Suggestion shown when the OS refuses access to the microphone
+fn microphone_permission_hint() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "Open System Settings > Privacy & Security > Microphone and allow access for Jenna, then restart it"
+    } else if cfg!(target_os = "windows") {
+        "Open Settings > Privacy > Microphone and enable 'Let desktop apps access your microphone'"
+    } else {
+        "Check that your user is in the 'audio' group and that PulseAudio/PipeWire is running"
+    }
+}
+
+fn check_microphone(findings: &mut Vec<Finding>) {
+    let host = cpal::default_host();
+    
+    let device = match host.default_input_device() {
+        Some(device) => device,
+        None => {
+            findings.push(Finding::problem(
+                "audio.input_device",
+                Severity::Error,
+                "No default input device is available".to_string(),
+                "Connect a microphone and select it as the default recording device",
+            ));
+            return;
+        }
+    };
+    
+    let name = device.name().unwrap_or_else(|_| "unknown".to_string());
+    match device.default_input_config() {
+        Ok(config) => findings.push(Finding::ok(
+            "audio.input_device",
+            format!("{} ({} Hz, {} channels)", name, config.sample_rate().0, config.channels()),
+        )),
+        Err(err) => findings.push(Finding::problem(
+            "audio.microphone_access",
+            Severity::Error,
+            format!("Cannot open {}: {}", name, err),
+            microphone_permission_hint(),
+        )),
+    }
+}
+
+fn check_models(findings: &mut Vec<Finding>, models: &HashMap<String, String>) {
+    let mut names: Vec<&String> = models.keys().collect();
+    names.sort();
+    
+    for name in names {
+        let path = Path::new(&models[name]);
+        let check = format!("model.{}", name);
+        if path.exists() {
+            findings.push(Finding::ok(&check, format!("Found {}", path.display())));
+        } else {
+            findings.push(Finding::problem(
+                &check,
+                Severity::Error,
+                format!("Model not found at {}", path.display()),
+                "Run the matching setup script in scripts/ (e.g. setup_vosk.py, setup_larynx.py) to download the model",
+            ));
+        }
+    }
+}
+
+/// Run all startup checks
+pub fn run_diagnostics(models: &HashMap<String, String>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check_libraries(&mut findings);
+    check_microphone(&mut findings);
+    check_models(&mut findings, models);
+    findings
+}
+
+/// Probe native libraries, microphone access and model files
+/// 
+/// `models` maps a model name to its expected path. Returns a list of dicts
+/// with `check`, `severity` ("ok", "warning" or "error"), `message` and an
+/// optional `suggestion` that the UI can show on first run.
+#[pyfunction]
+#[pyo3(signature = (models=None))]
+pub fn diagnose(py: Python, models: Option<HashMap<String, String>>) -> PyResult<Vec<Py<PyDict>>> {
+    let models = models.unwrap_or_default();
+    let findings = py.allow_threads(|| run_diagnostics(&models));
+    findings.iter().map(|finding| finding.to_dict(py)).collect()
+}
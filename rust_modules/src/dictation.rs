@@ -0,0 +1,272 @@
+use pyo3::prelude::*;
+
+/// Silence between two segments, in seconds, that starts a new paragraph by default
+const DEFAULT_PARAGRAPH_PAUSE: f32 = 3.0;
+
+/// Silence after the last segment, in seconds, that ends dictation by default
+const DEFAULT_IDLE_TIMEOUT: f32 = 20.0;
+
+/// How a dictation session is stitched together and when it gives up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DictationOptions {
+    pub paragraph_pause: f32,
+    pub idle_timeout: f32,
+}
+
+impl DictationOptions {
+    pub fn new(paragraph_pause: f32, idle_timeout: f32) -> Result<Self, String> {
+        if !paragraph_pause.is_finite() || paragraph_pause <= 0.0 || !idle_timeout.is_finite() || idle_timeout <= 0.0 {
+            return Err("Paragraph pause and idle timeout must be positive".to_string());
+        }
+        Ok(DictationOptions { paragraph_pause, idle_timeout })
+    }
+}
+
+impl Default for DictationOptions {
+    fn default() -> Self {
+        DictationOptions { paragraph_pause: DEFAULT_PARAGRAPH_PAUSE, idle_timeout: DEFAULT_IDLE_TIMEOUT }
+    }
+}
+
+/// A break between two runs of dictated text
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Break {
+    Line,
+    Paragraph,
+}
+
+impl Break {
+    fn separator(self) -> &'static str {
+        match self {
+            Break::Line => "\n",
+            Break::Paragraph => "\n\n",
+        }
+    }
+}
+
+/// A piece of a recognized segment: words to keep, or a spoken command
+#[derive(Debug, PartialEq)]
+enum Piece {
+    Text(String),
+    Break(Break),
+    Stop,
+}
+
+/// Spoken commands, as the normalized words that make them up
+const COMMANDS: &[(&str, &str)] = &[("new", "line"), ("new", "paragraph"), ("stop", "dictation")];
+
+fn normalize(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Split a transcript into the words to keep and the commands spoken between them
+/// 
+/// Commands match whole words, ignoring case and punctuation, so "New
+/// line." and "new line," both break the line. Punctuation the engine put
+/// right before a command ("milk, new line") is dropped with it.
+fn split_commands(text: &str) -> Vec<Piece> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let normalized: Vec<String> = words.iter().map(|word| normalize(word)).collect();
+    let mut pieces = Vec::new();
+    let mut run: Vec<&str> = Vec::new();
+    let mut index = 0;
+    
+    while index < words.len() {
+        let command = normalized
+            .get(index + 1)
+            .and_then(|next| COMMANDS.iter().position(|&(first, second)| normalized[index] == first && next == second));
+        let piece = match command {
+            Some(0) => Piece::Break(Break::Line),
+            Some(1) => Piece::Break(Break::Paragraph),
+            Some(_) => Piece::Stop,
+            None => {
+                run.push(words[index]);
+                index += 1;
+                continue;
+            }
+        };
+        if !run.is_empty() {
+            let text = run.join(" ");
+            pieces.push(Piece::Text(text.trim_end_matches([',', ';', ':']).to_string()));
+            run.clear();
+        }
+        pieces.push(piece);
+        index += 2;
+    }
+    if !run.is_empty() {
+        pieces.push(Piece::Text(run.join(" ")));
+    }
+    pieces
+}
+
+/// One recognized stretch of dictation, with the commands in it applied
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct DictationSegment {
+    /// Text of the segment; "new line" and "new paragraph" inside it are already line breaks
+    #[pyo3(get)]
+    pub text: String,
+    /// When the speech started and ended, in seconds since dictation started
+    #[pyo3(get)]
+    pub start: f64,
+    #[pyo3(get)]
+    pub end: f64,
+    /// Paragraph the segment starts in, counting from 0
+    #[pyo3(get)]
+    pub paragraph: usize,
+    #[pyo3(get)]
+    pub confidence: Option<f32>,
+}
+
+#[pymethods]
+impl DictationSegment {
+    fn __repr__(&self) -> String {
+        format!(
+            "DictationSegment(text={:?}, start={:.2}, end={:.2}, paragraph={})",
+            self.text, self.start, self.end, self.paragraph
+        )
+    }
+}
+
+/// Everything dictated so far, stitched into one text
+/// 
+/// Segments are joined with spaces; a pause of at least the paragraph
+/// pause, or a spoken "new paragraph", starts a new paragraph (a blank
+/// line), and "new line" starts a new line.
+#[pyclass]
+#[derive(Clone, Debug, Default)]
+pub struct DictationTranscript {
+    #[pyo3(get)]
+    pub text: String,
+    #[pyo3(get)]
+    pub segments: Vec<DictationSegment>,
+    paragraph: usize,
+    /// Break asked for by a command at the end of the last segment
+    pending: Option<Break>,
+}
+
+#[pymethods]
+impl DictationTranscript {
+    /// Text of each paragraph, in order
+    fn paragraphs(&self) -> Vec<String> {
+        self.text.split("\n\n").map(str::to_string).collect()
+    }
+    
+    fn __repr__(&self) -> String {
+        format!("DictationTranscript(segments={}, text={:?})", self.segments.len(), self.text)
+    }
+}
+
+impl DictationTranscript {
+    /// Stitch a recognized segment onto the transcript
+    /// 
+    /// Returns the segment added, if it had any words besides commands, and
+    /// whether "stop dictation" was said; words after it are dropped.
+    fn add(&mut self, text: &str, start: f64, end: f64, confidence: Option<f32>, paragraph_pause: f32) -> (Option<DictationSegment>, bool) {
+        let gap = self.segments.last().map(|last| start - last.end);
+        if gap.is_some_and(|gap| gap >= paragraph_pause as f64) {
+            self.pending = Some(Break::Paragraph);
+        }
+        
+        let mut added = String::new();
+        let mut paragraph = None;
+        let mut stop = false;
+        for piece in split_commands(text) {
+            match piece {
+                Piece::Text(words) => {
+                    if !self.text.is_empty() || !added.is_empty() {
+                        let separator = match self.pending.take() {
+                            Some(Break::Paragraph) => {
+                                self.paragraph += 1;
+                                Break::Paragraph.separator()
+                            }
+                            Some(Break::Line) => Break::Line.separator(),
+                            None => " ",
+                        };
+                        // The segment's own text starts at its first word
+                        if !added.is_empty() {
+                            added.push_str(separator);
+                        } else {
+                            self.text.push_str(separator);
+                        }
+                    }
+                    self.pending = None;
+                    paragraph.get_or_insert(self.paragraph);
+                    added.push_str(&words);
+                }
+                // "new line" right after "new paragraph" doesn't undo it
+                Piece::Break(kind) => self.pending = self.pending.max(Some(kind)),
+                Piece::Stop => {
+                    stop = true;
+                    break;
+                }
+            }
+        }
+        
+        let segment = paragraph.map(|paragraph| DictationSegment { text: added, start, end, paragraph, confidence });
+        if let Some(segment) = &segment {
+            self.text.push_str(&segment.text);
+            self.segments.push(segment.clone());
+        }
+        (segment, stop)
+    }
+}
+
+/// A dictation session in progress, counted in frames
+pub struct Dictation {
+    pub options: DictationOptions,
+    pub transcript: DictationTranscript,
+    frame_seconds: f64,
+    /// Frames since dictation started
+    frames: u64,
+    /// Frame the current segment's speech started on
+    segment_start: Option<u64>,
+    /// Set by `stop_dictation` or a spoken "stop dictation"
+    pub stopping: bool,
+}
+
+impl Dictation {
+    pub fn new(options: DictationOptions, frame_length: usize, sample_rate: u32) -> Self {
+        Dictation {
+            options,
+            transcript: DictationTranscript::default(),
+            frame_seconds: frame_length as f64 / sample_rate as f64,
+            frames: 0,
+            segment_start: None,
+            stopping: false,
+        }
+    }
+    
+    /// Count a frame, noting where speech starts
+    pub fn tick(&mut self, speech_started: bool) {
+        if speech_started && self.segment_start.is_none() {
+            self.segment_start = Some(self.frames);
+        }
+        self.frames += 1;
+    }
+    
+    /// Stitch the result of the segment that just ended, `trailing_silence` frames ago
+    pub fn finish_segment(&mut self, text: &str, confidence: Option<f32>, trailing_silence: u32) -> Option<DictationSegment> {
+        let start = self.segment_start.take().unwrap_or(self.frames);
+        let end = self.frames.saturating_sub(trailing_silence as u64).max(start);
+        let (segment, stop) = self.transcript.add(
+            text,
+            start as f64 * self.frame_seconds,
+            end as f64 * self.frame_seconds,
+            confidence,
+            self.options.paragraph_pause,
+        );
+        self.stopping |= stop;
+        segment
+    }
+    
+    /// Drop the start of a segment that produced no usable result
+    pub fn skip_segment(&mut self) {
+        self.segment_start = None;
+    }
+    
+    /// Frames of silence after which dictation ends by itself
+    pub fn idle_frames(&self) -> u32 {
+        (self.options.idle_timeout as f64 / self.frame_seconds).ceil() as u32
+    }
+}
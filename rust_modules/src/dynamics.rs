@@ -0,0 +1,335 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::VecDeque;
+use jenna_core::loudness;
+
+/// Level floor used when converting silent samples to dB
+const MIN_LEVEL_DB: f32 = -120.0;
+
+fn to_db(amplitude: f32) -> f32 {
+    if amplitude > 0.0 {
+        (20.0 * amplitude.log10()).max(MIN_LEVEL_DB)
+    } else {
+        MIN_LEVEL_DB
+    }
+}
+
+fn from_db(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// One-pole smoothing coefficient for a time constant in milliseconds
+fn time_coefficient(time_ms: f32, sample_rate: u32) -> f32 {
+    if time_ms <= 0.0 {
+        return 0.0;
+    }
+    (-1.0 / (time_ms / 1000.0 * sample_rate as f32)).exp()
+}
+
+/// Feed-forward dynamic range compressor
+/// 
+/// Gain reduction is computed from the signal peak level with a soft knee
+/// and smoothed with separate attack and release times, then makeup gain is
+/// applied. Intended for the TTS playback path to even out voice loudness.
+#[pyclass]
+pub struct Compressor {
+    #[pyo3(get)]
+    sample_rate: u32,
+    #[pyo3(get)]
+    threshold_db: f32,
+    #[pyo3(get)]
+    ratio: f32,
+    #[pyo3(get)]
+    attack_ms: f32,
+    #[pyo3(get)]
+    release_ms: f32,
+    #[pyo3(get, set)]
+    makeup_db: f32,
+    #[pyo3(get)]
+    knee_db: f32,
+    attack_coeff: f32,
+    release_coeff: f32,
+    gain_reduction_db: f32,
+}
+
+impl Compressor {
+    /// Create a compressor with the default speech settings
+    pub fn with_defaults(sample_rate: u32) -> Self {
+        let mut compressor = Compressor {
+            sample_rate,
+            threshold_db: -18.0,
+            ratio: 4.0,
+            attack_ms: 5.0,
+            release_ms: 100.0,
+            makeup_db: 0.0,
+            knee_db: 6.0,
+            attack_coeff: 0.0,
+            release_coeff: 0.0,
+            gain_reduction_db: 0.0,
+        };
+        compressor.update_coefficients();
+        compressor
+    }
+    
+    /// Static gain reduction (positive dB) for an input level
+    fn compute_reduction(&self, level_db: f32) -> f32 {
+        let over = level_db - self.threshold_db;
+        let slope = 1.0 - 1.0 / self.ratio;
+        
+        if self.knee_db > 0.0 && over.abs() <= self.knee_db / 2.0 {
+            let x = over + self.knee_db / 2.0;
+            slope * x * x / (2.0 * self.knee_db)
+        } else if over > 0.0 {
+            slope * over
+        } else {
+            0.0
+        }
+    }
+    
+    fn update_coefficients(&mut self) {
+        self.attack_coeff = time_coefficient(self.attack_ms, self.sample_rate);
+        self.release_coeff = time_coefficient(self.release_ms, self.sample_rate);
+    }
+    
+    /// Compress a buffer in place
+    pub fn process_in_place(&mut self, buffer: &mut [f32]) {
+        let makeup = self.makeup_db;
+        for sample in buffer.iter_mut() {
+            let target = self.compute_reduction(to_db(sample.abs()));
+            let coeff = if target > self.gain_reduction_db { self.attack_coeff } else { self.release_coeff };
+            self.gain_reduction_db = coeff * self.gain_reduction_db + (1.0 - coeff) * target;
+            *sample *= from_db(makeup - self.gain_reduction_db);
+        }
+    }
+}
+
+#[pymethods]
+impl Compressor {
+    /// Create a new compressor
+    #[new]
+    #[pyo3(signature = (sample_rate, threshold_db=-18.0, ratio=4.0, attack_ms=5.0, release_ms=100.0, makeup_db=0.0, knee_db=6.0))]
+    fn new(
+        sample_rate: u32,
+        threshold_db: f32,
+        ratio: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        makeup_db: f32,
+        knee_db: f32,
+    ) -> PyResult<Self> {
+        if ratio < 1.0 {
+            return Err(PyValueError::new_err("Ratio must be at least 1.0"));
+        }
+        if attack_ms < 0.0 || release_ms < 0.0 || knee_db < 0.0 {
+            return Err(PyValueError::new_err("Attack, release and knee must not be negative"));
+        }
+        
+        let mut compressor = Compressor {
+            sample_rate,
+            threshold_db,
+            ratio,
+            attack_ms,
+            release_ms,
+            makeup_db,
+            knee_db,
+            attack_coeff: 0.0,
+            release_coeff: 0.0,
+            gain_reduction_db: 0.0,
+        };
+        compressor.update_coefficients();
+        Ok(compressor)
+    }
+    
+    /// Update compressor parameters; omitted parameters keep their value
+    #[pyo3(signature = (threshold_db=None, ratio=None, attack_ms=None, release_ms=None, knee_db=None))]
+    fn set_params(
+        &mut self,
+        threshold_db: Option<f32>,
+        ratio: Option<f32>,
+        attack_ms: Option<f32>,
+        release_ms: Option<f32>,
+        knee_db: Option<f32>,
+    ) -> PyResult<()> {
+        if ratio.map(|r| r < 1.0).unwrap_or(false) {
+            return Err(PyValueError::new_err("Ratio must be at least 1.0"));
+        }
+        
+        self.threshold_db = threshold_db.unwrap_or(self.threshold_db);
+        self.ratio = ratio.unwrap_or(self.ratio);
+        self.attack_ms = attack_ms.unwrap_or(self.attack_ms).max(0.0);
+        self.release_ms = release_ms.unwrap_or(self.release_ms).max(0.0);
+        self.knee_db = knee_db.unwrap_or(self.knee_db).max(0.0);
+        self.update_coefficients();
+        Ok(())
+    }
+    
+    /// Compress a block of samples
+    fn process(&mut self, samples: Vec<f32>) -> PyResult<Vec<f32>> {
+        let mut output = samples;
+        self.process_in_place(&mut output);
+        Ok(output)
+    }
+    
+    /// Get the current gain reduction in dB
+    fn get_gain_reduction(&self) -> f32 {
+        self.gain_reduction_db
+    }
+    
+    /// Reset the envelope state
+    fn reset(&mut self) -> PyResult<()> {
+        self.gain_reduction_db = 0.0;
+        Ok(())
+    }
+}
+
+/// Look-ahead brickwall peak limiter
+/// 
+/// The signal is delayed by the look-ahead time so the gain can drop before
+/// a peak arrives, guaranteeing the output never exceeds the ceiling. The
+/// gain each sample needs is held at its minimum over the look-ahead window
+/// and then averaged over the same window, so every reduction ramps in
+/// smoothly across the look-ahead instead of stepping (which clicks), and
+/// reaches its full depth just as the peak comes out of the delay.
+#[pyclass]
+pub struct Limiter {
+    #[pyo3(get)]
+    sample_rate: u32,
+    #[pyo3(get, set)]
+    ceiling_db: f32,
+    #[pyo3(get)]
+    release_ms: f32,
+    release_coeff: f32,
+    delay: VecDeque<f32>,
+    /// Candidates for the smallest required gain in the window, as (position, gain), increasing
+    minimum: VecDeque<(u64, f32)>,
+    /// Samples seen, numbering the entries in `minimum`
+    position: u64,
+    /// Window minimum with the release applied
+    held: f32,
+    /// Last `held` values, averaged into the gain
+    ramp: VecDeque<f32>,
+    ramp_sum: f64,
+}
+
+impl Limiter {
+    /// Limit a buffer in place (output is delayed by the look-ahead)
+    pub fn process_in_place(&mut self, buffer: &mut [f32]) {
+        let ceiling = from_db(self.ceiling_db);
+        let window = self.ramp.len() as u64;
+        for sample in buffer.iter_mut() {
+            let peak = sample.abs();
+            let required = if peak > ceiling { ceiling / peak } else { 1.0 };
+            
+            // Sliding minimum of the required gain: entries that can never be
+            // the minimum again are dropped, so each sample is handled once
+            while self.minimum.back().map_or(false, |&(_, gain)| gain >= required) {
+                self.minimum.pop_back();
+            }
+            self.minimum.push_back((self.position, required));
+            while self.minimum.front().map_or(false, |&(position, _)| position + window <= self.position) {
+                self.minimum.pop_front();
+            }
+            let minimum = self.minimum.front().map_or(1.0, |&(_, gain)| gain);
+            self.position += 1;
+            
+            // Drops follow at once; recovery is smoothed, and stays below the minimum
+            self.held = if minimum < self.held {
+                minimum
+            } else {
+                self.release_coeff * self.held + (1.0 - self.release_coeff) * minimum
+            };
+            self.ramp.push_back(self.held);
+            let oldest = self.ramp.pop_front().unwrap_or(1.0);
+            self.ramp_sum += self.held as f64 - oldest as f64;
+            let gain = (self.ramp_sum / window as f64) as f32;
+            
+            self.delay.push_back(*sample);
+            let delayed = self.delay.pop_front().unwrap_or(0.0);
+            *sample = (delayed * gain).clamp(-ceiling, ceiling);
+        }
+    }
+}
+
+#[pymethods]
+impl Limiter {
+    /// Create a new limiter
+    #[new]
+    #[pyo3(signature = (sample_rate, ceiling_db=-1.0, lookahead_ms=5.0, release_ms=50.0))]
+    fn new(sample_rate: u32, ceiling_db: f32, lookahead_ms: f32, release_ms: f32) -> PyResult<Self> {
+        if lookahead_ms < 0.0 || release_ms < 0.0 {
+            return Err(PyValueError::new_err("Look-ahead and release must not be negative"));
+        }
+        
+        let lookahead = ((lookahead_ms / 1000.0 * sample_rate as f32) as usize).max(1);
+        // The gain for a sample is settled once it has been averaged over a
+        // full window, the look-ahead plus the sample itself
+        let window = lookahead + 1;
+        Ok(Limiter {
+            sample_rate,
+            ceiling_db,
+            release_ms,
+            release_coeff: time_coefficient(release_ms, sample_rate),
+            delay: VecDeque::from(vec![0.0; lookahead]),
+            minimum: VecDeque::with_capacity(window),
+            position: 0,
+            held: 1.0,
+            ramp: VecDeque::from(vec![1.0; window]),
+            ramp_sum: window as f64,
+        })
+    }
+    
+    /// Limit a block of samples
+    fn process(&mut self, samples: Vec<f32>) -> PyResult<Vec<f32>> {
+        let mut output = samples;
+        self.process_in_place(&mut output);
+        Ok(output)
+    }
+    
+    /// Get the look-ahead latency in samples
+    fn latency(&self) -> usize {
+        self.delay.len()
+    }
+    
+    /// Reset the delay line and gain state
+    fn reset(&mut self) -> PyResult<()> {
+        self.delay.iter_mut().for_each(|value| *value = 0.0);
+        self.ramp.iter_mut().for_each(|value| *value = 1.0);
+        self.ramp_sum = self.ramp.len() as f64;
+        self.minimum.clear();
+        self.held = 1.0;
+        Ok(())
+    }
+}
+
+/// Measure the integrated loudness of mono audio in LUFS (EBU R128), or None for silence
+#[pyfunction]
+pub fn measure_loudness(py: Python, samples: Vec<f32>, sample_rate: u32) -> PyResult<Option<f64>> {
+    if sample_rate == 0 {
+        return Err(PyValueError::new_err("Sample rate must be positive"));
+    }
+    Ok(py.allow_threads(|| loudness::integrated_loudness(&samples, sample_rate)))
+}
+
+/// Scale mono audio to `target_lufs`, never raising its peak above `max_peak_db`
+/// 
+/// Makes TTS, chimes and media play back at the same perceived volume.
+/// Silent audio is returned unchanged.
+#[pyfunction]
+#[pyo3(signature = (samples, sample_rate, target_lufs=loudness::EBU_R128_TARGET, max_peak_db=loudness::DEFAULT_MAX_PEAK_DB))]
+pub fn normalize_loudness(py: Python, samples: Vec<f32>, sample_rate: u32, target_lufs: f32, max_peak_db: f32) -> PyResult<Vec<f32>> {
+    check_loudness_target(target_lufs).map_err(PyValueError::new_err)?;
+    if sample_rate == 0 {
+        return Err(PyValueError::new_err("Sample rate must be positive"));
+    }
+    let mut output = samples;
+    py.allow_threads(|| loudness::normalize_loudness(&mut output, sample_rate, target_lufs, max_peak_db));
+    Ok(output)
+}
+
+/// Check that a loudness target is a usable level in LUFS
+pub fn check_loudness_target(target: f32) -> Result<(), String> {
+    if !target.is_finite() || !(-70.0..0.0).contains(&target) {
+        return Err(format!("Loudness target must be between -70 and 0 LUFS, got {}", target));
+    }
+    Ok(())
+}
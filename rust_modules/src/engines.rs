@@ -0,0 +1,156 @@
+use std::path::Path;
+use jenna_core::normalize::TextNormalizer;
+use jenna_core::resample::MonoResampler;
+use jenna_core::ssml::Prosody;
+use jenna_core::wake_word::{OpenWakeWordEngine, WakeWordEngine};
+
+use crate::speech::{load_stt_engine, render_text, SttEngine};
+use crate::tts::{resolve_voice, Lexicon, PiperVoice};
+use crate::wake_word::{try_build_porcupine, KeywordSource, PorcupineEngine};
+
+pub use crate::speech::{RecognitionResult, WordInfo};
+
+// Engines with plain Rust types, for bindings other than the Python module.
+// Each wraps the same backend the corresponding Python class drives, minus
+// the Python-only extras (background threads, events, caching). Errors are
+// returned as messages.
+
+/// Streaming mono resampler
+pub struct Resampler {
+    resampler: MonoResampler,
+}
+
+impl Resampler {
+    pub fn new(input_rate: u32, output_rate: u32) -> Result<Self, String> {
+        let resampler = MonoResampler::new(input_rate, output_rate).map_err(|err| err.to_string())?;
+        Ok(Resampler { resampler })
+    }
+    
+    /// Resample the next block of a stream; output lags input by the filter delay
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<f32>, String> {
+        self.resampler.push(samples).map_err(|err| err.to_string())
+    }
+}
+
+fn check_sensitivities(keyword_paths: &[String], sensitivities: &[f32]) -> Result<(), String> {
+    if keyword_paths.is_empty() {
+        return Err("At least one keyword is required".to_string());
+    }
+    if sensitivities.len() != keyword_paths.len() {
+        return Err(format!("Got {} sensitivities for {} keywords", sensitivities.len(), keyword_paths.len()));
+    }
+    if sensitivities.iter().any(|s| !(0.0..=1.0).contains(s)) {
+        return Err("Sensitivity must be between 0.0 and 1.0".to_string());
+    }
+    Ok(())
+}
+
+/// Wake word engine fed one fixed-size frame at a time
+pub struct WakeWord {
+    engine: Box<dyn WakeWordEngine>,
+}
+
+impl WakeWord {
+    /// Porcupine with keyword (.ppn) files; `model_path` None uses the bundled model
+    pub fn porcupine(
+        access_key: &str,
+        keyword_paths: Vec<String>,
+        sensitivities: &[f32],
+        model_path: Option<&str>,
+    ) -> Result<Self, String> {
+        check_sensitivities(&keyword_paths, sensitivities)?;
+        let porcupine = try_build_porcupine(access_key, &KeywordSource::Paths(keyword_paths), sensitivities, model_path)?;
+        Ok(WakeWord { engine: Box::new(PorcupineEngine::new(porcupine)) })
+    }
+    
+    /// openWakeWord with a directory of shared models and one classifier per keyword
+    pub fn openwakeword(model_dir: &Path, keyword_paths: Vec<String>, sensitivities: &[f32]) -> Result<Self, String> {
+        check_sensitivities(&keyword_paths, sensitivities)?;
+        let engine = OpenWakeWordEngine::new(model_dir, &keyword_paths, sensitivities)?;
+        Ok(WakeWord { engine: Box::new(engine) })
+    }
+    
+    pub fn frame_length(&self) -> usize {
+        self.engine.frame_length()
+    }
+    
+    pub fn sample_rate(&self) -> u32 {
+        self.engine.sample_rate()
+    }
+    
+    /// Process one frame, returning the index of the keyword that fired
+    pub fn process(&mut self, frame: &[i16]) -> Result<Option<usize>, String> {
+        if frame.len() != self.engine.frame_length() {
+            return Err(format!("Frame must have {} samples, got {}", self.engine.frame_length(), frame.len()));
+        }
+        Ok(self.engine.process(frame)?.keyword)
+    }
+}
+
+/// Streaming speech recognizer
+pub struct Recognizer {
+    engine: Box<dyn SttEngine>,
+    sample_rate: u32,
+}
+
+impl Recognizer {
+    /// Load a "vosk" model directory or "whisper" model file; `language` is a Whisper language code
+    pub fn new(engine: &str, model_path: &str, sample_rate: u32, language: Option<String>) -> Result<Self, String> {
+        if sample_rate == 0 {
+            return Err("Sample rate must be positive".to_string());
+        }
+        let engine = load_stt_engine(&engine.to_ascii_lowercase(), model_path, sample_rate, language, false, "auto")?;
+        Ok(Recognizer { engine, sample_rate })
+    }
+    
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    
+    /// Feed audio, returning a partial or final result when one is available
+    pub fn accept(&mut self, pcm: &[i16]) -> Result<Option<RecognitionResult>, String> {
+        self.engine.accept(pcm)
+    }
+    
+    /// End the current utterance and return its final result
+    pub fn finish(&mut self) -> Result<Option<RecognitionResult>, String> {
+        self.engine.finish()
+    }
+    
+    /// Discard the utterance in progress
+    pub fn reset(&mut self) {
+        self.engine.reset();
+    }
+    
+    /// Include per-word timings in final (and optionally partial) results
+    pub fn set_words(&mut self, words: bool, partial: bool) {
+        self.engine.set_words(words, partial);
+    }
+}
+
+/// Piper text-to-speech voice
+pub struct Synthesizer {
+    voice: PiperVoice,
+    normalizer: Option<TextNormalizer>,
+    lexicon: Lexicon,
+}
+
+impl Synthesizer {
+    /// Load a voice file, or the voice named `voice` from a directory of voices
+    pub fn new(model_path: &Path, voice: Option<&str>) -> Result<Self, String> {
+        let voice = PiperVoice::load(&resolve_voice(model_path, voice.unwrap_or("default"))?, false)?;
+        let language = voice.config.language.as_ref().map_or("en_US", |language| language.code.as_str());
+        let normalizer = TextNormalizer::for_language(language);
+        Ok(Synthesizer { voice, normalizer, lexicon: Lexicon::default() })
+    }
+    
+    /// Native sample rate of the voice
+    pub fn sample_rate(&self) -> u32 {
+        self.voice.sample_rate()
+    }
+    
+    /// Synthesize text or SSML to 16-bit mono PCM at the native rate
+    pub fn synthesize(&self, text: &str) -> Result<Vec<i16>, String> {
+        render_text(&self.voice, text, Prosody::default(), self.normalizer.as_ref(), &self.lexicon, self.sample_rate())
+    }
+}
@@ -0,0 +1,737 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use jenna_core::ssml::{ordinal_to_words, MONTHS, ONES, TENS};
+
+use crate::intent::tokenize;
+
+const SCALES: [(&str, u64); 4] = [("thousand", 1_000), ("million", 1_000_000), ("billion", 1_000_000_000), ("trillion", 1_000_000_000_000)];
+
+const WEEKDAYS: [&str; 7] = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"];
+
+/// Duration units and their length in seconds
+const UNITS: [(&str, f64); 16] = [
+    ("second", 1.0), ("seconds", 1.0), ("sec", 1.0), ("secs", 1.0),
+    ("minute", 60.0), ("minutes", 60.0), ("min", 60.0), ("mins", 60.0),
+    ("hour", 3600.0), ("hours", 3600.0), ("hr", 3600.0), ("hrs", 3600.0),
+    ("day", 86400.0), ("days", 86400.0),
+    ("week", 604800.0), ("weeks", 604800.0),
+];
+
+/// Kinds of entity, in the order ties are broken when extracting
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntityKind {
+    Date,
+    Time,
+    Duration,
+    Ordinal,
+    Number,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 5] = [EntityKind::Date, EntityKind::Time, EntityKind::Duration, EntityKind::Ordinal, EntityKind::Number];
+    
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "date" => Some(EntityKind::Date),
+            "time" => Some(EntityKind::Time),
+            "duration" => Some(EntityKind::Duration),
+            "ordinal" => Some(EntityKind::Ordinal),
+            "number" => Some(EntityKind::Number),
+            _ => None,
+        }
+    }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntityKind::Date => "date",
+            EntityKind::Time => "time",
+            EntityKind::Duration => "duration",
+            EntityKind::Ordinal => "ordinal",
+            EntityKind::Number => "number",
+        }
+    }
+}
+
+/// A calendar reference; any combination of fields may be set
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DateValue {
+    pub year: Option<i32>,
+    pub month: Option<u32>,
+    pub day: Option<u32>,
+    /// 0 is Monday
+    pub weekday: Option<u32>,
+    /// Days from today ("tomorrow" is 1)
+    pub relative_days: Option<i64>,
+}
+
+/// Structured value of an entity
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EntityValue {
+    Number(f64),
+    Ordinal(u64),
+    /// Length in seconds; `relative` for "in twenty minutes" (from now)
+    Duration { seconds: f64, relative: bool },
+    /// 24-hour clock time; hours without "am"/"pm" are kept as spoken
+    Time { hour: u32, minute: u32 },
+    Date(DateValue),
+}
+
+impl EntityValue {
+    pub fn kind(&self) -> EntityKind {
+        match self {
+            EntityValue::Number(_) => EntityKind::Number,
+            EntityValue::Ordinal(_) => EntityKind::Ordinal,
+            EntityValue::Duration { .. } => EntityKind::Duration,
+            EntityValue::Time { .. } => EntityKind::Time,
+            EntityValue::Date(_) => EntityKind::Date,
+        }
+    }
+    
+    /// Compact text form: "20", "3.5", "07:15", "2024-03-05", "--03-05", "tomorrow", "monday"
+    pub fn canonical(&self) -> String {
+        match self {
+            EntityValue::Number(value) => format_number(*value),
+            EntityValue::Ordinal(value) => value.to_string(),
+            EntityValue::Duration { seconds, .. } => format_number(*seconds),
+            EntityValue::Time { hour, minute } => format!("{:02}:{:02}", hour, minute),
+            EntityValue::Date(date) => match (date.year, date.month, date.day, date.weekday, date.relative_days) {
+                (Some(year), Some(month), Some(day), _, _) => format!("{:04}-{:02}-{:02}", year, month, day),
+                (None, Some(month), Some(day), _, _) => format!("--{:02}-{:02}", month, day),
+                (_, _, _, Some(weekday), _) => WEEKDAYS[weekday as usize].to_string(),
+                (_, _, _, _, Some(0)) => "today".to_string(),
+                (_, _, _, _, Some(1)) => "tomorrow".to_string(),
+                (_, _, _, _, Some(-1)) => "yesterday".to_string(),
+                (_, _, _, _, Some(days)) => format!("{:+} days", days),
+                _ => String::new(),
+            },
+        }
+    }
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// An entity found in a transcript
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct Entity {
+    /// The words the entity was read from
+    #[pyo3(get)]
+    pub text: String,
+    /// Index of the first word, counting words as `tokenize` splits them
+    #[pyo3(get)]
+    pub start: usize,
+    /// Index one past the last word
+    #[pyo3(get)]
+    pub end: usize,
+    pub value: EntityValue,
+}
+
+#[pymethods]
+impl Entity {
+    /// "number", "ordinal", "duration", "time" or "date"
+    #[getter]
+    fn kind(&self) -> &'static str {
+        self.value.kind().name()
+    }
+    
+    /// The value: a float for numbers, an int for ordinals, seconds for
+    /// durations, an `(hour, minute)` tuple for times and a dict with
+    /// `year`, `month`, `day`, `weekday` and `relative_days` for dates
+    #[getter]
+    fn value(&self, py: Python) -> PyResult<PyObject> {
+        Ok(match self.value {
+            EntityValue::Number(value) => value.into_py(py),
+            EntityValue::Ordinal(value) => value.into_py(py),
+            EntityValue::Duration { seconds, .. } => seconds.into_py(py),
+            EntityValue::Time { hour, minute } => (hour, minute).into_py(py),
+            EntityValue::Date(date) => {
+                let dict = PyDict::new(py);
+                dict.set_item("year", date.year)?;
+                dict.set_item("month", date.month)?;
+                dict.set_item("day", date.day)?;
+                dict.set_item("weekday", date.weekday)?;
+                dict.set_item("relative_days", date.relative_days)?;
+                dict.into()
+            }
+        })
+    }
+    
+    /// Whether a duration counts from now ("in twenty minutes")
+    #[getter]
+    fn relative(&self) -> bool {
+        matches!(self.value, EntityValue::Duration { relative: true, .. })
+    }
+    
+    /// The value as compact text ("07:15", "1200", "2024-03-05")
+    #[getter]
+    fn canonical(&self) -> String {
+        self.value.canonical()
+    }
+    
+    fn __repr__(&self) -> String {
+        format!("Entity(kind={:?}, text={:?}, value={})", self.kind(), self.text, self.value.canonical())
+    }
+}
+
+fn word_at<'a>(words: &'a [String], i: usize) -> Option<&'a str> {
+    words.get(i).map(String::as_str)
+}
+
+fn small_number(word: &str) -> Option<u64> {
+    ONES.iter().position(|&w| w == word).map(|n| n as u64)
+}
+
+fn tens_number(word: &str) -> Option<u64> {
+    TENS.iter().position(|&w| !w.is_empty() && w == word).map(|n| n as u64 * 10)
+}
+
+fn scale(word: &str) -> Option<u64> {
+    SCALES.iter().find(|(w, _)| *w == word).map(|(_, s)| *s)
+}
+
+fn is_number_word(word: &str) -> bool {
+    small_number(word).is_some() || tens_number(word).is_some()
+}
+
+/// Parse a number at the start of `words`, returning it and the words used
+/// 
+/// Reads digits ("20", "3.5", "1,200") and spoken forms: "minus three
+/// point five", "two hundred and six", "a thousand", "one and a half".
+pub fn parse_number(words: &[String]) -> Option<(f64, usize)> {
+    let (sign, mut i) = match word_at(words, 0) {
+        Some("minus") | Some("negative") => (-1.0, 1),
+        _ => (1.0, 0),
+    };
+    
+    if let Some(value) = word_at(words, i).and_then(|w| w.replace(',', "").parse::<f64>().ok()) {
+        let mut value = value;
+        i += 1;
+        if let Some((fraction, used)) = fraction_suffix(&words[i..]) {
+            value += fraction;
+            i += used;
+        }
+        return value.is_finite().then(|| (sign * value, i));
+    }
+    
+    #[derive(PartialEq)]
+    enum Last {
+        Start,
+        Ones,
+        Teen,
+        Tens,
+        Hundred,
+        Scale,
+    }
+    
+    let (mut total, mut current, mut last) = (0u64, 0u64, Last::Start);
+    while let Some(word) = word_at(words, i) {
+        let next = word_at(words, i + 1);
+        if let Some(n) = small_number(word) {
+            let allowed = match last {
+                Last::Start | Last::Hundred | Last::Scale => true,
+                Last::Tens => (1..10).contains(&n),
+                Last::Ones | Last::Teen => false,
+            };
+            if !allowed {
+                break;
+            }
+            current += n;
+            last = if n < 10 { Last::Ones } else { Last::Teen };
+        } else if let Some(n) = tens_number(word) {
+            if !matches!(last, Last::Start | Last::Hundred | Last::Scale) {
+                break;
+            }
+            current += n;
+            last = Last::Tens;
+        } else if word == "hundred" {
+            if !matches!(last, Last::Ones | Last::Teen | Last::Tens) || current >= 100 {
+                break;
+            }
+            current *= 100;
+            last = Last::Hundred;
+        } else if let Some(s) = scale(word) {
+            if !matches!(last, Last::Ones | Last::Teen | Last::Tens | Last::Hundred) {
+                break;
+            }
+            total += current * s;
+            current = 0;
+            last = Last::Scale;
+        } else if (word == "a" || word == "an") && last == Last::Start && next.map_or(false, |w| w == "hundred" || scale(w).is_some()) {
+            current = 1;
+            last = Last::Ones;
+        } else if word == "and" && matches!(last, Last::Hundred | Last::Scale) && next.map_or(false, is_number_word) {
+            // "one hundred and six"
+        } else {
+            break;
+        }
+        i += 1;
+    }
+    if last == Last::Start {
+        return None;
+    }
+    
+    let mut value = (total + current) as f64;
+    if word_at(words, i) == Some("point") {
+        let digits: Vec<u64> = words[i + 1..]
+            .iter()
+            .map_while(|w| if w == "oh" { Some(0) } else { small_number(w).filter(|&n| n < 10) })
+            .collect();
+        if !digits.is_empty() {
+            let fraction: String = digits.iter().map(|d| d.to_string()).collect();
+            value += format!("0.{}", fraction).parse::<f64>().unwrap_or(0.0);
+            i += 1 + digits.len();
+        }
+    } else if let Some((fraction, used)) = fraction_suffix(&words[i..]) {
+        value += fraction;
+        i += used;
+    }
+    Some((sign * value, i))
+}
+
+/// "and a half" / "and a quarter" after a number or unit
+fn fraction_suffix(words: &[String]) -> Option<(f64, usize)> {
+    match (word_at(words, 0), word_at(words, 1), word_at(words, 2)) {
+        (Some("and"), Some("a"), Some("half")) => Some((0.5, 3)),
+        (Some("and"), Some("a"), Some("quarter")) => Some((0.25, 3)),
+        _ => None,
+    }
+}
+
+/// A whole number in `range` at the start of `words`
+fn parse_integer(words: &[String], range: std::ops::RangeInclusive<u64>) -> Option<(u64, usize)> {
+    let (value, used) = parse_number(words)?;
+    (value >= 0.0 && value.fract() == 0.0 && range.contains(&(value as u64))).then(|| (value as u64, used))
+}
+
+fn ordinals() -> &'static HashMap<String, u64> {
+    static ORDINALS: OnceLock<HashMap<String, u64>> = OnceLock::new();
+    ORDINALS.get_or_init(|| (1..100).map(|n| (ordinal_to_words(n), n)).collect())
+}
+
+/// Parse an ordinal ("first", "twenty third", "21st") at the start of `words`
+pub fn parse_ordinal(words: &[String]) -> Option<(u64, usize)> {
+    if words.len() >= 2 {
+        if let Some(&n) = ordinals().get(&format!("{} {}", words[0], words[1])) {
+            return Some((n, 2));
+        }
+    }
+    let word = word_at(words, 0)?;
+    if let Some(&n) = ordinals().get(word) {
+        return Some((n, 1));
+    }
+    let digits = ["st", "nd", "rd", "th"].iter().find_map(|suffix| word.strip_suffix(suffix))?;
+    digits.parse().ok().filter(|&n| n > 0).map(|n| (n, 1))
+}
+
+fn unit_seconds(word: &str) -> Option<f64> {
+    UNITS.iter().find(|(w, _)| *w == word).map(|(_, s)| *s)
+}
+
+/// Parse a duration at the start of `words`, returning seconds, whether it
+/// was relative ("in ten minutes") and the words used
+/// 
+/// Handles "ninety seconds", "an hour and a half", "half an hour", "a
+/// quarter of an hour" and combinations such as "two hours and ten minutes".
+pub fn parse_duration(words: &[String]) -> Option<(f64, bool, usize)> {
+    let relative = word_at(words, 0) == Some("in");
+    let mut i = relative as usize;
+    let mut seconds = 0.0;
+    let mut parts = 0;
+    
+    loop {
+        let part_start = i;
+        if parts > 0 && word_at(words, i) == Some("and") {
+            i += 1;
+        }
+        
+        let part = match (word_at(words, i), word_at(words, i + 1), word_at(words, i + 2), word_at(words, i + 3)) {
+            // "half an hour"
+            (Some("half"), Some("a") | Some("an"), Some(unit), _) if unit_seconds(unit).is_some() => {
+                Some((0.5 * unit_seconds(unit).unwrap(), 3))
+            }
+            // "a quarter of an hour"
+            (Some("a"), Some("quarter"), Some("of"), Some("an")) if word_at(words, i + 4) == Some("hour") => Some((900.0, 5)),
+            (Some("quarter"), Some("of"), Some("an"), Some("hour")) => Some((900.0, 4)),
+            (Some("quarter"), Some("hour"), _, _) => Some((900.0, 2)),
+            _ => {
+                let amount = match word_at(words, i) {
+                    Some("a") | Some("an") => Some((1.0, 1)),
+                    _ => parse_number(&words[i..]),
+                };
+                amount.and_then(|(amount, used)| {
+                    let unit = unit_seconds(word_at(words, i + used)?)?;
+                    let mut length = amount * unit;
+                    let mut used = used + 1;
+                    // "an hour and a half"
+                    if let Some((fraction, extra)) = fraction_suffix(&words[i + used..]) {
+                        length += fraction * unit;
+                        used += extra;
+                    }
+                    Some((length, used))
+                })
+            }
+        };
+        
+        match part {
+            Some((length, used)) if length >= 0.0 => {
+                seconds += length;
+                i += used;
+                parts += 1;
+            }
+            _ => {
+                i = part_start;
+                break;
+            }
+        }
+    }
+    (parts > 0).then(|| (seconds, relative, i))
+}
+
+/// "am"/"pm" and spoken equivalents after a time; true for pm
+fn parse_meridiem(words: &[String]) -> Option<(bool, usize)> {
+    match (word_at(words, 0), word_at(words, 1), word_at(words, 2)) {
+        (Some("am") | Some("a.m"), _, _) => Some((false, 1)),
+        (Some("pm") | Some("p.m"), _, _) => Some((true, 1)),
+        (Some("a"), Some("m"), _) => Some((false, 2)),
+        (Some("p"), Some("m"), _) => Some((true, 2)),
+        (Some("in"), Some("the"), Some("morning")) => Some((false, 3)),
+        (Some("in"), Some("the"), Some("afternoon") | Some("evening")) => Some((true, 3)),
+        (Some("at"), Some("night"), _) | (Some("tonight"), _, _) => Some((true, if word_at(words, 0) == Some("at") { 2 } else { 1 })),
+        _ => None,
+    }
+}
+
+fn apply_meridiem(hour: u32, pm: bool) -> u32 {
+    match (hour, pm) {
+        (12, false) => 0,
+        (h, true) if h < 12 => h + 12,
+        (h, _) => h,
+    }
+}
+
+/// Parse a clock time at the start of `words`, returning the hour, minute and words used
+/// 
+/// Handles "7:15pm", "seven fifteen", "quarter past seven", "ten to
+/// eight", "half past six in the evening", "noon" and "seven o'clock".
+/// With `strict`, bare numbers ("seven fifteen") are only read as a time
+/// when something marks them as one: a colon, "am"/"pm" or "o'clock".
+pub fn parse_time(words: &[String], strict: bool) -> Option<(u32, u32, usize)> {
+    match word_at(words, 0)? {
+        "noon" | "midday" => return Some((12, 0, 1)),
+        "midnight" => return Some((0, 0, 1)),
+        _ => {}
+    }
+    
+    // "quarter past seven", "a quarter to eight", "twenty minutes after six"
+    let offset_start = (word_at(words, 0) == Some("a")) as usize;
+    // Bare numbers must be a multiple of five, so "from two to four" isn't 3:58
+    let offset = match word_at(words, offset_start) {
+        Some("quarter") => Some((15, 1)),
+        Some("half") => Some((30, 1)),
+        _ => parse_integer(&words[offset_start..], 1..=59).and_then(|(m, used)| {
+            let unit = matches!(word_at(words, offset_start + used), Some("minute") | Some("minutes"));
+            (unit || m % 5 == 0).then(|| (m as u32, used + unit as usize))
+        }),
+    };
+    if let Some((minutes, used)) = offset {
+        let i = offset_start + used;
+        let direction = match word_at(words, i) {
+            Some("past") | Some("after") => Some(true),
+            Some("to") | Some("till") | Some("before") => Some(false),
+            _ => None,
+        };
+        if let Some(past) = direction {
+            let hour = match word_at(words, i + 1) {
+                Some("noon") => Some((12, 1)),
+                Some("midnight") => Some((0, 1)),
+                _ => parse_integer(&words[i + 1..], 1..=12).map(|(h, used)| (h as u32, used)),
+            };
+            if let Some((hour, hour_used)) = hour {
+                let mut end = i + 1 + hour_used;
+                let (mut hour, minute) = if past { (hour, minutes) } else { ((hour + 23) % 24, 60 - minutes) };
+                if let Some((pm, used)) = parse_meridiem(&words[end..]) {
+                    hour = apply_meridiem(hour, pm);
+                    end += used;
+                }
+                return Some((hour, minute, end));
+            }
+        }
+    }
+    
+    // "7:15", "7:15pm", "7pm"
+    let word = word_at(words, 0)?;
+    let split = word.find(|c: char| !c.is_ascii_digit() && c != ':').unwrap_or(word.len());
+    let (clock, suffix) = word.split_at(split);
+    if !clock.is_empty() && clock.chars().next().unwrap().is_ascii_digit() && (clock.contains(':') || !suffix.is_empty()) {
+        let (hour, minute) = clock.split_once(':').unwrap_or((clock, "0"));
+        let (hour, minute) = (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?);
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        let (mut hour, mut used) = (hour, 1);
+        let meridiem = if suffix.is_empty() {
+            parse_meridiem(&words[1..]).map(|(pm, extra)| {
+                used += extra;
+                pm
+            })
+        } else {
+            Some(parse_meridiem(&[suffix.to_string()])?.0)
+        };
+        if let Some(pm) = meridiem {
+            hour = apply_meridiem(hour, pm);
+        }
+        return Some((hour, minute, used));
+    }
+    
+    // "seven fifteen pm", "twelve oh five", "seven o'clock"
+    let (hour, mut i) = parse_integer(words, 0..=23)?;
+    let mut hour = hour as u32;
+    let mut marked = false;
+    let mut minute = 0;
+    match word_at(words, i) {
+        Some("o'clock") => {
+            marked = true;
+            i += 1;
+        }
+        Some("oh") => {
+            if let Some(m) = word_at(words, i + 1).and_then(small_number).filter(|m| (1..10).contains(m)) {
+                minute = m as u32;
+                i += 2;
+            }
+        }
+        Some("hundred") => {
+            marked = true;
+            i += 1;
+        }
+        _ => {
+            if let Some((m, used)) = parse_integer(&words[i..], 10..=59) {
+                minute = m as u32;
+                i += used;
+            }
+        }
+    }
+    if let Some((pm, used)) = parse_meridiem(&words[i..]) {
+        hour = apply_meridiem(hour, pm);
+        marked = true;
+        i += used;
+    }
+    (marked || !strict).then(|| (hour, minute, i))
+}
+
+fn month_number(word: &str) -> Option<u32> {
+    let word = word.strip_suffix('.').unwrap_or(word);
+    MONTHS
+        .iter()
+        .position(|m| m.eq_ignore_ascii_case(word) || (word.len() == 3 && m[..3].eq_ignore_ascii_case(word)))
+        .map(|m| m as u32 + 1)
+}
+
+/// Day of the month as an ordinal or a number
+fn parse_day(words: &[String]) -> Option<(u32, usize)> {
+    parse_ordinal(words)
+        .filter(|(n, _)| (1..=31).contains(n))
+        .or_else(|| parse_integer(words, 1..=31))
+        .map(|(day, used)| (day as u32, used))
+}
+
+/// A year as digits, "two thousand twenty four" or "nineteen eighty four"
+fn parse_year(words: &[String]) -> Option<(i32, usize)> {
+    let (value, used) = parse_integer(words, 0..=9999)?;
+    if (1000..=9999).contains(&value) {
+        return Some((value as i32, used));
+    }
+    if !(10..=99).contains(&value) {
+        return None;
+    }
+    let rest = &words[used..];
+    if word_at(rest, 0) == Some("oh") {
+        let ones = word_at(rest, 1).and_then(small_number).filter(|n| (1..10).contains(n))?;
+        return Some(((value * 100 + ones) as i32, used + 2));
+    }
+    let (tail, tail_used) = parse_integer(rest, 10..=99)?;
+    Some(((value * 100 + tail) as i32, used + tail_used))
+}
+
+/// Parse a date at the start of `words`
+/// 
+/// Handles "today", "tomorrow", "the day after tomorrow", weekdays, ISO
+/// dates, "march fifth [twenty twenty four]" and "the fifth of march".
+pub fn parse_date(words: &[String]) -> Option<(DateValue, usize)> {
+    let relative = |days: i64, used: usize| Some((DateValue { relative_days: Some(days), ..Default::default() }, used));
+    match (word_at(words, 0)?, word_at(words, 1), word_at(words, 2), word_at(words, 3)) {
+        ("today", _, _, _) => return relative(0, 1),
+        ("tomorrow", _, _, _) => return relative(1, 1),
+        ("yesterday", _, _, _) => return relative(-1, 1),
+        ("the", Some("day"), Some("after"), Some("tomorrow")) => return relative(2, 4),
+        ("the", Some("day"), Some("before"), Some("yesterday")) => return relative(-2, 4),
+        _ => {}
+    }
+    
+    let skip = matches!(word_at(words, 0), Some("next") | Some("this")) as usize;
+    if let Some(weekday) = word_at(words, skip).and_then(|w| WEEKDAYS.iter().position(|&d| d == w)) {
+        return Some((DateValue { weekday: Some(weekday as u32), ..Default::default() }, skip + 1));
+    }
+    
+    // "2024-03-05"
+    let parts: Vec<&str> = words[0].split('-').collect();
+    if parts.len() == 3 && parts[0].len() == 4 {
+        if let (Ok(year), Ok(month), Ok(day)) = (parts[0].parse(), parts[1].parse(), parts[2].parse()) {
+            if (1..=12).contains(&month) && (1..=31).contains(&day) {
+                return Some((DateValue { year: Some(year), month: Some(month), day: Some(day), ..Default::default() }, 1));
+            }
+        }
+    }
+    
+    let (month, day, mut i) = if let Some(month) = month_number(&words[0]) {
+        // "march fifth", "march the fifth"
+        let skip = 1 + (word_at(words, 1) == Some("the")) as usize;
+        let (day, used) = parse_day(&words[skip..])?;
+        (month, day, skip + used)
+    } else {
+        // "the fifth of march"
+        let skip = (word_at(words, 0) == Some("the")) as usize;
+        let (day, used) = parse_day(&words[skip..])?;
+        let mut i = skip + used;
+        if word_at(words, i) == Some("of") {
+            i += 1;
+        }
+        let month = word_at(words, i).and_then(month_number)?;
+        (month, day, i + 1)
+    };
+    
+    let mut date = DateValue { month: Some(month), day: Some(day), ..Default::default() };
+    if let Some((year, used)) = parse_year(&words[i..]) {
+        date.year = Some(year);
+        i += used;
+    }
+    Some((date, i))
+}
+
+/// Parse one kind of entity at the start of `words`
+/// 
+/// Times are parsed leniently, as a slot typed `time` already says the
+/// words are one.
+pub fn parse_kind(kind: EntityKind, words: &[String]) -> Option<(EntityValue, usize)> {
+    if words.is_empty() {
+        return None;
+    }
+    match kind {
+        EntityKind::Number => parse_number(words).map(|(value, used)| (EntityValue::Number(value), used)),
+        EntityKind::Ordinal => parse_ordinal(words).map(|(value, used)| (EntityValue::Ordinal(value), used)),
+        EntityKind::Duration => {
+            parse_duration(words).map(|(seconds, relative, used)| (EntityValue::Duration { seconds, relative }, used))
+        }
+        EntityKind::Time => parse_time(words, false).map(|(hour, minute, used)| (EntityValue::Time { hour, minute }, used)),
+        EntityKind::Date => parse_date(words).map(|(date, used)| (EntityValue::Date(date), used)),
+    }
+}
+
+/// Find entities of the given kinds in already-tokenized words
+/// 
+/// Scans left to right, taking the longest entity starting at each word;
+/// ties go to the kind listed first in `EntityKind::ALL`. A bare time
+/// such as "seven thirty" is only taken when preceded by "at", "by",
+/// "until" or "from".
+pub fn extract(words: &[String], kinds: &[EntityKind]) -> Vec<Entity> {
+    let mut entities = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let mut best: Option<(EntityValue, usize)> = None;
+        for kind in EntityKind::ALL.iter().filter(|kind| kinds.contains(kind)) {
+            let found = match kind {
+                EntityKind::Time => {
+                    let cued = i > 0 && matches!(words[i - 1].as_str(), "at" | "by" | "until" | "from");
+                    parse_time(&words[i..], !cued).map(|(hour, minute, used)| (EntityValue::Time { hour, minute }, used))
+                }
+                kind => parse_kind(*kind, &words[i..]),
+            };
+            if let Some((value, used)) = found {
+                if best.as_ref().map_or(true, |(_, best_used)| used > *best_used) {
+                    best = Some((value, used));
+                }
+            }
+        }
+        
+        match best {
+            Some((value, used)) => {
+                entities.push(Entity { text: words[i..i + used].join(" "), start: i, end: i + used, value });
+                i += used;
+            }
+            None => i += 1,
+        }
+    }
+    entities
+}
+
+fn parse_kinds(kinds: Option<Vec<String>>) -> PyResult<Vec<EntityKind>> {
+    match kinds {
+        None => Ok(EntityKind::ALL.to_vec()),
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                EntityKind::from_name(name).ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "Unknown entity kind '{}'; expected number, ordinal, duration, time or date",
+                        name
+                    ))
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Extract numbers, ordinals, durations, times and dates from a transcript
+/// 
+/// `kinds` limits the search, e.g. `["duration", "time"]`. Entity `start`
+/// and `end` count words after lowercasing and stripping punctuation.
+#[pyfunction]
+#[pyo3(signature = (text, kinds=None))]
+pub fn extract_entities(text: &str, kinds: Option<Vec<String>>) -> PyResult<Vec<Entity>> {
+    let kinds = parse_kinds(kinds)?;
+    Ok(extract(&tokenize(text), &kinds))
+}
+
+/// First number in a transcript ("minus three point five" is -3.5), or None
+#[pyfunction]
+#[pyo3(name = "parse_number")]
+pub fn py_parse_number(text: &str) -> Option<f64> {
+    match extract(&tokenize(text), &[EntityKind::Number]).first()?.value {
+        EntityValue::Number(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// First duration in a transcript, in seconds ("in twenty minutes" is 1200.0), or None
+#[pyfunction]
+#[pyo3(name = "parse_duration")]
+pub fn py_parse_duration(text: &str) -> Option<f64> {
+    match extract(&tokenize(text), &[EntityKind::Duration]).first()?.value {
+        EntityValue::Duration { seconds, .. } => Some(seconds),
+        _ => None,
+    }
+}
+
+/// First clock time in a transcript as `(hour, minute)`, or None
+/// 
+/// "quarter past seven pm" is `(19, 15)`. Without "am"/"pm" the hour is
+/// kept as spoken, so "seven thirty" is `(7, 30)`. Unlike
+/// `extract_entities`, bare numbers are read as a time when nothing in
+/// the text is more clearly one.
+#[pyfunction]
+#[pyo3(name = "parse_time")]
+pub fn py_parse_time(text: &str) -> Option<(u32, u32)> {
+    let words = tokenize(text);
+    if let Some(EntityValue::Time { hour, minute }) = extract(&words, &[EntityKind::Time]).first().map(|e| e.value) {
+        return Some((hour, minute));
+    }
+    (0..words.len()).find_map(|i| parse_time(&words[i..], false).map(|(hour, minute, _)| (hour, minute)))
+}
@@ -0,0 +1,37 @@
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+
+create_exception!(
+    jenna_rust,
+    JennaError,
+    PyException,
+    "Base class for errors raised by the Jenna Rust modules."
+);
+
+create_exception!(
+    jenna_rust,
+    DeviceError,
+    JennaError,
+    "Raised when an audio device is missing, busy, denied by the OS or fails while streaming."
+);
+
+create_exception!(
+    jenna_rust,
+    ModelError,
+    JennaError,
+    "Raised when a model is missing, corrupt, fails to load or fails to download."
+);
+
+create_exception!(
+    jenna_rust,
+    NotInitializedError,
+    JennaError,
+    "Raised when a component is used before `initialize` succeeded or after `release`."
+);
+
+create_exception!(
+    jenna_rust,
+    LicenseError,
+    JennaError,
+    "Raised when an engine rejects its access key or license."
+);
@@ -0,0 +1,358 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Mutex, OnceLock};
+use std::thread::{self, JoinHandle};
+
+use crate::assistant::StateChange;
+use crate::lifecycle;
+use crate::panics;
+use crate::pipeline::PipelineEvent;
+use crate::speech::RecognitionResult;
+use crate::wake_word::{unix_time, WakeWordDetection};
+
+/// Events waiting for the dispatcher; posts beyond this are dropped and counted
+const EVENT_BACKLOG: usize = 1024;
+
+/// Event types posted by the Rust components
+pub const EVENT_TYPES: &[&str] = &[
+    "audio.error",
+    "audio.stalled",
+    "audio.recovered",
+    "thread.panicked",
+    "wake_word.detected",
+    "vad.speech_start",
+    "vad.speech_end",
+    "audio.class",
+    "presence.changed",
+    "privacy.changed",
+    "speech.final",
+    "pipeline.event",
+    "websocket.event",
+    "assistant.state",
+];
+
+/// Payload carried by an event
+pub enum EventData {
+    None,
+    Text(String),
+    WakeWord(WakeWordDetection),
+    Recognition(RecognitionResult),
+    Pipeline(PipelineEvent),
+    State(StateChange),
+    Object(PyObject),
+}
+
+impl EventData {
+    fn into_object(self, py: Python) -> PyResult<PyObject> {
+        Ok(match self {
+            EventData::None => py.None(),
+            EventData::Text(text) => text.into_py(py),
+            EventData::WakeWord(detection) => Py::new(py, detection)?.into_py(py),
+            EventData::Recognition(result) => Py::new(py, result)?.into_py(py),
+            EventData::Pipeline(event) => Py::new(py, event)?.into_py(py),
+            EventData::State(change) => Py::new(py, change)?.into_py(py),
+            EventData::Object(object) => object,
+        })
+    }
+}
+
+/// A typed event posted by a Rust component
+pub struct Event {
+    pub kind: &'static str,
+    pub source: String,
+    pub data: EventData,
+    pub timestamp: f64,
+}
+
+impl Event {
+    pub fn new(kind: &'static str, source: &str, data: EventData) -> Self {
+        Event { kind, source: source.to_string(), data, timestamp: unix_time() }
+    }
+}
+
+/// An event as delivered to Python callbacks
+#[pyclass]
+#[derive(Clone)]
+pub struct BusEvent {
+    /// Event type, e.g. "wake_word.detected"
+    #[pyo3(get)]
+    pub kind: String,
+    /// Component that posted the event
+    #[pyo3(get)]
+    pub source: String,
+    /// Event payload (a detection, recognition result, message or None)
+    #[pyo3(get)]
+    pub data: PyObject,
+    /// Wall-clock time the event was posted, in seconds since the Unix epoch
+    #[pyo3(get)]
+    pub timestamp: f64,
+}
+
+#[pymethods]
+impl BusEvent {
+    fn __repr__(&self) -> String {
+        format!("BusEvent(kind={:?}, source={:?}, timestamp={:.3})", self.kind, self.source, self.timestamp)
+    }
+}
+
+/// Rust handler for events; runs on the dispatcher thread without the GIL
+pub type Handler = Box<dyn Fn(&Event) + Send + Sync>;
+
+/// Where a subscription's events go
+enum Target {
+    Python(PyObject),
+    Rust(Handler),
+}
+
+struct Subscription {
+    id: u64,
+    pattern: String,
+    target: Target,
+}
+
+/// Process-wide event bus; the dispatcher thread starts with the first subscription
+struct Bus {
+    sender: Mutex<Option<SyncSender<Event>>>,
+    dispatcher: Mutex<Option<JoinHandle<()>>>,
+    subscriptions: Mutex<Vec<Subscription>>,
+    active: AtomicBool,
+    next_id: AtomicU64,
+    delivered: AtomicU64,
+    dropped: AtomicU64,
+}
+
+static BUS: OnceLock<Bus> = OnceLock::new();
+
+fn bus() -> &'static Bus {
+    BUS.get_or_init(|| Bus {
+        sender: Mutex::new(None),
+        dispatcher: Mutex::new(None),
+        subscriptions: Mutex::new(Vec::new()),
+        active: AtomicBool::new(false),
+        next_id: AtomicU64::new(1),
+        delivered: AtomicU64::new(0),
+        dropped: AtomicU64::new(0),
+    })
+}
+
+/// "*" matches everything, "vad.*" matches every "vad." event
+fn pattern_matches(pattern: &str, kind: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => kind.starts_with(prefix),
+        None => pattern == kind,
+    }
+}
+
+/// Post an event for delivery to Python subscribers
+/// 
+/// Never blocks, so it is safe to call from audio callbacks. Events are
+/// discarded when nobody is subscribed or the dispatcher is backed up.
+pub fn post(event: Event) {
+    let bus = bus();
+    if !bus.active.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Some(sender) = bus.sender.lock().unwrap().as_ref() {
+        if let Err(TrySendError::Full(_)) = sender.try_send(event) {
+            bus.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Deliver queued events and stop the dispatcher thread
+/// 
+/// Subscriptions are kept, but events posted afterwards are discarded
+/// until the next `subscribe` starts a new dispatcher. Call without the GIL.
+pub fn shutdown() {
+    let bus = bus();
+    // Dropping the sender lets the dispatcher drain the queue and exit
+    bus.sender.lock().unwrap().take();
+    let dispatcher = bus.dispatcher.lock().unwrap().take();
+    lifecycle::join_threads(dispatcher);
+}
+
+/// Deliver events to matching handlers, holding the GIL only while calling Python callbacks
+fn dispatch(receiver: &mpsc::Receiver<Event>) {
+    for event in receiver.iter() {
+        let mut python = false;
+        for subscription in bus().subscriptions.lock().unwrap().iter() {
+            if pattern_matches(&subscription.pattern, event.kind) {
+                match &subscription.target {
+                    Target::Rust(handler) => handler(&event),
+                    Target::Python(_) => python = true,
+                }
+            }
+        }
+        if !python {
+            continue;
+        }
+        
+        Python::with_gil(|py| {
+            let callbacks: Vec<PyObject> = bus()
+                .subscriptions
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|s| pattern_matches(&s.pattern, event.kind))
+                .filter_map(|s| match &s.target {
+                    Target::Python(callback) => Some(callback.clone_ref(py)),
+                    Target::Rust(_) => None,
+                })
+                .collect();
+            if callbacks.is_empty() {
+                return;
+            }
+            
+            let data = match event.data.into_object(py) {
+                Ok(data) => data,
+                Err(err) => {
+                    err.print(py);
+                    return;
+                }
+            };
+            let delivered = BusEvent { kind: event.kind.to_string(), source: event.source, data, timestamp: event.timestamp };
+            for callback in callbacks {
+                if let Err(err) = callback.call1(py, (delivered.clone(),)) {
+                    err.print(py);
+                }
+            }
+            bus().delivered.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+}
+
+/// Start the dispatcher thread if it isn't running
+fn ensure_dispatcher() -> Result<(), String> {
+    let bus = bus();
+    let mut sender = bus.sender.lock().unwrap();
+    if sender.is_none() {
+        let (tx, rx) = mpsc::sync_channel::<Event>(EVENT_BACKLOG);
+        let handle = thread::Builder::new()
+            .name("jenna-events".to_string())
+            .spawn(move || {
+                // A Rust handler that panics loses its event; the queued ones are still delivered
+                panics::supervise(
+                    "Event dispatcher",
+                    || {
+                        dispatch(&rx);
+                        Ok(())
+                    },
+                    |_, restart| {
+                        bus().subscriptions.clear_poison();
+                        restart.map_or(false, |delay| {
+                            thread::sleep(delay);
+                            true
+                        })
+                    },
+                );
+            })
+            .map_err(|err| format!("Failed to start event dispatcher: {}", err))?;
+        *sender = Some(tx);
+        *bus.dispatcher.lock().unwrap() = Some(handle);
+    }
+    Ok(())
+}
+
+fn add_subscription(pattern: String, target: Target) -> Result<u64, String> {
+    ensure_dispatcher()?;
+    let bus = bus();
+    let id = bus.next_id.fetch_add(1, Ordering::Relaxed);
+    bus.subscriptions.lock().unwrap().push(Subscription { id, pattern, target });
+    bus.active.store(true, Ordering::Relaxed);
+    Ok(id)
+}
+
+/// Call `handler` for events matching `pattern`, returning a subscription id
+/// 
+/// For Rust components that forward events elsewhere. Handlers run one at
+/// a time on the dispatcher thread with the subscription list locked, so
+/// they must not block for long or (un)subscribe themselves. Patterns
+/// work as in `EventBus.subscribe`.
+pub fn subscribe(pattern: &str, handler: Handler) -> Result<u64, String> {
+    add_subscription(pattern.to_string(), Target::Rust(handler))
+}
+
+/// Remove a subscription, returning whether it existed
+pub fn unsubscribe(subscription_id: u64) -> bool {
+    let bus = bus();
+    let mut subscriptions = bus.subscriptions.lock().unwrap();
+    let before = subscriptions.len();
+    subscriptions.retain(|s| s.id != subscription_id);
+    bus.active.store(!subscriptions.is_empty(), Ordering::Relaxed);
+    subscriptions.len() != before
+}
+
+/// Registry for Python callbacks on events posted by the Rust components
+/// 
+/// Callbacks run one at a time on a dedicated dispatcher thread that takes
+/// the GIL, never on audio threads. All instances share one process-wide bus.
+#[pyclass]
+pub struct EventBus {}
+
+#[pymethods]
+impl EventBus {
+    #[new]
+    fn new() -> Self {
+        EventBus {}
+    }
+    
+    /// Call `callback(event)` for events of `event_type`, returning a subscription id
+    /// 
+    /// `event_type` is an exact type, a prefix ending in "*" (e.g. "vad.*")
+    /// or "*" for everything.
+    fn subscribe(&self, py: Python, event_type: String, callback: PyObject) -> PyResult<u64> {
+        if !callback.as_ref(py).is_callable() {
+            return Err(PyValueError::new_err("Callback must be callable"));
+        }
+        
+        add_subscription(event_type, Target::Python(callback)).map_err(PyValueError::new_err)
+    }
+    
+    /// Remove a subscription, returning whether it existed
+    fn unsubscribe(&self, subscription_id: u64) -> bool {
+        unsubscribe(subscription_id)
+    }
+    
+    /// Remove every Python subscription
+    /// 
+    /// Subscriptions held by Rust components (such as an `MqttBridge`) stay.
+    fn clear(&self) -> PyResult<()> {
+        let bus = bus();
+        let mut subscriptions = bus.subscriptions.lock().unwrap();
+        subscriptions.retain(|s| matches!(s.target, Target::Rust(_)));
+        bus.active.store(!subscriptions.is_empty(), Ordering::Relaxed);
+        Ok(())
+    }
+    
+    /// Post an event from Python; it is delivered like any Rust event
+    #[pyo3(signature = (event_type, data=None, source="python"))]
+    fn post(&self, event_type: &str, data: Option<PyObject>, source: &str) -> PyResult<()> {
+        let kind = EVENT_TYPES
+            .iter()
+            .copied()
+            .find(|known| *known == event_type)
+            .ok_or_else(|| PyValueError::new_err(format!("Unknown event type: {}", event_type)))?;
+        let data = data.map(EventData::Object).unwrap_or(EventData::None);
+        post(Event::new(kind, source, data));
+        Ok(())
+    }
+    
+    /// List the event types posted by the Rust components
+    #[staticmethod]
+    fn event_types() -> Vec<&'static str> {
+        EVENT_TYPES.to_vec()
+    }
+    
+    /// Get bus counters: subscriptions, delivered and dropped
+    fn get_stats(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let bus = bus();
+        let dict = PyDict::new(py);
+        dict.set_item("subscriptions", bus.subscriptions.lock().unwrap().len())?;
+        dict.set_item("delivered", bus.delivered.load(Ordering::Relaxed))?;
+        dict.set_item("dropped", bus.dropped.load(Ordering::Relaxed))?;
+        Ok(dict.into())
+    }
+}
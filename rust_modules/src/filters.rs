@@ -0,0 +1,265 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use jenna_core::iir;
+
+pub use jenna_core::iir::{design_sos, BandParams, BandShape, BandType, FilterFamily, Section};
+
+/// Design an IIR filter and return it as second-order sections
+/// 
+/// `kind` is "butterworth" or "chebyshev1", `band` is "lowpass", "highpass",
+/// "bandpass" or "bandstop". Each returned section is
+/// `[b0, b1, b2, a0, a1, a2]` and can be passed directly to `SosFilter`.
+#[pyfunction]
+#[pyo3(signature = (kind, order, cutoffs, sample_rate, band="lowpass", ripple_db=1.0))]
+pub fn design_filter(
+    kind: &str,
+    order: usize,
+    cutoffs: Vec<f64>,
+    sample_rate: f64,
+    band: &str,
+    ripple_db: f64,
+) -> PyResult<Vec<Vec<f64>>> {
+    let family = FilterFamily::from_name(kind, ripple_db).map_err(PyValueError::new_err)?;
+    
+    let band = BandType::from_name(band).map_err(PyValueError::new_err)?;
+    let sos = design_sos(family, band, order, &cutoffs, sample_rate).map_err(PyValueError::new_err)?;
+    Ok(sos.iter().map(|section| section.to_vec()).collect())
+}
+
+/// Streaming filter made of cascaded second-order sections
+/// 
+/// Uses the transposed direct form II and keeps its state between calls,
+/// so audio can be filtered frame by frame without discontinuities.
+#[pyclass]
+pub struct SosFilter {
+    inner: iir::SosFilter,
+}
+
+impl SosFilter {
+    /// Build a filter from sections, normalising each one by `a0`
+    pub fn from_sections(sections: Vec<Section>) -> Result<Self, String> {
+        Ok(SosFilter { inner: iir::SosFilter::from_sections(sections)? })
+    }
+    
+    /// Filter a buffer in place
+    pub fn process_in_place(&mut self, buffer: &mut [f32]) {
+        self.inner.process_in_place(buffer);
+    }
+}
+
+fn parse_sections(sos: Vec<Vec<f64>>) -> PyResult<Vec<Section>> {
+    sos.into_iter()
+        .map(|section| {
+            <[f64; 6]>::try_from(section.as_slice())
+                .map_err(|_| PyValueError::new_err("Each section must have exactly 6 coefficients"))
+        })
+        .collect()
+}
+
+#[pymethods]
+impl SosFilter {
+    /// Create a streaming filter from second-order sections
+    #[new]
+    fn new(sos: Vec<Vec<f64>>) -> PyResult<Self> {
+        SosFilter::from_sections(parse_sections(sos)?).map_err(PyValueError::new_err)
+    }
+    
+    /// Filter a block of samples, continuing from the previous block
+    fn process(&mut self, py: Python, samples: Vec<f32>) -> PyResult<Vec<f32>> {
+        let mut output = samples;
+        py.allow_threads(|| self.process_in_place(&mut output));
+        Ok(output)
+    }
+    
+    /// Replace the filter coefficients, keeping the state if the section count is unchanged
+    fn set_sections(&mut self, sos: Vec<Vec<f64>>) -> PyResult<()> {
+        self.inner.set_sections(parse_sections(sos)?).map_err(PyValueError::new_err)
+    }
+    
+    /// Clear the filter state
+    fn reset(&mut self) -> PyResult<()> {
+        self.inner.reset();
+        Ok(())
+    }
+    
+    /// Get the number of second-order sections
+    fn num_sections(&self) -> usize {
+        self.inner.num_sections()
+    }
+}
+
+/// Number of samples between coefficient updates while a band is ramping
+const EQ_UPDATE_INTERVAL: usize = 32;
+
+/// An equalizer band that glides towards new parameters
+struct EqBand {
+    current: BandParams,
+    target: BandParams,
+    ramp_remaining: usize,
+    coefficients: Section,
+    state: [f64; 2],
+}
+
+impl EqBand {
+    fn new(params: BandParams, sample_rate: f64) -> Self {
+        EqBand {
+            current: params,
+            target: params,
+            ramp_remaining: 0,
+            coefficients: params.coefficients(sample_rate),
+            state: [0.0; 2],
+        }
+    }
+    
+    /// Advance the parameter ramp by one update interval
+    fn step_ramp(&mut self, sample_rate: f64) {
+        if self.ramp_remaining == 0 {
+            return;
+        }
+        
+        // Interpolate frequency geometrically and gain/Q linearly
+        let t = 1.0 / self.ramp_remaining as f64;
+        self.current.frequency *= (self.target.frequency / self.current.frequency).powf(t);
+        self.current.gain_db += (self.target.gain_db - self.current.gain_db) * t;
+        self.current.q += (self.target.q - self.current.q) * t;
+        self.ramp_remaining -= 1;
+        if self.ramp_remaining == 0 {
+            self.current = self.target;
+        }
+        self.coefficients = self.current.coefficients(sample_rate);
+    }
+    
+    #[inline]
+    fn process_sample(&mut self, x: f64) -> f64 {
+        let s = &self.coefficients;
+        let y = s[0] * x + self.state[0];
+        self.state[0] = s[1] * x - s[4] * y + self.state[1];
+        self.state[1] = s[2] * x - s[5] * y;
+        y
+    }
+}
+
+/// Multiband parametric equalizer
+/// 
+/// Each band is a peaking or shelving biquad. Parameter changes glide over
+/// a short ramp so live adjustments do not click. Use one instance on the
+/// capture chain for microphone correction and another on the playback chain.
+#[pyclass]
+pub struct Equalizer {
+    #[pyo3(get)]
+    sample_rate: f64,
+    ramp_updates: usize,
+    bands: Vec<EqBand>,
+}
+
+impl Equalizer {
+    fn validate(&self, params: &BandParams) -> PyResult<()> {
+        if params.frequency <= 0.0 || params.frequency >= self.sample_rate / 2.0 {
+            return Err(PyValueError::new_err(format!(
+                "Band frequency must be between 0 and {} Hz", self.sample_rate / 2.0
+            )));
+        }
+        if params.q <= 0.0 {
+            return Err(PyValueError::new_err("Band Q must be greater than zero"));
+        }
+        Ok(())
+    }
+    
+    fn band_mut(&mut self, index: usize) -> PyResult<&mut EqBand> {
+        self.bands
+            .get_mut(index)
+            .ok_or_else(|| PyValueError::new_err(format!("No equalizer band at index {}", index)))
+    }
+    
+    /// Equalize a buffer in place
+    pub fn process_in_place(&mut self, buffer: &mut [f32]) {
+        let sample_rate = self.sample_rate;
+        for chunk in buffer.chunks_mut(EQ_UPDATE_INTERVAL) {
+            for band in self.bands.iter_mut() {
+                band.step_ramp(sample_rate);
+                for sample in chunk.iter_mut() {
+                    *sample = band.process_sample(*sample as f64) as f32;
+                }
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl Equalizer {
+    /// Create an empty equalizer
+    #[new]
+    #[pyo3(signature = (sample_rate, ramp_ms=20.0))]
+    fn new(sample_rate: f64, ramp_ms: f64) -> PyResult<Self> {
+        if sample_rate <= 0.0 {
+            return Err(PyValueError::new_err("Sample rate must be greater than zero"));
+        }
+        
+        let ramp_samples = (ramp_ms.max(0.0) / 1000.0 * sample_rate) as usize;
+        Ok(Equalizer {
+            sample_rate,
+            ramp_updates: (ramp_samples / EQ_UPDATE_INTERVAL).max(1),
+            bands: Vec::new(),
+        })
+    }
+    
+    /// Add a band and return its index
+    /// 
+    /// `shape` is "peaking", "lowshelf" or "highshelf".
+    #[pyo3(signature = (shape, frequency, gain_db, q=0.707))]
+    fn add_band(&mut self, shape: &str, frequency: f64, gain_db: f64, q: f64) -> PyResult<usize> {
+        let params = BandParams { shape: BandShape::from_name(shape).map_err(PyValueError::new_err)?, frequency, gain_db, q };
+        self.validate(&params)?;
+        self.bands.push(EqBand::new(params, self.sample_rate));
+        Ok(self.bands.len() - 1)
+    }
+    
+    /// Update band parameters; changes are applied smoothly
+    #[pyo3(signature = (index, frequency=None, gain_db=None, q=None))]
+    fn set_band(&mut self, index: usize, frequency: Option<f64>, gain_db: Option<f64>, q: Option<f64>) -> PyResult<()> {
+        let ramp_updates = self.ramp_updates;
+        let mut target = self.band_mut(index)?.target;
+        target.frequency = frequency.unwrap_or(target.frequency);
+        target.gain_db = gain_db.unwrap_or(target.gain_db);
+        target.q = q.unwrap_or(target.q);
+        self.validate(&target)?;
+        
+        let band = self.band_mut(index)?;
+        band.target = target;
+        band.ramp_remaining = ramp_updates;
+        Ok(())
+    }
+    
+    /// Remove a band; indices of later bands shift down by one
+    fn remove_band(&mut self, index: usize) -> PyResult<()> {
+        self.band_mut(index)?;
+        self.bands.remove(index);
+        Ok(())
+    }
+    
+    /// Get all bands as `(shape, frequency, gain_db, q)` tuples
+    fn get_bands(&self) -> Vec<(String, f64, f64, f64)> {
+        self.bands
+            .iter()
+            .map(|band| {
+                let p = band.target;
+                (p.shape.as_str().to_string(), p.frequency, p.gain_db, p.q)
+            })
+            .collect()
+    }
+    
+    /// Equalize a block of samples
+    fn process(&mut self, py: Python, samples: Vec<f32>) -> PyResult<Vec<f32>> {
+        let mut output = samples;
+        py.allow_threads(|| self.process_in_place(&mut output));
+        Ok(output)
+    }
+    
+    /// Clear the filter state of all bands
+    fn reset(&mut self) -> PyResult<()> {
+        for band in self.bands.iter_mut() {
+            band.state = [0.0; 2];
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,440 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, UNIX_EPOCH};
+use jenna_core::fingerprint::{align, fingerprint, Landmark, FINGERPRINT_RATE, FRAME_SECONDS};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::audio::{read_audio_file_mono, resample_mono, AudioError};
+use crate::storage::StorageError;
+
+/// Schema version stored in `user_version`; bump when the tables change
+const FINGERPRINT_SCHEMA_VERSION: i32 = 1;
+
+const FINGERPRINT_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS tracks (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    artist TEXT,
+    duration REAL NOT NULL,
+    modified INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS landmarks (
+    hash INTEGER NOT NULL,
+    track_id INTEGER NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    frame INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS landmarks_hash ON landmarks(hash);
+CREATE INDEX IF NOT EXISTS landmarks_track ON landmarks(track_id);
+";
+
+/// How long a write waits for another process holding the database
+const FINGERPRINT_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Landmarks that must line up before a track counts as a match
+const MIN_ALIGNED: usize = 8;
+
+/// File extensions `index_library` reads
+const LIBRARY_EXTENSIONS: &[&str] = &["wav", "wave", "flac"];
+
+/// A track stored in the fingerprint database
+#[derive(Clone, Debug)]
+pub struct TrackRecord {
+    pub id: i64,
+    pub path: String,
+    pub title: String,
+    pub artist: Option<String>,
+    pub duration: f64,
+    pub modified: i64,
+}
+
+/// SQLite store of track landmarks
+pub struct FingerprintStore {
+    connection: Mutex<Connection>,
+}
+
+impl FingerprintStore {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        
+        let connection = Connection::open(path)?;
+        connection.busy_timeout(FINGERPRINT_BUSY_TIMEOUT)?;
+        connection.pragma_update(None, "foreign_keys", "ON")?;
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        
+        let version: i32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > FINGERPRINT_SCHEMA_VERSION {
+            return Err(StorageError::SchemaVersion { found: version, supported: FINGERPRINT_SCHEMA_VERSION });
+        }
+        connection.execute_batch(FINGERPRINT_SCHEMA)?;
+        connection.pragma_update(None, "user_version", FINGERPRINT_SCHEMA_VERSION)?;
+        
+        Ok(FingerprintStore { connection: Mutex::new(connection) })
+    }
+    
+    /// Store a track's landmarks, replacing any earlier entry for the same path
+    pub fn add_track(
+        &self,
+        path: &str,
+        title: &str,
+        artist: Option<&str>,
+        duration: f64,
+        modified: i64,
+        landmarks: &[Landmark],
+    ) -> Result<i64, StorageError> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute("DELETE FROM tracks WHERE path = ?1", params![path])?;
+        transaction.execute(
+            "INSERT INTO tracks (path, title, artist, duration, modified) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![path, title, artist, duration, modified],
+        )?;
+        let id = transaction.last_insert_rowid();
+        {
+            let mut insert = transaction.prepare_cached("INSERT INTO landmarks (hash, track_id, frame) VALUES (?1, ?2, ?3)")?;
+            for landmark in landmarks {
+                insert.execute(params![landmark.hash, id, landmark.frame])?;
+            }
+        }
+        transaction.commit()?;
+        Ok(id)
+    }
+    
+    /// Modification time stored for a path, None if it isn't indexed
+    pub fn modified(&self, path: &str) -> Result<Option<i64>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        Ok(connection
+            .query_row("SELECT modified FROM tracks WHERE path = ?1", params![path], |row| row.get(0))
+            .optional()?)
+    }
+    
+    /// Remove a track, returning whether it was indexed
+    pub fn remove(&self, path: &str) -> Result<bool, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        Ok(connection.execute("DELETE FROM tracks WHERE path = ?1", params![path])? > 0)
+    }
+    
+    pub fn tracks(&self) -> Result<Vec<TrackRecord>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement =
+            connection.prepare("SELECT id, path, title, artist, duration, modified FROM tracks ORDER BY artist, title")?;
+        let tracks = statement
+            .query_map([], |row| {
+                Ok(TrackRecord {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                    title: row.get(2)?,
+                    artist: row.get(3)?,
+                    duration: row.get(4)?,
+                    modified: row.get(5)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(tracks)
+    }
+    
+    pub fn track(&self, id: i64) -> Result<Option<TrackRecord>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        Ok(connection
+            .query_row(
+                "SELECT id, path, title, artist, duration, modified FROM tracks WHERE id = ?1",
+                params![id],
+                |row| {
+                    Ok(TrackRecord {
+                        id: row.get(0)?,
+                        path: row.get(1)?,
+                        title: row.get(2)?,
+                        artist: row.get(3)?,
+                        duration: row.get(4)?,
+                        modified: row.get(5)?,
+                    })
+                },
+            )
+            .optional()?)
+    }
+    
+    /// Every stored (track, track frame, query frame) sharing a hash with the query
+    pub fn lookup(&self, landmarks: &[Landmark]) -> Result<Vec<(i64, u32, u32)>, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare_cached("SELECT track_id, frame FROM landmarks WHERE hash = ?1")?;
+        let mut hits = Vec::new();
+        for landmark in landmarks {
+            let rows = statement.query_map(params![landmark.hash], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            for row in rows {
+                let (track, frame): (i64, u32) = row?;
+                hits.push((track, frame, landmark.frame));
+            }
+        }
+        Ok(hits)
+    }
+}
+
+/// Fingerprint mono audio at any sample rate
+fn landmarks_of(samples: &[f32], sample_rate: u32) -> Result<Vec<Landmark>, AudioError> {
+    let audio = resample_mono(samples, sample_rate, FINGERPRINT_RATE)?;
+    Ok(fingerprint(&audio))
+}
+
+/// File modification time as Unix seconds
+fn modified_secs(path: &Path) -> Result<i64, StorageError> {
+    let modified = fs::metadata(path)?.modified()?;
+    Ok(modified.duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs() as i64).unwrap_or(0))
+}
+
+/// Title and artist from a FLAC file's Vorbis comments
+fn flac_tags(path: &Path) -> (Option<String>, Option<String>) {
+    match claxon::FlacReader::open(path) {
+        Ok(reader) => (
+            reader.get_tag("TITLE").next().map(str::to_string),
+            reader.get_tag("ARTIST").next().map(str::to_string),
+        ),
+        Err(_) => (None, None),
+    }
+}
+
+/// Collect the audio files below `directory`
+fn library_files(directory: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> Result<(), StorageError> {
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if recursive {
+                library_files(&path, recursive, files)?;
+            }
+            continue;
+        }
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        if LIBRARY_EXTENSIONS.contains(&extension.as_str()) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// A track recognized in a recording
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct TrackMatch {
+    #[pyo3(get)]
+    pub title: String,
+    #[pyo3(get)]
+    pub artist: Option<String>,
+    #[pyo3(get)]
+    pub path: String,
+    /// Position in the track where the recording starts, in seconds
+    #[pyo3(get)]
+    pub offset: f64,
+    /// Fraction of the recording's landmarks that lined up with the track
+    #[pyo3(get)]
+    pub confidence: f32,
+    /// Number of landmarks that lined up
+    #[pyo3(get)]
+    pub matches: usize,
+}
+
+#[pymethods]
+impl TrackMatch {
+    fn __repr__(&self) -> String {
+        format!(
+            "TrackMatch(title='{}', artist={:?}, offset={:.1}, confidence={:.2})",
+            self.title, self.artist, self.offset, self.confidence
+        )
+    }
+}
+
+/// Local database of audio fingerprints for "what's playing?" queries
+/// 
+/// Tracks from the user's music library are reduced to landmarks: pairs of
+/// spectral peaks hashed with the time between them. A few seconds of
+/// captured audio (e.g. from `VoicePipeline.recent_audio`) then identify
+/// the track and the position in it, even over room noise. The database is
+/// SQLite at `path` (created if missing). WAV and FLAC files can be
+/// indexed; FLAC titles and artists come from the file's tags, otherwise
+/// the file name is the title.
+#[pyclass]
+pub struct FingerprintDatabase {
+    store: FingerprintStore,
+    #[pyo3(get)]
+    path: String,
+}
+
+impl FingerprintDatabase {
+    /// Fingerprint and store one file unless it is unchanged since it was indexed
+    fn index_file(&self, path: &Path, title: Option<String>, artist: Option<String>, force: bool) -> Result<bool, String> {
+        let key = path.to_string_lossy().to_string();
+        let modified = modified_secs(path).map_err(|err| err.to_string())?;
+        if !force && self.store.modified(&key).map_err(|err| err.to_string())? == Some(modified) {
+            return Ok(false);
+        }
+        
+        let (samples, sample_rate) = read_audio_file_mono(path).map_err(|err| err.to_string())?;
+        let landmarks = landmarks_of(&samples, sample_rate).map_err(|err| err.to_string())?;
+        let (tag_title, tag_artist) = flac_tags(path);
+        let title = title
+            .or(tag_title)
+            .unwrap_or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default());
+        let artist = artist.or(tag_artist);
+        let duration = samples.len() as f64 / sample_rate as f64;
+        
+        self.store
+            .add_track(&key, &title, artist.as_deref(), duration, modified, &landmarks)
+            .map_err(|err| err.to_string())?;
+        tracing::debug!(path = %key, landmarks = landmarks.len(), "Track fingerprinted");
+        Ok(true)
+    }
+    
+    /// Best matching track for a query's landmarks
+    fn best_match(&self, landmarks: &[Landmark]) -> Result<Option<TrackMatch>, StorageError> {
+        if landmarks.is_empty() {
+            return Ok(None);
+        }
+        let alignment = match align(self.store.lookup(landmarks)?).into_iter().next() {
+            Some(alignment) if alignment.count >= MIN_ALIGNED => alignment,
+            _ => return Ok(None),
+        };
+        let track = match self.store.track(alignment.track)? {
+            Some(track) => track,
+            None => return Ok(None),
+        };
+        
+        Ok(Some(TrackMatch {
+            title: track.title,
+            artist: track.artist,
+            path: track.path,
+            offset: (alignment.offset as f64 * FRAME_SECONDS).max(0.0),
+            confidence: (alignment.count as f32 / landmarks.len() as f32).min(1.0),
+            matches: alignment.count,
+        }))
+    }
+}
+
+#[pymethods]
+impl FingerprintDatabase {
+    #[new]
+    fn new(path: String) -> PyResult<Self> {
+        let store = FingerprintStore::open(Path::new(&path))?;
+        Ok(FingerprintDatabase { store, path })
+    }
+    
+    /// Compute the landmarks of mono audio as (hash, seconds) pairs
+    #[staticmethod]
+    fn fingerprint(py: Python, samples: Vec<i16>, sample_rate: u32) -> PyResult<Vec<(u32, f64)>> {
+        let audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let landmarks = py.allow_threads(|| landmarks_of(&audio, sample_rate))?;
+        Ok(landmarks
+            .into_iter()
+            .map(|landmark| (landmark.hash, landmark.frame as f64 * FRAME_SECONDS))
+            .collect())
+    }
+    
+    /// Fingerprint a WAV or FLAC file and store it, returning whether it was (re)indexed
+    /// 
+    /// Files that haven't changed since they were indexed are skipped
+    /// unless `force` is set. `title` and `artist` override the file's tags.
+    #[pyo3(signature = (path, title=None, artist=None, force=false))]
+    fn add_file(&self, py: Python, path: &str, title: Option<String>, artist: Option<String>, force: bool) -> PyResult<bool> {
+        let path = Path::new(path);
+        py.allow_threads(|| self.index_file(path, title, artist, force))
+            .map_err(PyValueError::new_err)
+    }
+    
+    /// Index every WAV and FLAC file in `directory`
+    /// 
+    /// Unchanged files are skipped and files that can't be read are logged
+    /// and counted, so one bad file doesn't stop the scan. With `prune`,
+    /// tracks under `directory` whose files are gone are removed. Returns
+    /// a dict with "added", "unchanged", "failed" and "removed" counts.
+    #[pyo3(signature = (directory, recursive=true, prune=true))]
+    fn index_library(&self, py: Python, directory: &str, recursive: bool, prune: bool) -> PyResult<Py<PyDict>> {
+        let root = Path::new(directory);
+        if !root.is_dir() {
+            return Err(PyValueError::new_err(format!("Not a directory: {}", directory)));
+        }
+        
+        let (added, unchanged, failed, removed) = py.allow_threads(|| -> Result<_, StorageError> {
+            let mut files = Vec::new();
+            library_files(root, recursive, &mut files)?;
+            files.sort();
+            
+            let (mut added, mut unchanged, mut failed) = (0, 0, 0);
+            for file in &files {
+                match self.index_file(file, None, None, false) {
+                    Ok(true) => added += 1,
+                    Ok(false) => unchanged += 1,
+                    Err(err) => {
+                        tracing::warn!(path = %file.display(), "Could not fingerprint track: {}", err);
+                        failed += 1;
+                    }
+                }
+            }
+            
+            let mut removed = 0;
+            if prune {
+                for track in self.store.tracks()? {
+                    let path = Path::new(&track.path);
+                    if path.starts_with(root) && !path.exists() && self.store.remove(&track.path)? {
+                        removed += 1;
+                    }
+                }
+            }
+            Ok((added, unchanged, failed, removed))
+        })?;
+        
+        let dict = PyDict::new(py);
+        dict.set_item("added", added)?;
+        dict.set_item("unchanged", unchanged)?;
+        dict.set_item("failed", failed)?;
+        dict.set_item("removed", removed)?;
+        Ok(dict.into())
+    }
+    
+    /// Remove a track by path, returning whether it was indexed
+    fn remove(&self, path: &str) -> PyResult<bool> {
+        Ok(self.store.remove(path)?)
+    }
+    
+    /// List the indexed tracks as dicts with path, title, artist and duration
+    fn tracks(&self, py: Python) -> PyResult<Vec<Py<PyDict>>> {
+        self.store
+            .tracks()?
+            .into_iter()
+            .map(|track| {
+                let dict = PyDict::new(py);
+                dict.set_item("path", track.path)?;
+                dict.set_item("title", track.title)?;
+                dict.set_item("artist", track.artist)?;
+                dict.set_item("duration", track.duration)?;
+                Ok(dict.into())
+            })
+            .collect()
+    }
+    
+    fn __len__(&self) -> PyResult<usize> {
+        Ok(self.store.tracks()?.len())
+    }
+    
+    /// Identify the track playing in mono audio, or None if nothing matches
+    /// 
+    /// Five to ten seconds of audio are usually enough.
+    fn identify(&self, py: Python, samples: Vec<i16>, sample_rate: u32) -> PyResult<Option<TrackMatch>> {
+        let audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let landmarks = py.allow_threads(|| landmarks_of(&audio, sample_rate))?;
+        Ok(py.allow_threads(|| self.best_match(&landmarks))?)
+    }
+    
+    /// Identify the track in a WAV or FLAC recording, or None if nothing matches
+    fn identify_file(&self, py: Python, path: &str) -> PyResult<Option<TrackMatch>> {
+        let landmarks = py.allow_threads(|| -> Result<_, AudioError> {
+            let (samples, sample_rate) = read_audio_file_mono(Path::new(path))?;
+            landmarks_of(&samples, sample_rate)
+        })?;
+        Ok(py.allow_threads(|| self.best_match(&landmarks))?)
+    }
+}
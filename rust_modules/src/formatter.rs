@@ -0,0 +1,347 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tract_onnx::prelude::*;
+use jenna_core::ssml::MONTHS;
+use jenna_core::inference::{self as onnx, OnnxPlan};
+
+use crate::errors::ModelError;
+use crate::speech::RecognitionResult;
+
+/// Words that make a sentence a question when they open it
+const QUESTION_STARTERS: &[&str] = &[
+    "what", "what's", "where", "where's", "when", "why", "who", "who's", "whom", "whose", "which", "how", "how's",
+    "is", "isn't", "are", "aren't", "am", "was", "wasn't", "were", "weren't", "do", "don't", "does", "doesn't",
+    "did", "didn't", "can", "can't", "could", "couldn't", "will", "won't", "would", "wouldn't", "should",
+    "shouldn't", "shall", "may", "might", "have", "haven't", "has", "hasn't", "had",
+];
+
+/// Words skipped when looking for a question starter ("so what time is it")
+const SENTENCE_OPENERS: &[&str] = &["so", "and", "but", "hey", "ok", "okay", "well", "oh", "jenna", "please"];
+
+const WEEKDAYS: [&str; 7] = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// Pause after a word, in seconds, that ends a sentence when word timings are available
+const DEFAULT_SENTENCE_PAUSE: f32 = 0.7;
+
+/// Pause after a word, in seconds, that becomes a comma
+const DEFAULT_COMMA_PAUSE: f32 = 0.4;
+
+/// Label predicted for one word: capitalize it, then append `punctuation`
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Label {
+    capitalize: bool,
+    punctuation: String,
+}
+
+impl Label {
+    /// Parse a label such as "O", ",", "U" or "U?"
+    fn parse(text: &str) -> Self {
+        let text = text.trim();
+        if text == "O" {
+            return Label::default();
+        }
+        match text.strip_prefix('U') {
+            Some(punctuation) => Label { capitalize: true, punctuation: punctuation.to_string() },
+            None => Label { capitalize: false, punctuation: text.to_string() },
+        }
+    }
+}
+
+/// Word-level punctuation and capitalization model run with tract
+/// 
+/// A model directory holds `model.onnx`, `vocab.txt` (one word per line;
+/// the line number is its id, and a `[UNK]` line is used for unknown
+/// words) and `labels.txt` (one label per line: "O" for nothing, a
+/// punctuation mark, "U" to capitalize, or "U" followed by a mark). The
+/// model takes `[1, words]` int64 ids and returns `[1, words, labels]`
+/// scores.
+struct PunctuationModel {
+    plan: OnnxPlan,
+    vocab: HashMap<String, i64>,
+    unknown: i64,
+    labels: Vec<Label>,
+}
+
+impl PunctuationModel {
+    fn load(directory: &Path) -> Result<Self, String> {
+        let read = |name: &str| {
+            let path = directory.join(name);
+            fs::read_to_string(&path).map_err(|err| format!("Cannot read {}: {}", path.display(), err))
+        };
+        let vocab: HashMap<String, i64> = read("vocab.txt")?
+            .lines()
+            .enumerate()
+            .map(|(id, word)| (word.trim().to_string(), id as i64))
+            .collect();
+        let unknown = vocab.get("[UNK]").copied().unwrap_or(0);
+        let labels: Vec<Label> = read("labels.txt")?.lines().filter(|l| !l.trim().is_empty()).map(Label::parse).collect();
+        if labels.is_empty() {
+            return Err(format!("No labels in {}", directory.join("labels.txt").display()));
+        }
+        
+        let model_path = directory.join("model.onnx");
+        let load_error = |err: TractError| format!("Failed to load {}: {}", model_path.display(), err);
+        let mut model = tract_onnx::onnx().model_for_path(&model_path).map_err(load_error)?;
+        let length = model.symbol_table.sym("N");
+        let plan = model
+            .with_input_fact(0, InferenceFact::dt_shape(i64::datum_type(), tvec!(1.to_dim(), length.to_dim())))
+            .and_then(|m| m.into_optimized())
+            .and_then(|m| m.into_runnable())
+            .map_err(load_error)?;
+        
+        tracing::info!(model = %model_path.display(), labels = labels.len(), "Loaded punctuation model");
+        Ok(PunctuationModel { plan, vocab, unknown, labels })
+    }
+    
+    fn predict(&self, words: &[String]) -> Result<Vec<Label>, String> {
+        let ids: Vec<i64> = words.iter().map(|w| self.vocab.get(w).copied().unwrap_or(self.unknown)).collect();
+        let input = onnx::tensor(&[1, ids.len()], &ids)?;
+        let outputs = onnx::run(&self.plan, tvec!(input.into())).map_err(|err| format!("Punctuation model failed: {}", err))?;
+        let scores: Vec<f32> = onnx::output(&outputs, 0)?;
+        if scores.len() != ids.len() * self.labels.len() {
+            return Err(format!("Punctuation model returned {} scores for {} words", scores.len(), ids.len()));
+        }
+        
+        Ok(scores
+            .chunks_exact(self.labels.len())
+            .map(|row| {
+                let best = row.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).map_or(0, |(i, _)| i);
+                self.labels[best].clone()
+            })
+            .collect())
+    }
+}
+
+/// Lowercased word with surrounding punctuation removed
+fn core_word(token: &str) -> String {
+    token.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'').to_lowercase()
+}
+
+fn capitalize(token: &str) -> String {
+    match token.char_indices().find(|(_, c)| c.is_alphabetic()) {
+        Some((i, c)) => format!("{}{}{}", &token[..i], c.to_uppercase(), &token[i + c.len_utf8()..]),
+        None => token.to_string(),
+    }
+}
+
+fn ends_sentence(token: &str) -> bool {
+    token.trim_end_matches(['"', '\'', ')']).ends_with(['.', '?', '!'])
+}
+
+/// Rule-based (or model-driven) punctuation and truecasing
+#[derive(Clone)]
+pub struct Formatter {
+    model: Option<Arc<PunctuationModel>>,
+    /// Lowercased word sequences and their cased forms
+    proper_nouns: HashMap<Vec<String>, Vec<String>>,
+    longest_noun: usize,
+    pub sentence_pause: f32,
+    pub comma_pause: f32,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Formatter::new()
+    }
+}
+
+impl Formatter {
+    pub fn new() -> Self {
+        let mut formatter = Formatter {
+            model: None,
+            proper_nouns: HashMap::new(),
+            longest_noun: 0,
+            sentence_pause: DEFAULT_SENTENCE_PAUSE,
+            comma_pause: DEFAULT_COMMA_PAUSE,
+        };
+        // "May" is left out: it is far more often the verb
+        let calendar = WEEKDAYS.iter().chain(MONTHS.iter().filter(|m| **m != "May"));
+        formatter.add_proper_nouns(calendar.copied().chain(["Jenna", "TV", "OK"]));
+        formatter
+    }
+    
+    pub fn load_model(&mut self, directory: &Path) -> Result<(), String> {
+        self.model = Some(Arc::new(PunctuationModel::load(directory)?));
+        Ok(())
+    }
+    
+    /// Add names written as they should appear ("New York", "TV")
+    pub fn add_proper_nouns<'a>(&mut self, nouns: impl IntoIterator<Item = &'a str>) {
+        for noun in nouns {
+            let cased: Vec<String> = noun.split_whitespace().map(str::to_string).collect();
+            if cased.is_empty() {
+                continue;
+            }
+            let key = cased.iter().map(|w| core_word(w)).collect();
+            self.longest_noun = self.longest_noun.max(cased.len());
+            self.proper_nouns.insert(key, cased);
+        }
+    }
+    
+    /// Punctuation to add after each word from pauses and question words
+    fn rule_labels(&self, cores: &[String], pauses: Option<&[f32]>) -> Vec<Label> {
+        let mut labels = vec![Label::default(); cores.len()];
+        let mut sentence_start = 0;
+        for i in 0..cores.len() {
+            let pause = pauses.and_then(|p| p.get(i).copied()).unwrap_or(0.0);
+            let last = i + 1 == cores.len();
+            if last || pause >= self.sentence_pause {
+                let opener = cores[sentence_start..=i]
+                    .iter()
+                    .find(|w| !SENTENCE_OPENERS.contains(&w.as_str()))
+                    .map_or("", String::as_str);
+                let question = QUESTION_STARTERS.contains(&opener);
+                labels[i].punctuation = if question { "?" } else { "." }.to_string();
+                sentence_start = i + 1;
+            } else if self.comma_pause > 0.0 && pause >= self.comma_pause {
+                labels[i].punctuation = ",".to_string();
+            }
+        }
+        labels
+    }
+    
+    /// Format text, given the pause after each whitespace-separated word when known
+    /// 
+    /// Text that already contains sentence punctuation (Whisper output) is
+    /// only recased; otherwise punctuation comes from the model if one is
+    /// loaded, or from pauses and question words.
+    pub fn format(&self, text: &str, pauses: Option<&[f32]>) -> Result<String, String> {
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        if tokens.is_empty() {
+            return Ok(text.to_string());
+        }
+        let cores: Vec<String> = tokens.iter().map(|t| core_word(t)).collect();
+        let pauses = pauses.filter(|p| p.len() == tokens.len());
+        
+        let punctuated = tokens.iter().any(|t| ends_sentence(t));
+        let labels = match (&self.model, punctuated) {
+            (_, true) => vec![Label::default(); tokens.len()],
+            (Some(model), false) => model.predict(&cores)?,
+            (None, false) => self.rule_labels(&cores, pauses),
+        };
+        
+        let mut output: Vec<String> = tokens.iter().zip(&labels).map(|(t, l)| format!("{}{}", t, l.punctuation)).collect();
+        
+        // Proper nouns, longest first
+        let mut i = 0;
+        while i < output.len() {
+            let matched = (1..=self.longest_noun.min(output.len() - i))
+                .rev()
+                .find_map(|len| self.proper_nouns.get(&cores[i..i + len]).map(|cased| (len, cased)));
+            match matched {
+                Some((len, cased)) => {
+                    for (token, word) in output[i..i + len].iter_mut().zip(cased) {
+                        let core = core_word(token);
+                        *token = token.replacen(&core, word, 1);
+                    }
+                    i += len;
+                }
+                None => i += 1,
+            }
+        }
+        
+        // Sentence starts, "I" and model capitalization
+        let mut sentence_start = true;
+        for (i, token) in output.iter_mut().enumerate() {
+            let core = &cores[i];
+            let pronoun = core == "i" || core.starts_with("i'");
+            if sentence_start || pronoun || labels[i].capitalize {
+                *token = capitalize(token);
+            }
+            sentence_start = ends_sentence(token);
+        }
+        Ok(output.join(" "))
+    }
+    
+    /// Format a final result's text, using its word timings for pauses when they line up
+    pub fn format_result(&self, result: &mut RecognitionResult) -> Result<(), String> {
+        let pauses: Option<Vec<f32>> = (!result.words.is_empty()).then(|| {
+            result
+                .words
+                .iter()
+                .zip(result.words.iter().skip(1).map(Some).chain(std::iter::once(None)))
+                .map(|(word, next)| next.map_or(0.0, |next| next.start - word.end))
+                .collect()
+        });
+        let count = result.text.split_whitespace().count();
+        let pauses = pauses.filter(|p| p.len() == count);
+        result.text = self.format(&result.text, pauses.as_deref())?;
+        Ok(())
+    }
+}
+
+/// Punctuation and capitalization for transcripts
+/// 
+/// Vosk returns lowercase text without punctuation. Without a model, the
+/// formatter capitalizes sentence starts, "I", weekdays, months and added
+/// proper nouns, ends sentences with "?" when they open with a question
+/// word and "." otherwise, and, when word timings are available, splits
+/// sentences at pauses of `sentence_pause` seconds and adds commas at
+/// `comma_pause`. With `model_dir`, an ONNX model predicts the punctuation
+/// and casing instead (see `load_model`). Pass it to
+/// `SpeechRecognizer.set_formatter` to format final results before they
+/// reach Python; word lists keep the recognizer's raw words.
+#[pyclass]
+#[derive(Clone)]
+pub struct TranscriptFormatter {
+    pub formatter: Formatter,
+}
+
+#[pymethods]
+impl TranscriptFormatter {
+    #[new]
+    #[pyo3(signature = (model_dir=None, sentence_pause=DEFAULT_SENTENCE_PAUSE, comma_pause=DEFAULT_COMMA_PAUSE))]
+    fn new(py: Python, model_dir: Option<String>, sentence_pause: f32, comma_pause: f32) -> PyResult<Self> {
+        if !sentence_pause.is_finite() || sentence_pause <= 0.0 || !comma_pause.is_finite() || comma_pause < 0.0 {
+            return Err(PyValueError::new_err("Sentence pause must be positive and comma pause not negative"));
+        }
+        let mut formatter = TranscriptFormatter { formatter: Formatter::new() };
+        formatter.formatter.sentence_pause = sentence_pause;
+        formatter.formatter.comma_pause = comma_pause;
+        if let Some(model_dir) = model_dir {
+            formatter.load_model(py, model_dir)?;
+        }
+        Ok(formatter)
+    }
+    
+    /// Load a punctuation model directory (`model.onnx`, `vocab.txt`, `labels.txt`)
+    /// 
+    /// The model takes `[1, words]` int64 word ids and returns
+    /// `[1, words, labels]` scores. Labels are "O" (nothing), a punctuation
+    /// mark, "U" (capitalize) or "U" followed by a mark.
+    fn load_model(&mut self, py: Python, model_dir: String) -> PyResult<()> {
+        let formatter = &mut self.formatter;
+        py.allow_threads(|| formatter.load_model(Path::new(&model_dir))).map_err(ModelError::new_err)
+    }
+    
+    /// Drop the model and go back to the rules
+    fn unload_model(&mut self) {
+        self.formatter.model = None;
+    }
+    
+    /// Check whether a punctuation model is loaded
+    fn has_model(&self) -> bool {
+        self.formatter.model.is_some()
+    }
+    
+    /// Add names written as they should appear, e.g. `["New York", "Spotify"]`
+    fn add_proper_nouns(&mut self, nouns: Vec<String>) {
+        self.formatter.add_proper_nouns(nouns.iter().map(String::as_str));
+    }
+    
+    /// Punctuate and recase text
+    fn format(&self, text: &str) -> PyResult<String> {
+        self.formatter.format(text, None).map_err(PyValueError::new_err)
+    }
+    
+    /// Return a copy of a result with its text formatted, using word timings for pauses
+    fn format_result(&self, result: PyRef<RecognitionResult>) -> PyResult<RecognitionResult> {
+        let mut result = result.clone();
+        self.formatter.format_result(&mut result).map_err(PyValueError::new_err)?;
+        Ok(result)
+    }
+}
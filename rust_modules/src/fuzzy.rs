@@ -0,0 +1,389 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::{HashMap, HashSet};
+
+use crate::intent::tokenize;
+
+/// Below this many entries every entry is scored; above it, only those
+/// sharing a trigram or a phonetic code with the query
+const FULL_SCAN_LIMIT: usize = 64;
+
+/// Phonetic agreement alone never scores a perfect match
+const PHONETIC_WEIGHT: f32 = 0.9;
+
+/// One phrase containing all of the other's words is close, but not exact
+const SUBSET_WEIGHT: f32 = 0.95;
+
+/// Edit distance between two strings, counted in characters
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return a.len().max(b.len());
+    }
+    
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + (ca != cb) as usize;
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+/// Edit distance scaled to 0..1, where 1 means identical
+pub fn ratio(a: &str, b: &str) -> f32 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein(a, b) as f32 / longest as f32
+}
+
+/// Similarity of two word lists ignoring order and repeats
+/// 
+/// Compares the shared words against each side's shared words plus its
+/// leftovers and keeps the best `ratio`, so "lights living room" matches
+/// "living room lights" perfectly and "the living room lights" closely.
+/// A phrase whose words are all in the other scores `SUBSET_WEIGHT`.
+pub fn token_set_ratio(a: &[String], b: &[String]) -> f32 {
+    let a: HashSet<&str> = a.iter().map(String::as_str).collect();
+    let b: HashSet<&str> = b.iter().map(String::as_str).collect();
+    if a.is_empty() || b.is_empty() {
+        return if a.is_empty() && b.is_empty() { 1.0 } else { 0.0 };
+    }
+    
+    let sorted = |set: HashSet<&str>| {
+        let mut words: Vec<&str> = set.into_iter().collect();
+        words.sort_unstable();
+        words.join(" ")
+    };
+    let common = sorted(a.intersection(&b).copied().collect());
+    let only_a = sorted(a.difference(&b).copied().collect());
+    let only_b = sorted(b.difference(&a).copied().collect());
+    let join = |head: &str, tail: &str| match (head.is_empty(), tail.is_empty()) {
+        (true, _) => tail.to_string(),
+        (_, true) => head.to_string(),
+        _ => format!("{} {}", head, tail),
+    };
+    let with_a = join(&common, &only_a);
+    let with_b = join(&common, &only_b);
+    
+    let mut best = ratio(&with_a, &with_b);
+    if !common.is_empty() {
+        best = best.max(ratio(&common, &with_a).max(ratio(&common, &with_b)) * SUBSET_WEIGHT);
+    }
+    best
+}
+
+/// Soundex code of one word ("room" and "rum" are both R500)
+pub fn soundex(word: &str) -> String {
+    let code = |c: char| match c {
+        'b' | 'f' | 'p' | 'v' => Some('1'),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+        'd' | 't' => Some('3'),
+        'l' => Some('4'),
+        'm' | 'n' => Some('5'),
+        'r' => Some('6'),
+        _ => None,
+    };
+    
+    let letters: Vec<char> = word.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_lowercase()).collect();
+    let first = match letters.first() {
+        Some(&first) => first,
+        // Digits and non-Latin words are their own code
+        None => return word.to_string(),
+    };
+    
+    let mut key = first.to_ascii_uppercase().to_string();
+    let mut last = code(first);
+    for &c in &letters[1..] {
+        let digit = code(c);
+        if let Some(d) = digit.filter(|_| digit != last) {
+            key.push(d);
+            if key.len() == 4 {
+                break;
+            }
+        }
+        // 'h' and 'w' don't separate letters with the same code; vowels do
+        if c != 'h' && c != 'w' {
+            last = digit;
+        }
+    }
+    while key.len() < 4 {
+        key.push('0');
+    }
+    key
+}
+
+/// Soundex codes of every word in a phrase
+pub fn phonetic_codes(words: &[String]) -> Vec<String> {
+    words.iter().map(|word| soundex(word)).collect()
+}
+
+/// Share of distinct Soundex codes two phrases have in common, 0..1
+pub fn phonetic_similarity(a: &[String], b: &[String]) -> f32 {
+    let a: HashSet<&String> = a.iter().collect();
+    let b: HashSet<&String> = b.iter().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / longest as f32
+}
+
+fn trigrams(text: &str) -> HashSet<String> {
+    let padded: Vec<char> = format!("  {} ", text).chars().collect();
+    padded.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+/// How a fuzzy match was scored
+fn score(query: &Prepared, entry: &Prepared) -> (f32, &'static str) {
+    if query.text == entry.text {
+        return (1.0, "exact");
+    }
+    let candidates = [
+        (ratio(&query.text, &entry.text), "edit"),
+        (token_set_ratio(&query.words, &entry.words), "token_set"),
+        (phonetic_similarity(&query.phonetic, &entry.phonetic) * PHONETIC_WEIGHT, "phonetic"),
+    ];
+    candidates.into_iter().fold((0.0, "edit"), |best, c| if c.0 > best.0 { c } else { best })
+}
+
+/// Normalized forms of a phrase
+struct Prepared {
+    text: String,
+    words: Vec<String>,
+    phonetic: Vec<String>,
+}
+
+impl Prepared {
+    fn new(phrase: &str) -> Self {
+        let words = tokenize(phrase);
+        Prepared { text: words.join(" "), phonetic: phonetic_codes(&words), words }
+    }
+}
+
+/// A phrase in the matcher and the value it resolves to
+struct Entry {
+    value: String,
+    phrase: String,
+    prepared: Prepared,
+}
+
+/// Result of a fuzzy lookup
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct FuzzyMatch {
+    /// The value the matched phrase belongs to
+    #[pyo3(get)]
+    pub value: String,
+    /// The phrase that matched: the value itself or one of its aliases
+    #[pyo3(get)]
+    pub matched: String,
+    /// 0..1, where 1 is an exact match after normalization
+    #[pyo3(get)]
+    pub score: f32,
+    /// "exact", "edit", "token_set" or "phonetic"
+    #[pyo3(get)]
+    pub method: String,
+}
+
+#[pymethods]
+impl FuzzyMatch {
+    fn __repr__(&self) -> String {
+        format!("FuzzyMatch(value={:?}, score={:.2}, method={:?})", self.value, self.score, self.method)
+    }
+}
+
+/// Dictionary of names resolved by fuzzy matching
+/// 
+/// Each value (a device, room or contact name) may have aliases. Phrases
+/// are normalized, split into words and given Soundex codes when added,
+/// and indexed by character trigram and phonetic code, so a lookup only
+/// scores plausible entries. A query scores against a phrase as the best
+/// of normalized edit distance, word-set overlap and shared Soundex
+/// codes, so "living rum lights" finds "living room lights" and "lights in
+/// the living room" finds it too.
+#[pyclass]
+pub struct FuzzyMatcher {
+    entries: Vec<Entry>,
+    by_trigram: HashMap<String, Vec<usize>>,
+    by_code: HashMap<String, Vec<usize>>,
+    threshold: f32,
+}
+
+impl FuzzyMatcher {
+    fn index_entry(&mut self, i: usize) {
+        let entry = &self.entries[i];
+        for trigram in trigrams(&entry.prepared.text) {
+            self.by_trigram.entry(trigram).or_default().push(i);
+        }
+        for code in entry.prepared.phonetic.iter().collect::<HashSet<_>>() {
+            self.by_code.entry(code.clone()).or_default().push(i);
+        }
+    }
+    
+    fn rebuild_index(&mut self) {
+        self.by_trigram.clear();
+        self.by_code.clear();
+        for i in 0..self.entries.len() {
+            self.index_entry(i);
+        }
+    }
+    
+    /// Score the query against plausible entries, best first, one result per value
+    pub fn search(&self, query: &str, threshold: f32) -> Vec<FuzzyMatch> {
+        let query = Prepared::new(query);
+        if query.text.is_empty() {
+            return Vec::new();
+        }
+        
+        let candidates: Vec<usize> = if self.entries.len() <= FULL_SCAN_LIMIT {
+            (0..self.entries.len()).collect()
+        } else {
+            let mut candidates: Vec<usize> = trigrams(&query.text)
+                .iter()
+                .filter_map(|trigram| self.by_trigram.get(trigram))
+                .chain(query.phonetic.iter().filter_map(|code| self.by_code.get(code)))
+                .flatten()
+                .copied()
+                .collect();
+            candidates.sort_unstable();
+            candidates.dedup();
+            candidates
+        };
+        
+        let mut best: HashMap<&str, FuzzyMatch> = HashMap::new();
+        for i in candidates {
+            let entry = &self.entries[i];
+            let (score, method) = score(&query, &entry.prepared);
+            if score >= threshold && best.get(entry.value.as_str()).map_or(true, |m| score > m.score) {
+                best.insert(&entry.value, FuzzyMatch {
+                    value: entry.value.clone(),
+                    matched: entry.phrase.clone(),
+                    score,
+                    method: method.to_string(),
+                });
+            }
+        }
+        
+        let mut matches: Vec<FuzzyMatch> = best.into_values().collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.value.cmp(&b.value)));
+        matches
+    }
+}
+
+fn check_threshold(threshold: f32) -> PyResult<f32> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(PyValueError::new_err("Threshold must be between 0 and 1"));
+    }
+    Ok(threshold)
+}
+
+#[pymethods]
+impl FuzzyMatcher {
+    /// Create a matcher from a list of names, or a dict mapping names to aliases
+    #[new]
+    #[pyo3(signature = (choices=None, threshold=0.7))]
+    fn new(choices: Option<&PyAny>, threshold: f32) -> PyResult<Self> {
+        let mut matcher = FuzzyMatcher {
+            entries: Vec::new(),
+            by_trigram: HashMap::new(),
+            by_code: HashMap::new(),
+            threshold: check_threshold(threshold)?,
+        };
+        if let Some(choices) = choices {
+            if let Ok(aliases) = choices.extract::<HashMap<String, Vec<String>>>() {
+                for (value, aliases) in aliases {
+                    matcher.add(value, aliases);
+                }
+            } else {
+                for value in choices.extract::<Vec<String>>()? {
+                    matcher.add(value, Vec::new());
+                }
+            }
+        }
+        Ok(matcher)
+    }
+    
+    /// Add a value with optional aliases ("tv" for "living room television")
+    #[pyo3(signature = (value, aliases=Vec::new()))]
+    fn add(&mut self, value: String, aliases: Vec<String>) {
+        for phrase in std::iter::once(value.clone()).chain(aliases) {
+            let prepared = Prepared::new(&phrase);
+            let duplicate = self.entries.iter().any(|e| e.value == value && e.prepared.text == prepared.text);
+            if prepared.text.is_empty() || duplicate {
+                continue;
+            }
+            self.entries.push(Entry { value: value.clone(), phrase, prepared });
+            self.index_entry(self.entries.len() - 1);
+        }
+    }
+    
+    /// Remove a value and its aliases, returning whether it existed
+    fn remove(&mut self, value: &str) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.value != value);
+        self.rebuild_index();
+        self.entries.len() != before
+    }
+    
+    /// List the values, without aliases
+    fn values(&self) -> Vec<String> {
+        let mut values: Vec<String> = self.entries.iter().map(|entry| entry.value.clone()).collect();
+        values.sort();
+        values.dedup();
+        values
+    }
+    
+    fn __len__(&self) -> usize {
+        self.values().len()
+    }
+    
+    /// Best match for a query, or None if nothing scores `threshold` (default: the matcher's)
+    #[pyo3(signature = (query, threshold=None))]
+    fn best(&self, py: Python, query: &str, threshold: Option<f32>) -> PyResult<Option<FuzzyMatch>> {
+        let threshold = check_threshold(threshold.unwrap_or(self.threshold))?;
+        Ok(py.allow_threads(|| self.search(query, threshold)).into_iter().next())
+    }
+    
+    /// Up to `limit` matches for a query, best first
+    #[pyo3(signature = (query, limit=5, threshold=None))]
+    fn top(&self, py: Python, query: &str, limit: usize, threshold: Option<f32>) -> PyResult<Vec<FuzzyMatch>> {
+        let threshold = check_threshold(threshold.unwrap_or(self.threshold))?;
+        let mut matches = py.allow_threads(|| self.search(query, threshold));
+        matches.truncate(limit);
+        Ok(matches)
+    }
+}
+
+/// Similarity of two phrases, 0..1, as `FuzzyMatcher` scores them
+#[pyfunction]
+pub fn fuzzy_score(a: &str, b: &str) -> f32 {
+    score(&Prepared::new(a), &Prepared::new(b)).0
+}
+
+/// Normalized edit-distance similarity of two strings, 0..1
+#[pyfunction]
+#[pyo3(name = "fuzzy_ratio")]
+pub fn py_ratio(a: &str, b: &str) -> f32 {
+    ratio(&tokenize(a).join(" "), &tokenize(b).join(" "))
+}
+
+/// Word-set similarity of two phrases, 0..1, ignoring word order and repeats
+#[pyfunction]
+#[pyo3(name = "token_set_ratio")]
+pub fn py_token_set_ratio(a: &str, b: &str) -> f32 {
+    token_set_ratio(&tokenize(a), &tokenize(b))
+}
+
+/// Soundex codes of each word in a phrase, joined by spaces
+#[pyfunction]
+pub fn phonetic_key(text: &str) -> String {
+    phonetic_codes(&tokenize(text)).join(" ")
+}
@@ -0,0 +1,510 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use jenna_core::dereverb::Dereverberator;
+use jenna_core::iir::{BandParams, BandShape, SosFilter};
+use jenna_core::level::{peak_dbfs, rms_dbfs, AutomaticGain, SILENCE_DBFS};
+use jenna_core::vad::VoiceActivityDetector;
+
+use crate::audio::MonoResampler;
+use crate::noise::NoiseGate;
+
+fn default_target_db() -> f32 {
+    -20.0
+}
+
+fn default_max_gain_db() -> f32 {
+    30.0
+}
+
+fn default_gate_db() -> f32 {
+    -60.0
+}
+
+fn default_gate_threshold_db() -> f32 {
+    6.0
+}
+
+fn default_reduction_db() -> f32 {
+    24.0
+}
+
+fn default_learn_secs() -> f32 {
+    1.0
+}
+
+fn default_q() -> f64 {
+    0.707
+}
+
+fn default_vad_threshold_db() -> f32 {
+    12.0
+}
+
+/// One equalizer band of an "eq" node
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EqBandSpec {
+    /// "peaking", "lowshelf" or "highshelf"
+    pub shape: String,
+    pub frequency: f64,
+    pub gain_db: f64,
+    #[serde(default = "default_q")]
+    pub q: f64,
+}
+
+/// A node of an `AudioGraph` as written in Python or a config file
+/// 
+/// Each is a dict or table whose "type" names the node; the other keys are
+/// its settings, all optional except the resampler's rate and the bands'
+/// frequencies and gains.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeSpec {
+    /// Convert to another sample rate
+    Resample { rate: u32 },
+    /// Automatic gain control towards `target_db` (dBFS RMS)
+    Agc {
+        #[serde(default = "default_target_db")]
+        target_db: f32,
+        #[serde(default = "default_max_gain_db")]
+        max_gain_db: f32,
+        #[serde(default = "default_gate_db")]
+        gate_db: f32,
+    },
+    /// Spectral noise gate that learns the noise from the first `learn_secs` of audio
+    NoiseSuppressor {
+        #[serde(default = "default_gate_threshold_db")]
+        threshold_db: f32,
+        #[serde(default = "default_reduction_db")]
+        reduction_db: f32,
+        #[serde(default = "default_learn_secs")]
+        learn_secs: f32,
+    },
+    /// Parametric equalizer
+    Eq { bands: Vec<EqBandSpec> },
+    /// Voice activity detection; passes audio through and reports whether speech is present
+    Vad {
+        #[serde(default = "default_vad_threshold_db")]
+        threshold_db: f32,
+    },
+    /// Level meter; passes audio through and reports the RMS and peak level
+    Meter,
+    /// Late reverberation removal (see `VoicePipeline`'s `dereverberation`)
+    Dereverb,
+}
+
+impl NodeSpec {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NodeSpec::Resample { .. } => "resample",
+            NodeSpec::Agc { .. } => "agc",
+            NodeSpec::NoiseSuppressor { .. } => "noise_suppressor",
+            NodeSpec::Eq { .. } => "eq",
+            NodeSpec::Vad { .. } => "vad",
+            NodeSpec::Meter => "meter",
+            NodeSpec::Dereverb => "dereverb",
+        }
+    }
+    
+    /// Create the node for audio arriving at `sample_rate`, returning it and its output rate
+    fn build(&self, sample_rate: u32) -> Result<(Box<dyn Node>, u32), String> {
+        let node: Box<dyn Node> = match self {
+            NodeSpec::Resample { rate } => {
+                if *rate == 0 {
+                    return Err("Resample rate must be positive".to_string());
+                }
+                let resampler = MonoResampler::new(sample_rate, *rate).map_err(|err| err.to_string())?;
+                return Ok((Box::new(ResampleNode(resampler)), *rate));
+            }
+            NodeSpec::Agc { target_db, max_gain_db, gate_db } => {
+                if *target_db > 0.0 || *gate_db > 0.0 {
+                    return Err("AGC levels must be at most 0 dBFS".to_string());
+                }
+                Box::new(AgcNode(AutomaticGain::new(sample_rate, *target_db, *max_gain_db, *gate_db)))
+            }
+            NodeSpec::NoiseSuppressor { threshold_db, reduction_db, learn_secs } => {
+                let learn_samples = (learn_secs.max(0.0) * sample_rate as f32) as usize;
+                Box::new(NoiseNode::new(sample_rate, *threshold_db, *reduction_db, learn_samples)?)
+            }
+            NodeSpec::Eq { bands } => {
+                let mut sections = Vec::with_capacity(bands.len());
+                for band in bands {
+                    let params = BandParams {
+                        shape: BandShape::from_name(&band.shape)?,
+                        frequency: band.frequency,
+                        gain_db: band.gain_db,
+                        q: band.q,
+                    };
+                    if params.frequency <= 0.0 || params.frequency >= sample_rate as f64 / 2.0 {
+                        return Err(format!("Band frequency must be between 0 and {} Hz", sample_rate / 2));
+                    }
+                    if params.q <= 0.0 {
+                        return Err("Band Q must be greater than zero".to_string());
+                    }
+                    sections.push(params.coefficients(sample_rate as f64));
+                }
+                Box::new(EqNode(SosFilter::from_sections(sections)?))
+            }
+            NodeSpec::Vad { threshold_db } => Box::new(VadNode(VoiceActivityDetector::new(*threshold_db))),
+            NodeSpec::Meter => Box::new(MeterNode { rms_db: SILENCE_DBFS, peak_db: SILENCE_DBFS }),
+            NodeSpec::Dereverb => Box::new(DereverbNode(Dereverberator::new(sample_rate))),
+        };
+        Ok((node, sample_rate))
+    }
+}
+
+/// A running stage of a graph
+trait Node: Send {
+    /// Process a block; stages working in frames may return more or fewer samples than they got
+    fn process(&mut self, block: Vec<f32>) -> Result<Vec<f32>, String>;
+    /// Current readings, such as levels or speech state
+    fn readings(&self) -> Vec<(&'static str, f32)> {
+        Vec::new()
+    }
+}
+
+struct ResampleNode(MonoResampler);
+
+impl Node for ResampleNode {
+    fn process(&mut self, block: Vec<f32>) -> Result<Vec<f32>, String> {
+        self.0.push(&block).map_err(|err| err.to_string())
+    }
+}
+
+struct AgcNode(AutomaticGain);
+
+impl Node for AgcNode {
+    fn process(&mut self, mut block: Vec<f32>) -> Result<Vec<f32>, String> {
+        self.0.process(&mut block);
+        Ok(block)
+    }
+    
+    fn readings(&self) -> Vec<(&'static str, f32)> {
+        vec![("gain_db", self.0.gain_db())]
+    }
+}
+
+/// Noise gate that learns its profile from the start of the stream
+struct NoiseNode {
+    gate: NoiseGate,
+    learn_samples: usize,
+    learning: Vec<f32>,
+    learned: bool,
+}
+
+impl NoiseNode {
+    fn new(sample_rate: u32, threshold_db: f32, reduction_db: f32, learn_samples: usize) -> Result<Self, String> {
+        let frame_size = ((sample_rate as f32 * 0.032) as usize).next_power_of_two().max(4);
+        let gate = NoiseGate::create(sample_rate, frame_size, threshold_db, reduction_db, 0.6)?;
+        Ok(NoiseNode { gate, learn_samples: learn_samples.max(frame_size), learning: Vec::new(), learned: false })
+    }
+}
+
+impl Node for NoiseNode {
+    fn process(&mut self, block: Vec<f32>) -> Result<Vec<f32>, String> {
+        if !self.learned {
+            self.learning.extend_from_slice(&block);
+            if self.learning.len() >= self.learn_samples {
+                self.gate.learn(&self.learning)?;
+                self.learning = Vec::new();
+                self.learned = true;
+            }
+            return Ok(block);
+        }
+        Ok(self.gate.gate(&block))
+    }
+    
+    fn readings(&self) -> Vec<(&'static str, f32)> {
+        vec![("learning", if self.learned { 0.0 } else { 1.0 })]
+    }
+}
+
+struct EqNode(SosFilter);
+
+impl Node for EqNode {
+    fn process(&mut self, mut block: Vec<f32>) -> Result<Vec<f32>, String> {
+        self.0.process_in_place(&mut block);
+        Ok(block)
+    }
+}
+
+struct VadNode(VoiceActivityDetector);
+
+impl Node for VadNode {
+    fn process(&mut self, block: Vec<f32>) -> Result<Vec<f32>, String> {
+        let frame: Vec<i16> = block.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect();
+        self.0.process(&frame);
+        Ok(block)
+    }
+    
+    fn readings(&self) -> Vec<(&'static str, f32)> {
+        vec![
+            ("speech", if self.0.is_speech() { 1.0 } else { 0.0 }),
+            ("noise_floor_db", self.0.noise_floor().unwrap_or(SILENCE_DBFS)),
+        ]
+    }
+}
+
+struct MeterNode {
+    rms_db: f32,
+    peak_db: f32,
+}
+
+impl Node for MeterNode {
+    fn process(&mut self, block: Vec<f32>) -> Result<Vec<f32>, String> {
+        if !block.is_empty() {
+            self.rms_db = rms_dbfs(&block);
+            self.peak_db = peak_dbfs(&block);
+        }
+        Ok(block)
+    }
+    
+    fn readings(&self) -> Vec<(&'static str, f32)> {
+        vec![("rms_db", self.rms_db), ("peak_db", self.peak_db)]
+    }
+}
+
+struct DereverbNode(Dereverberator);
+
+impl Node for DereverbNode {
+    fn process(&mut self, mut block: Vec<f32>) -> Result<Vec<f32>, String> {
+        self.0.process(&mut block);
+        Ok(block)
+    }
+}
+
+/// A chain of processing nodes run in order on mono audio
+pub struct ProcessingGraph {
+    input_rate: u32,
+    output_rate: u32,
+    specs: Vec<NodeSpec>,
+    nodes: Vec<Box<dyn Node>>,
+}
+
+impl ProcessingGraph {
+    pub fn new(specs: Vec<NodeSpec>, input_rate: u32) -> Result<Self, String> {
+        if input_rate == 0 {
+            return Err("Sample rate must be positive".to_string());
+        }
+        let mut graph = ProcessingGraph { input_rate, output_rate: input_rate, specs, nodes: Vec::new() };
+        graph.rebuild()?;
+        Ok(graph)
+    }
+    
+    /// Recreate every node from the specs, dropping their state
+    fn rebuild(&mut self) -> Result<(), String> {
+        let mut nodes = Vec::with_capacity(self.specs.len());
+        let mut rate = self.input_rate;
+        for (index, spec) in self.specs.iter().enumerate() {
+            let (node, output_rate) = spec.build(rate).map_err(|err| format!("Node {} ({}): {}", index, spec.kind(), err))?;
+            nodes.push(node);
+            rate = output_rate;
+        }
+        self.nodes = nodes;
+        self.output_rate = rate;
+        Ok(())
+    }
+    
+    /// Replace the nodes; the graph is unchanged if any of them is invalid
+    pub fn set_specs(&mut self, specs: Vec<NodeSpec>) -> Result<(), String> {
+        let previous = std::mem::replace(&mut self.specs, specs);
+        if let Err(err) = self.rebuild() {
+            self.specs = previous;
+            self.rebuild()?;
+            return Err(err);
+        }
+        Ok(())
+    }
+    
+    pub fn specs(&self) -> &[NodeSpec] {
+        &self.specs
+    }
+    
+    pub fn input_rate(&self) -> u32 {
+        self.input_rate
+    }
+    
+    pub fn output_rate(&self) -> u32 {
+        self.output_rate
+    }
+    
+    /// Run a block through every node
+    pub fn process(&mut self, block: Vec<f32>) -> Result<Vec<f32>, String> {
+        self.nodes.iter_mut().try_fold(block, |block, node| node.process(block))
+    }
+    
+    /// Readings of every node, in order, with the node's type
+    pub fn readings(&self) -> Vec<(&'static str, Vec<(&'static str, f32)>)> {
+        self.specs.iter().zip(&self.nodes).map(|(spec, node)| (spec.kind(), node.readings())).collect()
+    }
+    
+    /// Drop all streaming state, as if the graph had just been built
+    pub fn reset(&mut self) -> Result<(), String> {
+        self.rebuild()
+    }
+}
+
+/// Graph shared between its Python object and a running pipeline
+pub type SharedGraph = Arc<Mutex<ProcessingGraph>>;
+
+/// Parse a node dict, or a list of them, from Python through JSON
+pub fn from_py<T: DeserializeOwned>(py: Python, value: &PyAny) -> PyResult<T> {
+    let json: String = py.import("json")?.call_method1("dumps", (value,))?.extract()?;
+    serde_json::from_str(&json).map_err(|err| PyValueError::new_err(format!("Invalid graph node: {}", err)))
+}
+
+/// Describe nodes as a list of dicts
+pub fn specs_to_py(py: Python, specs: &[NodeSpec]) -> PyResult<PyObject> {
+    let json = serde_json::to_string(specs).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.into())
+}
+
+/// A configurable chain of audio processing nodes, executed in Rust
+/// 
+/// Nodes are dicts with a "type" and its settings, run in order:
+/// "resample" (`rate`), "agc" (`target_db`, `max_gain_db`, `gate_db`),
+/// "noise_suppressor" (`threshold_db`, `reduction_db`, `learn_secs`),
+/// "eq" (`bands`: dicts with `shape`, `frequency`, `gain_db`, `q`),
+/// "vad" (`threshold_db`), "meter" and "dereverb". For example
+/// `AudioGraph(16000, [{"type": "eq", "bands": [{"shape": "lowshelf",
+/// "frequency": 150, "gain_db": -6}]}, {"type": "agc"}, {"type": "meter"}])`.
+/// 
+/// Different rooms and microphones need different chains; attach a graph
+/// to a `VoicePipeline` with `set_capture_graph`, or set `capture_graph`
+/// in the config, to run it on the captured audio. Changing the nodes
+/// restarts every node's state.
+#[pyclass]
+pub struct AudioGraph {
+    graph: SharedGraph,
+}
+
+impl AudioGraph {
+    pub fn shared_graph(&self) -> SharedGraph {
+        self.graph.clone()
+    }
+    
+    /// Change the node list, keeping the old one if the new one is invalid
+    fn edit(&self, edit: impl FnOnce(&mut Vec<NodeSpec>) -> PyResult<()>) -> PyResult<()> {
+        let mut graph = self.graph.lock().unwrap();
+        let mut specs = graph.specs().to_vec();
+        edit(&mut specs)?;
+        graph.set_specs(specs).map_err(PyValueError::new_err)
+    }
+}
+
+#[pymethods]
+impl AudioGraph {
+    /// Create a graph for audio at `sample_rate`, empty or from a list of node dicts
+    #[new]
+    #[pyo3(signature = (sample_rate, nodes=None))]
+    fn new(py: Python, sample_rate: u32, nodes: Option<&PyAny>) -> PyResult<Self> {
+        let specs = match nodes {
+            Some(nodes) => from_py(py, nodes)?,
+            None => Vec::new(),
+        };
+        let graph = ProcessingGraph::new(specs, sample_rate).map_err(PyValueError::new_err)?;
+        Ok(AudioGraph { graph: Arc::new(Mutex::new(graph)) })
+    }
+    
+    /// Rate of the audio passed to `process`
+    #[getter]
+    fn sample_rate(&self) -> u32 {
+        self.graph.lock().unwrap().input_rate()
+    }
+    
+    /// Rate of the audio coming out, after any resampling
+    #[getter]
+    fn output_rate(&self) -> u32 {
+        self.graph.lock().unwrap().output_rate()
+    }
+    
+    /// The nodes as a list of dicts, with every setting filled in
+    #[getter]
+    fn nodes(&self, py: Python) -> PyResult<PyObject> {
+        specs_to_py(py, self.graph.lock().unwrap().specs())
+    }
+    
+    /// Replace all nodes
+    #[setter]
+    fn set_nodes(&self, py: Python, nodes: &PyAny) -> PyResult<()> {
+        let specs = from_py(py, nodes)?;
+        self.edit(|current| {
+            *current = specs;
+            Ok(())
+        })
+    }
+    
+    /// Add a node at the end, or before `index`
+    #[pyo3(signature = (node, index=None))]
+    fn add(&self, py: Python, node: &PyAny, index: Option<usize>) -> PyResult<()> {
+        let spec = from_py(py, node)?;
+        self.edit(|specs| {
+            let index = index.unwrap_or(specs.len());
+            if index > specs.len() {
+                return Err(PyValueError::new_err(format!("No position {} in a graph of {} nodes", index, specs.len())));
+            }
+            specs.insert(index, spec);
+            Ok(())
+        })
+    }
+    
+    /// Remove the node at `index`
+    fn remove(&self, index: usize) -> PyResult<()> {
+        self.edit(|specs| {
+            if index >= specs.len() {
+                return Err(PyValueError::new_err(format!("No node at index {}", index)));
+            }
+            specs.remove(index);
+            Ok(())
+        })
+    }
+    
+    /// Move the node at `from_index` to `to_index`
+    fn move_node(&self, from_index: usize, to_index: usize) -> PyResult<()> {
+        self.edit(|specs| {
+            if from_index >= specs.len() || to_index >= specs.len() {
+                return Err(PyValueError::new_err(format!("Indices must be below {}", specs.len())));
+            }
+            let spec = specs.remove(from_index);
+            specs.insert(to_index, spec);
+            Ok(())
+        })
+    }
+    
+    /// Process a block of samples at `sample_rate`
+    /// 
+    /// Frame-based nodes (noise suppressor) can return fewer or more samples
+    /// than they were given; the stream as a whole keeps its length.
+    fn process(&self, py: Python, samples: Vec<f32>) -> PyResult<Vec<f32>> {
+        let graph = self.graph.clone();
+        py.allow_threads(|| graph.lock().unwrap().process(samples)).map_err(PyValueError::new_err)
+    }
+    
+    /// Current readings of each node, e.g. `{"type": "meter", "rms_db": -32.5, "peak_db": -18.0}`
+    fn readings(&self, py: Python) -> PyResult<Vec<Py<PyDict>>> {
+        let readings = self.graph.lock().unwrap().readings();
+        readings
+            .into_iter()
+            .map(|(kind, values)| {
+                let dict = PyDict::new(py);
+                dict.set_item("type", kind)?;
+                for (name, value) in values {
+                    dict.set_item(name, value)?;
+                }
+                Ok(dict.into())
+            })
+            .collect()
+    }
+    
+    /// Drop the state of every node (adapted gains, learned noise, filter memory)
+    fn reset(&self) -> PyResult<()> {
+        self.graph.lock().unwrap().reset().map_err(PyValueError::new_err)
+    }
+    
+    fn __len__(&self) -> usize {
+        self.graph.lock().unwrap().specs().len()
+    }
+}
@@ -0,0 +1,532 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::runtime::{Handle, Runtime};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::audio::{resample_mono, MonoResampler};
+use crate::lifecycle::{self, Resource};
+use crate::speech::{RecognitionResult, SharedEngine, SpeechRecognizer, SttEngine, TextToSpeech};
+use crate::wake_word::{unix_time, DetectorState, WakeWordDetector};
+
+/// Code generated from `proto/speech.proto`
+pub mod proto {
+    tonic::include_proto!("jenna.speech");
+}
+
+use proto::speech_service_server::{SpeechService, SpeechServiceServer};
+use proto::{
+    recognize_request, AudioChunk, AudioFormat, InfoRequest, RecognitionResponse, RecognizeRequest, ServiceInfo,
+    SynthesizeRequest, TranscribeRequest, WakeWordEvent, WordInfo,
+};
+
+/// Default port for the gRPC speech service
+pub const DEFAULT_PORT: u16 = 50051;
+
+/// Sample rate expected by `SttEngine::transcribe`
+const RECOGNITION_SAMPLE_RATE: u32 = 16000;
+
+/// Samples per `AudioChunk` when streaming synthesized speech
+const SYNTHESIS_CHUNK_SAMPLES: usize = 4096;
+
+/// Responses buffered per streaming call before the engine waits for the client
+const RESPONSE_BUFFER: usize = 32;
+
+/// Longest the server waits for in-flight calls when stopping
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+type ResponseStream<T> = Pin<Box<dyn tokio_stream::Stream<Item = Result<T, Status>> + Send>>;
+
+/// Decode interleaved 16-bit little-endian PCM and mix it down to mono
+fn decode_pcm(pcm: &[u8], channels: u32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let samples: Vec<f32> = pcm
+        .chunks_exact(2)
+        .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0)
+        .collect();
+    if channels == 1 {
+        return samples;
+    }
+    samples
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+fn to_i16(samples: &[f32]) -> Vec<i16> {
+    samples.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect()
+}
+
+impl From<RecognitionResult> for RecognitionResponse {
+    fn from(result: RecognitionResult) -> Self {
+        RecognitionResponse {
+            text: result.text,
+            is_final: result.is_final,
+            confidence: result.confidence,
+            words: result
+                .words
+                .into_iter()
+                .map(|w| WordInfo { word: w.word, start: w.start, end: w.end, confidence: w.confidence })
+                .collect(),
+        }
+    }
+}
+
+/// Converts a call's audio chunks to mono audio at the engine rate
+/// 
+/// The format comes from the first chunk; later chunks may repeat it but
+/// not change it.
+struct ChunkDecoder {
+    engine_rate: u32,
+    format: Option<(u32, u32)>,
+    resampler: Option<MonoResampler>,
+}
+
+impl ChunkDecoder {
+    fn new(engine_rate: u32) -> Self {
+        ChunkDecoder { engine_rate, format: None, resampler: None }
+    }
+    
+    fn decode(&mut self, chunk: &AudioChunk) -> Result<Vec<f32>, Status> {
+        let format = chunk.format.as_ref().map(|f| {
+            (if f.sample_rate == 0 { self.engine_rate } else { f.sample_rate }, f.channels.max(1))
+        });
+        match (self.format, format) {
+            (Some(current), Some(format)) if current != format => {
+                return Err(Status::invalid_argument("Audio format changed within a stream"));
+            }
+            (None, _) => {
+                let (rate, channels) = format.unwrap_or((self.engine_rate, 1));
+                let resampler = MonoResampler::new(rate, self.engine_rate)
+                    .map_err(|err| Status::invalid_argument(err.to_string()))?;
+                self.format = Some((rate, channels));
+                self.resampler = Some(resampler);
+            }
+            _ => {}
+        }
+        
+        let (_, channels) = self.format.unwrap_or((self.engine_rate, 1));
+        let audio = decode_pcm(&chunk.pcm, channels);
+        match self.resampler.as_mut() {
+            Some(resampler) => resampler.push(&audio).map_err(|err| Status::internal(err.to_string())),
+            None => Ok(audio),
+        }
+    }
+}
+
+/// Marks an engine busy for the lifetime of a streaming call
+struct StreamGuard(Arc<AtomicBool>);
+
+impl StreamGuard {
+    fn acquire(flag: &Arc<AtomicBool>, what: &str) -> Result<Self, Status> {
+        flag.compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed)
+            .map(|_| StreamGuard(flag.clone()))
+            .map_err(|_| Status::unavailable(format!("The {} is serving another stream", what)))
+    }
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// Engines exposed by a running server, resolved when it starts
+struct Engines {
+    recognizer: Option<(SharedEngine, u32)>,
+    tts: Option<Py<TextToSpeech>>,
+    detector: Option<Arc<Mutex<DetectorState>>>,
+    info: ServiceInfo,
+    /// Live recognition and wake word streams change engine state, so each serves one call at a time
+    recognizing: Arc<AtomicBool>,
+    detecting: Arc<AtomicBool>,
+}
+
+impl Engines {
+    fn recognizer(&self) -> Result<(SharedEngine, u32), Status> {
+        self.recognizer.clone().ok_or_else(|| Status::unimplemented("Speech recognition is not served"))
+    }
+}
+
+/// Run `f` on the recognizer's active engine
+fn with_recognizer<R>(
+    engine: &SharedEngine,
+    f: impl FnOnce(&mut dyn SttEngine) -> Result<R, String>,
+) -> Result<R, Status> {
+    let mut guard = engine.lock().unwrap();
+    let engine = guard
+        .as_deref_mut()
+        .ok_or_else(|| Status::failed_precondition("Speech recognizer not initialized"))?;
+    f(engine).map_err(Status::internal)
+}
+
+/// Feed a `Recognize` call's audio to the live engine, sending each result
+/// 
+/// Runs on the blocking pool, waiting for requests with `handle`.
+fn run_recognition(
+    engine: &SharedEngine,
+    engine_rate: u32,
+    requests: &mut Streaming<RecognizeRequest>,
+    handle: &Handle,
+    sender: &mpsc::Sender<Result<RecognitionResponse, Status>>,
+) -> Result<(), Status> {
+    let mut decoder = ChunkDecoder::new(engine_rate);
+    with_recognizer(engine, |e| {
+        e.reset();
+        Ok(())
+    })?;
+    while let Some(message) = handle.block_on(requests.message())? {
+        let result = match message.request {
+            Some(recognize_request::Request::Audio(chunk)) => {
+                let frame = to_i16(&decoder.decode(&chunk)?);
+                with_recognizer(engine, |e| e.accept(&frame))?
+            }
+            Some(recognize_request::Request::Finish(true)) => with_recognizer(engine, |e| e.finish())?,
+            _ => None,
+        };
+        if let Some(result) = result {
+            if sender.blocking_send(Ok(result.into())).is_err() {
+                // The client went away
+                return Ok(());
+            }
+        }
+    }
+    // The client closed its side: report what is left of the utterance
+    if let Some(result) = with_recognizer(engine, |e| e.finish())? {
+        let _ = sender.blocking_send(Ok(result.into()));
+    }
+    Ok(())
+}
+
+/// Feed a `DetectWakeWord` call's audio to the detector, sending each detection
+fn run_detection(
+    state: &Mutex<DetectorState>,
+    (frame_length, engine_rate): (usize, u32),
+    chunks: &mut Streaming<AudioChunk>,
+    handle: &Handle,
+    sender: &mpsc::Sender<Result<WakeWordEvent, Status>>,
+) -> Result<(), Status> {
+    let mut decoder = ChunkDecoder::new(engine_rate);
+    let mut pending: Vec<i16> = Vec::with_capacity(frame_length * 2);
+    let mut processed: u64 = 0;
+    while let Some(chunk) = handle.block_on(chunks.message())? {
+        pending.extend(to_i16(&decoder.decode(&chunk)?));
+        while pending.len() >= frame_length {
+            let frame: Vec<i16> = pending.drain(..frame_length).collect();
+            processed += frame_length as u64;
+            let detection = state.lock().unwrap().process_frame(&frame).map_err(Status::internal)?;
+            if let Some(detection) = detection {
+                let event = WakeWordEvent {
+                    keyword: detection.keyword,
+                    index: detection.index as u32,
+                    confidence: detection.confidence,
+                    stream_time: processed as f64 / engine_rate as f64,
+                    timestamp: unix_time(),
+                };
+                if sender.blocking_send(Ok(event)).is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+struct Service {
+    engines: Arc<Engines>,
+}
+
+#[tonic::async_trait]
+impl SpeechService for Service {
+    async fn get_info(&self, _request: Request<InfoRequest>) -> Result<Response<ServiceInfo>, Status> {
+        Ok(Response::new(self.engines.info.clone()))
+    }
+    
+    type RecognizeStream = ResponseStream<RecognitionResponse>;
+    
+    async fn recognize(
+        &self,
+        request: Request<Streaming<RecognizeRequest>>,
+    ) -> Result<Response<Self::RecognizeStream>, Status> {
+        let (engine, engine_rate) = self.engines.recognizer()?;
+        let guard = StreamGuard::acquire(&self.engines.recognizing, "recognizer")?;
+        let mut requests = request.into_inner();
+        let (sender, receiver) = mpsc::channel(RESPONSE_BUFFER);
+        let handle = Handle::current();
+        
+        // Engine calls block, so the call runs on the blocking pool and waits for requests there
+        tokio::task::spawn_blocking(move || {
+            let _guard = guard;
+            if let Err(status) = run_recognition(&engine, engine_rate, &mut requests, &handle, &sender) {
+                let _ = sender.blocking_send(Err(status));
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+    
+    async fn transcribe(&self, request: Request<TranscribeRequest>) -> Result<Response<RecognitionResponse>, Status> {
+        let (engine, _) = self.engines.recognizer()?;
+        let chunk = request.into_inner().audio.unwrap_or_default();
+        let result = tokio::task::spawn_blocking(move || {
+            let (rate, channels) = chunk
+                .format
+                .as_ref()
+                .map_or((RECOGNITION_SAMPLE_RATE, 1), |f: &AudioFormat| (f.sample_rate, f.channels));
+            let rate = if rate == 0 { RECOGNITION_SAMPLE_RATE } else { rate };
+            let audio = resample_mono(&decode_pcm(&chunk.pcm, channels), rate, RECOGNITION_SAMPLE_RATE)
+                .map_err(|err| Status::invalid_argument(err.to_string()))?;
+            with_recognizer(&engine, |e| e.transcribe(&audio))
+        })
+        .await
+        .map_err(|err| Status::internal(err.to_string()))??;
+        Ok(Response::new(result.into()))
+    }
+    
+    type SynthesizeStream = ResponseStream<AudioChunk>;
+    
+    async fn synthesize(&self, request: Request<SynthesizeRequest>) -> Result<Response<Self::SynthesizeStream>, Status> {
+        let engines = self.engines.clone();
+        let text = request.into_inner().text;
+        let (pcm, sample_rate) = tokio::task::spawn_blocking(move || {
+            let tts = engines.tts.as_ref().ok_or_else(|| Status::unimplemented("Text-to-speech is not served"))?;
+            Python::with_gil(|py| tts.borrow(py).render(py, &text).map_err(|err| Status::internal(err.to_string())))
+        })
+        .await
+        .map_err(|err| Status::internal(err.to_string()))??;
+        
+        let format = AudioFormat { sample_rate, channels: 1 };
+        let chunks: Vec<Result<AudioChunk, Status>> = pcm
+            .chunks(SYNTHESIS_CHUNK_SAMPLES)
+            .enumerate()
+            .map(|(index, samples)| {
+                Ok(AudioChunk {
+                    format: (index == 0).then(|| format.clone()),
+                    pcm: samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+                })
+            })
+            .collect();
+        Ok(Response::new(Box::pin(tokio_stream::iter(chunks))))
+    }
+    
+    type DetectWakeWordStream = ResponseStream<WakeWordEvent>;
+    
+    async fn detect_wake_word(
+        &self,
+        request: Request<Streaming<AudioChunk>>,
+    ) -> Result<Response<Self::DetectWakeWordStream>, Status> {
+        let state = self.engines.detector.clone().ok_or_else(|| Status::unimplemented("Wake word detection is not served"))?;
+        let guard = StreamGuard::acquire(&self.engines.detecting, "wake word detector")?;
+        let (frame_length, engine_rate) = state.lock().unwrap().frame_format().map_err(Status::failed_precondition)?;
+        let mut chunks = request.into_inner();
+        let (sender, receiver) = mpsc::channel(RESPONSE_BUFFER);
+        let handle = Handle::current();
+        
+        tokio::task::spawn_blocking(move || {
+            let _guard = guard;
+            if let Err(status) = run_detection(&state, (frame_length, engine_rate), &mut chunks, &handle, &sender) {
+                let _ = sender.blocking_send(Err(status));
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+}
+
+/// A running server: its runtime and the signal that ends `serve`
+struct Server {
+    address: SocketAddr,
+    runtime: Mutex<Option<Runtime>>,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl Resource for Server {
+    /// Stop accepting calls and wait briefly for the ones in flight
+    fn shutdown(&self) {
+        if let Some(signal) = self.shutdown.lock().unwrap().take() {
+            let _ = signal.send(());
+        }
+        if let Some(runtime) = self.runtime.lock().unwrap().take() {
+            runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+        }
+    }
+}
+
+/// gRPC server for the speech engines (`proto/speech.proto`)
+/// 
+/// Serves any combination of a `SpeechRecognizer`, `TextToSpeech` and
+/// `WakeWordDetector` as the `jenna.speech.SpeechService`, so clients
+/// that can't load the Python bindings (the mobile app, other services)
+/// can stream audio in and get results out. Audio is 16-bit PCM at any
+/// rate and channel count; it is converted to what each engine expects.
+/// 
+/// `Transcribe` uses offline transcription and can run alongside other
+/// calls. `Recognize` and `DetectWakeWord` drive the engines' live state,
+/// so each serves one call at a time (others get UNAVAILABLE), and the
+/// engines should not be used elsewhere while a stream is open.
+#[pyclass]
+pub struct GrpcServer {
+    recognizer: Option<Py<SpeechRecognizer>>,
+    tts: Option<Py<TextToSpeech>>,
+    detector: Option<Py<WakeWordDetector>>,
+    host: String,
+    port: u16,
+    server: Option<Arc<Server>>,
+}
+
+impl GrpcServer {
+    /// Resolve the engines and describe them
+    fn engines(&self, py: Python) -> PyResult<Engines> {
+        let mut info = ServiceInfo { version: env!("CARGO_PKG_VERSION").to_string(), ..Default::default() };
+        
+        let recognizer = match &self.recognizer {
+            Some(recognizer) => {
+                let (engine, sample_rate) = recognizer.borrow(py).shared_engine()?;
+                info.recognizer = engine.lock().unwrap().as_deref().map_or("", |e| e.name()).to_string();
+                info.recognizer_sample_rate = sample_rate;
+                Some((engine, sample_rate))
+            }
+            None => None,
+        };
+        if let Some(tts) = &self.tts {
+            info.voice = tts.borrow(py).voice_info().0;
+        }
+        let detector = match &self.detector {
+            Some(detector) => {
+                let state = detector.borrow(py).shared_state();
+                {
+                    let state = state.lock().unwrap();
+                    let (_, sample_rate) = state.frame_format().map_err(PyValueError::new_err)?;
+                    info.keywords = state.keyword_names().to_vec();
+                    info.wake_word_sample_rate = sample_rate;
+                }
+                Some(state)
+            }
+            None => None,
+        };
+        
+        Ok(Engines {
+            recognizer,
+            tts: self.tts.as_ref().map(|tts| tts.clone_ref(py)),
+            detector,
+            info,
+            recognizing: Arc::new(AtomicBool::new(false)),
+            detecting: Arc::new(AtomicBool::new(false)),
+        })
+    }
+    
+    fn running(&self) -> Option<&Arc<Server>> {
+        self.server.as_ref().filter(|server| server.runtime.lock().unwrap().is_some())
+    }
+}
+
+#[pymethods]
+impl GrpcServer {
+    /// Create a server for the given engines; at least one is required
+    /// 
+    /// Port 0 picks a free port.
+    #[new]
+    #[pyo3(signature = (recognizer=None, tts=None, detector=None, host="0.0.0.0", port=DEFAULT_PORT))]
+    fn new(
+        recognizer: Option<Py<SpeechRecognizer>>,
+        tts: Option<Py<TextToSpeech>>,
+        detector: Option<Py<WakeWordDetector>>,
+        host: &str,
+        port: u16,
+    ) -> PyResult<Self> {
+        if recognizer.is_none() && tts.is_none() && detector.is_none() {
+            return Err(PyValueError::new_err("A gRPC server needs a recognizer, a voice or a wake word detector"));
+        }
+        Ok(GrpcServer { recognizer, tts, detector, host: host.to_string(), port, server: None })
+    }
+    
+    /// Bind the port and start serving, returning the bound port
+    /// 
+    /// The engines must be initialized. Fails if the server is already running.
+    fn start(&mut self, py: Python) -> PyResult<u16> {
+        if self.running().is_some() {
+            return Err(PyValueError::new_err("gRPC server is already running"));
+        }
+        let engines = self.engines(py)?;
+        let address = format!("{}:{}", self.host, self.port);
+        let listener = std::net::TcpListener::bind(&address)
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|err| PyValueError::new_err(format!("Failed to bind {}: {}", address, err)))?;
+        let local = listener.local_addr().map_err(|err| PyValueError::new_err(err.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("jenna-grpc")
+            .enable_all()
+            .build()
+            .map_err(|err| PyValueError::new_err(format!("Failed to start gRPC runtime: {}", err)))?;
+        let listener = {
+            let _context = runtime.enter();
+            tokio::net::TcpListener::from_std(listener).map_err(|err| PyValueError::new_err(err.to_string()))?
+        };
+        
+        let (signal, stopped) = oneshot::channel::<()>();
+        let service = SpeechServiceServer::new(Service { engines: Arc::new(engines) });
+        runtime.spawn(async move {
+            let served = tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async {
+                    let _ = stopped.await;
+                })
+                .await;
+            if let Err(err) = served {
+                tracing::error!("gRPC server stopped: {}", err);
+            }
+        });
+        
+        tracing::info!(address = %local, "gRPC server listening");
+        let server = Arc::new(Server {
+            address: local,
+            runtime: Mutex::new(Some(runtime)),
+            shutdown: Mutex::new(Some(signal)),
+        });
+        lifecycle::register(&server);
+        self.server = Some(server);
+        Ok(local.port())
+    }
+    
+    /// Stop serving; open streams are cancelled
+    fn stop(&mut self, py: Python) {
+        if let Some(server) = self.server.take() {
+            // Synthesis calls may need the GIL to finish
+            py.allow_threads(|| server.shutdown());
+        }
+    }
+    
+    fn is_running(&self) -> bool {
+        self.running().is_some()
+    }
+    
+    /// Port the server is bound to, or the configured port before `start`
+    #[getter]
+    fn get_port(&self) -> u16 {
+        self.running().map_or(self.port, |server| server.address.port())
+    }
+    
+    fn __repr__(&self) -> String {
+        format!("GrpcServer(host='{}', port={}, running={})", self.host, self.get_port(), self.is_running())
+    }
+}
+
+impl Drop for GrpcServer {
+    fn drop(&mut self) {
+        // Don't wait for calls that may need the GIL (see `VoicePipeline`'s Drop)
+        if let Some(server) = self.server.take() {
+            if let Some(signal) = server.shutdown.lock().unwrap().take() {
+                let _ = signal.send(());
+            }
+            if let Some(runtime) = server.runtime.lock().unwrap().take() {
+                runtime.shutdown_background();
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyDict, PyList};
+use jenna_core::inference::{cached_models, run_limit, set_max_concurrent_runs};
+
+/// Run at most `threads` neural model inferences at once, or lift the limit with None
+/// 
+/// Wake word, speaker, sound event, punctuation and Piper models each run
+/// on the thread that calls them, so this caps the CPU cores they take
+/// together; callers over the limit wait their turn. Whisper and Vosk
+/// manage their own threads and aren't counted.
+#[pyfunction]
+#[pyo3(signature = (threads=None))]
+pub fn set_inference_threads(threads: Option<usize>) -> PyResult<()> {
+    set_max_concurrent_runs(threads).map_err(PyValueError::new_err)
+}
+
+/// Shared ONNX models and the inference limit
+/// 
+/// Returns a dict with "max_threads" (None without a limit), "running"
+/// (inferences executing now) and "models" (one dict per cached model
+/// with "path", "shape" and "users", the engines sharing it).
+#[pyfunction]
+pub fn inference_status(py: Python) -> PyResult<Py<PyDict>> {
+    let models = PyList::empty(py);
+    for model in cached_models() {
+        let entry = PyDict::new(py);
+        entry.set_item("path", model.path.display().to_string())?;
+        entry.set_item("shape", model.shape)?;
+        entry.set_item("users", model.users)?;
+        models.append(entry)?;
+    }
+    
+    let (max_threads, running) = run_limit();
+    let status = PyDict::new(py);
+    status.set_item("max_threads", max_threads)?;
+    status.set_item("running", running)?;
+    status.set_item("models", models)?;
+    Ok(status.into())
+}
@@ -0,0 +1,530 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::{HashMap, HashSet};
+
+use crate::entities::{parse_kind, Entity, EntityKind};
+
+/// Most alternatives a single template may expand to
+const MAX_EXPANSIONS: usize = 1024;
+
+/// Most words a free-text slot captures
+const MAX_SLOT_WORDS: usize = 8;
+
+/// Words skipped between template elements by default
+const DEFAULT_FILLERS: &[&str] = &["please", "could", "can", "you", "would", "kindly", "just", "um", "uh", "hey"];
+
+/// Weight of a word captured by a free-text slot, relative to a literal
+const FREE_SLOT_WEIGHT: f32 = 0.6;
+
+/// Weight of a skipped filler word, relative to a literal
+const FILLER_WEIGHT: f32 = 0.3;
+
+/// Lowercase a transcript and split it into words, dropping punctuation
+/// 
+/// Apostrophes inside words are kept ("what's"), as are decimal points
+/// and signs attached to digits ("3.5", "-2").
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|word| {
+            let word = word.to_lowercase();
+            let trimmed = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-' && c != '.');
+            let trimmed = trimmed.trim_end_matches(['.', '-']).trim_matches('\'');
+            // Keep a leading minus only on numbers
+            if trimmed.starts_with('-') && !trimmed[1..].starts_with(|c: char| c.is_ascii_digit()) {
+                trimmed.trim_start_matches('-').to_string()
+            } else {
+                trimmed.to_string()
+            }
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// A template element before expansion
+#[derive(Clone, Debug)]
+enum Element {
+    Word(String),
+    Slot(String, Option<EntityKind>),
+    /// One of several sequences; an empty sequence makes the group optional
+    Choice(Vec<Vec<Element>>),
+}
+
+/// Parse a template: words, `{slot}` or `{slot:kind}`, `(a | b)` alternatives and `[optional]` parts
+fn parse_template(template: &str) -> Result<Vec<Element>, String> {
+    let chars: Vec<char> = template.chars().collect();
+    let mut pos = 0;
+    let alternatives = parse_alternatives(&chars, &mut pos, None)?;
+    if alternatives.len() == 1 {
+        Ok(alternatives.into_iter().next().unwrap())
+    } else {
+        Ok(vec![Element::Choice(alternatives)])
+    }
+}
+
+/// Parse `|`-separated sequences up to `close` (or the end of the template)
+fn parse_alternatives(chars: &[char], pos: &mut usize, close: Option<char>) -> Result<Vec<Vec<Element>>, String> {
+    let mut alternatives = Vec::new();
+    let mut sequence = Vec::new();
+    let mut word = String::new();
+    
+    let flush = |word: &mut String, sequence: &mut Vec<Element>| {
+        for token in tokenize(word) {
+            sequence.push(Element::Word(token));
+        }
+        word.clear();
+    };
+    
+    while *pos < chars.len() {
+        let c = chars[*pos];
+        *pos += 1;
+        match c {
+            '(' | '[' => {
+                flush(&mut word, &mut sequence);
+                let closing = if c == '(' { ')' } else { ']' };
+                let mut group = parse_alternatives(chars, pos, Some(closing))?;
+                if c == '[' {
+                    group.push(Vec::new());
+                }
+                sequence.push(Element::Choice(group));
+            }
+            ')' | ']' => {
+                if Some(c) != close {
+                    return Err(format!("Unexpected '{}' at position {}", c, *pos - 1));
+                }
+                flush(&mut word, &mut sequence);
+                alternatives.push(sequence);
+                return Ok(alternatives);
+            }
+            '|' => {
+                flush(&mut word, &mut sequence);
+                alternatives.push(std::mem::take(&mut sequence));
+            }
+            '{' => {
+                flush(&mut word, &mut sequence);
+                let end = chars[*pos..]
+                    .iter()
+                    .position(|&c| c == '}')
+                    .ok_or_else(|| format!("Unclosed '{{' at position {}", *pos - 1))?;
+                let spec: String = chars[*pos..*pos + end].iter().collect();
+                let (name, kind) = match spec.split_once(':') {
+                    Some((name, kind)) => {
+                        let kind = EntityKind::from_name(kind)
+                            .ok_or_else(|| format!("Unknown slot type '{}' in '{{{}}}'", kind.trim(), spec))?;
+                        (name.trim().to_string(), Some(kind))
+                    }
+                    None => (spec.trim().to_string(), None),
+                };
+                if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    return Err(format!("Invalid slot name '{}'", name));
+                }
+                *pos += end + 1;
+                sequence.push(Element::Slot(name, kind));
+            }
+            '}' => return Err(format!("Unexpected '}}' at position {}", *pos - 1)),
+            _ => word.push(c),
+        }
+    }
+    
+    if let Some(close) = close {
+        return Err(format!("Missing '{}'", close));
+    }
+    flush(&mut word, &mut sequence);
+    alternatives.push(sequence);
+    Ok(alternatives)
+}
+
+/// A template element after expansion
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Slot(String, Option<EntityKind>),
+}
+
+/// Expand choices into every linear sequence they allow
+fn expand(elements: &[Element]) -> Result<Vec<Vec<Token>>, String> {
+    let mut sequences: Vec<Vec<Token>> = vec![Vec::new()];
+    for element in elements {
+        match element {
+            Element::Word(word) => sequences.iter_mut().for_each(|s| s.push(Token::Word(word.clone()))),
+            Element::Slot(name, kind) => sequences.iter_mut().for_each(|s| s.push(Token::Slot(name.clone(), *kind))),
+            Element::Choice(alternatives) => {
+                let mut expanded = Vec::new();
+                for alternative in alternatives {
+                    for tail in expand(alternative)? {
+                        for head in &sequences {
+                            expanded.push(head.iter().cloned().chain(tail.iter().cloned()).collect());
+                        }
+                    }
+                    if expanded.len() > MAX_EXPANSIONS {
+                        return Err(format!("Template expands to more than {} alternatives", MAX_EXPANSIONS));
+                    }
+                }
+                sequences = expanded;
+            }
+        }
+    }
+    Ok(sequences)
+}
+
+/// One linear expansion of a template
+struct Pattern {
+    intent: String,
+    template: String,
+    tokens: Vec<Token>,
+    /// Literal words that must all appear in the transcript
+    literals: HashSet<String>,
+}
+
+/// Known values of a slot, as token sequences mapped to their canonical value
+#[derive(Default)]
+struct SlotValues {
+    values: Vec<(Vec<String>, String)>,
+}
+
+/// A slot filled while matching
+#[derive(Clone)]
+struct SlotFill {
+    name: String,
+    raw: String,
+    value: String,
+    entity: Option<Entity>,
+}
+
+/// Best match of one pattern found so far while backtracking
+#[derive(Clone, Default)]
+struct Assignment {
+    slots: Vec<SlotFill>,
+    literal_words: usize,
+    known_slot_words: usize,
+    free_slot_words: usize,
+    fillers: usize,
+}
+
+impl Assignment {
+    fn score(&self, total: usize) -> f32 {
+        let weighted = self.literal_words as f32
+            + self.known_slot_words as f32
+            + self.free_slot_words as f32 * FREE_SLOT_WEIGHT
+            + self.fillers as f32 * FILLER_WEIGHT;
+        weighted / total.max(1) as f32
+    }
+}
+
+/// Intent and slots recognized in a transcript
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct IntentMatch {
+    #[pyo3(get)]
+    pub intent: String,
+    /// Slot values; slots with known values map to the canonical value
+    #[pyo3(get)]
+    pub slots: HashMap<String, String>,
+    /// Slot text as spoken
+    #[pyo3(get)]
+    pub raw_slots: HashMap<String, String>,
+    /// Parsed values of typed slots such as `{minutes:number}`
+    #[pyo3(get)]
+    pub entities: HashMap<String, Entity>,
+    /// 1.0 when every word matched a literal or a known slot value
+    #[pyo3(get)]
+    pub confidence: f32,
+    /// The template that matched
+    #[pyo3(get)]
+    pub template: String,
+}
+
+#[pymethods]
+impl IntentMatch {
+    fn __repr__(&self) -> String {
+        format!(
+            "IntentMatch(intent={:?}, slots={:?}, confidence={:.2})",
+            self.intent, self.slots, self.confidence
+        )
+    }
+}
+
+/// Template-based intent matcher
+/// 
+/// Templates are plain words with `{slot}` placeholders, `(a | b)`
+/// alternatives and `[optional words]`, e.g. "turn (on | off) the {device}
+/// [in the {room}]". Each template is expanded once into linear patterns,
+/// indexed by their literal words, so a transcript is only matched against
+/// patterns whose words it contains. A slot captures up to eight words, or
+/// exactly one of its values if `add_slot_values` registered any. Typed
+/// slots (`{minutes:number}`, `{when:time}`, `{length:duration}`,
+/// `{day:date}`, `{position:ordinal}`) capture a spoken entity and report
+/// its parsed value in `IntentMatch.entities`. Filler words ("please",
+/// "can you") may appear between template words.
+#[pyclass]
+pub struct IntentEngine {
+    patterns: Vec<Pattern>,
+    slot_values: HashMap<String, SlotValues>,
+    fillers: HashSet<String>,
+    /// Patterns containing each literal word, for the pre-filter
+    index: HashMap<String, Vec<usize>>,
+    /// Patterns made only of slots, which every transcript must be tried against
+    unindexed: Vec<usize>,
+}
+
+impl IntentEngine {
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        self.unindexed.clear();
+        for (i, pattern) in self.patterns.iter().enumerate() {
+            // Index on the rarest-looking word: the longest literal
+            match pattern.literals.iter().max_by_key(|word| (word.len(), word.as_str())) {
+                Some(word) => self.index.entry(word.clone()).or_default().push(i),
+                None => self.unindexed.push(i),
+            }
+        }
+    }
+    
+    /// Match a transcript, best first, keeping at most one match per intent
+    pub fn matches(&self, text: &str) -> Vec<IntentMatch> {
+        let words = tokenize(text);
+        if words.is_empty() {
+            return Vec::new();
+        }
+        let present: HashSet<&str> = words.iter().map(String::as_str).collect();
+        
+        let mut candidates: Vec<usize> = present
+            .iter()
+            .filter_map(|word| self.index.get(*word))
+            .flatten()
+            .copied()
+            .chain(self.unindexed.iter().copied())
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        
+        let mut best: HashMap<&str, IntentMatch> = HashMap::new();
+        for i in candidates {
+            let pattern = &self.patterns[i];
+            if !pattern.literals.iter().all(|word| present.contains(word.as_str())) {
+                continue;
+            }
+            let mut found: Option<Assignment> = None;
+            self.match_tokens(&pattern.tokens, &words, Assignment::default(), &mut found);
+            let assignment = match found {
+                Some(assignment) => assignment,
+                None => continue,
+            };
+            
+            let confidence = assignment.score(words.len());
+            if best.get(pattern.intent.as_str()).map_or(true, |m| confidence > m.confidence) {
+                let mut slots = HashMap::new();
+                let mut raw_slots = HashMap::new();
+                let mut entities = HashMap::new();
+                for fill in assignment.slots {
+                    if let Some(entity) = fill.entity {
+                        entities.insert(fill.name.clone(), entity);
+                    }
+                    raw_slots.insert(fill.name.clone(), fill.raw);
+                    slots.insert(fill.name, fill.value);
+                }
+                best.insert(&pattern.intent, IntentMatch {
+                    intent: pattern.intent.clone(),
+                    slots,
+                    raw_slots,
+                    entities,
+                    confidence,
+                    template: pattern.template.clone(),
+                });
+            }
+        }
+        
+        let mut matches: Vec<IntentMatch> = best.into_values().collect();
+        matches.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.intent.cmp(&b.intent)));
+        matches
+    }
+    
+    /// Backtracking match of `tokens` against all of `words`, keeping the best-scoring assignment
+    fn match_tokens(&self, tokens: &[Token], words: &[String], current: Assignment, best: &mut Option<Assignment>) {
+        let total = current.literal_words + current.known_slot_words + current.free_slot_words + current.fillers;
+        let remaining = &words[total..];
+        
+        let (token, rest) = match tokens.split_first() {
+            Some(split) => split,
+            None => {
+                // Trailing fillers are allowed
+                if remaining.iter().all(|word| self.fillers.contains(word)) {
+                    let complete = Assignment { fillers: current.fillers + remaining.len(), ..current };
+                    let score = complete.score(words.len());
+                    if best.as_ref().map_or(true, |b| score > b.score(words.len())) {
+                        *best = Some(complete);
+                    }
+                }
+                return;
+            }
+        };
+        if remaining.is_empty() {
+            return;
+        }
+        
+        match token {
+            Token::Word(word) => {
+                if &remaining[0] == word {
+                    let next = Assignment { literal_words: current.literal_words + 1, ..current.clone() };
+                    self.match_tokens(rest, words, next, best);
+                }
+            }
+            Token::Slot(name, Some(kind)) => {
+                if let Some((value, len)) = parse_kind(*kind, remaining) {
+                    let raw = remaining[..len].join(" ");
+                    let start = total;
+                    let entity = Entity { text: raw.clone(), start, end: start + len, value };
+                    let mut next = current.clone();
+                    next.known_slot_words += len;
+                    next.slots.push(SlotFill { name: name.clone(), raw, value: value.canonical(), entity: Some(entity) });
+                    self.match_tokens(rest, words, next, best);
+                }
+            }
+            Token::Slot(name, None) => match self.slot_values.get(name) {
+                Some(known) => {
+                    for (value_words, canonical) in &known.values {
+                        if remaining.len() >= value_words.len() && remaining[..value_words.len()] == value_words[..] {
+                            let mut next = current.clone();
+                            next.known_slot_words += value_words.len();
+                            next.slots.push(SlotFill {
+                                name: name.clone(),
+                                raw: value_words.join(" "),
+                                value: canonical.clone(),
+                                entity: None,
+                            });
+                            self.match_tokens(rest, words, next, best);
+                        }
+                    }
+                }
+                None => {
+                    for len in 1..=remaining.len().min(MAX_SLOT_WORDS) {
+                        let text = remaining[..len].join(" ");
+                        let mut next = current.clone();
+                        next.free_slot_words += len;
+                        next.slots.push(SlotFill { name: name.clone(), raw: text.clone(), value: text, entity: None });
+                        self.match_tokens(rest, words, next, best);
+                    }
+                }
+            },
+        }
+        
+        // Skip a filler word before this token
+        if self.fillers.contains(&remaining[0]) {
+            let next = Assignment { fillers: current.fillers + 1, ..current };
+            self.match_tokens(tokens, words, next, best);
+        }
+    }
+}
+
+#[pymethods]
+impl IntentEngine {
+    #[new]
+    fn new() -> Self {
+        IntentEngine {
+            patterns: Vec::new(),
+            slot_values: HashMap::new(),
+            fillers: DEFAULT_FILLERS.iter().map(|w| w.to_string()).collect(),
+            index: HashMap::new(),
+            unindexed: Vec::new(),
+        }
+    }
+    
+    /// Register templates for an intent, adding to any it already has
+    /// 
+    /// Raises ValueError if a template is malformed.
+    fn add_intent(&mut self, name: String, templates: Vec<String>) -> PyResult<()> {
+        let mut patterns = Vec::new();
+        for template in templates {
+            let elements = parse_template(&template)
+                .map_err(|err| PyValueError::new_err(format!("Invalid template '{}': {}", template, err)))?;
+            let sequences = expand(&elements)
+                .map_err(|err| PyValueError::new_err(format!("Invalid template '{}': {}", template, err)))?;
+            for tokens in sequences.into_iter().filter(|tokens| !tokens.is_empty()) {
+                let literals = tokens
+                    .iter()
+                    .filter_map(|token| match token {
+                        Token::Word(word) => Some(word.clone()),
+                        Token::Slot(..) => None,
+                    })
+                    .collect();
+                patterns.push(Pattern { intent: name.clone(), template: template.clone(), tokens, literals });
+            }
+        }
+        
+        self.patterns.extend(patterns);
+        self.rebuild_index();
+        Ok(())
+    }
+    
+    /// Remove an intent and its templates, returning whether it existed
+    fn remove_intent(&mut self, name: &str) -> bool {
+        let before = self.patterns.len();
+        self.patterns.retain(|pattern| pattern.intent != name);
+        self.rebuild_index();
+        self.patterns.len() != before
+    }
+    
+    /// Restrict a slot to known values
+    /// 
+    /// `values` is a list of phrases, or a dict mapping each canonical value
+    /// to its spoken synonyms (e.g. `{"on": ["on", "up"]}`). Without values
+    /// a slot captures free text.
+    fn add_slot_values(&mut self, slot: String, values: &PyAny) -> PyResult<()> {
+        let entries: Vec<(String, String)> = if let Ok(synonyms) = values.extract::<HashMap<String, Vec<String>>>() {
+            synonyms
+                .into_iter()
+                .flat_map(|(canonical, phrases)| {
+                    std::iter::once(canonical.clone())
+                        .chain(phrases)
+                        .map(move |phrase| (phrase, canonical.clone()))
+                })
+                .collect()
+        } else {
+            values.extract::<Vec<String>>()?.into_iter().map(|phrase| (phrase.clone(), phrase)).collect()
+        };
+        
+        let known = self.slot_values.entry(slot).or_default();
+        for (phrase, canonical) in entries {
+            let words = tokenize(&phrase);
+            if !words.is_empty() && !known.values.iter().any(|(existing, _)| *existing == words) {
+                known.values.push((words, canonical));
+            }
+        }
+        Ok(())
+    }
+    
+    /// Let a slot capture free text again
+    fn clear_slot_values(&mut self, slot: &str) {
+        self.slot_values.remove(slot);
+    }
+    
+    /// Replace the words that may be skipped between template words
+    fn set_fillers(&mut self, fillers: Vec<String>) {
+        self.fillers = fillers.iter().flat_map(|filler| tokenize(filler)).collect();
+    }
+    
+    /// List the registered intent names
+    fn intents(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.patterns.iter().map(|pattern| pattern.intent.clone()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+    
+    /// Find the best intent for a transcript, or None below `min_confidence`
+    #[pyo3(signature = (text, min_confidence=0.5))]
+    fn parse(&self, py: Python, text: &str, min_confidence: f32) -> Option<IntentMatch> {
+        py.allow_threads(|| self.matches(text))
+            .into_iter()
+            .next()
+            .filter(|m| m.confidence >= min_confidence)
+    }
+    
+    /// Get up to `limit` intents matching a transcript, best first
+    #[pyo3(signature = (text, limit=5, min_confidence=0.0))]
+    fn parse_all(&self, py: Python, text: &str, limit: usize, min_confidence: f32) -> Vec<IntentMatch> {
+        let mut matches = py.allow_threads(|| self.matches(text));
+        matches.retain(|m| m.confidence >= min_confidence);
+        matches.truncate(limit);
+        matches
+    }
+}
@@ -0,0 +1,134 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use jenna_core::keywords::KeywordSpotter as Spotter;
+
+/// Watched phrases and the callbacks registered for them
+pub struct SpotterState {
+    spotter: Spotter,
+    callbacks: HashMap<String, PyObject>,
+}
+
+impl SpotterState {
+    /// Scan a transcript and run the callbacks of the phrases newly heard
+    /// 
+    /// Callbacks run without the spotter locked, so they may add or remove
+    /// phrases themselves.
+    pub fn spot(state: &Mutex<SpotterState>, transcript: &str) -> Vec<String> {
+        let (heard, callbacks) = {
+            let mut state = state.lock().unwrap();
+            let heard = state.spotter.feed(transcript);
+            if heard.is_empty() {
+                return heard;
+            }
+            // Cloning only defers the reference count, so the GIL isn't needed while locked
+            let callbacks: Vec<(String, PyObject)> = heard
+                .iter()
+                .filter_map(|phrase| state.callbacks.get(phrase).map(|callback| (phrase.clone(), callback.clone())))
+                .collect();
+            (heard, callbacks)
+        };
+        if !callbacks.is_empty() {
+            Python::with_gil(|py| {
+                for (phrase, callback) in callbacks {
+                    if let Err(err) = callback.call1(py, (phrase, transcript)) {
+                        err.print(py);
+                    }
+                }
+            });
+        }
+        heard
+    }
+    
+    pub fn reset(&mut self) {
+        self.spotter.reset();
+    }
+}
+
+/// Spotter shared between its Python object and a running pipeline
+pub type SharedKeywordSpotter = Arc<Mutex<SpotterState>>;
+
+/// Spots trigger phrases ("stop", "cancel", "emergency") in partial transcripts
+/// 
+/// Feed every partial result of an utterance to `feed`; each phrase is
+/// reported once per time it is said, as soon as a partial contains it,
+/// and its callback is called with `(phrase, transcript)`. Phrases match
+/// whole words, ignoring case and punctuation, and all of them are found
+/// in a single pass (Aho-Corasick). Call `reset` between utterances, or
+/// attach the spotter to a `VoicePipeline` with `set_keyword_spotter`,
+/// which does both and also posts "keyword" events.
+#[pyclass]
+pub struct KeywordSpotter {
+    state: SharedKeywordSpotter,
+}
+
+impl KeywordSpotter {
+    pub fn shared_spotter(&self) -> SharedKeywordSpotter {
+        self.state.clone()
+    }
+}
+
+#[pymethods]
+impl KeywordSpotter {
+    #[new]
+    #[pyo3(signature = (phrases=None))]
+    fn new(phrases: Option<Vec<String>>) -> Self {
+        let spotter = Spotter::new(phrases.unwrap_or_default());
+        KeywordSpotter { state: Arc::new(Mutex::new(SpotterState { spotter, callbacks: HashMap::new() })) }
+    }
+    
+    /// Watched phrases, in the order they were added
+    #[getter]
+    fn phrases(&self) -> Vec<String> {
+        self.state.lock().unwrap().spotter.phrases().to_vec()
+    }
+    
+    /// Watch for `phrase`, calling `callback(phrase, transcript)` when it is heard
+    /// 
+    /// Adding a phrase that is already watched replaces its callback.
+    #[pyo3(signature = (phrase, callback=None))]
+    fn add(&self, py: Python, phrase: &str, callback: Option<PyObject>) -> PyResult<()> {
+        if let Some(callback) = &callback {
+            if !callback.as_ref(py).is_callable() {
+                return Err(PyValueError::new_err("Callback must be callable"));
+            }
+        }
+        let mut state = self.state.lock().unwrap();
+        state.spotter.add(phrase);
+        let watched = state
+            .spotter
+            .find(phrase)
+            .map(str::to_string)
+            .ok_or_else(|| PyValueError::new_err("Phrase must contain a word"))?;
+        match callback {
+            Some(callback) => state.callbacks.insert(watched, callback),
+            None => state.callbacks.remove(&watched),
+        };
+        Ok(())
+    }
+    
+    /// Stop watching `phrase`; returns whether it was watched
+    fn remove(&self, phrase: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if let Some(watched) = state.spotter.find(phrase).map(str::to_string) {
+            state.callbacks.remove(&watched);
+        }
+        state.spotter.remove(phrase)
+    }
+    
+    /// Scan the latest partial or final transcript, returning the phrases newly heard
+    fn feed(&self, py: Python, transcript: &str) -> Vec<String> {
+        let state = self.state.clone();
+        py.allow_threads(|| SpotterState::spot(&state, transcript))
+    }
+    
+    /// Start a new utterance
+    fn reset(&self) {
+        self.state.lock().unwrap().reset();
+    }
+    
+    fn __len__(&self) -> usize {
+        self.state.lock().unwrap().spotter.phrases().len()
+    }
+}
@@ -1,9 +1,62 @@
 use pyo3::prelude::*;
 
+mod errors;
+mod lifecycle;
+mod panics;
+mod config;
+mod logging;
+mod metrics;
 mod audio;
 mod signal;
+mod filters;
+mod dynamics;
 mod wake_word;
 mod speech;
+mod tts;
+mod intent;
+mod keywords;
+mod entities;
+mod fuzzy;
+mod content_filter;
+mod formatter;
+mod wyoming;
+mod websocket;
+mod protocol;
+mod grpc;
+mod mqtt;
+mod rtp;
+pub mod engines;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "capi")]
+mod capi;
+mod speaker;
+mod sound_events;
+mod fingerprint;
+mod pipeline;
+mod dictation;
+mod session;
+mod assistant;
+mod events;
+mod backup;
+mod storage;
+mod models;
+mod loading;
+mod memory;
+mod inference;
+mod accelerator;
+mod priority;
+mod pitch;
+mod tones;
+mod onset;
+mod noise;
+mod graph;
+mod diagnostics;
+mod tuning;
+mod soak;
+mod benchmarks;
+mod comparison;
+mod testing;
 
 /// Jenna Voice Assistant Rust modules
 /// 
@@ -11,21 +64,230 @@ mod speech;
 /// and speech recognition capabilities for the Jenna Voice Assistant.
 #[pymodule]
 fn jenna_rust(_py: Python, m: &PyModule) -> PyResult<()> {
+    logging::init();
+    panics::install_hook();
+    
+    // Register exception hierarchy
+    m.add("JennaError", _py.get_type::<errors::JennaError>())?;
+    m.add("DeviceError", _py.get_type::<errors::DeviceError>())?;
+    m.add("ModelError", _py.get_type::<errors::ModelError>())?;
+    m.add("NotInitializedError", _py.get_type::<errors::NotInitializedError>())?;
+    m.add("LicenseError", _py.get_type::<errors::LicenseError>())?;
+    
+    // Register shutdown, also run at interpreter exit
+    m.add_function(wrap_pyfunction!(lifecycle::shutdown, m)?)?;
+    _py.import("atexit")?.call_method1("register", (m.getattr("shutdown")?,))?;
+    
+    // Register logging bridge
+    m.add_function(wrap_pyfunction!(logging::set_log_level, m)?)?;
+    m.add_function(wrap_pyfunction!(logging::get_log_level, m)?)?;
+    m.add_function(wrap_pyfunction!(logging::get_dropped_log_records, m)?)?;
+    
+    // Register metrics
+    m.add_function(wrap_pyfunction!(metrics::get_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::export_prometheus, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::reset_metrics, m)?)?;
+    m.add_function(wrap_pyfunction!(metrics::latency_stages, m)?)?;
+    
+    // Register memory budget controls
+    m.add_function(wrap_pyfunction!(memory::memory_usage, m)?)?;
+    m.add_function(wrap_pyfunction!(memory::set_memory_budget, m)?)?;
+    m.add_function(wrap_pyfunction!(memory::unload_idle_models, m)?)?;
+    
+    // Register neural inference controls
+    m.add_function(wrap_pyfunction!(inference::set_inference_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(inference::inference_status, m)?)?;
+    m.add_function(wrap_pyfunction!(accelerator::available_accelerators, m)?)?;
+    
+    // Register panic reporting
+    m.add_function(wrap_pyfunction!(panics::panic_count, m)?)?;
+    
+    // Register configuration
+    m.add_class::<config::JennaConfig>()?;
+    
     // Register audio module
     m.add_class::<audio::AudioBuffer>()?;
     m.add_function(wrap_pyfunction!(audio::get_input_devices, m)?)?;
     m.add_function(wrap_pyfunction!(audio::get_output_devices, m)?)?;
+    m.add_function(wrap_pyfunction!(audio::check_microphone_access, m)?)?;
+    m.add_function(wrap_pyfunction!(audio::ensure_microphone_access, m)?)?;
+    m.add_class::<tuning::DeviceConfigStore>()?;
+    m.add_class::<tuning::LatencyTuner>()?;
     
     // Register signal processing module
     m.add_function(wrap_pyfunction!(signal::compute_fft, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::compute_stft, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::get_window, m)?)?;
+    m.add_class::<signal::FftProcessor>()?;
     m.add_function(wrap_pyfunction!(signal::apply_filter, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::compute_fft_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::apply_filter_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::apply_fir_filter_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::configure_thread_pool, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::apply_fir_filter, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::design_fir, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::cross_correlate, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::estimate_delay, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::time_stretch, m)?)?;
+    m.add_function(wrap_pyfunction!(signal::pitch_shift, m)?)?;
+    
+    // Register filter design module
+    m.add_function(wrap_pyfunction!(filters::design_filter, m)?)?;
+    m.add_class::<filters::SosFilter>()?;
+    m.add_class::<filters::Equalizer>()?;
+    
+    // Register dynamics processing module
+    m.add_class::<dynamics::Compressor>()?;
+    m.add_class::<dynamics::Limiter>()?;
+    m.add_function(wrap_pyfunction!(dynamics::measure_loudness, m)?)?;
+    m.add_function(wrap_pyfunction!(dynamics::normalize_loudness, m)?)?;
+    
+    // Register pitch detection module
+    m.add_function(wrap_pyfunction!(pitch::detect_pitch, m)?)?;
+    m.add_class::<pitch::PitchTracker>()?;
+    
+    // Register tone detection module
+    m.add_function(wrap_pyfunction!(tones::goertzel, m)?)?;
+    m.add_class::<tones::ToneDetector>()?;
+    m.add_class::<tones::DtmfDetector>()?;
+    
+    // Register onset detection module
+    m.add_class::<onset::OnsetDetector>()?;
+    
+    // Register noise reduction module
+    m.add_class::<noise::NoiseGate>()?;
+    
+    // Register audio processing graph
+    m.add_class::<graph::AudioGraph>()?;
     
     // Register wake word detection module
     m.add_class::<wake_word::WakeWordDetector>()?;
+    m.add_class::<wake_word::ContextSnapshot>()?;
+    m.add_class::<wake_word::WakeWordDetection>()?;
+    m.add("WakeWordLicenseError", _py.get_type::<wake_word::WakeWordLicenseError>())?;
     
     // Register speech recognition module
     m.add_class::<speech::SpeechRecognizer>()?;
+    m.add_class::<speech::RecognitionResult>()?;
+    m.add_class::<speech::WordInfo>()?;
+    m.add_class::<content_filter::ContentFilter>()?;
+    m.add_class::<formatter::TranscriptFormatter>()?;
     m.add_class::<speech::TextToSpeech>()?;
+    m.add_class::<tts::VoiceInfo>()?;
+    m.add_function(wrap_pyfunction!(tts::list_voices, m)?)?;
+    
+    // Register Wyoming protocol server
+    m.add_class::<wyoming::WyomingServer>()?;
+    
+    // Register WebSocket audio server
+    m.add_class::<websocket::WebSocketServer>()?;
+    
+    // Register client/server message schema
+    m.add_function(wrap_pyfunction!(protocol::encode_message, m)?)?;
+    m.add_function(wrap_pyfunction!(protocol::decode_message, m)?)?;
+    
+    // Register gRPC speech service
+    m.add_class::<grpc::GrpcServer>()?;
+    
+    // Register MQTT bridge
+    m.add_class::<mqtt::MqttBridge>()?;
+    
+    // Register RTP satellite audio
+    m.add_class::<rtp::RtpReceiver>()?;
+    m.add_class::<rtp::RtpSender>()?;
+    
+    // Register event bus
+    m.add_class::<events::EventBus>()?;
+    m.add_class::<events::BusEvent>()?;
+    
+    // Register voice pipeline
+    m.add_class::<pipeline::VoicePipeline>()?;
+    m.add_class::<pipeline::PipelineEvent>()?;
+    m.add_class::<pipeline::PipelineEventStream>()?;
+    m.add_class::<dictation::DictationSegment>()?;
+    m.add_class::<dictation::DictationTranscript>()?;
+    m.add_function(wrap_pyfunction!(pipeline::running_zones, m)?)?;
+    m.add_class::<session::SessionEvent>()?;
+    m.add_class::<session::SessionReplay>()?;
+    
+    // Register intent engine
+    m.add_class::<intent::IntentEngine>()?;
+    m.add_class::<intent::IntentMatch>()?;
+    
+    // Register entity extraction
+    m.add_class::<entities::Entity>()?;
+    m.add_function(wrap_pyfunction!(entities::extract_entities, m)?)?;
+    m.add_function(wrap_pyfunction!(entities::py_parse_number, m)?)?;
+    m.add_function(wrap_pyfunction!(entities::py_parse_duration, m)?)?;
+    m.add_function(wrap_pyfunction!(entities::py_parse_time, m)?)?;
+    
+    // Register fuzzy matching
+    m.add_class::<fuzzy::FuzzyMatcher>()?;
+    m.add_class::<fuzzy::FuzzyMatch>()?;
+    m.add_function(wrap_pyfunction!(fuzzy::fuzzy_score, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzzy::py_ratio, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzzy::py_token_set_ratio, m)?)?;
+    m.add_function(wrap_pyfunction!(fuzzy::phonetic_key, m)?)?;
+    
+    // Register assistant state machine
+    m.add_class::<assistant::AssistantState>()?;
+    m.add_class::<assistant::StateChange>()?;
+    
+    // Register speaker identification module
+    m.add_class::<speaker::SpeakerIdentifier>()?;
+    m.add_class::<speaker::SpeakerSegment>()?;
+    
+    // Register keyword spotting
+    m.add_class::<keywords::KeywordSpotter>()?;
+    
+    // Register sound event classification
+    m.add_class::<sound_events::SoundEventClassifier>()?;
+    m.add_class::<sound_events::SoundEvent>()?;
+    
+    // Register audio fingerprinting
+    m.add_class::<fingerprint::FingerprintDatabase>()?;
+    m.add_class::<fingerprint::TrackMatch>()?;
+    
+    // Register backup and restore functions
+    m.add_function(wrap_pyfunction!(backup::backup, m)?)?;
+    m.add_function(wrap_pyfunction!(backup::restore, m)?)?;
+    
+    // Register storage guard, interaction history and encryption
+    m.add_class::<storage::StorageGuard>()?;
+    m.add_class::<storage::InteractionHistory>()?;
+    m.add_class::<storage::StorageEncryption>()?;
+    
+    // Register model manager
+    m.add_class::<models::ModelManager>()?;
+    
+    // Register startup diagnostics
+    m.add_function(wrap_pyfunction!(diagnostics::diagnose, m)?)?;
+    m.add_class::<soak::SoakTest>()?;
+    
+    // Register benchmarks submodule
+    let bench = PyModule::new(_py, "benchmarks")?;
+    bench.add_function(wrap_pyfunction!(benchmarks::fft, bench)?)?;
+    bench.add_function(wrap_pyfunction!(benchmarks::filtering, bench)?)?;
+    bench.add_function(wrap_pyfunction!(benchmarks::resampling, bench)?)?;
+    bench.add_function(wrap_pyfunction!(benchmarks::wake_word, bench)?)?;
+    bench.add_function(wrap_pyfunction!(benchmarks::recognition, bench)?)?;
+    bench.add_function(wrap_pyfunction!(benchmarks::run_all, bench)?)?;
+    m.add_submodule(bench)?;
+    
+    // Register engine comparison
+    m.add_class::<comparison::WakeWordComparison>()?;
+    m.add_class::<comparison::RecognizerComparison>()?;
+    
+    // Register test signal generators submodule
+    let signals = PyModule::new(_py, "testing")?;
+    signals.add_function(wrap_pyfunction!(testing::silence, signals)?)?;
+    signals.add_function(wrap_pyfunction!(testing::sine, signals)?)?;
+    signals.add_function(wrap_pyfunction!(testing::sine_sweep, signals)?)?;
+    signals.add_function(wrap_pyfunction!(testing::white_noise, signals)?)?;
+    signals.add_function(wrap_pyfunction!(testing::pink_noise, signals)?)?;
+    signals.add_function(wrap_pyfunction!(testing::speech_shaped_noise, signals)?)?;
+    signals.add_function(wrap_pyfunction!(testing::to_pcm16, signals)?)?;
+    m.add_submodule(signals)?;
     
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+use pyo3::prelude::*;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::events;
+use crate::logging;
+
+/// Longest `shutdown` waits for queued log records to reach Python
+const LOG_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A component that owns background threads, capture streams or worker queues
+/// 
+/// `shutdown` stops the background work and waits for it to finish. It is
+/// called without the GIL, possibly more than once and possibly after the
+/// owning Python object has already released itself, so it must be
+/// idempotent.
+pub trait Resource: Send + Sync {
+    fn shutdown(&self);
+}
+
+static RESOURCES: OnceLock<Mutex<Vec<Weak<dyn Resource>>>> = OnceLock::new();
+
+fn resources() -> &'static Mutex<Vec<Weak<dyn Resource>>> {
+    RESOURCES.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Track a component so the module-level `shutdown()` can stop it
+/// 
+/// Call this whenever the component starts background work. Only a weak
+/// reference is kept; registering twice is harmless, and components that
+/// have been dropped are forgotten on the next registration.
+pub fn register<R: Resource + 'static>(resource: &Arc<R>) {
+    let resource: Arc<dyn Resource> = resource.clone();
+    let weak = Arc::downgrade(&resource);
+    let mut resources = resources().lock().unwrap();
+    resources.retain(|r| r.strong_count() > 0 && !r.ptr_eq(&weak));
+    resources.push(weak);
+}
+
+/// Join background threads, skipping the calling thread
+/// 
+/// A Python callback running on one of the threads may itself trigger a
+/// shutdown; that thread exits on its own once it sees the stop flag.
+pub fn join_threads(handles: impl IntoIterator<Item = JoinHandle<()>>) {
+    let current = thread::current().id();
+    for handle in handles {
+        if handle.thread().id() != current {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Stop every live component and flush pending events and log records
+/// 
+/// Stops pipelines, wake word listeners, recognition workers (after their
+/// queued jobs) and assistant timers, closing their audio streams, then
+/// delivers queued events to subscribers and queued log records to Python
+/// logging. Objects stay usable: `start` or `start_listening` can be called
+/// again afterwards, and a later `release()` is a no-op. Registered with
+/// `atexit` on import, so devices are freed even if the application never
+/// calls `release()`. Returns the number of components stopped.
+#[pyfunction]
+pub fn shutdown(py: Python) -> usize {
+    let live: Vec<Arc<dyn Resource>> = resources()
+        .lock()
+        .unwrap()
+        .drain(..)
+        .filter_map(|r| r.upgrade())
+        .collect();
+    let stopped = live.len();
+    tracing::debug!(components = stopped, "Shutting down");
+    
+    // Threads may need the GIL to finish a callback
+    py.allow_threads(|| {
+        for resource in live {
+            resource.shutdown();
+        }
+        events::shutdown();
+        logging::flush(LOG_FLUSH_TIMEOUT);
+    });
+    stopped
+}
@@ -0,0 +1,241 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use memmap2::Mmap;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Stages of a background model load, as reported to its progress callback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoadStage {
+    Loading,
+    WarmingUp,
+    Ready,
+    Failed,
+    Cancelled,
+}
+
+impl LoadStage {
+    pub fn name(self) -> &'static str {
+        match self {
+            LoadStage::Loading => "loading",
+            LoadStage::WarmingUp => "warming_up",
+            LoadStage::Ready => "ready",
+            LoadStage::Failed => "failed",
+            LoadStage::Cancelled => "cancelled",
+        }
+    }
+    
+    /// Rough fraction of the work done once this stage is reached
+    fn progress(self) -> f32 {
+        match self {
+            LoadStage::Loading => 0.0,
+            LoadStage::WarmingUp => 0.8,
+            _ => 1.0,
+        }
+    }
+    
+    pub fn is_finished(self) -> bool {
+        matches!(self, LoadStage::Ready | LoadStage::Failed | LoadStage::Cancelled)
+    }
+}
+
+/// A model loading on a background thread
+/// 
+/// The loader moves through the stages and the owner polls, waits on or
+/// cancels it. A cancelled load still runs to the end, but its result is
+/// dropped instead of being installed.
+pub struct ModelLoad {
+    state: Mutex<(LoadStage, Option<String>)>,
+    changed: Condvar,
+    progress: Option<PyObject>,
+}
+
+impl ModelLoad {
+    pub fn stage(&self) -> LoadStage {
+        self.state.lock().unwrap().0
+    }
+    
+    /// Why the load failed, if it did
+    pub fn error(&self) -> Option<String> {
+        self.state.lock().unwrap().1.clone()
+    }
+    
+    pub fn is_ready(&self) -> bool {
+        self.stage() == LoadStage::Ready
+    }
+    
+    /// Move to `stage` unless the load was cancelled, running `install` under the state lock
+    /// 
+    /// Holding the lock while installing means `cancel` either comes first
+    /// and the result is dropped, or comes after and sees it installed.
+    fn advance(&self, stage: LoadStage, error: Option<String>, install: impl FnOnce()) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.0.is_finished() {
+                return;
+            }
+            install();
+            *state = (stage, error);
+        }
+        self.changed.notify_all();
+        self.notify(stage);
+    }
+    
+    /// Report that the model is loaded and is being warmed up
+    pub fn warming_up(&self) {
+        self.advance(LoadStage::WarmingUp, None, || {});
+    }
+    
+    /// Stop the load from installing its result; returns whether it was still running
+    pub fn cancel(&self) -> bool {
+        let cancelled = {
+            let mut state = self.state.lock().unwrap();
+            let running = !state.0.is_finished();
+            if running {
+                *state = (LoadStage::Cancelled, None);
+            }
+            running
+        };
+        if cancelled {
+            self.changed.notify_all();
+            self.notify(LoadStage::Cancelled);
+        }
+        cancelled
+    }
+    
+    /// Block until the load finishes or `timeout` passes, returning the stage reached
+    pub fn wait(&self, timeout: Option<Duration>) -> LoadStage {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut state = self.state.lock().unwrap();
+        while !state.0.is_finished() {
+            state = match deadline {
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    self.changed.wait_timeout(state, remaining).unwrap().0
+                }
+                None => self.changed.wait(state).unwrap(),
+            };
+        }
+        state.0
+    }
+    
+    /// Call the progress callback with `(stage, fraction)`
+    fn notify(&self, stage: LoadStage) {
+        if let Some(callback) = &self.progress {
+            Python::with_gil(|py| {
+                if let Err(err) = callback.call1(py, (stage.name(), stage.progress())) {
+                    err.print(py);
+                }
+            });
+        }
+    }
+}
+
+/// Shared handle to a background load
+pub type SharedModelLoad = Arc<ModelLoad>;
+
+/// Load a model on a background thread named after `name`
+/// 
+/// `load` builds the model and may call `warming_up` before priming it;
+/// `install` then hands it to its owner, unless the load was cancelled
+/// first. `progress(stage, fraction)` is called from the loading thread
+/// as each stage is reached, ending with "ready", "failed" or
+/// "cancelled".
+pub fn spawn_load<T: 'static>(
+    py: Python,
+    name: &str,
+    progress: Option<PyObject>,
+    load: impl FnOnce(&ModelLoad) -> Result<T, String> + Send + 'static,
+    install: impl FnOnce(T) + Send + 'static,
+) -> PyResult<SharedModelLoad> {
+    if let Some(callback) = &progress {
+        if !callback.as_ref(py).is_callable() {
+            return Err(PyValueError::new_err("progress must be callable"));
+        }
+    }
+    
+    let status = Arc::new(ModelLoad {
+        state: Mutex::new((LoadStage::Loading, None)),
+        changed: Condvar::new(),
+        progress,
+    });
+    let worker = status.clone();
+    thread::Builder::new()
+        .name(format!("jenna-load-{}", name))
+        .spawn(move || {
+            worker.notify(LoadStage::Loading);
+            let started = Instant::now();
+            match load(&worker) {
+                Ok(model) => {
+                    tracing::info!(seconds = started.elapsed().as_secs_f64(), "Model loaded in the background");
+                    worker.advance(LoadStage::Ready, None, || install(model));
+                }
+                Err(err) => {
+                    tracing::error!(error = %err, "Background model load failed");
+                    worker.advance(LoadStage::Failed, Some(err), || {});
+                }
+            }
+        })
+        .map_err(|err| PyValueError::new_err(format!("Failed to start loading thread: {}", err)))?;
+    Ok(status)
+}
+
+/// Map a model file into memory read-only
+/// 
+/// The pages are shared with the OS file cache, so a model that is already
+/// cached (e.g. after a restart) is read without copying it through a
+/// buffer first.
+pub fn map_model(path: &Path) -> Result<Mmap, String> {
+    let file = File::open(path).map_err(|err| format!("Cannot open model {}: {}", path.display(), err))?;
+    // Safety: model files are only read, and aren't modified while loaded
+    unsafe { Mmap::map(&file) }.map_err(|err| format!("Cannot map model {}: {}", path.display(), err))
+}
+
+/// Loaded models by kind and canonical path
+type ModelCache = HashMap<(&'static str, PathBuf), Weak<dyn Any + Send + Sync>>;
+
+static MODELS: OnceLock<Mutex<ModelCache>> = OnceLock::new();
+
+fn models() -> &'static Mutex<ModelCache> {
+    MODELS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Load a model once per process and share it with every engine using the same file
+/// 
+/// Pipelines running side by side for different zones each keep their
+/// own recognizer and voice state, but the weights behind them are loaded
+/// once. Only weak references are cached, so a model is freed as soon as
+/// no engine holds it. Two zones loading the same model at once may both
+/// load it; the first one cached wins.
+pub fn shared_model<T: Send + Sync + 'static>(
+    kind: &'static str,
+    path: &Path,
+    load: impl FnOnce() -> Result<T, String>,
+) -> Result<Arc<T>, String> {
+    let key = (kind, path.canonicalize().unwrap_or_else(|_| path.to_path_buf()));
+    let cached = |models: &ModelCache| {
+        models.get(&key).and_then(|model| model.upgrade()).and_then(|model| model.downcast::<T>().ok())
+    };
+    if let Some(model) = cached(&models().lock().unwrap()) {
+        tracing::debug!(kind, path = %path.display(), "Sharing a loaded model");
+        return Ok(model);
+    }
+    
+    let model = Arc::new(load()?);
+    let mut models = models().lock().unwrap();
+    if let Some(existing) = cached(&models) {
+        return Ok(existing);
+    }
+    models.retain(|_, model| model.strong_count() > 0);
+    let erased: Arc<dyn Any + Send + Sync> = model.clone();
+    models.insert(key, Arc::downgrade(&erased));
+    Ok(model)
+}
@@ -0,0 +1,249 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Metadata, Subscriber};
+use tracing_log::NormalizeEvent;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Records waiting for the forwarding thread; records beyond this are dropped and counted
+const LOG_BACKLOG: usize = 4096;
+
+/// Logger name prefix; "jenna_rust::speech" becomes the Python logger "jenna_rust.speech"
+const LOGGER_ROOT: &str = "jenna_rust";
+
+/// Levels from most to least severe, with their Python `logging` values and `log` filters
+const LEVELS: &[(&str, Level, u32, log::LevelFilter)] = &[
+    ("error", Level::ERROR, 40, log::LevelFilter::Error),
+    ("warning", Level::WARN, 30, log::LevelFilter::Warn),
+    ("info", Level::INFO, 20, log::LevelFilter::Info),
+    ("debug", Level::DEBUG, 10, log::LevelFilter::Debug),
+    ("trace", Level::TRACE, 5, log::LevelFilter::Trace),
+];
+
+/// Index into `LEVELS` of the most verbose level forwarded; starts at "info"
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(2);
+
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+/// Records sent to the forwarding thread and not yet passed to Python
+static PENDING: AtomicUsize = AtomicUsize::new(0);
+
+static SENDER: OnceLock<SyncSender<Record>> = OnceLock::new();
+
+/// A formatted record on its way to Python
+struct Record {
+    logger: String,
+    level: u32,
+    message: String,
+}
+
+fn level_index(level: &Level) -> u8 {
+    LEVELS.iter().position(|(_, l, _, _)| l == level).unwrap_or(0) as u8
+}
+
+/// Collects the `message` field and appends the other fields as `key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+    
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else if !field.name().starts_with("log.") {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Python logger name for a tracing target or `log` module path
+fn logger_name(target: &str) -> String {
+    let name = target.replace("::", ".");
+    match name.strip_prefix("jenna_rust") {
+        Some(rest) => format!("{}{}", LOGGER_ROOT, rest),
+        None => format!("{}.{}", LOGGER_ROOT, name),
+    }
+}
+
+/// Logger name, `LEVELS` index and message of an event
+fn format_event<S>(event: &Event<'_>, ctx: &Context<'_, S>) -> (String, u8, String)
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    // Records bridged from the `log` crate carry their real target and level in fields
+    let normalized = event.normalized_metadata();
+    let metadata = normalized.as_ref().unwrap_or_else(|| event.metadata());
+    let mut visitor = MessageVisitor::default();
+    event.record(&mut visitor);
+    
+    // Prefix the enclosing spans, outermost first: "pipeline:turn: message"
+    let mut message = String::new();
+    if let Some(scope) = ctx.event_scope(event) {
+        for span in scope.from_root() {
+            let _ = write!(message, "{}:", span.name());
+        }
+        message.push(' ');
+    }
+    message.push_str(&visitor.message);
+    message.push_str(&visitor.fields);
+    
+    (logger_name(metadata.target()), level_index(metadata.level()), message)
+}
+
+/// Tracing layer that hands records to the forwarding thread without blocking
+struct PythonLogLayer {
+    sender: SyncSender<Record>,
+}
+
+impl<S> Layer<S> for PythonLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        level_index(metadata.level()) <= MAX_LEVEL.load(Ordering::Relaxed)
+    }
+    
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let (logger, level, message) = format_event(event, &ctx);
+        let record = Record { logger, level: LEVELS[level as usize].2, message };
+        PENDING.fetch_add(1, Ordering::Relaxed);
+        if let Err(err) = self.sender.try_send(record) {
+            PENDING.fetch_sub(1, Ordering::Relaxed);
+            if let TrySendError::Full(_) = err {
+                DROPPED.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Pass records to `logging.getLogger(name).log(level, message)`
+fn forward(receiver: mpsc::Receiver<Record>) {
+    for record in receiver {
+        Python::with_gil(|py| {
+            let result = py.import("logging").and_then(|logging| {
+                logging
+                    .call_method1("getLogger", (record.logger.as_str(),))?
+                    .call_method1("log", (record.level, record.message.as_str()))
+            });
+            if let Err(err) = result {
+                err.print(py);
+            }
+        });
+        PENDING.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Wait up to `timeout` for queued records to reach Python; call without the GIL
+pub fn flush(timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+    while PENDING.load(Ordering::Relaxed) > 0 && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Install the Python logging bridge; later calls do nothing
+/// 
+/// Records from `tracing` and from the `log` crate (ours and our
+/// dependencies') are forwarded on a dedicated thread, so logging from
+/// audio callbacks never waits for the GIL.
+pub fn init() {
+    SENDER.get_or_init(|| {
+        let (sender, receiver) = mpsc::sync_channel::<Record>(LOG_BACKLOG);
+        let spawned = thread::Builder::new()
+            .name("jenna-logging".to_string())
+            .spawn(move || forward(receiver));
+        if spawned.is_ok() {
+            let subscriber = tracing_subscriber::registry().with(PythonLogLayer { sender: sender.clone() });
+            let _ = tracing::subscriber::set_global_default(subscriber);
+            let _ = tracing_log::LogTracer::init();
+            log::set_max_level(log::LevelFilter::Info);
+        }
+        sender
+    });
+}
+
+/// Tracing layer that writes records to stderr, for hosts without Python logging
+struct StderrLayer;
+
+impl<S> Layer<S> for StderrLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        level_index(metadata.level()) <= MAX_LEVEL.load(Ordering::Relaxed)
+    }
+    
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let (logger, level, message) = format_event(event, &ctx);
+        eprintln!("{:<7} {}: {}", LEVELS[level as usize].0.to_uppercase(), logger, message);
+    }
+}
+
+/// Log to stderr at `level` instead of forwarding to Python
+/// 
+/// For the `jenna-core` binary; does nothing if a subscriber is already installed.
+pub fn init_stderr(level: &str) -> Result<(), String> {
+    let lower = level.to_lowercase();
+    let lower = if lower == "warn" { "warning".to_string() } else { lower };
+    let index = LEVELS
+        .iter()
+        .position(|(name, _, _, _)| *name == lower)
+        .ok_or_else(|| format!("Unknown log level: {}", level))?;
+    MAX_LEVEL.store(index as u8, Ordering::Relaxed);
+    let subscriber = tracing_subscriber::registry().with(StderrLayer);
+    if tracing::subscriber::set_global_default(subscriber).is_ok() {
+        let _ = tracing_log::LogTracer::init();
+        log::set_max_level(LEVELS[index].3);
+    }
+    Ok(())
+}
+
+/// Set the most verbose level forwarded to Python logging
+/// 
+/// One of "error", "warning", "info", "debug" or "trace". Python's own
+/// logger levels still apply on top; this avoids formatting records that
+/// Python would discard. The default is "info".
+#[pyfunction]
+pub fn set_log_level(level: &str) -> PyResult<()> {
+    let lower = level.to_lowercase();
+    let lower = if lower == "warn" { "warning".to_string() } else { lower };
+    let index = LEVELS
+        .iter()
+        .position(|(name, _, _, _)| *name == lower)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown log level: {}", level)))?;
+    
+    MAX_LEVEL.store(index as u8, Ordering::Relaxed);
+    log::set_max_level(LEVELS[index].3);
+    tracing::callsite::rebuild_interest_cache();
+    Ok(())
+}
+
+/// Get the most verbose level forwarded to Python logging
+#[pyfunction]
+pub fn get_log_level() -> &'static str {
+    LEVELS[MAX_LEVEL.load(Ordering::Relaxed) as usize].0
+}
+
+/// Number of log records dropped because the forwarding thread fell behind
+#[pyfunction]
+pub fn get_dropped_log_records() -> u64 {
+    DROPPED.load(Ordering::Relaxed)
+}
@@ -0,0 +1,179 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyDict, PyList};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use std::thread;
+use std::time::Duration;
+
+use crate::soak::resident_memory_bytes;
+
+/// How often the sweeper looks for idle models and checks the budget
+const SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// An engine holding a model in memory
+/// 
+/// Sizes are estimates taken from the model files, which is close for the
+/// weights that dominate but ignores per-stream buffers. Engines that
+/// can't be reloaded transparently report no idle time and are never
+/// unloaded.
+pub trait LoadedModel: Send + Sync {
+    /// Label in `memory_usage()`, e.g. "tts:en_US-lessac-medium"
+    fn name(&self) -> String;
+    
+    /// Approximate bytes held by the loaded model, 0 when nothing is loaded
+    fn memory_bytes(&self) -> u64;
+    
+    /// How long the model has gone unused, if it is loaded and can be unloaded
+    fn idle_for(&self) -> Option<Duration>;
+    
+    /// How long the model may go unused before the sweeper unloads it
+    fn idle_timeout(&self) -> Option<Duration>;
+    
+    /// Drop the model until it is next needed, returning the bytes freed
+    fn unload(&self) -> u64;
+}
+
+static MODELS: OnceLock<Mutex<Vec<Weak<dyn LoadedModel>>>> = OnceLock::new();
+
+/// Soft limit on the total bytes of loaded models
+static BUDGET: Mutex<Option<u64>> = Mutex::new(None);
+
+static SWEEPER: OnceLock<()> = OnceLock::new();
+
+fn models() -> &'static Mutex<Vec<Weak<dyn LoadedModel>>> {
+    MODELS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Track a model for `memory_usage()`, the memory budget and idle unloading
+/// 
+/// Only a weak reference is kept; registering twice is harmless.
+pub fn register<M: LoadedModel + 'static>(model: &Arc<M>) {
+    let model: Arc<dyn LoadedModel> = model.clone();
+    let weak = Arc::downgrade(&model);
+    let mut models = models().lock().unwrap();
+    models.retain(|m| m.strong_count() > 0 && !m.ptr_eq(&weak));
+    models.push(weak);
+}
+
+fn live_models() -> Vec<Arc<dyn LoadedModel>> {
+    models().lock().unwrap().iter().filter_map(|m| m.upgrade()).collect()
+}
+
+/// Start the background sweeper, once; it sleeps between checks and never holds the GIL
+pub fn start_sweeper() {
+    SWEEPER.get_or_init(|| {
+        let spawned = thread::Builder::new().name("jenna-memory".to_string()).spawn(|| loop {
+            thread::sleep(SWEEP_INTERVAL);
+            let freed = sweep(None);
+            if freed > 0 {
+                tracing::info!(bytes = freed, "Unloaded idle models");
+            }
+        });
+        if let Err(err) = spawned {
+            tracing::error!("Failed to start memory sweeper: {}", err);
+        }
+    });
+}
+
+/// Unload idle models, returning the bytes freed
+/// 
+/// Models past their idle timeout (or idle at least `min_idle`) go first,
+/// then the longest idle ones while the budget is exceeded.
+fn sweep(min_idle: Option<Duration>) -> u64 {
+    let models = live_models();
+    let mut freed = 0;
+    for model in &models {
+        let limit = min_idle.or_else(|| model.idle_timeout());
+        if let (Some(idle), Some(limit)) = (model.idle_for(), limit) {
+            if idle >= limit {
+                freed += model.unload();
+            }
+        }
+    }
+    
+    let budget = *BUDGET.lock().unwrap();
+    if let Some(budget) = budget {
+        let mut total: u64 = models.iter().map(|model| model.memory_bytes()).sum();
+        let mut idle: Vec<(Duration, &Arc<dyn LoadedModel>)> =
+            models.iter().filter_map(|model| model.idle_for().map(|idle| (idle, model))).collect();
+        idle.sort_by(|a, b| b.0.cmp(&a.0));
+        for (_, model) in idle {
+            if total <= budget {
+                break;
+            }
+            let released = model.unload();
+            total = total.saturating_sub(released);
+            freed += released;
+        }
+        if total > budget {
+            tracing::warn!(total, budget, "Loaded models exceed the memory budget");
+        }
+    }
+    freed
+}
+
+/// Size of a model file, or of every file below a model directory
+pub fn disk_size(path: &Path) -> u64 {
+    match fs::metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| disk_size(&entry.path())).sum())
+            .unwrap_or(0),
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }
+}
+
+/// Approximate memory held by loaded models, and the process as a whole
+/// 
+/// Returns a dict with "models" (one dict per model with "name", "bytes"
+/// and "idle_seconds", None for models that are never unloaded),
+/// "model_bytes", "resident_bytes" (None where the platform doesn't
+/// report it) and "budget_bytes". A model shared by several zones is
+/// loaded once but listed under each engine using it.
+#[pyfunction]
+pub fn memory_usage(py: Python) -> PyResult<Py<PyDict>> {
+    let entries = PyList::empty(py);
+    let mut total = 0;
+    for model in live_models() {
+        let bytes = model.memory_bytes();
+        total += bytes;
+        let entry = PyDict::new(py);
+        entry.set_item("name", model.name())?;
+        entry.set_item("bytes", bytes)?;
+        entry.set_item("idle_seconds", model.idle_for().map(|idle| idle.as_secs_f64()))?;
+        entries.append(entry)?;
+    }
+    
+    let usage = PyDict::new(py);
+    usage.set_item("models", entries)?;
+    usage.set_item("model_bytes", total)?;
+    usage.set_item("resident_bytes", resident_memory_bytes())?;
+    usage.set_item("budget_bytes", *BUDGET.lock().unwrap())?;
+    Ok(usage.into())
+}
+
+/// Keep loaded models under `budget_bytes`, or lift the limit with None
+/// 
+/// Every few seconds, while the models' total is over the budget, the
+/// longest idle unloadable model is unloaded; it is reloaded when next
+/// used. Recognizers are never unloaded, so the budget is a target rather
+/// than a hard cap.
+#[pyfunction]
+#[pyo3(signature = (budget_bytes=None))]
+pub fn set_memory_budget(budget_bytes: Option<u64>) {
+    *BUDGET.lock().unwrap() = budget_bytes;
+    if budget_bytes.is_some() {
+        start_sweeper();
+    }
+}
+
+/// Unload every unloadable model idle for at least `idle_seconds` now, returning the bytes freed
+#[pyfunction]
+#[pyo3(signature = (idle_seconds=0.0))]
+pub fn unload_idle_models(py: Python, idle_seconds: f64) -> PyResult<u64> {
+    let min_idle = Duration::try_from_secs_f64(idle_seconds)
+        .map_err(|_| PyValueError::new_err("Idle time must be finite and non-negative"))?;
+    Ok(py.allow_threads(|| sweep(Some(min_idle))))
+}
@@ -0,0 +1,204 @@
+use pyo3::prelude::*;
+use pyo3::types::{IntoPyDict, PyDict};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Latency histogram bucket bounds in seconds
+const LATENCY_BUCKETS: &[f64] = &[0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Most recent observations kept per stage for percentiles
+const RECENT_SAMPLES: usize = 1024;
+
+/// Stages whose latency is recorded, with their descriptions
+pub const LATENCY_STAGES: &[(&str, &str)] = &[
+    ("wake_decision", "Frame capture to wake word decision"),
+    ("final_text", "End of speech to final transcript"),
+    ("tts_first_sample", "Text to first synthesized sample"),
+];
+
+/// Latency distribution for one stage
+#[derive(Clone, Debug)]
+struct Histogram {
+    /// Observations at or below each bound in `LATENCY_BUCKETS` (not cumulative)
+    buckets: Vec<u64>,
+    count: u64,
+    sum: f64,
+    max: f64,
+    recent: VecDeque<f64>,
+}
+
+impl Histogram {
+    fn new() -> Self {
+        Histogram {
+            buckets: vec![0; LATENCY_BUCKETS.len()],
+            count: 0,
+            sum: 0.0,
+            max: 0.0,
+            recent: VecDeque::with_capacity(RECENT_SAMPLES),
+        }
+    }
+    
+    fn observe(&mut self, seconds: f64) {
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|&bound| seconds <= bound) {
+            self.buckets[bucket] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+        self.max = self.max.max(seconds);
+        if self.recent.len() == RECENT_SAMPLES {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(seconds);
+    }
+    
+    /// Percentile (0-100) of the recent observations
+    fn percentile(&self, percent: f64) -> f64 {
+        if self.recent.is_empty() {
+            return 0.0;
+        }
+        let mut sorted: Vec<f64> = self.recent.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let rank = ((percent / 100.0) * (sorted.len() - 1) as f64).round() as usize;
+        sorted[rank.min(sorted.len() - 1)]
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    latency: BTreeMap<&'static str, Histogram>,
+    /// Current occupancy of audio buffers, in samples
+    buffers: BTreeMap<&'static str, f64>,
+    /// Peak occupancy since the last reset, in samples
+    buffer_peaks: BTreeMap<&'static str, f64>,
+    /// CPU time spent by each processing thread, in seconds
+    cpu: BTreeMap<&'static str, f64>,
+}
+
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+fn registry() -> &'static Mutex<Registry> {
+    REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+/// Record how long a pipeline stage took
+pub fn observe_latency(stage: &'static str, elapsed: Duration) {
+    registry()
+        .lock()
+        .unwrap()
+        .latency
+        .entry(stage)
+        .or_insert_with(Histogram::new)
+        .observe(elapsed.as_secs_f64());
+}
+
+/// Record the current occupancy of an audio buffer, in samples
+pub fn set_buffer(buffer: &'static str, samples: usize) {
+    let mut registry = registry().lock().unwrap();
+    let samples = samples as f64;
+    registry.buffers.insert(buffer, samples);
+    let peak = registry.buffer_peaks.entry(buffer).or_insert(0.0);
+    *peak = peak.max(samples);
+}
+
+/// Add CPU time spent by a processing thread
+pub fn add_cpu_time(thread: &'static str, elapsed: Duration) {
+    *registry().lock().unwrap().cpu.entry(thread).or_insert(0.0) += elapsed.as_secs_f64();
+}
+
+/// CPU time of the current thread, for measuring a stretch of work with `add_cpu_time`
+pub fn thread_cpu_time() -> Duration {
+    cpu_time::ThreadTime::try_now().map(|t| t.as_duration()).unwrap_or_default()
+}
+
+/// CPU time used by the whole process
+fn process_cpu_seconds() -> f64 {
+    cpu_time::ProcessTime::try_now().map(|t| t.as_duration().as_secs_f64()).unwrap_or(0.0)
+}
+
+/// Get all metrics as a dict
+/// 
+/// `latency` maps each stage to count, sum, mean, p50, p95 and max in
+/// seconds (percentiles over the last 1024 observations); `buffers` and
+/// `buffer_peaks` map buffers to their current and peak occupancy in
+/// samples; `cpu` maps processing threads to CPU seconds, plus "process"
+/// for the whole process.
+#[pyfunction]
+pub fn get_metrics(py: Python) -> PyResult<Py<PyDict>> {
+    let registry = registry().lock().unwrap();
+    let dict = PyDict::new(py);
+    
+    let latency = PyDict::new(py);
+    for (stage, histogram) in &registry.latency {
+        let stats = PyDict::new(py);
+        stats.set_item("count", histogram.count)?;
+        stats.set_item("sum", histogram.sum)?;
+        stats.set_item("mean", if histogram.count > 0 { histogram.sum / histogram.count as f64 } else { 0.0 })?;
+        stats.set_item("p50", histogram.percentile(50.0))?;
+        stats.set_item("p95", histogram.percentile(95.0))?;
+        stats.set_item("max", histogram.max)?;
+        latency.set_item(*stage, stats)?;
+    }
+    dict.set_item("latency", latency)?;
+    
+    dict.set_item("buffers", registry.buffers.iter().into_py_dict(py))?;
+    dict.set_item("buffer_peaks", registry.buffer_peaks.iter().into_py_dict(py))?;
+    let cpu = registry.cpu.iter().into_py_dict(py);
+    cpu.set_item("process", process_cpu_seconds())?;
+    dict.set_item("cpu", cpu)?;
+    Ok(dict.into())
+}
+
+/// Render all metrics in the Prometheus text exposition format
+#[pyfunction]
+pub fn export_prometheus() -> String {
+    let registry = registry().lock().unwrap();
+    let mut out = String::new();
+    
+    let _ = writeln!(out, "# HELP jenna_stage_latency_seconds Latency of each voice pipeline stage");
+    let _ = writeln!(out, "# TYPE jenna_stage_latency_seconds histogram");
+    for (stage, histogram) in &registry.latency {
+        let mut cumulative = 0;
+        for (bound, count) in LATENCY_BUCKETS.iter().zip(&histogram.buckets) {
+            cumulative += count;
+            let _ = writeln!(out, "jenna_stage_latency_seconds_bucket{{stage=\"{}\",le=\"{}\"}} {}", stage, bound, cumulative);
+        }
+        let _ = writeln!(out, "jenna_stage_latency_seconds_bucket{{stage=\"{}\",le=\"+Inf\"}} {}", stage, histogram.count);
+        let _ = writeln!(out, "jenna_stage_latency_seconds_sum{{stage=\"{}\"}} {}", stage, histogram.sum);
+        let _ = writeln!(out, "jenna_stage_latency_seconds_count{{stage=\"{}\"}} {}", stage, histogram.count);
+    }
+    
+    let _ = writeln!(out, "# HELP jenna_buffer_samples Current occupancy of audio buffers in samples");
+    let _ = writeln!(out, "# TYPE jenna_buffer_samples gauge");
+    for (buffer, samples) in &registry.buffers {
+        let _ = writeln!(out, "jenna_buffer_samples{{buffer=\"{}\"}} {}", buffer, samples);
+    }
+    let _ = writeln!(out, "# HELP jenna_buffer_peak_samples Peak occupancy of audio buffers in samples");
+    let _ = writeln!(out, "# TYPE jenna_buffer_peak_samples gauge");
+    for (buffer, samples) in &registry.buffer_peaks {
+        let _ = writeln!(out, "jenna_buffer_peak_samples{{buffer=\"{}\"}} {}", buffer, samples);
+    }
+    
+    let _ = writeln!(out, "# HELP jenna_thread_cpu_seconds_total CPU time spent by processing threads");
+    let _ = writeln!(out, "# TYPE jenna_thread_cpu_seconds_total counter");
+    for (thread, seconds) in &registry.cpu {
+        let _ = writeln!(out, "jenna_thread_cpu_seconds_total{{thread=\"{}\"}} {}", thread, seconds);
+    }
+    let _ = writeln!(out, "# HELP jenna_process_cpu_seconds_total CPU time used by the process");
+    let _ = writeln!(out, "# TYPE jenna_process_cpu_seconds_total counter");
+    let _ = writeln!(out, "jenna_process_cpu_seconds_total {}", process_cpu_seconds());
+    out
+}
+
+/// Clear all recorded latencies, buffer levels and CPU times
+#[pyfunction]
+pub fn reset_metrics() {
+    *registry().lock().unwrap() = Registry::default();
+}
+
+/// List the latency stages with their descriptions
+#[pyfunction]
+pub fn latency_stages() -> Vec<(&'static str, &'static str)> {
+    LATENCY_STAGES.to_vec()
+}
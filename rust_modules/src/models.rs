@@ -0,0 +1,658 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use flate2::read::GzDecoder;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+use crate::errors;
+use crate::storage::{StorageError, StorageGuard, MODELS_CATEGORY};
+
+/// Directory below the models root holding partial downloads and staging areas
+const DOWNLOAD_DIR: &str = ".downloads";
+
+/// Directory below the models root holding install markers
+const MARKER_DIR: &str = ".installed";
+
+/// How often download progress is reported, in bytes
+const PROGRESS_INTERVAL: u64 = 1 << 20;
+
+#[derive(Error, Debug)]
+pub enum ModelError {
+    #[error("Model I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Download failed for {name}: {message}")]
+    DownloadError { name: String, message: String },
+    
+    #[error("Checksum mismatch for {name}: expected {expected}, got {actual}")]
+    ChecksumMismatch { name: String, expected: String, actual: String },
+    
+    #[error("Failed to unpack {name}: {message}")]
+    UnpackError { name: String, message: String },
+    
+    #[error("Not enough storage for {name}: {source}")]
+    Storage { name: String, source: StorageError },
+    
+    #[error("Model manifest error: {0}")]
+    ManifestError(String),
+    
+    #[error("Unknown model: {0}")]
+    UnknownModel(String),
+    
+    #[error("Download of {0} was cancelled")]
+    Cancelled(String),
+}
+
+impl From<ModelError> for PyErr {
+    fn from(err: ModelError) -> PyErr {
+        errors::ModelError::new_err(err.to_string())
+    }
+}
+
+/// A model the assistant needs, where to fetch it and where it is installed
+/// 
+/// `path` is relative to the models directory. URLs ending in `.zip`,
+/// `.tar.gz` or `.tgz` are unpacked into `path` (a single top-level
+/// directory in the archive is stripped); anything else is saved as the
+/// file `path`. `sha256` is checked against the downloaded file.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ModelSpec {
+    pub name: String,
+    pub kind: String,
+    pub url: String,
+    pub path: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ModelManifest {
+    models: Vec<ModelSpec>,
+}
+
+/// Recorded after a successful install so archives can be re-verified later
+#[derive(Serialize, Deserialize, Debug)]
+struct InstallMarker {
+    url: String,
+    sha256: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModelStatus {
+    Installed,
+    Partial,
+    Missing,
+}
+
+impl ModelStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModelStatus::Installed => "installed",
+            ModelStatus::Partial => "partial",
+            ModelStatus::Missing => "missing",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ArchiveKind {
+    Zip,
+    TarGz,
+    Plain,
+}
+
+fn archive_kind(url: &str) -> ArchiveKind {
+    let path = url.split(['?', '#']).next().unwrap_or(url).to_ascii_lowercase();
+    if path.ends_with(".zip") {
+        ArchiveKind::Zip
+    } else if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+        ArchiveKind::TarGz
+    } else {
+        ArchiveKind::Plain
+    }
+}
+
+/// Models needed for a default English setup
+/// 
+/// Porcupine keyword files are account specific, so they have to be added
+/// from a manifest or with `add_model`.
+pub fn default_models() -> Vec<ModelSpec> {
+    let piper = "https://huggingface.co/rhasspy/piper-voices/resolve/v1.0.0/en/en_US/lessac/medium";
+    vec![
+        ModelSpec {
+            name: "vosk-en-us-small".to_string(),
+            kind: "vosk".to_string(),
+            url: "https://alphacephei.com/vosk/models/vosk-model-small-en-us-0.15.zip".to_string(),
+            path: "vosk/vosk-model-small-en-us-0.15".to_string(),
+            sha256: None,
+            required: true,
+        },
+        ModelSpec {
+            name: "piper-en-us-lessac-medium".to_string(),
+            kind: "tts_voice".to_string(),
+            url: format!("{}/en_US-lessac-medium.onnx", piper),
+            path: "tts/en_US-lessac-medium.onnx".to_string(),
+            sha256: None,
+            required: true,
+        },
+        ModelSpec {
+            name: "piper-en-us-lessac-medium-config".to_string(),
+            kind: "tts_voice".to_string(),
+            url: format!("{}/en_US-lessac-medium.onnx.json", piper),
+            path: "tts/en_US-lessac-medium.onnx.json".to_string(),
+            sha256: None,
+            required: true,
+        },
+    ]
+}
+
+fn sha256_file(path: &Path) -> Result<String, ModelError> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn part_path(root: &Path, spec: &ModelSpec) -> PathBuf {
+    root.join(DOWNLOAD_DIR).join(format!("{}.part", spec.name))
+}
+
+fn marker_path(root: &Path, spec: &ModelSpec) -> PathBuf {
+    root.join(MARKER_DIR).join(format!("{}.json", spec.name))
+}
+
+/// Check that a spec's name and path stay inside the models directory
+/// 
+/// The name is used as a file name for partial downloads and markers, and
+/// the path is replaced wholesale on install, so an empty path (the root
+/// itself), an absolute one or one climbing out with `..` is refused, as
+/// are paths into the download and marker directories.
+pub fn check_spec(spec: &ModelSpec) -> Result<(), ModelError> {
+    let invalid = |message: String| Err(ModelError::ManifestError(message));
+    let name = Path::new(&spec.name);
+    let mut name_parts = name.components();
+    if !matches!((name_parts.next(), name_parts.next()), (Some(Component::Normal(_)), None)) || spec.name.contains(['/', '\\']) {
+        return invalid(format!("Invalid model name '{}': it must be a plain file name", spec.name));
+    }
+    
+    let path = Path::new(&spec.path);
+    let first = path.components().next();
+    let escapes = path.components().any(|part| !matches!(part, Component::Normal(_) | Component::CurDir));
+    let reserved = first.map_or(false, |part| part.as_os_str() == DOWNLOAD_DIR || part.as_os_str() == MARKER_DIR);
+    if path.components().all(|part| part == Component::CurDir) || escapes || path.is_absolute() || reserved {
+        return invalid(format!(
+            "Invalid path '{}' for model {}: it must be relative and inside the models directory",
+            spec.path, spec.name
+        ));
+    }
+    Ok(())
+}
+
+pub fn target_path(root: &Path, spec: &ModelSpec) -> PathBuf {
+    root.join(&spec.path)
+}
+
+/// Installed if the target exists (including models copied in by hand)
+pub fn model_status(root: &Path, spec: &ModelSpec) -> ModelStatus {
+    if target_path(root, spec).exists() {
+        ModelStatus::Installed
+    } else if part_path(root, spec).exists() {
+        ModelStatus::Partial
+    } else {
+        ModelStatus::Missing
+    }
+}
+
+/// Check an installed model against its checksum
+/// 
+/// Plain files are re-hashed; unpacked archives are checked against the
+/// checksum recorded when they were installed. Models without a checksum
+/// only need to exist.
+pub fn verify_model(root: &Path, spec: &ModelSpec) -> Result<bool, ModelError> {
+    let target = target_path(root, spec);
+    if !target.exists() {
+        return Ok(false);
+    }
+    let expected = match &spec.sha256 {
+        Some(expected) => expected.to_ascii_lowercase(),
+        None => return Ok(true),
+    };
+    
+    let actual = match archive_kind(&spec.url) {
+        ArchiveKind::Plain => sha256_file(&target)?,
+        _ => match fs::read(marker_path(root, spec)) {
+            Ok(data) => serde_json::from_slice::<InstallMarker>(&data)
+                .map_err(|err| ModelError::ManifestError(err.to_string()))?
+                .sha256,
+            Err(_) => return Ok(false),
+        },
+    };
+    Ok(actual == expected)
+}
+
+/// Fetch the model into its partial file, resuming a previous attempt
+/// 
+/// `progress(downloaded, total)` is called periodically and cancels the
+/// download by returning false; the partial file is kept for resuming.
+/// `reserve(bytes)` checks the space for the rest of the download before
+/// any of it is written.
+#[tracing::instrument(name = "download", skip_all, fields(model = %spec.name))]
+fn fetch(
+    root: &Path,
+    spec: &ModelSpec,
+    progress: &mut dyn FnMut(u64, Option<u64>) -> bool,
+    reserve: &mut dyn FnMut(u64) -> Result<(), StorageError>,
+) -> Result<PathBuf, ModelError> {
+    let download_error = |message: String| ModelError::DownloadError { name: spec.name.clone(), message };
+    let part = part_path(root, spec);
+    fs::create_dir_all(root.join(DOWNLOAD_DIR))?;
+    
+    let mut offset = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    let mut request = ureq::get(&spec.url);
+    if offset > 0 {
+        request = request.set("Range", &format!("bytes={}-", offset));
+    }
+    
+    let response = match request.call() {
+        Ok(response) => response,
+        // The partial file already holds everything
+        Err(ureq::Error::Status(416, _)) if offset > 0 => return Ok(part),
+        Err(err) => return Err(download_error(err.to_string())),
+    };
+    if response.status() != 206 {
+        offset = 0;
+    }
+    tracing::info!(url = %spec.url, resume_from = offset, "Downloading model");
+    
+    let remaining = response.header("Content-Length").and_then(|len| len.parse::<u64>().ok());
+    let total = remaining.map(|len| len + offset);
+    if let Some(required) = remaining {
+        reserve(required).map_err(|source| ModelError::Storage { name: spec.name.clone(), source })?;
+    }
+    
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(&part)?;
+    let mut reader = response.into_reader();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut downloaded = offset;
+    let mut reported = offset;
+    
+    if !progress(downloaded, total) {
+        return Err(ModelError::Cancelled(spec.name.clone()));
+    }
+    loop {
+        let read = reader.read(&mut buffer).map_err(|err| download_error(err.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])?;
+        downloaded += read as u64;
+        
+        if downloaded - reported >= PROGRESS_INTERVAL {
+            reported = downloaded;
+            if !progress(downloaded, total) {
+                return Err(ModelError::Cancelled(spec.name.clone()));
+            }
+        }
+    }
+    file.flush()?;
+    
+    if let Some(total) = total {
+        if downloaded < total {
+            return Err(download_error(format!("connection closed after {} of {} bytes", downloaded, total)));
+        }
+    }
+    progress(downloaded, total);
+    Ok(part)
+}
+
+/// Unpack an archive into `staging`, returning the directory to install
+/// 
+/// Archives that wrap everything in a single top-level directory (as Vosk
+/// models do) have that directory stripped.
+fn unpack(archive: &Path, kind: ArchiveKind, staging: &Path, name: &str) -> Result<PathBuf, ModelError> {
+    let unpack_error = |message: String| ModelError::UnpackError { name: name.to_string(), message };
+    if staging.exists() {
+        fs::remove_dir_all(staging)?;
+    }
+    fs::create_dir_all(staging)?;
+    
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipArchive::new(File::open(archive)?).map_err(|err| unpack_error(err.to_string()))?;
+            zip.extract(staging).map_err(|err| unpack_error(err.to_string()))?;
+        }
+        ArchiveKind::TarGz => {
+            tar::Archive::new(GzDecoder::new(File::open(archive)?))
+                .unpack(staging)
+                .map_err(|err| unpack_error(err.to_string()))?;
+        }
+        ArchiveKind::Plain => return Ok(archive.to_path_buf()),
+    }
+    
+    let entries: Vec<PathBuf> = fs::read_dir(staging)?.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    match entries.as_slice() {
+        [single] if single.is_dir() => Ok(single.clone()),
+        _ => Ok(staging.to_path_buf()),
+    }
+}
+
+/// Delete a file or directory tree, if there is one
+fn remove_path(path: &Path) -> Result<(), ModelError> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Download, verify and install a model, returning its installed path
+pub fn install_model(
+    root: &Path,
+    spec: &ModelSpec,
+    progress: &mut dyn FnMut(u64, Option<u64>) -> bool,
+    reserve: &mut dyn FnMut(u64) -> Result<(), StorageError>,
+) -> Result<PathBuf, ModelError> {
+    check_spec(spec)?;
+    let part = fetch(root, spec, progress, reserve)?;
+    
+    let actual = sha256_file(&part)?;
+    if let Some(expected) = &spec.sha256 {
+        if !actual.eq_ignore_ascii_case(expected) {
+            // A corrupt partial file can't be resumed into a valid one
+            fs::remove_file(&part)?;
+            return Err(ModelError::ChecksumMismatch {
+                name: spec.name.clone(),
+                expected: expected.clone(),
+                actual,
+            });
+        }
+    }
+    
+    let kind = archive_kind(&spec.url);
+    let staging = root.join(DOWNLOAD_DIR).join(format!("{}.unpack", spec.name));
+    let source = unpack(&part, kind, &staging, &spec.name)?;
+    
+    // The old model stays in place until the new one is ready to take its name
+    let target = target_path(root, spec);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let previous = root.join(DOWNLOAD_DIR).join(format!("{}.previous", spec.name));
+    remove_path(&previous)?;
+    let replacing = target.exists();
+    if replacing {
+        fs::rename(&target, &previous)?;
+    }
+    if let Err(err) = fs::rename(&source, &target) {
+        if replacing {
+            fs::rename(&previous, &target)?;
+        }
+        return Err(err.into());
+    }
+    remove_path(&previous)?;
+    if kind != ArchiveKind::Plain {
+        fs::remove_file(&part)?;
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+    }
+    
+    let marker = InstallMarker { url: spec.url.clone(), sha256: actual };
+    fs::create_dir_all(root.join(MARKER_DIR))?;
+    fs::write(
+        marker_path(root, spec),
+        serde_json::to_vec_pretty(&marker).map_err(|err| ModelError::ManifestError(err.to_string()))?,
+    )?;
+    
+    tracing::info!("Installed model {} to {}", spec.name, target.display());
+    Ok(target)
+}
+
+/// Manages the models the assistant needs: status, download and verification
+/// 
+/// Models are described by a JSON manifest (`{"models": [{"name", "kind",
+/// "url", "path", "sha256", "required"}]}`); without one a default English
+/// set (Vosk small model and a Piper voice) is used. Interrupted downloads
+/// resume where they stopped.
+/// 
+/// Downloads are checked against `storage` (a `StorageGuard`) under the
+/// "models" category; without one they only need to fit on the disk.
+#[pyclass]
+pub struct ModelManager {
+    root: PathBuf,
+    models: Vec<ModelSpec>,
+    storage: Option<Py<StorageGuard>>,
+}
+
+impl ModelManager {
+    fn spec(&self, name: &str) -> Result<&ModelSpec, ModelError> {
+        self.models
+            .iter()
+            .find(|spec| spec.name == name)
+            .ok_or_else(|| ModelError::UnknownModel(name.to_string()))
+    }
+    
+    fn upsert(&mut self, spec: ModelSpec) {
+        match self.models.iter_mut().find(|existing| existing.name == spec.name) {
+            Some(existing) => *existing = spec,
+            None => self.models.push(spec),
+        }
+    }
+}
+
+#[pymethods]
+impl ModelManager {
+    /// Create a model manager for `models_dir`, using `manifest_path` if given
+    #[new]
+    #[pyo3(signature = (models_dir, manifest_path=None, storage=None))]
+    fn new(models_dir: String, manifest_path: Option<String>, storage: Option<Py<StorageGuard>>) -> PyResult<Self> {
+        let mut manager = ModelManager {
+            root: PathBuf::from(models_dir),
+            models: Vec::new(),
+            storage,
+        };
+        match manifest_path {
+            Some(path) => {
+                manager.load_manifest(&path)?;
+            }
+            None => manager.models = default_models(),
+        }
+        Ok(manager)
+    }
+    
+    /// Add models from a manifest file, replacing any with the same name
+    fn load_manifest(&mut self, path: &str) -> PyResult<usize> {
+        let data = fs::read(path).map_err(ModelError::from)?;
+        let manifest: ModelManifest = serde_json::from_slice(&data)
+            .map_err(|err| ModelError::ManifestError(format!("{}: {}", path, err)))?;
+        for spec in &manifest.models {
+            check_spec(spec)?;
+        }
+        let count = manifest.models.len();
+        for spec in manifest.models {
+            self.upsert(spec);
+        }
+        Ok(count)
+    }
+    
+    /// Write the current model list as a manifest file
+    fn save_manifest(&self, path: &str) -> PyResult<()> {
+        let manifest = ModelManifest { models: self.models.clone() };
+        let data = serde_json::to_vec_pretty(&manifest).map_err(|err| ModelError::ManifestError(err.to_string()))?;
+        fs::write(path, data).map_err(ModelError::from)?;
+        Ok(())
+    }
+    
+    /// Add or replace a model
+    /// 
+    /// `kind` is informational ("porcupine_keyword", "vosk", "whisper",
+    /// "tts_voice", ...); `path` is relative to the models directory.
+    #[pyo3(signature = (name, kind, url, path, sha256=None, required=true))]
+    fn add_model(
+        &mut self,
+        name: String,
+        kind: String,
+        url: String,
+        path: String,
+        sha256: Option<String>,
+        required: bool,
+    ) -> PyResult<()> {
+        let spec = ModelSpec { name, kind, url, path, sha256, required };
+        check_spec(&spec)?;
+        self.upsert(spec);
+        Ok(())
+    }
+    
+    /// Forget a model (installed files are left alone)
+    fn remove_model(&mut self, name: &str) -> bool {
+        let before = self.models.len();
+        self.models.retain(|spec| spec.name != name);
+        self.models.len() != before
+    }
+    
+    /// List models with their install status
+    /// 
+    /// Each entry has name, kind, url, path, sha256, required, status
+    /// ("installed", "partial" or "missing") and bytes_downloaded for
+    /// partial downloads.
+    fn list_models(&self, py: Python) -> PyResult<Vec<Py<PyDict>>> {
+        self.models
+            .iter()
+            .map(|spec| {
+                let dict = PyDict::new(py);
+                dict.set_item("name", &spec.name)?;
+                dict.set_item("kind", &spec.kind)?;
+                dict.set_item("url", &spec.url)?;
+                dict.set_item("path", target_path(&self.root, spec).display().to_string())?;
+                dict.set_item("sha256", &spec.sha256)?;
+                dict.set_item("required", spec.required)?;
+                dict.set_item("status", model_status(&self.root, spec).as_str())?;
+                dict.set_item(
+                    "bytes_downloaded",
+                    fs::metadata(part_path(&self.root, spec)).map(|m| m.len()).unwrap_or(0),
+                )?;
+                Ok(dict.into())
+            })
+            .collect()
+    }
+    
+    /// Get a model's status: "installed", "partial" or "missing"
+    fn get_status(&self, name: &str) -> PyResult<String> {
+        Ok(model_status(&self.root, self.spec(name)?).as_str().to_string())
+    }
+    
+    /// Get the path a model is (or will be) installed at
+    fn get_path(&self, name: &str) -> PyResult<String> {
+        Ok(target_path(&self.root, self.spec(name)?).display().to_string())
+    }
+    
+    /// Names of required models that are not installed
+    fn missing_models(&self) -> Vec<String> {
+        self.models
+            .iter()
+            .filter(|spec| spec.required && model_status(&self.root, spec) != ModelStatus::Installed)
+            .map(|spec| spec.name.clone())
+            .collect()
+    }
+    
+    /// Whether every required model is installed
+    fn is_ready(&self) -> bool {
+        self.missing_models().is_empty()
+    }
+    
+    /// Download, verify and install a model, returning its installed path
+    /// 
+    /// `progress(name, downloaded_bytes, total_bytes)` is called about every
+    /// megabyte (`total_bytes` is None when the server doesn't say); return
+    /// False from it to cancel. Cancelled or interrupted downloads resume on
+    /// the next call.
+    #[pyo3(signature = (name, progress=None))]
+    fn download(&self, py: Python, name: &str, progress: Option<PyObject>) -> PyResult<String> {
+        let spec = self.spec(name)?.clone();
+        if let Some(callback) = &progress {
+            if !callback.as_ref(py).is_callable() {
+                return Err(PyValueError::new_err("progress must be callable"));
+            }
+        }
+        
+        let root = self.root.clone();
+        let storage = self.storage.as_ref().map(|guard| guard.clone_ref(py));
+        // Without a guard a download only has to fit in the free space
+        let mut free_space = StorageGuard::new(root.display().to_string(), 0, 1.0)?;
+        let target = py.allow_threads(move || {
+            let mut report = |downloaded: u64, total: Option<u64>| match &progress {
+                Some(callback) => Python::with_gil(|py| match callback.call1(py, (spec.name.as_str(), downloaded, total)) {
+                    Ok(result) => !result.as_ref(py).eq(false).unwrap_or(false),
+                    Err(err) => {
+                        err.print(py);
+                        true
+                    }
+                }),
+                None => true,
+            };
+            let mut reserve = |required: u64| match &storage {
+                Some(guard) => Python::with_gil(|py| guard.borrow_mut(py).admit(py, MODELS_CATEGORY, required)),
+                None => free_space.evaluate(MODELS_CATEGORY, required).map(drop),
+            };
+            install_model(&root, &spec, &mut report, &mut reserve)
+        })?;
+        Ok(target.display().to_string())
+    }
+    
+    /// Download every required model that is missing, returning their names
+    #[pyo3(signature = (progress=None))]
+    fn install_missing(&self, py: Python, progress: Option<PyObject>) -> PyResult<Vec<String>> {
+        let missing = self.missing_models();
+        for name in &missing {
+            self.download(py, name, progress.as_ref().map(|p| p.clone_ref(py)))?;
+        }
+        Ok(missing)
+    }
+    
+    /// Check an installed model against its checksum
+    fn verify(&self, py: Python, name: &str) -> PyResult<bool> {
+        let spec = self.spec(name)?;
+        let root = &self.root;
+        Ok(py.allow_threads(|| verify_model(root, spec))?)
+    }
+    
+    /// Delete an installed model and any partial download
+    fn uninstall(&self, name: &str) -> PyResult<bool> {
+        let spec = self.spec(name)?;
+        let target = target_path(&self.root, spec);
+        let existed = target.exists();
+        remove_path(&target)?;
+        let _ = fs::remove_file(part_path(&self.root, spec));
+        let _ = fs::remove_file(marker_path(&self.root, spec));
+        Ok(existed)
+    }
+    
+    /// Get the models directory
+    fn get_models_dir(&self) -> String {
+        self.root.display().to_string()
+    }
+}
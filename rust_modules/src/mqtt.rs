@@ -0,0 +1,511 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use rumqttc::{Client, Connection, LastWill, MqttOptions, Packet, QoS};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::events::{self, Event, EventData};
+use crate::lifecycle::{self, Resource};
+
+/// Default MQTT broker port
+pub const DEFAULT_PORT: u16 = 1883;
+
+/// Outgoing messages queued before publishing starts dropping them
+const REQUEST_CAPACITY: usize = 64;
+
+/// Pause before reconnecting after the broker connection fails
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Event types published by default and their topics under the prefix
+const DEFAULT_TOPICS: &[(&str, &str)] = &[
+    ("wake_word.detected", "event/wake"),
+    ("speech.final", "event/transcript"),
+    ("assistant.state", "event/state"),
+    ("pipeline.event", "event/pipeline"),
+];
+
+/// Commands accepted under `<prefix>/command/`
+pub const COMMANDS: &[&str] = &["mute", "say", "volume"];
+
+/// Availability topic, "online" while connected (retained, with a last will)
+const STATUS_TOPIC: &str = "status";
+
+/// Current assistant state as a plain retained string, for dashboards
+const STATE_TOPIC: &str = "state";
+
+fn parse_qos(qos: u8) -> Result<QoS, String> {
+    match qos {
+        0 => Ok(QoS::AtMostOnce),
+        1 => Ok(QoS::AtLeastOnce),
+        2 => Ok(QoS::ExactlyOnce),
+        _ => Err(format!("Invalid QoS level: {} (expected 0, 1 or 2)", qos)),
+    }
+}
+
+/// JSON payload for an event
+fn event_payload(event: &Event) -> Value {
+    let mut payload = match &event.data {
+        EventData::WakeWord(detection) => json!({
+            "keyword": detection.keyword,
+            "index": detection.index,
+            "confidence": detection.confidence,
+        }),
+        EventData::Recognition(result) => json!({
+            "text": result.text,
+            "is_final": result.is_final,
+            "confidence": result.confidence,
+        }),
+        EventData::Pipeline(event) => json!({
+            "kind": event.kind,
+            "text": event.text,
+            "keyword": event.keyword,
+            "confidence": event.confidence,
+        }),
+        EventData::State(change) => json!({
+            "previous": change.previous,
+            "state": change.state,
+            "reason": change.reason,
+        }),
+        EventData::Text(text) => json!({ "text": text }),
+        EventData::None | EventData::Object(_) => json!({}),
+    };
+    if let Value::Object(map) = &mut payload {
+        map.insert("type".to_string(), json!(event.kind));
+        map.insert("source".to_string(), json!(event.source));
+        map.insert("timestamp".to_string(), json!(event.timestamp));
+    }
+    payload
+}
+
+/// A command received on a command topic
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Mute(bool),
+    Say(String),
+    /// Output volume in [0, 1]
+    Volume(f32),
+    /// Any other command, with its raw payload
+    Other(String, String),
+}
+
+impl Command {
+    /// Parse a command; payloads may be plain values or JSON
+    /// 
+    /// `mute` takes true/false/on/off/1/0, `say` takes text or
+    /// `{"text": ...}`, and `volume` takes a fraction in [0, 1] or a
+    /// percentage up to 100.
+    fn parse(name: &str, payload: &str) -> Result<Command, String> {
+        let payload = payload.trim();
+        let json: Option<Value> = serde_json::from_str(payload).ok();
+        match name {
+            "mute" => {
+                let value = match &json {
+                    Some(Value::Bool(value)) => Some(*value),
+                    Some(Value::Object(map)) => map.get("mute").and_then(Value::as_bool),
+                    _ => match payload.to_ascii_lowercase().as_str() {
+                        "true" | "on" | "1" | "yes" => Some(true),
+                        "false" | "off" | "0" | "no" => Some(false),
+                        _ => None,
+                    },
+                };
+                value.map(Command::Mute).ok_or_else(|| format!("Invalid mute payload: {:?}", payload))
+            }
+            "say" => {
+                let text = match &json {
+                    Some(Value::Object(map)) => map.get("text").and_then(Value::as_str).unwrap_or_default().to_string(),
+                    Some(Value::String(text)) => text.clone(),
+                    _ => payload.to_string(),
+                };
+                if text.trim().is_empty() {
+                    return Err("Nothing to say".to_string());
+                }
+                Ok(Command::Say(text))
+            }
+            "volume" => {
+                let value = match &json {
+                    Some(Value::Object(map)) => map.get("volume").and_then(Value::as_f64),
+                    Some(value) => value.as_f64(),
+                    None => None,
+                };
+                let value = value.ok_or_else(|| format!("Invalid volume payload: {:?}", payload))?;
+                let volume = if value > 1.0 { value / 100.0 } else { value };
+                if !(0.0..=1.0).contains(&volume) {
+                    return Err(format!("Volume out of range: {}", value));
+                }
+                Ok(Command::Volume(volume as f32))
+            }
+            _ => Ok(Command::Other(name.to_string(), payload.to_string())),
+        }
+    }
+    
+    fn name(&self) -> &str {
+        match self {
+            Command::Mute(_) => "mute",
+            Command::Say(_) => "say",
+            Command::Volume(_) => "volume",
+            Command::Other(name, _) => name,
+        }
+    }
+    
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            Command::Mute(muted) => muted.into_py(py),
+            Command::Say(text) => text.into_py(py),
+            Command::Volume(volume) => volume.into_py(py),
+            Command::Other(_, payload) => payload.into_py(py),
+        }
+    }
+}
+
+/// Connection settings, kept so the bridge can be restarted
+#[derive(Clone)]
+struct Settings {
+    host: String,
+    port: u16,
+    client_id: String,
+    prefix: String,
+    username: Option<String>,
+    password: Option<String>,
+    keep_alive: u64,
+    qos: QoS,
+}
+
+impl Settings {
+    fn topic(&self, suffix: &str) -> String {
+        format!("{}/{}", self.prefix, suffix)
+    }
+    
+    fn options(&self) -> MqttOptions {
+        let mut options = MqttOptions::new(self.client_id.clone(), self.host.clone(), self.port);
+        options.set_keep_alive(Duration::from_secs(self.keep_alive));
+        if let Some(username) = &self.username {
+            options.set_credentials(username.clone(), self.password.clone().unwrap_or_default());
+        }
+        options.set_last_will(LastWill::new(self.topic(STATUS_TOPIC), "offline", QoS::AtLeastOnce, true));
+        options
+    }
+}
+
+/// A connected bridge: the client, its connection thread and the bus subscription
+struct Bridge {
+    client: Client,
+    settings: Settings,
+    stop: AtomicBool,
+    connected: AtomicBool,
+    subscription: Mutex<Option<u64>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Resource for Bridge {
+    /// Stop publishing, mark the assistant offline and disconnect
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(subscription) = self.subscription.lock().unwrap().take() {
+            events::unsubscribe(subscription);
+        }
+        if self.connected.load(Ordering::Relaxed) {
+            let _ = self.client.try_publish(self.settings.topic(STATUS_TOPIC), QoS::AtLeastOnce, true, "offline");
+        }
+        let _ = self.client.try_disconnect();
+        let thread = self.thread.lock().unwrap().take();
+        lifecycle::join_threads(thread);
+    }
+}
+
+impl Bridge {
+    /// Publish an event on its topic, dropping it if the outgoing queue is full
+    fn publish_event(&self, topic: &str, event: &Event) {
+        if !self.connected.load(Ordering::Relaxed) {
+            return;
+        }
+        let payload = event_payload(event).to_string();
+        if let Err(err) = self.client.try_publish(topic, self.settings.qos, false, payload) {
+            tracing::debug!(topic, error = %err, "Dropped MQTT event");
+        }
+        if let EventData::State(change) = &event.data {
+            let _ = self.client.try_publish(self.settings.topic(STATE_TOPIC), self.settings.qos, true, change.state.clone());
+        }
+    }
+    
+    /// Handle a message on a command topic
+    fn command(&self, topic: &str, payload: &[u8], callback: &Option<PyObject>) {
+        let name = match topic.strip_prefix(&self.settings.topic("command/")) {
+            Some(name) if !name.is_empty() => name,
+            _ => return,
+        };
+        let payload = String::from_utf8_lossy(payload);
+        let command = match Command::parse(name, &payload) {
+            Ok(command) => command,
+            Err(err) => {
+                tracing::warn!(command = name, "Ignoring MQTT command: {}", err);
+                return;
+            }
+        };
+        tracing::debug!(command = command.name(), "MQTT command");
+        if let Some(callback) = callback {
+            Python::with_gil(|py| {
+                let name = command.name().to_string();
+                if let Err(err) = callback.call1(py, (name, command.into_py(py))) {
+                    err.print(py);
+                }
+            });
+        }
+    }
+    
+    /// Drive the connection until stopped, reconnecting after failures
+    fn run(&self, mut connection: Connection, callback: Option<PyObject>) {
+        for notification in connection.iter() {
+            if self.stop.load(Ordering::Relaxed) {
+                break;
+            }
+            match notification {
+                Ok(rumqttc::Event::Incoming(Packet::ConnAck(_))) => {
+                    self.connected.store(true, Ordering::Relaxed);
+                    tracing::info!(host = %self.settings.host, port = self.settings.port, "MQTT connected");
+                    let _ = self.client.try_subscribe(self.settings.topic("command/#"), QoS::AtLeastOnce);
+                    let _ = self.client.try_publish(self.settings.topic(STATUS_TOPIC), QoS::AtLeastOnce, true, "online");
+                }
+                Ok(rumqttc::Event::Incoming(Packet::Publish(publish))) => {
+                    self.command(&publish.topic, &publish.payload, &callback);
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    if self.stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if self.connected.swap(false, Ordering::Relaxed) {
+                        tracing::warn!("MQTT connection lost: {}", err);
+                    } else {
+                        tracing::debug!("MQTT connection failed: {}", err);
+                    }
+                    thread::sleep(RECONNECT_DELAY);
+                }
+            }
+        }
+        self.connected.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Bridges the event bus to an MQTT broker for smart-home dashboards
+/// 
+/// Once started, events from the Rust components are published as JSON
+/// under `topic_prefix`: by default wake word detections on
+/// `<prefix>/event/wake`, final transcripts on `<prefix>/event/transcript`,
+/// assistant state changes on `<prefix>/event/state` (and the bare state,
+/// retained, on `<prefix>/state`) and pipeline events on
+/// `<prefix>/event/pipeline`. `<prefix>/status` holds "online" or
+/// "offline" (retained, also set by the broker if the connection drops).
+/// 
+/// Messages on `<prefix>/command/<name>` are parsed and passed to
+/// `callback(name, value)`: "mute" gives a bool, "say" the text and
+/// "volume" a fraction in [0, 1]; other commands give the raw payload.
+/// The bridge reconnects on its own after the broker goes away.
+#[pyclass]
+pub struct MqttBridge {
+    settings: Settings,
+    topics: HashMap<String, String>,
+    bridge: Option<Arc<Bridge>>,
+}
+
+impl MqttBridge {
+    fn running(&self) -> Option<&Arc<Bridge>> {
+        self.bridge.as_ref().filter(|bridge| !bridge.stop.load(Ordering::Relaxed))
+    }
+}
+
+#[pymethods]
+impl MqttBridge {
+    /// Configure a bridge to the broker at `host`:`port`
+    /// 
+    /// `topics` maps event types (see `EventBus.event_types()`) to topics
+    /// under the prefix, replacing the defaults; an empty topic turns an
+    /// event type off. `qos` applies to published events.
+    #[new]
+    #[pyo3(signature = (
+        host="localhost",
+        port=DEFAULT_PORT,
+        client_id="jenna",
+        topic_prefix="jenna",
+        username=None,
+        password=None,
+        topics=None,
+        keep_alive=30,
+        qos=1
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        host: &str,
+        port: u16,
+        client_id: &str,
+        topic_prefix: &str,
+        username: Option<String>,
+        password: Option<String>,
+        topics: Option<HashMap<String, String>>,
+        keep_alive: u64,
+        qos: u8,
+    ) -> PyResult<Self> {
+        let prefix = topic_prefix.trim_matches('/');
+        if prefix.is_empty() || prefix.contains(['+', '#']) {
+            return Err(PyValueError::new_err(format!("Invalid topic prefix: {:?}", topic_prefix)));
+        }
+        if keep_alive < 5 {
+            return Err(PyValueError::new_err("Keep-alive must be at least 5 seconds"));
+        }
+        let mut mapping: HashMap<String, String> =
+            DEFAULT_TOPICS.iter().map(|(kind, topic)| (kind.to_string(), topic.to_string())).collect();
+        for (kind, topic) in topics.unwrap_or_default() {
+            if !events::EVENT_TYPES.contains(&kind.as_str()) {
+                return Err(PyValueError::new_err(format!("Unknown event type: {}", kind)));
+            }
+            if topic.contains(['+', '#']) {
+                return Err(PyValueError::new_err(format!("Wildcards are not allowed in topic {:?}", topic)));
+            }
+            mapping.insert(kind, topic.trim_matches('/').to_string());
+        }
+        mapping.retain(|_, topic| !topic.is_empty());
+        
+        Ok(MqttBridge {
+            settings: Settings {
+                host: host.to_string(),
+                port,
+                client_id: client_id.to_string(),
+                prefix: prefix.to_string(),
+                username,
+                password,
+                keep_alive,
+                qos: parse_qos(qos).map_err(PyValueError::new_err)?,
+            },
+            topics: mapping,
+            bridge: None,
+        })
+    }
+    
+    /// Connect to the broker and start publishing events
+    /// 
+    /// Returns immediately; the connection is made (and remade after
+    /// failures) in the background, see `is_connected`. `callback(name,
+    /// value)` receives commands from a background thread.
+    #[pyo3(signature = (callback=None))]
+    fn start(&mut self, py: Python, callback: Option<PyObject>) -> PyResult<()> {
+        if self.running().is_some() {
+            return Err(PyValueError::new_err("MQTT bridge is already running"));
+        }
+        if let Some(callback) = &callback {
+            if !callback.as_ref(py).is_callable() {
+                return Err(PyValueError::new_err("Callback must be callable"));
+            }
+        }
+        
+        let (client, connection) = Client::new(self.settings.options(), REQUEST_CAPACITY);
+        let bridge = Arc::new(Bridge {
+            client,
+            settings: self.settings.clone(),
+            stop: AtomicBool::new(false),
+            connected: AtomicBool::new(false),
+            subscription: Mutex::new(None),
+            thread: Mutex::new(None),
+        });
+        
+        let running = bridge.clone();
+        let handle = thread::Builder::new()
+            .name("jenna-mqtt".to_string())
+            .spawn(move || running.run(connection, callback))
+            .map_err(|err| PyValueError::new_err(format!("Failed to start MQTT thread: {}", err)))?;
+        *bridge.thread.lock().unwrap() = Some(handle);
+        
+        let topics: HashMap<String, String> =
+            self.topics.iter().map(|(kind, topic)| (kind.clone(), self.settings.topic(topic))).collect();
+        let publisher = Arc::downgrade(&bridge);
+        let subscription = events::subscribe(
+            "*",
+            Box::new(move |event| {
+                if let (Some(bridge), Some(topic)) = (publisher.upgrade(), topics.get(event.kind)) {
+                    bridge.publish_event(topic, event);
+                }
+            }),
+        );
+        match subscription {
+            Ok(id) => *bridge.subscription.lock().unwrap() = Some(id),
+            Err(err) => {
+                py.allow_threads(|| bridge.shutdown());
+                return Err(PyValueError::new_err(err));
+            }
+        }
+        
+        lifecycle::register(&bridge);
+        self.bridge = Some(bridge);
+        Ok(())
+    }
+    
+    /// Publish "offline", disconnect and stop forwarding events
+    fn stop(&mut self, py: Python) {
+        if let Some(bridge) = self.bridge.take() {
+            // The connection thread may be delivering a command to Python
+            py.allow_threads(|| bridge.shutdown());
+        }
+    }
+    
+    fn is_running(&self) -> bool {
+        self.running().is_some()
+    }
+    
+    /// Check whether the broker connection is currently up
+    fn is_connected(&self) -> bool {
+        self.running().map_or(false, |bridge| bridge.connected.load(Ordering::Relaxed))
+    }
+    
+    /// Publish `payload` on `topic` under the prefix
+    /// 
+    /// For application messages such as assistant replies. Fails if the
+    /// bridge isn't running or the outgoing queue is full.
+    #[pyo3(signature = (topic, payload, retain=false))]
+    fn publish(&self, topic: &str, payload: &str, retain: bool) -> PyResult<()> {
+        let bridge = self.running().ok_or_else(|| PyValueError::new_err("MQTT bridge is not running"))?;
+        if topic.contains(['+', '#']) {
+            return Err(PyValueError::new_err(format!("Wildcards are not allowed in topic {:?}", topic)));
+        }
+        bridge
+            .client
+            .try_publish(self.settings.topic(topic.trim_matches('/')), self.settings.qos, retain, payload.to_string())
+            .map_err(|err| PyValueError::new_err(format!("Failed to publish: {}", err)))
+    }
+    
+    /// Get the event type to topic mapping, with the prefix applied
+    fn get_topics(&self) -> HashMap<String, String> {
+        self.topics.iter().map(|(kind, topic)| (kind.clone(), self.settings.topic(topic))).collect()
+    }
+    
+    /// List the commands that are parsed before reaching the callback
+    #[staticmethod]
+    fn commands() -> Vec<&'static str> {
+        COMMANDS.to_vec()
+    }
+    
+    fn __repr__(&self) -> String {
+        format!(
+            "MqttBridge(host='{}', port={}, prefix='{}', running={}, connected={})",
+            self.settings.host,
+            self.settings.port,
+            self.settings.prefix,
+            self.is_running(),
+            self.is_connected()
+        )
+    }
+}
+
+impl Drop for MqttBridge {
+    fn drop(&mut self) {
+        // Signal without joining (see `VoicePipeline`'s Drop)
+        if let Some(bridge) = &self.bridge {
+            bridge.stop.store(true, Ordering::Relaxed);
+            if let Some(subscription) = bridge.subscription.lock().unwrap().take() {
+                events::unsubscribe(subscription);
+            }
+            let _ = bridge.client.try_disconnect();
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use realfft::{ComplexToReal, RealFftPlanner, RealToComplex};
+use rustfft::num_complex::Complex32;
+use std::sync::Arc;
+
+use crate::signal::WindowType;
+
+/// Spectral noise gate with a learned noise profile
+/// 
+/// `learn_noise` averages the magnitude spectrum of a few seconds of ambient
+/// audio. During `process`, each STFT bin whose magnitude is within
+/// `threshold_db` of the profile is attenuated by `reduction_db`; the gain
+/// mask is smoothed over time to avoid musical noise. Frames are
+/// reconstructed with 50% overlap-add, so output lags input by one hop.
+#[pyclass]
+pub struct NoiseGate {
+    #[pyo3(get)]
+    sample_rate: u32,
+    #[pyo3(get)]
+    frame_size: usize,
+    #[pyo3(get, set)]
+    threshold_db: f32,
+    #[pyo3(get, set)]
+    reduction_db: f32,
+    #[pyo3(get, set)]
+    smoothing: f32,
+    hop_size: usize,
+    window: Vec<f32>,
+    forward: Arc<dyn RealToComplex<f32>>,
+    inverse: Arc<dyn ComplexToReal<f32>>,
+    profile: Option<Vec<f32>>,
+    mask: Vec<f32>,
+    input: Vec<f32>,
+    overlap: Vec<f32>,
+}
+
+impl NoiseGate {
+    /// Create a gate; it passes audio through until a noise profile is learned
+    pub fn create(sample_rate: u32, frame_size: usize, threshold_db: f32, reduction_db: f32, smoothing: f32) -> Result<Self, String> {
+        if frame_size < 4 || frame_size % 2 != 0 {
+            return Err("Frame size must be an even number of at least 4".to_string());
+        }
+        if !(0.0..1.0).contains(&smoothing) {
+            return Err("Smoothing must be in [0.0, 1.0)".to_string());
+        }
+        
+        // Periodic sqrt-Hann analysis/synthesis windows sum to one at 50% overlap
+        let hann = WindowType::Hann.coefficients(frame_size + 1);
+        let window: Vec<f32> = hann[..frame_size].iter().map(|w| w.sqrt()).collect();
+        
+        let mut planner = RealFftPlanner::<f32>::new();
+        let forward = planner.plan_fft_forward(frame_size);
+        let inverse = planner.plan_fft_inverse(frame_size);
+        let bins = frame_size / 2 + 1;
+        
+        Ok(NoiseGate {
+            sample_rate,
+            frame_size,
+            threshold_db,
+            reduction_db,
+            smoothing,
+            hop_size: frame_size / 2,
+            window,
+            forward,
+            inverse,
+            profile: None,
+            mask: vec![1.0; bins],
+            input: Vec::with_capacity(frame_size * 2),
+            overlap: vec![0.0; frame_size],
+        })
+    }
+    
+    /// Learn the noise profile from ambient audio
+    pub fn learn(&mut self, samples: &[f32]) -> Result<(), String> {
+        if samples.len() < self.frame_size {
+            return Err(format!("At least {} samples are needed to learn a noise profile", self.frame_size));
+        }
+        
+        let bins = self.frame_size / 2 + 1;
+        let mut profile = vec![0.0f32; bins];
+        let mut frames = 0;
+        let mut start = 0;
+        while start + self.frame_size <= samples.len() {
+            let spectrum = self.analyse(&samples[start..start + self.frame_size]);
+            for (acc, bin) in profile.iter_mut().zip(spectrum.iter()) {
+                *acc += bin.norm();
+            }
+            frames += 1;
+            start += self.hop_size;
+        }
+        
+        profile.iter_mut().for_each(|p| *p /= frames as f32);
+        self.profile = Some(profile);
+        Ok(())
+    }
+    
+    /// Gate a block of samples, returning the completed hops
+    pub fn gate(&mut self, frame: &[f32]) -> Vec<f32> {
+        let profile = match &self.profile {
+            Some(profile) => profile.clone(),
+            None => return frame.to_vec(),
+        };
+        
+        self.input.extend_from_slice(frame);
+        let mut output = Vec::with_capacity(frame.len());
+        while self.input.len() >= self.frame_size {
+            let analysis: Vec<f32> = self.input[..self.frame_size].to_vec();
+            self.gate_frame(&analysis, &profile, &mut output);
+            self.input.drain(..self.hop_size);
+        }
+        output
+    }
+    
+    /// Magnitude spectrum of a windowed frame
+    fn analyse(&self, frame: &[f32]) -> Vec<Complex32> {
+        let mut buffer: Vec<f32> = frame.iter().zip(self.window.iter()).map(|(x, w)| x * w).collect();
+        let mut spectrum = self.forward.make_output_vec();
+        self.forward.process(&mut buffer, &mut spectrum)
+            .expect("FFT buffer sizes are derived from the plan");
+        spectrum
+    }
+    
+    /// Gate one analysis frame and overlap-add it into the output
+    fn gate_frame(&mut self, frame: &[f32], profile: &[f32], output: &mut Vec<f32>) {
+        let mut spectrum = self.analyse(frame);
+        
+        let threshold = 10f32.powf(self.threshold_db / 20.0);
+        let floor = 10f32.powf(-self.reduction_db.abs() / 20.0);
+        for ((bin, mask), &noise) in spectrum.iter_mut().zip(self.mask.iter_mut()).zip(profile.iter()) {
+            let target = if bin.norm() > noise * threshold { 1.0 } else { floor };
+            *mask = self.smoothing * *mask + (1.0 - self.smoothing) * target;
+            *bin *= *mask;
+        }
+        spectrum[0].im = 0.0;
+        let last = spectrum.len() - 1;
+        spectrum[last].im = 0.0;
+        
+        let mut time = self.inverse.make_output_vec();
+        self.inverse.process(&mut spectrum, &mut time)
+            .expect("FFT buffer sizes are derived from the plan");
+        
+        let scale = 1.0 / self.frame_size as f32;
+        for (i, sample) in time.iter().enumerate() {
+            self.overlap[i] += sample * scale * self.window[i];
+        }
+        
+        output.extend_from_slice(&self.overlap[..self.hop_size]);
+        self.overlap.drain(..self.hop_size);
+        self.overlap.extend(std::iter::repeat(0.0).take(self.hop_size));
+    }
+}
+
+#[pymethods]
+impl NoiseGate {
+    /// Create a new noise gate
+    #[new]
+    #[pyo3(signature = (sample_rate, frame_size=512, threshold_db=6.0, reduction_db=24.0, smoothing=0.6))]
+    fn new(sample_rate: u32, frame_size: usize, threshold_db: f32, reduction_db: f32, smoothing: f32) -> PyResult<Self> {
+        NoiseGate::create(sample_rate, frame_size, threshold_db, reduction_db, smoothing).map_err(PyValueError::new_err)
+    }
+    
+    /// Learn the noise profile from ambient audio (ideally a few seconds without speech)
+    fn learn_noise(&mut self, samples: Vec<f32>) -> PyResult<()> {
+        self.learn(&samples).map_err(PyValueError::new_err)
+    }
+    
+    /// Check whether a noise profile has been learned
+    fn has_profile(&self) -> bool {
+        self.profile.is_some()
+    }
+    
+    /// Discard the learned noise profile
+    fn clear_profile(&mut self) -> PyResult<()> {
+        self.profile = None;
+        Ok(())
+    }
+    
+    /// Gate a block of samples
+    /// 
+    /// Returns as many samples as full hops are available; without a learned
+    /// profile the input passes through unchanged.
+    fn process(&mut self, py: Python, frame: Vec<f32>) -> PyResult<Vec<f32>> {
+        Ok(py.allow_threads(|| self.gate(&frame)))
+    }
+    
+    /// Reset the streaming state, keeping the learned profile
+    fn reset(&mut self) -> PyResult<()> {
+        self.input.clear();
+        self.overlap.iter_mut().for_each(|x| *x = 0.0);
+        self.mask.iter_mut().for_each(|m| *m = 1.0);
+        Ok(())
+    }
+}
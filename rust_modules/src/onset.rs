@@ -0,0 +1,154 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::VecDeque;
+
+use crate::signal::FftProcessor;
+
+/// Streaming onset detector based on spectral flux
+/// 
+/// Each hop the log-magnitude spectrum is compared with the previous one
+/// and the positive differences are summed. A frame is an onset when its
+/// flux is a local maximum above an adaptive threshold (median of recent
+/// flux times `multiplier`, plus `delta`). Onsets closer than
+/// `min_interval_ms` to the previous one are suppressed.
+#[pyclass]
+pub struct OnsetDetector {
+    #[pyo3(get)]
+    sample_rate: u32,
+    #[pyo3(get)]
+    frame_size: usize,
+    #[pyo3(get)]
+    hop_size: usize,
+    #[pyo3(get, set)]
+    multiplier: f32,
+    #[pyo3(get, set)]
+    delta: f32,
+    #[pyo3(get, set)]
+    min_interval_ms: f32,
+    fft: FftProcessor,
+    pending: Vec<f32>,
+    previous_spectrum: Option<Vec<f32>>,
+    history: VecDeque<f32>,
+    history_len: usize,
+    /// Flux of the two most recent frames, for local-maximum picking
+    recent: [f32; 2],
+    frames: u64,
+    last_onset: Option<f64>,
+}
+
+impl OnsetDetector {
+    fn flux(&mut self, frame: &[f32]) -> PyResult<f32> {
+        let spectrum: Vec<f32> = self.fft
+            .magnitudes(frame)?
+            .into_iter()
+            .map(|m| (1.0 + 1000.0 * m).ln())
+            .collect();
+        
+        let flux = match &self.previous_spectrum {
+            Some(previous) => spectrum
+                .iter()
+                .zip(previous.iter())
+                .map(|(&current, &prev)| (current - prev).max(0.0))
+                .sum::<f32>() / spectrum.len() as f32,
+            None => 0.0,
+        };
+        self.previous_spectrum = Some(spectrum);
+        Ok(flux)
+    }
+    
+    fn threshold(&self) -> f32 {
+        if self.history.is_empty() {
+            return self.delta;
+        }
+        let mut sorted: Vec<f32> = self.history.iter().cloned().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        sorted[sorted.len() / 2] * self.multiplier + self.delta
+    }
+}
+
+#[pymethods]
+impl OnsetDetector {
+    /// Create a new onset detector
+    #[new]
+    #[pyo3(signature = (sample_rate, frame_size=1024, hop_size=512, multiplier=1.5, delta=0.05, min_interval_ms=80.0, history_frames=16))]
+    fn new(
+        sample_rate: u32,
+        frame_size: usize,
+        hop_size: usize,
+        multiplier: f32,
+        delta: f32,
+        min_interval_ms: f32,
+        history_frames: usize,
+    ) -> PyResult<Self> {
+        if frame_size == 0 || hop_size == 0 || hop_size > frame_size {
+            return Err(PyValueError::new_err("Hop size must be between 1 and the frame size"));
+        }
+        
+        Ok(OnsetDetector {
+            sample_rate,
+            frame_size,
+            hop_size,
+            multiplier,
+            delta,
+            min_interval_ms,
+            fft: FftProcessor::with_window("hann")?,
+            pending: Vec::with_capacity(frame_size * 2),
+            previous_spectrum: None,
+            history: VecDeque::with_capacity(history_frames.max(1)),
+            history_len: history_frames.max(1),
+            recent: [0.0; 2],
+            frames: 0,
+            last_onset: None,
+        })
+    }
+    
+    /// Feed samples and return the timestamps (seconds) of detected onsets
+    /// 
+    /// Onsets are reported one hop after they occur, since a peak can only
+    /// be confirmed once the following frame has been analysed.
+    fn process(&mut self, samples: Vec<f32>) -> PyResult<Vec<f64>> {
+        self.pending.extend_from_slice(&samples);
+        
+        let mut onsets = Vec::new();
+        while self.pending.len() >= self.frame_size {
+            let frame: Vec<f32> = self.pending[..self.frame_size].to_vec();
+            self.pending.drain(..self.hop_size);
+            
+            let flux = self.flux(&frame)?;
+            let [before, candidate] = self.recent;
+            let threshold = self.threshold();
+            
+            // The previous frame is an onset if it peaks above the threshold
+            if self.frames >= 2 && candidate > threshold && candidate >= before && candidate > flux {
+                let time = (self.frames - 1) as f64 * self.hop_size as f64 / self.sample_rate as f64;
+                let far_enough = self.last_onset
+                    .map(|last| (time - last) * 1000.0 >= self.min_interval_ms as f64)
+                    .unwrap_or(true);
+                if far_enough {
+                    onsets.push(time);
+                    self.last_onset = Some(time);
+                }
+            }
+            
+            self.history.push_back(candidate);
+            if self.history.len() > self.history_len {
+                self.history.pop_front();
+            }
+            self.recent = [candidate, flux];
+            self.frames += 1;
+        }
+        
+        Ok(onsets)
+    }
+    
+    /// Reset the detector state
+    fn reset(&mut self) -> PyResult<()> {
+        self.pending.clear();
+        self.previous_spectrum = None;
+        self.history.clear();
+        self.recent = [0.0; 2];
+        self.frames = 0;
+        self.last_onset = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use pyo3::prelude::*;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::panic::{self, AssertUnwindSafe, UnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::events::{self, Event, EventData};
+
+/// Panics caught since the module was loaded
+static PANICS: AtomicU64 = AtomicU64::new(0);
+
+static HOOK: OnceLock<()> = OnceLock::new();
+
+/// Times a failed worker thread is restarted before it is left stopped
+const MAX_RESTARTS: u32 = 5;
+
+/// Wait before the first restart of a failed thread; each further one waits longer
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// A thread that ran this long before failing starts counting restarts afresh
+const RESTART_WINDOW: Duration = Duration::from_secs(60);
+
+/// How often a restart backoff checks whether its owner is stopping
+const STOP_POLL: Duration = Duration::from_millis(20);
+
+/// Route panic reports through tracing, and so to Python logging, with a backtrace
+/// 
+/// The previous hook still runs afterwards, so a panic is also on stderr
+/// if logging hasn't been set up yet.
+pub fn install_hook() {
+    HOOK.get_or_init(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let thread = thread::current();
+            let location = info.location().map(|location| location.to_string()).unwrap_or_default();
+            tracing::error!(
+                thread = thread.name().unwrap_or("unnamed"),
+                location = %location,
+                backtrace = %Backtrace::force_capture(),
+                "Panic: {}",
+                payload_message(info.payload())
+            );
+            previous(info);
+        }));
+    });
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Run `f`, turning a panic into an error instead of unwinding further
+/// 
+/// Used wherever a panic would otherwise cross into C, Python or an audio
+/// backend, or end a worker thread silently. The hook has already logged
+/// the backtrace; this also posts "thread.panicked" on the event bus with
+/// `context` and the panic message.
+pub fn catch<R>(context: &str, f: impl FnOnce() -> R + UnwindSafe) -> Result<R, String> {
+    panic::catch_unwind(f).map_err(|payload| {
+        PANICS.fetch_add(1, Ordering::Relaxed);
+        let message = format!("{} panicked: {}", context, payload_message(payload.as_ref()));
+        events::post(Event::new("thread.panicked", context, EventData::Text(message.clone())));
+        message
+    })
+}
+
+/// Run a worker thread's body, restarting it with a growing backoff when it fails or panics
+/// 
+/// Returns when the body returns Ok. On each failure `failed` gets the
+/// error and the delay before the restart, or None once the thread has
+/// failed `MAX_RESTARTS` times in a row and is left stopped. It reports
+/// the error, waits out the delay unless the owner is stopping, clears the
+/// poison the panic left on shared locks, and returns whether to restart.
+pub fn supervise(
+    name: &str,
+    mut body: impl FnMut() -> Result<(), String>,
+    mut failed: impl FnMut(String, Option<Duration>) -> bool,
+) {
+    let mut restarts = 0;
+    loop {
+        let started = Instant::now();
+        let err = match catch(name, AssertUnwindSafe(&mut body)).and_then(|result| result) {
+            Ok(()) => return,
+            Err(err) => err,
+        };
+        tracing::error!("{} stopped: {}", name, err);
+        if started.elapsed() >= RESTART_WINDOW {
+            restarts = 0;
+        }
+        if restarts >= MAX_RESTARTS {
+            tracing::error!("{} failed {} times in a row; leaving it stopped", name, restarts + 1);
+            failed(err, None);
+            return;
+        }
+        restarts += 1;
+        let delay = RESTART_DELAY * restarts;
+        tracing::warn!(restarts, "Restarting {} in {:?}", name, delay);
+        if !failed(err, Some(delay)) {
+            return;
+        }
+    }
+}
+
+/// Sleep for `delay` unless `stop` is set first; returns whether it is still unset
+pub fn wait_unless_stopped(stop: &AtomicBool, delay: Duration) -> bool {
+    let deadline = Instant::now() + delay;
+    while !stop.load(Ordering::Relaxed) && Instant::now() < deadline {
+        thread::sleep(STOP_POLL);
+    }
+    !stop.load(Ordering::Relaxed)
+}
+
+/// Number of panics caught and turned into errors since the module was loaded
+#[pyfunction]
+pub fn panic_count() -> u64 {
+    PANICS.load(Ordering::Relaxed)
+}
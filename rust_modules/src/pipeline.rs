@@ -0,0 +1,2318 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::{PyStopAsyncIteration, PyValueError};
+use pyo3::types::PyDict;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::{Arc, Condvar, Mutex, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use jenna_core::audio_class::{AudioClass, AudioClassifier, PresenceTracker, SpeechActivity};
+use jenna_core::calibration::NoiseCalibrator;
+use jenna_core::dereverb::Dereverberator;
+use jenna_core::echo::EchoCanceller;
+use jenna_core::loudness::{normalize_loudness, DEFAULT_MAX_PEAK_DB, EBU_R128_TARGET};
+use jenna_core::vad::VoiceActivityDetector;
+
+use crate::assistant::{Assistant, AssistantState, Phase};
+use crate::audio::{
+    open_mono_playback, read_audio_file_mono, resample_mono, CaptureSource, Captured, Earcon, MonoResampler, PlaybackMonitor,
+    PrivacyGate, StallPolicy,
+};
+use crate::config::{check_timeouts, JennaConfig};
+use crate::dictation::{Dictation, DictationOptions, DictationTranscript};
+use crate::dynamics::check_loudness_target;
+use crate::errors::NotInitializedError;
+use crate::panics;
+use crate::priority::{self, ThreadPolicy};
+use crate::events::{self, Event, EventData};
+use crate::lifecycle::{self, Resource};
+use crate::metrics;
+use crate::session::{read_session, SessionError, SessionEvent, SessionHeader, SessionReplay, SessionWriter};
+use crate::graph::{AudioGraph, ProcessingGraph, SharedGraph};
+use crate::keywords::{KeywordSpotter, SharedKeywordSpotter, SpotterState};
+use crate::sound_events::{SharedSoundDetector, SoundEventClassifier, SOUND_SAMPLE_RATE};
+use crate::speech::{RecognitionResult, SharedEngine, SpeechRecognizer, SttEngine, TextToSpeech};
+use crate::storage::{StorageEncryption, StorageGuard, RECORDINGS_CATEGORY};
+use crate::wake_word::{unix_time, ContextSnapshot, DetectorState, WakeWordDetector};
+
+/// Events kept for `poll_event` when no callback is set; the oldest are dropped beyond this
+const EVENT_QUEUE_LIMIT: usize = 256;
+
+/// How often the playback thread checks for shutdown
+const PLAYBACK_POLL: Duration = Duration::from_millis(50);
+
+/// How often a waiting `events()` iterator checks for shutdown
+const STREAM_POLL: Duration = Duration::from_millis(100);
+
+/// Frames queued for the sound event classifier before new ones are dropped
+const SOUND_QUEUE_FRAMES: usize = 100;
+
+/// Playback gain while a barge-in turn is listening in "duck" mode (-20 dB)
+const DUCK_GAIN: f32 = 0.1;
+
+/// Longest speaker-to-microphone echo path the canceller models, in seconds
+const ECHO_TAIL: f32 = 0.1;
+
+/// Played audio kept ahead of the capture for the echo canceller, in seconds
+const ECHO_MAX_LEAD: f32 = 0.05;
+
+/// Seconds without speech before the room counts as empty again
+const PRESENCE_TIMEOUT: f32 = 30.0;
+
+/// Seconds of captured audio kept for `recent_audio`
+const RECENT_AUDIO_SECS: f32 = 15.0;
+
+/// Bytes a session recording claims from its storage guard at a time
+const RECORDING_CHUNK: u64 = 1 << 20;
+
+/// A high-level event emitted by a running `VoicePipeline`
+/// 
+/// `kind` is one of "wake" (a turn started; `keyword` is None when it was
+/// started by `trigger` or follow-up speech), "speech_start", "partial",
+/// "final", "unknown" (the recognizer's acceptance policy rejected the
+/// transcript; it is in `text` and `result`), "timeout" (no speech
+/// followed the wake word), "keyword" (a watched phrase was heard; it is
+/// in `keyword`, the transcript so far in `text`), "sound" (a watched
+/// sound was recognized; its event name is in `text`), "privacy" (privacy
+/// mode was turned "on" or "off", in `text`), "dictation_start",
+/// "dictation" (a segment was dictated; its text is in `text`, the
+/// transcript so far in `dictation`), "dictation_end" (the full text is in
+/// `text` and `dictation`) or "error".
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct PipelineEvent {
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub text: Option<String>,
+    #[pyo3(get)]
+    pub keyword: Option<String>,
+    #[pyo3(get)]
+    pub confidence: Option<f32>,
+    /// Full recognition result for "final", "unknown" and "dictation" events
+    #[pyo3(get)]
+    pub result: Option<RecognitionResult>,
+    /// Transcript so far for "dictation" and "dictation_end" events
+    #[pyo3(get)]
+    pub dictation: Option<DictationTranscript>,
+    /// Wall-clock time of the event, in seconds since the Unix epoch
+    #[pyo3(get)]
+    pub timestamp: f64,
+    /// Zone of the pipeline that emitted the event, if it was given one
+    #[pyo3(get)]
+    pub zone: Option<String>,
+    /// Context the wake word captured, for "wake" events it started
+    #[pyo3(get)]
+    pub context: Option<ContextSnapshot>,
+}
+
+impl PipelineEvent {
+    pub fn new(kind: &str) -> Self {
+        PipelineEvent {
+            kind: kind.to_string(),
+            text: None,
+            keyword: None,
+            confidence: None,
+            result: None,
+            dictation: None,
+            timestamp: unix_time(),
+            zone: None,
+            context: None,
+        }
+    }
+    
+    pub fn error(message: String) -> Self {
+        PipelineEvent { text: Some(message), ..PipelineEvent::new("error") }
+    }
+}
+
+#[pymethods]
+impl PipelineEvent {
+    fn __repr__(&self) -> String {
+        format!(
+            "PipelineEvent(kind={:?}, text={:?}, keyword={:?}, confidence={:?}, timestamp={:.3})",
+            self.kind, self.text, self.keyword, self.confidence, self.timestamp
+        )
+    }
+}
+
+/// When a turn ends, in seconds
+#[derive(Clone, Copy, Debug)]
+pub struct Endpointing {
+    pub vad_threshold_db: f32,
+    pub silence_timeout: f32,
+    pub no_speech_timeout: f32,
+    pub max_utterance: f32,
+}
+
+/// What a wake word heard during playback does to the playback
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BargeIn {
+    /// Drop the rest of the queued audio
+    Stop,
+    /// Lower the volume until the turn ends, then resume
+    Duck,
+}
+
+impl BargeIn {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "stop" => Ok(BargeIn::Stop),
+            "duck" => Ok(BargeIn::Duck),
+            _ => Err(format!("Unknown barge-in mode '{}'; expected 'stop' or 'duck'", name)),
+        }
+    }
+}
+
+/// An open session recording and the storage guard its growth is checked against
+struct Recording {
+    writer: SessionWriter,
+    storage: Option<Arc<Py<StorageGuard>>>,
+    /// Size the recording may grow to before the next chunk is claimed
+    reserved: u64,
+}
+
+impl Recording {
+    /// Open a recording, claiming its first chunk from `storage`
+    fn new(py: Python, writer: SessionWriter, storage: Option<Py<StorageGuard>>) -> Result<Self, SessionError> {
+        if let Some(storage) = &storage {
+            storage.borrow_mut(py).admit(py, RECORDINGS_CATEGORY, RECORDING_CHUNK)?;
+        }
+        let reserved = writer.bytes_written() + RECORDING_CHUNK;
+        Ok(Recording { writer, storage: storage.map(Arc::new), reserved })
+    }
+    
+    /// The storage guard, once the recording has used up its last chunk
+    fn due_claim(&self) -> Option<Arc<Py<StorageGuard>>> {
+        self.storage.clone().filter(|_| self.writer.bytes_written() >= self.reserved)
+    }
+}
+
+/// State shared between the pipeline object and its threads
+struct Shared {
+    zone: Option<String>,
+    stop: AtomicBool,
+    listen_request: AtomicBool,
+    listening: AtomicBool,
+    /// Dictation start or stop asked for by Python, taken by the next frame
+    dictation_request: Mutex<Option<DictationRequest>>,
+    dictating: AtomicBool,
+    events: Mutex<VecDeque<PipelineEvent>>,
+    events_ready: Condvar,
+    playback: Arc<Mutex<VecDeque<f32>>>,
+    /// Output device rate while playback is running, 0 otherwise
+    output_rate: AtomicU32,
+    /// Playback gain and the played samples, for ducking and echo cancellation
+    monitor: Arc<PlaybackMonitor>,
+    barge_in: BargeIn,
+    /// What the microphone currently hears, and whether someone has been talking
+    audio_class: Mutex<AudioClass>,
+    present: AtomicBool,
+    /// Speech time per minute from the VAD, kept across restarts
+    activity: Mutex<SpeechActivity>,
+    /// The last `RECENT_AUDIO_SECS` of captured audio at the engine rate
+    recent: Mutex<VecDeque<i16>>,
+    /// Mute checked in the capture callback, kept across restarts
+    privacy: Arc<PrivacyGate>,
+    assistant: Option<Arc<Assistant>>,
+    threads: Mutex<Vec<JoinHandle<()>>>,
+    recorder: Mutex<Option<Recording>>,
+}
+
+impl Resource for Shared {
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.events_ready.notify_all();
+        let threads = std::mem::take(&mut *self.threads.lock().unwrap());
+        lifecycle::join_threads(threads);
+        self.listening.store(false, Ordering::Relaxed);
+        self.dictating.store(false, Ordering::Relaxed);
+        self.playback.lock().unwrap().clear();
+        self.monitor.set_gain(1.0);
+        self.monitor.clear_earcons();
+        *self.audio_class.lock().unwrap() = AudioClass::Silence;
+        self.present.store(false, Ordering::Relaxed);
+        self.recent.lock().unwrap().clear();
+        // A recording stays open across restarts; just get it onto disk
+        self.record(|recorder| recorder.flush());
+    }
+}
+
+/// A started pipeline and the engines it feeds
+struct ZoneClaim {
+    shared: Weak<Shared>,
+    detector: Weak<Mutex<DetectorState>>,
+    recognizer: Weak<Mutex<Option<Box<dyn SttEngine>>>>,
+}
+
+/// Pipelines started in this process, checked so zones don't share engines
+static ZONES: Mutex<Vec<ZoneClaim>> = Mutex::new(Vec::new());
+
+/// Zones with a running pipeline, unnamed pipelines as None
+#[pyfunction]
+pub fn running_zones() -> Vec<Option<String>> {
+    ZONES
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|claim| claim.shared.upgrade())
+        .filter(|shared| shared.is_running())
+        .map(|shared| shared.zone.clone())
+        .collect()
+}
+
+impl Shared {
+    fn is_running(&self) -> bool {
+        self.threads.lock().unwrap().iter().any(|handle| !handle.is_finished())
+    }
+    
+    /// Sleep for `delay` unless the pipeline is stopped first; returns whether it is still running
+    fn wait_unless_stopped(&self, delay: Duration) -> bool {
+        panics::wait_unless_stopped(&self.stop, delay)
+    }
+    
+    /// Clear the poison a panicking thread left on the shared locks, so a restarted one can use them
+    fn clear_poison(&self) {
+        self.events.clear_poison();
+        self.dictation_request.clear_poison();
+        self.playback.clear_poison();
+        self.audio_class.clear_poison();
+        self.activity.clear_poison();
+        self.recent.clear_poison();
+        self.recorder.clear_poison();
+    }
+    
+    /// Event bus source for a component, e.g. "pipeline:kitchen"
+    fn source(&self, component: &str) -> String {
+        match &self.zone {
+            Some(zone) => format!("{}:{}", component, zone),
+            None => component.to_string(),
+        }
+    }
+    
+    /// Write to the session recording, if one is open
+    /// 
+    /// A write error, or the storage guard refusing more space, ends the recording.
+    fn record(&self, write: impl FnOnce(&mut SessionWriter) -> Result<(), SessionError>) {
+        // The guard needs the GIL, so it is asked without holding the recorder lock
+        let claim = self.recorder.lock().unwrap().as_ref().and_then(Recording::due_claim);
+        let claimed = claim.map(|storage| {
+            Python::with_gil(|py| storage.borrow_mut(py).admit(py, RECORDINGS_CATEGORY, RECORDING_CHUNK))
+        });
+        
+        let mut recorder = self.recorder.lock().unwrap();
+        if let Some(recording) = recorder.as_mut() {
+            let outcome = match claimed {
+                Some(Err(err)) => Err(err.into()),
+                Some(Ok(())) => {
+                    recording.reserved = recording.writer.bytes_written() + RECORDING_CHUNK;
+                    write(&mut recording.writer)
+                }
+                None => write(&mut recording.writer),
+            };
+            if let Err(err) = outcome {
+                tracing::error!("Session recording stopped: {}", err);
+                *recorder = None;
+            }
+        }
+    }
+    
+    /// Deliver an event to the callback, or queue it for `poll_event`
+    fn emit(&self, callback: &Option<PyObject>, mut event: PipelineEvent) {
+        event.zone = self.zone.clone();
+        events::post(Event::new("pipeline.event", &self.source("pipeline"), EventData::Pipeline(event.clone())));
+        match callback {
+            Some(callback) => Python::with_gil(|py| {
+                if let Err(err) = callback.call1(py, (event,)) {
+                    err.print(py);
+                }
+            }),
+            None => {
+                let mut events = self.events.lock().unwrap();
+                events.push_back(event);
+                while events.len() > EVENT_QUEUE_LIMIT {
+                    events.pop_front();
+                }
+                self.events_ready.notify_all();
+            }
+        }
+    }
+}
+
+/// Run `f` on the recognizer's active engine
+fn recognize<R>(engine: &SharedEngine, f: impl FnOnce(&mut dyn SttEngine) -> Result<R, String>) -> Result<R, String> {
+    let mut guard = engine.lock().unwrap();
+    let engine = guard
+        .as_deref_mut()
+        .ok_or_else(|| "Speech recognizer not initialized".to_string())?;
+    f(engine)
+}
+
+/// Report watched phrases newly heard in the transcript so far
+fn spot_keywords(keywords: Option<&SharedKeywordSpotter>, transcript: &str, env: &mut dyn FrameEnv) {
+    if let Some(keywords) = keywords {
+        for phrase in SpotterState::spot(keywords, transcript) {
+            env.emit(PipelineEvent {
+                text: Some(transcript.to_string()),
+                keyword: Some(phrase),
+                ..PipelineEvent::new("keyword")
+            });
+        }
+    }
+}
+
+/// Progress of the utterance being recognized, counted in frames
+struct Turn {
+    frames: u32,
+    silent_frames: u32,
+    speech_started: bool,
+    last_partial: String,
+    /// When the last frame of speech was captured, for end-of-speech latency
+    last_speech: Option<Instant>,
+}
+
+impl Turn {
+    fn new() -> Self {
+        Turn { frames: 0, silent_frames: 0, speech_started: false, last_partial: String::new(), last_speech: None }
+    }
+}
+
+/// Everything the frame logic reads or affects besides the audio and engines
+/// 
+/// Live capture reads the pipeline's shared state (recording each read when
+/// a session is being recorded); replay feeds the recorded reads back so
+/// the frames take the same path.
+pub trait FrameEnv {
+    /// Whether TTS audio is queued for playback
+    fn playing(&mut self) -> bool;
+    /// The user started talking over the assistant
+    fn interrupt_playback(&mut self);
+    /// Consume a pending `trigger()` request
+    fn take_trigger(&mut self) -> bool;
+    /// Consume a pending `start_dictation` or `stop_dictation` request
+    fn take_dictation_request(&mut self) -> Option<DictationRequest>;
+    /// Start a turn without the wake word if the follow-up window is open
+    fn begin_follow_up(&mut self, detector: &mut DetectorState) -> bool;
+    /// Current assistant phase, None without an assistant
+    fn assistant_phase(&mut self) -> Option<Phase>;
+    /// Assistant to report transitions to
+    fn assistant(&self) -> Option<&Assistant>;
+    fn set_listening(&mut self, listening: bool);
+    fn set_dictating(&mut self, dictating: bool);
+    fn vad_boundary(&mut self, kind: &'static str);
+    /// The stream changed between silence, speech, music and noise
+    fn audio_class_changed(&mut self, class: AudioClass);
+    fn presence_changed(&mut self, present: bool);
+    /// `seconds` of audio passed, with the VAD's verdict, outside playback
+    fn voice_activity(&mut self, speaking: bool, seconds: f32);
+    fn emit(&mut self, event: PipelineEvent);
+}
+
+/// Asked of the frame loop by `start_dictation` and `stop_dictation`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DictationRequest {
+    Start(DictationOptions),
+    Stop,
+}
+
+/// Encode a dictation request as a recorded read
+/// 
+/// Only the kind is recorded, so a replayed start uses the default options.
+fn dictation_code(request: Option<DictationRequest>) -> u8 {
+    match request {
+        None => 0,
+        Some(DictationRequest::Start(_)) => 1,
+        Some(DictationRequest::Stop) => 2,
+    }
+}
+
+fn dictation_from_code(code: u8) -> Option<DictationRequest> {
+    match code {
+        1 => Some(DictationRequest::Start(DictationOptions::default())),
+        2 => Some(DictationRequest::Stop),
+        _ => None,
+    }
+}
+
+/// Encode an assistant phase as a recorded read
+fn phase_code(phase: Option<Phase>) -> u8 {
+    match phase {
+        None => 0,
+        Some(Phase::Idle) => 1,
+        Some(Phase::WakeDetected) => 2,
+        Some(Phase::Listening) => 3,
+        Some(Phase::Thinking) => 4,
+        Some(Phase::Speaking) => 5,
+    }
+}
+
+fn phase_from_code(code: u8) -> Option<Phase> {
+    match code {
+        1 => Some(Phase::Idle),
+        2 => Some(Phase::WakeDetected),
+        3 => Some(Phase::Listening),
+        4 => Some(Phase::Thinking),
+        5 => Some(Phase::Speaking),
+        _ => None,
+    }
+}
+
+/// The running pipeline's environment
+struct LiveEnv<'a> {
+    shared: &'a Shared,
+    callback: &'a Option<PyObject>,
+}
+
+impl LiveEnv<'_> {
+    fn read(&self, value: u8) -> u8 {
+        self.shared.record(|recorder| {
+            recorder.record_read(value);
+            Ok(())
+        });
+        value
+    }
+}
+
+impl FrameEnv for LiveEnv<'_> {
+    fn playing(&mut self) -> bool {
+        let playing = !self.shared.playback.lock().unwrap().is_empty();
+        self.read(playing as u8) != 0
+    }
+    
+    fn interrupt_playback(&mut self) {
+        match self.shared.barge_in {
+            BargeIn::Stop => self.shared.playback.lock().unwrap().clear(),
+            BargeIn::Duck => self.shared.monitor.set_gain(DUCK_GAIN),
+        }
+    }
+    
+    fn take_trigger(&mut self) -> bool {
+        let triggered = self.shared.listen_request.swap(false, Ordering::Relaxed);
+        self.read(triggered as u8) != 0
+    }
+    
+    fn take_dictation_request(&mut self) -> Option<DictationRequest> {
+        let request = self.shared.dictation_request.lock().unwrap().take();
+        self.read(dictation_code(request));
+        request
+    }
+    
+    fn begin_follow_up(&mut self, detector: &mut DetectorState) -> bool {
+        let follow_up = detector.begin_follow_up();
+        self.read(follow_up as u8) != 0
+    }
+    
+    fn assistant_phase(&mut self) -> Option<Phase> {
+        let phase = self.shared.assistant.as_ref().map(|assistant| assistant.phase());
+        self.read(phase_code(phase));
+        phase
+    }
+    
+    fn assistant(&self) -> Option<&Assistant> {
+        self.shared.assistant.as_deref()
+    }
+    
+    fn set_listening(&mut self, listening: bool) {
+        self.shared.listening.store(listening, Ordering::Relaxed);
+        if !listening {
+            // Ducked playback resumes once the barge-in turn is over
+            self.shared.monitor.set_gain(1.0);
+        }
+    }
+    
+    fn set_dictating(&mut self, dictating: bool) {
+        self.shared.dictating.store(dictating, Ordering::Relaxed);
+    }
+    
+    fn vad_boundary(&mut self, kind: &'static str) {
+        events::post(Event::new(kind, &self.shared.source("vad"), EventData::None));
+        self.shared.record(|recorder| recorder.record_event(kind, None, None, None));
+    }
+    
+    fn audio_class_changed(&mut self, class: AudioClass) {
+        *self.shared.audio_class.lock().unwrap() = class;
+        events::post(Event::new("audio.class", &self.shared.source("pipeline"), EventData::Text(class.name().to_string())));
+    }
+    
+    fn presence_changed(&mut self, present: bool) {
+        self.shared.present.store(present, Ordering::Relaxed);
+        let state = if present { "present" } else { "absent" };
+        events::post(Event::new("presence.changed", &self.shared.source("pipeline"), EventData::Text(state.to_string())));
+    }
+    
+    fn voice_activity(&mut self, speaking: bool, seconds: f32) {
+        self.shared.activity.lock().unwrap().record(unix_time(), seconds, speaking);
+    }
+    
+    fn emit(&mut self, event: PipelineEvent) {
+        self.shared.record(|recorder| {
+            recorder.record_event(&event.kind, event.text.clone(), event.keyword.clone(), event.confidence)
+        });
+        self.shared.emit(self.callback, event);
+    }
+}
+
+/// Feeds a recording's reads back and collects the events the frames produce
+struct ReplayEnv {
+    header: SessionHeader,
+    frame: u64,
+    reads: VecDeque<u8>,
+    events: Vec<SessionEvent>,
+    input_mismatches: u64,
+    /// Whether the current frame has already been counted as a mismatch
+    mismatched: bool,
+}
+
+impl ReplayEnv {
+    fn begin_frame(&mut self, frame: u64, reads: &[u8]) {
+        self.frame = frame;
+        self.reads = reads.iter().copied().collect();
+        self.mismatched = false;
+    }
+    
+    /// Check that the frame consumed exactly the reads that were recorded
+    fn end_frame(&mut self) {
+        if !self.reads.is_empty() {
+            self.mismatch();
+        }
+    }
+    
+    fn mismatch(&mut self) {
+        if !self.mismatched {
+            self.mismatched = true;
+            self.input_mismatches += 1;
+        }
+    }
+    
+    fn read(&mut self) -> u8 {
+        match self.reads.pop_front() {
+            Some(value) => value,
+            None => {
+                self.mismatch();
+                0
+            }
+        }
+    }
+    
+    fn push(&mut self, kind: &str, text: Option<String>, keyword: Option<String>, confidence: Option<f32>) {
+        self.events.push(SessionEvent {
+            frame: self.frame,
+            time: self.frame as f64 * self.header.frame_length as f64 / self.header.sample_rate as f64,
+            kind: kind.to_string(),
+            text,
+            keyword,
+            confidence,
+        });
+    }
+}
+
+impl FrameEnv for ReplayEnv {
+    fn playing(&mut self) -> bool {
+        self.read() != 0
+    }
+    
+    fn interrupt_playback(&mut self) {}
+    
+    fn take_trigger(&mut self) -> bool {
+        self.read() != 0
+    }
+    
+    fn take_dictation_request(&mut self) -> Option<DictationRequest> {
+        dictation_from_code(self.read())
+    }
+    
+    fn begin_follow_up(&mut self, _detector: &mut DetectorState) -> bool {
+        self.read() != 0
+    }
+    
+    fn assistant_phase(&mut self) -> Option<Phase> {
+        phase_from_code(self.read())
+    }
+    
+    fn assistant(&self) -> Option<&Assistant> {
+        None
+    }
+    
+    fn set_listening(&mut self, _listening: bool) {}
+    
+    fn set_dictating(&mut self, _dictating: bool) {}
+    
+    fn vad_boundary(&mut self, kind: &'static str) {
+        self.push(kind, None, None, None);
+    }
+    
+    fn audio_class_changed(&mut self, _class: AudioClass) {}
+    
+    fn presence_changed(&mut self, _present: bool) {}
+    
+    fn voice_activity(&mut self, _speaking: bool, _seconds: f32) {}
+    
+    fn emit(&mut self, event: PipelineEvent) {
+        self.push(&event.kind, event.text, event.keyword, event.confidence);
+    }
+}
+
+/// Wake word detection, VAD and turn tracking applied frame by frame
+pub struct FrameProcessor {
+    detector: Arc<Mutex<DetectorState>>,
+    recognizer: SharedEngine,
+    pub frame_length: usize,
+    pub sample_rate: u32,
+    silence_frames: u32,
+    no_speech_frames: u32,
+    max_frames: u32,
+    vad: VoiceActivityDetector,
+    classifier: AudioClassifier,
+    presence: PresenceTracker,
+    /// Trigger phrases watched for in the partial transcripts
+    keywords: Option<SharedKeywordSpotter>,
+    turn: Option<Turn>,
+    /// Dictation in progress; while it runs, the turn stays open across pauses
+    dictation: Option<Dictation>,
+    /// Record stage latencies; off for replay, where timing means nothing
+    observe: bool,
+}
+
+impl FrameProcessor {
+    pub fn new(
+        detector: Arc<Mutex<DetectorState>>,
+        recognizer: SharedEngine,
+        endpointing: Endpointing,
+        observe: bool,
+    ) -> Result<Self, String> {
+        let (frame_length, sample_rate) = detector.lock().unwrap().frame_format()?;
+        let frames_for = |seconds: f32| (seconds * sample_rate as f32 / frame_length as f32).ceil() as u32;
+        
+        Ok(FrameProcessor {
+            detector,
+            recognizer,
+            frame_length,
+            sample_rate,
+            silence_frames: frames_for(endpointing.silence_timeout),
+            no_speech_frames: frames_for(endpointing.no_speech_timeout),
+            max_frames: frames_for(endpointing.max_utterance),
+            vad: VoiceActivityDetector::new(endpointing.vad_threshold_db),
+            classifier: AudioClassifier::new(sample_rate),
+            presence: PresenceTracker::new(PRESENCE_TIMEOUT),
+            keywords: None,
+            turn: None,
+            dictation: None,
+            observe,
+        })
+    }
+    
+    pub fn set_keyword_spotter(&mut self, keywords: Option<SharedKeywordSpotter>) {
+        self.keywords = keywords;
+    }
+    
+    /// Process one frame captured at `captured`
+    pub fn process(&mut self, frame: &[i16], captured: Instant, env: &mut dyn FrameEnv) -> Result<(), String> {
+        let was_speaking = self.vad.is_speech();
+        let speaking = self.vad.process(frame);
+        if speaking != was_speaking {
+            env.vad_boundary(if speaking { "vad.speech_start" } else { "vad.speech_end" });
+        }
+        let playing = env.playing();
+        
+        let previous_class = self.classifier.class();
+        let class = self.classifier.process(frame);
+        if class != previous_class {
+            env.audio_class_changed(class);
+        }
+        // The assistant's own voice doesn't mean someone is in the room
+        if !playing {
+            let seconds = frame.len() as f32 / self.sample_rate as f32;
+            if let Some(present) = self.presence.update(class, seconds) {
+                env.presence_changed(present);
+            }
+            env.voice_activity(speaking, seconds);
+        }
+        
+        match env.take_dictation_request() {
+            Some(DictationRequest::Start(options)) => self.begin_dictation(options, env)?,
+            Some(DictationRequest::Stop) => {
+                if let Some(dictation) = self.dictation.as_mut() {
+                    dictation.stopping = true;
+                }
+            }
+            None => {}
+        }
+        if self.dictation.is_some() {
+            return self.dictate(frame, speaking, env);
+        }
+        
+        // The assistant can open a turn itself (follow-up window or a Python `wake`)
+        let assistant_phase = env.assistant_phase();
+        if self.turn.is_none() {
+            let (detection, follow_up) = {
+                let mut state = self.detector.lock().unwrap();
+                state.set_playback_state(if playing { "playing" } else { "idle" });
+                let detection = state.process_frame(frame)?;
+                if self.observe {
+                    metrics::observe_latency("wake_decision", captured.elapsed());
+                }
+                // Vocals in music would otherwise open follow-up turns; the wake word still works
+                let music = class == AudioClass::Music;
+                let follow_up = detection.is_none() && speaking && !music && env.begin_follow_up(&mut state);
+                (detection, follow_up)
+            };
+            
+            let mut event = PipelineEvent::new("wake");
+            let reason = if let Some(detection) = detection {
+                // The user is talking over the assistant
+                env.interrupt_playback();
+                event.keyword = Some(detection.keyword);
+                event.confidence = detection.confidence;
+                event.context = detection.context;
+                "wake_word"
+            } else if follow_up {
+                "follow_up"
+            } else if env.take_trigger() {
+                "trigger"
+            } else if assistant_phase == Some(Phase::WakeDetected) {
+                "assistant"
+            } else {
+                return Ok(());
+            };
+            
+            if let Err(err) = recognize(&self.recognizer, |engine| {
+                engine.reset();
+                Ok(())
+            }) {
+                env.emit(PipelineEvent::error(err));
+                return Ok(());
+            }
+            env.set_listening(true);
+            tracing::debug!(reason, "Turn started");
+            if let Some(assistant) = env.assistant() {
+                assistant.wake(reason);
+            }
+            env.emit(event);
+            if let Some(keywords) = &self.keywords {
+                keywords.lock().unwrap().reset();
+            }
+            self.turn = Some(Turn::new());
+            // Follow-up speech is already under way, so its first frame belongs to the turn
+            if !follow_up {
+                return Ok(());
+            }
+        }
+        let current = match self.turn.as_mut() {
+            Some(current) => current,
+            None => return Ok(()),
+        };
+        
+        current.frames += 1;
+        if speaking {
+            if !current.speech_started {
+                current.speech_started = true;
+                if let Some(assistant) = env.assistant() {
+                    assistant.speech_started();
+                }
+                env.emit(PipelineEvent::new("speech_start"));
+            }
+            current.silent_frames = 0;
+            current.last_speech = Some(captured);
+        } else if current.speech_started {
+            current.silent_frames += 1;
+        }
+        
+        let accepted = recognize(&self.recognizer, |engine| engine.accept(frame));
+        let ended = current.speech_started && current.silent_frames >= self.silence_frames;
+        let timed_out = !current.speech_started && current.frames >= self.no_speech_frames;
+        // The assistant's own timeouts (listen timeout, max utterance) also end the turn
+        let assistant_done = matches!(env.assistant_phase(), Some(Phase::Idle | Phase::Thinking));
+        
+        let outcome = match accepted {
+            Ok(Some(result)) if result.is_final => Some(Ok(Some(result))),
+            Ok(Some(result)) => {
+                if !result.text.is_empty() && result.text != current.last_partial {
+                    current.last_partial = result.text.clone();
+                    spot_keywords(self.keywords.as_ref(), &result.text, env);
+                    env.emit(PipelineEvent { text: Some(result.text), ..PipelineEvent::new("partial") });
+                }
+                None
+            }
+            Ok(None) => None,
+            Err(err) => Some(Err(err)),
+        };
+        let outcome = match outcome {
+            Some(outcome) => outcome,
+            None if ended || timed_out || assistant_done || current.frames >= self.max_frames => {
+                recognize(&self.recognizer, |engine| engine.finish())
+            }
+            None => return Ok(()),
+        };
+        
+        if let Ok(Some(result)) = &outcome {
+            // Phrases the partials missed, or engines without partial results
+            if result.rejection.is_none() {
+                spot_keywords(self.keywords.as_ref(), &result.text, env);
+            }
+        }
+        let event = match outcome {
+            // Heard something, but too unsure to act on
+            Ok(Some(result)) if result.rejection.is_some() => PipelineEvent {
+                text: Some(result.text.clone()),
+                confidence: result.confidence,
+                result: Some(result),
+                ..PipelineEvent::new("unknown")
+            },
+            Ok(Some(result)) if !result.text.trim().is_empty() => PipelineEvent {
+                text: Some(result.text.clone()),
+                confidence: result.confidence,
+                result: Some(result),
+                ..PipelineEvent::new("final")
+            },
+            Ok(_) => PipelineEvent::new("timeout"),
+            Err(err) => PipelineEvent::error(err),
+        };
+        if let (Some(last_speech), "final", true) = (current.last_speech, event.kind.as_str(), self.observe) {
+            metrics::observe_latency("final_text", last_speech.elapsed());
+        }
+        tracing::debug!(outcome = %event.kind, frames = current.frames, "Turn ended");
+        self.turn = None;
+        self.vad.reset();
+        self.detector.lock().unwrap().end_interaction();
+        env.set_listening(false);
+        if let Some(assistant) = env.assistant() {
+            match event.kind.as_str() {
+                "final" => assistant.speech_ended("final"),
+                kind => assistant.cancel(kind),
+            };
+        }
+        env.emit(event);
+        Ok(())
+    }
+    
+    /// Drop the turn in progress, if any, without waiting for a result
+    /// 
+    /// The recognizer is reset rather than finished, so nothing heard so
+    /// far is turned into a transcript.
+    pub fn abandon_turn(&mut self, reason: &str, env: &mut dyn FrameEnv) {
+        // Dictation ends with what was stitched before the turn was dropped
+        if self.dictation.is_some() {
+            self.end_dictation(env);
+        }
+        if self.turn.take().is_none() {
+            return;
+        }
+        if let Err(err) = recognize(&self.recognizer, |engine| {
+            engine.reset();
+            Ok(())
+        }) {
+            tracing::warn!("Failed to reset recognizer: {}", err);
+        }
+        tracing::debug!(reason, "Turn abandoned");
+        self.vad.reset();
+        self.detector.lock().unwrap().end_interaction();
+        env.set_listening(false);
+        if let Some(assistant) = env.assistant() {
+            assistant.cancel(reason);
+        }
+    }
+    
+    /// Start dictating, carrying on the turn in progress if there is one
+    fn begin_dictation(&mut self, options: DictationOptions, env: &mut dyn FrameEnv) -> Result<(), String> {
+        if self.dictation.is_some() {
+            return Ok(());
+        }
+        if self.turn.is_none() {
+            recognize(&self.recognizer, |engine| {
+                engine.reset();
+                Ok(())
+            })?;
+            if let Some(keywords) = &self.keywords {
+                keywords.lock().unwrap().reset();
+            }
+            self.turn = Some(Turn::new());
+            env.set_listening(true);
+        }
+        // Dictation doesn't go through the assistant, whose timeouts would end it early
+        if let Some(assistant) = env.assistant() {
+            assistant.cancel("dictation");
+        }
+        tracing::debug!(paragraph_pause = options.paragraph_pause, idle_timeout = options.idle_timeout, "Dictation started");
+        self.dictation = Some(Dictation::new(options, self.frame_length, self.sample_rate));
+        env.set_dictating(true);
+        env.emit(PipelineEvent::new("dictation_start"));
+        Ok(())
+    }
+    
+    /// Process a frame while dictating
+    /// 
+    /// Each pause the VAD ends, or `max_utterance` of speech, closes a
+    /// segment: the recognizer's result is stitched onto the transcript and
+    /// the recognizer starts over for the next one. Dictation ends on
+    /// "stop dictation", `stop_dictation` or the idle timeout.
+    fn dictate(&mut self, frame: &[i16], speaking: bool, env: &mut dyn FrameEnv) -> Result<(), String> {
+        let current = self.turn.get_or_insert_with(Turn::new);
+        let dictation = match self.dictation.as_mut() {
+            Some(dictation) => dictation,
+            None => return Ok(()),
+        };
+        
+        current.frames += 1;
+        if speaking {
+            if !current.speech_started {
+                current.speech_started = true;
+                env.emit(PipelineEvent::new("speech_start"));
+            }
+            current.silent_frames = 0;
+        } else if current.speech_started {
+            current.silent_frames += 1;
+        }
+        dictation.tick(current.speech_started);
+        
+        let accepted = recognize(&self.recognizer, |engine| engine.accept(frame));
+        let segment_ended = current.speech_started
+            && (current.silent_frames >= self.silence_frames || current.frames >= self.max_frames);
+        let idle = !current.speech_started && current.frames >= dictation.idle_frames();
+        
+        let outcome = match accepted {
+            Ok(Some(result)) if result.is_final => Ok(Some(result)),
+            Ok(Some(result)) => {
+                if !result.text.is_empty() && result.text != current.last_partial {
+                    current.last_partial = result.text.clone();
+                    env.emit(PipelineEvent { text: Some(result.text), ..PipelineEvent::new("partial") });
+                }
+                Ok(None)
+            }
+            Ok(None) => Ok(None),
+            Err(err) => Err(err),
+        };
+        let outcome = match outcome {
+            Ok(None) if segment_ended || (dictation.stopping && current.speech_started) => {
+                recognize(&self.recognizer, |engine| engine.finish())
+            }
+            Ok(None) if dictation.stopping || idle => {
+                self.end_dictation(env);
+                return Ok(());
+            }
+            Ok(None) => return Ok(()),
+            outcome => outcome,
+        };
+        
+        let trailing_silence = current.silent_frames;
+        self.turn = Some(Turn::new());
+        match outcome {
+            Ok(Some(result)) if result.rejection.is_none() && !result.text.trim().is_empty() => {
+                if let Some(segment) = dictation.finish_segment(&result.text, result.confidence, trailing_silence) {
+                    env.emit(PipelineEvent {
+                        text: Some(segment.text),
+                        confidence: result.confidence,
+                        result: Some(result),
+                        dictation: Some(dictation.transcript.clone()),
+                        ..PipelineEvent::new("dictation")
+                    });
+                }
+            }
+            // Nothing usable in this stretch; the next one starts afresh
+            Ok(_) => dictation.skip_segment(),
+            Err(err) => {
+                dictation.skip_segment();
+                env.emit(PipelineEvent::error(err));
+            }
+        }
+        recognize(&self.recognizer, |engine| {
+            engine.reset();
+            Ok(())
+        })?;
+        if dictation.stopping {
+            self.end_dictation(env);
+        }
+        Ok(())
+    }
+    
+    /// Stop dictating and report the whole transcript
+    fn end_dictation(&mut self, env: &mut dyn FrameEnv) {
+        let dictation = match self.dictation.take() {
+            Some(dictation) => dictation,
+            None => return,
+        };
+        tracing::debug!(segments = dictation.transcript.segments.len(), "Dictation ended");
+        self.turn = None;
+        self.vad.reset();
+        if let Err(err) = recognize(&self.recognizer, |engine| {
+            engine.reset();
+            Ok(())
+        }) {
+            tracing::warn!("Failed to reset recognizer: {}", err);
+        }
+        self.detector.lock().unwrap().end_interaction();
+        env.set_listening(false);
+        env.set_dictating(false);
+        env.emit(PipelineEvent {
+            text: Some(dictation.transcript.text.clone()),
+            dictation: Some(dictation.transcript),
+            ..PipelineEvent::new("dictation_end")
+        });
+    }
+}
+
+/// Removes the pipeline's own playback from captured audio
+/// 
+/// The output callback keeps every sample it plays; these are resampled to
+/// the engine rate and lined up with each captured block. Played audio
+/// further ahead than `ECHO_MAX_LEAD` is dropped, which absorbs the drift
+/// between the input and output device clocks.
+struct EchoPath {
+    canceller: EchoCanceller,
+    engine_rate: u32,
+    output_rate: u32,
+    resampler: Option<MonoResampler>,
+    reference: VecDeque<f32>,
+    max_lead: usize,
+}
+
+impl EchoPath {
+    fn new(engine_rate: u32) -> Self {
+        EchoPath {
+            canceller: EchoCanceller::new((ECHO_TAIL * engine_rate as f32) as usize),
+            engine_rate,
+            output_rate: 0,
+            resampler: None,
+            reference: VecDeque::new(),
+            max_lead: (ECHO_MAX_LEAD * engine_rate as f32) as usize,
+        }
+    }
+    
+    /// Cancel the echo of recent playback from a block captured at the engine rate
+    fn process(&mut self, shared: &Shared, block: &mut [f32]) -> Result<(), String> {
+        let output_rate = shared.output_rate.load(Ordering::Relaxed);
+        if output_rate == 0 {
+            return Ok(());
+        }
+        if output_rate != self.output_rate {
+            // Playback (re)started on a device with a different rate
+            self.resampler = Some(MonoResampler::new(output_rate, self.engine_rate).map_err(|err| err.to_string())?);
+            self.output_rate = output_rate;
+            self.reference.clear();
+            self.canceller.reset();
+        }
+        
+        let played = shared.monitor.take_reference();
+        if let Some(resampler) = self.resampler.as_mut() {
+            self.reference.extend(resampler.push(&played).map_err(|err| err.to_string())?);
+        }
+        let excess = self.reference.len().saturating_sub(block.len() + self.max_lead);
+        self.reference.drain(..excess);
+        let available = block.len().min(self.reference.len());
+        let reference: Vec<f32> = self.reference.drain(..available).collect();
+        self.canceller.process(block, &reference);
+        Ok(())
+    }
+}
+
+fn privacy_state(muted: bool) -> &'static str {
+    if muted {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Check that a capture graph takes and returns audio at the engine rate
+fn check_capture_graph(graph: &ProcessingGraph, engine_rate: u32) -> Result<(), String> {
+    if graph.input_rate() != engine_rate || graph.output_rate() != engine_rate {
+        return Err(format!(
+            "Capture graph must take and return audio at the engine rate ({} Hz), not {} Hz in and {} Hz out",
+            engine_rate,
+            graph.input_rate(),
+            graph.output_rate()
+        ));
+    }
+    Ok(())
+}
+
+/// Capture audio and run wake word detection, VAD and recognition until stopped
+#[tracing::instrument(name = "pipeline", skip_all, fields(device = ?device_name, zone = ?shared.zone))]
+#[allow(clippy::too_many_arguments)]
+fn run_pipeline(
+    shared: Arc<Shared>,
+    detector: Arc<Mutex<DetectorState>>,
+    recognizer: SharedEngine,
+    device_name: Option<String>,
+    stall: StallPolicy,
+    endpointing: Endpointing,
+    echo_cancellation: bool,
+    dereverberation: bool,
+    capture_graph: Option<SharedGraph>,
+    keywords: Option<SharedKeywordSpotter>,
+    sound_frames: Option<SyncSender<Vec<i16>>>,
+    callback: Option<PyObject>,
+) -> Result<(), String> {
+    let mut processor = FrameProcessor::new(detector, recognizer, endpointing, true)?;
+    processor.set_keyword_spotter(keywords);
+    let frame_length = processor.frame_length;
+    let mut capture = CaptureSource::open_gated(device_name, processor.sample_rate, stall, Some(shared.privacy.clone()))
+        .map_err(|err| err.to_string())?;
+    let mut echo = if echo_cancellation { Some(EchoPath::new(processor.sample_rate)) } else { None };
+    let mut dereverb = if dereverberation { Some(Dereverberator::new(processor.sample_rate)) } else { None };
+    shared.monitor.clear_reference();
+    let mut pending: Vec<i16> = Vec::with_capacity(frame_length * 2);
+    let mut env = LiveEnv { shared: &shared, callback: &callback };
+    let recent_limit = (RECENT_AUDIO_SECS * processor.sample_rate as f32) as usize;
+    let mut muted = false;
+    
+    while !shared.stop.load(Ordering::Relaxed) {
+        if shared.privacy.is_muted() != muted {
+            muted = !muted;
+            if muted {
+                // Nothing heard before the mute may finish as a transcript or stay buffered
+                processor.abandon_turn("privacy", &mut env);
+                shared.listen_request.store(false, Ordering::Relaxed);
+                pending.clear();
+                shared.recent.lock().unwrap().clear();
+            }
+            tracing::info!(muted, "Privacy mode changed");
+            env.emit(PipelineEvent { text: Some(privacy_state(muted).to_string()), ..PipelineEvent::new("privacy") });
+        }
+        
+        let received = capture.recv(Duration::from_millis(100)).map_err(|err| err.to_string())?;
+        let mut resampled = match received {
+            // Blocks queued just before the mute are dropped too
+            Captured::Audio(_) if shared.privacy.is_muted() => continue,
+            Captured::Audio(samples) => samples,
+            Captured::Idle => continue,
+            Captured::Closed => break,
+        };
+        
+        let captured = Instant::now();
+        let cpu_start = metrics::thread_cpu_time();
+        if let Some(echo) = echo.as_mut() {
+            echo.process(&shared, &mut resampled)?;
+        }
+        if let Some(dereverb) = dereverb.as_mut() {
+            dereverb.process(&mut resampled);
+        }
+        if let Some(graph) = &capture_graph {
+            resampled = graph.lock().unwrap().process(resampled)?;
+        }
+        pending.extend(resampled.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+        
+        while pending.len() >= frame_length {
+            let frame: Vec<i16> = pending.drain(..frame_length).collect();
+            if let Some(sender) = &sound_frames {
+                // The classifier is slower than a frame; skip audio rather than hold up wake detection
+                let _ = sender.try_send(frame.clone());
+            }
+            processor.process(&frame, captured, &mut env)?;
+            shared.record(|recorder| recorder.record_frame(&frame));
+            
+            let mut recent = shared.recent.lock().unwrap();
+            recent.extend(frame.iter().copied());
+            let excess = recent.len().saturating_sub(recent_limit);
+            recent.drain(..excess);
+        }
+        metrics::set_buffer("capture_pending", pending.len());
+        metrics::add_cpu_time("pipeline", metrics::thread_cpu_time().saturating_sub(cpu_start));
+    }
+    
+    drop(capture);
+    Ok(())
+}
+
+/// Classify captured frames for sound events, beside the wake word path
+fn run_sound_events(
+    shared: Arc<Shared>,
+    detector: SharedSoundDetector,
+    frames: &Receiver<Vec<i16>>,
+    sample_rate: u32,
+    callback: Option<PyObject>,
+) -> Result<(), String> {
+    let mut resampler = MonoResampler::new(sample_rate, SOUND_SAMPLE_RATE).map_err(|err| err.to_string())?;
+    detector.lock().unwrap().reset();
+    
+    while !shared.stop.load(Ordering::Relaxed) {
+        let frame = match frames.recv_timeout(STREAM_POLL) {
+            Ok(frame) => frame,
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let audio: Vec<f32> = frame.iter().map(|&s| s as f32 / 32768.0).collect();
+        let audio = resampler.push(&audio).map_err(|err| err.to_string())?;
+        let sounds = detector.lock().unwrap().process(&audio)?;
+        for sound in sounds {
+            tracing::info!(label = %sound.label, confidence = sound.confidence, "Sound recognized");
+            shared.emit(
+                &callback,
+                PipelineEvent { text: Some(sound.label), confidence: Some(sound.confidence), ..PipelineEvent::new("sound") },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Keep an output stream open, playing whatever is queued
+/// 
+/// Returns an error if the output callback panics, so the supervisor
+/// reopens the stream.
+fn run_playback(shared: Arc<Shared>, device_name: Option<String>) -> Result<(), String> {
+    let (stream, sample_rate) =
+        open_mono_playback(device_name.as_deref(), shared.playback.clone(), Some(shared.monitor.clone()))
+            .map_err(|err| err.to_string())?;
+    shared.output_rate.store(sample_rate, Ordering::Relaxed);
+    
+    let mut was_playing = false;
+    let mut outcome = Ok(());
+    while !shared.stop.load(Ordering::Relaxed) {
+        thread::sleep(PLAYBACK_POLL);
+        if shared.monitor.callback_failed() {
+            outcome = Err("Playback callback panicked".to_string());
+            break;
+        }
+        let queued = shared.playback.lock().unwrap().len();
+        metrics::set_buffer("playback_queue", queued);
+        let playing = queued > 0;
+        if let Some(assistant) = &shared.assistant {
+            if playing && !was_playing {
+                assistant.speaking_started();
+            } else if was_playing && !playing {
+                assistant.speaking_finished();
+            }
+        }
+        was_playing = playing;
+    }
+    
+    shared.output_rate.store(0, Ordering::Relaxed);
+    shared.playback.lock().unwrap().clear();
+    drop(stream);
+    outcome
+}
+
+/// Async iterator over queued pipeline events, returned by `VoicePipeline.events`
+/// 
+/// Each `__anext__` waits on a worker thread for the next event; iteration
+/// ends once the pipeline is stopped and the queue is empty.
+#[pyclass]
+pub struct PipelineEventStream {
+    shared: Arc<Shared>,
+}
+
+#[pymethods]
+impl PipelineEventStream {
+    fn __aiter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+    
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Option<&'py PyAny>> {
+        let shared = self.shared.clone();
+        let next = pyo3_asyncio::tokio::future_into_py(py, async move {
+            let event = tokio::task::spawn_blocking(move || {
+                let mut events = shared.events.lock().unwrap();
+                loop {
+                    if let Some(event) = events.pop_front() {
+                        return Some(event);
+                    }
+                    if shared.stop.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    events = shared.events_ready.wait_timeout(events, STREAM_POLL).unwrap().0;
+                }
+            })
+            .await
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+            event.ok_or_else(|| PyStopAsyncIteration::new_err(()))
+        })?;
+        Ok(Some(next))
+    }
+}
+
+/// End-to-end voice pipeline running entirely in Rust threads
+/// 
+/// Captures audio, resamples it to the wake word engine's rate, runs wake
+/// word detection and, once a keyword fires, feeds the recognizer until the
+/// VAD sees `silence_timeout` seconds of silence after speech (or nothing is
+/// said within `no_speech_timeout`). Only high-level `PipelineEvent`s cross
+/// into Python. TTS output is played on `output_device`; the wake word
+/// stays live while it plays, with the playback cancelled from the
+/// microphone signal, and stops or ducks it when heard. Speech during the
+/// detector's follow-up window starts a turn without the wake word.
+/// 
+/// The detector and recognizer must be initialized, run at the same sample
+/// rate, and not be used elsewhere while the pipeline runs.
+/// 
+/// Several pipelines can run in one process, one per room or `zone`, each
+/// with its own devices, detector and recognizer. Recognizers and voices
+/// loaded from the same files share one copy of the model. Events carry
+/// the zone, and bus events come from e.g. "pipeline:kitchen".
+#[pyclass]
+pub struct VoicePipeline {
+    detector: Arc<Mutex<DetectorState>>,
+    /// The detector object, for applying calibrated sensitivities
+    wake_word: Py<WakeWordDetector>,
+    recognizer: SharedEngine,
+    input_device: Option<String>,
+    output_device: Option<String>,
+    playback: bool,
+    echo_cancellation: bool,
+    /// Remove reverberation from the captured audio before detection
+    dereverberation: bool,
+    /// Nodes run on the captured audio after echo cancellation and de-reverberation
+    capture_graph: Option<SharedGraph>,
+    /// Loudness `play` normalizes to, in LUFS
+    loudness_target: Option<f32>,
+    endpointing: Endpointing,
+    stall: StallPolicy,
+    /// Sound event classifier run on the captured audio
+    sound: Option<SharedSoundDetector>,
+    /// Trigger phrases spotted in the partial transcripts
+    keywords: Option<SharedKeywordSpotter>,
+    /// Decoded feedback sounds by name, for `play_earcon`
+    earcons: HashMap<String, Earcon>,
+    /// Priority and CPU affinity of the threads by stage
+    thread_policies: HashMap<String, ThreadPolicy>,
+    shared: Arc<Shared>,
+}
+
+impl VoicePipeline {
+    fn running(&self) -> bool {
+        self.shared.is_running()
+    }
+    
+    /// Make sure no other running pipeline uses this one's zone, detector or recognizer
+    /// 
+    /// Two pipelines feeding the same wake word detector or recognizer
+    /// would interleave their audio in it, so each zone needs its own
+    /// (they still share the loaded models).
+    fn claim_zone(&self) -> Result<(), String> {
+        let mut zones = ZONES.lock().unwrap();
+        zones.retain(|claim| claim.shared.upgrade().map_or(false, |shared| shared.is_running()));
+        for claim in zones.iter() {
+            let other = match claim.shared.upgrade() {
+                Some(other) if !Arc::ptr_eq(&other, &self.shared) => other,
+                _ => continue,
+            };
+            let name = other.zone.as_deref().unwrap_or("(unnamed)");
+            if self.shared.zone.is_some() && other.zone == self.shared.zone {
+                return Err(format!("A pipeline for zone '{}' is already running", name));
+            }
+            if claim.detector.upgrade().map_or(false, |detector| Arc::ptr_eq(&detector, &self.detector)) {
+                return Err(format!("The wake word detector is already used by the pipeline for zone {}", name));
+            }
+            if claim.recognizer.upgrade().map_or(false, |recognizer| Arc::ptr_eq(&recognizer, &self.recognizer)) {
+                return Err(format!("The speech recognizer is already used by the pipeline for zone {}", name));
+            }
+        }
+        zones.push(ZoneClaim {
+            shared: Arc::downgrade(&self.shared),
+            detector: Arc::downgrade(&self.detector),
+            recognizer: Arc::downgrade(&self.recognizer),
+        });
+        Ok(())
+    }
+    
+    fn stop_threads(&self, py: Python) {
+        // Threads may need the GIL to finish a callback
+        let shared = &self.shared;
+        py.allow_threads(|| shared.shutdown());
+    }
+    
+    fn spawn(
+        &self,
+        name: &str,
+        stage: &str,
+        callback: Option<PyObject>,
+        recover: impl Fn() + Send + 'static,
+        body: impl Fn() -> Result<(), String> + Send + 'static,
+    ) -> PyResult<()> {
+        let shared = self.shared.clone();
+        let thread_name = name.to_string();
+        let policy = self.thread_policies.get(stage).cloned();
+        let handle = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                if let Some(policy) = policy {
+                    policy.apply_to_current();
+                }
+                // Errors and panics are reported, then the body is restarted with a backoff
+                panics::supervise(&thread_name, &body, |err, restart| {
+                    // Clear the poison at once, so Python calls on the engines don't panic meanwhile
+                    shared.clear_poison();
+                    recover();
+                    shared.emit(&callback, PipelineEvent::error(err));
+                    restart.map_or(false, |delay| shared.wait_unless_stopped(delay))
+                });
+            })
+            .map_err(|err| PyValueError::new_err(format!("Failed to start {} thread: {}", name, err)))?;
+        self.shared.threads.lock().unwrap().push(handle);
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl VoicePipeline {
+    /// Create a pipeline from an initialized detector and recognizer
+    /// 
+    /// Set `playback` to False to skip opening an output device. With an
+    /// `assistant`, the pipeline drives its state machine: turns move it
+    /// through wake_detected, listening and thinking, playback through
+    /// speaking, and its timeouts end the current turn. A follow-up window
+    /// or `assistant.wake()` opens a turn without the wake word. A `config`
+    /// (`JennaConfig`) replaces the device, playback, endpointing, stall,
+    /// dereverberation and zone arguments, its `capture_graph` is run on
+    /// the captured audio, and its `thread_priorities` and `thread_cpus`
+    /// are applied as with `set_thread_policy`.
+    /// 
+    /// If the capture callback delivers nothing for `stall_timeout` seconds
+    /// (0 disables the check), an "audio.stalled" event is posted on the
+    /// event bus and, with `restart_on_stall`, the stream is reopened.
+    /// 
+    /// The wake word is detected during playback too (barge-in). With
+    /// `echo_cancellation`, the pipeline's own playback is subtracted from
+    /// the captured audio first so the assistant can't wake itself and the
+    /// user is heard over it. `barge_in` decides what the wake word does to
+    /// the playback: "stop" drops it, "duck" lowers it by 20 dB until the
+    /// turn ends.
+    /// 
+    /// Everything played is normalized to `loudness_target` LUFS (EBU R128
+    /// by default; None plays audio as it is), so speech, chimes and media
+    /// come out equally loud.
+    /// 
+    /// With `dereverberation`, late room reverberation is removed from the
+    /// captured audio (after echo cancellation) for better wake word and
+    /// recognition accuracy with the microphone across the room. It adds
+    /// 32 ms of latency and takes noticeable CPU, so it is off by default.
+    /// 
+    /// `zone` names the room the pipeline serves; `start` fails if another
+    /// running pipeline has the same zone or uses the same detector or
+    /// recognizer.
+    #[new]
+    #[pyo3(signature = (
+        detector,
+        recognizer,
+        input_device=None,
+        output_device=None,
+        playback=true,
+        vad_threshold_db=12.0,
+        silence_timeout=0.8,
+        no_speech_timeout=5.0,
+        max_utterance=15.0,
+        assistant=None,
+        config=None,
+        stall_timeout=2.0,
+        restart_on_stall=true,
+        barge_in="stop",
+        echo_cancellation=true,
+        loudness_target=Some(EBU_R128_TARGET),
+        dereverberation=false,
+        zone=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        detector: PyRef<WakeWordDetector>,
+        recognizer: PyRef<SpeechRecognizer>,
+        input_device: Option<String>,
+        output_device: Option<String>,
+        playback: bool,
+        vad_threshold_db: f32,
+        silence_timeout: f32,
+        no_speech_timeout: f32,
+        max_utterance: f32,
+        assistant: Option<PyRef<AssistantState>>,
+        config: Option<PyRef<JennaConfig>>,
+        stall_timeout: f32,
+        restart_on_stall: bool,
+        barge_in: &str,
+        echo_cancellation: bool,
+        loudness_target: Option<f32>,
+        dereverberation: bool,
+        zone: Option<String>,
+    ) -> PyResult<Self> {
+        // A config replaces the device and endpointing arguments
+        let (input_device, output_device, playback, vad_threshold_db, silence_timeout, no_speech_timeout, max_utterance) =
+            match &config {
+                Some(config) => {
+                    config.check().map_err(PyValueError::new_err)?;
+                    (
+                        config.input_device.clone(),
+                        config.output_device.clone(),
+                        config.playback,
+                        config.vad_threshold_db,
+                        config.silence_timeout,
+                        config.no_speech_timeout,
+                        config.max_utterance,
+                    )
+                }
+                None => (
+                    input_device,
+                    output_device,
+                    playback,
+                    vad_threshold_db,
+                    silence_timeout,
+                    no_speech_timeout,
+                    max_utterance,
+                ),
+            };
+        let stall = match &config {
+            Some(config) => StallPolicy::new(config.stall_timeout, config.restart_on_stall),
+            None => StallPolicy::new(stall_timeout, restart_on_stall),
+        }
+        .map_err(PyValueError::new_err)?;
+        let barge_in = BargeIn::from_name(barge_in).map_err(PyValueError::new_err)?;
+        let dereverberation = config.as_ref().map_or(dereverberation, |config| config.dereverberation);
+        let thread_policies = match &config {
+            Some(config) => config.thread_policies().map_err(PyValueError::new_err)?,
+            None => HashMap::new(),
+        };
+        let zone = config.as_ref().map_or(zone, |config| config.zone.clone());
+        let loudness_target = match &config {
+            Some(config) if config.playback_loudness == 0.0 => None,
+            Some(config) => Some(config.playback_loudness),
+            None => loudness_target,
+        };
+        if let Some(target) = loudness_target {
+            check_loudness_target(target).map_err(PyValueError::new_err)?;
+        }
+        let state = detector.shared_state();
+        let wake_word: Py<WakeWordDetector> = detector.into();
+        let (_, engine_rate) = state.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+        let (engine, recognizer_rate) = recognizer.shared_engine()?;
+        if engine_rate != recognizer_rate {
+            return Err(PyValueError::new_err(format!(
+                "Recognizer sample rate ({} Hz) must match the wake word engine ({} Hz)",
+                recognizer_rate, engine_rate
+            )));
+        }
+        check_timeouts(&[silence_timeout, no_speech_timeout, max_utterance]).map_err(PyValueError::new_err)?;
+        let capture_graph = match config.as_ref().filter(|config| !config.capture_graph.is_empty()) {
+            Some(config) => {
+                let graph = ProcessingGraph::new(config.capture_graph.clone(), engine_rate).map_err(PyValueError::new_err)?;
+                check_capture_graph(&graph, engine_rate).map_err(PyValueError::new_err)?;
+                Some(Arc::new(Mutex::new(graph)))
+            }
+            None => None,
+        };
+        
+        Ok(VoicePipeline {
+            detector: state,
+            wake_word,
+            recognizer: engine,
+            input_device,
+            output_device,
+            playback,
+            echo_cancellation: playback && echo_cancellation,
+            dereverberation,
+            capture_graph,
+            loudness_target,
+            endpointing: Endpointing { vad_threshold_db, silence_timeout, no_speech_timeout, max_utterance },
+            stall,
+            sound: None,
+            keywords: None,
+            earcons: HashMap::new(),
+            thread_policies,
+            shared: Arc::new(Shared {
+                zone,
+                stop: AtomicBool::new(false),
+                listen_request: AtomicBool::new(false),
+                listening: AtomicBool::new(false),
+                dictation_request: Mutex::new(None),
+                dictating: AtomicBool::new(false),
+                events: Mutex::new(VecDeque::new()),
+                events_ready: Condvar::new(),
+                playback: Arc::new(Mutex::new(VecDeque::new())),
+                output_rate: AtomicU32::new(0),
+                monitor: Arc::new(PlaybackMonitor::new(playback && echo_cancellation)),
+                barge_in,
+                audio_class: Mutex::new(AudioClass::Silence),
+                present: AtomicBool::new(false),
+                activity: Mutex::new(SpeechActivity::new()),
+                recent: Mutex::new(VecDeque::new()),
+                privacy: Arc::new(PrivacyGate::new()),
+                assistant: assistant.map(|assistant| assistant.shared()),
+                threads: Mutex::new(Vec::new()),
+                recorder: Mutex::new(None),
+            }),
+        })
+    }
+    
+    /// Start the capture (and playback) threads
+    /// 
+    /// Events go to `callback(event)` if given; otherwise they are queued
+    /// for `poll_event`. A thread that fails or panics reports an "error"
+    /// event and is restarted after a growing delay, up to 5 times in a
+    /// row before it is left stopped.
+    #[pyo3(signature = (callback=None))]
+    fn start(&mut self, py: Python, callback: Option<PyObject>) -> PyResult<()> {
+        if self.running() {
+            return Err(PyValueError::new_err("Pipeline is already running"));
+        }
+        if let Some(callback) = &callback {
+            if !callback.as_ref(py).is_callable() {
+                return Err(PyValueError::new_err("Callback must be callable"));
+            }
+        }
+        // Reap threads that exited on their own (e.g. the device went away)
+        self.stop_threads(py);
+        self.claim_zone().map_err(PyValueError::new_err)?;
+        self.shared.stop.store(false, Ordering::Relaxed);
+        self.shared.listen_request.store(false, Ordering::Relaxed);
+        lifecycle::register(&self.shared);
+        
+        if self.playback {
+            let (shared, device) = (self.shared.clone(), self.output_device.clone());
+            self.spawn("jenna-playback", "playback", callback.as_ref().map(|c| c.clone_ref(py)), || {}, move || {
+                run_playback(shared.clone(), device.clone())
+            })?;
+        }
+        
+        let sound_frames = match &self.sound {
+            Some(sound) => {
+                let (_, sample_rate) = self.detector.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+                let (sender, receiver) = mpsc::sync_channel(SOUND_QUEUE_FRAMES);
+                let (shared, sound) = (self.shared.clone(), sound.clone());
+                let thread_callback = callback.as_ref().map(|c| c.clone_ref(py));
+                let recover = {
+                    let sound = sound.clone();
+                    move || sound.clear_poison()
+                };
+                self.spawn("jenna-sound-events", "sound_events", callback.as_ref().map(|c| c.clone_ref(py)), recover, move || {
+                    run_sound_events(shared.clone(), sound.clone(), &receiver, sample_rate, thread_callback.clone())
+                })?;
+                Some(sender)
+            }
+            None => None,
+        };
+        
+        let shared = self.shared.clone();
+        let detector = self.detector.clone();
+        let recognizer = self.recognizer.clone();
+        let device = self.input_device.clone();
+        let (stall, endpointing) = (self.stall, self.endpointing);
+        let (echo_cancellation, dereverberation) = (self.echo_cancellation, self.dereverberation);
+        let (capture_graph, keywords) = (self.capture_graph.clone(), self.keywords.clone());
+        let thread_callback = callback.as_ref().map(|c| c.clone_ref(py));
+        let recover = {
+            let (detector, recognizer) = (detector.clone(), recognizer.clone());
+            let (capture_graph, keywords) = (capture_graph.clone(), keywords.clone());
+            move || {
+                detector.clear_poison();
+                recognizer.clear_poison();
+                if let Some(graph) = &capture_graph {
+                    graph.clear_poison();
+                }
+                if let Some(keywords) = &keywords {
+                    keywords.clear_poison();
+                }
+            }
+        };
+        self.spawn("jenna-pipeline", "capture", callback, recover, move || {
+            run_pipeline(
+                shared.clone(),
+                detector.clone(),
+                recognizer.clone(),
+                device.clone(),
+                stall,
+                endpointing,
+                echo_cancellation,
+                dereverberation,
+                capture_graph.clone(),
+                keywords.clone(),
+                sound_frames.clone(),
+                thread_callback.clone(),
+            )
+        })
+    }
+    
+    /// Run a `SoundEventClassifier` on the captured audio from the next `start`, or stop with None
+    /// 
+    /// The classifier runs on its own thread beside wake word detection
+    /// and reports watched sounds as "sound" events; if it falls behind,
+    /// audio is skipped rather than delaying the wake word.
+    fn set_sound_classifier(&mut self, classifier: Option<PyRef<SoundEventClassifier>>) {
+        self.sound = classifier.map(|classifier| classifier.shared_detector());
+    }
+    
+    /// Run an `AudioGraph` on the captured audio from the next `start`, or stop with None
+    /// 
+    /// The graph runs after echo cancellation and de-reverberation, on
+    /// audio at the engine rate, and must hand back audio at that rate.
+    /// The graph stays shared with the Python object, so its meters and VAD
+    /// can be read while the pipeline runs.
+    fn set_capture_graph(&mut self, graph: Option<PyRef<AudioGraph>>) -> PyResult<()> {
+        if let Some(graph) = &graph {
+            let (_, engine_rate) = self.detector.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+            check_capture_graph(&graph.shared_graph().lock().unwrap(), engine_rate).map_err(PyValueError::new_err)?;
+        }
+        self.capture_graph = graph.map(|graph| graph.shared_graph());
+        Ok(())
+    }
+    
+    /// Watch the partial transcripts for a `KeywordSpotter`'s phrases from the next `start`, or stop with None
+    /// 
+    /// A phrase heard mid-utterance runs its callback and posts a "keyword"
+    /// event straight away, without waiting for the final result, so "stop"
+    /// or "cancel" can interrupt the assistant with little delay.
+    fn set_keyword_spotter(&mut self, spotter: Option<PyRef<KeywordSpotter>>) {
+        self.keywords = spotter.map(|spotter| spotter.shared_spotter());
+    }
+    
+    /// Stop all threads and close the audio streams
+    fn stop(&mut self, py: Python) -> PyResult<()> {
+        self.stop_threads(py);
+        Ok(())
+    }
+    
+    /// Check whether the pipeline threads are running
+    fn is_running(&self) -> bool {
+        self.running()
+    }
+    
+    /// Get the zone this pipeline serves, if it was given one
+    fn get_zone(&self) -> Option<String> {
+        self.shared.zone.clone()
+    }
+    
+    /// Get the pipeline state: "stopped", "idle" (waiting for the wake word), "listening" or "dictating"
+    fn get_state(&self) -> &'static str {
+        if !self.running() {
+            "stopped"
+        } else if self.shared.dictating.load(Ordering::Relaxed) {
+            "dictating"
+        } else if self.shared.listening.load(Ordering::Relaxed) {
+            "listening"
+        } else {
+            "idle"
+        }
+    }
+    
+    /// Get what the microphone hears: "silence", "speech", "music" or "noise"
+    /// 
+    /// Changes are also posted on the event bus as "audio.class" events.
+    /// While music is playing, speech no longer opens a follow-up turn;
+    /// the wake word still does.
+    fn get_audio_class(&self) -> &'static str {
+        self.shared.audio_class.lock().unwrap().name()
+    }
+    
+    /// Turn privacy mode on or off
+    /// 
+    /// While it is on, the capture callback drops every block before it is
+    /// passed on, so no audio reaches the wake word detector, recognizer,
+    /// sound classifier, session recording or `recent_audio`, whatever the
+    /// hardware mute state is. Turning it on abandons the turn in progress
+    /// and clears the recent audio. With `keep_metering`, the callback
+    /// still measures the input level for `get_input_level`. The change is
+    /// posted on the event bus as "privacy.changed" with "on" or "off", and
+    /// a running pipeline emits a "privacy" event. The mode is kept across
+    /// restarts.
+    #[pyo3(signature = (enabled, keep_metering=true))]
+    fn set_privacy_mode(&self, enabled: bool, keep_metering: bool) {
+        if self.shared.privacy.set(enabled, keep_metering) {
+            events::post(Event::new(
+                "privacy.changed",
+                &self.shared.source("pipeline"),
+                EventData::Text(privacy_state(enabled).to_string()),
+            ));
+        }
+    }
+    
+    /// Check whether privacy mode is on
+    fn is_privacy_mode(&self) -> bool {
+        self.shared.privacy.is_muted()
+    }
+    
+    /// Get the level of the last captured block in dBFS, or None with metering off
+    /// 
+    /// The level is measured inside the capture callback, so it stays live
+    /// in privacy mode when `keep_metering` was set.
+    fn get_input_level(&self) -> Option<f32> {
+        self.shared.privacy.level_db()
+    }
+    
+    /// Check whether someone has been talking recently
+    /// 
+    /// Someone counts as present after half a second of speech (not the
+    /// assistant's own playback) and until 30 seconds pass without any.
+    /// Changes are posted on the event bus as "presence.changed" events
+    /// with "present" or "absent".
+    fn is_present(&self) -> bool {
+        self.shared.present.load(Ordering::Relaxed)
+    }
+    
+    /// Get statistics of the speech heard over the last `hours` hours (at most 24)
+    /// 
+    /// Returns a dict with "present" (as `is_present`), "last_speech" (Unix
+    /// time speech was last heard, or None), "seconds_since_speech",
+    /// "speech_minutes_last_hour" (a rolling hour) and "hourly", a list of
+    /// `(hour_start, speech_minutes)` per clock hour, oldest first, ending
+    /// with the current hour so far. Only VAD decisions outside playback
+    /// are counted and no audio is kept, so this can serve as an occupancy
+    /// sensor.
+    #[pyo3(signature = (hours=24))]
+    fn speech_statistics(&self, py: Python, hours: usize) -> PyResult<Py<PyDict>> {
+        if !(1..=24).contains(&hours) {
+            return Err(PyValueError::new_err("Hours must be between 1 and 24"));
+        }
+        let now = unix_time();
+        let activity = self.shared.activity.lock().unwrap();
+        let last_speech = activity.last_speech();
+        
+        let dict = PyDict::new(py);
+        dict.set_item("present", self.shared.present.load(Ordering::Relaxed))?;
+        dict.set_item("last_speech", last_speech)?;
+        dict.set_item("seconds_since_speech", last_speech.map(|time| (now - time).max(0.0)))?;
+        dict.set_item("speech_minutes_last_hour", activity.speech_minutes(now, 3600.0))?;
+        dict.set_item("hourly", activity.hourly(now, hours))?;
+        Ok(dict.into())
+    }
+    
+    /// Get up to the last `seconds` of captured audio (at most 15) and its sample rate
+    /// 
+    /// For analysing what the microphone just heard, e.g. identifying the
+    /// music playing with a `FingerprintDatabase`. Empty when the pipeline
+    /// is stopped.
+    #[pyo3(signature = (seconds=10.0))]
+    fn recent_audio(&self, seconds: f32) -> PyResult<(Vec<i16>, u32)> {
+        let (_, sample_rate) = self.detector.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+        let wanted = (seconds.max(0.0) * sample_rate as f32) as usize;
+        let recent = self.shared.recent.lock().unwrap();
+        let start = recent.len().saturating_sub(wanted);
+        Ok((recent.range(start..).copied().collect(), sample_rate))
+    }
+    
+    /// Start a turn without the wake word (e.g. push-to-talk)
+    fn trigger(&self) -> PyResult<()> {
+        if !self.running() {
+            return Err(PyValueError::new_err("Pipeline is not running"));
+        }
+        self.shared.listen_request.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+    
+    /// Start long-form dictation, carrying on the turn in progress if there is one
+    /// 
+    /// The recognizer keeps running across pauses: each stretch of speech
+    /// is reported as a "dictation" event and stitched onto the transcript,
+    /// with a paragraph break after `paragraph_pause` seconds of silence.
+    /// Saying "new line" or "new paragraph" breaks the text there; saying
+    /// "stop dictation", calling `stop_dictation` or `idle_timeout` seconds
+    /// of silence ends it with a "dictation_end" event holding the whole
+    /// transcript. Wake word detection is paused meanwhile.
+    #[pyo3(signature = (paragraph_pause=3.0, idle_timeout=20.0))]
+    fn start_dictation(&self, paragraph_pause: f32, idle_timeout: f32) -> PyResult<()> {
+        if !self.running() {
+            return Err(PyValueError::new_err("Pipeline is not running"));
+        }
+        let options = DictationOptions::new(paragraph_pause, idle_timeout).map_err(PyValueError::new_err)?;
+        *self.shared.dictation_request.lock().unwrap() = Some(DictationRequest::Start(options));
+        Ok(())
+    }
+    
+    /// End dictation after the segment being spoken, as if "stop dictation" was said
+    fn stop_dictation(&self) {
+        *self.shared.dictation_request.lock().unwrap() = Some(DictationRequest::Stop);
+    }
+    
+    /// Check whether the pipeline is dictating
+    fn is_dictating(&self) -> bool {
+        self.shared.dictating.load(Ordering::Relaxed)
+    }
+    
+    /// Take the next queued event, waiting up to `timeout` seconds (None returns immediately)
+    #[pyo3(signature = (timeout=None))]
+    fn poll_event(&self, py: Python, timeout: Option<f64>) -> PyResult<Option<PipelineEvent>> {
+        let wait = match timeout {
+            Some(seconds) if seconds > 0.0 => Some(
+                Duration::try_from_secs_f64(seconds)
+                    .map_err(|_| PyValueError::new_err("Timeout must be a finite number of seconds"))?,
+            ),
+            _ => None,
+        };
+        let shared = self.shared.clone();
+        Ok(py.allow_threads(move || {
+            let events = shared.events.lock().unwrap();
+            let mut events = match wait {
+                Some(wait) => {
+                    shared
+                        .events_ready
+                        .wait_timeout_while(events, wait, |events| events.is_empty())
+                        .unwrap()
+                        .0
+                }
+                _ => events,
+            };
+            events.pop_front()
+        }))
+    }
+    
+    /// Take all queued events
+    fn get_events(&self) -> Vec<PipelineEvent> {
+        self.shared.events.lock().unwrap().drain(..).collect()
+    }
+    
+    /// Iterate over queued events with `async for event in pipeline.events()`
+    /// 
+    /// Only sees events when the pipeline was started without a callback.
+    /// Several iterators share one queue, so each event goes to one of them.
+    fn events(&self) -> PipelineEventStream {
+        PipelineEventStream { shared: self.shared.clone() }
+    }
+    
+    /// Queue PCM for playback at the loudness target, returning its duration in seconds
+    fn play(&self, py: Python, samples: Vec<i16>, sample_rate: u32) -> PyResult<f64> {
+        let output_rate = self.shared.output_rate.load(Ordering::Relaxed);
+        if output_rate == 0 {
+            return Err(PyValueError::new_err("Playback is not running"));
+        }
+        
+        let mut audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let target = self.loudness_target;
+        let audio = py
+            .allow_threads(|| {
+                if let Some(target) = target {
+                    normalize_loudness(&mut audio, sample_rate, target, DEFAULT_MAX_PEAK_DB);
+                }
+                resample_mono(&audio, sample_rate, output_rate)
+            })
+            .map_err(PyErr::from)?;
+        self.shared.playback.lock().unwrap().extend(audio);
+        Ok(samples.len() as f64 / sample_rate as f64)
+    }
+    
+    /// Set the loudness `play` normalizes to in LUFS, or None to play audio as it is
+    fn set_loudness_target(&mut self, target: Option<f32>) -> PyResult<()> {
+        if let Some(target) = target {
+            check_loudness_target(target).map_err(PyValueError::new_err)?;
+        }
+        self.loudness_target = target;
+        Ok(())
+    }
+    
+    /// Synthesize `text` with `tts` and queue it for playback, returning its duration in seconds
+    fn speak(&self, py: Python, tts: PyRef<TextToSpeech>, text: &str) -> PyResult<f64> {
+        let (pcm, sample_rate) = tts.render(py, text)?;
+        self.play(py, pcm, sample_rate)
+    }
+    
+    /// Drop any queued playback, earcons included
+    fn stop_playback(&self) -> PyResult<()> {
+        self.shared.playback.lock().unwrap().clear();
+        self.shared.monitor.clear_earcons();
+        Ok(())
+    }
+    
+    /// Set the priority and CPU affinity of a pipeline thread from the next `start`
+    /// 
+    /// `stage` is "capture" (the thread reading the microphone and running
+    /// wake word detection, VAD and recognition), "playback" or
+    /// "sound_events". `priority` is "normal", "high" (a lower nice value)
+    /// or "realtime" (SCHED_FIFO), and `cpus` pins the thread to those CPUs
+    /// (Linux only). Raising priority needs CAP_SYS_NICE or matching
+    /// limits; if the OS refuses, a warning is logged and the thread runs
+    /// unchanged. The audio callbacks themselves run on threads owned by
+    /// the audio backend.
+    #[pyo3(signature = (stage, priority="normal", cpus=None))]
+    fn set_thread_policy(&mut self, stage: &str, priority: &str, cpus: Option<Vec<usize>>) -> PyResult<()> {
+        priority::check_stage(stage).map_err(PyValueError::new_err)?;
+        let policy = ThreadPolicy::new(priority, cpus.unwrap_or_default()).map_err(PyValueError::new_err)?;
+        if policy == ThreadPolicy::default() {
+            self.thread_policies.remove(stage);
+        } else {
+            self.thread_policies.insert(stage.to_string(), policy);
+        }
+        Ok(())
+    }
+    
+    /// Decode a short sound file (WAV or FLAC) and keep it as the earcon `name`
+    /// 
+    /// Earcons are decoded once, so playing them later doesn't touch the
+    /// disk or the decoder. Loading a name again replaces the sound.
+    /// Returns its duration in seconds.
+    fn load_earcon(&mut self, py: Python, name: String, path: &str) -> PyResult<f64> {
+        let (samples, sample_rate) = py.allow_threads(|| read_audio_file_mono(Path::new(path))).map_err(PyErr::from)?;
+        if samples.is_empty() {
+            return Err(PyValueError::new_err(format!("Earcon file has no audio: {}", path)));
+        }
+        let earcon = Earcon::new(samples, sample_rate);
+        let duration = earcon.duration();
+        self.earcons.insert(name, earcon);
+        Ok(duration)
+    }
+    
+    /// Forget the earcon `name`; returns whether it was loaded
+    fn remove_earcon(&mut self, name: &str) -> bool {
+        self.earcons.remove(name).is_some()
+    }
+    
+    /// List the loaded earcons
+    fn earcon_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.earcons.keys().cloned().collect();
+        names.sort();
+        names
+    }
+    
+    /// Play the earcon `name` over whatever is queued for playback
+    /// 
+    /// With `mode` "mix" the earcon is layered over speech; with "duck"
+    /// the speech drops to the barge-in duck level while it plays. The
+    /// earcon starts `delay` seconds after the audio already handed to
+    /// the device, or exactly at output frame `at_frame` (see
+    /// `get_playback_clock`) if given, so sounds can be lined up sample
+    /// for sample. It is mixed in the output callback, not queued, and
+    /// never delays the speech. Returns the output frame it starts at.
+    #[pyo3(signature = (name, mode="mix", delay=0.0, gain=1.0, at_frame=None))]
+    fn play_earcon(&mut self, name: &str, mode: &str, delay: f64, gain: f32, at_frame: Option<u64>) -> PyResult<u64> {
+        let output_rate = self.shared.output_rate.load(Ordering::Relaxed);
+        if output_rate == 0 {
+            return Err(PyValueError::new_err("Playback is not running"));
+        }
+        let duck = match mode {
+            "mix" => None,
+            "duck" => Some(DUCK_GAIN),
+            _ => return Err(PyValueError::new_err(format!("Unknown earcon mode '{}'; expected 'mix' or 'duck'", mode))),
+        };
+        if !delay.is_finite() || delay < 0.0 {
+            return Err(PyValueError::new_err("Earcon delay must not be negative"));
+        }
+        let earcon = self
+            .earcons
+            .get_mut(name)
+            .ok_or_else(|| PyValueError::new_err(format!("No earcon named '{}'", name)))?;
+        
+        let samples = earcon.at_rate(output_rate).map_err(PyErr::from)?;
+        let start = at_frame.unwrap_or_else(|| self.shared.monitor.clock() + (delay * output_rate as f64).round() as u64);
+        self.shared.monitor.schedule_earcon(samples, start, gain, duck);
+        Ok(start)
+    }
+    
+    /// Get the number of output frames played so far, the clock `play_earcon` schedules against
+    /// 
+    /// The clock advances at the output device rate while playback runs.
+    fn get_playback_clock(&self) -> u64 {
+        self.shared.monitor.clock()
+    }
+    
+    /// Check whether audio is still queued for playback
+    fn is_speaking(&self) -> bool {
+        !self.shared.playback.lock().unwrap().is_empty()
+    }
+    
+    /// Measure ambient noise for `duration_secs` and recommend detection settings
+    /// 
+    /// Captures from the input device (the pipeline must be stopped) while
+    /// the room is in its usual state; speaking a few words during the
+    /// measurement lets the speech level be measured instead of assumed.
+    /// Returns a dict with the noise floor, its spread, the speech level and
+    /// SNR, and the recommended `vad_threshold_db` and `sensitivity`. With
+    /// `apply`, the VAD threshold is used from the next `start` and the
+    /// detector is rebuilt with the new sensitivity for every keyword.
+    #[pyo3(signature = (duration_secs=5.0, apply=false))]
+    fn calibrate(&mut self, py: Python, duration_secs: f64, apply: bool) -> PyResult<Py<PyDict>> {
+        if self.running() {
+            return Err(PyValueError::new_err("Stop the pipeline before calibrating"));
+        }
+        if !duration_secs.is_finite() || duration_secs <= 0.0 {
+            return Err(PyValueError::new_err("Calibration duration must be positive"));
+        }
+        let (frame_length, sample_rate) = self
+            .detector
+            .lock()
+            .unwrap()
+            .frame_format()
+            .map_err(NotInitializedError::new_err)?;
+        let (device, stall) = (self.input_device.clone(), self.stall);
+        let total_frames = (duration_secs * sample_rate as f64 / frame_length as f64).ceil() as usize;
+        
+        let calibration = py
+            .allow_threads(|| -> Result<_, String> {
+                let mut capture = CaptureSource::open(device, sample_rate, stall).map_err(|err| err.to_string())?;
+                let mut calibrator = NoiseCalibrator::new();
+                let mut pending: Vec<i16> = Vec::with_capacity(frame_length * 2);
+                while calibrator.frames() < total_frames {
+                    let resampled = match capture.recv(Duration::from_millis(100)).map_err(|err| err.to_string())? {
+                        Captured::Audio(samples) => samples,
+                        Captured::Idle => continue,
+                        Captured::Closed => break,
+                    };
+                    pending.extend(resampled.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+                    while pending.len() >= frame_length && calibrator.frames() < total_frames {
+                        let frame: Vec<i16> = pending.drain(..frame_length).collect();
+                        calibrator.process(&frame);
+                    }
+                }
+                calibrator.finish().ok_or_else(|| "No audio was captured during calibration".to_string())
+            })
+            .map_err(PyValueError::new_err)?;
+        
+        tracing::info!(
+            noise_floor_db = calibration.noise_floor_db,
+            snr_db = calibration.snr_db,
+            vad_threshold_db = calibration.vad_threshold_db,
+            sensitivity = calibration.sensitivity,
+            apply,
+            "Calibrated to ambient noise"
+        );
+        if apply {
+            self.wake_word.borrow_mut(py).set_sensitivity(calibration.sensitivity)?;
+            self.endpointing.vad_threshold_db = calibration.vad_threshold_db;
+        }
+        
+        let dict = PyDict::new(py);
+        dict.set_item("noise_floor_db", calibration.noise_floor_db)?;
+        dict.set_item("noise_spread_db", calibration.noise_spread_db)?;
+        dict.set_item("speech_level_db", calibration.speech_level_db)?;
+        dict.set_item("speech_detected", calibration.speech_detected)?;
+        dict.set_item("snr_db", calibration.snr_db)?;
+        dict.set_item("vad_threshold_db", calibration.vad_threshold_db)?;
+        dict.set_item("sensitivity", calibration.sensitivity)?;
+        dict.set_item("applied", apply)?;
+        Ok(dict.into())
+    }
+    
+    /// Record the session to `path` for later `replay`
+    /// 
+    /// Every frame the pipeline processes (mono, at the wake word engine's
+    /// rate) is written along with the playback, trigger, follow-up and
+    /// assistant state it read, plus every pipeline event and VAD boundary.
+    /// Recording can start before or while the pipeline runs; an existing
+    /// file is replaced. With `encryption` (`StorageEncryption`), the file
+    /// is encrypted with its primary key. With `storage` (`StorageGuard`),
+    /// the recording is checked against it under the "recordings" category
+    /// as it grows, and stops when the guard refuses more space.
+    #[pyo3(signature = (path, encryption=None, storage=None))]
+    fn start_recording(
+        &self,
+        py: Python,
+        path: &str,
+        encryption: Option<PyRef<StorageEncryption>>,
+        storage: Option<Py<StorageGuard>>,
+    ) -> PyResult<()> {
+        let (frame_length, sample_rate) = self
+            .detector
+            .lock()
+            .unwrap()
+            .frame_format()
+            .map_err(NotInitializedError::new_err)?;
+        let keys = encryption.map(|encryption| encryption.key_ring());
+        let writer = SessionWriter::create(Path::new(path), sample_rate, frame_length as u32, keys.as_ref())?;
+        let recording = Recording::new(py, writer, storage)?;
+        if let Some(previous) = self.shared.recorder.lock().unwrap().replace(recording) {
+            previous.writer.finish()?;
+        }
+        Ok(())
+    }
+    
+    /// Stop recording and flush the file, returning the number of frames recorded
+    fn stop_recording(&self) -> PyResult<u64> {
+        match self.shared.recorder.lock().unwrap().take() {
+            Some(recording) => Ok(recording.writer.finish()?),
+            None => Ok(0),
+        }
+    }
+    
+    /// Check whether a session is being recorded
+    fn is_recording(&self) -> bool {
+        self.shared.recorder.lock().unwrap().is_some()
+    }
+    
+    /// Feed a recorded session through this pipeline's detector and recognizer
+    /// 
+    /// Runs on the calling thread, as fast as the engines allow, with the
+    /// recorded playback, trigger, follow-up and assistant state standing in
+    /// for the live ones, so with the same models and settings the replay
+    /// takes the same path as the recording. No callbacks are called and no
+    /// audio is played. Returns a `SessionReplay` comparing the regenerated
+    /// events with the recorded ones; the pipeline must not be running.
+    /// Encrypted recordings need the `encryption` they were made with.
+    #[pyo3(signature = (path, encryption=None))]
+    fn replay(&self, py: Python, path: &str, encryption: Option<PyRef<StorageEncryption>>) -> PyResult<SessionReplay> {
+        if self.running() {
+            return Err(PyValueError::new_err("Stop the pipeline before replaying a session"));
+        }
+        let keys = encryption.map(|encryption| encryption.key_ring());
+        let session = py.allow_threads(|| read_session(Path::new(path), keys.as_ref()))?;
+        let (detector, recognizer, endpointing) = (self.detector.clone(), self.recognizer.clone(), self.endpointing);
+        
+        let (events, input_mismatches) = py
+            .allow_threads(|| -> Result<(Vec<SessionEvent>, u64), String> {
+                let mut processor = FrameProcessor::new(detector, recognizer, endpointing, false)?;
+                if processor.sample_rate != session.header.sample_rate || processor.frame_length != session.header.frame_length as usize {
+                    return Err(format!(
+                        "Session was recorded at {} Hz with {}-sample frames, but the detector uses {} Hz with {}-sample frames",
+                        session.header.sample_rate, session.header.frame_length, processor.sample_rate, processor.frame_length
+                    ));
+                }
+            
+                let mut env = ReplayEnv {
+                    header: session.header.clone(),
+                    frame: 0,
+                    reads: VecDeque::new(),
+                    events: Vec::new(),
+                    input_mismatches: 0,
+                    mismatched: false,
+                };
+                for (index, frame) in session.frames.iter().enumerate() {
+                    env.begin_frame(index as u64, &frame.reads);
+                    processor.process(&frame.samples, Instant::now(), &mut env)?;
+                    env.end_frame();
+                }
+                Ok((env.events, env.input_mismatches))
+            })
+            .map_err(PyValueError::new_err)?;
+        
+        Ok(SessionReplay::new(&session, events, input_mismatches))
+    }
+    
+    /// Stop the threads and close the audio streams; safe to call more than once
+    fn release(&mut self, py: Python) -> PyResult<()> {
+        self.stop_threads(py);
+        self.stop_recording()?;
+        Ok(())
+    }
+}
+
+impl Drop for VoicePipeline {
+    fn drop(&mut self) {
+        // Signal without joining: this may run during garbage collection with
+        // the GIL held, and a thread waiting for the GIL to deliver a callback
+        // would never finish. The threads close their streams on the way out.
+        self.shared.stop.store(true, Ordering::Relaxed);
+        self.shared.events_ready.notify_all();
+    }
+}
+
+/// Run a pipeline built from `config` without a Python host until `stop` is set
+/// 
+/// Used by the `jenna-core` binary. The engines are still constructed through
+/// their Python classes, so this embeds an interpreter, but no Python code
+/// runs. Returns when `stop` is set or the capture stream closes.
+pub fn run_headless(config: JennaConfig, stop: &AtomicBool, mut on_event: impl FnMut(&PipelineEvent)) -> Result<(), String> {
+    pyo3::prepare_freethreaded_python();
+    Python::with_gil(|py| -> PyResult<()> {
+        let config = PyCell::new(py, config)?;
+        let detector = PyCell::new(py, WakeWordDetector::from_config(config.borrow())?)?;
+        let recognizer = PyCell::new(py, SpeechRecognizer::from_config(py, config.borrow())?)?;
+        let defaults = JennaConfig::default();
+        let mut pipeline = VoicePipeline::new(
+            detector.borrow(),
+            recognizer.borrow(),
+            None,
+            None,
+            false,
+            defaults.vad_threshold_db,
+            defaults.silence_timeout,
+            defaults.no_speech_timeout,
+            defaults.max_utterance,
+            None,
+            Some(config.borrow()),
+            defaults.stall_timeout,
+            defaults.restart_on_stall,
+        )?;
+        pipeline.start(py, None)?;
+        
+        while !stop.load(Ordering::Relaxed) && pipeline.running() {
+            if let Some(event) = pipeline.poll_event(py, Some(STREAM_POLL.as_secs_f64()))? {
+                on_event(&event);
+            }
+        }
+        for event in pipeline.get_events() {
+            on_event(&event);
+        }
+        pipeline.release(py)
+    })
+    .map_err(|err| err.to_string())
+}
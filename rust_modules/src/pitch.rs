@@ -0,0 +1,209 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+/// Default lowest frequency considered by the pitch detector (Hz)
+const DEFAULT_MIN_FREQ: f32 = 60.0;
+
+/// Default highest frequency considered by the pitch detector (Hz)
+const DEFAULT_MAX_FREQ: f32 = 1000.0;
+
+/// Default YIN absolute threshold on the cumulative mean normalized difference
+const DEFAULT_THRESHOLD: f32 = 0.15;
+
+/// Estimate the fundamental frequency of a frame with the YIN algorithm
+/// 
+/// Returns the frequency in Hz and a confidence in [0, 1], or `None` when the
+/// frame is unvoiced or too short for the requested frequency range.
+pub fn yin(frame: &[f32], sample_rate: u32, min_freq: f32, max_freq: f32, threshold: f32) -> Option<(f32, f32)> {
+    let sample_rate = sample_rate as f32;
+    let min_lag = (sample_rate / max_freq).floor().max(2.0) as usize;
+    // Compared as a float, so a tiny `min_freq` can't saturate the cast and overflow
+    let max_lag = (sample_rate / min_freq).ceil();
+    if !(max_lag + 1.0 < frame.len() as f32) {
+        return None;
+    }
+    let max_lag = max_lag as usize;
+    if min_lag >= max_lag {
+        return None;
+    }
+    
+    let window = frame.len() - max_lag;
+    
+    // Difference function d(tau)
+    let mut diff = vec![0.0f32; max_lag + 1];
+    for tau in 1..=max_lag {
+        let mut sum = 0.0;
+        for i in 0..window {
+            let delta = frame[i] - frame[i + tau];
+            sum += delta * delta;
+        }
+        diff[tau] = sum;
+    }
+    
+    // Cumulative mean normalized difference d'(tau)
+    let mut cmnd = vec![1.0f32; max_lag + 1];
+    let mut running_sum = 0.0;
+    for tau in 1..=max_lag {
+        running_sum += diff[tau];
+        cmnd[tau] = if running_sum > 0.0 { diff[tau] * tau as f32 / running_sum } else { 1.0 };
+    }
+    
+    // First dip below the threshold, followed down to its local minimum
+    let mut best = None;
+    let mut tau = min_lag;
+    while tau <= max_lag {
+        if cmnd[tau] < threshold {
+            while tau + 1 <= max_lag && cmnd[tau + 1] < cmnd[tau] {
+                tau += 1;
+            }
+            best = Some(tau);
+            break;
+        }
+        tau += 1;
+    }
+    
+    let tau = best?;
+    
+    // Parabolic interpolation around the minimum for sub-sample precision
+    let refined = if tau > 1 && tau < max_lag {
+        let (s0, s1, s2) = (cmnd[tau - 1], cmnd[tau], cmnd[tau + 1]);
+        let denom = s0 + s2 - 2.0 * s1;
+        if denom.abs() > f32::EPSILON {
+            tau as f32 + 0.5 * (s0 - s2) / denom
+        } else {
+            tau as f32
+        }
+    } else {
+        tau as f32
+    };
+    
+    let confidence = (1.0 - cmnd[tau]).clamp(0.0, 1.0);
+    Some((sample_rate / refined, confidence))
+}
+
+/// Resolve the defaults of a search range and check that it bounds the YIN lag
+fn frequency_range(min_freq: Option<f32>, max_freq: Option<f32>) -> PyResult<(f32, f32)> {
+    let min_freq = min_freq.unwrap_or(DEFAULT_MIN_FREQ);
+    let max_freq = max_freq.unwrap_or(DEFAULT_MAX_FREQ);
+    if min_freq.is_nan() || min_freq <= 0.0 || max_freq <= min_freq || !max_freq.is_finite() {
+        return Err(PyValueError::new_err("Frequency range must satisfy 0 < min_freq < max_freq"));
+    }
+    Ok((min_freq, max_freq))
+}
+
+/// Detect the pitch of a single audio frame
+/// 
+/// Returns a `(frequency_hz, confidence)` tuple, or `None` if no pitch was found.
+#[pyfunction]
+#[pyo3(signature = (frame, sample_rate, min_freq=None, max_freq=None, threshold=None))]
+pub fn detect_pitch(
+    frame: Vec<f32>,
+    sample_rate: u32,
+    min_freq: Option<f32>,
+    max_freq: Option<f32>,
+    threshold: Option<f32>,
+) -> PyResult<Option<(f32, f32)>> {
+    let (min_freq, max_freq) = frequency_range(min_freq, max_freq)?;
+    Ok(yin(&frame, sample_rate, min_freq, max_freq, threshold.unwrap_or(DEFAULT_THRESHOLD)))
+}
+
+/// Streaming pitch tracker
+/// 
+/// Accepts arbitrarily sized chunks of audio, analyses overlapping frames and
+/// smooths the resulting pitch track with a short median filter.
+#[pyclass]
+pub struct PitchTracker {
+    #[pyo3(get)]
+    sample_rate: u32,
+    #[pyo3(get)]
+    frame_size: usize,
+    #[pyo3(get)]
+    hop_size: usize,
+    min_freq: f32,
+    max_freq: f32,
+    threshold: f32,
+    pending: Vec<f32>,
+    history: Vec<f32>,
+    smoothing: usize,
+}
+
+#[pymethods]
+impl PitchTracker {
+    /// Create a new pitch tracker
+    #[new]
+    #[pyo3(signature = (sample_rate, frame_size=2048, hop_size=512, min_freq=None, max_freq=None, threshold=None, smoothing=5))]
+    fn new(
+        sample_rate: u32,
+        frame_size: usize,
+        hop_size: usize,
+        min_freq: Option<f32>,
+        max_freq: Option<f32>,
+        threshold: Option<f32>,
+        smoothing: usize,
+    ) -> PyResult<Self> {
+        if frame_size == 0 || hop_size == 0 {
+            return Err(PyValueError::new_err("Frame size and hop size must be greater than zero"));
+        }
+        let (min_freq, max_freq) = frequency_range(min_freq, max_freq)?;
+        
+        Ok(PitchTracker {
+            sample_rate,
+            frame_size,
+            hop_size,
+            min_freq,
+            max_freq,
+            threshold: threshold.unwrap_or(DEFAULT_THRESHOLD),
+            pending: Vec::with_capacity(frame_size * 2),
+            history: Vec::new(),
+            smoothing: smoothing.max(1),
+        })
+    }
+    
+    /// Feed samples and return one `(frequency_hz, confidence)` entry per analysed frame
+    /// 
+    /// Unvoiced frames are reported with a frequency of 0.0.
+    fn process(&mut self, samples: Vec<f32>) -> PyResult<Vec<(f32, f32)>> {
+        self.pending.extend_from_slice(&samples);
+        
+        let mut results = Vec::new();
+        while self.pending.len() >= self.frame_size {
+            let (freq, confidence) = yin(
+                &self.pending[..self.frame_size],
+                self.sample_rate,
+                self.min_freq,
+                self.max_freq,
+                self.threshold,
+            ).unwrap_or((0.0, 0.0));
+            
+            self.history.push(freq);
+            if self.history.len() > self.smoothing {
+                self.history.remove(0);
+            }
+            
+            results.push((self.smoothed(freq), confidence));
+            self.pending.drain(..self.hop_size.min(self.pending.len()));
+        }
+        
+        Ok(results)
+    }
+    
+    /// Reset the tracker state
+    fn reset(&mut self) -> PyResult<()> {
+        self.pending.clear();
+        self.history.clear();
+        Ok(())
+    }
+}
+
+impl PitchTracker {
+    /// Median of the recent voiced estimates, keeping unvoiced frames unvoiced
+    fn smoothed(&self, current: f32) -> f32 {
+        if current <= 0.0 {
+            return 0.0;
+        }
+        
+        let mut voiced: Vec<f32> = self.history.iter().cloned().filter(|&f| f > 0.0).collect();
+        voiced.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        voiced[voiced.len() / 2]
+    }
+}
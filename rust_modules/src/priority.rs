@@ -0,0 +1,163 @@
+use std::io;
+
+/// Pipeline threads that take a scheduling policy, by stage name
+/// 
+/// "capture" reads the microphone and runs echo cancellation, the capture
+/// graph, wake word detection, VAD and recognition; "playback" feeds the
+/// output device; "sound_events" runs the sound classifier.
+pub const THREAD_STAGES: &[&str] = &["capture", "playback", "sound_events"];
+
+/// Nice value given to "high" priority threads
+#[cfg(target_os = "linux")]
+const HIGH_NICE: i32 = -10;
+
+/// SCHED_FIFO priority given to "realtime" threads, below the audio server's own threads
+#[cfg(unix)]
+const REALTIME_PRIORITY: i32 = 50;
+
+/// How a pipeline thread is scheduled relative to the rest of the process
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThreadPriority {
+    /// Left as the OS starts it
+    #[default]
+    Normal,
+    /// A lower nice value, so the thread wins over busy normal threads
+    High,
+    /// SCHED_FIFO, so normal threads can't preempt it at all
+    Realtime,
+}
+
+impl ThreadPriority {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Ok(ThreadPriority::Normal),
+            "high" => Ok(ThreadPriority::High),
+            "realtime" => Ok(ThreadPriority::Realtime),
+            _ => Err(format!("Unknown thread priority '{}'; expected 'normal', 'high' or 'realtime'", name)),
+        }
+    }
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            ThreadPriority::Normal => "normal",
+            ThreadPriority::High => "high",
+            ThreadPriority::Realtime => "realtime",
+        }
+    }
+}
+
+/// Priority and CPU affinity applied to a thread as it starts
+/// 
+/// Raising priority usually needs privileges: CAP_SYS_NICE, or an
+/// `rtprio`/`nice` limit in /etc/security/limits.conf. A policy that
+/// can't be applied is logged and the thread runs as before, so a missing
+/// permission never stops the pipeline.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ThreadPolicy {
+    pub priority: ThreadPriority,
+    /// CPUs the thread may run on; empty leaves it on any CPU (Linux only)
+    pub cpus: Vec<usize>,
+}
+
+impl ThreadPolicy {
+    pub fn new(priority: &str, cpus: Vec<usize>) -> Result<Self, String> {
+        let priority = ThreadPriority::from_name(priority)?;
+        check_cpus(&cpus)?;
+        Ok(ThreadPolicy { priority, cpus })
+    }
+    
+    /// Apply the policy to the calling thread, logging what couldn't be applied
+    pub fn apply_to_current(&self) {
+        if let Err(err) = set_current_priority(self.priority) {
+            tracing::warn!(priority = self.priority.name(), "Failed to raise thread priority: {}", err);
+        }
+        if !self.cpus.is_empty() {
+            if let Err(err) = set_current_affinity(&self.cpus) {
+                tracing::warn!(cpus = ?self.cpus, "Failed to set thread CPU affinity: {}", err);
+            }
+        }
+    }
+}
+
+/// Check that a stage name is one `THREAD_STAGES` lists
+pub fn check_stage(stage: &str) -> Result<(), String> {
+    if THREAD_STAGES.contains(&stage) {
+        Ok(())
+    } else {
+        Err(format!("Unknown pipeline thread '{}'; expected one of {}", stage, THREAD_STAGES.join(", ")))
+    }
+}
+
+fn check_cpus(cpus: &[usize]) -> Result<(), String> {
+    let available = std::thread::available_parallelism().map_or(usize::MAX, |count| count.get());
+    match cpus.iter().find(|&&cpu| cpu >= available) {
+        Some(cpu) => Err(format!("CPU {} doesn't exist; this machine has {} CPUs", cpu, available)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_current_priority(priority: ThreadPriority) -> io::Result<()> {
+    match priority {
+        ThreadPriority::Normal => Ok(()),
+        // Linux keeps a nice value per thread, addressed by its thread id
+        ThreadPriority::High => {
+            let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, HIGH_NICE) };
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::last_os_error())
+            }
+        }
+        ThreadPriority::Realtime => set_current_fifo(),
+    }
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn set_current_priority(priority: ThreadPriority) -> io::Result<()> {
+    match priority {
+        ThreadPriority::Normal => Ok(()),
+        ThreadPriority::High => Err(io::Error::new(io::ErrorKind::Unsupported, "per-thread nice values need Linux")),
+        ThreadPriority::Realtime => set_current_fifo(),
+    }
+}
+
+#[cfg(not(unix))]
+fn set_current_priority(priority: ThreadPriority) -> io::Result<()> {
+    match priority {
+        ThreadPriority::Normal => Ok(()),
+        _ => Err(io::Error::new(io::ErrorKind::Unsupported, "thread priorities are not supported on this platform")),
+    }
+}
+
+#[cfg(unix)]
+fn set_current_fifo() -> io::Result<()> {
+    let param = libc::sched_param { sched_priority: REALTIME_PRIORITY };
+    // pthread functions return the error number instead of setting errno
+    match unsafe { libc::pthread_setschedparam(libc::pthread_self(), libc::SCHED_FIFO, &param) } {
+        0 => Ok(()),
+        code => Err(io::Error::from_raw_os_error(code)),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_current_affinity(cpus: &[usize]) -> io::Result<()> {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &cpu in cpus {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("CPU {} is out of range", cpu)));
+        }
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    // Thread id 0 is the calling thread
+    if unsafe { libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_current_affinity(_cpus: &[usize]) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "CPU affinity is only supported on Linux"))
+}
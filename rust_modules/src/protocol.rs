@@ -0,0 +1,33 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyBytes, PyDict};
+use jenna_core::protocol::{Encoding, Message};
+
+fn parse_encoding(encoding: &str) -> PyResult<Encoding> {
+    Encoding::from_name(encoding).map_err(PyValueError::new_err)
+}
+
+/// Validate a client or server message dict and encode it for the wire
+/// 
+/// `encoding` is "json" or "msgpack". Unknown message types, missing fields and wrong types raise
+/// `ValueError`, so messages built in Python match what the Rust servers
+/// and the web client expect.
+#[pyfunction]
+#[pyo3(signature = (message, encoding="json"))]
+pub fn encode_message(py: Python, message: &PyDict, encoding: &str) -> PyResult<PyObject> {
+    let encoding = parse_encoding(encoding)?;
+    let json: String = py.import("json")?.call_method1("dumps", (message,))?.extract()?;
+    let value = serde_json::from_str(&json).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    let data = Message::from_value(value)
+        .and_then(|message| message.encode(encoding))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(PyBytes::new(py, &data).into())
+}
+
+/// Decode and validate a message, returning it as a dict
+#[pyfunction]
+#[pyo3(signature = (data, encoding="json"))]
+pub fn decode_message(py: Python, data: &[u8], encoding: &str) -> PyResult<PyObject> {
+    let message = Message::decode(data, parse_encoding(encoding)?).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (message.to_value().to_string(),))?.into())
+}
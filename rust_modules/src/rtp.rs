@@ -0,0 +1,795 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use std::collections::{BTreeMap, VecDeque};
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::audio::{resample_mono, MonoResampler};
+use crate::lifecycle::{self, Resource};
+use crate::speech::TextToSpeech;
+
+/// Fixed part of an RTP header
+const RTP_HEADER_LEN: usize = 12;
+
+/// Largest datagram accepted
+const MAX_DATAGRAM: usize = 2048;
+
+/// How often the receive and send threads check the stop flag
+const SOCKET_POLL: Duration = Duration::from_millis(50);
+
+/// Decoded audio kept for `read`; the oldest is dropped beyond this many seconds
+const MAX_BUFFERED_SECONDS: usize = 10;
+
+/// Sequence numbers further than this behind the playout point mean the sender restarted
+const SEQUENCE_RESET_GAP: u64 = 1000;
+
+/// Sample encoding of an RTP payload
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// 16-bit big-endian linear PCM (RFC 3551 L16)
+    L16,
+    /// 16-bit little-endian linear PCM, as most microcontrollers produce it
+    PcmS16le,
+    /// G.711 mu-law
+    Pcmu,
+    /// G.711 A-law
+    Pcma,
+}
+
+impl Encoding {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "l16" => Some(Encoding::L16),
+            "pcm_s16le" | "s16le" => Some(Encoding::PcmS16le),
+            "pcmu" | "ulaw" | "mulaw" => Some(Encoding::Pcmu),
+            "pcma" | "alaw" => Some(Encoding::Pcma),
+            _ => None,
+        }
+    }
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::L16 => "l16",
+            Encoding::PcmS16le => "pcm_s16le",
+            Encoding::Pcmu => "pcmu",
+            Encoding::Pcma => "pcma",
+        }
+    }
+    
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            Encoding::L16 | Encoding::PcmS16le => 2,
+            Encoding::Pcmu | Encoding::Pcma => 1,
+        }
+    }
+    
+    /// Decode a payload to samples in [-1, 1]
+    fn decode(self, payload: &[u8]) -> Vec<f32> {
+        let to_f32 = |s: i16| s as f32 / 32768.0;
+        match self {
+            Encoding::L16 => payload.chunks_exact(2).map(|b| to_f32(i16::from_be_bytes([b[0], b[1]]))).collect(),
+            Encoding::PcmS16le => payload.chunks_exact(2).map(|b| to_f32(i16::from_le_bytes([b[0], b[1]]))).collect(),
+            Encoding::Pcmu => payload.iter().map(|&b| to_f32(ulaw_decode(b))).collect(),
+            Encoding::Pcma => payload.iter().map(|&b| to_f32(alaw_decode(b))).collect(),
+        }
+    }
+    
+    fn encode(self, samples: &[i16]) -> Vec<u8> {
+        match self {
+            Encoding::L16 => samples.iter().flat_map(|s| s.to_be_bytes()).collect(),
+            Encoding::PcmS16le => samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+            Encoding::Pcmu => samples.iter().map(|&s| ulaw_encode(s)).collect(),
+            Encoding::Pcma => samples.iter().map(|&s| alaw_encode(s)).collect(),
+        }
+    }
+}
+
+fn ulaw_decode(byte: u8) -> i16 {
+    let byte = !byte;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = (byte & 0x0F) as i32;
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if byte & 0x80 != 0 { -magnitude as i16 } else { magnitude as i16 }
+}
+
+fn ulaw_encode(sample: i16) -> u8 {
+    const BIAS: i32 = 0x84;
+    const CLIP: i32 = 32635;
+    let sign = if sample < 0 { 0x80 } else { 0 };
+    let magnitude = (sample as i32).abs().min(CLIP) + BIAS;
+    let exponent = (7 - (magnitude.leading_zeros() as i32 - 17)).clamp(0, 7);
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !(sign | ((exponent as u8) << 4) | mantissa as u8)
+}
+
+fn alaw_decode(byte: u8) -> i16 {
+    let byte = byte ^ 0x55;
+    let exponent = ((byte >> 4) & 0x07) as i32;
+    let mantissa = (byte & 0x0F) as i32;
+    let magnitude = if exponent == 0 {
+        (mantissa << 4) + 8
+    } else {
+        ((mantissa << 4) + 0x108) << (exponent - 1)
+    };
+    if byte & 0x80 != 0 { magnitude as i16 } else { -magnitude as i16 }
+}
+
+fn alaw_encode(sample: i16) -> u8 {
+    let sign = if sample >= 0 { 0x80 } else { 0 };
+    let magnitude = ((sample as i32).abs() >> 3).min(0x0FFF);
+    let encoded = if magnitude < 0x20 {
+        (magnitude >> 1) as u8
+    } else {
+        let exponent = (31 - magnitude.leading_zeros() as i32 - 4).clamp(1, 7);
+        ((exponent as u8) << 4) | ((magnitude >> exponent) & 0x0F) as u8
+    };
+    (sign | encoded) ^ 0x55
+}
+
+/// The fields of an RTP packet the receiver uses
+#[derive(Debug)]
+struct RtpPacket<'a> {
+    sequence: u16,
+    timestamp: u32,
+    ssrc: u32,
+    payload: &'a [u8],
+}
+
+/// Parse an RTP packet, skipping CSRCs, header extensions and padding
+fn parse_rtp(data: &[u8]) -> Option<RtpPacket<'_>> {
+    if data.len() < RTP_HEADER_LEN || data[0] >> 6 != 2 {
+        return None;
+    }
+    let padding = data[0] & 0x20 != 0;
+    let extension = data[0] & 0x10 != 0;
+    let csrc_count = (data[0] & 0x0F) as usize;
+    let mut offset = RTP_HEADER_LEN + csrc_count * 4;
+    if extension {
+        let length = data.get(offset + 2..offset + 4)?;
+        offset += 4 + u16::from_be_bytes([length[0], length[1]]) as usize * 4;
+    }
+    let mut end = data.len();
+    if padding {
+        end = end.checked_sub(*data.last()? as usize)?;
+    }
+    Some(RtpPacket {
+        sequence: u16::from_be_bytes([data[2], data[3]]),
+        timestamp: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+        ssrc: u32::from_be_bytes([data[8], data[9], data[10], data[11]]),
+        payload: data.get(offset..end)?,
+    })
+}
+
+/// Write an RTP header followed by the payload
+fn build_rtp(payload_type: u8, marker: bool, sequence: u16, timestamp: u32, ssrc: u32, payload: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(RTP_HEADER_LEN + payload.len());
+    packet.push(0x80);
+    packet.push((payload_type & 0x7F) | if marker { 0x80 } else { 0 });
+    packet.extend_from_slice(&sequence.to_be_bytes());
+    packet.extend_from_slice(&timestamp.to_be_bytes());
+    packet.extend_from_slice(&ssrc.to_be_bytes());
+    packet.extend_from_slice(payload);
+    packet
+}
+
+/// Counters reported by `RtpReceiver.get_stats`
+#[derive(Debug, Default, Clone)]
+struct JitterStats {
+    received: u64,
+    lost: u64,
+    late: u64,
+    duplicates: u64,
+    reordered: u64,
+    /// RFC 3550 interarrival jitter, in seconds
+    jitter: f64,
+}
+
+/// Reorders packets by sequence number and conceals the ones that never arrive
+/// 
+/// A packet is released once every earlier one has been released, or once
+/// it has waited `delay` for a missing predecessor; the gap is then filled
+/// with silence. Packets arriving after their slot was released are late
+/// and dropped.
+struct JitterBuffer {
+    delay: Duration,
+    clock_rate: u32,
+    /// Extended sequence number of the next packet to release
+    next: Option<u64>,
+    /// Extended sequence number of the highest packet seen, for unwrapping
+    highest: Option<u64>,
+    pending: BTreeMap<u64, (Instant, Vec<f32>)>,
+    /// Samples per packet, for concealing lost ones
+    packet_samples: usize,
+    transit: Option<f64>,
+    stats: JitterStats,
+}
+
+impl JitterBuffer {
+    fn new(delay: Duration, clock_rate: u32) -> Self {
+        JitterBuffer {
+            delay,
+            clock_rate,
+            next: None,
+            highest: None,
+            pending: BTreeMap::new(),
+            packet_samples: 0,
+            transit: None,
+            stats: JitterStats::default(),
+        }
+    }
+    
+    /// Forget the stream position, e.g. when a new source starts
+    fn reset(&mut self) {
+        self.next = None;
+        self.highest = None;
+        self.pending.clear();
+        self.transit = None;
+    }
+    
+    /// Extend a 16-bit sequence number using the closest value to the highest seen
+    fn extend(&self, sequence: u16) -> u64 {
+        let highest = match self.highest {
+            Some(highest) => highest,
+            None => return sequence as u64 + (1 << 16),
+        };
+        let base = highest & !0xFFFF;
+        [base.wrapping_sub(1 << 16), base, base + (1 << 16)]
+            .into_iter()
+            .map(|b| b.wrapping_add(sequence as u64))
+            .min_by_key(|&candidate| candidate.abs_diff(highest))
+            .unwrap_or(sequence as u64)
+    }
+    
+    /// Update the interarrival jitter estimate (RFC 3550 section 6.4.1)
+    fn observe_transit(&mut self, timestamp: u32, arrival: f64) {
+        let transit = arrival - timestamp as f64 / self.clock_rate as f64;
+        if let Some(previous) = self.transit {
+            let delta = (transit - previous).abs();
+            self.stats.jitter += (delta - self.stats.jitter) / 16.0;
+        }
+        self.transit = Some(transit);
+    }
+    
+    fn push(&mut self, sequence: u16, timestamp: u32, arrival: f64, samples: Vec<f32>) {
+        let sequence = self.extend(sequence);
+        if let Some(next) = self.next {
+            if sequence + SEQUENCE_RESET_GAP < next {
+                // The sender restarted its sequence
+                self.reset();
+            } else if sequence < next {
+                self.stats.late += 1;
+                return;
+            }
+        }
+        if self.pending.contains_key(&sequence) {
+            self.stats.duplicates += 1;
+            return;
+        }
+        match self.highest {
+            Some(highest) if sequence < highest => self.stats.reordered += 1,
+            _ => self.highest = Some(sequence),
+        }
+        self.stats.received += 1;
+        self.observe_transit(timestamp, arrival);
+        self.packet_samples = samples.len();
+        self.next.get_or_insert(sequence);
+        self.pending.insert(sequence, (Instant::now(), samples));
+    }
+    
+    /// Release the audio that is ready, in order, with silence for lost packets
+    fn pop_ready(&mut self, now: Instant) -> Vec<f32> {
+        let mut output = Vec::new();
+        while let Some(next) = self.next {
+            if let Some((_, samples)) = self.pending.remove(&next) {
+                output.extend(samples);
+                self.next = Some(next + 1);
+                continue;
+            }
+            // Give up on the missing packet once its successor has waited long enough
+            let first = match self.pending.iter().next() {
+                Some((&first, (arrived, _))) if now.duration_since(*arrived) >= self.delay => first,
+                _ => break,
+            };
+            let missing = first - next;
+            self.stats.lost += missing;
+            output.extend(std::iter::repeat(0.0).take(missing as usize * self.packet_samples));
+            self.next = Some(first);
+        }
+        output
+    }
+}
+
+/// State shared between an `RtpReceiver` and its thread
+struct ReceiverShared {
+    stop: AtomicBool,
+    audio: Mutex<VecDeque<f32>>,
+    audio_ready: Condvar,
+    jitter: Mutex<JitterBuffer>,
+    /// Current source and where its packets come from
+    peer: Mutex<Option<(u32, SocketAddr)>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Resource for ReceiverShared {
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.audio_ready.notify_all();
+        let thread = self.thread.lock().unwrap().take();
+        lifecycle::join_threads(thread);
+    }
+}
+
+/// Receive packets, pass them through the jitter buffer and queue the resampled audio
+fn run_receiver(socket: UdpSocket, shared: Arc<ReceiverShared>, format: StreamFormat, output_rate: u32) {
+    let mut resampler = match MonoResampler::new(format.sample_rate, output_rate) {
+        Ok(resampler) => resampler,
+        Err(err) => {
+            tracing::error!("RTP receiver stopped: {}", err);
+            return;
+        }
+    };
+    let started = Instant::now();
+    let limit = MAX_BUFFERED_SECONDS * output_rate as usize;
+    let mut datagram = [0u8; MAX_DATAGRAM];
+    
+    while !shared.stop.load(Ordering::Relaxed) {
+        match socket.recv_from(&mut datagram) {
+            Ok((length, from)) => {
+                let packet = match parse_rtp(&datagram[..length]) {
+                    Some(packet) => packet,
+                    None => continue,
+                };
+                {
+                    let mut peer = shared.peer.lock().unwrap();
+                    if peer.map_or(true, |(ssrc, _)| ssrc != packet.ssrc) {
+                        tracing::info!(%from, ssrc = packet.ssrc, "RTP source started");
+                        shared.jitter.lock().unwrap().reset();
+                        *peer = Some((packet.ssrc, from));
+                    }
+                }
+                let samples = format.decode(packet.payload);
+                let arrival = started.elapsed().as_secs_f64();
+                shared.jitter.lock().unwrap().push(packet.sequence, packet.timestamp, arrival, samples);
+            }
+            Err(err) if matches!(err.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+            Err(err) => {
+                tracing::warn!("RTP receive failed: {}", err);
+                thread::sleep(SOCKET_POLL);
+            }
+        }
+        
+        let ready = shared.jitter.lock().unwrap().pop_ready(Instant::now());
+        if ready.is_empty() {
+            continue;
+        }
+        match resampler.push(&ready) {
+            Ok(resampled) => {
+                let mut audio = shared.audio.lock().unwrap();
+                audio.extend(resampled);
+                let excess = audio.len().saturating_sub(limit);
+                audio.drain(..excess);
+                shared.audio_ready.notify_all();
+            }
+            Err(err) => tracing::warn!("RTP resampling failed: {}", err),
+        }
+    }
+}
+
+/// Encoding, rate and channel count of an RTP stream
+#[derive(Debug, Clone, Copy)]
+struct StreamFormat {
+    encoding: Encoding,
+    sample_rate: u32,
+    channels: usize,
+}
+
+impl StreamFormat {
+    fn new(encoding: &str, sample_rate: u32, channels: usize) -> PyResult<Self> {
+        let encoding = Encoding::from_name(encoding)
+            .ok_or_else(|| PyValueError::new_err(format!("Unknown encoding: {} (expected l16, pcm_s16le, pcmu or pcma)", encoding)))?;
+        if sample_rate == 0 || !(1..=2).contains(&channels) {
+            return Err(PyValueError::new_err("Sample rate must be positive and channels 1 or 2"));
+        }
+        Ok(StreamFormat { encoding, sample_rate, channels })
+    }
+    
+    /// Decode a payload and mix it down to mono
+    fn decode(&self, payload: &[u8]) -> Vec<f32> {
+        let samples = self.encoding.decode(payload);
+        if self.channels == 1 {
+            return samples;
+        }
+        samples.chunks_exact(self.channels).map(|frame| frame.iter().sum::<f32>() / self.channels as f32).collect()
+    }
+}
+
+/// Receives microphone audio from a satellite over RTP
+/// 
+/// Listens on a UDP port for RTP packets (any payload type) carrying
+/// `encoding` audio at `stream_rate`, reorders them in a jitter buffer
+/// that waits up to `jitter_ms` for a missing packet before filling it
+/// with silence, and resamples the result to `sample_rate`. Read it with
+/// `read`, e.g. to feed a `WakeWordDetector` frame by frame. When a new
+/// SSRC appears the buffer restarts, so a rebooted satellite just works;
+/// `get_peer` tells where to send audio back.
+#[pyclass]
+pub struct RtpReceiver {
+    host: String,
+    port: u16,
+    format: StreamFormat,
+    sample_rate: u32,
+    jitter_ms: u32,
+    shared: Option<Arc<ReceiverShared>>,
+    local: Option<SocketAddr>,
+}
+
+impl RtpReceiver {
+    fn running(&self) -> Option<&Arc<ReceiverShared>> {
+        self.shared.as_ref().filter(|shared| !shared.stop.load(Ordering::Relaxed))
+    }
+}
+
+#[pymethods]
+impl RtpReceiver {
+    /// Create a receiver for `host`:`port`; port 0 picks a free port
+    /// 
+    /// `encoding` is "l16" (RFC 3551, big-endian), "pcm_s16le", "pcmu" or
+    /// "pcma". Stereo streams are mixed down.
+    #[new]
+    #[pyo3(signature = (port=5004, host="0.0.0.0", sample_rate=16000, stream_rate=16000, channels=1, encoding="l16", jitter_ms=60))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        port: u16,
+        host: &str,
+        sample_rate: u32,
+        stream_rate: u32,
+        channels: usize,
+        encoding: &str,
+        jitter_ms: u32,
+    ) -> PyResult<Self> {
+        if sample_rate == 0 {
+            return Err(PyValueError::new_err("Sample rate must be positive"));
+        }
+        Ok(RtpReceiver {
+            host: host.to_string(),
+            port,
+            format: StreamFormat::new(encoding, stream_rate, channels)?,
+            sample_rate,
+            jitter_ms,
+            shared: None,
+            local: None,
+        })
+    }
+    
+    /// Bind the port and start receiving, returning the bound port
+    fn start(&mut self) -> PyResult<u16> {
+        if self.running().is_some() {
+            return Err(PyValueError::new_err("RTP receiver is already running"));
+        }
+        let address = format!("{}:{}", self.host, self.port);
+        let socket = UdpSocket::bind(&address)
+            .and_then(|socket| socket.set_read_timeout(Some(SOCKET_POLL)).map(|_| socket))
+            .map_err(|err| PyValueError::new_err(format!("Failed to bind {}: {}", address, err)))?;
+        let local = socket.local_addr().map_err(|err| PyValueError::new_err(err.to_string()))?;
+        
+        let shared = Arc::new(ReceiverShared {
+            stop: AtomicBool::new(false),
+            audio: Mutex::new(VecDeque::new()),
+            audio_ready: Condvar::new(),
+            jitter: Mutex::new(JitterBuffer::new(
+                Duration::from_millis(self.jitter_ms as u64),
+                self.format.sample_rate,
+            )),
+            peer: Mutex::new(None),
+            thread: Mutex::new(None),
+        });
+        let running = shared.clone();
+        let (format, output_rate) = (self.format, self.sample_rate);
+        let handle = thread::Builder::new()
+            .name("jenna-rtp-rx".to_string())
+            .spawn(move || run_receiver(socket, running, format, output_rate))
+            .map_err(|err| PyValueError::new_err(format!("Failed to start RTP receiver: {}", err)))?;
+        *shared.thread.lock().unwrap() = Some(handle);
+        
+        tracing::info!(address = %local, encoding = format.encoding.name(), "RTP receiver listening");
+        lifecycle::register(&shared);
+        self.shared = Some(shared);
+        self.local = Some(local);
+        Ok(local.port())
+    }
+    
+    fn stop(&mut self, py: Python) {
+        if let Some(shared) = self.shared.take() {
+            py.allow_threads(|| shared.shutdown());
+        }
+    }
+    
+    fn is_running(&self) -> bool {
+        self.running().is_some()
+    }
+    
+    /// Read `frame_length` samples at `sample_rate`, waiting up to `timeout` seconds
+    /// 
+    /// Returns None if not enough audio arrived in time; nothing is consumed then.
+    #[pyo3(signature = (frame_length, timeout=1.0))]
+    fn read(&self, py: Python, frame_length: usize, timeout: f64) -> PyResult<Option<Vec<i16>>> {
+        let shared = self.running().ok_or_else(|| PyValueError::new_err("RTP receiver is not running"))?;
+        // Negative, NaN and infinite timeouts have no duration
+        let deadline = Duration::try_from_secs_f64(timeout).ok().and_then(|wait| Instant::now().checked_add(wait));
+        let deadline = match deadline {
+            Some(deadline) if frame_length > 0 => deadline,
+            _ => return Err(PyValueError::new_err("Frame length must be positive and timeout finite and non-negative")),
+        };
+        py.allow_threads(|| {
+            let mut audio = shared.audio.lock().unwrap();
+            while audio.len() < frame_length && !shared.stop.load(Ordering::Relaxed) {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Ok(None);
+                }
+                audio = shared.audio_ready.wait_timeout(audio, deadline - now).unwrap().0;
+            }
+            if audio.len() < frame_length {
+                return Ok(None);
+            }
+            Ok(Some(audio.drain(..frame_length).map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect()))
+        })
+    }
+    
+    /// Number of samples waiting to be read
+    fn available(&self) -> usize {
+        self.running().map_or(0, |shared| shared.audio.lock().unwrap().len())
+    }
+    
+    /// Address of the current satellite as (host, port), None before the first packet
+    fn get_peer(&self) -> Option<(String, u16)> {
+        let shared = self.shared.as_ref()?;
+        let peer = *shared.peer.lock().unwrap();
+        peer.map(|(_, address)| (address.ip().to_string(), address.port()))
+    }
+    
+    /// Get jitter buffer counters: received, lost, late, duplicates, reordered and jitter_ms
+    fn get_stats(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let stats = self
+            .shared
+            .as_ref()
+            .map(|shared| shared.jitter.lock().unwrap().stats.clone())
+            .unwrap_or_default();
+        let dict = PyDict::new(py);
+        dict.set_item("received", stats.received)?;
+        dict.set_item("lost", stats.lost)?;
+        dict.set_item("late", stats.late)?;
+        dict.set_item("duplicates", stats.duplicates)?;
+        dict.set_item("reordered", stats.reordered)?;
+        dict.set_item("jitter_ms", stats.jitter * 1000.0)?;
+        Ok(dict.into())
+    }
+    
+    /// Port the receiver is bound to, or the configured port before `start`
+    #[getter]
+    fn get_port(&self) -> u16 {
+        self.local.filter(|_| self.is_running()).map_or(self.port, |local| local.port())
+    }
+    
+    #[getter]
+    fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
+
+impl Drop for RtpReceiver {
+    fn drop(&mut self) {
+        // The thread exits within one socket poll; no need to wait for it
+        if let Some(shared) = &self.shared {
+            shared.stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// State shared between an `RtpSender` and its pacing thread
+struct SenderShared {
+    stop: AtomicBool,
+    queue: Mutex<VecDeque<i16>>,
+    queued: Condvar,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Resource for SenderShared {
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.queued.notify_all();
+        let thread = self.thread.lock().unwrap().take();
+        lifecycle::join_threads(thread);
+    }
+}
+
+/// Packet parameters of an `RtpSender`
+#[derive(Debug, Clone, Copy)]
+struct SenderConfig {
+    encoding: Encoding,
+    payload_type: u8,
+    packet_samples: usize,
+    ptime: Duration,
+    ssrc: u32,
+}
+
+/// Send queued audio one packet per `ptime`, marking the first packet of each talkspurt
+fn run_sender(socket: UdpSocket, destination: SocketAddr, shared: Arc<SenderShared>, config: SenderConfig) {
+    let mut sequence = (config.ssrc >> 16) as u16;
+    let mut timestamp = config.ssrc.rotate_left(7);
+    let mut talkspurt = true;
+    let mut next_send = Instant::now();
+    
+    while !shared.stop.load(Ordering::Relaxed) {
+        let packet: Vec<i16> = {
+            let mut queue = shared.queue.lock().unwrap();
+            if queue.is_empty() {
+                talkspurt = true;
+                queue = shared.queued.wait_timeout(queue, SOCKET_POLL).unwrap().0;
+                if queue.is_empty() {
+                    continue;
+                }
+                next_send = Instant::now();
+            }
+            let count = config.packet_samples.min(queue.len());
+            let mut packet: Vec<i16> = queue.drain(..count).collect();
+            packet.resize(config.packet_samples, 0);
+            packet
+        };
+        
+        let now = Instant::now();
+        if next_send > now {
+            thread::sleep(next_send - now);
+        }
+        let payload = config.encoding.encode(&packet);
+        let datagram = build_rtp(config.payload_type, talkspurt, sequence, timestamp, config.ssrc, &payload);
+        if let Err(err) = socket.send_to(&datagram, destination) {
+            tracing::warn!(%destination, "RTP send failed: {}", err);
+        }
+        talkspurt = false;
+        sequence = sequence.wrapping_add(1);
+        timestamp = timestamp.wrapping_add(config.packet_samples as u32);
+        next_send += config.ptime;
+    }
+}
+
+/// Sends audio (typically TTS replies) to a satellite over RTP
+/// 
+/// Audio passed to `send` or `speak` is resampled to `sample_rate`,
+/// encoded and sent as one packet every `ptime_ms`, paced in real time so
+/// the satellite can play packets as they arrive.
+#[pyclass]
+pub struct RtpSender {
+    destination: SocketAddr,
+    sample_rate: u32,
+    config: SenderConfig,
+    shared: Option<Arc<SenderShared>>,
+}
+
+impl RtpSender {
+    /// The running sender, starting it on first use
+    fn sender(&mut self) -> PyResult<Arc<SenderShared>> {
+        if let Some(shared) = self.shared.as_ref().filter(|shared| !shared.stop.load(Ordering::Relaxed)) {
+            return Ok(shared.clone());
+        }
+        let bind = if self.destination.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" };
+        let socket = UdpSocket::bind(bind).map_err(|err| PyValueError::new_err(format!("Failed to open RTP socket: {}", err)))?;
+        let shared = Arc::new(SenderShared {
+            stop: AtomicBool::new(false),
+            queue: Mutex::new(VecDeque::new()),
+            queued: Condvar::new(),
+            thread: Mutex::new(None),
+        });
+        let running = shared.clone();
+        let (destination, config) = (self.destination, self.config);
+        let handle = thread::Builder::new()
+            .name("jenna-rtp-tx".to_string())
+            .spawn(move || run_sender(socket, destination, running, config))
+            .map_err(|err| PyValueError::new_err(format!("Failed to start RTP sender: {}", err)))?;
+        *shared.thread.lock().unwrap() = Some(handle);
+        lifecycle::register(&shared);
+        self.shared = Some(shared.clone());
+        Ok(shared)
+    }
+}
+
+#[pymethods]
+impl RtpSender {
+    /// Create a sender to `host`:`port`
+    /// 
+    /// `encoding` is as for `RtpReceiver`; `payload_type` defaults to the
+    /// first dynamic type.
+    #[new]
+    #[pyo3(signature = (host, port=5004, sample_rate=16000, encoding="l16", payload_type=96, ptime_ms=20))]
+    fn new(host: &str, port: u16, sample_rate: u32, encoding: &str, payload_type: u8, ptime_ms: u32) -> PyResult<Self> {
+        let destination = (host, port)
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addresses| addresses.next())
+            .ok_or_else(|| PyValueError::new_err(format!("Cannot resolve {}:{}", host, port)))?;
+        let format = StreamFormat::new(encoding, sample_rate, 1)?;
+        if payload_type > 127 || !(5..=200).contains(&ptime_ms) {
+            return Err(PyValueError::new_err("Payload type must be below 128 and ptime between 5 and 200 ms"));
+        }
+        let packet_samples = (sample_rate as u64 * ptime_ms as u64 / 1000) as usize;
+        let packet_bytes = packet_samples * format.encoding.bytes_per_sample();
+        if packet_samples == 0 || packet_bytes + RTP_HEADER_LEN > MAX_DATAGRAM {
+            return Err(PyValueError::new_err(format!("A {} ms packet does not fit in a datagram", ptime_ms)));
+        }
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.subsec_nanos());
+        
+        Ok(RtpSender {
+            destination,
+            sample_rate,
+            config: SenderConfig {
+                encoding: format.encoding,
+                payload_type,
+                packet_samples,
+                ptime: Duration::from_micros(ptime_ms as u64 * 1000),
+                ssrc: seed ^ ((port as u32) << 16),
+            },
+            shared: None,
+        })
+    }
+    
+    /// Queue `samples` at `sample_rate` for sending, returning their duration in seconds
+    fn send(&mut self, py: Python, samples: Vec<i16>, sample_rate: u32) -> PyResult<f64> {
+        if sample_rate == 0 {
+            return Err(PyValueError::new_err("Sample rate must be positive"));
+        }
+        let shared = self.sender()?;
+        let output_rate = self.sample_rate;
+        let audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let audio = py.allow_threads(|| resample_mono(&audio, sample_rate, output_rate)).map_err(PyErr::from)?;
+        shared
+            .queue
+            .lock()
+            .unwrap()
+            .extend(audio.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+        shared.queued.notify_all();
+        Ok(samples.len() as f64 / sample_rate as f64)
+    }
+    
+    /// Synthesize `text` with `tts` and queue it, returning its duration in seconds
+    fn speak(&mut self, py: Python, tts: PyRef<TextToSpeech>, text: &str) -> PyResult<f64> {
+        let (pcm, sample_rate) = tts.render(py, text)?;
+        self.send(py, pcm, sample_rate)
+    }
+    
+    /// Drop audio that has not been sent yet
+    fn clear(&self) {
+        if let Some(shared) = &self.shared {
+            shared.queue.lock().unwrap().clear();
+        }
+    }
+    
+    /// Check whether audio is still waiting to be sent
+    fn is_sending(&self) -> bool {
+        self.shared.as_ref().map_or(false, |shared| !shared.queue.lock().unwrap().is_empty())
+    }
+    
+    /// Stop the sender thread, dropping queued audio; `send` restarts it
+    fn stop(&mut self, py: Python) {
+        if let Some(shared) = self.shared.take() {
+            py.allow_threads(|| shared.shutdown());
+        }
+    }
+    
+    /// Destination as (host, port)
+    fn get_destination(&self) -> (String, u16) {
+        (self.destination.ip().to_string(), self.destination.port())
+    }
+}
+
+impl Drop for RtpSender {
+    fn drop(&mut self) {
+        if let Some(shared) = &self.shared {
+            shared.stop.store(true, Ordering::Relaxed);
+            shared.queued.notify_all();
+        }
+    }
+}
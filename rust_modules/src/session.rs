@@ -0,0 +1,306 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use serde::{Deserialize, Serialize};
+use std::io::{BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::errors::JennaError;
+use crate::storage::{self, KeyRing, StorageError};
+use crate::wake_word::unix_time;
+
+/// File signature; the last two bytes are the layout version
+const SESSION_MAGIC: &[u8; 8] = b"JNASES01";
+
+const RECORD_FRAME: u8 = 1;
+const RECORD_EVENT: u8 = 2;
+
+#[derive(Error, Debug)]
+pub enum SessionError {
+    #[error("Session I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Invalid session file: {0}")]
+    FormatError(String),
+    
+    #[error("Session storage error: {0}")]
+    StorageError(#[from] StorageError),
+}
+
+impl From<SessionError> for PyErr {
+    fn from(err: SessionError) -> PyErr {
+        match err {
+            SessionError::IoError(_) | SessionError::StorageError(_) => JennaError::new_err(err.to_string()),
+            SessionError::FormatError(_) => PyValueError::new_err(err.to_string()),
+        }
+    }
+}
+
+/// Settings the recording was made with
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SessionHeader {
+    pub sample_rate: u32,
+    pub frame_length: u32,
+    /// Wall-clock start, in seconds since the Unix epoch
+    pub started: f64,
+}
+
+/// An event recorded in, or regenerated from, a session
+#[pyclass]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SessionEvent {
+    /// Index of the frame that produced the event
+    #[pyo3(get)]
+    pub frame: u64,
+    /// Offset of that frame into the session, in seconds
+    #[pyo3(get)]
+    pub time: f64,
+    /// A `PipelineEvent` kind, or "vad.speech_start" / "vad.speech_end"
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub text: Option<String>,
+    #[pyo3(get)]
+    pub keyword: Option<String>,
+    #[pyo3(get)]
+    pub confidence: Option<f32>,
+}
+
+impl SessionEvent {
+    /// Events match if they happened on the same frame with the same outcome
+    pub fn same_as(&self, other: &SessionEvent) -> bool {
+        self.frame == other.frame && self.kind == other.kind && self.text == other.text && self.keyword == other.keyword
+    }
+}
+
+#[pymethods]
+impl SessionEvent {
+    fn __repr__(&self) -> String {
+        format!(
+            "SessionEvent(frame={}, time={:.3}, kind={:?}, text={:?}, keyword={:?})",
+            self.frame, self.time, self.kind, self.text, self.keyword
+        )
+    }
+}
+
+/// One frame of audio and the non-audio inputs read while processing it
+pub struct RecordedFrame {
+    pub samples: Vec<i16>,
+    /// Values read from outside the audio (playback state, triggers, the
+    /// follow-up window, assistant phase), in the order they were read
+    pub reads: Vec<u8>,
+}
+
+/// A session loaded into memory
+pub struct Session {
+    pub header: SessionHeader,
+    pub frames: Vec<RecordedFrame>,
+    pub events: Vec<SessionEvent>,
+}
+
+/// Write one record, returning its size in bytes
+fn write_block(out: &mut impl Write, tag: u8, payload: &[u8]) -> std::io::Result<u64> {
+    out.write_all(&[tag])?;
+    out.write_all(&(payload.len() as u32).to_le_bytes())?;
+    out.write_all(payload)?;
+    Ok(5 + payload.len() as u64)
+}
+
+/// Appends frames and events to a session file as the pipeline runs
+/// 
+/// The file starts with `SESSION_MAGIC` and a length-prefixed JSON header,
+/// followed by tagged, length-prefixed records: frames (a sample count, the
+/// little-endian i16 samples and the recorded reads) and events (JSON).
+/// With keys, the whole file is encrypted with the primary key.
+pub struct SessionWriter {
+    out: BufWriter<Box<dyn Write + Send>>,
+    header: SessionHeader,
+    frames: u64,
+    reads: Vec<u8>,
+    /// Bytes written so far, before any encryption
+    bytes: u64,
+}
+
+impl SessionWriter {
+    pub fn create(path: &Path, sample_rate: u32, frame_length: u32, keys: Option<&KeyRing>) -> Result<Self, SessionError> {
+        let header = SessionHeader { sample_rate, frame_length, started: unix_time() };
+        let mut out = BufWriter::new(storage::create_writer(path, keys)?);
+        out.write_all(SESSION_MAGIC)?;
+        let json = serde_json::to_vec(&header).map_err(|err| SessionError::FormatError(err.to_string()))?;
+        out.write_all(&(json.len() as u32).to_le_bytes())?;
+        out.write_all(&json)?;
+        let bytes = (SESSION_MAGIC.len() + 4 + json.len()) as u64;
+        Ok(SessionWriter { out, header, frames: 0, reads: Vec::new(), bytes })
+    }
+    
+    /// Size of the recording so far, before any encryption
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes
+    }
+    
+    /// Note an input read while processing the current frame
+    pub fn record_read(&mut self, value: u8) {
+        self.reads.push(value);
+    }
+    
+    /// Record an event produced by the current frame
+    pub fn record_event(&mut self, kind: &str, text: Option<String>, keyword: Option<String>, confidence: Option<f32>) -> Result<(), SessionError> {
+        let event = SessionEvent {
+            frame: self.frames,
+            time: self.frames as f64 * self.header.frame_length as f64 / self.header.sample_rate as f64,
+            kind: kind.to_string(),
+            text,
+            keyword,
+            confidence,
+        };
+        let json = serde_json::to_vec(&event).map_err(|err| SessionError::FormatError(err.to_string()))?;
+        self.bytes += write_block(&mut self.out, RECORD_EVENT, &json)?;
+        Ok(())
+    }
+    
+    /// Record the frame just processed, with the reads noted since the previous one
+    pub fn record_frame(&mut self, samples: &[i16]) -> Result<(), SessionError> {
+        let mut payload = Vec::with_capacity(4 + samples.len() * 2 + self.reads.len());
+        payload.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        payload.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
+        payload.append(&mut self.reads);
+        self.bytes += write_block(&mut self.out, RECORD_FRAME, &payload)?;
+        self.frames += 1;
+        Ok(())
+    }
+    
+    pub fn flush(&mut self) -> Result<(), SessionError> {
+        self.out.flush()?;
+        Ok(())
+    }
+    
+    pub fn finish(mut self) -> Result<u64, SessionError> {
+        self.out.flush()?;
+        Ok(self.frames)
+    }
+}
+
+/// Read a whole session file, decrypting it with `keys` if it is encrypted
+pub fn read_session(path: &Path, keys: Option<&KeyRing>) -> Result<Session, SessionError> {
+    let mut input = storage::open_reader(path, keys)?;
+    let mut magic = [0u8; 8];
+    input.read_exact(&mut magic)?;
+    if &magic != SESSION_MAGIC {
+        return Err(SessionError::FormatError(format!("{} is not a session recording", path.display())));
+    }
+    
+    let mut len = [0u8; 4];
+    input.read_exact(&mut len)?;
+    let mut json = vec![0u8; u32::from_le_bytes(len) as usize];
+    input.read_exact(&mut json)?;
+    let header: SessionHeader = serde_json::from_slice(&json).map_err(|err| SessionError::FormatError(err.to_string()))?;
+    
+    let mut session = Session { header, frames: Vec::new(), events: Vec::new() };
+    loop {
+        let mut tag = [0u8; 1];
+        match input.read_exact(&mut tag) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let mut payload = Vec::new();
+        let read = input.read_exact(&mut len).and_then(|()| {
+            payload.resize(u32::from_le_bytes(len) as usize, 0);
+            input.read_exact(&mut payload)
+        });
+        match read {
+            Ok(()) => {}
+            // A recording cut short by a crash keeps everything before the last record
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        
+        match tag[0] {
+            RECORD_FRAME => {
+                let count = payload
+                    .get(..4)
+                    .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+                    .filter(|count| payload.len() >= 4 + count * 2)
+                    .ok_or_else(|| SessionError::FormatError(format!("Truncated frame {}", session.frames.len())))?;
+                let samples = payload[4..4 + count * 2]
+                    .chunks_exact(2)
+                    .map(|b| i16::from_le_bytes([b[0], b[1]]))
+                    .collect();
+                let reads = payload[4 + count * 2..].to_vec();
+                session.frames.push(RecordedFrame { samples, reads });
+            }
+            RECORD_EVENT => {
+                let event = serde_json::from_slice(&payload).map_err(|err| SessionError::FormatError(err.to_string()))?;
+                session.events.push(event);
+            }
+            other => return Err(SessionError::FormatError(format!("Unknown record type {}", other))),
+        }
+    }
+    Ok(session)
+}
+
+/// Outcome of replaying a recorded session through a pipeline
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct SessionReplay {
+    /// Frames fed through the pipeline
+    #[pyo3(get)]
+    pub frames: u64,
+    /// Length of the recorded audio, in seconds
+    #[pyo3(get)]
+    pub duration: f64,
+    /// Events the replay produced
+    #[pyo3(get)]
+    pub events: Vec<SessionEvent>,
+    /// Events stored in the recording
+    #[pyo3(get)]
+    pub recorded: Vec<SessionEvent>,
+    /// Index into `events` and `recorded` of the first difference, None if they agree
+    #[pyo3(get)]
+    pub first_mismatch: Option<usize>,
+    /// Frames whose recorded inputs didn't line up with what the replay read,
+    /// which means the pipeline took a different path from that frame on
+    #[pyo3(get)]
+    pub input_mismatches: u64,
+}
+
+impl SessionReplay {
+    pub fn new(session: &Session, events: Vec<SessionEvent>, input_mismatches: u64) -> Self {
+        let recorded = session.events.clone();
+        let first_mismatch = events
+            .iter()
+            .zip(&recorded)
+            .position(|(replayed, original)| !replayed.same_as(original))
+            .or_else(|| (events.len() != recorded.len()).then(|| events.len().min(recorded.len())));
+        let samples: usize = session.frames.iter().map(|frame| frame.samples.len()).sum();
+        
+        SessionReplay {
+            frames: session.frames.len() as u64,
+            duration: samples as f64 / session.header.sample_rate as f64,
+            events,
+            recorded,
+            first_mismatch,
+            input_mismatches,
+        }
+    }
+}
+
+#[pymethods]
+impl SessionReplay {
+    /// Whether the replay reproduced the recorded events exactly
+    #[getter]
+    fn matches(&self) -> bool {
+        self.first_mismatch.is_none() && self.input_mismatches == 0
+    }
+    
+    fn __repr__(&self) -> String {
+        format!(
+            "SessionReplay(frames={}, duration={:.1}, events={}, recorded={}, first_mismatch={:?})",
+            self.frames,
+            self.duration,
+            self.events.len(),
+            self.recorded.len(),
+            self.first_mismatch
+        )
+    }
+}
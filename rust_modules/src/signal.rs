@@ -1,45 +1,190 @@
 use pyo3::prelude::*;
 use pyo3::exceptions::PyValueError;
-use rustfft::{FftPlanner, num_complex::{Complex, Complex32}};
-use ndarray::{Array1, ArrayView1};
+use pyo3::types::PyDict;
+use rustfft::FftPlanner;
+use rayon::prelude::*;
+use jenna_core::dsp::{
+    self, apply_window, cross_correlation, delay_in_samples, design_fir_kernel, fft_magnitudes, filter_buffer,
+    validate_filter_type, FilterParams,
+};
+use jenna_core::stretch::{self, MAX_SEMITONES, MAX_SPEED, MIN_SPEED};
+
+pub use jenna_core::dsp::{fir_filter, WindowType};
+
+/// Parse a window name as passed from Python
+fn window_from_name(name: &str) -> PyResult<WindowType> {
+    WindowType::from_name(name).map_err(PyValueError::new_err)
+}
+
+/// Resolve an optional window name into a window type, defaulting to rectangular
+fn resolve_window(window: Option<&str>) -> PyResult<WindowType> {
+    match window {
+        Some(name) => window_from_name(name),
+        None => Ok(WindowType::Rectangular),
+    }
+}
+
+/// Get the window coefficients for the given window type and length
+#[pyfunction]
+pub fn get_window(window: &str, length: usize) -> PyResult<Vec<f32>> {
+    Ok(window_from_name(window)?.coefficients(length))
+}
 
 /// Compute Fast Fourier Transform (FFT) on audio data
 /// 
 /// This function takes a numpy array of audio samples and computes the FFT
-/// using the RustFFT library for improved performance.
+/// using the RustFFT library for improved performance. An optional window
+/// ("hann", "hamming", "blackman" or "flattop") is applied before transforming.
 #[pyfunction]
-pub fn compute_fft(py: Python, input_buffer: &PyAny) -> PyResult<Py<PyAny>> {
+#[pyo3(signature = (input_buffer, window=None))]
+pub fn compute_fft(py: Python, input_buffer: &PyAny, window: Option<&str>) -> PyResult<Py<PyAny>> {
+    let window = resolve_window(window)?;
+
     // Convert PyAny to numpy array
     let numpy = PyModule::import(py, "numpy")?;
     let array: &PyAny = numpy.getattr("array")?.call1((input_buffer,))?;
     
     // Get buffer as contiguous array of f32
-    let buffer: Vec<f32> = array.extract()?;
-    
-    // Create complex input for FFT
-    let mut complex_input: Vec<Complex32> = buffer
-        .iter()
-        .map(|&x| Complex32::new(x, 0.0))
-        .collect();
+    let mut buffer: Vec<f32> = array.extract()?;
     
-    // Create FFT planner and get FFT instance
-    let mut planner = FftPlanner::new();
-    let fft = planner.plan_fft_forward(complex_input.len());
+    let magnitudes = py.allow_threads(move || {
+        // Apply the window before transforming
+        if window != WindowType::Rectangular {
+            let coefficients = window.coefficients(buffer.len());
+            apply_window(&mut buffer, &coefficients);
+        }
+        
+        // Create FFT planner and extract magnitudes
+        let mut planner = FftPlanner::new();
+        fft_magnitudes(&mut planner, &buffer)
+    });
     
-    // Perform FFT in-place
-    fft.process(&mut complex_input);
+    // Convert back to numpy array
+    let result = numpy.getattr("array")?.call1((magnitudes,))?;
+    Ok(result.into())
+}
+
+/// Compute the Short-Time Fourier Transform (STFT) of audio data
+/// 
+/// The input is split into frames of `frame_size` samples advanced by
+/// `hop_size`, each frame is windowed (Hann by default) and transformed.
+/// Returns a 2D numpy array of magnitudes with one row per frame.
+#[pyfunction]
+#[pyo3(signature = (input_buffer, frame_size, hop_size, window=None))]
+pub fn compute_stft(
+    py: Python,
+    input_buffer: &PyAny,
+    frame_size: usize,
+    hop_size: usize,
+    window: Option<&str>,
+) -> PyResult<Py<PyAny>> {
+    if frame_size == 0 || hop_size == 0 {
+        return Err(PyValueError::new_err("Frame size and hop size must be greater than zero"));
+    }
+    let window = match window {
+        Some(name) => window_from_name(name)?,
+        None => WindowType::Hann,
+    };
+
+    // Convert PyAny to numpy array
+    let numpy = PyModule::import(py, "numpy")?;
+    let array: &PyAny = numpy.getattr("array")?.call1((input_buffer,))?;
+    let buffer: Vec<f32> = array.extract()?;
     
-    // Extract magnitudes
-    let magnitudes: Vec<f32> = complex_input
-        .iter()
-        .map(|c| (c.re * c.re + c.im * c.im).sqrt())
-        .collect();
+    let frames = py.allow_threads(move || {
+        let coefficients = window.coefficients(frame_size);
+        let mut planner = FftPlanner::new();
+        let mut frames: Vec<Vec<f32>> = Vec::new();
+        
+        let mut start = 0;
+        while start + frame_size <= buffer.len() {
+            let mut frame = buffer[start..start + frame_size].to_vec();
+            apply_window(&mut frame, &coefficients);
+            frames.push(fft_magnitudes(&mut planner, &frame));
+            start += hop_size;
+        }
+        frames
+    });
     
     // Convert back to numpy array
-    let result = numpy.getattr("array")?.call1((magnitudes,))?;
+    let result = numpy.getattr("array")?.call1((frames,))?;
     Ok(result.into())
 }
 
+/// FFT processor that caches real-to-complex plans and window coefficients per frame size
+/// 
+/// Creating an FFT plan is expensive compared to transforming a short frame,
+/// so the processor keeps one plan (and scratch buffers) for every frame size
+/// it has seen. Input is real-valued, so only the `n / 2 + 1` non-redundant
+/// bins are computed and returned.
+#[pyclass]
+pub struct FftProcessor {
+    inner: dsp::FftProcessor,
+}
+
+impl FftProcessor {
+    /// Create a processor applying the named window
+    pub fn with_window(window: &str) -> PyResult<Self> {
+        Ok(FftProcessor { inner: dsp::FftProcessor::new(window_from_name(window)?) })
+    }
+    
+    /// Compute the magnitude spectrum of a single frame
+    pub fn magnitudes(&mut self, frame: &[f32]) -> PyResult<Vec<f32>> {
+        self.inner.magnitudes(frame).map_err(PyValueError::new_err)
+    }
+}
+
+#[pymethods]
+impl FftProcessor {
+    /// Create a new FFT processor with an optional window
+    #[new]
+    #[pyo3(signature = (window=None))]
+    fn new(window: Option<&str>) -> PyResult<Self> {
+        Ok(FftProcessor { inner: dsp::FftProcessor::new(resolve_window(window)?) })
+    }
+    
+    /// Compute the magnitude spectrum (`n / 2 + 1` bins) of a single frame
+    fn process(&mut self, py: Python, frame: Vec<f32>) -> PyResult<Vec<f32>> {
+        py.allow_threads(|| self.magnitudes(&frame))
+    }
+    
+    /// Compute the magnitude spectra of a batch of frames in one call
+    fn process_batch(&mut self, py: Python, frames: Vec<Vec<f32>>) -> PyResult<Vec<Vec<f32>>> {
+        py.allow_threads(|| frames.iter().map(|frame| self.magnitudes(frame)).collect())
+    }
+    
+    /// Set the window applied before transforming
+    fn set_window(&mut self, window: &str) -> PyResult<()> {
+        self.inner.set_window(window_from_name(window)?);
+        Ok(())
+    }
+    
+    /// Get the frame sizes that currently have a cached plan
+    fn cached_sizes(&self) -> Vec<usize> {
+        self.inner.cached_sizes()
+    }
+    
+    /// Drop all cached plans and window coefficients
+    fn clear_cache(&mut self) {
+        self.inner.clear_cache();
+    }
+}
+
+/// Read simple filter parameters from an optional Python dict, falling back to defaults
+fn filter_params(params: Option<&PyDict>) -> FilterParams {
+    let get = |key: &str| -> Option<f32> {
+        params
+            .and_then(|p| p.get_item(key))
+            .and_then(|value| value.extract().ok())
+    };
+    
+    FilterParams {
+        cutoff_low: get("cutoff_low"),
+        cutoff_high: get("cutoff_high"),
+        q_factor: get("q_factor").unwrap_or(1.0),
+    }
+}
+
 /// Apply a filter to audio data
 /// 
 /// This function applies a filter to audio data for noise reduction,
@@ -54,90 +199,203 @@ pub fn apply_filter(py: Python, input_buffer: &PyAny, filter_type: &str, params:
     let buffer: Vec<f32> = array.extract()?;
     
     // Get parameters with defaults
-    let params = params.unwrap_or_else(|| PyDict::new(py));
-    let cutoff_low = params.get_item("cutoff_low").unwrap_or_else(|| py.None());
-    let cutoff_high = params.get_item("cutoff_high").unwrap_or_else(|| py.None());
-    let q_factor = params.get_item("q_factor").unwrap_or_else(|| py.None());
-    
-    let cutoff_low: Option<f32> = cutoff_low.extract().unwrap_or(None);
-    let cutoff_high: Option<f32> = cutoff_high.extract().unwrap_or(None);
-    let q_factor: f32 = q_factor.extract().unwrap_or(1.0);
+    let params = filter_params(params);
+    validate_filter_type(filter_type).map_err(PyValueError::new_err)?;
     
     // Apply different filter types
-    let filtered = match filter_type {
-        "lowpass" => apply_lowpass_filter(&buffer, cutoff_high.unwrap_or(1000.0), q_factor),
-        "highpass" => apply_highpass_filter(&buffer, cutoff_low.unwrap_or(500.0), q_factor),
-        "bandpass" => apply_bandpass_filter(
-            &buffer, 
-            cutoff_low.unwrap_or(500.0), 
-            cutoff_high.unwrap_or(2000.0), 
-            q_factor
-        ),
-        "notch" => apply_notch_filter(
-            &buffer, 
-            cutoff_low.unwrap_or(500.0), 
-            cutoff_high.unwrap_or(2000.0), 
-            q_factor
-        ),
-        _ => return Err(PyValueError::new_err(format!("Unknown filter type: {}", filter_type)))
-    };
+    let filtered = py.allow_threads(|| filter_buffer(&buffer, filter_type, &params));
     
     // Convert back to numpy array
     let result = numpy.getattr("array")?.call1((filtered,))?;
     Ok(result.into())
 }
 
-// Simple implementation of a low-pass filter
-fn apply_lowpass_filter(buffer: &[f32], cutoff: f32, q: f32) -> Vec<f32> {
-    // This is a simple first-order low-pass filter
-    // In a real implementation, you would use more sophisticated filters
-    let alpha = 0.1; // Simplified coefficient based on cutoff
-    let mut output = vec![0.0; buffer.len()];
+/// Compute the FFT magnitudes of many frames in parallel
+/// 
+/// Intended for offline analysis of recorded sessions, where per-frame calls
+/// are dominated by FFI overhead. Frames are processed on the rayon pool.
+#[pyfunction]
+#[pyo3(signature = (frames, window=None))]
+pub fn compute_fft_batch(py: Python, frames: Vec<Vec<f32>>, window: Option<&str>) -> PyResult<Vec<Vec<f32>>> {
+    let window = resolve_window(window)?;
+    
+    Ok(py.allow_threads(move || {
+        frames
+            .into_par_iter()
+            .map_init(FftPlanner::new, |planner, mut frame| {
+                if window != WindowType::Rectangular {
+                    let coefficients = window.coefficients(frame.len());
+                    apply_window(&mut frame, &coefficients);
+                }
+                fft_magnitudes(planner, &frame)
+            })
+            .collect()
+    }))
+}
+
+/// Apply a filter to many independent buffers in parallel
+/// 
+/// Accepts the same filter types and parameters as `apply_filter`.
+#[pyfunction]
+#[pyo3(signature = (buffers, filter_type, params=None))]
+pub fn apply_filter_batch(
+    py: Python,
+    buffers: Vec<Vec<f32>>,
+    filter_type: &str,
+    params: Option<&PyDict>,
+) -> PyResult<Vec<Vec<f32>>> {
+    let params = filter_params(params);
+    validate_filter_type(filter_type).map_err(PyValueError::new_err)?;
     
-    if buffer.is_empty() {
-        return output;
-    }
+    Ok(py.allow_threads(|| {
+        buffers
+            .par_iter()
+            .map(|buffer| filter_buffer(buffer, filter_type, &params))
+            .collect()
+    }))
+}
+
+/// Apply an FIR kernel to many independent buffers in parallel
+#[pyfunction]
+pub fn apply_fir_filter_batch(py: Python, buffers: Vec<Vec<f32>>, kernel: Vec<f32>) -> PyResult<Vec<Vec<f32>>> {
+    Ok(py.allow_threads(|| {
+        buffers
+            .par_iter()
+            .map(|buffer| fir_filter(buffer, &kernel))
+            .collect()
+    }))
+}
+
+/// Configure the number of worker threads used by the batch functions
+/// 
+/// Must be called before the first batch call; the pool cannot be resized later.
+#[pyfunction]
+pub fn configure_thread_pool(num_threads: usize) -> PyResult<()> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build_global()
+        .map_err(|err| PyValueError::new_err(format!("Failed to configure thread pool: {}", err)))
+}
+
+/// Apply an FIR filter to audio data
+/// 
+/// Short kernels are applied directly; long kernels (e.g. room impulse
+/// responses) use FFT-based overlap-save convolution. The output has the
+/// same length as the input.
+#[pyfunction]
+pub fn apply_fir_filter(py: Python, input_buffer: &PyAny, kernel: Vec<f32>) -> PyResult<Py<PyAny>> {
+    // Convert PyAny to numpy array
+    let numpy = PyModule::import(py, "numpy")?;
+    let array: &PyAny = numpy.getattr("array")?.call1((input_buffer,))?;
+    let buffer: Vec<f32> = array.extract()?;
     
-    output[0] = buffer[0];
-    for i in 1..buffer.len() {
-        output[i] = output[i-1] + alpha * (buffer[i] - output[i-1]);
-    }
+    let filtered = py.allow_threads(|| fir_filter(&buffer, &kernel));
     
-    output
+    // Convert back to numpy array
+    let result = numpy.getattr("array")?.call1((filtered,))?;
+    Ok(result.into())
 }
 
-// Simple implementation of a high-pass filter
-fn apply_highpass_filter(buffer: &[f32], cutoff: f32, q: f32) -> Vec<f32> {
-    // This is a simple first-order high-pass filter
-    let alpha = 0.9; // Simplified coefficient based on cutoff
-    let mut output = vec![0.0; buffer.len()];
+/// Design an FIR kernel from a frequency response
+/// 
+/// `frequencies` (Hz) must run from 0 to the Nyquist frequency with matching
+/// linear `gains`. The kernel is tapered with a Hamming window by default.
+#[pyfunction]
+#[pyo3(signature = (num_taps, frequencies, gains, sample_rate, window=None))]
+pub fn design_fir(
+    num_taps: usize,
+    frequencies: Vec<f32>,
+    gains: Vec<f32>,
+    sample_rate: f32,
+    window: Option<&str>,
+) -> PyResult<Vec<f32>> {
+    let window = match window {
+        Some(name) => window_from_name(name)?,
+        None => WindowType::Hamming,
+    };
     
-    if buffer.is_empty() {
-        return output;
-    }
+    design_fir_kernel(num_taps, &frequencies, &gains, sample_rate, window)
+        .map_err(PyValueError::new_err)
+}
+
+/// Compute the full cross-correlation of two signals
+/// 
+/// Returns `len(a) + len(b) - 1` values ordered from lag `-(len(b) - 1)` to
+/// `len(a) - 1`. Set `phat=True` for the phase-transform weighted variant.
+#[pyfunction]
+#[pyo3(signature = (a, b, phat=false))]
+pub fn cross_correlate(py: Python, a: &PyAny, b: &PyAny, phat: bool) -> PyResult<Py<PyAny>> {
+    let numpy = PyModule::import(py, "numpy")?;
+    let a: Vec<f32> = numpy.getattr("array")?.call1((a,))?.extract()?;
+    let b: Vec<f32> = numpy.getattr("array")?.call1((b,))?.extract()?;
     
-    output[0] = buffer[0];
-    for i in 1..buffer.len() {
-        output[i] = alpha * (output[i-1] + buffer[i] - buffer[i-1]);
-    }
+    let correlation = py.allow_threads(|| cross_correlation(&a, &b, phat));
+    
+    let result = numpy.getattr("array")?.call1((correlation,))?;
+    Ok(result.into())
+}
+
+/// Estimate the time delay of `a` relative to `b`
+/// 
+/// Returns `(delay_seconds, peak)`; a positive delay means `a` lags behind
+/// `b`. `max_delay` (seconds) limits the search range. GCC-PHAT weighting is
+/// used by default since it is robust to room reverberation.
+#[pyfunction]
+#[pyo3(signature = (a, b, sample_rate, max_delay=None, phat=true))]
+pub fn estimate_delay(
+    py: Python,
+    a: &PyAny,
+    b: &PyAny,
+    sample_rate: u32,
+    max_delay: Option<f32>,
+    phat: bool,
+) -> PyResult<(f32, f32)> {
+    let numpy = PyModule::import(py, "numpy")?;
+    let a: Vec<f32> = numpy.getattr("array")?.call1((a,))?.extract()?;
+    let b: Vec<f32> = numpy.getattr("array")?.call1((b,))?.extract()?;
+    
+    let max_lag = max_delay.map(|d| (d.max(0.0) * sample_rate as f32).ceil() as usize);
+    let (lag, peak) = py.allow_threads(|| delay_in_samples(&a, &b, max_lag, phat))
+        .ok_or_else(|| PyValueError::new_err("Both signals must be non-empty"))?;
     
-    output
+    Ok((lag / sample_rate as f32, peak))
 }
 
-// Simple implementation of a band-pass filter
-fn apply_bandpass_filter(buffer: &[f32], low_cutoff: f32, high_cutoff: f32, q: f32) -> Vec<f32> {
-    // Apply low-pass and high-pass in sequence
-    let highpassed = apply_highpass_filter(buffer, low_cutoff, q);
-    apply_lowpass_filter(&highpassed, high_cutoff, q)
+/// Change the speed of a recording without changing its pitch
+/// 
+/// `factor` is the playback speed: 1.5 plays 1.5 times faster ("play my
+/// memo at 1.5x") and 0.5 at half speed, from 0.25 to 4.
+#[pyfunction]
+#[pyo3(signature = (input_buffer, factor, sample_rate=16000))]
+pub fn time_stretch(py: Python, input_buffer: &PyAny, factor: f32, sample_rate: u32) -> PyResult<Py<PyAny>> {
+    if !(MIN_SPEED..=MAX_SPEED).contains(&factor) {
+        return Err(PyValueError::new_err(format!("factor must be between {} and {}", MIN_SPEED, MAX_SPEED)));
+    }
+    let numpy = PyModule::import(py, "numpy")?;
+    let buffer: Vec<f32> = numpy.getattr("array")?.call1((input_buffer,))?.extract()?;
+    
+    let stretched = py.allow_threads(|| stretch::time_stretch(&buffer, factor, sample_rate));
+    
+    let result = numpy.getattr("array")?.call1((stretched,))?;
+    Ok(result.into())
 }
 
-// Simple implementation of a notch filter
-fn apply_notch_filter(buffer: &[f32], low_cutoff: f32, high_cutoff: f32, q: f32) -> Vec<f32> {
-    // A notch filter can be implemented as the original signal minus the band-pass
-    let bandpassed = apply_bandpass_filter(buffer, low_cutoff, high_cutoff, q);
+/// Shift the pitch of a recording by `semitones` without changing its speed
+/// 
+/// Positive values raise the pitch; 12 is one octave. Limited to two
+/// octaves either way.
+#[pyfunction]
+#[pyo3(signature = (input_buffer, semitones, sample_rate=16000))]
+pub fn pitch_shift(py: Python, input_buffer: &PyAny, semitones: f32, sample_rate: u32) -> PyResult<Py<PyAny>> {
+    if !(-MAX_SEMITONES..=MAX_SEMITONES).contains(&semitones) {
+        return Err(PyValueError::new_err(format!("semitones must be between -{} and {}", MAX_SEMITONES, MAX_SEMITONES)));
+    }
+    let numpy = PyModule::import(py, "numpy")?;
+    let buffer: Vec<f32> = numpy.getattr("array")?.call1((input_buffer,))?.extract()?;
     
-    buffer.iter()
-        .zip(bandpassed.iter())
-        .map(|(&original, &filtered)| original - filtered)
-        .collect()
-}
\ No newline at end of file
+    let shifted = py.allow_threads(|| stretch::pitch_shift(&buffer, semitones, sample_rate))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    
+    let result = numpy.getattr("array")?.call1((shifted,))?;
+    Ok(result.into())
+}
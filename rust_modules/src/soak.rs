@@ -0,0 +1,414 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use jenna_core::audio_class::AudioClass;
+
+use crate::assistant::{Assistant, Phase};
+use crate::dynamics::Compressor;
+use crate::errors::NotInitializedError;
+use crate::filters::{design_sos, BandType, FilterFamily, SosFilter};
+use crate::pipeline::{DictationRequest, Endpointing, FrameEnv, FrameProcessor, PipelineEvent};
+use crate::signal::{fir_filter, FftProcessor};
+use crate::speech::{SharedEngine, SpeechRecognizer};
+use crate::wake_word::{DetectorState, WakeWordDetector};
+
+/// Length of one cycle of synthetic audio: a burst of speech-like sound, then near silence
+const CYCLE_SECONDS: f64 = 5.0;
+
+/// Part of each cycle that sounds like speech
+const BURST_SECONDS: f64 = 1.5;
+
+/// Resident memory of the current process in bytes, where the platform exposes it
+pub fn resident_memory_bytes() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+        let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+        if page_size <= 0 {
+            return None;
+        }
+        Some(pages * page_size as u64)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Number of open file descriptors/handles, where the platform exposes it
+pub fn open_handle_count() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        Some(std::fs::read_dir("/proc/self/fd").ok()?.count() as u64)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Small deterministic PRNG for synthetic noise (xorshift32)
+pub struct NoiseSource(pub u32);
+
+impl NoiseSource {
+    pub fn next(&mut self) -> f32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        (x as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+/// One periodic measurement taken during a soak run
+#[derive(Clone, Debug)]
+pub struct SoakSample {
+    pub elapsed_s: f64,
+    pub frames: u64,
+    pub memory_bytes: Option<u64>,
+    pub handles: Option<u64>,
+    pub mean_frame_us: f64,
+    pub drift_ms: f64,
+}
+
+/// Accumulated soak-test results
+#[derive(Default)]
+struct SoakState {
+    samples: Vec<SoakSample>,
+    frames: u64,
+    /// Pipeline events and VAD boundaries seen, by kind
+    events: HashMap<String, u64>,
+    running: bool,
+}
+
+/// Engines driven frame by frame the way a running pipeline drives them
+struct SoakPipeline {
+    detector: Arc<Mutex<DetectorState>>,
+    recognizer: SharedEngine,
+}
+
+/// Frame environment for a soak run: no playback or assistant, a turn per burst
+/// 
+/// Synthetic audio won't say the wake word, so each burst opens a turn as
+/// `trigger()` would, sending it through the recognizer and endpointing.
+struct SoakEnv<'a> {
+    state: &'a Mutex<SoakState>,
+    trigger: bool,
+}
+
+impl SoakEnv<'_> {
+    fn count(&self, kind: &str) {
+        *self.state.lock().unwrap().events.entry(kind.to_string()).or_insert(0) += 1;
+    }
+}
+
+impl FrameEnv for SoakEnv<'_> {
+    fn playing(&mut self) -> bool {
+        false
+    }
+    
+    fn interrupt_playback(&mut self) {}
+    
+    fn take_trigger(&mut self) -> bool {
+        std::mem::take(&mut self.trigger)
+    }
+    
+    fn take_dictation_request(&mut self) -> Option<DictationRequest> {
+        None
+    }
+    
+    fn begin_follow_up(&mut self, _detector: &mut DetectorState) -> bool {
+        false
+    }
+    
+    fn assistant_phase(&mut self) -> Option<Phase> {
+        None
+    }
+    
+    fn assistant(&self) -> Option<&Assistant> {
+        None
+    }
+    
+    fn set_listening(&mut self, _listening: bool) {}
+    
+    fn set_dictating(&mut self, _dictating: bool) {}
+    
+    fn vad_boundary(&mut self, kind: &'static str) {
+        self.count(kind);
+    }
+    
+    fn audio_class_changed(&mut self, _class: AudioClass) {}
+    
+    fn presence_changed(&mut self, _present: bool) {}
+    
+    fn voice_activity(&mut self, _speaking: bool, _seconds: f32) {}
+    
+    fn emit(&mut self, event: PipelineEvent) {
+        self.count(&event.kind);
+    }
+}
+
+/// Growth per hour of a series, estimated by least squares
+fn slope_per_hour(points: &[(f64, f64)]) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let var: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if var <= 0.0 {
+        return None;
+    }
+    Some(cov / var * 3600.0)
+}
+
+/// Long-running soak test of the voice pipeline with synthetic audio
+/// 
+/// Frames of synthetic audio (bursts of a speech-band tone every few
+/// seconds over low noise) are pushed through the FFT, filtering and
+/// dynamics stages at real-time pace. Given a wake word detector and a
+/// recognizer, each frame also goes through wake word detection, VAD and
+/// endpointing as in a running pipeline, with every burst recognized as a
+/// turn. Memory, open handles, per-frame processing time and clock drift
+/// (audio time vs. wall time) are sampled periodically so slow leaks and
+/// degradation can be spotted.
+#[pyclass]
+pub struct SoakTest {
+    #[pyo3(get)]
+    sample_rate: u32,
+    #[pyo3(get)]
+    frame_size: usize,
+    #[pyo3(get)]
+    sample_interval_s: f64,
+    #[pyo3(get)]
+    realtime: bool,
+    pipeline: Option<SoakPipeline>,
+    stop_flag: Arc<AtomicBool>,
+    state: Arc<Mutex<SoakState>>,
+}
+
+impl SoakTest {
+    fn run_loop(&self, duration: Duration) -> Result<(), String> {
+        let mut fft = FftProcessor::with_window("hann").map_err(|err| err.to_string())?;
+        let sections = design_sos(FilterFamily::Butterworth, BandType::Bandpass, 4, &[300.0, 3400.0], self.sample_rate as f64)?;
+        let mut band_filter = SosFilter::from_sections(sections)?;
+        let mut compressor = Compressor::with_defaults(self.sample_rate);
+        let kernel: Vec<f32> = (0..129).map(|i| if i == 64 { 1.0 } else { 0.0 }).collect();
+        let mut noise = NoiseSource(0x1234_5678);
+        let mut processor = match &self.pipeline {
+            Some(pipeline) => Some(FrameProcessor::new(
+                Arc::clone(&pipeline.detector),
+                Arc::clone(&pipeline.recognizer),
+                Endpointing { vad_threshold_db: 12.0, silence_timeout: 0.8, no_speech_timeout: 5.0, max_utterance: 15.0 },
+                false,
+            )?),
+            None => None,
+        };
+        let mut env = SoakEnv { state: &self.state, trigger: false };
+        let mut pcm = vec![0i16; self.frame_size];
+        let frame_seconds = self.frame_size as f64 / self.sample_rate as f64;
+        
+        let frame_duration = Duration::from_secs_f64(self.frame_size as f64 / self.sample_rate as f64);
+        let sample_interval = Duration::from_secs_f64(self.sample_interval_s);
+        let start = Instant::now();
+        // A sample time past what an Instant can hold is never reached
+        let mut next_sample = start.checked_add(sample_interval);
+        let mut window_time = Duration::ZERO;
+        let mut window_frames = 0u64;
+        let mut phase = 0.0f32;
+        let mut frame = vec![0.0f32; self.frame_size];
+        
+        let mut frame_index = 0u64;
+        
+        while start.elapsed() < duration && !self.stop_flag.load(Ordering::Relaxed) {
+            // Synthetic input: bursts of a syllable-modulated tone over low noise
+            let cycle_time = (frame_index as f64 * frame_seconds) % CYCLE_SECONDS;
+            let burst = cycle_time < BURST_SECONDS;
+            env.trigger |= burst && cycle_time < frame_seconds;
+            for (i, sample) in frame.iter_mut().enumerate() {
+                phase += 2.0 * std::f32::consts::PI * 440.0 / self.sample_rate as f32;
+                if phase > 2.0 * std::f32::consts::PI {
+                    phase -= 2.0 * std::f32::consts::PI;
+                }
+                let t = cycle_time + i as f64 / self.sample_rate as f64;
+                let envelope = if burst { (0.5 - 0.5 * (2.0 * std::f64::consts::PI * 4.0 * t).cos()) as f32 } else { 0.0 };
+                *sample = 0.3 * envelope * phase.sin() + 0.005 * noise.next();
+            }
+            frame_index += 1;
+            
+            let frame_start = Instant::now();
+            if let Some(processor) = &mut processor {
+                for (pcm, &sample) in pcm.iter_mut().zip(frame.iter()) {
+                    *pcm = (sample * i16::MAX as f32) as i16;
+                }
+                processor.process(&pcm, frame_start, &mut env)?;
+            }
+            band_filter.process_in_place(&mut frame);
+            let filtered = fir_filter(&frame, &kernel);
+            let _ = fft.magnitudes(&filtered).map_err(|err| err.to_string())?;
+            frame.copy_from_slice(&filtered);
+            compressor.process_in_place(&mut frame);
+            window_time += frame_start.elapsed();
+            window_frames += 1;
+            
+            let frames = {
+                let mut state = self.state.lock().unwrap();
+                state.frames += 1;
+                state.frames
+            };
+            
+            let audio_time = frame_duration * frames as u32;
+            if self.realtime {
+                if let Some(wait) = audio_time.checked_sub(start.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+            }
+            
+            let now = Instant::now();
+            if next_sample.map_or(false, |at| now >= at) {
+                let elapsed = now.duration_since(start);
+                let sample = SoakSample {
+                    elapsed_s: elapsed.as_secs_f64(),
+                    frames,
+                    memory_bytes: resident_memory_bytes(),
+                    handles: open_handle_count(),
+                    mean_frame_us: window_time.as_secs_f64() * 1e6 / window_frames.max(1) as f64,
+                    drift_ms: (elapsed.as_secs_f64() - audio_time.as_secs_f64()) * 1000.0,
+                };
+                self.state.lock().unwrap().samples.push(sample);
+                window_time = Duration::ZERO;
+                window_frames = 0;
+                next_sample = now.checked_add(sample_interval);
+            }
+        }
+        
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl SoakTest {
+    /// Create a soak test
+    /// 
+    /// With `realtime` the pipeline is paced at the audio rate; otherwise it
+    /// runs as fast as possible to accelerate leak detection. Pass an
+    /// initialized `detector` and `recognizer` to soak the engines too;
+    /// their frame length and sample rate then replace `frame_size` and
+    /// `sample_rate`. Don't pass engines a running pipeline is using.
+    #[new]
+    #[pyo3(signature = (sample_rate=16000, frame_size=512, sample_interval_s=60.0, realtime=true, detector=None, recognizer=None))]
+    fn new(
+        sample_rate: u32,
+        frame_size: usize,
+        sample_interval_s: f64,
+        realtime: bool,
+        detector: Option<PyRef<WakeWordDetector>>,
+        recognizer: Option<PyRef<SpeechRecognizer>>,
+    ) -> PyResult<Self> {
+        if sample_rate == 0 || frame_size == 0 || !sample_interval_s.is_finite() || sample_interval_s <= 0.0 {
+            return Err(PyValueError::new_err("Sample rate, frame size and sample interval must be positive"));
+        }
+        Duration::try_from_secs_f64(sample_interval_s)
+            .map_err(|_| PyValueError::new_err("Sample interval is too large"))?;
+        
+        let (pipeline, frame_size, sample_rate) = match (detector, recognizer) {
+            (Some(detector), Some(recognizer)) => {
+                let detector = detector.shared_state();
+                let (frame_length, engine_rate) = detector.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+                let (recognizer, recognizer_rate) = recognizer.shared_engine()?;
+                if engine_rate != recognizer_rate {
+                    return Err(PyValueError::new_err(format!(
+                        "Recognizer sample rate ({} Hz) must match the wake word engine ({} Hz)",
+                        recognizer_rate, engine_rate
+                    )));
+                }
+                (Some(SoakPipeline { detector, recognizer }), frame_length, engine_rate)
+            }
+            (None, None) => (None, frame_size, sample_rate),
+            _ => return Err(PyValueError::new_err("Detector and recognizer must be given together")),
+        };
+        
+        Ok(SoakTest {
+            sample_rate,
+            frame_size,
+            sample_interval_s,
+            realtime,
+            pipeline,
+            stop_flag: Arc::new(AtomicBool::new(false)),
+            state: Arc::new(Mutex::new(SoakState::default())),
+        })
+    }
+    
+    /// Run the soak test for `duration_s` seconds without holding the GIL
+    /// 
+    /// Returns the final report (see `report`).
+    fn run(&self, py: Python, duration_s: f64) -> PyResult<Py<PyDict>> {
+        let duration = Duration::try_from_secs_f64(duration_s)
+            .map_err(|_| PyValueError::new_err("Duration must be finite and non-negative"))?;
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.running {
+                return Err(PyValueError::new_err("Soak test is already running"));
+            }
+            *state = SoakState { running: true, ..SoakState::default() };
+        }
+        self.stop_flag.store(false, Ordering::Relaxed);
+        
+        let result = py.allow_threads(|| self.run_loop(duration));
+        self.state.lock().unwrap().running = false;
+        result.map_err(PyValueError::new_err)?;
+        
+        self.report(py)
+    }
+    
+    /// Ask a running soak test to stop after the current frame
+    fn stop(&self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+    }
+    
+    /// Get the current report
+    /// 
+    /// Contains the periodic samples plus memory growth (bytes/hour), handle
+    /// growth (handles/hour), processing time trend (us/hour), final drift
+    /// and "events", the pipeline events seen by kind when engines are soaked.
+    fn report(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let state = self.state.lock().unwrap();
+        let report = PyDict::new(py);
+        
+        let samples: Vec<Py<PyDict>> = state.samples
+            .iter()
+            .map(|s| -> PyResult<Py<PyDict>> {
+                let entry = PyDict::new(py);
+                entry.set_item("elapsed_s", s.elapsed_s)?;
+                entry.set_item("frames", s.frames)?;
+                entry.set_item("memory_bytes", s.memory_bytes)?;
+                entry.set_item("handles", s.handles)?;
+                entry.set_item("mean_frame_us", s.mean_frame_us)?;
+                entry.set_item("drift_ms", s.drift_ms)?;
+                Ok(entry.into())
+            })
+            .collect::<PyResult<_>>()?;
+        
+        let series = |f: &dyn Fn(&SoakSample) -> Option<f64>| -> Vec<(f64, f64)> {
+            state.samples.iter().filter_map(|s| f(s).map(|v| (s.elapsed_s, v))).collect()
+        };
+        
+        report.set_item("running", state.running)?;
+        report.set_item("frames", state.frames)?;
+        report.set_item("samples", samples)?;
+        report.set_item("events", state.events.clone())?;
+        report.set_item("memory_growth_per_hour", slope_per_hour(&series(&|s| s.memory_bytes.map(|m| m as f64))))?;
+        report.set_item("handle_growth_per_hour", slope_per_hour(&series(&|s| s.handles.map(|h| h as f64))))?;
+        report.set_item("frame_time_trend_per_hour", slope_per_hour(&series(&|s| Some(s.mean_frame_us))))?;
+        report.set_item("final_drift_ms", state.samples.last().map(|s| s.drift_ms))?;
+        Ok(report.into())
+    }
+}
@@ -0,0 +1,381 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use jenna_core::inference::{load_shared, run_onnx, OnnxPlan};
+
+use crate::audio::{resample_mono, AudioError, MonoResampler};
+use crate::errors::ModelError;
+use crate::wake_word::unix_time;
+
+/// Sample rate sound event models take (YAMNet and other AudioSet models)
+pub const SOUND_SAMPLE_RATE: u32 = 16000;
+
+/// Default analysis window in seconds (one YAMNet patch)
+const DEFAULT_WINDOW: f32 = 0.975;
+
+/// Default hop between windows in seconds
+const DEFAULT_HOP: f32 = 0.5;
+
+/// Default score needed to report a watched label
+const DEFAULT_THRESHOLD: f32 = 0.5;
+
+/// Default seconds an event stays quiet after firing, so one alarm isn't reported every hop
+const DEFAULT_HOLDOFF: f32 = 3.0;
+
+/// Parse a class list: one label per line, or an AudioSet class map CSV
+/// (`index,mid,display_name`, as shipped with YAMNet) whose last column is
+/// the label
+fn parse_labels(text: &str) -> Vec<String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty()).peekable();
+    let csv = lines.peek().map_or(false, |header| header.to_lowercase().starts_with("index,"));
+    if csv {
+        lines.next();
+    }
+    lines
+        .map(|line| if csv { last_csv_field(line) } else { line.to_string() })
+        .collect()
+}
+
+/// Last field of a CSV line, unquoting it
+fn last_csv_field(line: &str) -> String {
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => field.clear(),
+            _ => field.push(c),
+        }
+    }
+    field
+}
+
+/// A watched model label and the event name it is reported as
+#[derive(Clone, Debug)]
+struct Watch {
+    name: String,
+    threshold: f32,
+}
+
+/// A sound the classifier recognized
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct SoundEvent {
+    /// Event name given to `watch` (the model label unless renamed)
+    #[pyo3(get)]
+    pub label: String,
+    /// Model class that fired
+    #[pyo3(get)]
+    pub model_label: String,
+    #[pyo3(get)]
+    pub confidence: f32,
+    #[pyo3(get)]
+    pub timestamp: f64,
+}
+
+#[pymethods]
+impl SoundEvent {
+    fn __repr__(&self) -> String {
+        format!(
+            "SoundEvent(label={:?}, model_label={:?}, confidence={:.2}, timestamp={:.3})",
+            self.label, self.model_label, self.confidence, self.timestamp
+        )
+    }
+}
+
+/// Acoustic event classifier over a sliding window of 16 kHz audio
+/// 
+/// The model takes a window of waveform samples and returns one score per
+/// class (or several rows of scores, of which the highest counts), as
+/// YAMNet does. Only watched labels are reported; several labels can share
+/// an event name (e.g. "Glass" and "Shatter" as "glass_break"), and an
+/// event stays quiet for `holdoff` seconds after it fires.
+pub struct SoundEventDetector {
+    plan: Arc<OnnxPlan>,
+    shape: Vec<usize>,
+    labels: Vec<String>,
+    watches: HashMap<usize, Watch>,
+    window: usize,
+    hop: usize,
+    holdoff: f32,
+    buffer: VecDeque<f32>,
+    /// Samples received since the window was last classified
+    since_hop: usize,
+    /// Audio time in seconds, for the holdoff
+    clock: f64,
+    last_fired: HashMap<String, f64>,
+}
+
+impl SoundEventDetector {
+    pub fn load(model_path: &Path, labels_path: &Path, window: f32, hop: f32, batched: bool) -> Result<Self, String> {
+        if !window.is_finite() || !hop.is_finite() || window <= 0.0 || hop <= 0.0 {
+            return Err("Window and hop must be positive".to_string());
+        }
+        let labels = fs::read_to_string(labels_path)
+            .map(|text| parse_labels(&text))
+            .map_err(|err| format!("Cannot read {}: {}", labels_path.display(), err))?;
+        if labels.is_empty() {
+            return Err(format!("{} lists no labels", labels_path.display()));
+        }
+        
+        let window = (window * SOUND_SAMPLE_RATE as f32) as usize;
+        let shape = if batched { vec![1, window] } else { vec![window] };
+        let plan = load_shared(model_path, &shape)?;
+        tracing::info!(model = %model_path.display(), labels = labels.len(), "Loaded sound event model");
+        
+        Ok(SoundEventDetector {
+            plan,
+            shape,
+            labels,
+            watches: HashMap::new(),
+            window,
+            hop: ((hop * SOUND_SAMPLE_RATE as f32) as usize).max(1),
+            holdoff: DEFAULT_HOLDOFF,
+            buffer: VecDeque::with_capacity(window),
+            since_hop: 0,
+            clock: 0.0,
+            last_fired: HashMap::new(),
+        })
+    }
+    
+    /// Highest score per label over one window of audio
+    pub fn scores(&self, window: &[f32]) -> Result<Vec<f32>, String> {
+        let mut data = window.to_vec();
+        data.resize(self.window, 0.0);
+        let output = run_onnx(&self.plan, &self.shape, data)?;
+        if output.is_empty() || output.len() % self.labels.len() != 0 {
+            return Err(format!(
+                "Model returned {} scores, which doesn't match the {} labels",
+                output.len(),
+                self.labels.len()
+            ));
+        }
+        
+        let mut scores = vec![f32::MIN; self.labels.len()];
+        for row in output.chunks(self.labels.len()) {
+            scores.iter_mut().zip(row).for_each(|(best, &score)| *best = best.max(score));
+        }
+        Ok(scores)
+    }
+    
+    /// Push 16 kHz audio, returning the watched events recognized
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<SoundEvent>, String> {
+        let mut events = Vec::new();
+        for &sample in samples {
+            if self.buffer.len() == self.window {
+                self.buffer.pop_front();
+            }
+            self.buffer.push_back(sample);
+            self.since_hop += 1;
+            self.clock += 1.0 / SOUND_SAMPLE_RATE as f64;
+            
+            if self.buffer.len() < self.window || self.since_hop < self.hop || self.watches.is_empty() {
+                continue;
+            }
+            self.since_hop = 0;
+            let window: Vec<f32> = self.buffer.iter().copied().collect();
+            let scores = self.scores(&window)?;
+            
+            // Report each event name once per window, with its best label
+            let mut best: HashMap<&str, (usize, f32)> = HashMap::new();
+            for (&index, watch) in &self.watches {
+                let score = scores[index];
+                if score >= watch.threshold && best.get(watch.name.as_str()).map_or(true, |&(_, top)| score > top) {
+                    best.insert(&watch.name, (index, score));
+                }
+            }
+            for (name, (index, score)) in best {
+                let quiet = self.last_fired.get(name).map_or(true, |&fired| self.clock - fired >= self.holdoff as f64);
+                if quiet {
+                    self.last_fired.insert(name.to_string(), self.clock);
+                    events.push(SoundEvent {
+                        label: name.to_string(),
+                        model_label: self.labels[index].clone(),
+                        confidence: score,
+                        timestamp: unix_time(),
+                    });
+                }
+            }
+        }
+        Ok(events)
+    }
+    
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.since_hop = 0;
+        self.last_fired.clear();
+    }
+}
+
+/// Classifier shared between its Python object and a running pipeline
+pub type SharedSoundDetector = Arc<Mutex<SoundEventDetector>>;
+
+/// Recognizes sounds such as doorbells, alarms and breaking glass
+/// 
+/// Loads an ONNX audio classifier (YAMNet-style: a window of 16 kHz
+/// waveform in, one score per AudioSet class out) and the class list
+/// (`labels_path`: one label per line or YAMNet's class map CSV). Nothing
+/// is reported until labels are `watch`ed. Feed audio with `process`, or
+/// attach the classifier to a `VoicePipeline` with `set_sound_classifier`
+/// to run it on the capture stream alongside wake word detection. Set
+/// `batched` for models taking `[1, samples]` instead of `[samples]`.
+/// With YAMNet, watching "Smoke detector, smoke alarm" as "smoke_alarm",
+/// "Doorbell" as "doorbell" and both "Glass" and "Shatter" as
+/// "glass_break" covers the usual safety sounds.
+#[pyclass]
+pub struct SoundEventClassifier {
+    detector: SharedSoundDetector,
+    /// Rate of the audio passed to `process`
+    #[pyo3(get)]
+    sample_rate: u32,
+    resampler: Option<MonoResampler>,
+}
+
+impl SoundEventClassifier {
+    pub fn shared_detector(&self) -> SharedSoundDetector {
+        self.detector.clone()
+    }
+}
+
+#[pymethods]
+impl SoundEventClassifier {
+    /// Load the model and its class list
+    /// 
+    /// `window` and `hop` are in seconds; `sample_rate` is the rate of the
+    /// audio passed to `process` and `classify`.
+    #[new]
+    #[pyo3(signature = (model_path, labels_path, window=DEFAULT_WINDOW, hop=DEFAULT_HOP, sample_rate=SOUND_SAMPLE_RATE, batched=false))]
+    fn new(
+        py: Python,
+        model_path: String,
+        labels_path: String,
+        window: f32,
+        hop: f32,
+        sample_rate: u32,
+        batched: bool,
+    ) -> PyResult<Self> {
+        let detector = py
+            .allow_threads(|| SoundEventDetector::load(Path::new(&model_path), Path::new(&labels_path), window, hop, batched))
+            .map_err(ModelError::new_err)?;
+        let resampler = if sample_rate == SOUND_SAMPLE_RATE {
+            None
+        } else {
+            Some(MonoResampler::new(sample_rate, SOUND_SAMPLE_RATE).map_err(AudioError::from)?)
+        };
+        
+        Ok(SoundEventClassifier { detector: Arc::new(Mutex::new(detector)), sample_rate, resampler })
+    }
+    
+    /// All labels the model knows
+    #[getter]
+    fn labels(&self) -> Vec<String> {
+        self.detector.lock().unwrap().labels.clone()
+    }
+    
+    /// Watched labels mapped to their event name and threshold
+    #[getter]
+    fn watched(&self) -> HashMap<String, (String, f32)> {
+        let detector = self.detector.lock().unwrap();
+        detector
+            .watches
+            .iter()
+            .map(|(&index, watch)| (detector.labels[index].clone(), (watch.name.clone(), watch.threshold)))
+            .collect()
+    }
+    
+    /// Seconds an event stays quiet after firing
+    #[getter]
+    fn holdoff(&self) -> f32 {
+        self.detector.lock().unwrap().holdoff
+    }
+    
+    #[setter]
+    fn set_holdoff(&self, holdoff: f32) -> PyResult<()> {
+        if !holdoff.is_finite() || holdoff < 0.0 {
+            return Err(PyValueError::new_err("Holdoff must be zero or positive"));
+        }
+        self.detector.lock().unwrap().holdoff = holdoff;
+        Ok(())
+    }
+    
+    /// Report `label` (matched ignoring case) when it scores at least `threshold`, as `name` if given
+    #[pyo3(signature = (label, threshold=DEFAULT_THRESHOLD, name=None))]
+    fn watch(&self, label: &str, threshold: f32, name: Option<String>) -> PyResult<()> {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(PyValueError::new_err("Threshold must be between 0.0 and 1.0"));
+        }
+        let mut detector = self.detector.lock().unwrap();
+        let index = detector
+            .labels
+            .iter()
+            .position(|known| known.eq_ignore_ascii_case(label))
+            .ok_or_else(|| PyValueError::new_err(format!("The model has no label '{}'", label)))?;
+        let name = name.unwrap_or_else(|| detector.labels[index].clone());
+        detector.watches.insert(index, Watch { name, threshold });
+        Ok(())
+    }
+    
+    /// Stop reporting a label, or every label reported under an event name; returns whether any was watched
+    fn unwatch(&self, label: &str) -> bool {
+        let mut detector = self.detector.lock().unwrap();
+        let SoundEventDetector { labels, watches, .. } = &mut *detector;
+        let before = watches.len();
+        watches.retain(|&index, watch| !labels[index].eq_ignore_ascii_case(label) && watch.name != label);
+        watches.len() < before
+    }
+    
+    /// Feed streaming audio, returning the watched sounds recognized
+    fn process(&mut self, py: Python, samples: Vec<i16>) -> PyResult<Vec<SoundEvent>> {
+        let audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let audio = match self.resampler.as_mut() {
+            Some(resampler) => resampler.push(&audio).map_err(AudioError::from)?,
+            None => audio,
+        };
+        let detector = self.detector.clone();
+        py.allow_threads(|| detector.lock().unwrap().process(&audio)).map_err(PyValueError::new_err)
+    }
+    
+    /// Score a whole clip, returning the `top_k` labels as `(label, score)`, best first
+    /// 
+    /// Each label scores its best window; watches and holdoff don't apply.
+    #[pyo3(signature = (samples, sample_rate=None, top_k=5))]
+    fn classify(&self, py: Python, samples: Vec<i16>, sample_rate: Option<u32>, top_k: usize) -> PyResult<Vec<(String, f32)>> {
+        let audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let audio = resample_mono(&audio, sample_rate.unwrap_or(self.sample_rate), SOUND_SAMPLE_RATE)?;
+        let detector = self.detector.clone();
+        
+        py.allow_threads(|| {
+            let detector = detector.lock().unwrap();
+            let mut best = vec![f32::MIN; detector.labels.len()];
+            let mut start = 0;
+            loop {
+                let end = (start + detector.window).min(audio.len());
+                let scores = detector.scores(&audio[start..end])?;
+                best.iter_mut().zip(&scores).for_each(|(best, &score)| *best = best.max(score));
+                if end == audio.len() {
+                    break;
+                }
+                start += detector.hop;
+            }
+            
+            let mut ranked: Vec<(String, f32)> = detector.labels.iter().cloned().zip(best).collect();
+            ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            ranked.truncate(top_k);
+            Ok(ranked)
+        })
+        .map_err(PyValueError::new_err)
+    }
+    
+    /// Clear the audio window and holdoffs
+    fn reset(&self) {
+        self.detector.lock().unwrap().reset();
+    }
+}
@@ -0,0 +1,525 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use realfft::RealFftPlanner;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use jenna_core::inference::{load_shared, run_onnx, OnnxPlan};
+
+use crate::audio::{read_audio_file_mono, resample_mono};
+use crate::errors::ModelError;
+use crate::storage::{self, KeyRing, StorageEncryption};
+
+/// Sample rate the embedding model expects
+const SPEAKER_SAMPLE_RATE: u32 = 16000;
+
+/// Analysis window of 25 ms
+const FBANK_FRAME: usize = 400;
+
+/// Hop of 10 ms
+const FBANK_HOP: usize = 160;
+
+/// FFT size (window zero-padded to a power of two)
+const FBANK_FFT: usize = 512;
+
+/// Number of mel filterbank channels fed to the model
+const FBANK_BINS: usize = 80;
+
+/// Feature frames per model call (about 3 seconds)
+const EMBEDDING_FRAMES: usize = 300;
+
+/// Shortest audio accepted for enrollment or identification (1 second)
+const MIN_SAMPLES: usize = SPEAKER_SAMPLE_RATE as usize;
+
+/// Default cosine similarity needed to accept an identification
+const DEFAULT_THRESHOLD: f32 = 0.5;
+
+/// Diarization analysis window in seconds
+const DIARIZE_WINDOW: f32 = 1.5;
+
+/// Diarization hop between windows in seconds
+const DIARIZE_HOP: f32 = 0.75;
+
+/// Windows quieter than this (dBFS) are treated as non-speech
+const DIARIZE_SILENCE_DBFS: f32 = -50.0;
+
+/// Default similarity above which clusters are merged when the speaker count is unknown
+const DIARIZE_MERGE_THRESHOLD: f32 = 0.6;
+
+fn hz_to_mel(hz: f32) -> f32 {
+    1127.0 * (1.0 + hz / 700.0).ln()
+}
+
+/// Triangular mel filters over the positive FFT bins
+fn mel_filterbank() -> Vec<Vec<f32>> {
+    let bins = FBANK_FFT / 2 + 1;
+    let low = hz_to_mel(20.0);
+    let high = hz_to_mel(SPEAKER_SAMPLE_RATE as f32 / 2.0);
+    let step = (high - low) / (FBANK_BINS + 1) as f32;
+    
+    (0..FBANK_BINS)
+        .map(|m| {
+            let (left, center, right) = (low + step * m as f32, low + step * (m + 1) as f32, low + step * (m + 2) as f32);
+            (0..bins)
+                .map(|k| {
+                    let mel = hz_to_mel(k as f32 * SPEAKER_SAMPLE_RATE as f32 / FBANK_FFT as f32);
+                    if mel > left && mel <= center {
+                        (mel - left) / (center - left)
+                    } else if mel > center && mel < right {
+                        (right - mel) / (right - center)
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Compute mean-normalized log mel filterbank features (Kaldi-style fbank)
+pub fn compute_fbank(samples: &[f32]) -> Vec<[f32; FBANK_BINS]> {
+    if samples.len() < FBANK_FRAME {
+        return Vec::new();
+    }
+    
+    let filters = mel_filterbank();
+    let mut planner = RealFftPlanner::<f32>::new();
+    let fft = planner.plan_fft_forward(FBANK_FFT);
+    let mut input = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+    let window: Vec<f32> = (0..FBANK_FRAME)
+        .map(|n| 0.54 - 0.46 * (2.0 * std::f32::consts::PI * n as f32 / (FBANK_FRAME - 1) as f32).cos())
+        .collect();
+    
+    let mut features = Vec::new();
+    for start in (0..=samples.len() - FBANK_FRAME).step_by(FBANK_HOP) {
+        let frame = &samples[start..start + FBANK_FRAME];
+        let mean = frame.iter().sum::<f32>() / FBANK_FRAME as f32;
+        
+        input.iter_mut().for_each(|x| *x = 0.0);
+        // Remove DC, pre-emphasize and window, scaled to 16-bit range as Kaldi does
+        for n in 0..FBANK_FRAME {
+            let previous = if n > 0 { frame[n - 1] - mean } else { frame[0] - mean };
+            input[n] = ((frame[n] - mean) - 0.97 * previous) * window[n] * 32768.0;
+        }
+        if fft.process(&mut input, &mut spectrum).is_err() {
+            continue;
+        }
+        
+        let power: Vec<f32> = spectrum.iter().map(|c| c.norm_sqr()).collect();
+        let mut bands = [0.0f32; FBANK_BINS];
+        for (band, filter) in bands.iter_mut().zip(&filters) {
+            let energy: f32 = filter.iter().zip(&power).map(|(w, p)| w * p).sum();
+            *band = energy.max(f32::EPSILON).ln();
+        }
+        features.push(bands);
+    }
+    
+    // Cepstral mean normalization over the utterance
+    let count = features.len().max(1) as f32;
+    let mut means = [0.0f32; FBANK_BINS];
+    for frame in &features {
+        for (mean, value) in means.iter_mut().zip(frame) {
+            *mean += value / count;
+        }
+    }
+    for frame in features.iter_mut() {
+        for (value, mean) in frame.iter_mut().zip(&means) {
+            *value -= mean;
+        }
+    }
+    features
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// Cosine similarity of two embeddings
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a > 0.0 && norm_b > 0.0 {
+        dot / (norm_a * norm_b)
+    } else {
+        0.0
+    }
+}
+
+/// Agglomerative clustering of embeddings by centroid cosine similarity
+/// 
+/// Merges the most similar pair until `num_speakers` clusters remain, or,
+/// when the count is unknown, until no pair is more similar than
+/// `threshold`. Returns a cluster index per embedding, numbered in order of
+/// first appearance.
+pub fn cluster_embeddings(embeddings: &[Vec<f32>], num_speakers: Option<usize>, threshold: f32) -> Vec<usize> {
+    let mut clusters: Vec<(Vec<usize>, Vec<f32>)> = embeddings
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (vec![i], e.clone()))
+        .collect();
+    
+    while clusters.len() > num_speakers.unwrap_or(1).max(1) {
+        let mut best = (0, 1, f32::MIN);
+        for i in 0..clusters.len() {
+            for j in i + 1..clusters.len() {
+                let similarity = cosine_similarity(&clusters[i].1, &clusters[j].1);
+                if similarity > best.2 {
+                    best = (i, j, similarity);
+                }
+            }
+        }
+        if num_speakers.is_none() && best.2 < threshold {
+            break;
+        }
+        
+        let (i, j, _) = best;
+        let (members, centroid) = clusters.remove(j);
+        let (target_members, target_centroid) = &mut clusters[i];
+        let (n, m) = (target_members.len() as f32, members.len() as f32);
+        for (t, c) in target_centroid.iter_mut().zip(&centroid) {
+            *t = (*t * n + c * m) / (n + m);
+        }
+        target_members.extend(members);
+    }
+    
+    let mut labels = vec![0; embeddings.len()];
+    clusters.sort_by_key(|(members, _)| members.iter().copied().min().unwrap_or(0));
+    for (label, (members, _)) in clusters.iter().enumerate() {
+        for &member in members {
+            labels[member] = label;
+        }
+    }
+    labels
+}
+
+/// A span of audio attributed to one speaker
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct SpeakerSegment {
+    /// Enrolled profile name, or "speaker_N" for unknown voices
+    #[pyo3(get)]
+    pub speaker: String,
+    /// Start time in seconds
+    #[pyo3(get)]
+    pub start: f32,
+    /// End time in seconds
+    #[pyo3(get)]
+    pub end: f32,
+}
+
+#[pymethods]
+impl SpeakerSegment {
+    fn __repr__(&self) -> String {
+        format!("SpeakerSegment(speaker={:?}, start={:.2}, end={:.2})", self.speaker, self.start, self.end)
+    }
+}
+
+/// Speaker embedding model (x-vector / ECAPA style) run with tract
+/// 
+/// The model takes `[1, frames, 80]` fbank features and returns one
+/// embedding vector.
+pub struct SpeakerEmbedder {
+    plan: Arc<OnnxPlan>,
+}
+
+impl SpeakerEmbedder {
+    pub fn load(model_path: &Path) -> Result<Self, String> {
+        let plan = load_shared(model_path, &[1, EMBEDDING_FRAMES, FBANK_BINS])?;
+        tracing::info!(model = %model_path.display(), "Loaded speaker embedding model");
+        Ok(SpeakerEmbedder { plan })
+    }
+    
+    /// Embed 16 kHz audio, averaging over ~3 second chunks
+    pub fn embed(&self, samples: &[f32]) -> Result<Vec<f32>, String> {
+        let features = compute_fbank(samples);
+        if features.is_empty() {
+            return Err("Audio is too short to embed".to_string());
+        }
+        
+        let mut sum: Vec<f32> = Vec::new();
+        let chunks = (features.len() + EMBEDDING_FRAMES - 1) / EMBEDDING_FRAMES;
+        for chunk in 0..chunks {
+            // A short last (or only) chunk is padded by repeating its frames
+            let start = chunk * EMBEDDING_FRAMES;
+            let end = (start + EMBEDDING_FRAMES).min(features.len());
+            let data: Vec<f32> = features[start..end]
+                .iter()
+                .cycle()
+                .take(EMBEDDING_FRAMES)
+                .flat_map(|frame| frame.iter().copied())
+                .collect();
+            
+            let mut embedding = run_onnx(&self.plan, &[1, EMBEDDING_FRAMES, FBANK_BINS], data)?;
+            normalize(&mut embedding);
+            if sum.is_empty() {
+                sum = embedding;
+            } else {
+                sum.iter_mut().zip(&embedding).for_each(|(s, e)| *s += e);
+            }
+        }
+        
+        normalize(&mut sum);
+        Ok(sum)
+    }
+}
+
+/// Enrolled voice profile: the mean of its enrollment embeddings
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct VoiceProfile {
+    pub embedding: Vec<f32>,
+    pub samples: u32,
+}
+
+/// Speaker identification against enrolled voice profiles
+/// 
+/// Profiles are stored as JSON at `profile_path` and saved on every change,
+/// encrypted when an `encryption` key ring is given.
+#[pyclass]
+pub struct SpeakerIdentifier {
+    embedder: SpeakerEmbedder,
+    profile_path: PathBuf,
+    /// Keys the profile store is encrypted with; rotations apply from the next save
+    encryption: Option<Arc<RwLock<KeyRing>>>,
+    profiles: HashMap<String, VoiceProfile>,
+    sample_rate: u32,
+    threshold: f32,
+}
+
+impl SpeakerIdentifier {
+    fn save(&self) -> Result<(), String> {
+        if let Some(parent) = self.profile_path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(&self.profiles).map_err(|err| err.to_string())?;
+        let keys = self.encryption.as_ref().map(|keys| keys.read().unwrap().clone());
+        storage::write_file(&self.profile_path, data.as_bytes(), keys.as_ref()).map_err(|err| err.to_string())
+    }
+    
+    /// Convert i16 input to 16 kHz f32 and embed it
+    pub fn embed_samples(&self, samples: &[i16]) -> Result<Vec<f32>, String> {
+        let audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let audio = resample_mono(&audio, self.sample_rate, SPEAKER_SAMPLE_RATE).map_err(|err| err.to_string())?;
+        if audio.len() < MIN_SAMPLES {
+            return Err("At least one second of audio is required".to_string());
+        }
+        self.embedder.embed(&audio)
+    }
+    
+    /// Segment 16 kHz audio by speaker turns
+    fn diarize_audio(&self, audio: &[f32], num_speakers: Option<usize>) -> Result<Vec<SpeakerSegment>, String> {
+        let window = (DIARIZE_WINDOW * SPEAKER_SAMPLE_RATE as f32) as usize;
+        let hop = (DIARIZE_HOP * SPEAKER_SAMPLE_RATE as f32) as usize;
+        if audio.len() < window {
+            return Err("Recording is too short to diarize".to_string());
+        }
+        
+        // Embed each voiced window; silent windows separate turns
+        let mut starts = Vec::new();
+        let mut embeddings = Vec::new();
+        for start in (0..=audio.len() - window).step_by(hop) {
+            let chunk = &audio[start..start + window];
+            let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / window as f32).sqrt();
+            if 20.0 * rms.max(1e-10).log10() < DIARIZE_SILENCE_DBFS {
+                continue;
+            }
+            starts.push(start);
+            embeddings.push(self.embedder.embed(chunk)?);
+        }
+        if embeddings.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let labels = cluster_embeddings(&embeddings, num_speakers, DIARIZE_MERGE_THRESHOLD);
+        
+        // Name each cluster after the enrolled profile its centroid matches, if any
+        let cluster_count = labels.iter().max().map_or(0, |m| m + 1);
+        let names: Vec<String> = (0..cluster_count)
+            .map(|label| {
+                let mut centroid = vec![0.0; embeddings[0].len()];
+                for (embedding, _) in embeddings.iter().zip(&labels).filter(|&(_, &l)| l == label) {
+                    centroid.iter_mut().zip(embedding).for_each(|(c, e)| *c += e);
+                }
+                match self.best_match(&centroid) {
+                    Some((name, score)) if score >= self.threshold => name,
+                    _ => format!("speaker_{}", label + 1),
+                }
+            })
+            .collect();
+        
+        // Each window owns the hop it starts; consecutive windows of one speaker merge
+        let rate = SPEAKER_SAMPLE_RATE as f32;
+        let mut segments: Vec<SpeakerSegment> = Vec::new();
+        for (index, (&start, &label)) in starts.iter().zip(&labels).enumerate() {
+            let contiguous = index > 0 && starts[index - 1] + hop == start;
+            let end = if index + 1 == starts.len() || starts[index + 1] != start + hop { start + window } else { start + hop };
+            
+            match segments.last_mut() {
+                Some(segment) if contiguous && segment.speaker == names[label] => segment.end = end as f32 / rate,
+                _ => segments.push(SpeakerSegment {
+                    speaker: names[label].clone(),
+                    start: start as f32 / rate,
+                    end: end as f32 / rate,
+                }),
+            }
+        }
+        Ok(segments)
+    }
+    
+    /// Best matching profile and its similarity, if any profiles exist
+    pub fn best_match(&self, embedding: &[f32]) -> Option<(String, f32)> {
+        self.profiles
+            .iter()
+            .map(|(name, profile)| (name.clone(), cosine_similarity(embedding, &profile.embedding)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+#[pymethods]
+impl SpeakerIdentifier {
+    /// Load the embedding model and the profile store
+    /// 
+    /// `sample_rate` is the rate of the audio passed to `enroll`/`identify`.
+    /// With `encryption` (`StorageEncryption`), the profile store is saved
+    /// encrypted; an existing plain store is read and encrypted on the
+    /// next change.
+    #[new]
+    #[pyo3(signature = (model_path, profile_path, sample_rate=None, threshold=None, encryption=None))]
+    fn new(
+        py: Python,
+        model_path: String,
+        profile_path: String,
+        sample_rate: Option<u32>,
+        threshold: Option<f32>,
+        encryption: Option<PyRef<StorageEncryption>>,
+    ) -> PyResult<Self> {
+        let embedder = py
+            .allow_threads(|| SpeakerEmbedder::load(Path::new(&model_path)))
+            .map_err(ModelError::new_err)?;
+        
+        let profile_path = PathBuf::from(profile_path);
+        let encryption = encryption.map(|encryption| encryption.shared_keys());
+        let profiles = if profile_path.exists() {
+            let keys = encryption.as_ref().map(|keys| keys.read().unwrap().clone());
+            let data = storage::read_file(&profile_path, keys.as_ref())?;
+            serde_json::from_slice(&data).map_err(|err| PyValueError::new_err(format!("Invalid profile store: {}", err)))?
+        } else {
+            HashMap::new()
+        };
+        
+        Ok(SpeakerIdentifier {
+            embedder,
+            profile_path,
+            encryption,
+            profiles,
+            sample_rate: sample_rate.unwrap_or(SPEAKER_SAMPLE_RATE),
+            threshold: threshold.unwrap_or(DEFAULT_THRESHOLD),
+        })
+    }
+    
+    /// Enroll (or refine) a voice profile from a recording of the speaker
+    /// 
+    /// Calling this again for the same name averages in the new sample,
+    /// which improves accuracy. Returns the number of enrollment samples.
+    fn enroll(&mut self, py: Python, name: String, samples: Vec<i16>) -> PyResult<u32> {
+        if name.trim().is_empty() {
+            return Err(PyValueError::new_err("Profile name must not be empty"));
+        }
+        
+        let embedding = py.allow_threads(|| self.embed_samples(&samples)).map_err(PyValueError::new_err)?;
+        let profile = self.profiles.entry(name).or_insert_with(|| VoiceProfile { embedding: vec![0.0; embedding.len()], samples: 0 });
+        if profile.embedding.len() != embedding.len() {
+            return Err(PyValueError::new_err("Stored profile was created with a different model"));
+        }
+        
+        let n = profile.samples as f32;
+        for (stored, new) in profile.embedding.iter_mut().zip(&embedding) {
+            *stored = (*stored * n + new) / (n + 1.0);
+        }
+        profile.samples += 1;
+        let count = profile.samples;
+        
+        self.save().map_err(PyValueError::new_err)?;
+        Ok(count)
+    }
+    
+    /// Identify the speaker of a recording
+    /// 
+    /// Returns `(name, score)` where `score` is the cosine similarity to the
+    /// closest profile; `name` is None if no profile reaches the threshold.
+    fn identify(&self, py: Python, samples: Vec<i16>) -> PyResult<(Option<String>, f32)> {
+        let embedding = py.allow_threads(|| self.embed_samples(&samples)).map_err(PyValueError::new_err)?;
+        
+        match self.best_match(&embedding) {
+            Some((name, score)) if score >= self.threshold => Ok((Some(name), score)),
+            Some((_, score)) => Ok((None, score)),
+            None => Ok((None, 0.0)),
+        }
+    }
+    
+    /// Split a recording into speaker turns
+    /// 
+    /// Returns `SpeakerSegment`s in time order. Voices matching an enrolled
+    /// profile are labeled with its name, others as "speaker_1",
+    /// "speaker_2", ... Pass `num_speakers` if the count is known.
+    #[pyo3(signature = (samples, num_speakers=None))]
+    fn diarize(&self, py: Python, samples: Vec<i16>, num_speakers: Option<usize>) -> PyResult<Vec<SpeakerSegment>> {
+        py.allow_threads(|| {
+            let audio: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+            let audio = resample_mono(&audio, self.sample_rate, SPEAKER_SAMPLE_RATE).map_err(|err| err.to_string())?;
+            self.diarize_audio(&audio, num_speakers)
+        })
+        .map_err(PyValueError::new_err)
+    }
+    
+    /// Split a WAV or FLAC recording into speaker turns
+    /// 
+    /// Segment times match the word timestamps of
+    /// `SpeechRecognizer.transcribe_file`, so the two can be joined into a
+    /// "who said what" transcript.
+    #[pyo3(signature = (path, num_speakers=None))]
+    fn diarize_file(&self, py: Python, path: String, num_speakers: Option<usize>) -> PyResult<Vec<SpeakerSegment>> {
+        py.allow_threads(|| {
+            let (audio, sample_rate) = read_audio_file_mono(Path::new(&path)).map_err(|err| err.to_string())?;
+            let audio = resample_mono(&audio, sample_rate, SPEAKER_SAMPLE_RATE).map_err(|err| err.to_string())?;
+            self.diarize_audio(&audio, num_speakers)
+        })
+        .map_err(PyValueError::new_err)
+    }
+    
+    /// Delete a voice profile; returns False if it did not exist
+    fn remove_profile(&mut self, name: &str) -> PyResult<bool> {
+        let removed = self.profiles.remove(name).is_some();
+        if removed {
+            self.save().map_err(PyValueError::new_err)?;
+        }
+        Ok(removed)
+    }
+    
+    /// Get the names of enrolled profiles
+    fn get_profiles(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.keys().cloned().collect();
+        names.sort();
+        names
+    }
+    
+    /// Set the minimum similarity for a positive identification
+    fn set_threshold(&mut self, threshold: f32) -> PyResult<()> {
+        if !(-1.0..=1.0).contains(&threshold) {
+            return Err(PyValueError::new_err("Threshold must be between -1.0 and 1.0"));
+        }
+        self.threshold = threshold;
+        Ok(())
+    }
+    
+    /// Get the identification threshold
+    fn get_threshold(&self) -> f32 {
+        self.threshold
+    }
+}
@@ -1,85 +1,1432 @@
 use pyo3::prelude::*;
 use pyo3::exceptions::PyValueError;
 use pyo3::types::{PyDict, PyList};
-use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use vosk::{CompleteResult, DecodingState, Model, Recognizer, Word};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use jenna_core::normalize::TextNormalizer;
+use jenna_core::ssml::Prosody;
+use jenna_core::vad::VoiceActivityDetector;
 
-/// Speech recognition module that will integrate with Vosk
+use crate::accelerator::{self, Accelerator};
+use crate::audio::{open_mono_playback, read_audio_file_mono, resample_mono};
+use crate::config::JennaConfig;
+use crate::content_filter::{ContentFilter, FilterMode, WordFilter};
+use crate::formatter::{Formatter, TranscriptFormatter};
+use crate::errors::{ModelError, NotInitializedError};
+use crate::events::{self, Event, EventData};
+use crate::lifecycle::{self, Resource};
+use crate::loading::{map_model, shared_model, spawn_load, LoadStage, SharedModelLoad};
+use crate::memory::{self, disk_size, LoadedModel};
+use crate::metrics;
+use crate::panics;
+use crate::tts::{list_voices, plan_speech, render_unit, resolve_voice, Lexicon, PiperVoice, SpeechUnit, SynthesisCache, VoiceInfo};
+
+/// A recognized word with its timing (seconds) and confidence (0.0-1.0)
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct WordInfo {
+    #[pyo3(get)]
+    pub word: String,
+    #[pyo3(get)]
+    pub start: f32,
+    #[pyo3(get)]
+    pub end: f32,
+    #[pyo3(get)]
+    pub confidence: f32,
+}
+
+#[pymethods]
+impl WordInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "WordInfo(word={:?}, start={:.2}, end={:.2}, confidence={:.2})",
+            self.word, self.start, self.end, self.confidence
+        )
+    }
+}
+
+/// Recognition result tagged as partial (utterance in progress) or final
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct RecognitionResult {
+    /// Recognized text (may be empty for a partial result)
+    #[pyo3(get)]
+    pub text: String,
+    /// True once the utterance has ended and the text will not change
+    #[pyo3(get)]
+    pub is_final: bool,
+    /// Per-word timings and confidences, when word output is enabled
+    #[pyo3(get)]
+    pub words: Vec<WordInfo>,
+    /// Mean word confidence of a final result, when available
+    #[pyo3(get)]
+    pub confidence: Option<f32>,
+    /// Share of the utterance's audio the VAD heard no speech in (final results)
+    #[pyo3(get)]
+    pub no_speech_prob: Option<f32>,
+    /// Why the recognizer's acceptance policy rejected the result:
+    /// "low_confidence", "too_few_words" or "no_speech"
+    #[pyo3(get)]
+    pub rejection: Option<String>,
+}
+
+impl RecognitionResult {
+    /// A final result with nothing but the text, words and confidence filled in
+    fn complete(text: String, words: Vec<WordInfo>, confidence: Option<f32>) -> Self {
+        RecognitionResult { text, is_final: true, words, confidence, no_speech_prob: None, rejection: None }
+    }
+}
+
+#[pymethods]
+impl RecognitionResult {
+    /// "accepted", "unknown" (heard something, but the acceptance policy
+    /// rejected it) or "empty" (nothing was recognized)
+    #[getter]
+    fn outcome(&self) -> &'static str {
+        if self.rejection.is_some() {
+            "unknown"
+        } else if self.text.trim().is_empty() {
+            "empty"
+        } else {
+            "accepted"
+        }
+    }
+    
+    fn __repr__(&self) -> String {
+        format!(
+            "RecognitionResult(text={:?}, is_final={}, words={}, confidence={:?}, outcome={:?})",
+            self.text,
+            self.is_final,
+            self.words.len(),
+            self.confidence,
+            self.outcome()
+        )
+    }
+}
+
+/// Mean confidence of a list of words
+fn mean_confidence(words: &[WordInfo]) -> Option<f32> {
+    if words.is_empty() {
+        None
+    } else {
+        Some(words.iter().map(|w| w.confidence).sum::<f32>() / words.len() as f32)
+    }
+}
+
+/// A speech-to-text backend
 /// 
-/// This is a placeholder implementation that will be replaced with actual
-/// Vosk integration. For now, it provides the interface that will be used.
+/// Streaming engines return partial and final results from `accept`;
+/// batch engines buffer audio and only produce a result from `finish`.
+pub trait SttEngine: Send {
+    /// Backend name as used in the constructor
+    fn name(&self) -> &'static str;
+    
+    /// Feed a frame and return a partial or final result when one is available
+    fn accept(&mut self, frame: &[i16]) -> Result<Option<RecognitionResult>, String>;
+    
+    /// Current hypothesis for the utterance in progress
+    fn partial(&mut self) -> String;
+    
+    /// End the current utterance and return its final result
+    fn finish(&mut self) -> Result<Option<RecognitionResult>, String>;
+    
+    /// Discard the utterance in progress
+    fn reset(&mut self);
+    
+    /// Change the input sample rate
+    fn set_sample_rate(&mut self, sample_rate: u32) -> Result<(), String>;
+    
+    /// Enable per-word output on final (and optionally partial) results
+    fn set_words(&mut self, words: bool, partial: bool);
+    
+    /// Transcribe a complete 16 kHz recording without touching the live stream state
+    /// 
+    /// The result always carries word timings relative to the start of the audio.
+    fn transcribe(&self, audio: &[f32]) -> Result<RecognitionResult, String>;
+    
+    /// Restrict recognition to a phrase list, or lift the restriction with None
+    fn set_grammar(&mut self, _grammar: Option<Vec<String>>) -> Result<(), String> {
+        Err(format!("The {} engine does not support grammars", self.name()))
+    }
+    
+    /// Approximate bytes held by the loaded model
+    fn memory_bytes(&self) -> u64 {
+        0
+    }
+    
+    /// Where the model runs
+    fn accelerator(&self) -> Accelerator {
+        Accelerator::Cpu
+    }
+}
+
+/// Convert Vosk word entries into Python-facing word info
+fn word_infos(words: &[Word]) -> Vec<WordInfo> {
+    words
+        .iter()
+        .map(|w| WordInfo {
+            word: w.word.to_string(),
+            start: w.start,
+            end: w.end,
+            confidence: w.conf,
+        })
+        .collect()
+}
+
+/// Convert a final Vosk result, ignoring empty utterances
+/// 
+/// Word details are only present when word output is enabled.
+fn final_recognition(result: CompleteResult) -> Option<RecognitionResult> {
+    let (text, words, confidence) = match result {
+        CompleteResult::Single(single) => {
+            let words = word_infos(&single.result);
+            let confidence = mean_confidence(&words);
+            (single.text.to_string(), words, confidence)
+        }
+        CompleteResult::Multiple(multiple) => match multiple.alternatives.first() {
+            Some(alternative) => (alternative.text.to_string(), Vec::new(), Some(alternative.confidence)),
+            None => (String::new(), Vec::new(), None),
+        },
+    };
+    
+    let text = text.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(RecognitionResult::complete(text.to_string(), words, confidence))
+    }
+}
+
+/// Streaming recognition with a Vosk model directory
+struct VoskEngine {
+    /// Shared with every other recognizer using the same model directory
+    model: Arc<Model>,
+    recognizer: Recognizer,
+    sample_rate: u32,
+    grammar: Option<Vec<String>>,
+    words: bool,
+    partial_words: bool,
+}
+
+impl VoskEngine {
+    #[tracing::instrument(name = "vosk_load", skip_all, fields(model = model_path))]
+    fn load(model_path: &str, sample_rate: u32) -> Result<Self, String> {
+        let model = shared_model("vosk", Path::new(model_path), || {
+            Model::new(model_path).ok_or_else(|| format!("Failed to load Vosk model: {}", model_path))
+        })?;
+        let recognizer = Recognizer::new(&model, sample_rate as f32)
+            .ok_or_else(|| format!("Failed to create recognizer at {} Hz", sample_rate))?;
+        
+        tracing::info!(sample_rate, "Loaded Vosk model");
+        Ok(VoskEngine { model, recognizer, sample_rate, grammar: None, words: false, partial_words: false })
+    }
+    
+    /// Recreate the recognizer with the current rate, grammar and word options
+    fn rebuild(&mut self) -> Result<(), String> {
+        let recognizer = match &self.grammar {
+            Some(grammar) => Recognizer::new_with_grammar(&self.model, self.sample_rate as f32, grammar),
+            None => Recognizer::new(&self.model, self.sample_rate as f32),
+        };
+        let mut recognizer = recognizer
+            .ok_or_else(|| format!("Failed to create recognizer at {} Hz", self.sample_rate))?;
+        recognizer.set_words(self.words);
+        recognizer.set_partial_words(self.partial_words);
+        
+        self.recognizer = recognizer;
+        Ok(())
+    }
+}
+
+impl SttEngine for VoskEngine {
+    fn name(&self) -> &'static str {
+        "vosk"
+    }
+    
+    fn accept(&mut self, frame: &[i16]) -> Result<Option<RecognitionResult>, String> {
+        match self.recognizer.accept_waveform(frame) {
+            DecodingState::Finalized => Ok(final_recognition(self.recognizer.result())),
+            DecodingState::Running => {
+                let partial = self.recognizer.partial_result();
+                let text = partial.partial.trim().to_string();
+                if text.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(RecognitionResult {
+                        text,
+                        is_final: false,
+                        words: word_infos(&partial.partial_result),
+                        confidence: None,
+                        no_speech_prob: None,
+                        rejection: None,
+                    }))
+                }
+            }
+            DecodingState::Failed => Err("Vosk failed to decode audio frame".to_string()),
+        }
+    }
+    
+    fn partial(&mut self) -> String {
+        self.recognizer.partial_result().partial.trim().to_string()
+    }
+    
+    fn finish(&mut self) -> Result<Option<RecognitionResult>, String> {
+        Ok(final_recognition(self.recognizer.final_result()))
+    }
+    
+    fn reset(&mut self) {
+        self.recognizer.reset();
+    }
+    
+    fn set_sample_rate(&mut self, sample_rate: u32) -> Result<(), String> {
+        self.sample_rate = sample_rate;
+        self.rebuild()
+    }
+    
+    fn set_words(&mut self, words: bool, partial: bool) {
+        self.words = words;
+        self.partial_words = words && partial;
+        self.recognizer.set_words(self.words);
+        self.recognizer.set_partial_words(self.partial_words);
+    }
+    
+    fn set_grammar(&mut self, grammar: Option<Vec<String>>) -> Result<(), String> {
+        self.grammar = grammar;
+        self.rebuild()
+    }
+    
+    fn transcribe(&self, audio: &[f32]) -> Result<RecognitionResult, String> {
+        // A separate recognizer leaves the streaming one untouched
+        let mut recognizer = Recognizer::new(&self.model, TRANSCRIBE_SAMPLE_RATE as f32)
+            .ok_or_else(|| "Failed to create recognizer for transcription".to_string())?;
+        recognizer.set_words(true);
+        
+        let samples: Vec<i16> = audio.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect();
+        let mut segments = Vec::new();
+        // Feed in 100 ms chunks, as Vosk endpoints on chunk boundaries
+        for chunk in samples.chunks(TRANSCRIBE_SAMPLE_RATE as usize / 10) {
+            match recognizer.accept_waveform(chunk) {
+                DecodingState::Finalized => segments.extend(final_recognition(recognizer.result())),
+                DecodingState::Running => {}
+                DecodingState::Failed => return Err("Vosk failed to decode audio".to_string()),
+            }
+        }
+        segments.extend(final_recognition(recognizer.final_result()));
+        
+        Ok(merge_segments(segments))
+    }
+}
+
+/// Join consecutive final results into one transcript
+fn merge_segments(segments: Vec<RecognitionResult>) -> RecognitionResult {
+    let text = segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" ");
+    let words: Vec<WordInfo> = segments.into_iter().flat_map(|s| s.words).collect();
+    let confidence = mean_confidence(&words);
+    
+    RecognitionResult::complete(text, words, confidence)
+}
+
+/// Sample rate expected by Whisper models
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Sample rate file transcription is resampled to
+const TRANSCRIBE_SAMPLE_RATE: u32 = 16000;
+
+/// How often `speak` checks whether its playback queue has drained
+const PLAYBACK_POLL: Duration = Duration::from_millis(20);
+
+/// Extra time `speak` keeps the stream open for the device's own buffer
+const PLAYBACK_TAIL: Duration = Duration::from_millis(150);
+
+/// Phrase a voice synthesizes when warmed up after a background load
+const WARM_UP_TEXT: &str = "Hello.";
+
+/// Batch recognition of completed utterances with a whisper.cpp (ggml) model
+/// 
+/// Audio is buffered until `finish`, then transcribed in one pass.
+struct WhisperEngine {
+    /// Shared with every other recognizer using the same model file
+    context: Arc<WhisperContext>,
+    accelerator: Accelerator,
+    sample_rate: u32,
+    language: Option<String>,
+    buffer: Vec<f32>,
+    words: bool,
+}
+
+impl WhisperEngine {
+    #[tracing::instrument(name = "whisper_load", skip_all, fields(model = model_path))]
+    fn load(
+        model_path: &str,
+        sample_rate: u32,
+        language: Option<String>,
+        memory_map: bool,
+        accelerator: Accelerator,
+    ) -> Result<Self, String> {
+        // A context offloaded to the GPU can't be shared with one kept on the CPU
+        let kind = if accelerator == Accelerator::Cpu { "whisper" } else { "whisper-gpu" };
+        let context = shared_model(kind, Path::new(model_path), || {
+            let mut params = WhisperContextParameters::default();
+            params.use_gpu(accelerator != Accelerator::Cpu);
+            if memory_map {
+                let mapped = map_model(Path::new(model_path))?;
+                WhisperContext::new_from_buffer_with_params(&mapped, params)
+            } else {
+                WhisperContext::new_with_params(model_path, params)
+            }
+            .map_err(|err| format!("Failed to load Whisper model {}: {:?}", model_path, err))
+        })?;
+        
+        tracing::info!(
+            language = language.as_deref().unwrap_or("auto"),
+            accelerator = accelerator.name(),
+            "Loaded Whisper model"
+        );
+        Ok(WhisperEngine { context, accelerator, sample_rate, language, buffer: Vec::new(), words: false })
+    }
+    
+    /// Transcribe 16 kHz mono audio
+    fn run(&self, audio: &[f32], words: bool) -> Result<Option<RecognitionResult>, String> {
+        let mut state = self.context.create_state().map_err(|err| format!("{:?}", err))?;
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_language(Some(self.language.as_deref().unwrap_or("auto")));
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        params.set_token_timestamps(words);
+        
+        state.full(params, audio).map_err(|err| format!("Whisper transcription failed: {:?}", err))?;
+        
+        let segments = state.full_n_segments().map_err(|err| format!("{:?}", err))?;
+        let mut text = String::new();
+        let mut words: Vec<WordInfo> = Vec::new();
+        for segment in 0..segments {
+            text.push_str(&state.full_get_segment_text(segment).map_err(|err| format!("{:?}", err))?);
+            if !words {
+                continue;
+            }
+            
+            // Tokens are sub-word pieces; a leading space starts a new word
+            let tokens = state.full_n_tokens(segment).map_err(|err| format!("{:?}", err))?;
+            for token in 0..tokens {
+                let piece = state.full_get_token_text(segment, token).map_err(|err| format!("{:?}", err))?;
+                if piece.starts_with("[_") || piece.starts_with("<|") {
+                    continue;
+                }
+                let data = state.full_get_token_data(segment, token).map_err(|err| format!("{:?}", err))?;
+                let (start, end) = (data.t0 as f32 / 100.0, data.t1 as f32 / 100.0);
+                
+                match words.last_mut() {
+                    Some(word) if !piece.starts_with(' ') => {
+                        word.word.push_str(&piece);
+                        word.end = end;
+                        word.confidence = word.confidence.min(data.p);
+                    }
+                    _ => words.push(WordInfo {
+                        word: piece.trim().to_string(),
+                        start,
+                        end,
+                        confidence: data.p,
+                    }),
+                }
+            }
+        }
+        
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let confidence = mean_confidence(&words);
+        Ok(Some(RecognitionResult::complete(text.to_string(), words, confidence)))
+    }
+}
+
+impl SttEngine for WhisperEngine {
+    fn name(&self) -> &'static str {
+        "whisper"
+    }
+    
+    fn accelerator(&self) -> Accelerator {
+        self.accelerator
+    }
+    
+    fn accept(&mut self, frame: &[i16]) -> Result<Option<RecognitionResult>, String> {
+        self.buffer.extend(frame.iter().map(|&s| s as f32 / 32768.0));
+        Ok(None)
+    }
+    
+    fn partial(&mut self) -> String {
+        String::new()
+    }
+    
+    fn finish(&mut self) -> Result<Option<RecognitionResult>, String> {
+        let buffered = std::mem::take(&mut self.buffer);
+        if buffered.is_empty() {
+            return Ok(None);
+        }
+        
+        let audio = resample_mono(&buffered, self.sample_rate, WHISPER_SAMPLE_RATE).map_err(|err| err.to_string())?;
+        self.run(&audio, self.words)
+    }
+    
+    fn reset(&mut self) {
+        self.buffer.clear();
+    }
+    
+    fn set_sample_rate(&mut self, sample_rate: u32) -> Result<(), String> {
+        self.buffer.clear();
+        self.sample_rate = sample_rate;
+        Ok(())
+    }
+    
+    fn set_words(&mut self, words: bool, _partial: bool) {
+        self.words = words;
+    }
+    
+    fn transcribe(&self, audio: &[f32]) -> Result<RecognitionResult, String> {
+        Ok(self.run(audio, true)?.unwrap_or_else(|| RecognitionResult::complete(String::new(), Vec::new(), None)))
+    }
+}
+
+/// When a final result is good enough to act on
+/// 
+/// Results failing a check keep their text but are marked rejected, so
+/// callers can tell "heard something unusable" (e.g. a TV in the
+/// background) from "heard nothing". Results without a confidence or
+/// no-speech estimate pass those checks.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Acceptance {
+    pub min_confidence: Option<f32>,
+    pub min_words: usize,
+    pub max_no_speech: Option<f32>,
+}
+
+impl Acceptance {
+    pub fn new(min_confidence: Option<f32>, min_words: usize, max_no_speech: Option<f32>) -> Result<Self, String> {
+        if [min_confidence, max_no_speech].iter().flatten().any(|value| !(0.0..=1.0).contains(value)) {
+            return Err("Confidence and no-speech thresholds must be between 0.0 and 1.0".to_string());
+        }
+        Ok(Acceptance { min_confidence, min_words, max_no_speech })
+    }
+    
+    /// Reason a final result is rejected, or None to accept it
+    fn check(&self, result: &RecognitionResult) -> Option<&'static str> {
+        if result.text.trim().is_empty() {
+            return None;
+        }
+        if let (Some(min), Some(confidence)) = (self.min_confidence, result.confidence) {
+            if confidence < min {
+                return Some("low_confidence");
+            }
+        }
+        if result.text.split_whitespace().count() < self.min_words {
+            return Some("too_few_words");
+        }
+        if let (Some(max), Some(no_speech)) = (self.max_no_speech, result.no_speech_prob) {
+            if no_speech > max {
+                return Some("no_speech");
+            }
+        }
+        None
+    }
+}
+
+/// Post-processing applied to results before they leave the engine
+#[derive(Default)]
+struct PostProcessing {
+    filter: Option<WordFilter>,
+    /// Applied to final results only
+    formatter: Option<Formatter>,
+    acceptance: Acceptance,
+}
+
+/// VAD threshold used to estimate how much of an utterance was speech, in dB above the noise floor
+const NO_SPEECH_VAD_DB: f32 = 12.0;
+
+/// Frame length the no-speech estimate splits transcribed audio into
+const NO_SPEECH_FRAME: usize = 480;
+
+/// Speech and total frames seen by the VAD during an utterance
+struct SpeechTally {
+    vad: VoiceActivityDetector,
+    speech: u32,
+    frames: u32,
+}
+
+impl SpeechTally {
+    fn new() -> Self {
+        SpeechTally { vad: VoiceActivityDetector::new(NO_SPEECH_VAD_DB), speech: 0, frames: 0 }
+    }
+    
+    fn add(&mut self, frame: &[i16]) {
+        self.speech += self.vad.process(frame) as u32;
+        self.frames += 1;
+    }
+    
+    /// Share of the frames without speech since the last call, restarting the count
+    fn take_no_speech(&mut self) -> Option<f32> {
+        let no_speech = (self.frames > 0).then(|| 1.0 - self.speech as f32 / self.frames as f32);
+        self.speech = 0;
+        self.frames = 0;
+        no_speech
+    }
+}
+
+/// Post-processing shared by a recognizer's engines
+type SharedPostProcessing = Arc<RwLock<PostProcessing>>;
+
+/// Engine wrapper that runs every result through the recognizer's
+/// content filter and transcript formatter
+/// 
+/// Wrapping the engine rather than the Python methods means results fed
+/// from Rust threads (the worker, `VoicePipeline`) are processed too.
+struct PostProcessedEngine {
+    inner: Box<dyn SttEngine>,
+    post: SharedPostProcessing,
+    /// Size of the model files, standing in for the memory they take once loaded
+    model_bytes: u64,
+    /// Speech heard in the current utterance, for the no-speech estimate
+    tally: SpeechTally,
+}
+
+impl PostProcessedEngine {
+    fn new(inner: Box<dyn SttEngine>, post: SharedPostProcessing, model_bytes: u64) -> Self {
+        PostProcessedEngine { inner, post, model_bytes, tally: SpeechTally::new() }
+    }
+    
+    /// Mark a final result the acceptance policy rejects, before filtering changes its text
+    fn judge(&self, result: &mut RecognitionResult) {
+        let acceptance = self.post.read().unwrap().acceptance;
+        if let Some(reason) = acceptance.check(result) {
+            tracing::debug!(reason, text = %result.text, "Recognition result rejected");
+            result.rejection = Some(reason.to_string());
+        }
+    }
+    
+    fn judged(&mut self, mut result: RecognitionResult) -> RecognitionResult {
+        if result.is_final {
+            result.no_speech_prob = self.tally.take_no_speech();
+            self.judge(&mut result);
+        }
+        self.filtered(result)
+    }
+    
+    fn filtered(&self, mut result: RecognitionResult) -> RecognitionResult {
+        let post = self.post.read().unwrap();
+        if let Some(filter) = &post.filter {
+            filter.apply_result(&mut result);
+        }
+        if let Some(formatter) = post.formatter.as_ref().filter(|_| result.is_final) {
+            if let Err(err) = formatter.format_result(&mut result) {
+                tracing::warn!(error = %err, "Transcript formatting failed");
+            }
+        }
+        result
+    }
+}
+
+impl SttEngine for PostProcessedEngine {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+    
+    fn accept(&mut self, frame: &[i16]) -> Result<Option<RecognitionResult>, String> {
+        self.tally.add(frame);
+        Ok(self.inner.accept(frame)?.map(|result| self.judged(result)))
+    }
+    
+    fn partial(&mut self) -> String {
+        let partial = self.inner.partial();
+        match self.post.read().unwrap().filter.as_ref() {
+            Some(filter) => filter.apply(&partial),
+            None => partial,
+        }
+    }
+    
+    fn finish(&mut self) -> Result<Option<RecognitionResult>, String> {
+        let result = self.inner.finish()?.map(|result| self.judged(result));
+        self.tally.take_no_speech();
+        Ok(result)
+    }
+    
+    fn reset(&mut self) {
+        self.tally.take_no_speech();
+        self.inner.reset()
+    }
+    
+    fn set_sample_rate(&mut self, sample_rate: u32) -> Result<(), String> {
+        self.inner.set_sample_rate(sample_rate)
+    }
+    
+    fn set_words(&mut self, words: bool, partial: bool) {
+        self.inner.set_words(words, partial)
+    }
+    
+    fn transcribe(&self, audio: &[f32]) -> Result<RecognitionResult, String> {
+        let mut result = self.inner.transcribe(audio)?;
+        let mut tally = SpeechTally::new();
+        let pcm: Vec<i16> = audio.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect();
+        for frame in pcm.chunks(NO_SPEECH_FRAME) {
+            tally.add(frame);
+        }
+        result.no_speech_prob = tally.take_no_speech();
+        self.judge(&mut result);
+        Ok(self.filtered(result))
+    }
+    
+    fn set_grammar(&mut self, grammar: Option<Vec<String>>) -> Result<(), String> {
+        self.inner.set_grammar(grammar)
+    }
+    
+    fn memory_bytes(&self) -> u64 {
+        self.model_bytes
+    }
+    
+    fn accelerator(&self) -> Accelerator {
+        self.inner.accelerator()
+    }
+}
+
+/// Load a bare "vosk" or "whisper" engine, without transcript post-processing
+/// 
+/// `memory_map` reads a Whisper model through a read-only memory map, and
+/// `accelerator` picks where it runs (see `accelerator::select_for_whisper`);
+/// Vosk reads its model directory itself, runs on the CPU and ignores both.
+pub fn load_stt_engine(
+    engine: &str,
+    model_path: &str,
+    sample_rate: u32,
+    language: Option<String>,
+    memory_map: bool,
+    accelerator: &str,
+) -> Result<Box<dyn SttEngine>, String> {
+    match engine {
+        "whisper" => {
+            let accelerator = accelerator::select_for_whisper(accelerator)?;
+            Ok(Box::new(WhisperEngine::load(model_path, sample_rate, language, memory_map, accelerator)?))
+        }
+        "vosk" => Ok(Box::new(VoskEngine::load(model_path, sample_rate)?)),
+        _ => Err(format!("Unknown speech engine '{}'; expected 'vosk' or 'whisper'", engine)),
+    }
+}
+
+/// Active engine, shared with the background worker
+pub type SharedEngine = Arc<Mutex<Option<Box<dyn SttEngine>>>>;
+
+/// Recognizers are reported but never unloaded, since pipelines and
+/// servers feed the shared engine directly
+impl LoadedModel for Mutex<Option<Box<dyn SttEngine>>> {
+    fn name(&self) -> String {
+        match self.lock().unwrap().as_ref() {
+            Some(engine) => format!("stt:{}", engine.name()),
+            None => "stt".to_string(),
+        }
+    }
+    
+    fn memory_bytes(&self) -> u64 {
+        self.lock().unwrap().as_ref().map_or(0, |engine| engine.memory_bytes())
+    }
+    
+    fn idle_for(&self) -> Option<Duration> {
+        None
+    }
+    
+    fn idle_timeout(&self) -> Option<Duration> {
+        None
+    }
+    
+    fn unload(&self) -> u64 {
+        0
+    }
+}
+
+/// Run `f` on the active engine, mapping engine errors to `ValueError`
+fn with_engine<R>(
+    engine: &SharedEngine,
+    f: impl FnOnce(&mut dyn SttEngine) -> Result<R, String>,
+) -> PyResult<R> {
+    let mut guard = engine.lock().unwrap();
+    let engine = guard
+        .as_deref_mut()
+        .ok_or_else(|| NotInitializedError::new_err("Speech recognizer not initialized"))?;
+    f(engine).map_err(PyValueError::new_err)
+}
+
+/// Work item for the background recognition thread
+enum SttJob {
+    Frame(Vec<i16>, PyObject),
+    File(String, PyObject),
+}
+
+/// Background recognition thread and its job queue
+struct SttWorker {
+    sender: Mutex<Option<Sender<SttJob>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl SttWorker {
+    /// Queue a job, returning false once the worker has been closed or has stopped
+    fn send(&self, job: SttJob) -> bool {
+        self.sender.lock().unwrap().as_ref().map_or(false, |sender| sender.send(job).is_ok())
+    }
+    
+    /// Stop accepting jobs; the thread finishes the queued ones and exits
+    fn close(&self) {
+        self.sender.lock().unwrap().take();
+    }
+    
+    fn is_closed(&self) -> bool {
+        self.sender.lock().unwrap().is_none()
+    }
+}
+
+impl Resource for SttWorker {
+    fn shutdown(&self) {
+        self.close();
+        let handle = self.handle.lock().unwrap().take();
+        lifecycle::join_threads(handle);
+    }
+}
+
+/// Spawn the recognition worker; it exits when the worker is closed or dropped
+/// 
+/// Jobs run in submission order. Each callback is invoked as
+/// `callback(result, error)` with the GIL held only for the call itself.
+fn spawn_worker(engine: SharedEngine) -> PyResult<Arc<SttWorker>> {
+    let (sender, receiver) = mpsc::channel::<SttJob>();
+    
+    let handle = thread::Builder::new()
+        .name("jenna-stt".to_string())
+        .spawn(move || {
+            // A job that panics loses its callback; the worker restarts for the jobs after it
+            panics::supervise(
+                "Recognition worker",
+                || {
+                    for job in receiver.iter() {
+                        let (outcome, callback) = match job {
+                            SttJob::Frame(frame, callback) => {
+                                match with_engine(&engine, |e| e.accept(&frame)) {
+                                    // Only completed utterances are reported
+                                    Ok(result) if !result.as_ref().map_or(false, |r| r.is_final) => continue,
+                                    outcome => {
+                                        if let Ok(result) = &outcome {
+                                            post_final(result);
+                                        }
+                                        (outcome, callback)
+                                    }
+                                }
+                            }
+                            SttJob::File(path, callback) => {
+                                (with_engine(&engine, |e| transcribe_path(e, &path)).map(Some), callback)
+                            }
+                        };
+                        
+                        Python::with_gil(|py| {
+                            let call = match outcome {
+                                Ok(result) => callback.call1(py, (result, py.None())),
+                                Err(err) => callback.call1(py, (py.None(), err.value(py).to_string())),
+                            };
+                            if let Err(err) = call {
+                                err.print(py);
+                            }
+                        });
+                    }
+                    Ok(())
+                },
+                |_, restart| {
+                    // Python calls on the recognizer shouldn't panic on the poison meanwhile
+                    engine.clear_poison();
+                    restart.map_or(false, |delay| {
+                        thread::sleep(delay);
+                        true
+                    })
+                },
+            );
+        })
+        .map_err(|err| PyValueError::new_err(format!("Failed to start recognition worker: {}", err)))?;
+    
+    let worker = Arc::new(SttWorker { sender: Mutex::new(Some(sender)), handle: Mutex::new(Some(handle)) });
+    lifecycle::register(&worker);
+    Ok(worker)
+}
+
+/// Post a final recognition result to the event bus
+fn post_final(result: &Option<RecognitionResult>) {
+    if let Some(result) = result.as_ref().filter(|r| r.is_final) {
+        events::post(Event::new("speech.final", "recognizer", EventData::Recognition(result.clone())));
+    }
+}
+
+/// Decode, resample and transcribe an audio file with the given engine
+#[tracing::instrument(name = "transcribe", skip(engine), fields(engine = engine.name()))]
+fn transcribe_path(engine: &mut dyn SttEngine, path: &str) -> Result<RecognitionResult, String> {
+    let (samples, sample_rate) = read_audio_file_mono(Path::new(path)).map_err(|err| err.to_string())?;
+    let audio = resample_mono(&samples, sample_rate, TRANSCRIBE_SAMPLE_RATE).map_err(|err| err.to_string())?;
+    tracing::debug!(seconds = audio.len() as f64 / TRANSCRIBE_SAMPLE_RATE as f64, "Transcribing");
+    engine.transcribe(&audio)
+}
+
+/// Speech recognizer with a selectable backend
+/// 
+/// `engine="vosk"` (default) streams: feed 16-bit mono frames to `process`
+/// and it returns the transcript once Vosk detects the end of an utterance.
+/// `engine="whisper"` is non-streaming and more accurate: frames are
+/// buffered and transcribed when `final_result` is called, which suits
+/// dictation better than short commands.
 #[pyclass]
 pub struct SpeechRecognizer {
     model_path: String,
+    engine_name: String,
+    language: Option<String>,
+    engine: SharedEngine,
+    worker: Option<Arc<SttWorker>>,
+    language_tag: Option<String>,
+    standby: HashMap<String, Box<dyn SttEngine>>,
+    words: bool,
+    partial_words: bool,
+    post: SharedPostProcessing,
     is_initialized: bool,
     sample_rate: u32,
     is_active: bool,
+    memory_map: bool,
+    /// Accelerator preference applied to Whisper models as they load
+    accelerator: String,
+    loading: Option<SharedModelLoad>,
+}
+
+impl SpeechRecognizer {
+    /// Loader for a model for the selected backend, with the current options applied
+    fn engine_loader(&self, path: &str, language: Option<String>) -> impl FnOnce() -> Result<Box<dyn SttEngine>, String> + Send {
+        let (engine_name, path) = (self.engine_name.clone(), path.to_string());
+        let (sample_rate, memory_map, accelerator) = (self.sample_rate, self.memory_map, self.accelerator.clone());
+        let (post, words, partial_words) = (self.post.clone(), self.words, self.partial_words);
+        move || {
+            let inner = load_stt_engine(&engine_name, &path, sample_rate, language, memory_map, &accelerator)?;
+            let mut engine = PostProcessedEngine::new(inner, post, disk_size(Path::new(&path)));
+            engine.set_words(words, partial_words);
+            Ok(Box::new(engine) as Box<dyn SttEngine>)
+        }
+    }
+    
+    /// Load a model for the selected backend with the current options applied
+    fn load_engine(&self, py: Python, path: &str, language: Option<String>) -> PyResult<Box<dyn SttEngine>> {
+        // Loading a large model takes seconds; don't hold the GIL meanwhile
+        let load = self.engine_loader(path, language);
+        py.allow_threads(load).map_err(ModelError::new_err)
+    }
+    
+    /// Whether a model is loaded, directly or by a finished background load
+    fn ready(&self) -> bool {
+        self.is_initialized || self.loading.as_ref().map_or(false, |load| load.is_ready())
+    }
+    
+    /// Drop the result of a background load that hasn't finished
+    fn cancel_loading(&mut self) {
+        if let Some(load) = self.loading.take() {
+            if load.cancel() {
+                tracing::info!("Cancelled background model load");
+            }
+            self.is_initialized = self.is_initialized || load.is_ready();
+        }
+    }
+    
+    /// Queue a job on the worker thread, starting it on first use or after a shutdown
+    fn submit(&mut self, job: SttJob) -> PyResult<()> {
+        if self.worker.as_ref().map_or(true, |worker| worker.is_closed()) {
+            self.worker = Some(spawn_worker(self.engine.clone())?);
+        }
+        
+        let worker = self.worker.as_ref().unwrap();
+        if !worker.send(job) {
+            self.worker = None;
+            return Err(PyValueError::new_err("Recognition worker has stopped"));
+        }
+        Ok(())
+    }
+    
+    /// Feed a frame, returning None without touching the engine while inactive
+    fn accept(&mut self, py: Python, audio_frame: Vec<i16>) -> PyResult<Option<RecognitionResult>> {
+        if !self.is_active {
+            if !self.ready() {
+                return Err(NotInitializedError::new_err("Speech recognizer not initialized"));
+            }
+            return Ok(None);
+        }
+        
+        let engine = self.engine.clone();
+        let result = py.allow_threads(|| with_engine(&engine, |e| e.accept(&audio_frame)))?;
+        post_final(&result);
+        Ok(result)
+    }
+    
+    /// Engine handle and input sample rate for threads that feed audio from Rust
+    pub fn shared_engine(&self) -> PyResult<(SharedEngine, u32)> {
+        if !self.ready() {
+            return Err(NotInitializedError::new_err("Speech recognizer not initialized"));
+        }
+        Ok((self.engine.clone(), self.sample_rate))
+    }
 }
 
 #[pymethods]
 impl SpeechRecognizer {
     /// Create a new speech recognizer
+    /// 
+    /// `engine` is "vosk" (model directory) or "whisper" (ggml model file).
+    /// `language` is a Whisper language code; None lets Whisper detect it.
+    /// `memory_map` reads a Whisper model through a read-only memory map.
+    /// `accelerator` is where Whisper runs: "auto" (the first usable GPU,
+    /// else the CPU), "cpu", "cuda", "metal", "opencl" or "directml"; a
+    /// GPU this build or machine can't use falls back to the CPU, and
+    /// `get_accelerator` tells which one was picked.
     #[new]
-    fn new(model_path: String, sample_rate: Option<u32>) -> PyResult<Self> {
+    #[pyo3(signature = (model_path, sample_rate=None, engine="vosk", language=None, memory_map=false, accelerator="auto"))]
+    fn new(
+        model_path: String,
+        sample_rate: Option<u32>,
+        engine: &str,
+        language: Option<String>,
+        memory_map: bool,
+        accelerator: &str,
+    ) -> PyResult<Self> {
         let sample_rate = sample_rate.unwrap_or(16000);
+        let engine_name = engine.to_ascii_lowercase();
+        if engine_name != "vosk" && engine_name != "whisper" {
+            return Err(PyValueError::new_err(format!(
+                "Unknown speech engine '{}'; expected 'vosk' or 'whisper'", engine
+            )));
+        }
+        accelerator::check_preference(accelerator).map_err(PyValueError::new_err)?;
+        
+        let engine: SharedEngine = Arc::new(Mutex::new(None));
+        memory::register(&engine);
         
         Ok(SpeechRecognizer {
             model_path,
+            engine_name,
+            language,
+            engine,
+            worker: None,
+            language_tag: None,
+            standby: HashMap::new(),
+            words: false,
+            partial_words: false,
+            post: Arc::new(RwLock::new(PostProcessing::default())),
             is_initialized: false,
             sample_rate,
             is_active: false,
+            memory_map,
+            accelerator: accelerator.to_ascii_lowercase(),
+            loading: None,
         })
     }
     
-    /// Initialize the speech recognizer with the given model
-    fn initialize(&mut self, py: Python) -> PyResult<()> {
-        let model_path = Path::new(&self.model_path);
-        
-        if !model_path.exists() {
-            return Err(PyValueError::new_err(format!("Model directory not found: {}", model_path.display())));
+    /// Create and initialize a recognizer from a `JennaConfig`
+    #[staticmethod]
+    pub fn from_config(py: Python, config: PyRef<JennaConfig>) -> PyResult<Self> {
+        config.check().map_err(PyValueError::new_err)?;
+        let model_path = config
+            .stt_model_path
+            .clone()
+            .ok_or_else(|| PyValueError::new_err("stt_model_path is not set"))?;
+        let mut recognizer = SpeechRecognizer::new(
+            model_path,
+            Some(config.stt_sample_rate),
+            &config.stt_engine,
+            config.stt_language.clone(),
+            config.memory_map_models,
+            &config.accelerator,
+        )?;
+        if let Some(mode) = &config.content_filter {
+            let mut filter = WordFilter::new(FilterMode::from_name(mode).map_err(PyValueError::new_err)?, true);
+            if let Some(path) = &config.content_filter_words {
+                filter.load(Path::new(path)).map_err(PyValueError::new_err)?;
+            }
+            recognizer.post.write().unwrap().filter = Some(filter);
+        }
+        if config.format_transcripts || config.punctuation_model.is_some() {
+            let mut formatter = Formatter::new();
+            if let Some(model_dir) = &config.punctuation_model {
+                py.allow_threads(|| formatter.load_model(Path::new(model_dir))).map_err(ModelError::new_err)?;
+            }
+            recognizer.post.write().unwrap().formatter = Some(formatter);
+        }
+        recognizer.post.write().unwrap().acceptance =
+            Acceptance::new(config.stt_min_confidence, config.stt_min_words, config.stt_max_no_speech)
+                .map_err(PyValueError::new_err)?;
+        recognizer.initialize(py)?;
+        Ok(recognizer)
+    }
+    
+    /// Initialize the speech recognizer by loading the model
+    fn initialize(&mut self, py: Python) -> PyResult<()> {
+        let model_path = Path::new(&self.model_path);
+        
+        if !model_path.exists() {
+            return Err(ModelError::new_err(format!("Model not found: {}", model_path.display())));
+        }
+        
+        self.cancel_loading();
+        let path = self.model_path.clone();
+        let language = self.language.clone();
+        let engine = self.load_engine(py, &path, language)?;
+        
+        *self.engine.lock().unwrap() = Some(engine);
+        self.is_initialized = true;
+        self.is_active = true;
+        
+        Ok(())
+    }
+    
+    /// Load the model on a background thread and return immediately
+    /// 
+    /// `progress(stage, fraction)` is called from the loading thread as the
+    /// load reaches "loading", "warming_up" and finally "ready" or "failed"
+    /// (see `load_error`). With `warm_up`, the model decodes half a second
+    /// of silence before it is reported ready, so the first utterance
+    /// doesn't pay for the engine's lazy setup. Until `is_ready()`, feeding
+    /// frames raises `NotInitializedError`.
+    #[pyo3(signature = (progress=None, warm_up=true))]
+    fn initialize_async(&mut self, py: Python, progress: Option<PyObject>, warm_up: bool) -> PyResult<()> {
+        if !Path::new(&self.model_path).exists() {
+            return Err(ModelError::new_err(format!("Model not found: {}", self.model_path)));
+        }
+        
+        self.cancel_loading();
+        let load = self.engine_loader(&self.model_path, self.language.clone());
+        let silence = vec![0i16; self.sample_rate as usize / 2];
+        let engine = self.engine.clone();
+        let loading = spawn_load(
+            py,
+            "stt",
+            progress,
+            move |status| {
+                let mut loaded = load()?;
+                if warm_up {
+                    status.warming_up();
+                    if let Err(err) = loaded.accept(&silence).and_then(|_| loaded.finish()) {
+                        tracing::warn!(error = %err, "Recognizer warm-up failed");
+                    }
+                    loaded.reset();
+                }
+                Ok(loaded)
+            },
+            move |loaded| *engine.lock().unwrap() = Some(loaded),
+        )?;
+        self.loading = Some(loading);
+        self.is_active = true;
+        Ok(())
+    }
+    
+    /// Check whether a model is loaded and frames can be fed
+    fn is_ready(&self) -> bool {
+        self.ready()
+    }
+    
+    /// Wait for a background load to finish, returning whether the model is ready
+    /// 
+    /// Returns False when `timeout` (seconds) passes first or the load failed.
+    /// An infinite timeout waits as long as None does.
+    #[pyo3(signature = (timeout=None))]
+    fn wait_ready(&self, py: Python, timeout: Option<f64>) -> bool {
+        let timeout = timeout.and_then(|seconds| Duration::try_from_secs_f64(seconds.max(0.0)).ok());
+        match &self.loading {
+            Some(load) => py.allow_threads(|| load.wait(timeout)) == LoadStage::Ready || self.is_initialized,
+            None => self.is_initialized,
+        }
+    }
+    
+    /// Why the last background load failed, if it did
+    fn load_error(&self) -> Option<String> {
+        self.loading.as_ref().and_then(|load| load.error())
+    }
+    
+    /// Approximate memory held by the active and standby language models
+    /// 
+    /// Returns a dict with "model_bytes" (active model) and
+    /// "standby_bytes" (models kept for `switch_language`), estimated
+    /// from the model files.
+    fn memory_usage(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let active = self.engine.lock().unwrap().as_ref().map_or(0, |engine| engine.memory_bytes());
+        let usage = PyDict::new(py);
+        usage.set_item("model_bytes", active)?;
+        usage.set_item("standby_bytes", self.standby.values().map(|engine| engine.memory_bytes()).sum::<u64>())?;
+        Ok(usage.into())
+    }
+    
+    /// Process audio frame and return the recognized text when an utterance ends
+    fn process(&mut self, py: Python, audio_frame: Vec<i16>) -> PyResult<Option<String>> {
+        let result = self.accept(py, audio_frame)?;
+        Ok(result.filter(|r| r.is_final).map(|r| r.text))
+    }
+    
+    /// Process audio frame and return a partial or final `RecognitionResult`
+    /// 
+    /// While the user is speaking the result is partial and carries the live
+    /// hypothesis; once the utterance ends it is final. Returns None while
+    /// nothing has been heard (always, for the Whisper engine).
+    fn process_streaming(&mut self, py: Python, audio_frame: Vec<i16>) -> PyResult<Option<RecognitionResult>> {
+        self.accept(py, audio_frame)
+    }
+    
+    /// Get the current partial hypothesis for the utterance in progress
+    fn partial_result(&mut self) -> PyResult<String> {
+        with_engine(&self.engine, |e| Ok(e.partial()))
+    }
+    
+    /// Force the end of the current utterance and return its final text
+    /// 
+    /// Use this when the caller's own endpointing (e.g. push-to-talk release)
+    /// decides the user has finished speaking.
+    fn final_result(&mut self, py: Python) -> PyResult<Option<String>> {
+        Ok(self.final_result_detailed(py)?.map(|r| r.text))
+    }
+    
+    /// Force the end of the current utterance and return it as a `RecognitionResult`
+    fn final_result_detailed(&mut self, py: Python) -> PyResult<Option<RecognitionResult>> {
+        let engine = self.engine.clone();
+        py.allow_threads(|| with_engine(&engine, |e| e.finish()))
+    }
+    
+    /// Enable per-word start/end times and confidences in results
+    /// 
+    /// Applies to final results and, if `partial` is True, to partial
+    /// results as well.
+    #[pyo3(signature = (enabled, partial=false))]
+    fn set_word_timestamps(&mut self, enabled: bool, partial: bool) -> PyResult<()> {
+        with_engine(&self.engine, |e| {
+            e.set_words(enabled, partial);
+            Ok(())
+        })?;
+        self.standby.values_mut().for_each(|engine| engine.set_words(enabled, partial));
+        self.words = enabled;
+        self.partial_words = enabled && partial;
+        Ok(())
+    }
+    
+    /// Load a model for a language, e.g. `load_model("models/vosk-ne", "ne-NP")`
+    /// 
+    /// The model is kept in memory alongside the others so `switch_language`
+    /// is instant. If no model is active yet, this one becomes active. For
+    /// the Whisper engine the tag's primary subtag is used as the language.
+    fn load_model(&mut self, py: Python, path: String, language_tag: String) -> PyResult<()> {
+        if !Path::new(&path).exists() {
+            return Err(ModelError::new_err(format!("Model not found: {}", path)));
         }
         
-        // This is a placeholder for actual Vosk initialization
-        // In the real implementation, we would initialize the Vosk model here
-        // For now, we just check if the model directory exists
-        
-        self.is_initialized = true;
-        self.is_active = true;
+        self.cancel_loading();
+        let language = language_tag.split(['-', '_']).next().map(|l| l.to_lowercase());
+        let engine = self.load_engine(py, &path, language)?;
+        let mut active = self.engine.lock().unwrap();
         
+        if active.is_none() {
+            *active = Some(engine);
+            self.model_path = path;
+            self.language_tag = Some(language_tag);
+            self.is_initialized = true;
+            self.is_active = true;
+        } else if self.language_tag.as_deref() == Some(language_tag.as_str()) {
+            *active = Some(engine);
+            self.model_path = path;
+        } else {
+            self.standby.insert(language_tag, engine);
+        }
         Ok(())
     }
     
-    /// Process audio frame and return recognized text
-    fn process(&self, py: Python, audio_frame: Vec<i16>) -> PyResult<Option<String>> {
-        if !self.is_initialized {
-            return Err(PyValueError::new_err("Speech recognizer not initialized"));
+    /// Make a previously loaded language model the active one
+    /// 
+    /// Any utterance in progress is discarded; the caller can keep feeding
+    /// frames from the same audio stream.
+    fn switch_language(&mut self, language_tag: String) -> PyResult<()> {
+        if self.language_tag.as_deref() == Some(language_tag.as_str()) {
+            return Ok(());
         }
         
+        let mut engine = self.standby.remove(&language_tag).ok_or_else(|| {
+            ModelError::new_err(format!("No model loaded for language '{}'", language_tag))
+        })?;
+        engine.reset();
+        
+        let previous = self.engine.lock().unwrap().replace(engine);
+        if let Some(previous) = previous {
+            let previous_tag = self.language_tag.clone().unwrap_or_else(|| "default".to_string());
+            self.standby.insert(previous_tag, previous);
+        }
+        self.language_tag = Some(language_tag);
+        Ok(())
+    }
+    
+    /// Get the tag of the active language, if one was set with `load_model`
+    fn get_language(&self) -> Option<String> {
+        self.language_tag.clone()
+    }
+    
+    /// Get the tags of all loaded languages
+    fn get_languages(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.standby.keys().cloned().collect();
+        tags.extend(self.language_tag.clone());
+        tags.sort();
+        tags
+    }
+    
+    /// Transcribe a WAV or FLAC file and return the transcript with word timestamps
+    /// 
+    /// The file is downmixed and resampled to 16 kHz and recognized with a
+    /// separate decoder, so a live stream fed to `process` is unaffected.
+    /// Word `start`/`end` times are seconds from the start of the file.
+    fn transcribe_file(&mut self, py: Python, path: String) -> PyResult<RecognitionResult> {
+        let engine = self.engine.clone();
+        py.allow_threads(|| with_engine(&engine, |e| transcribe_path(e, &path)))
+    }
+    
+    /// Transcribe a WAV or FLAC file without blocking the event loop
+    /// 
+    /// Returns an awaitable that resolves to the same `RecognitionResult` as
+    /// `transcribe_file`. Decoding runs on a worker thread.
+    fn transcribe<'py>(&self, py: Python<'py>, path: String) -> PyResult<&'py PyAny> {
+        let engine = self.engine.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            tokio::task::spawn_blocking(move || with_engine(&engine, |e| transcribe_path(e, &path)))
+                .await
+                .map_err(|err| PyValueError::new_err(err.to_string()))?
+        })
+    }
+    
+    /// Queue a frame for recognition on the worker thread
+    /// 
+    /// Returns immediately. When a frame completes an utterance,
+    /// `callback(result, error)` is called from the worker thread with the
+    /// final `RecognitionResult`; on failure `result` is None and `error`
+    /// holds the message. Frames are processed in order. Returns
+    /// False without queueing if the recognizer is inactive.
+    fn process_async(&mut self, py: Python, audio_frame: Vec<i16>, callback: PyObject) -> PyResult<bool> {
+        if !self.ready() {
+            return Err(NotInitializedError::new_err("Speech recognizer not initialized"));
+        }
+        if !callback.as_ref(py).is_callable() {
+            return Err(PyValueError::new_err("Callback must be callable"));
+        }
         if !self.is_active {
-            return Ok(None);
+            return Ok(false);
         }
         
-        // This is a placeholder for actual Vosk processing
-        // In the real implementation, we would process the audio frame with Vosk
-        // For now, we just return None to indicate no recognition result
-        
-        Ok(None)
+        self.submit(SttJob::Frame(audio_frame, callback))?;
+        Ok(true)
     }
     
-    /// Reset the recognizer state
-    fn reset(&mut self) -> PyResult<()> {
-        if !self.is_initialized {
-            return Err(PyValueError::new_err("Speech recognizer not initialized"));
+    /// Transcribe a file on the worker thread and report through `callback(result, error)`
+    /// 
+    /// From asyncio, wrap the callback with `loop.call_soon_threadsafe` to
+    /// resolve a future and keep the event loop responsive.
+    fn transcribe_file_async(&mut self, py: Python, path: String, callback: PyObject) -> PyResult<()> {
+        if !self.ready() {
+            return Err(NotInitializedError::new_err("Speech recognizer not initialized"));
+        }
+        if !callback.as_ref(py).is_callable() {
+            return Err(PyValueError::new_err("Callback must be callable"));
         }
         
-        // This is a placeholder for actual Vosk reset
-        // In the real implementation, we would reset the Vosk recognizer
+        self.submit(SttJob::File(path, callback))
+    }
+    
+    /// Constrain recognition to a list of phrases (Vosk only)
+    /// 
+    /// Use the known skill vocabulary and device names to improve accuracy.
+    /// Anything else is reported as "[unk]", which is added to the list
+    /// automatically. The utterance in progress is discarded.
+    fn set_grammar(&mut self, phrases: Vec<String>) -> PyResult<()> {
+        if phrases.is_empty() {
+            return Err(PyValueError::new_err("Grammar must contain at least one phrase"));
+        }
         
+        let mut grammar: Vec<String> = phrases.iter().map(|p| p.trim().to_lowercase()).collect();
+        if !grammar.iter().any(|p| p == "[unk]") {
+            grammar.push("[unk]".to_string());
+        }
+        with_engine(&self.engine, |e| e.set_grammar(Some(grammar)))
+    }
+    
+    /// Filter profanity and other listed words out of every result, or stop filtering with None
+    /// 
+    /// Applies to partial and final results, word lists, file transcriptions
+    /// and results delivered to callbacks or a `VoicePipeline`. The filter is
+    /// copied; call this again after changing it.
+    #[pyo3(signature = (content_filter=None))]
+    fn set_content_filter(&mut self, content_filter: Option<PyRef<ContentFilter>>) {
+        self.post.write().unwrap().filter = content_filter.map(|f| f.filter.clone());
+    }
+    
+    /// Check whether a content filter is applied to results
+    fn has_content_filter(&self) -> bool {
+        self.post.read().unwrap().filter.is_some()
+    }
+    
+    /// Punctuate and recase final results with a `TranscriptFormatter`, or stop with None
+    /// 
+    /// Runs after the content filter. Partial results are left as they are,
+    /// since they change with every frame. The formatter is copied (a loaded
+    /// model is shared, not reloaded); call this again after changing it.
+    #[pyo3(signature = (formatter=None))]
+    fn set_formatter(&mut self, formatter: Option<PyRef<TranscriptFormatter>>) {
+        self.post.write().unwrap().formatter = formatter.map(|f| f.formatter.clone());
+    }
+    
+    /// Check whether final results are formatted
+    fn has_formatter(&self) -> bool {
+        self.post.read().unwrap().formatter.is_some()
+    }
+    
+    /// Reject final results below `min_confidence`, with fewer than `min_words`
+    /// words, or with a no-speech share above `max_no_speech`
+    /// 
+    /// Rejected results keep their text but have `outcome` "unknown" and
+    /// the reason in `rejection`; a `VoicePipeline` reports them as
+    /// "unknown" events instead of "final". The no-speech share is the
+    /// part of the utterance the VAD heard no speech in. The defaults
+    /// accept everything.
+    #[pyo3(signature = (min_confidence=None, min_words=0, max_no_speech=None))]
+    fn set_acceptance(&mut self, min_confidence: Option<f32>, min_words: usize, max_no_speech: Option<f32>) -> PyResult<()> {
+        let acceptance = Acceptance::new(min_confidence, min_words, max_no_speech).map_err(PyValueError::new_err)?;
+        self.post.write().unwrap().acceptance = acceptance;
         Ok(())
     }
     
+    /// Get the acceptance policy as a dict of `set_acceptance` arguments
+    fn get_acceptance(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let acceptance = self.post.read().unwrap().acceptance;
+        let dict = PyDict::new(py);
+        dict.set_item("min_confidence", acceptance.min_confidence)?;
+        dict.set_item("min_words", acceptance.min_words)?;
+        dict.set_item("max_no_speech", acceptance.max_no_speech)?;
+        Ok(dict.into())
+    }
+    
+    /// Remove the grammar and return to open-vocabulary recognition
+    fn clear_grammar(&mut self) -> PyResult<()> {
+        with_engine(&self.engine, |e| e.set_grammar(None))
+    }
+    
+    /// Reset the recognizer state, discarding any partial utterance
+    fn reset(&mut self) -> PyResult<()> {
+        with_engine(&self.engine, |e| {
+            e.reset();
+            Ok(())
+        })
+    }
+    
     /// Set the sample rate for audio processing
+    /// 
+    /// If the model is loaded the recognizer is recreated at the new rate.
     fn set_sample_rate(&mut self, sample_rate: u32) -> PyResult<()> {
+        if let Some(engine) = self.engine.lock().unwrap().as_mut() {
+            engine.set_sample_rate(sample_rate).map_err(PyValueError::new_err)?;
+        }
         self.sample_rate = sample_rate;
         Ok(())
     }
@@ -89,6 +1436,16 @@ impl SpeechRecognizer {
         self.sample_rate
     }
     
+    /// Get the name of the selected engine backend
+    fn get_engine(&self) -> String {
+        self.engine_name.clone()
+    }
+    
+    /// Get where the loaded model runs ("cpu", "cuda", "metal" or "opencl"), or None before it is loaded
+    fn get_accelerator(&self) -> Option<&'static str> {
+        self.engine.lock().unwrap().as_ref().map(|engine| engine.accelerator().name())
+    }
+    
     /// Check if the recognizer is active
     fn is_active(&self) -> bool {
         self.is_active
@@ -96,86 +1453,643 @@ impl SpeechRecognizer {
     
     /// Set the active state of the recognizer
     fn set_active(&mut self, active: bool) -> PyResult<()> {
-        if active && !self.is_initialized {
-            return Err(PyValueError::new_err("Cannot activate uninitialized recognizer"));
+        if active && !self.ready() {
+            return Err(NotInitializedError::new_err("Cannot activate uninitialized recognizer"));
         }
         
         self.is_active = active;
         Ok(())
     }
     
-    /// Release resources
+    /// Release resources; safe to call more than once
     fn release(&mut self) -> PyResult<()> {
-        // This is a placeholder for actual Vosk resource release
-        // In the real implementation, we would release Vosk resources
-        
+        // The worker finishes queued jobs and exits
+        if let Some(worker) = self.worker.take() {
+            worker.close();
+        }
+        self.cancel_loading();
+        *self.engine.lock().unwrap() = None;
+        self.standby.clear();
+        self.language_tag = None;
         self.is_initialized = false;
         self.is_active = false;
         Ok(())
     }
 }
 
-/// Text-to-speech module that will integrate with Larynx
+/// Plan and render text (or SSML) to i16 PCM at `output_rate`
+#[tracing::instrument(name = "synthesize", level = "debug", skip_all, fields(chars = text.len()))]
+pub fn render_text(
+    voice: &PiperVoice,
+    text: &str,
+    prosody: Prosody,
+    normalizer: Option<&TextNormalizer>,
+    lexicon: &Lexicon,
+    output_rate: u32,
+) -> Result<Vec<i16>, String> {
+    let mut pcm = Vec::new();
+    for unit in plan_speech(voice, text, prosody, normalizer, lexicon)? {
+        pcm.extend(render_unit(voice, &unit, output_rate)?);
+    }
+    Ok(pcm)
+}
+
+/// Play PCM on an output device and wait for it to finish, returning its duration in seconds
+fn play_blocking(pcm: &[i16], sample_rate: u32, device_name: Option<&str>) -> PyResult<f64> {
+    let queue = Arc::new(Mutex::new(VecDeque::new()));
+    let (stream, output_rate) = open_mono_playback(device_name, queue.clone(), None)?;
+    
+    let audio: Vec<f32> = pcm.iter().map(|&s| s as f32 / 32768.0).collect();
+    let audio = resample_mono(&audio, sample_rate, output_rate)?;
+    queue.lock().unwrap().extend(audio);
+    
+    while !queue.lock().unwrap().is_empty() {
+        thread::sleep(PLAYBACK_POLL);
+    }
+    // Let the device drain its own buffer before closing the stream
+    thread::sleep(PLAYBACK_TAIL);
+    drop(stream);
+    Ok(pcm.len() as f64 / sample_rate as f64)
+}
+
+/// Text-to-speech engine using Piper ONNX voice models
 /// 
-/// This is a placeholder implementation that will be replaced with actual
-/// Larynx integration. For now, it provides the interface that will be used.
+/// `model_path` is either a voice `.onnx` file or a directory of voices
+/// (each with its `.onnx.json` config); `voice` selects a voice by file
+/// name. Audio is returned as i16 PCM at the voice's native sample rate
+/// unless a different rate is set with `set_sample_rate`.
 #[pyclass]
 pub struct TextToSpeech {
     model_path: String,
     is_initialized: bool,
     voice: String,
+    loaded: Arc<Mutex<LoadedVoice>>,
+    prosody: Prosody,
+    normalize: bool,
+    normalize_language: Option<String>,
+    lexicon: Lexicon,
+    cache: Arc<Mutex<Option<SynthesisCache>>>,
+    language_voices: HashMap<String, String>,
+    language_tag: Option<String>,
+    memory_map: bool,
+    loading: Option<SharedModelLoad>,
+}
+
+/// Voice model and output rate, shared with background loads and the memory sweeper
+/// 
+/// An idle unload drops only `model`; the rest stays so the voice can be
+/// loaded again on demand, keeping its output rate.
+struct LoadedVoice {
+    model: Option<Arc<PiperVoice>>,
     sample_rate: u32,
+    /// Voice file the model came from, None until a voice is loaded
+    path: Option<PathBuf>,
+    memory_map: bool,
+    language: Option<String>,
+    model_bytes: u64,
+    last_used: Instant,
+    idle_timeout: Option<Duration>,
+}
+
+impl LoadedVoice {
+    fn new(sample_rate: u32) -> Self {
+        LoadedVoice {
+            model: None,
+            sample_rate,
+            path: None,
+            memory_map: false,
+            language: None,
+            model_bytes: 0,
+            last_used: Instant::now(),
+            idle_timeout: None,
+        }
+    }
+    
+    /// Make a freshly loaded voice current and adopt its native sample rate
+    fn install(&mut self, voice: Arc<PiperVoice>, path: PathBuf, memory_map: bool) {
+        self.sample_rate = voice.sample_rate();
+        self.language = voice.config.language.as_ref().map(|language| language.code.clone());
+        self.model_bytes = disk_size(&path);
+        self.model = Some(voice);
+        self.path = Some(path);
+        self.memory_map = memory_map;
+        self.last_used = Instant::now();
+    }
+}
+
+/// Load a voice, or share the one another engine already loaded from the same file
+fn load_voice_model(path: &Path, memory_map: bool) -> Result<Arc<PiperVoice>, String> {
+    shared_model("piper", path, || PiperVoice::load(path, memory_map))
+}
+
+impl LoadedModel for Mutex<LoadedVoice> {
+    fn name(&self) -> String {
+        let loaded = self.lock().unwrap();
+        let voice = loaded.path.as_ref().and_then(|path| path.file_stem()).map(|stem| stem.to_string_lossy().into_owned());
+        format!("tts:{}", voice.unwrap_or_default())
+    }
+    
+    fn memory_bytes(&self) -> u64 {
+        let loaded = self.lock().unwrap();
+        if loaded.model.is_some() {
+            loaded.model_bytes
+        } else {
+            0
+        }
+    }
+    
+    fn idle_for(&self) -> Option<Duration> {
+        let loaded = self.lock().unwrap();
+        loaded.model.as_ref().map(|_| loaded.last_used.elapsed())
+    }
+    
+    fn idle_timeout(&self) -> Option<Duration> {
+        self.lock().unwrap().idle_timeout
+    }
+    
+    fn unload(&self) -> u64 {
+        let mut loaded = self.lock().unwrap();
+        match loaded.model.take() {
+            Some(_) => {
+                tracing::info!(bytes = loaded.model_bytes, "Unloaded idle voice");
+                loaded.model_bytes
+            }
+            None => 0,
+        }
+    }
+}
+
+impl TextToSpeech {
+    /// The loaded voice, loading it again if it was unloaded while idle
+    fn voice_model(&self, py: Python) -> PyResult<Arc<PiperVoice>> {
+        let not_initialized = || NotInitializedError::new_err("Text-to-speech engine not initialized");
+        let (path, memory_map) = {
+            let mut loaded = self.loaded.lock().unwrap();
+            loaded.last_used = Instant::now();
+            if let Some(model) = &loaded.model {
+                return Ok(model.clone());
+            }
+            (loaded.path.clone().ok_or_else(not_initialized)?, loaded.memory_map)
+        };
+        
+        // Not under the lock: Python threads may be waiting on it while holding the GIL
+        let voice = py
+            .allow_threads(|| load_voice_model(&path, memory_map))
+            .map_err(ModelError::new_err)?;
+        tracing::info!(voice = %path.display(), "Reloaded voice after an idle unload");
+        let mut loaded = self.loaded.lock().unwrap();
+        if loaded.path.as_ref() != Some(&path) {
+            // Released or switched to another voice meanwhile
+            return loaded.model.clone().ok_or_else(not_initialized);
+        }
+        Ok(loaded.model.get_or_insert(voice).clone())
+    }
+    
+    fn output_rate(&self) -> u32 {
+        self.loaded.lock().unwrap().sample_rate
+    }
+    
+    /// Language code declared by the loaded voice's config
+    fn voice_language(&self) -> Option<String> {
+        self.loaded.lock().unwrap().language.clone()
+    }
+    
+    /// Whether a voice is loaded, directly or by a finished background load
+    fn ready(&self) -> bool {
+        self.is_initialized || self.loading.as_ref().map_or(false, |load| load.is_ready())
+    }
+    
+    /// Drop the result of a background load that hasn't finished
+    fn cancel_loading(&mut self) {
+        if let Some(load) = self.loading.take() {
+            if load.cancel() {
+                tracing::info!("Cancelled background voice load");
+            }
+            self.is_initialized = self.is_initialized || load.is_ready();
+        }
+    }
+    
+    /// Normalization rules for the override language, else the voice's language
+    fn normalizer(&self) -> Option<TextNormalizer> {
+        if !self.normalize {
+            return None;
+        }
+        let voice_language = self.voice_language();
+        TextNormalizer::for_language(self.normalize_language.as_deref().or(voice_language.as_deref()).unwrap_or("en_US"))
+    }
+    
+    fn cache_key(&self, text: &str) -> String {
+        SynthesisCache::key(text, &self.voice, self.normalizer().as_ref(), &self.lexicon, self.prosody, self.output_rate())
+    }
+    
+    fn cache_get(&self, key: &str) -> Option<Vec<i16>> {
+        self.cache.lock().unwrap().as_mut().and_then(|cache| cache.get(key))
+    }
+    
+    fn cache_insert(&self, key: &str, pcm: &[i16]) {
+        if let Some(cache) = self.cache.lock().unwrap().as_mut() {
+            cache.insert(key, pcm.to_vec());
+        }
+    }
+    
+    /// Load the configured voice and adopt its native sample rate
+    fn load_voice(&mut self, py: Python) -> PyResult<()> {
+        self.cancel_loading();
+        let voice_path = resolve_voice(Path::new(&self.model_path), &self.voice).map_err(ModelError::new_err)?;
+        let memory_map = self.memory_map;
+        let voice = py
+            .allow_threads(|| load_voice_model(&voice_path, memory_map))
+            .map_err(ModelError::new_err)?;
+        
+        self.loaded.lock().unwrap().install(voice, voice_path, memory_map);
+        Ok(())
+    }
+    
+    /// Synthesize text, returning the PCM and its sample rate
+    pub fn render(&self, py: Python, text: &str) -> PyResult<(Vec<i16>, u32)> {
+        Ok((self.synthesize(py, text)?, self.output_rate()))
+    }
+    
+    /// Name of the current voice and its language code, if the voice config declares one
+    pub fn voice_info(&self) -> (String, Option<String>) {
+        (self.voice.clone(), self.voice_language())
+    }
 }
 
 #[pymethods]
 impl TextToSpeech {
     /// Create a new text-to-speech engine
+    /// 
+    /// `memory_map` reads voice models through a read-only memory map.
     #[new]
-    fn new(model_path: String, voice: Option<String>, sample_rate: Option<u32>) -> PyResult<Self> {
+    #[pyo3(signature = (model_path, voice=None, sample_rate=None, memory_map=false))]
+    fn new(model_path: String, voice: Option<String>, sample_rate: Option<u32>, memory_map: bool) -> PyResult<Self> {
         let voice = voice.unwrap_or_else(|| "default".to_string());
         let sample_rate = sample_rate.unwrap_or(22050);
+        let loaded = Arc::new(Mutex::new(LoadedVoice::new(sample_rate)));
+        memory::register(&loaded);
         
         Ok(TextToSpeech {
             model_path,
             is_initialized: false,
             voice,
-            sample_rate,
+            loaded,
+            prosody: Prosody::default(),
+            normalize: true,
+            normalize_language: None,
+            lexicon: Lexicon::default(),
+            cache: Arc::new(Mutex::new(None)),
+            language_voices: HashMap::new(),
+            language_tag: None,
+            memory_map,
+            loading: None,
         })
     }
     
-    /// Initialize the text-to-speech engine with the given model
+    /// Create and initialize a text-to-speech engine from a `JennaConfig`
+    /// 
+    /// `tts_sample_rate`, when set, replaces the voice's native rate.
+    #[staticmethod]
+    fn from_config(py: Python, config: PyRef<JennaConfig>) -> PyResult<Self> {
+        config.check().map_err(PyValueError::new_err)?;
+        let model_path = config
+            .tts_model_path
+            .clone()
+            .ok_or_else(|| PyValueError::new_err("tts_model_path is not set"))?;
+        let mut tts = TextToSpeech::new(model_path, config.tts_voice.clone(), None, config.memory_map_models)?;
+        tts.initialize(py)?;
+        if let Some(sample_rate) = config.tts_sample_rate {
+            tts.loaded.lock().unwrap().sample_rate = sample_rate;
+        }
+        Ok(tts)
+    }
+    
+    /// Initialize the text-to-speech engine by loading the configured voice
     fn initialize(&mut self, py: Python) -> PyResult<()> {
         let model_path = Path::new(&self.model_path);
         
         if !model_path.exists() {
-            return Err(PyValueError::new_err(format!("Model directory not found: {}", model_path.display())));
+            return Err(ModelError::new_err(format!("Model path not found: {}", model_path.display())));
         }
         
-        // This is a placeholder for actual Larynx initialization
-        // In the real implementation, we would initialize the Larynx model here
-        // For now, we just check if the model directory exists
-        
+        self.load_voice(py)?;
         self.is_initialized = true;
         
         Ok(())
     }
     
+    /// Load the configured voice on a background thread and return immediately
+    /// 
+    /// `progress(stage, fraction)` is called from the loading thread as the
+    /// load reaches "loading", "warming_up" and finally "ready" or "failed"
+    /// (see `load_error`). With `warm_up`, a short phrase is synthesized
+    /// before the voice is reported ready, so the first reply doesn't pay
+    /// for the model's first run. Until `is_ready()`, synthesis raises
+    /// `NotInitializedError`.
+    #[pyo3(signature = (progress=None, warm_up=true))]
+    fn initialize_async(&mut self, py: Python, progress: Option<PyObject>, warm_up: bool) -> PyResult<()> {
+        if !Path::new(&self.model_path).exists() {
+            return Err(ModelError::new_err(format!("Model path not found: {}", self.model_path)));
+        }
+        
+        self.cancel_loading();
+        let voice_path = resolve_voice(Path::new(&self.model_path), &self.voice).map_err(ModelError::new_err)?;
+        let memory_map = self.memory_map;
+        let loaded = self.loaded.clone();
+        let loading = spawn_load(
+            py,
+            "tts",
+            progress,
+            move |status| {
+                let voice = load_voice_model(&voice_path, memory_map)?;
+                if warm_up {
+                    status.warming_up();
+                    let rate = voice.sample_rate();
+                    if let Err(err) = render_text(&voice, WARM_UP_TEXT, Prosody::default(), None, &Lexicon::default(), rate) {
+                        tracing::warn!(error = %err, "Voice warm-up failed");
+                    }
+                }
+                Ok((voice, voice_path))
+            },
+            move |(voice, path)| loaded.lock().unwrap().install(voice, path, memory_map),
+        )?;
+        self.loading = Some(loading);
+        Ok(())
+    }
+    
+    /// Check whether a voice is loaded and text can be synthesized
+    fn is_ready(&self) -> bool {
+        self.ready()
+    }
+    
+    /// Wait for a background load to finish, returning whether the voice is ready
+    /// 
+    /// Returns False when `timeout` (seconds) passes first or the load failed.
+    /// An infinite timeout waits as long as None does.
+    #[pyo3(signature = (timeout=None))]
+    fn wait_ready(&self, py: Python, timeout: Option<f64>) -> bool {
+        let timeout = timeout.and_then(|seconds| Duration::try_from_secs_f64(seconds.max(0.0)).ok());
+        match &self.loading {
+            Some(load) => py.allow_threads(|| load.wait(timeout)) == LoadStage::Ready || self.is_initialized,
+            None => self.is_initialized,
+        }
+    }
+    
+    /// Why the last background load failed, if it did
+    fn load_error(&self) -> Option<String> {
+        self.loading.as_ref().and_then(|load| load.error())
+    }
+    
     /// Synthesize speech from text
+    /// 
+    /// Text starting with `<speak>` is parsed as SSML; `<break>`,
+    /// `<emphasis>`, `<prosody rate pitch volume>` and `<say-as>` (characters,
+    /// cardinal, ordinal, digits, date, time) are supported.
     fn synthesize(&self, py: Python, text: &str) -> PyResult<Vec<i16>> {
-        if !self.is_initialized {
-            return Err(PyValueError::new_err("Text-to-speech engine not initialized"));
+        let voice = self.voice_model(py)?;
+        let started = Instant::now();
+        let output_rate = self.output_rate();
+        let key = self.cache_key(text);
+        if let Some(pcm) = self.cache_get(&key) {
+            metrics::observe_latency("tts_first_sample", started.elapsed());
+            return Ok(pcm);
+        }
+        
+        let normalizer = self.normalizer();
+        let pcm = py
+            .allow_threads(|| render_text(&voice, text, self.prosody, normalizer.as_ref(), &self.lexicon, output_rate))
+            .map_err(PyValueError::new_err)?;
+        metrics::observe_latency("tts_first_sample", started.elapsed());
+        
+        self.cache_insert(&key, &pcm);
+        Ok(pcm)
+    }
+    
+    /// Synthesize `text` and play it on `output_device` without blocking the event loop
+    /// 
+    /// Returns an awaitable that resolves to the duration played in seconds
+    /// once playback has finished. Synthesis and playback run on a worker
+    /// thread; the cache is used like `synthesize`.
+    #[pyo3(signature = (text, output_device=None))]
+    fn speak<'py>(&self, py: Python<'py>, text: String, output_device: Option<String>) -> PyResult<&'py PyAny> {
+        let voice = self.voice_model(py)?;
+        let key = self.cache_key(&text);
+        let normalizer = self.normalizer();
+        let lexicon = self.lexicon.clone();
+        let prosody = self.prosody;
+        let output_rate = self.output_rate();
+        let cache = self.cache.clone();
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            tokio::task::spawn_blocking(move || -> PyResult<f64> {
+                let started = Instant::now();
+                let cached = cache.lock().unwrap().as_mut().and_then(|cache| cache.get(&key));
+                let pcm = match cached {
+                    Some(pcm) => pcm,
+                    None => {
+                        let pcm = render_text(&voice, &text, prosody, normalizer.as_ref(), &lexicon, output_rate)
+                            .map_err(PyValueError::new_err)?;
+                        if let Some(cache) = cache.lock().unwrap().as_mut() {
+                            cache.insert(&key, pcm.clone());
+                        }
+                        pcm
+                    }
+                };
+                metrics::observe_latency("tts_first_sample", started.elapsed());
+                play_blocking(&pcm, output_rate, output_device.as_deref())
+            })
+            .await
+            .map_err(|err| PyValueError::new_err(err.to_string()))?
+        })
+    }
+    
+    /// Synthesize sentence by sentence, calling `callback(chunk)` as each is ready
+    /// 
+    /// Each chunk is i16 PCM for one sentence (with the pause before it), so
+    /// playback can start after the first sentence instead of the whole
+    /// text. Accepts SSML like `synthesize`. The GIL is released while
+    /// synthesizing. If the callback returns False, synthesis stops early.
+    /// Returns the number of chunks delivered.
+    fn synthesize_streaming(&self, py: Python, text: &str, callback: PyObject) -> PyResult<usize> {
+        let voice = self.voice_model(py)?;
+        if !callback.as_ref(py).is_callable() {
+            return Err(PyValueError::new_err("Callback must be callable"));
         }
         
-        // This is a placeholder for actual Larynx synthesis
-        // In the real implementation, we would synthesize speech with Larynx
-        // For now, we just return an empty vector
+        let started = Instant::now();
+        let key = self.cache_key(text);
+        if let Some(pcm) = self.cache_get(&key) {
+            metrics::observe_latency("tts_first_sample", started.elapsed());
+            callback.call1(py, (pcm,))?;
+            return Ok(1);
+        }
+        
+        let output_rate = self.output_rate();
+        let normalizer = self.normalizer();
+        let units = py
+            .allow_threads(|| plan_speech(&voice, text, self.prosody, normalizer.as_ref(), &self.lexicon))
+            .map_err(PyValueError::new_err)?;
+        let mut delivered = 0;
+        let mut pending: Vec<i16> = Vec::new();
+        let mut complete: Vec<i16> = Vec::new();
+        
+        // Pauses are carried into the next sentence's chunk
+        for unit in &units {
+            let mut chunk = py
+                .allow_threads(|| render_unit(&voice, unit, output_rate))
+                .map_err(PyValueError::new_err)?;
+            pending.append(&mut chunk);
+            if let SpeechUnit::Pause(_) = unit {
+                continue;
+            }
+            
+            if delivered == 0 {
+                metrics::observe_latency("tts_first_sample", started.elapsed());
+            }
+            complete.extend_from_slice(&pending);
+            let keep_going = callback.call1(py, (std::mem::take(&mut pending),))?;
+            delivered += 1;
+            if keep_going.as_ref(py).eq(false)? {
+                return Ok(delivered);
+            }
+        }
+        if !pending.is_empty() {
+            complete.extend_from_slice(&pending);
+            callback.call1(py, (pending,))?;
+            delivered += 1;
+        }
         
-        Ok(Vec::new())
+        // Only complete utterances are cached
+        self.cache_insert(&key, &complete);
+        Ok(delivered)
+    }
+    
+    /// Enable caching of synthesized audio
+    /// 
+    /// Up to `max_entries` results are kept in memory (least recently used
+    /// are evicted). If `directory` is given, results are also stored there
+    /// and survive restarts. Entries are keyed by text, voice, normalization
+    /// language, lexicon, rate, pitch, volume and sample rate.
+    #[pyo3(signature = (max_entries=128, directory=None))]
+    fn enable_cache(&mut self, max_entries: usize, directory: Option<String>) -> PyResult<()> {
+        *self.cache.lock().unwrap() = Some(SynthesisCache::new(max_entries, directory.map(PathBuf::from)));
+        Ok(())
+    }
+    
+    /// Disable the cache and drop the in-memory entries (disk files are kept)
+    fn disable_cache(&mut self) -> PyResult<()> {
+        *self.cache.lock().unwrap() = None;
+        Ok(())
+    }
+    
+    /// Get cache statistics: hits, misses, hit_rate, entries and memory_bytes
+    fn get_cache_stats(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let guard = self.cache.lock().unwrap();
+        let dict = PyDict::new(py);
+        dict.set_item("enabled", guard.is_some())?;
+        if let Some(cache) = guard.as_ref() {
+            let lookups = cache.hits + cache.misses;
+            dict.set_item("hits", cache.hits)?;
+            dict.set_item("misses", cache.misses)?;
+            dict.set_item("hit_rate", if lookups > 0 { cache.hits as f64 / lookups as f64 } else { 0.0 })?;
+            dict.set_item("entries", cache.len())?;
+            dict.set_item("memory_bytes", cache.memory_bytes())?;
+            dict.set_item("directory", cache.directory().map(|d| d.display().to_string()))?;
+        }
+        Ok(dict.into())
+    }
+    
+    /// Remove all cached audio, including files in the cache directory
+    fn clear_cache(&mut self) -> PyResult<()> {
+        if let Some(cache) = self.cache.lock().unwrap().as_mut() {
+            cache.clear();
+        }
+        Ok(())
+    }
+    
+    /// Enable or disable text normalization before synthesis
+    /// 
+    /// Numbers, dates, times, currencies, abbreviations and units are
+    /// rewritten as words ("3:30pm on 12/05" becomes "three thirty PM on
+    /// December fifth"). Rules follow `language` (e.g. "en_GB" reads dates
+    /// day first) or, if not given, the voice's language.
+    #[pyo3(signature = (enabled, language=None))]
+    fn set_text_normalization(&mut self, enabled: bool, language: Option<String>) -> PyResult<()> {
+        if let Some(language) = &language {
+            if TextNormalizer::for_language(language).is_none() {
+                return Err(PyValueError::new_err(format!("No text normalization rules for language: {}", language)));
+            }
+        }
+        self.normalize = enabled;
+        self.normalize_language = language;
+        Ok(())
+    }
+    
+    /// Get the language whose normalization rules are in effect, or None if disabled
+    fn get_text_normalization(&self) -> Option<String> {
+        self.normalizer().map(|normalizer| normalizer.language().to_string())
+    }
+    
+    /// Return text as it will be spoken after normalization
+    fn normalize_text(&self, text: &str) -> String {
+        match self.normalizer() {
+            Some(normalizer) => normalizer.normalize(text),
+            None => text.to_string(),
+        }
+    }
+    
+    /// Override how a word is pronounced
+    /// 
+    /// `phonemes` are in the voice's phoneme alphabet (IPA for most Piper
+    /// voices), e.g. `add_pronunciation("Mainali", "mʌɪnˈɑːli")`. Matching
+    /// ignores case and surrounding punctuation.
+    fn add_pronunciation(&mut self, word: &str, phonemes: &str) -> PyResult<()> {
+        self.lexicon.insert(word, phonemes).map_err(PyValueError::new_err)
+    }
+    
+    /// Remove a pronunciation override, returning whether it existed
+    fn remove_pronunciation(&mut self, word: &str) -> bool {
+        self.lexicon.remove(word)
+    }
+    
+    /// Get all pronunciation overrides as a word -> phonemes dict
+    fn get_pronunciations(&self) -> HashMap<String, String> {
+        self.lexicon.entries().clone()
+    }
+    
+    /// Remove all pronunciation overrides
+    fn clear_pronunciations(&mut self) -> PyResult<()> {
+        self.lexicon.clear();
+        Ok(())
+    }
+    
+    /// Merge pronunciations from a lexicon file, returning how many were loaded
+    /// 
+    /// Each line holds a word and its phonemes separated by whitespace;
+    /// blank lines and `#` comments are skipped.
+    fn load_lexicon(&mut self, path: &str) -> PyResult<usize> {
+        self.lexicon.load(Path::new(path)).map_err(PyValueError::new_err)
+    }
+    
+    /// Save the pronunciation overrides to a lexicon file
+    fn save_lexicon(&self, path: &str) -> PyResult<()> {
+        self.lexicon.save(Path::new(path)).map_err(PyValueError::new_err)
     }
     
     /// Set the voice for speech synthesis
-    fn set_voice(&mut self, voice: &str) -> PyResult<()> {
-        self.voice = voice.to_string();
+    /// 
+    /// Accepts a voice name from `list_voices`, a dataset name (e.g.
+    /// "lessac") or a language code (e.g. "en_US"), picking the highest
+    /// quality match. If the engine is initialized the new voice is loaded
+    /// immediately.
+    fn set_voice(&mut self, py: Python, voice: &str) -> PyResult<()> {
+        let previous = std::mem::replace(&mut self.voice, voice.to_string());
+        if self.ready() {
+            if let Err(err) = self.load_voice(py) {
+                self.voice = previous;
+                return Err(err);
+            }
+        }
         Ok(())
     }
     
@@ -184,23 +2098,155 @@ impl TextToSpeech {
         self.voice.clone()
     }
     
-    /// Set the sample rate for speech synthesis
+    /// List the voices available in the configured model directory
+    fn get_voices(&self, py: Python) -> PyResult<Vec<VoiceInfo>> {
+        let model_path = Path::new(&self.model_path);
+        if model_path.is_file() {
+            let directory = model_path.parent().unwrap_or(Path::new("."));
+            let voices = list_voices(py, directory.display().to_string())?;
+            return Ok(voices.into_iter().filter(|v| Path::new(&v.path) == model_path).collect());
+        }
+        list_voices(py, self.model_path.clone())
+    }
+    
+    /// Associate a voice with a language tag for `switch_language`
+    fn set_language_voice(&mut self, language_tag: String, voice: String) -> PyResult<()> {
+        self.language_voices.insert(language_tag, voice);
+        Ok(())
+    }
+    
+    /// Switch to the voice registered for a language tag
+    fn switch_language(&mut self, py: Python, language_tag: String) -> PyResult<()> {
+        let voice = self.language_voices.get(&language_tag).cloned().ok_or_else(|| {
+            PyValueError::new_err(format!("No voice registered for language '{}'", language_tag))
+        })?;
+        
+        self.set_voice(py, &voice)?;
+        self.language_tag = Some(language_tag);
+        Ok(())
+    }
+    
+    /// Get the active language tag, if one was selected with `switch_language`
+    fn get_language(&self) -> Option<String> {
+        self.language_tag.clone()
+    }
+    
+    /// Set the speaking rate (0.5 to 2.0, 1.0 is the voice default)
+    /// 
+    /// Applied through the voice's duration model, so speech is slowed down
+    /// or sped up without changing its pitch. Takes effect from the next
+    /// sentence.
+    fn set_rate(&mut self, rate: f32) -> PyResult<()> {
+        if !(0.5..=2.0).contains(&rate) {
+            return Err(PyValueError::new_err("Rate must be between 0.5 and 2.0"));
+        }
+        self.prosody.rate = rate;
+        Ok(())
+    }
+    
+    /// Get the speaking rate
+    fn get_rate(&self) -> f32 {
+        self.prosody.rate
+    }
+    
+    /// Set the pitch (0.5 to 2.0, 1.0 is the voice default)
+    /// 
+    /// Piper voices have no pitch control, so the pitch is shifted in Rust
+    /// while the speaking rate is preserved.
+    fn set_pitch(&mut self, pitch: f32) -> PyResult<()> {
+        if !(0.5..=2.0).contains(&pitch) {
+            return Err(PyValueError::new_err("Pitch must be between 0.5 and 2.0"));
+        }
+        self.prosody.pitch = pitch;
+        Ok(())
+    }
+    
+    /// Get the pitch
+    fn get_pitch(&self) -> f32 {
+        self.prosody.pitch
+    }
+    
+    /// Set the output volume (0.0 to 1.0, relative to full scale)
+    fn set_volume(&mut self, volume: f32) -> PyResult<()> {
+        if !(0.0..=1.0).contains(&volume) {
+            return Err(PyValueError::new_err("Volume must be between 0.0 and 1.0"));
+        }
+        self.prosody.volume = volume;
+        Ok(())
+    }
+    
+    /// Get the output volume
+    fn get_volume(&self) -> f32 {
+        self.prosody.volume
+    }
+    
+    /// Set the output sample rate; synthesized audio is resampled to it
     fn set_sample_rate(&mut self, sample_rate: u32) -> PyResult<()> {
-        self.sample_rate = sample_rate;
+        self.loaded.lock().unwrap().sample_rate = sample_rate;
         Ok(())
     }
     
-    /// Get the sample rate for speech synthesis
+    /// Get the output sample rate (the voice's native rate after loading)
     fn get_sample_rate(&self) -> u32 {
-        self.sample_rate
+        self.output_rate()
+    }
+    
+    /// Get where synthesis runs; always "cpu", since tract has no GPU backend
+    fn get_accelerator(&self) -> &'static str {
+        Accelerator::Cpu.name()
+    }
+    
+    /// Unload the voice after `seconds` unused, or keep it loaded with None
+    /// 
+    /// An unloaded voice is loaded again by the next synthesis, which then
+    /// takes as long as `initialize`. Useful on small devices where the
+    /// assistant speaks rarely.
+    #[pyo3(signature = (seconds=None))]
+    fn set_idle_timeout(&mut self, seconds: Option<f64>) -> PyResult<()> {
+        if seconds.map_or(false, |s| !s.is_finite() || s <= 0.0) {
+            return Err(PyValueError::new_err("Idle timeout must be positive"));
+        }
+        let idle_timeout = seconds
+            .map(Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|_| PyValueError::new_err("Idle timeout is too large"))?;
+        self.loaded.lock().unwrap().idle_timeout = idle_timeout;
+        if seconds.is_some() {
+            memory::start_sweeper();
+        }
+        Ok(())
+    }
+    
+    /// Unload the voice now; the next synthesis loads it again
+    /// 
+    /// Returns the approximate bytes freed.
+    fn unload(&self) -> u64 {
+        self.loaded.unload()
+    }
+    
+    /// Approximate memory held by the voice and the synthesis cache
+    /// 
+    /// Returns a dict with "model_bytes" (estimated from the voice file,
+    /// 0 while unloaded), "cache_bytes" and "idle_seconds" (None while
+    /// unloaded).
+    fn memory_usage(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let cache_bytes = self.cache.lock().unwrap().as_ref().map_or(0, |cache| cache.memory_bytes());
+        let usage = PyDict::new(py);
+        usage.set_item("model_bytes", self.loaded.memory_bytes())?;
+        usage.set_item("cache_bytes", cache_bytes)?;
+        usage.set_item("idle_seconds", self.loaded.idle_for().map(|idle| idle.as_secs_f64()))?;
+        Ok(usage.into())
     }
     
     /// Release resources
     fn release(&mut self) -> PyResult<()> {
-        // This is a placeholder for actual Larynx resource release
-        // In the real implementation, we would release Larynx resources
-        
+        self.cancel_loading();
+        {
+            let mut loaded = self.loaded.lock().unwrap();
+            loaded.model = None;
+            loaded.path = None;
+        }
         self.is_initialized = false;
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,1412 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{PyBytes, PyDict};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::errors::JennaError;
+use crate::intent::IntentMatch;
+use crate::pipeline::PipelineEvent;
+use crate::wake_word::unix_time;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Storage I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    
+    #[error("Insufficient disk space: {required} bytes required, {available} bytes available above the reserve")]
+    InsufficientSpace { required: u64, available: u64 },
+    
+    #[error("Quota exceeded for {category}: {used} of {quota} bytes used, {required} more requested")]
+    QuotaExceeded { category: String, used: u64, quota: u64, required: u64 },
+    
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    
+    #[error("Database schema version {found} is newer than the supported version {supported}")]
+    SchemaVersion { found: i32, supported: i32 },
+    
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    
+    #[error("File was encrypted with key {0}, which is not in the key ring")]
+    UnknownKey(String),
+}
+
+impl From<StorageError> for PyErr {
+    fn from(err: StorageError) -> PyErr {
+        JennaError::new_err(err.to_string())
+    }
+}
+
+/// Compute the total size in bytes of all files below `path`
+pub fn directory_size(path: &Path) -> Result<u64, StorageError> {
+    if path.is_file() {
+        return Ok(fs::metadata(path)?.len());
+    }
+    if !path.is_dir() {
+        return Ok(0);
+    }
+    
+    let mut total = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            total += directory_size(&entry.path())?;
+        } else if file_type.is_file() {
+            total += entry.metadata()?.len();
+        }
+    }
+    Ok(total)
+}
+
+/// Category model downloads are checked under
+pub const MODELS_CATEGORY: &str = "models";
+
+/// Category session recordings are checked under
+pub const RECORDINGS_CATEGORY: &str = "recordings";
+
+/// Per-category quota configuration
+struct CategoryQuota {
+    directory: PathBuf,
+    max_bytes: u64,
+}
+
+/// Guard that checks free disk space and per-category quotas before writing
+/// 
+/// Model downloads ("models") and session recordings ("recordings") check
+/// the bytes they are about to write against it. The check fails when the write would eat into the
+/// reserved free space or exceed the category quota, and a warning event is
+/// emitted the first time usage crosses the warning threshold.
+#[pyclass]
+pub struct StorageGuard {
+    root: PathBuf,
+    #[pyo3(get, set)]
+    reserve_bytes: u64,
+    #[pyo3(get)]
+    warning_threshold: f64,
+    quotas: HashMap<String, CategoryQuota>,
+    warned: HashSet<String>,
+    callback: Option<PyObject>,
+}
+
+impl StorageGuard {
+    /// Free space on the volume holding the root directory
+    pub fn available_space(&self) -> Result<u64, StorageError> {
+        // Walk up to the nearest existing ancestor so checks work before the directory is created
+        let mut probe = self.root.as_path();
+        while !probe.exists() {
+            match probe.parent() {
+                Some(parent) => probe = parent,
+                None => break,
+            }
+        }
+        Ok(fs2::available_space(probe)?)
+    }
+    
+    /// Check whether `required_bytes` can be written for `category`, returning any warnings to emit
+    pub fn evaluate(&mut self, category: &str, required_bytes: u64) -> Result<Vec<(String, u64, u64)>, StorageError> {
+        let mut warnings = Vec::new();
+        
+        let available = self.available_space()?;
+        let usable = available.saturating_sub(self.reserve_bytes);
+        if required_bytes > usable {
+            return Err(StorageError::InsufficientSpace { required: required_bytes, available: usable });
+        }
+        
+        // Warn when the write would bring free space close to the reserve
+        let remaining = available - required_bytes;
+        let low_space_mark = (self.reserve_bytes as f64 / self.warning_threshold) as u64;
+        if remaining < low_space_mark {
+            if self.warned.insert("disk".to_string()) {
+                warnings.push(("disk".to_string(), remaining, low_space_mark));
+            }
+        } else {
+            self.warned.remove("disk");
+        }
+        
+        if let Some(quota) = self.quotas.get(category) {
+            let used = directory_size(&quota.directory)?;
+            if used + required_bytes > quota.max_bytes {
+                return Err(StorageError::QuotaExceeded {
+                    category: category.to_string(),
+                    used,
+                    quota: quota.max_bytes,
+                    required: required_bytes,
+                });
+            }
+            
+            let projected = used + required_bytes;
+            if projected as f64 >= quota.max_bytes as f64 * self.warning_threshold {
+                if self.warned.insert(category.to_string()) {
+                    warnings.push((category.to_string(), projected, quota.max_bytes));
+                }
+            } else {
+                self.warned.remove(category);
+            }
+        }
+        
+        Ok(warnings)
+    }
+    
+    /// Check a write from Rust, as `check` does; a failing warning callback is printed
+    pub fn admit(&mut self, py: Python, category: &str, required_bytes: u64) -> Result<(), StorageError> {
+        let warnings = self.evaluate(category, required_bytes)?;
+        if let Err(err) = self.notify(py, warnings) {
+            err.print(py);
+        }
+        Ok(())
+    }
+    
+    /// Log the crossed thresholds and pass them to the warning callback
+    fn notify(&self, py: Python, warnings: Vec<(String, u64, u64)>) -> PyResult<()> {
+        for (name, used, limit) in warnings {
+            tracing::warn!("Storage threshold crossed for {}: {} of {} bytes", name, used, limit);
+            if let Some(callback) = &self.callback {
+                callback.call1(py, (name, used, limit))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_warning_threshold(warning_threshold: f64) -> PyResult<()> {
+    if !(warning_threshold > 0.0 && warning_threshold <= 1.0) {
+        return Err(PyValueError::new_err("Warning threshold must be above 0.0 and at most 1.0"));
+    }
+    Ok(())
+}
+
+#[pymethods]
+impl StorageGuard {
+    /// Create a new storage guard for the given data directory
+    /// 
+    /// Pass it to `ModelManager` and `VoicePipeline.start_recording` to
+    /// have downloads and recordings checked against it.
+    #[new]
+    #[pyo3(signature = (root, reserve_bytes=1_073_741_824, warning_threshold=0.9))]
+    pub fn new(root: String, reserve_bytes: u64, warning_threshold: f64) -> PyResult<Self> {
+        check_warning_threshold(warning_threshold)?;
+        
+        Ok(StorageGuard {
+            root: PathBuf::from(root),
+            reserve_bytes,
+            warning_threshold,
+            quotas: HashMap::new(),
+            warned: HashSet::new(),
+            callback: None,
+        })
+    }
+    
+    /// Set a quota for a category stored in `subdir` (defaults to the category name)
+    #[pyo3(signature = (category, max_bytes, subdir=None))]
+    fn set_quota(&mut self, category: String, max_bytes: u64, subdir: Option<String>) -> PyResult<()> {
+        let directory = self.root.join(subdir.unwrap_or_else(|| category.clone()));
+        self.quotas.insert(category, CategoryQuota { directory, max_bytes });
+        Ok(())
+    }
+    
+    /// Remove the quota for a category
+    fn remove_quota(&mut self, category: &str) -> PyResult<()> {
+        self.quotas.remove(category);
+        self.warned.remove(category);
+        Ok(())
+    }
+    
+    /// Set the fraction of a quota at which warning events are emitted
+    fn set_warning_threshold(&mut self, warning_threshold: f64) -> PyResult<()> {
+        check_warning_threshold(warning_threshold)?;
+        self.warning_threshold = warning_threshold;
+        Ok(())
+    }
+    
+    /// Register a callback invoked as `callback(category, used_bytes, limit_bytes)` on warnings
+    fn set_warning_callback(&mut self, callback: Option<PyObject>) -> PyResult<()> {
+        self.callback = callback;
+        Ok(())
+    }
+    
+    /// Get the free space in bytes on the volume holding the data directory
+    fn free_space(&self) -> PyResult<u64> {
+        Ok(self.available_space()?)
+    }
+    
+    /// Get the bytes currently used by a category
+    fn usage(&self, category: &str) -> PyResult<u64> {
+        match self.quotas.get(category) {
+            Some(quota) => Ok(directory_size(&quota.directory)?),
+            None => Ok(directory_size(&self.root.join(category))?),
+        }
+    }
+    
+    /// Get usage and quota for every configured category
+    fn report(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let report = PyDict::new(py);
+        for (category, quota) in &self.quotas {
+            let entry = PyDict::new(py);
+            entry.set_item("used", directory_size(&quota.directory)?)?;
+            entry.set_item("quota", quota.max_bytes)?;
+            report.set_item(category, entry)?;
+        }
+        Ok(report.into())
+    }
+    
+    /// Check that `required_bytes` can be written for `category`
+    /// 
+    /// Raises an error if the write would exceed the quota or the disk reserve.
+    fn check(&mut self, py: Python, category: &str, required_bytes: u64) -> PyResult<()> {
+        let warnings = self.evaluate(category, required_bytes)?;
+        self.notify(py, warnings)
+    }
+}
+
+/// Version of the history schema, stored as the database's `user_version`
+const HISTORY_SCHEMA_VERSION: i32 = 1;
+
+const HISTORY_SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS wake_events (
+    id INTEGER PRIMARY KEY,
+    timestamp REAL NOT NULL,
+    keyword TEXT,
+    confidence REAL
+);
+CREATE TABLE IF NOT EXISTS interactions (
+    id INTEGER PRIMARY KEY,
+    timestamp REAL NOT NULL,
+    wake_id INTEGER REFERENCES wake_events(id) ON DELETE SET NULL,
+    transcript TEXT NOT NULL,
+    confidence REAL,
+    intent TEXT,
+    intent_confidence REAL,
+    slots TEXT,
+    correct INTEGER
+);
+CREATE TABLE IF NOT EXISTS timings (
+    interaction_id INTEGER NOT NULL REFERENCES interactions(id) ON DELETE CASCADE,
+    stage TEXT NOT NULL,
+    milliseconds REAL NOT NULL,
+    PRIMARY KEY (interaction_id, stage)
+);
+CREATE INDEX IF NOT EXISTS wake_events_timestamp ON wake_events(timestamp);
+CREATE INDEX IF NOT EXISTS interactions_timestamp ON interactions(timestamp);
+CREATE INDEX IF NOT EXISTS interactions_intent ON interactions(intent);
+";
+
+/// How long a write waits for another process holding the database
+const HISTORY_BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Intent recognized in a stored transcript
+#[derive(Clone, Debug, Default)]
+pub struct IntentRecord {
+    pub name: String,
+    pub confidence: f32,
+    pub slots: HashMap<String, String>,
+}
+
+/// A stored transcript with the wake event that started its turn
+#[derive(Clone, Debug)]
+pub struct InteractionRecord {
+    pub id: i64,
+    pub timestamp: f64,
+    pub wake_id: Option<i64>,
+    /// Keyword of the wake event, None when the turn started without one
+    pub keyword: Option<String>,
+    pub transcript: String,
+    pub confidence: Option<f32>,
+    pub intent: Option<IntentRecord>,
+    /// Whether the interaction was handled correctly, if anyone said
+    pub correct: Option<bool>,
+    /// Stage timings in milliseconds
+    pub timings: HashMap<String, f64>,
+}
+
+#[derive(Clone, Debug)]
+pub struct WakeRecord {
+    pub id: i64,
+    pub timestamp: f64,
+    pub keyword: Option<String>,
+    pub confidence: Option<f32>,
+}
+
+/// Filter for history queries; times are Unix timestamps
+#[derive(Clone, Debug, Default)]
+pub struct HistoryQuery {
+    pub since: Option<f64>,
+    pub until: Option<f64>,
+    pub intent: Option<String>,
+    pub keyword: Option<String>,
+    /// Case-insensitive text the transcript must contain
+    pub contains: Option<String>,
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct StageTiming {
+    pub count: u64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Totals over a time range
+#[derive(Clone, Debug, Default)]
+pub struct HistoryStats {
+    pub interactions: u64,
+    pub wake_events: u64,
+    /// Wake events with no transcript after them (false accepts or abandoned turns)
+    pub unanswered_wakes: u64,
+    /// Interactions no intent was recognized in
+    pub unrecognized: u64,
+    pub intents: HashMap<String, u64>,
+    /// Interactions with feedback, and how many of those were correct
+    pub rated: u64,
+    pub correct: u64,
+    pub timings: HashMap<String, StageTiming>,
+}
+
+/// Append `timestamp >= since AND timestamp < until` conditions for `column`
+fn time_conditions(column: &str, since: Option<f64>, until: Option<f64>, conditions: &mut Vec<String>, params: &mut Vec<SqlValue>) {
+    if let Some(since) = since {
+        conditions.push(format!("{} >= ?", column));
+        params.push(SqlValue::Real(since));
+    }
+    if let Some(until) = until {
+        conditions.push(format!("{} < ?", column));
+        params.push(SqlValue::Real(until));
+    }
+}
+
+fn where_clause(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", conditions.join(" AND "))
+    }
+}
+
+/// Escape LIKE wildcards so `text` matches literally
+fn like_pattern(text: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// Interaction history in an embedded SQLite database
+/// 
+/// Wake events and transcripts are separate tables so wake events that no
+/// transcript followed can be counted; each transcript links to the wake
+/// event that started its turn and carries its intent, optional
+/// correctness feedback and per-stage timings. Several processes may open
+/// the same file; writes wait for each other.
+pub struct History {
+    connection: Mutex<Connection>,
+}
+
+impl History {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        
+        let connection = Connection::open(path)?;
+        connection.busy_timeout(HISTORY_BUSY_TIMEOUT)?;
+        connection.pragma_update(None, "foreign_keys", "ON")?;
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        
+        let version: i32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > HISTORY_SCHEMA_VERSION {
+            return Err(StorageError::SchemaVersion { found: version, supported: HISTORY_SCHEMA_VERSION });
+        }
+        connection.execute_batch(HISTORY_SCHEMA)?;
+        connection.pragma_update(None, "user_version", HISTORY_SCHEMA_VERSION)?;
+        
+        Ok(History { connection: Mutex::new(connection) })
+    }
+    
+    pub fn record_wake(&self, timestamp: f64, keyword: Option<&str>, confidence: Option<f32>) -> Result<i64, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            "INSERT INTO wake_events (timestamp, keyword, confidence) VALUES (?1, ?2, ?3)",
+            params![timestamp, keyword, confidence],
+        )?;
+        Ok(connection.last_insert_rowid())
+    }
+    
+    pub fn record_interaction(
+        &self,
+        timestamp: f64,
+        wake_id: Option<i64>,
+        transcript: &str,
+        confidence: Option<f32>,
+        intent: Option<&IntentRecord>,
+        timings: &HashMap<String, f64>,
+    ) -> Result<i64, StorageError> {
+        // Slot maps only hold strings, so serializing can't fail
+        let slots = intent.map(|intent| serde_json::to_string(&intent.slots).unwrap_or_default());
+        
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        transaction.execute(
+            "INSERT INTO interactions (timestamp, wake_id, transcript, confidence, intent, intent_confidence, slots)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                timestamp,
+                wake_id,
+                transcript,
+                confidence,
+                intent.map(|intent| &intent.name),
+                intent.map(|intent| intent.confidence),
+                slots,
+            ],
+        )?;
+        let id = transaction.last_insert_rowid();
+        for (stage, milliseconds) in timings {
+            transaction.execute(
+                "INSERT INTO timings (interaction_id, stage, milliseconds) VALUES (?1, ?2, ?3)",
+                params![id, stage, milliseconds],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(id)
+    }
+    
+    /// Mark an interaction as handled correctly or not, returning whether it exists
+    pub fn set_feedback(&self, id: i64, correct: bool) -> Result<bool, StorageError> {
+        let connection = self.connection.lock().unwrap();
+        let updated = connection.execute("UPDATE interactions SET correct = ?1 WHERE id = ?2", params![correct, id])?;
+        Ok(updated > 0)
+    }
+    
+    /// Interactions matching `query`, newest first
+    pub fn interactions(&self, query: &HistoryQuery) -> Result<Vec<InteractionRecord>, StorageError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        time_conditions("i.timestamp", query.since, query.until, &mut conditions, &mut values);
+        if let Some(intent) = &query.intent {
+            conditions.push("i.intent = ?".to_string());
+            values.push(SqlValue::Text(intent.clone()));
+        }
+        if let Some(keyword) = &query.keyword {
+            conditions.push("w.keyword = ?".to_string());
+            values.push(SqlValue::Text(keyword.clone()));
+        }
+        if let Some(text) = &query.contains {
+            conditions.push("i.transcript LIKE ? ESCAPE '\\'".to_string());
+            values.push(SqlValue::Text(like_pattern(text)));
+        }
+        values.push(SqlValue::Integer(query.limit as i64));
+        
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(&format!(
+            "SELECT i.id, i.timestamp, i.wake_id, w.keyword, i.transcript, i.confidence,
+                    i.intent, i.intent_confidence, i.slots, i.correct
+             FROM interactions i LEFT JOIN wake_events w ON w.id = i.wake_id
+             {}
+             ORDER BY i.timestamp DESC, i.id DESC
+             LIMIT ?",
+            where_clause(&conditions)
+        ))?;
+        let mut records = statement
+            .query_map(params_from_iter(values), |row| {
+                let intent = row.get::<_, Option<String>>(6)?.map(|name| IntentRecord {
+                    name,
+                    confidence: row.get::<_, Option<f32>>(7).ok().flatten().unwrap_or(0.0),
+                    slots: row
+                        .get::<_, Option<String>>(8)
+                        .ok()
+                        .flatten()
+                        .and_then(|slots| serde_json::from_str(&slots).ok())
+                        .unwrap_or_default(),
+                });
+                Ok(InteractionRecord {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    wake_id: row.get(2)?,
+                    keyword: row.get(3)?,
+                    transcript: row.get(4)?,
+                    confidence: row.get(5)?,
+                    intent,
+                    correct: row.get(9)?,
+                    timings: HashMap::new(),
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let mut timings = connection.prepare("SELECT stage, milliseconds FROM timings WHERE interaction_id = ?1")?;
+        for record in &mut records {
+            record.timings = timings
+                .query_map(params![record.id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<_, _>>()?;
+        }
+        Ok(records)
+    }
+    
+    /// Wake events matching the time range and keyword of `query`, newest first
+    pub fn wake_events(&self, query: &HistoryQuery) -> Result<Vec<WakeRecord>, StorageError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        time_conditions("timestamp", query.since, query.until, &mut conditions, &mut values);
+        if let Some(keyword) = &query.keyword {
+            conditions.push("keyword = ?".to_string());
+            values.push(SqlValue::Text(keyword.clone()));
+        }
+        values.push(SqlValue::Integer(query.limit as i64));
+        
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(&format!(
+            "SELECT id, timestamp, keyword, confidence FROM wake_events {} ORDER BY timestamp DESC, id DESC LIMIT ?",
+            where_clause(&conditions)
+        ))?;
+        let records = statement
+            .query_map(params_from_iter(values), |row| {
+                Ok(WakeRecord { id: row.get(0)?, timestamp: row.get(1)?, keyword: row.get(2)?, confidence: row.get(3)? })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(records)
+    }
+    
+    pub fn stats(&self, since: Option<f64>, until: Option<f64>) -> Result<HistoryStats, StorageError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        time_conditions("i.timestamp", since, until, &mut conditions, &mut values);
+        let interactions_where = where_clause(&conditions);
+        conditions.push("i.intent IS NOT NULL".to_string());
+        let intents_where = where_clause(&conditions);
+        let interaction_values = values;
+        
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        time_conditions("w.timestamp", since, until, &mut conditions, &mut values);
+        let wake_where = where_clause(&conditions);
+        let wake_values = values;
+        
+        let connection = self.connection.lock().unwrap();
+        let mut stats = HistoryStats::default();
+        
+        (stats.interactions, stats.unrecognized, stats.rated, stats.correct) = connection.query_row(
+            &format!(
+                "SELECT COUNT(*), COUNT(*) - COUNT(i.intent), COUNT(i.correct), COALESCE(SUM(i.correct), 0)
+                 FROM interactions i {}",
+                interactions_where
+            ),
+            params_from_iter(interaction_values.iter()),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )?;
+        (stats.wake_events, stats.unanswered_wakes) = connection.query_row(
+            &format!(
+                "SELECT COUNT(*), COUNT(*) - COUNT(DISTINCT i.wake_id)
+                 FROM wake_events w LEFT JOIN interactions i ON i.wake_id = w.id {}",
+                wake_where
+            ),
+            params_from_iter(wake_values.iter()),
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        
+        let mut intents = connection.prepare(&format!(
+            "SELECT i.intent, COUNT(*) FROM interactions i {} GROUP BY i.intent",
+            intents_where
+        ))?;
+        stats.intents = intents
+            .query_map(params_from_iter(interaction_values.iter()), |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        
+        let mut timings = connection.prepare(&format!(
+            "SELECT t.stage, COUNT(*), AVG(t.milliseconds), MAX(t.milliseconds)
+             FROM timings t JOIN interactions i ON i.id = t.interaction_id {} GROUP BY t.stage",
+            interactions_where
+        ))?;
+        stats.timings = timings
+            .query_map(params_from_iter(interaction_values.iter()), |row| {
+                Ok((row.get(0)?, StageTiming { count: row.get(1)?, mean_ms: row.get(2)?, max_ms: row.get(3)? }))
+            })?
+            .collect::<Result<_, _>>()?;
+        Ok(stats)
+    }
+    
+    /// Delete everything recorded before `timestamp`, returning the number of interactions and wake events removed
+    pub fn delete_before(&self, timestamp: f64) -> Result<(usize, usize), StorageError> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction()?;
+        let interactions = transaction.execute("DELETE FROM interactions WHERE timestamp < ?1", params![timestamp])?;
+        let wake_events = transaction.execute("DELETE FROM wake_events WHERE timestamp < ?1", params![timestamp])?;
+        transaction.commit()?;
+        Ok((interactions, wake_events))
+    }
+}
+
+fn interaction_dict(py: Python, record: InteractionRecord) -> PyResult<Py<PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("id", record.id)?;
+    dict.set_item("timestamp", record.timestamp)?;
+    dict.set_item("wake_id", record.wake_id)?;
+    dict.set_item("keyword", record.keyword)?;
+    dict.set_item("transcript", record.transcript)?;
+    dict.set_item("confidence", record.confidence)?;
+    match record.intent {
+        Some(intent) => {
+            dict.set_item("intent", intent.name)?;
+            dict.set_item("intent_confidence", intent.confidence)?;
+            dict.set_item("slots", intent.slots)?;
+        }
+        None => {
+            dict.set_item("intent", py.None())?;
+            dict.set_item("intent_confidence", py.None())?;
+            dict.set_item("slots", PyDict::new(py))?;
+        }
+    }
+    dict.set_item("correct", record.correct)?;
+    dict.set_item("timings", record.timings)?;
+    Ok(dict.into())
+}
+
+/// Persistent history of wake events, transcripts, intents and timings
+/// 
+/// Backed by an SQLite database at `path` (created if missing), so the
+/// assistant can look back at earlier requests ("what did I ask you this
+/// morning?") and accuracy and latency can be tracked over time. Times are
+/// Unix timestamps. Events can be recorded one by one or straight from a
+/// `VoicePipeline` with `record_event`.
+#[pyclass]
+pub struct InteractionHistory {
+    history: History,
+    #[pyo3(get)]
+    path: String,
+    /// Id and time of the last wake event recorded through `record_event`
+    last_wake: Mutex<Option<(i64, f64)>>,
+}
+
+#[pymethods]
+impl InteractionHistory {
+    #[new]
+    fn new(path: String) -> PyResult<Self> {
+        let history = History::open(Path::new(&path))?;
+        Ok(InteractionHistory { history, path, last_wake: Mutex::new(None) })
+    }
+    
+    /// Record a wake event, returning its id; `keyword` is None for turns started another way
+    #[pyo3(signature = (keyword=None, confidence=None, timestamp=None))]
+    fn record_wake(&self, keyword: Option<&str>, confidence: Option<f32>, timestamp: Option<f64>) -> PyResult<i64> {
+        Ok(self.history.record_wake(timestamp.unwrap_or_else(unix_time), keyword, confidence)?)
+    }
+    
+    /// Record a transcript, returning its id
+    /// 
+    /// `intent` is the `IntentMatch` recognized in it, `wake_id` the wake
+    /// event that started the turn and `timings` maps stage names to
+    /// milliseconds.
+    #[pyo3(signature = (transcript, confidence=None, intent=None, wake_id=None, timings=None, timestamp=None))]
+    fn record_interaction(
+        &self,
+        transcript: &str,
+        confidence: Option<f32>,
+        intent: Option<PyRef<IntentMatch>>,
+        wake_id: Option<i64>,
+        timings: Option<HashMap<String, f64>>,
+        timestamp: Option<f64>,
+    ) -> PyResult<i64> {
+        let intent = intent.map(|intent| IntentRecord {
+            name: intent.intent.clone(),
+            confidence: intent.confidence,
+            slots: intent.slots.clone(),
+        });
+        Ok(self.history.record_interaction(
+            timestamp.unwrap_or_else(unix_time),
+            wake_id,
+            transcript,
+            confidence,
+            intent.as_ref(),
+            &timings.unwrap_or_default(),
+        )?)
+    }
+    
+    /// Record a `PipelineEvent`, returning the id of the new row
+    /// 
+    /// "wake" events become wake events and "final" events interactions
+    /// linked to the last wake recorded here, with the time from wake to
+    /// final transcript as the "turn" timing. Other events are ignored and
+    /// return None.
+    #[pyo3(signature = (event, intent=None, timings=None))]
+    fn record_event(
+        &self,
+        event: PyRef<PipelineEvent>,
+        intent: Option<PyRef<IntentMatch>>,
+        timings: Option<HashMap<String, f64>>,
+    ) -> PyResult<Option<i64>> {
+        match event.kind.as_str() {
+            "wake" => {
+                let id = self.history.record_wake(event.timestamp, event.keyword.as_deref(), event.confidence)?;
+                *self.last_wake.lock().unwrap() = Some((id, event.timestamp));
+                Ok(Some(id))
+            }
+            "final" => {
+                let wake = self.last_wake.lock().unwrap().take();
+                let mut timings = timings.unwrap_or_default();
+                if let Some((_, woke)) = wake {
+                    timings.entry("turn".to_string()).or_insert((event.timestamp - woke) * 1000.0);
+                }
+                let intent = intent.map(|intent| IntentRecord {
+                    name: intent.intent.clone(),
+                    confidence: intent.confidence,
+                    slots: intent.slots.clone(),
+                });
+                let id = self.history.record_interaction(
+                    event.timestamp,
+                    wake.map(|(id, _)| id),
+                    event.text.as_deref().unwrap_or_default(),
+                    event.confidence,
+                    intent.as_ref(),
+                    &timings,
+                )?;
+                Ok(Some(id))
+            }
+            _ => Ok(None),
+        }
+    }
+    
+    /// Mark an interaction as handled correctly or not, returning False if it doesn't exist
+    fn set_feedback(&self, interaction_id: i64, correct: bool) -> PyResult<bool> {
+        Ok(self.history.set_feedback(interaction_id, correct)?)
+    }
+    
+    /// Get interactions as dicts, newest first
+    /// 
+    /// Filters by time range (`since` inclusive, `until` exclusive), intent
+    /// name, wake keyword and text contained in the transcript (ignoring
+    /// case).
+    #[pyo3(signature = (since=None, until=None, intent=None, keyword=None, contains=None, limit=50))]
+    #[allow(clippy::too_many_arguments)]
+    fn interactions(
+        &self,
+        py: Python,
+        since: Option<f64>,
+        until: Option<f64>,
+        intent: Option<String>,
+        keyword: Option<String>,
+        contains: Option<String>,
+        limit: usize,
+    ) -> PyResult<Vec<Py<PyDict>>> {
+        let query = HistoryQuery { since, until, intent, keyword, contains, limit };
+        self.history
+            .interactions(&query)?
+            .into_iter()
+            .map(|record| interaction_dict(py, record))
+            .collect()
+    }
+    
+    /// Get wake events as dicts, newest first
+    #[pyo3(signature = (since=None, until=None, keyword=None, limit=50))]
+    fn wake_events(
+        &self,
+        py: Python,
+        since: Option<f64>,
+        until: Option<f64>,
+        keyword: Option<String>,
+        limit: usize,
+    ) -> PyResult<Vec<Py<PyDict>>> {
+        let query = HistoryQuery { since, until, keyword, limit, ..HistoryQuery::default() };
+        self.history
+            .wake_events(&query)?
+            .into_iter()
+            .map(|record| {
+                let dict = PyDict::new(py);
+                dict.set_item("id", record.id)?;
+                dict.set_item("timestamp", record.timestamp)?;
+                dict.set_item("keyword", record.keyword)?;
+                dict.set_item("confidence", record.confidence)?;
+                Ok(dict.into())
+            })
+            .collect()
+    }
+    
+    /// Summarize a time range
+    /// 
+    /// Returns counts of interactions and wake events, wake events no
+    /// transcript followed, interactions without an intent, counts per
+    /// intent, `accuracy` over the interactions with feedback (None without
+    /// any) and count, mean and max milliseconds per timing stage.
+    #[pyo3(signature = (since=None, until=None))]
+    fn stats(&self, py: Python, since: Option<f64>, until: Option<f64>) -> PyResult<Py<PyDict>> {
+        let stats = self.history.stats(since, until)?;
+        let dict = PyDict::new(py);
+        dict.set_item("interactions", stats.interactions)?;
+        dict.set_item("wake_events", stats.wake_events)?;
+        dict.set_item("unanswered_wakes", stats.unanswered_wakes)?;
+        dict.set_item("unrecognized", stats.unrecognized)?;
+        dict.set_item("intents", stats.intents)?;
+        dict.set_item("rated", stats.rated)?;
+        dict.set_item("accuracy", (stats.rated > 0).then(|| stats.correct as f64 / stats.rated as f64))?;
+        
+        let timings = PyDict::new(py);
+        for (stage, timing) in stats.timings {
+            let entry = PyDict::new(py);
+            entry.set_item("count", timing.count)?;
+            entry.set_item("mean_ms", timing.mean_ms)?;
+            entry.set_item("max_ms", timing.max_ms)?;
+            timings.set_item(stage, entry)?;
+        }
+        dict.set_item("timings", timings)?;
+        Ok(dict.into())
+    }
+    
+    /// Delete everything recorded before `timestamp`, returning the number of rows removed
+    fn delete_before(&self, timestamp: f64) -> PyResult<usize> {
+        let (interactions, wake_events) = self.history.delete_before(timestamp)?;
+        Ok(interactions + wake_events)
+    }
+}
+
+/// Length of an encryption key in bytes (AES-256)
+pub const KEY_LENGTH: usize = 32;
+
+/// Signature of encrypted files; the last two bytes are the layout version
+const ENCRYPTED_MAGIC: &[u8; 8] = b"JNAENC01";
+
+const KEY_ID_LENGTH: usize = 8;
+const FILE_ID_LENGTH: usize = 16;
+const ENCRYPTED_HEADER_LENGTH: usize = 8 + KEY_ID_LENGTH + FILE_ID_LENGTH;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+/// Plaintext bytes per encrypted chunk
+const ENCRYPTION_CHUNK: usize = 64 * 1024;
+
+/// Shortest salt `derive_key` accepts
+const MIN_SALT_LENGTH: usize = 8;
+
+type KeyId = [u8; KEY_ID_LENGTH];
+
+/// Identify a key without revealing it
+fn key_id(key: &[u8]) -> KeyId {
+    let digest = Sha256::new().chain_update(b"jenna-key-id").chain_update(key).finalize();
+    let mut id = [0u8; KEY_ID_LENGTH];
+    id.copy_from_slice(&digest[..KEY_ID_LENGTH]);
+    id
+}
+
+fn key_id_hex(id: &KeyId) -> String {
+    id.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn parse_key_id(text: &str) -> Option<KeyId> {
+    if text.len() != KEY_ID_LENGTH * 2 || !text.is_ascii() {
+        return None;
+    }
+    let mut id = [0u8; KEY_ID_LENGTH];
+    for (index, byte) in id.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&text[index * 2..index * 2 + 2], 16).ok()?;
+    }
+    Some(id)
+}
+
+/// Derive a key from a passphrase with PBKDF2-HMAC-SHA256
+pub fn derive_key(passphrase: &str, salt: &[u8], iterations: u32) -> Result<[u8; KEY_LENGTH], StorageError> {
+    if salt.len() < MIN_SALT_LENGTH {
+        return Err(StorageError::Encryption(format!("Salt must be at least {} bytes", MIN_SALT_LENGTH)));
+    }
+    if iterations == 0 {
+        return Err(StorageError::Encryption("Iteration count must be positive".to_string()));
+    }
+    let mut key = [0u8; KEY_LENGTH];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    Ok(key)
+}
+
+/// Encryption keys for files at rest
+/// 
+/// The primary key encrypts; every key in the ring decrypts, so files
+/// written before a rotation stay readable until they are re-encrypted
+/// and the old key is removed.
+#[derive(Clone)]
+pub struct KeyRing {
+    /// Primary key first
+    keys: Vec<(KeyId, Aes256Gcm)>,
+}
+
+impl KeyRing {
+    pub fn new(key: &[u8]) -> Result<Self, StorageError> {
+        let mut ring = KeyRing { keys: Vec::new() };
+        ring.rotate(key)?;
+        Ok(ring)
+    }
+    
+    fn cipher(key: &[u8]) -> Result<Aes256Gcm, StorageError> {
+        if key.len() != KEY_LENGTH {
+            return Err(StorageError::Encryption(format!("Keys must be {} bytes, got {}", KEY_LENGTH, key.len())));
+        }
+        Aes256Gcm::new_from_slice(key).map_err(|err| StorageError::Encryption(err.to_string()))
+    }
+    
+    pub fn primary_id(&self) -> KeyId {
+        self.keys[0].0
+    }
+    
+    pub fn ids(&self) -> Vec<KeyId> {
+        self.keys.iter().map(|(id, _)| *id).collect()
+    }
+    
+    /// Add a key for decrypting only, returning its id
+    pub fn add(&mut self, key: &[u8]) -> Result<KeyId, StorageError> {
+        let cipher = KeyRing::cipher(key)?;
+        let id = key_id(key);
+        if !self.keys.iter().any(|(existing, _)| *existing == id) {
+            self.keys.push((id, cipher));
+        }
+        Ok(id)
+    }
+    
+    /// Make `key` the primary key, keeping the previous ones for decrypting
+    pub fn rotate(&mut self, key: &[u8]) -> Result<KeyId, StorageError> {
+        let cipher = KeyRing::cipher(key)?;
+        let id = key_id(key);
+        self.keys.retain(|(existing, _)| *existing != id);
+        self.keys.insert(0, (id, cipher));
+        Ok(id)
+    }
+    
+    /// Remove a decrypt-only key, returning whether it was in the ring
+    pub fn remove(&mut self, id: &KeyId) -> Result<bool, StorageError> {
+        if *id == self.primary_id() {
+            return Err(StorageError::Encryption("The primary key cannot be removed; rotate to a new key first".to_string()));
+        }
+        let before = self.keys.len();
+        self.keys.retain(|(existing, _)| existing != id);
+        Ok(self.keys.len() < before)
+    }
+    
+    /// Start an encrypted stream on `inner`, writing its header
+    pub fn writer<W: Write>(&self, mut inner: W) -> io::Result<EncryptedWriter<W>> {
+        let (id, cipher) = &self.keys[0];
+        let mut header = [0u8; ENCRYPTED_HEADER_LENGTH];
+        header[..8].copy_from_slice(ENCRYPTED_MAGIC);
+        header[8..8 + KEY_ID_LENGTH].copy_from_slice(id);
+        OsRng.fill_bytes(&mut header[8 + KEY_ID_LENGTH..]);
+        inner.write_all(&header)?;
+        
+        Ok(EncryptedWriter {
+            inner,
+            cipher: cipher.clone(),
+            header,
+            index: 0,
+            buffer: Vec::with_capacity(ENCRYPTION_CHUNK),
+        })
+    }
+    
+    /// Read an encrypted stream whose signature has already been consumed
+    fn reader_after_magic<R: Read>(&self, mut inner: R) -> Result<DecryptingReader<R>, StorageError> {
+        let mut header = [0u8; ENCRYPTED_HEADER_LENGTH];
+        header[..8].copy_from_slice(ENCRYPTED_MAGIC);
+        inner.read_exact(&mut header[8..])?;
+        let mut id = [0u8; KEY_ID_LENGTH];
+        id.copy_from_slice(&header[8..8 + KEY_ID_LENGTH]);
+        let cipher = self
+            .keys
+            .iter()
+            .find(|(existing, _)| *existing == id)
+            .map(|(_, cipher)| cipher.clone())
+            .ok_or_else(|| StorageError::UnknownKey(key_id_hex(&id)))?;
+        
+        Ok(DecryptingReader { inner, cipher, header, index: 0, plaintext: Vec::new(), position: 0 })
+    }
+    
+    /// Id of the key an encrypted file was written with, or None if it isn't encrypted
+    pub fn file_key(path: &Path) -> Result<Option<KeyId>, StorageError> {
+        let mut header = [0u8; ENCRYPTED_HEADER_LENGTH];
+        let mut file = File::open(path)?;
+        if read_full(&mut file, &mut header)? < ENCRYPTED_HEADER_LENGTH || &header[..8] != ENCRYPTED_MAGIC {
+            return Ok(None);
+        }
+        let mut id = [0u8; KEY_ID_LENGTH];
+        id.copy_from_slice(&header[8..8 + KEY_ID_LENGTH]);
+        Ok(Some(id))
+    }
+    
+    /// Rewrite a file encrypted with an older key under the primary key, returning whether it changed
+    pub fn reencrypt(&self, path: &Path) -> Result<bool, StorageError> {
+        match KeyRing::file_key(path)? {
+            Some(id) if id != self.primary_id() => {
+                let data = read_file(path, Some(self))?;
+                write_file(path, &data, Some(self))?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Fill `buf` as far as the input allows, returning the number of bytes read
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match input.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+/// Associated data of a chunk: the file header and the chunk's position
+fn chunk_aad(header: &[u8; ENCRYPTED_HEADER_LENGTH], index: u64) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(ENCRYPTED_HEADER_LENGTH + 8);
+    aad.extend_from_slice(header);
+    aad.extend_from_slice(&index.to_le_bytes());
+    aad
+}
+
+/// Encrypts a byte stream with AES-256-GCM in independently sealed chunks
+/// 
+/// The file starts with `ENCRYPTED_MAGIC`, the key id and a random file
+/// id; each chunk is a random nonce, the ciphertext length and the
+/// ciphertext with its tag. Chunks are authenticated together with the
+/// header and their position, so they can't be reordered or moved between
+/// files. Flushing seals the buffered bytes as a chunk, so a file cut short
+/// by a crash decrypts up to its last flush; truncation at a chunk boundary
+/// is indistinguishable from that.
+pub struct EncryptedWriter<W: Write> {
+    inner: W,
+    cipher: Aes256Gcm,
+    header: [u8; ENCRYPTED_HEADER_LENGTH],
+    index: u64,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> EncryptedWriter<W> {
+    fn seal_chunk(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let aad = chunk_aad(&self.header, self.index);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, Payload { msg: &self.buffer, aad: &aad })
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "Encryption failed"))?;
+        self.inner.write_all(&nonce)?;
+        self.inner.write_all(&(ciphertext.len() as u32).to_le_bytes())?;
+        self.inner.write_all(&ciphertext)?;
+        self.buffer.clear();
+        self.index += 1;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for EncryptedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let taken = buf.len().min(ENCRYPTION_CHUNK - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..taken]);
+        if self.buffer.len() == ENCRYPTION_CHUNK {
+            self.seal_chunk()?;
+        }
+        Ok(taken)
+    }
+    
+    fn flush(&mut self) -> io::Result<()> {
+        self.seal_chunk()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for EncryptedWriter<W> {
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            tracing::error!("Failed to write the end of an encrypted file: {}", err);
+        }
+    }
+}
+
+/// Decrypts a stream written by `EncryptedWriter`
+pub struct DecryptingReader<R: Read> {
+    inner: R,
+    cipher: Aes256Gcm,
+    header: [u8; ENCRYPTED_HEADER_LENGTH],
+    index: u64,
+    plaintext: Vec<u8>,
+    position: usize,
+}
+
+impl<R: Read> DecryptingReader<R> {
+    /// Decrypt the next chunk, returning false at the end of the stream
+    fn open_chunk(&mut self) -> io::Result<bool> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        match read_full(&mut self.inner, &mut nonce)? {
+            0 => return Ok(false),
+            NONCE_LENGTH => {}
+            _ => return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Encrypted chunk is cut short")),
+        }
+        let mut length = [0u8; 4];
+        self.inner.read_exact(&mut length)?;
+        let length = u32::from_le_bytes(length) as usize;
+        if length < TAG_LENGTH || length > ENCRYPTION_CHUNK + TAG_LENGTH {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid encrypted chunk length"));
+        }
+        let mut ciphertext = vec![0u8; length];
+        self.inner.read_exact(&mut ciphertext)?;
+        
+        let aad = chunk_aad(&self.header, self.index);
+        self.plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Decryption failed: wrong key or corrupted file"))?;
+        self.position = 0;
+        self.index += 1;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for DecryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.plaintext.len() {
+            if !self.open_chunk()? {
+                return Ok(0);
+            }
+        }
+        let count = buf.len().min(self.plaintext.len() - self.position);
+        buf[..count].copy_from_slice(&self.plaintext[self.position..self.position + count]);
+        self.position += count;
+        Ok(count)
+    }
+}
+
+/// Open a file for reading, decrypting it if it is encrypted
+/// 
+/// Plain files are read as they are, so data saved before encryption was
+/// enabled stays readable; encrypted files need `keys`.
+pub fn open_reader(path: &Path, keys: Option<&KeyRing>) -> Result<Box<dyn Read + Send>, StorageError> {
+    let mut file = File::open(path)?;
+    let mut magic = [0u8; 8];
+    let read = read_full(&mut file, &mut magic)?;
+    if read == magic.len() && &magic == ENCRYPTED_MAGIC {
+        let keys = keys.ok_or_else(|| StorageError::Encryption(format!("{} is encrypted; a key is required", path.display())))?;
+        return Ok(Box::new(keys.reader_after_magic(BufReader::new(file))?));
+    }
+    file.seek(SeekFrom::Start(0))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Create (or replace) a file for writing, encrypting it with the primary key if `keys` are given
+pub fn create_writer(path: &Path, keys: Option<&KeyRing>) -> Result<Box<dyn Write + Send>, StorageError> {
+    let file = BufWriter::new(File::create(path)?);
+    Ok(match keys {
+        Some(keys) => Box::new(keys.writer(file)?),
+        None => Box::new(file),
+    })
+}
+
+/// Read a whole file, decrypting it if it is encrypted
+pub fn read_file(path: &Path, keys: Option<&KeyRing>) -> Result<Vec<u8>, StorageError> {
+    let mut data = Vec::new();
+    open_reader(path, keys)?.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+/// Write a whole file through a temporary file, so a failed write leaves the old contents
+pub fn write_file(path: &Path, data: &[u8], keys: Option<&KeyRing>) -> Result<(), StorageError> {
+    let mut temp_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    
+    let mut writer = create_writer(&temp_path, keys)?;
+    writer.write_all(data)?;
+    writer.flush()?;
+    drop(writer);
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Every regular file below `path`
+fn files_below(path: &Path, files: &mut Vec<PathBuf>) -> Result<(), StorageError> {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            files_below(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+/// AES-256-GCM encryption for recordings and voice profiles at rest
+/// 
+/// Holds a ring of 32-byte keys: the primary key encrypts, all of them
+/// decrypt. Pass it as `encryption` to `VoicePipeline.start_recording` /
+/// `replay` or `SpeakerIdentifier` so their files are unreadable without
+/// the key. To rotate, call `rotate(new_key)`, re-encrypt existing files
+/// with `reencrypt_directory`, then `remove_key` the old key id. Keys
+/// never leave the process; only an id derived from each key is written
+/// to files.
+#[pyclass]
+pub struct StorageEncryption {
+    keys: Arc<RwLock<KeyRing>>,
+}
+
+impl StorageEncryption {
+    /// The key ring, shared with the objects that read and write through it
+    pub fn shared_keys(&self) -> Arc<RwLock<KeyRing>> {
+        self.keys.clone()
+    }
+    
+    /// A copy of the current keys
+    pub fn key_ring(&self) -> KeyRing {
+        self.keys.read().unwrap().clone()
+    }
+}
+
+fn key_id_arg(key_id: &str) -> PyResult<KeyId> {
+    parse_key_id(key_id).ok_or_else(|| PyValueError::new_err(format!("Invalid key id '{}'", key_id)))
+}
+
+#[pymethods]
+impl StorageEncryption {
+    /// Create an encryption ring with `key` as the primary key
+    #[new]
+    fn new(key: &[u8]) -> PyResult<Self> {
+        Ok(StorageEncryption { keys: Arc::new(RwLock::new(KeyRing::new(key)?)) })
+    }
+    
+    /// Generate a random 32-byte key
+    #[staticmethod]
+    fn generate_key(py: Python) -> PyObject {
+        let key = Aes256Gcm::generate_key(&mut OsRng);
+        PyBytes::new(py, &key).into()
+    }
+    
+    /// Derive a 32-byte key from a passphrase with PBKDF2-HMAC-SHA256
+    /// 
+    /// Store the salt (at least 8 random bytes) alongside the data; the
+    /// same passphrase and salt always give the same key.
+    #[staticmethod]
+    #[pyo3(signature = (passphrase, salt, iterations=600_000))]
+    fn derive_key(py: Python, passphrase: &str, salt: &[u8], iterations: u32) -> PyResult<PyObject> {
+        let key = py.allow_threads(|| derive_key(passphrase, salt, iterations))?;
+        Ok(PyBytes::new(py, &key).into())
+    }
+    
+    /// Check whether a file was written encrypted
+    #[staticmethod]
+    fn is_encrypted(path: &str) -> PyResult<bool> {
+        Ok(KeyRing::file_key(Path::new(path))?.is_some())
+    }
+    
+    /// Id of the key new files are encrypted with
+    #[getter]
+    fn primary_key_id(&self) -> String {
+        key_id_hex(&self.keys.read().unwrap().primary_id())
+    }
+    
+    /// Ids of every key in the ring, primary first
+    #[getter]
+    fn key_ids(&self) -> Vec<String> {
+        self.keys.read().unwrap().ids().iter().map(key_id_hex).collect()
+    }
+    
+    /// Id of the key a file was encrypted with, or None if it isn't encrypted
+    fn file_key_id(&self, path: &str) -> PyResult<Option<String>> {
+        Ok(KeyRing::file_key(Path::new(path))?.map(|id| key_id_hex(&id)))
+    }
+    
+    /// Add an older key so files encrypted with it can still be read, returning its id
+    fn add_key(&self, key: &[u8]) -> PyResult<String> {
+        Ok(key_id_hex(&self.keys.write().unwrap().add(key)?))
+    }
+    
+    /// Encrypt new files with `key` from now on, keeping the previous keys for reading
+    fn rotate(&self, key: &[u8]) -> PyResult<String> {
+        let id = self.keys.write().unwrap().rotate(key)?;
+        tracing::info!("Encryption key rotated to {}", key_id_hex(&id));
+        Ok(key_id_hex(&id))
+    }
+    
+    /// Remove a key that is no longer needed, returning whether it was in the ring
+    fn remove_key(&self, key_id: &str) -> PyResult<bool> {
+        Ok(self.keys.write().unwrap().remove(&key_id_arg(key_id)?)?)
+    }
+    
+    /// Encrypt a file with the primary key, in place unless `output` is given
+    #[pyo3(signature = (path, output=None))]
+    fn encrypt_file(&self, py: Python, path: &str, output: Option<&str>) -> PyResult<()> {
+        let keys = self.key_ring();
+        py.allow_threads(|| {
+            let data = read_file(Path::new(path), Some(&keys))?;
+            write_file(Path::new(output.unwrap_or(path)), &data, Some(&keys))
+        })?;
+        Ok(())
+    }
+    
+    /// Decrypt a file, in place unless `output` is given
+    #[pyo3(signature = (path, output=None))]
+    fn decrypt_file(&self, py: Python, path: &str, output: Option<&str>) -> PyResult<()> {
+        let keys = self.key_ring();
+        py.allow_threads(|| {
+            let data = read_file(Path::new(path), Some(&keys))?;
+            write_file(Path::new(output.unwrap_or(path)), &data, None)
+        })?;
+        Ok(())
+    }
+    
+    /// Read a file into memory, decrypting it if it is encrypted
+    fn read(&self, py: Python, path: &str) -> PyResult<PyObject> {
+        let keys = self.key_ring();
+        let data = py.allow_threads(|| read_file(Path::new(path), Some(&keys)))?;
+        Ok(PyBytes::new(py, &data).into())
+    }
+    
+    /// Write `data` to a file encrypted with the primary key
+    fn write(&self, py: Python, path: &str, data: &[u8]) -> PyResult<()> {
+        let keys = self.key_ring();
+        py.allow_threads(|| write_file(Path::new(path), data, Some(&keys)))?;
+        Ok(())
+    }
+    
+    /// Re-encrypt a file written with an older key under the primary key, returning whether it changed
+    fn reencrypt(&self, py: Python, path: &str) -> PyResult<bool> {
+        let keys = self.key_ring();
+        Ok(py.allow_threads(|| keys.reencrypt(Path::new(path)))?)
+    }
+    
+    /// Re-encrypt every encrypted file below `directory` that uses an older key, returning how many changed
+    /// 
+    /// Plain files are left alone. Once this succeeds, the older keys can
+    /// be removed.
+    fn reencrypt_directory(&self, py: Python, directory: &str) -> PyResult<usize> {
+        let keys = self.key_ring();
+        let changed = py.allow_threads(|| -> Result<usize, StorageError> {
+            let mut files = Vec::new();
+            files_below(Path::new(directory), &mut files)?;
+            let mut changed = 0;
+            for file in files {
+                if keys.reencrypt(&file)? {
+                    changed += 1;
+                }
+            }
+            Ok(changed)
+        })?;
+        Ok(changed)
+    }
+}
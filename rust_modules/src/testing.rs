@@ -0,0 +1,105 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use jenna_core::testing;
+
+/// Check the arguments shared by every generator
+fn check_signal(duration: f64, sample_rate: u32, amplitude: f32) -> PyResult<()> {
+    if !duration.is_finite() || duration < 0.0 {
+        return Err(PyValueError::new_err("Duration must not be negative"));
+    }
+    if sample_rate == 0 {
+        return Err(PyValueError::new_err("Sample rate must be positive"));
+    }
+    if !amplitude.is_finite() || amplitude < 0.0 {
+        return Err(PyValueError::new_err("Amplitude must not be negative"));
+    }
+    Ok(())
+}
+
+/// Check that a frequency is between 0 Hz and Nyquist
+fn check_frequency(frequency: f64, sample_rate: u32) -> PyResult<()> {
+    if !frequency.is_finite() || frequency < 0.0 || frequency > sample_rate as f64 / 2.0 {
+        return Err(PyValueError::new_err(format!(
+            "Frequency {} Hz must be between 0 and {} Hz",
+            frequency,
+            sample_rate / 2
+        )));
+    }
+    Ok(())
+}
+
+/// Generate `duration` seconds of silence
+#[pyfunction]
+#[pyo3(signature = (duration, sample_rate=16000))]
+pub fn silence(duration: f64, sample_rate: u32) -> PyResult<Vec<f32>> {
+    check_signal(duration, sample_rate, 0.0)?;
+    Ok(testing::silence(duration, sample_rate))
+}
+
+/// Generate a steady tone with peak `amplitude`
+#[pyfunction]
+#[pyo3(signature = (frequency, duration, sample_rate=16000, amplitude=0.5))]
+pub fn sine(frequency: f64, duration: f64, sample_rate: u32, amplitude: f32) -> PyResult<Vec<f32>> {
+    check_signal(duration, sample_rate, amplitude)?;
+    check_frequency(frequency, sample_rate)?;
+    Ok(testing::sine(frequency, duration, sample_rate, amplitude))
+}
+
+/// Generate a tone gliding from `start_hz` to `end_hz`
+/// 
+/// Logarithmic sweeps (the default) spend equal time per octave and need
+/// both frequencies above 0 Hz; linear ones spend equal time per hertz.
+#[pyfunction]
+#[pyo3(signature = (start_hz, end_hz, duration, sample_rate=16000, amplitude=0.5, logarithmic=true))]
+pub fn sine_sweep(
+    start_hz: f64,
+    end_hz: f64,
+    duration: f64,
+    sample_rate: u32,
+    amplitude: f32,
+    logarithmic: bool,
+) -> PyResult<Vec<f32>> {
+    check_signal(duration, sample_rate, amplitude)?;
+    check_frequency(start_hz, sample_rate)?;
+    check_frequency(end_hz, sample_rate)?;
+    if logarithmic && (start_hz <= 0.0 || end_hz <= 0.0) {
+        return Err(PyValueError::new_err("Logarithmic sweeps need frequencies above 0 Hz"));
+    }
+    Ok(testing::sine_sweep(start_hz, end_hz, duration, sample_rate, amplitude, logarithmic))
+}
+
+/// Generate white noise with peak `amplitude`; the same `seed` gives the same noise
+#[pyfunction]
+#[pyo3(signature = (duration, sample_rate=16000, amplitude=0.5, seed=1))]
+pub fn white_noise(duration: f64, sample_rate: u32, amplitude: f32, seed: u32) -> PyResult<Vec<f32>> {
+    check_signal(duration, sample_rate, amplitude)?;
+    Ok(testing::white_noise(duration, sample_rate, amplitude, seed))
+}
+
+/// Generate pink (-3 dB per octave) noise with peak `amplitude`
+#[pyfunction]
+#[pyo3(signature = (duration, sample_rate=16000, amplitude=0.5, seed=1))]
+pub fn pink_noise(duration: f64, sample_rate: u32, amplitude: f32, seed: u32) -> PyResult<Vec<f32>> {
+    check_signal(duration, sample_rate, amplitude)?;
+    Ok(testing::pink_noise(duration, sample_rate, amplitude, seed))
+}
+
+/// Generate noise with the spectrum and syllable rhythm of speech
+/// 
+/// Voice activity detection and endpointing treat it as talking, so it can
+/// stand in for a recorded utterance when the words don't matter.
+#[pyfunction]
+#[pyo3(signature = (duration, sample_rate=16000, amplitude=0.5, seed=1))]
+pub fn speech_shaped_noise(duration: f64, sample_rate: u32, amplitude: f32, seed: u32) -> PyResult<Vec<f32>> {
+    check_signal(duration, sample_rate, amplitude)?;
+    Ok(testing::speech_shaped_noise(duration, sample_rate, amplitude, seed))
+}
+
+/// Convert float samples (-1.0 to 1.0) to 16-bit PCM, clipping out-of-range values
+#[pyfunction]
+pub fn to_pcm16(samples: Vec<f32>) -> Vec<i16> {
+    samples
+        .iter()
+        .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect()
+}
@@ -0,0 +1,256 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+
+/// DTMF row frequencies (Hz)
+const DTMF_ROWS: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+
+/// DTMF column frequencies (Hz)
+const DTMF_COLUMNS: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+
+/// DTMF keypad layout indexed by [row][column]
+const DTMF_KEYS: [[char; 4]; 4] = [
+    ['1', '2', '3', 'A'],
+    ['4', '5', '6', 'B'],
+    ['7', '8', '9', 'C'],
+    ['*', '0', '#', 'D'],
+];
+
+/// Power of a single frequency component using the Goertzel algorithm
+/// 
+/// The result is normalised by the frame length so it is comparable with
+/// the mean-square energy of the frame.
+pub fn goertzel_power(samples: &[f32], frequency: f32, sample_rate: u32) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    
+    let omega = 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+    let coeff = 2.0 * omega.cos();
+    let (mut s1, mut s2) = (0.0f32, 0.0f32);
+    for &x in samples {
+        let s0 = x + coeff * s1 - s2;
+        s2 = s1;
+        s1 = s0;
+    }
+    
+    let power = s1 * s1 + s2 * s2 - coeff * s1 * s2;
+    2.0 * power / (samples.len() as f32 * samples.len() as f32)
+}
+
+/// Mean-square energy of a frame
+fn frame_energy(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    samples.iter().map(|x| x * x).sum::<f32>() / samples.len() as f32
+}
+
+/// Compute Goertzel power for each of the given frequencies
+#[pyfunction]
+pub fn goertzel(samples: Vec<f32>, frequencies: Vec<f32>, sample_rate: u32) -> PyResult<Vec<f32>> {
+    Ok(frequencies
+        .iter()
+        .map(|&f| goertzel_power(&samples, f, sample_rate))
+        .collect())
+}
+
+/// Streaming detector for a set of fixed tones (appliance beeps, alerts)
+/// 
+/// Audio is analysed in blocks; a tone is reported once it has dominated
+/// the block energy for `min_blocks` consecutive blocks.
+#[pyclass]
+pub struct ToneDetector {
+    #[pyo3(get)]
+    sample_rate: u32,
+    #[pyo3(get)]
+    frequencies: Vec<f32>,
+    #[pyo3(get, set)]
+    threshold: f32,
+    #[pyo3(get, set)]
+    min_blocks: usize,
+    block_size: usize,
+    pending: Vec<f32>,
+    counts: Vec<usize>,
+    samples_seen: u64,
+}
+
+#[pymethods]
+impl ToneDetector {
+    /// Create a tone detector
+    /// 
+    /// `threshold` is the fraction of block energy a tone must carry.
+    #[new]
+    #[pyo3(signature = (frequencies, sample_rate, block_size=205, threshold=0.5, min_blocks=3))]
+    fn new(frequencies: Vec<f32>, sample_rate: u32, block_size: usize, threshold: f32, min_blocks: usize) -> PyResult<Self> {
+        if frequencies.is_empty() || block_size == 0 {
+            return Err(PyValueError::new_err("At least one frequency and a non-zero block size are required"));
+        }
+        
+        let counts = vec![0; frequencies.len()];
+        Ok(ToneDetector {
+            sample_rate,
+            frequencies,
+            threshold,
+            min_blocks: min_blocks.max(1),
+            block_size,
+            pending: Vec::with_capacity(block_size),
+            counts,
+            samples_seen: 0,
+        })
+    }
+    
+    /// Feed samples and return `(frequency, time_seconds)` for each tone that became active
+    fn process(&mut self, samples: Vec<f32>) -> PyResult<Vec<(f32, f64)>> {
+        self.pending.extend_from_slice(&samples);
+        
+        let mut events = Vec::new();
+        while self.pending.len() >= self.block_size {
+            let block: Vec<f32> = self.pending.drain(..self.block_size).collect();
+            let energy = frame_energy(&block);
+            
+            for (i, &frequency) in self.frequencies.iter().enumerate() {
+                let power = goertzel_power(&block, frequency, self.sample_rate);
+                if energy > 1e-8 && power >= self.threshold * energy {
+                    self.counts[i] += 1;
+                    if self.counts[i] == self.min_blocks {
+                        let start = self.samples_seen + self.block_size as u64 - (self.min_blocks * self.block_size) as u64;
+                        events.push((frequency, start as f64 / self.sample_rate as f64));
+                    }
+                } else {
+                    self.counts[i] = 0;
+                }
+            }
+            self.samples_seen += self.block_size as u64;
+        }
+        
+        Ok(events)
+    }
+    
+    /// Reset the detector state
+    fn reset(&mut self) -> PyResult<()> {
+        self.pending.clear();
+        self.counts.iter_mut().for_each(|c| *c = 0);
+        self.samples_seen = 0;
+        Ok(())
+    }
+}
+
+/// Streaming DTMF decoder
+/// 
+/// Each block is checked for exactly one strong row and one strong column
+/// tone with a bounded level difference (twist). A digit is emitted once it
+/// has been stable for `min_blocks` blocks and is not repeated until a gap.
+#[pyclass]
+pub struct DtmfDetector {
+    #[pyo3(get)]
+    sample_rate: u32,
+    block_size: usize,
+    min_blocks: usize,
+    pending: Vec<f32>,
+    candidate: Option<char>,
+    candidate_count: usize,
+    emitted: Option<char>,
+    samples_seen: u64,
+}
+
+impl DtmfDetector {
+    /// Decode a single block, returning the key if a valid DTMF pair is present
+    fn decode_block(&self, block: &[f32]) -> Option<char> {
+        let energy = frame_energy(block);
+        if energy < 1e-6 {
+            return None;
+        }
+        
+        let strongest = |freqs: &[f32; 4]| -> (usize, f32, f32) {
+            let powers: Vec<f32> = freqs.iter().map(|&f| goertzel_power(block, f, self.sample_rate)).collect();
+            let (index, &best) = powers
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .unwrap();
+            let second = powers.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, &p)| p).fold(0.0f32, f32::max);
+            (index, best, second)
+        };
+        
+        let (row, row_power, row_second) = strongest(&DTMF_ROWS);
+        let (column, column_power, column_second) = strongest(&DTMF_COLUMNS);
+        
+        // Both tones must dominate their group and the block energy
+        let dominant = row_power > 4.0 * row_second && column_power > 4.0 * column_second;
+        let strong = row_power + column_power > 0.6 * energy;
+        // Twist: the two tones may differ by at most ~8 dB
+        let twist = (row_power / column_power.max(f32::EPSILON)).log10().abs() * 10.0 <= 8.0;
+        
+        if dominant && strong && twist {
+            Some(DTMF_KEYS[row][column])
+        } else {
+            None
+        }
+    }
+}
+
+#[pymethods]
+impl DtmfDetector {
+    /// Create a DTMF detector
+    #[new]
+    #[pyo3(signature = (sample_rate, block_size=None, min_blocks=2))]
+    fn new(sample_rate: u32, block_size: Option<usize>, min_blocks: usize) -> PyResult<Self> {
+        // 205 samples at 8 kHz is the classic choice; scale for other rates
+        let block_size = block_size.unwrap_or((sample_rate as usize * 205) / 8000);
+        if block_size == 0 {
+            return Err(PyValueError::new_err("Block size must be greater than zero"));
+        }
+        
+        Ok(DtmfDetector {
+            sample_rate,
+            block_size,
+            min_blocks: min_blocks.max(1),
+            pending: Vec::with_capacity(block_size),
+            candidate: None,
+            candidate_count: 0,
+            emitted: None,
+            samples_seen: 0,
+        })
+    }
+    
+    /// Feed samples and return `(key, time_seconds)` for each newly detected key press
+    fn process(&mut self, samples: Vec<f32>) -> PyResult<Vec<(char, f64)>> {
+        self.pending.extend_from_slice(&samples);
+        
+        let mut events = Vec::new();
+        while self.pending.len() >= self.block_size {
+            let block: Vec<f32> = self.pending.drain(..self.block_size).collect();
+            let key = self.decode_block(&block);
+            
+            if key == self.candidate {
+                self.candidate_count += 1;
+            } else {
+                self.candidate = key;
+                self.candidate_count = 1;
+            }
+            
+            match key {
+                Some(k) if self.candidate_count >= self.min_blocks && self.emitted != Some(k) => {
+                    let start = self.samples_seen + self.block_size as u64 - (self.candidate_count * self.block_size) as u64;
+                    events.push((k, start as f64 / self.sample_rate as f64));
+                    self.emitted = Some(k);
+                }
+                None if self.candidate_count >= self.min_blocks => self.emitted = None,
+                _ => {}
+            }
+            self.samples_seen += self.block_size as u64;
+        }
+        
+        Ok(events)
+    }
+    
+    /// Reset the detector state
+    fn reset(&mut self) -> PyResult<()> {
+        self.pending.clear();
+        self.candidate = None;
+        self.candidate_count = 0;
+        self.emitted = None;
+        self.samples_seen = 0;
+        Ok(())
+    }
+}
@@ -0,0 +1,778 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tract_onnx::prelude::*;
+use jenna_core::inference::{self as onnx, OnnxPlan};
+use jenna_core::normalize::TextNormalizer;
+use jenna_core::ssml::{is_ssml, parse_ssml, Prosody, SsmlSegment};
+
+use crate::audio::resample_mono;
+use crate::loading::map_model;
+
+/// Silence inserted between sentences, in seconds
+pub const SENTENCE_SILENCE: f32 = 0.2;
+
+/// Fade applied at sentence edges so prosody changes between sentences don't click
+const EDGE_FADE: f32 = 0.005;
+
+/// Phoneme id map markers used by Piper voices
+const PAD: &str = "_";
+const BOS: &str = "^";
+const EOS: &str = "$";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PiperAudio {
+    pub sample_rate: u32,
+    #[serde(default)]
+    pub quality: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PiperEspeak {
+    pub voice: String,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PiperInference {
+    #[serde(default = "default_noise_scale")]
+    pub noise_scale: f32,
+    #[serde(default = "default_length_scale")]
+    pub length_scale: f32,
+    #[serde(default = "default_noise_w")]
+    pub noise_w: f32,
+}
+
+fn default_noise_scale() -> f32 {
+    0.667
+}
+
+fn default_length_scale() -> f32 {
+    1.0
+}
+
+fn default_noise_w() -> f32 {
+    0.8
+}
+
+impl Default for PiperInference {
+    fn default() -> Self {
+        PiperInference {
+            noise_scale: default_noise_scale(),
+            length_scale: default_length_scale(),
+            noise_w: default_noise_w(),
+        }
+    }
+}
+
+/// Voice configuration stored next to the model as `<voice>.onnx.json`
+#[derive(Deserialize, Clone, Debug)]
+pub struct PiperConfig {
+    pub audio: PiperAudio,
+    #[serde(default)]
+    pub espeak: Option<PiperEspeak>,
+    #[serde(default)]
+    pub inference: PiperInference,
+    #[serde(default)]
+    pub phoneme_type: Option<String>,
+    pub phoneme_id_map: HashMap<String, Vec<i64>>,
+    #[serde(default)]
+    pub num_speakers: Option<u32>,
+    #[serde(default)]
+    pub language: Option<PiperLanguage>,
+    #[serde(default)]
+    pub dataset: Option<String>,
+    #[serde(default)]
+    pub gender: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PiperLanguage {
+    pub code: String,
+}
+
+impl PiperConfig {
+    pub fn load(model_path: &Path) -> Result<Self, String> {
+        let config_path = config_path(model_path);
+        let data = fs::read_to_string(&config_path)
+            .map_err(|err| format!("Cannot read voice config {}: {}", config_path.display(), err))?;
+        serde_json::from_str(&data).map_err(|err| format!("Invalid voice config {}: {}", config_path.display(), err))
+    }
+}
+
+/// Path of the JSON config for a Piper model
+pub fn config_path(model_path: &Path) -> PathBuf {
+    let mut path = model_path.as_os_str().to_owned();
+    path.push(".json");
+    PathBuf::from(path)
+}
+
+/// Quality tiers of Piper voices, lowest first
+const QUALITY_TIERS: [&str; 4] = ["x_low", "low", "medium", "high"];
+
+/// Metadata describing an installed voice
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct VoiceInfo {
+    /// Identifier accepted by `TextToSpeech.set_voice` (the model file stem)
+    #[pyo3(get)]
+    pub name: String,
+    /// Language code such as "en_US"
+    #[pyo3(get)]
+    pub language: String,
+    /// Speaker gender if the voice config declares one
+    #[pyo3(get)]
+    pub gender: Option<String>,
+    #[pyo3(get)]
+    pub sample_rate: u32,
+    /// Quality tier: "x_low", "low", "medium" or "high"
+    #[pyo3(get)]
+    pub quality: String,
+    /// Training dataset / speaker name, e.g. "lessac"
+    #[pyo3(get)]
+    pub dataset: Option<String>,
+    #[pyo3(get)]
+    pub path: String,
+}
+
+#[pymethods]
+impl VoiceInfo {
+    fn __repr__(&self) -> String {
+        format!(
+            "VoiceInfo(name={:?}, language={:?}, quality={:?}, sample_rate={})",
+            self.name, self.language, self.quality, self.sample_rate
+        )
+    }
+}
+
+impl VoiceInfo {
+    fn from_model(model_path: &Path) -> Result<Self, String> {
+        let config = PiperConfig::load(model_path)?;
+        let name = model_path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        
+        // Piper names voices "<language>-<dataset>-<quality>"; fall back to that
+        let mut parts = name.splitn(3, '-');
+        let (name_language, name_dataset, name_quality) = (parts.next(), parts.next(), parts.next());
+        
+        Ok(VoiceInfo {
+            language: config
+                .language
+                .map(|l| l.code)
+                .or_else(|| name_language.map(|l| l.to_string()))
+                .unwrap_or_default(),
+            gender: config.gender,
+            sample_rate: config.audio.sample_rate,
+            quality: config
+                .audio
+                .quality
+                .or_else(|| name_quality.map(|q| q.to_string()))
+                .unwrap_or_else(|| "medium".to_string()),
+            dataset: config.dataset.or_else(|| name_dataset.map(|d| d.to_string())),
+            path: model_path.display().to_string(),
+            name,
+        })
+    }
+    
+    fn quality_rank(&self) -> usize {
+        QUALITY_TIERS.iter().position(|q| *q == self.quality).unwrap_or(0)
+    }
+}
+
+/// Read metadata for every voice in a directory, sorted by name
+/// 
+/// Models without a readable config are skipped.
+pub fn discover_voices(model_dir: &Path) -> Result<Vec<VoiceInfo>, String> {
+    let mut voices: Vec<VoiceInfo> = fs::read_dir(model_dir)
+        .map_err(|err| format!("Cannot read {}: {}", model_dir.display(), err))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().map(|ext| ext == "onnx").unwrap_or(false))
+        .filter_map(|path| match VoiceInfo::from_model(&path) {
+            Ok(info) => Some(info),
+            Err(err) => {
+                tracing::warn!("Skipping voice {}: {}", path.display(), err);
+                None
+            }
+        })
+        .collect();
+    voices.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(voices)
+}
+
+/// List the voices installed in `model_dir` with their metadata
+#[pyfunction]
+pub fn list_voices(py: Python, model_dir: String) -> PyResult<Vec<VoiceInfo>> {
+    py.allow_threads(|| discover_voices(Path::new(&model_dir)))
+        .map_err(PyValueError::new_err)
+}
+
+/// Resolve a voice identifier to a model file
+/// 
+/// `model_path` may be an `.onnx` file or a directory of voices. In a
+/// directory, `voice` is matched against the voice name (file stem), then
+/// its dataset (e.g. "lessac"), then its language code (e.g. "en_US" or
+/// "en-US"); among several matches the highest quality tier wins.
+/// "default" picks the first voice alphabetically.
+pub fn resolve_voice(model_path: &Path, voice: &str) -> Result<PathBuf, String> {
+    if model_path.is_file() {
+        return Ok(model_path.to_path_buf());
+    }
+    
+    let candidate = model_path.join(format!("{}.onnx", voice));
+    if candidate.exists() {
+        return Ok(candidate);
+    }
+    
+    let voices = discover_voices(model_path)?;
+    if voice == "default" {
+        return voices
+            .into_iter()
+            .next()
+            .map(|v| PathBuf::from(v.path))
+            .ok_or_else(|| format!("No voices found in {}", model_path.display()));
+    }
+    
+    let wanted = voice.replace('-', "_").to_lowercase();
+    let matches = |field: &Option<String>| {
+        field.as_ref().map(|f| f.replace('-', "_").to_lowercase() == wanted).unwrap_or(false)
+    };
+    voices
+        .iter()
+        .filter(|v| matches(&v.dataset))
+        .max_by_key(|v| v.quality_rank())
+        .or_else(|| voices.iter().filter(|v| matches(&Some(v.language.clone()))).max_by_key(|v| v.quality_rank()))
+        .map(|v| PathBuf::from(&v.path))
+        .ok_or_else(|| format!("Voice not found: {}", voice))
+}
+
+/// Piper (VITS) voice model run with tract
+pub struct PiperVoice {
+    plan: OnnxPlan,
+    pub config: PiperConfig,
+    has_speaker_input: bool,
+}
+
+impl PiperVoice {
+    /// Load a voice, reading the model through a read-only memory map if `memory_map` is set
+    #[tracing::instrument(name = "piper_load", skip_all, fields(voice = %model_path.display()))]
+    pub fn load(model_path: &Path, memory_map: bool) -> Result<Self, String> {
+        let config = PiperConfig::load(model_path)?;
+        let load_error = |err: TractError| format!("Failed to load voice {}: {}", model_path.display(), err);
+        
+        // The phoneme sequence length varies per sentence
+        let mut model = if memory_map {
+            let mapped = map_model(model_path)?;
+            tract_onnx::onnx().model_for_read(&mut &mapped[..])
+        } else {
+            tract_onnx::onnx().model_for_path(model_path)
+        }
+        .map_err(load_error)?;
+        let length = model.symbol_table.sym("N");
+        let has_speaker_input = model.inputs.len() > 3;
+        model = model
+            .with_input_fact(0, InferenceFact::dt_shape(i64::datum_type(), tvec!(1.to_dim(), length.to_dim())))
+            .and_then(|m| m.with_input_fact(1, InferenceFact::dt_shape(i64::datum_type(), tvec!(1.to_dim()))))
+            .and_then(|m| m.with_input_fact(2, InferenceFact::dt_shape(f32::datum_type(), tvec!(3.to_dim()))))
+            .map_err(load_error)?;
+        if has_speaker_input {
+            model = model
+                .with_input_fact(3, InferenceFact::dt_shape(i64::datum_type(), tvec!(1.to_dim())))
+                .map_err(load_error)?;
+        }
+        let plan = model
+            .into_optimized()
+            .and_then(|m| m.into_runnable())
+            .map_err(load_error)?;
+        
+        Ok(PiperVoice { plan, config, has_speaker_input })
+    }
+    
+    pub fn sample_rate(&self) -> u32 {
+        self.config.audio.sample_rate
+    }
+    
+    /// Convert text to one phoneme string per sentence
+    /// 
+    /// Voices with `phoneme_type: "text"` use characters directly; others
+    /// are phonemized with the `espeak-ng` command line tool.
+    /// 
+    /// Words found in `lexicon` use its phonemes instead.
+    pub fn phonemize(&self, text: &str, lexicon: &Lexicon) -> Result<Vec<String>, String> {
+        if lexicon.is_empty() {
+            return self.phonemize_run(text);
+        }
+        
+        let mut sentences = Vec::new();
+        for sentence in split_sentences(text) {
+            let mut phonemes: Vec<String> = Vec::new();
+            let mut run: Vec<&str> = Vec::new();
+            for word in sentence.split_whitespace() {
+                match lexicon.get(word) {
+                    Some(override_phonemes) => {
+                        if !run.is_empty() {
+                            phonemes.extend(self.phonemize_run(&run.join(" "))?);
+                            run.clear();
+                        }
+                        phonemes.push(override_phonemes.to_string());
+                    }
+                    None => run.push(word),
+                }
+            }
+            if !run.is_empty() {
+                phonemes.extend(self.phonemize_run(&run.join(" "))?);
+            }
+            if !phonemes.is_empty() {
+                sentences.push(phonemes.join(" "));
+            }
+        }
+        Ok(sentences)
+    }
+    
+    fn phonemize_run(&self, text: &str) -> Result<Vec<String>, String> {
+        let espeak_voice = match (&self.config.phoneme_type, &self.config.espeak) {
+            (Some(kind), _) if kind == "text" => None,
+            (_, Some(espeak)) => Some(espeak.voice.clone()),
+            _ => Some("en-us".to_string()),
+        };
+        
+        let espeak_voice = match espeak_voice {
+            Some(voice) => voice,
+            None => return Ok(split_sentences(text).into_iter().map(|s| s.to_lowercase()).collect()),
+        };
+        
+        let output = Command::new("espeak-ng")
+            .args(["-q", "--ipa", "-v", &espeak_voice])
+            .arg(text)
+            .output()
+            .map_err(|err| format!("Failed to run espeak-ng (is it installed?): {}", err))?;
+        if !output.status.success() {
+            return Err(format!("espeak-ng failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect())
+    }
+    
+    /// Map a phoneme string to model ids, interleaving padding as Piper expects
+    pub fn phoneme_ids(&self, phonemes: &str) -> Vec<i64> {
+        let map = &self.config.phoneme_id_map;
+        let id = |symbol: &str| map.get(symbol).cloned().unwrap_or_default();
+        
+        let mut ids = id(BOS);
+        ids.extend(id(PAD));
+        for phoneme in phonemes.chars() {
+            let symbol = phoneme.to_string();
+            if let Some(phoneme_ids) = map.get(&symbol) {
+                ids.extend(phoneme_ids);
+                ids.extend(id(PAD));
+            }
+        }
+        ids.extend(id(EOS));
+        ids
+    }
+    
+    /// Run the model on one phoneme id sequence and return f32 audio
+    /// 
+    /// `length_scale` multiplies the voice's default (larger is slower).
+    pub fn infer(&self, ids: &[i64], length_scale: f32) -> Result<Vec<f32>, String> {
+        let inference = &self.config.inference;
+        let scales = [inference.noise_scale, inference.length_scale * length_scale, inference.noise_w];
+        
+        let mut inputs: TVec<TValue> = tvec!(
+            onnx::tensor(&[1, ids.len()], ids)?.into(),
+            onnx::tensor(&[1], &[ids.len() as i64])?.into(),
+            onnx::tensor(&[3], &scales)?.into(),
+        );
+        if self.has_speaker_input {
+            inputs.push(onnx::tensor(&[1], &[0i64])?.into());
+        }
+        
+        let outputs = onnx::run(&self.plan, inputs).map_err(|err| format!("Synthesis failed: {}", err))?;
+        onnx::output(&outputs, 0)
+    }
+    
+    /// Phonemize text and return the model ids for each sentence
+    pub fn sentence_ids(&self, text: &str, lexicon: &Lexicon) -> Result<Vec<Vec<i64>>, String> {
+        Ok(self
+            .phonemize(text, lexicon)?
+            .iter()
+            .map(|sentence| self.phoneme_ids(sentence))
+            .filter(|ids| ids.len() > 3)
+            .collect())
+    }
+}
+
+/// Split text into sentences on terminal punctuation
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    
+    for c in text.chars() {
+        current.push(c);
+        if matches!(c, '.' | '!' | '?' | ';' | '\n') {
+            let sentence = current.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            current.clear();
+        }
+    }
+    if !current.trim().is_empty() {
+        sentences.push(current.trim().to_string());
+    }
+    sentences
+}
+
+/// User pronunciations applied before phonemization
+/// 
+/// Words are matched case-insensitively with surrounding punctuation
+/// stripped. Phonemes are written in the voice's phoneme alphabet (IPA for
+/// espeak-based voices). Lexicon files hold one `word<TAB>phonemes` entry
+/// per line; blank lines and lines starting with `#` are ignored.
+#[derive(Clone, Debug, Default)]
+pub struct Lexicon {
+    entries: HashMap<String, String>,
+}
+
+impl Lexicon {
+    fn normalize(word: &str) -> String {
+        word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+    }
+    
+    pub fn insert(&mut self, word: &str, phonemes: &str) -> Result<(), String> {
+        let key = Self::normalize(word);
+        let phonemes = phonemes.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("Invalid lexicon word: {:?}", word));
+        }
+        if phonemes.is_empty() {
+            return Err(format!("No phonemes given for {:?}", word));
+        }
+        self.entries.insert(key, phonemes.to_string());
+        Ok(())
+    }
+    
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.entries.remove(&Self::normalize(word)).is_some()
+    }
+    
+    pub fn get(&self, word: &str) -> Option<&str> {
+        self.entries.get(&Self::normalize(word)).map(|p| p.as_str())
+    }
+    
+    pub fn entries(&self) -> &HashMap<String, String> {
+        &self.entries
+    }
+    
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+    
+    /// Merge entries from a lexicon file, returning how many were read
+    pub fn load(&mut self, path: &Path) -> Result<usize, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read lexicon {}: {}", path.display(), err))?;
+        
+        let mut count = 0;
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, phonemes) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| format!("{}:{}: expected a word and its phonemes", path.display(), number + 1))?;
+            self.insert(word, phonemes)
+                .map_err(|err| format!("{}:{}: {}", path.display(), number + 1, err))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+    
+    fn sorted_words(&self) -> Vec<&String> {
+        let mut words: Vec<&String> = self.entries.keys().collect();
+        words.sort();
+        words
+    }
+    
+    /// Write all entries to a lexicon file, sorted by word
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let contents: String = self
+            .sorted_words()
+            .into_iter()
+            .map(|word| format!("{}\t{}\n", word, self.entries[word]))
+            .collect();
+        fs::write(path, contents).map_err(|err| format!("Failed to write lexicon {}: {}", path.display(), err))
+    }
+    
+    /// Stable hash of the entries, used to key cached audio
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        for word in self.sorted_words() {
+            hasher.update(word.as_bytes());
+            hasher.update([0]);
+            hasher.update(self.entries[word].as_bytes());
+            hasher.update([0]);
+        }
+        hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Convert f32 audio to i16 PCM, normalizing peaks the way Piper does
+/// 
+/// `gain` is applied after normalization; values above 1.0 clip.
+pub fn to_pcm(audio: &[f32], gain: f32) -> Vec<i16> {
+    let peak = audio.iter().fold(0.0f32, |max, s| max.max(s.abs())).max(0.01);
+    let scale = 32767.0 / peak * gain;
+    audio
+        .iter()
+        .map(|&s| (s * scale).clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+        .collect()
+}
+
+/// A unit of synthesis work: one sentence with its prosody, or a pause
+#[derive(Clone, Debug)]
+pub enum SpeechUnit {
+    Sentence { ids: Vec<i64>, prosody: Prosody },
+    Pause(f32),
+}
+
+/// Turn plain text or SSML into synthesis units
+/// 
+/// Consecutive sentences are separated by a short pause; explicit SSML
+/// breaks replace it. `base` is the engine-wide prosody that SSML prosody
+/// is applied on top of; `normalizer` rewrites numbers, dates and the like
+/// as words and `lexicon` overrides word pronunciations.
+pub fn plan_speech(
+    voice: &PiperVoice,
+    text: &str,
+    base: Prosody,
+    normalizer: Option<&TextNormalizer>,
+    lexicon: &Lexicon,
+) -> Result<Vec<SpeechUnit>, String> {
+    let segments = if is_ssml(text) {
+        parse_ssml(text)?
+    } else {
+        vec![SsmlSegment::Text { text: text.to_string(), prosody: Prosody::default() }]
+    };
+    
+    let mut units = Vec::new();
+    for segment in segments {
+        match segment {
+            SsmlSegment::Text { text, prosody } => {
+                let text = match normalizer {
+                    Some(normalizer) => normalizer.normalize(&text),
+                    None => text,
+                };
+                for ids in voice.sentence_ids(&text, lexicon)? {
+                    if let Some(SpeechUnit::Sentence { .. }) = units.last() {
+                        units.push(SpeechUnit::Pause(SENTENCE_SILENCE));
+                    }
+                    units.push(SpeechUnit::Sentence { ids, prosody: base.combine(prosody) });
+                }
+            }
+            SsmlSegment::Break(seconds) => units.push(SpeechUnit::Pause(seconds)),
+        }
+    }
+    Ok(units)
+}
+
+/// Render a unit to PCM at `output_rate`
+/// 
+/// Piper has no pitch control, so pitch is shifted by synthesizing
+/// proportionally slower and then resampling faster: the pitch rises by
+/// the speed-up while the duration returns to the requested rate.
+pub fn render_unit(voice: &PiperVoice, unit: &SpeechUnit, output_rate: u32) -> Result<Vec<i16>, String> {
+    match unit {
+        SpeechUnit::Pause(seconds) => Ok(vec![0; (seconds * output_rate as f32) as usize]),
+        SpeechUnit::Sentence { ids, prosody } => {
+            let audio = voice.infer(ids, prosody.pitch / prosody.rate)?;
+            let source_rate = (voice.sample_rate() as f32 * prosody.pitch).round() as u32;
+            let mut audio = resample_mono(&audio, source_rate, output_rate).map_err(|err| err.to_string())?;
+            
+            let fade = ((EDGE_FADE * output_rate as f32) as usize).min(audio.len() / 2);
+            let len = audio.len();
+            for i in 0..fade {
+                let gain = i as f32 / fade as f32;
+                audio[i] *= gain;
+                audio[len - 1 - i] *= gain;
+            }
+            Ok(to_pcm(&audio, prosody.volume))
+        }
+    }
+}
+
+/// LRU cache of synthesized PCM, optionally persisted to disk
+/// 
+/// Keys are hashes of everything that affects the audio: text, voice,
+/// normalization language, lexicon, rate, pitch, volume and output sample
+/// rate.
+pub struct SynthesisCache {
+    capacity: usize,
+    directory: Option<PathBuf>,
+    entries: HashMap<String, Vec<i16>>,
+    order: VecDeque<String>,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl SynthesisCache {
+    pub fn new(capacity: usize, directory: Option<PathBuf>) -> Self {
+        SynthesisCache {
+            capacity,
+            directory,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+    
+    pub fn key(
+        text: &str,
+        voice: &str,
+        normalizer: Option<&TextNormalizer>,
+        lexicon: &Lexicon,
+        prosody: Prosody,
+        sample_rate: u32,
+    ) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(text.as_bytes());
+        hasher.update([0]);
+        hasher.update(voice.as_bytes());
+        hasher.update([0]);
+        if let Some(normalizer) = normalizer {
+            hasher.update(normalizer.language().as_bytes());
+        }
+        hasher.update([0]);
+        if !lexicon.is_empty() {
+            hasher.update(lexicon.fingerprint().as_bytes());
+        }
+        hasher.update(prosody.rate.to_le_bytes());
+        hasher.update(prosody.pitch.to_le_bytes());
+        hasher.update(prosody.volume.to_le_bytes());
+        hasher.update(sample_rate.to_le_bytes());
+        hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+    }
+    
+    fn disk_path(&self, key: &str) -> Option<PathBuf> {
+        self.directory.as_ref().map(|dir| dir.join(format!("{}.pcm", key)))
+    }
+    
+    fn touch(&mut self, key: &str) {
+        if let Some(position) = self.order.iter().position(|k| k == key) {
+            self.order.remove(position);
+        }
+        self.order.push_back(key.to_string());
+    }
+    
+    /// Look up PCM, falling back to the disk cache
+    pub fn get(&mut self, key: &str) -> Option<Vec<i16>> {
+        if let Some(pcm) = self.entries.get(key).cloned() {
+            self.touch(key);
+            self.hits += 1;
+            return Some(pcm);
+        }
+        
+        let from_disk = self.disk_path(key).and_then(|path| fs::read(path).ok()).map(|bytes| {
+            bytes
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]))
+                .collect::<Vec<i16>>()
+        });
+        match from_disk {
+            Some(pcm) => {
+                self.hits += 1;
+                self.insert_memory(key, pcm.clone());
+                Some(pcm)
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+    
+    fn insert_memory(&mut self, key: &str, pcm: Vec<i16>) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.entries.insert(key.to_string(), pcm);
+        self.touch(key);
+        while self.entries.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+    
+    /// Store PCM in memory and, if configured, on disk
+    pub fn insert(&mut self, key: &str, pcm: Vec<i16>) {
+        if let Some(path) = self.disk_path(key) {
+            let bytes: Vec<u8> = pcm.iter().flat_map(|s| s.to_le_bytes()).collect();
+            let written = path
+                .parent()
+                .map(fs::create_dir_all)
+                .unwrap_or(Ok(()))
+                .and_then(|_| fs::write(&path, bytes));
+            if let Err(err) = written {
+                tracing::warn!("Failed to write TTS cache entry {}: {}", path.display(), err);
+            }
+        }
+        self.insert_memory(key, pcm);
+    }
+    
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    
+    pub fn memory_bytes(&self) -> usize {
+        self.entries.values().map(|pcm| pcm.len() * 2).sum()
+    }
+    
+    pub fn directory(&self) -> Option<&Path> {
+        self.directory.as_deref()
+    }
+    
+    /// Drop all entries (including disk files) and reset the counters
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+        self.hits = 0;
+        self.misses = 0;
+        
+        if let Some(dir) = &self.directory {
+            if let Ok(entries) = fs::read_dir(dir) {
+                for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+                    if path.extension().map(|ext| ext == "pcm").unwrap_or(false) {
+                        let _ = fs::remove_file(path);
+                    }
+                }
+            }
+        }
+    }
+}
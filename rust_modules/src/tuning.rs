@@ -0,0 +1,292 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use cpal::traits::{DeviceTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::audio::{classify_stream_error, find_input_device, AudioError};
+
+/// Preferred capture configuration for a single device
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PreferredConfig {
+    pub sample_rate: u32,
+    pub block_size: u32,
+    pub queue_depth: u32,
+    pub latency_ms: f64,
+    pub callbacks_per_second: f64,
+}
+
+impl PreferredConfig {
+    fn to_dict(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("sample_rate", self.sample_rate)?;
+        dict.set_item("block_size", self.block_size)?;
+        dict.set_item("queue_depth", self.queue_depth)?;
+        dict.set_item("latency_ms", self.latency_ms)?;
+        dict.set_item("callbacks_per_second", self.callbacks_per_second)?;
+        Ok(dict.into())
+    }
+}
+
+type SharedConfigs = Arc<RwLock<HashMap<String, PreferredConfig>>>;
+
+/// Configurations capture streams open with, from the store last applied
+static APPLIED: Mutex<Option<SharedConfigs>> = Mutex::new(None);
+
+/// Preferred configuration for a device in the applied store, if there is one
+pub fn preferred_config(device: &str) -> Option<PreferredConfig> {
+    APPLIED.lock().unwrap().as_ref().and_then(|configs| configs.read().unwrap().get(device).cloned())
+}
+
+/// Per-device preferred configuration store persisted as JSON
+#[pyclass]
+pub struct DeviceConfigStore {
+    path: PathBuf,
+    configs: SharedConfigs,
+}
+
+impl DeviceConfigStore {
+    pub fn load(path: PathBuf) -> Result<Self, String> {
+        let configs = if path.exists() {
+            let data = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+            serde_json::from_str(&data).map_err(|err| err.to_string())?
+        } else {
+            HashMap::new()
+        };
+        Ok(DeviceConfigStore { path, configs: Arc::new(RwLock::new(configs)) })
+    }
+    
+    pub fn save(&self) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(&*self.configs.read().unwrap()).map_err(|err| err.to_string())?;
+        fs::write(&self.path, data).map_err(|err| err.to_string())
+    }
+    
+    pub fn get_config(&self, device: &str) -> Option<PreferredConfig> {
+        self.configs.read().unwrap().get(device).cloned()
+    }
+    
+    pub fn set_config(&mut self, device: String, config: PreferredConfig) {
+        self.configs.write().unwrap().insert(device, config);
+    }
+}
+
+#[pymethods]
+impl DeviceConfigStore {
+    /// Open the store at `path`, loading existing entries
+    #[new]
+    fn new(path: String) -> PyResult<Self> {
+        DeviceConfigStore::load(PathBuf::from(path)).map_err(PyValueError::new_err)
+    }
+    
+    /// Get the preferred configuration for a device
+    fn get(&self, py: Python, device: &str) -> PyResult<Option<Py<PyDict>>> {
+        self.get_config(device).map(|config| config.to_dict(py)).transpose()
+    }
+    
+    /// Get the names of all devices with a stored configuration
+    fn devices(&self) -> Vec<String> {
+        let mut devices: Vec<String> = self.configs.read().unwrap().keys().cloned().collect();
+        devices.sort();
+        devices
+    }
+    
+    /// Remove the configuration for a device
+    fn remove(&mut self, device: &str) -> PyResult<()> {
+        self.configs.write().unwrap().remove(device);
+        self.save().map_err(PyValueError::new_err)
+    }
+    
+    /// Open capture streams with this store's configurations from now on
+    /// 
+    /// A stream on a device with a stored configuration uses its block size,
+    /// as long as the device still runs at the stored sample rate. Later
+    /// calibrations into this store apply to the streams opened after them.
+    fn apply(&self) {
+        *APPLIED.lock().unwrap() = Some(self.configs.clone());
+    }
+    
+    /// Open capture streams with the device defaults again
+    #[staticmethod]
+    fn reset_applied() {
+        *APPLIED.lock().unwrap() = None;
+    }
+}
+
+/// Result of measuring a single block size
+#[derive(Clone, Debug)]
+pub struct BlockMeasurement {
+    pub block_size: u32,
+    pub callbacks: usize,
+    pub nominal_interval: Duration,
+    pub max_interval: Duration,
+}
+
+impl BlockMeasurement {
+    /// Smallest queue depth that absorbs the worst observed callback jitter
+    pub fn required_queue_depth(&self) -> u32 {
+        let ratio = self.max_interval.as_secs_f64() / self.nominal_interval.as_secs_f64().max(f64::EPSILON);
+        (ratio.ceil() as u32).max(1).saturating_add(1)
+    }
+    
+    pub fn latency_ms(&self) -> f64 {
+        self.nominal_interval.as_secs_f64() * 1000.0 * self.required_queue_depth() as f64
+    }
+}
+
+/// Run a capture stream with a fixed block size and record callback timing
+fn measure_block_size(
+    device: &cpal::Device,
+    block_size: u32,
+    duration: Duration,
+) -> Result<BlockMeasurement, AudioError> {
+    let supported = device
+        .default_input_config()
+        .map_err(|err| classify_stream_error(err.to_string()))?;
+    let mut config = supported.config();
+    config.buffer_size = cpal::BufferSize::Fixed(block_size);
+    let sample_rate = config.sample_rate.0;
+    
+    let timestamps: Arc<Mutex<Vec<Instant>>> = Arc::new(Mutex::new(Vec::new()));
+    let sink = timestamps.clone();
+    
+    let stream = device
+        .build_input_stream_raw(
+            &config,
+            supported.sample_format(),
+            move |_data: &cpal::Data, _: &cpal::InputCallbackInfo| {
+                sink.lock().unwrap().push(Instant::now());
+            },
+            |err| tracing::warn!("Latency calibration stream error: {}", err),
+            None,
+        )
+        .map_err(|err| classify_stream_error(err.to_string()))?;
+    stream.play().map_err(|err| classify_stream_error(err.to_string()))?;
+    std::thread::sleep(duration);
+    drop(stream);
+    
+    let timestamps = timestamps.lock().unwrap();
+    // Skip the first callbacks, which include stream start-up
+    let steady: Vec<&Instant> = timestamps.iter().skip(3).collect();
+    let max_interval = steady
+        .windows(2)
+        .map(|w| w[1].duration_since(*w[0]))
+        .max()
+        .ok_or_else(|| AudioError::StreamError(format!("No callbacks received for block size {}", block_size)))?;
+    
+    Ok(BlockMeasurement {
+        block_size,
+        callbacks: timestamps.len(),
+        nominal_interval: Duration::from_secs_f64(block_size as f64 / sample_rate as f64),
+        max_interval,
+    })
+}
+
+fn check_block_sizes(block_sizes: &[u32]) -> PyResult<()> {
+    if block_sizes.is_empty() || block_sizes.contains(&0) {
+        return Err(PyValueError::new_err("Candidate block sizes must be a non-empty list of positive sizes"));
+    }
+    Ok(())
+}
+
+/// Calibrates capture block size and queue depth against a latency budget
+/// 
+/// Each candidate block size is run for a short period while callback
+/// timing is recorded. The queue depth needed to absorb the observed jitter
+/// is derived from the worst callback interval, and the configuration with
+/// the fewest callbacks per second that stays within the budget is chosen.
+#[pyclass]
+pub struct LatencyTuner {
+    #[pyo3(get, set)]
+    latency_budget_ms: f64,
+    #[pyo3(get, set)]
+    candidate_block_sizes: Vec<u32>,
+    #[pyo3(get, set)]
+    measure_ms: u64,
+}
+
+#[pymethods]
+impl LatencyTuner {
+    /// Create a tuner for the given latency budget
+    #[new]
+    #[pyo3(signature = (latency_budget_ms, candidate_block_sizes=None, measure_ms=500))]
+    fn new(latency_budget_ms: f64, candidate_block_sizes: Option<Vec<u32>>, measure_ms: u64) -> PyResult<Self> {
+        if latency_budget_ms <= 0.0 {
+            return Err(PyValueError::new_err("Latency budget must be greater than zero"));
+        }
+        let candidate_block_sizes = candidate_block_sizes.unwrap_or_else(|| vec![128, 256, 512, 1024, 2048]);
+        check_block_sizes(&candidate_block_sizes)?;
+        
+        Ok(LatencyTuner { latency_budget_ms, candidate_block_sizes, measure_ms })
+    }
+    
+    /// Run the calibration on a device and write the result into `store`
+    /// 
+    /// Returns the chosen configuration, or raises if no candidate meets the
+    /// budget. Capture streams use it once the store is applied.
+    #[pyo3(signature = (store, device_name=None))]
+    fn calibrate(&self, py: Python, store: &mut DeviceConfigStore, device_name: Option<String>) -> PyResult<Py<PyDict>> {
+        let budget = self.latency_budget_ms;
+        // The candidates can be replaced after construction
+        check_block_sizes(&self.candidate_block_sizes)?;
+        let candidates = self.candidate_block_sizes.clone();
+        let duration = Duration::from_millis(self.measure_ms);
+        
+        let (name, chosen) = py.allow_threads(|| -> Result<(String, Option<PreferredConfig>), AudioError> {
+            let device = find_input_device(device_name.as_deref())?;
+            let name = device.name().map_err(|err| AudioError::DeviceError(err.to_string()))?;
+            let sample_rate = device
+                .default_input_config()
+                .map_err(|err| classify_stream_error(err.to_string()))?
+                .sample_rate()
+                .0;
+            
+            let mut best: Option<PreferredConfig> = None;
+            for block_size in candidates {
+                let measurement = match measure_block_size(&device, block_size, duration) {
+                    Ok(measurement) => measurement,
+                    Err(err) => {
+                        tracing::debug!("Block size {} rejected: {}", block_size, err);
+                        continue;
+                    }
+                };
+                
+                let latency_ms = measurement.latency_ms();
+                if latency_ms > budget {
+                    continue;
+                }
+                
+                let callbacks_per_second = sample_rate as f64 / block_size as f64;
+                let better = best
+                    .as_ref()
+                    .map(|b| callbacks_per_second < b.callbacks_per_second)
+                    .unwrap_or(true);
+                if better {
+                    best = Some(PreferredConfig {
+                        sample_rate,
+                        block_size,
+                        queue_depth: measurement.required_queue_depth(),
+                        latency_ms,
+                        callbacks_per_second,
+                    });
+                }
+            }
+            Ok((name, best))
+        })?;
+        
+        let chosen = chosen.ok_or_else(|| PyValueError::new_err(format!(
+            "No block size met the {} ms latency budget", budget
+        )))?;
+        
+        store.set_config(name, chosen.clone());
+        store.save().map_err(PyValueError::new_err)?;
+        chosen.to_dict(py)
+    }
+}
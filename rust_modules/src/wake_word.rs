@@ -1,101 +1,1350 @@
 use pyo3::prelude::*;
+use pyo3::create_exception;
 use pyo3::exceptions::PyValueError;
-use porcupine::{Porcupine, PorcupineBuilder};
-use std::path::Path;
+use pyo3::types::PyDict;
+use porcupine::{BuiltinKeywords, Porcupine, PorcupineBuilder};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-/// Wake word detector using Porcupine
+use crate::audio::{read_wav_mono, CaptureSource, Captured, MonoResampler, StallPolicy};
+use crate::config::JennaConfig;
+use crate::errors::{JennaError, LicenseError, ModelError, NotInitializedError};
+use crate::events::{self, Event, EventData};
+use crate::lifecycle::{self, Resource};
+use crate::metrics;
+use crate::panics;
+
+use jenna_core::level::{check_gate_threshold, frame_dbfs, SILENCE_DBFS};
+use jenna_core::wake_word::{keyword_name_from_path, EngineOutput, OpenWakeWordEngine, WakeWordEngine};
+
+/// Smoothing factor for the ambient noise estimate (per frame)
+const NOISE_SMOOTHING: f32 = 0.05;
+
+/// Frames kept open after the level drops below the energy gate, so quiet
+/// keyword tails still reach the engine
+const GATE_HANGOVER_FRAMES: u32 = 10;
+
+/// Most recent gated frames replayed to the engine when the gate opens, so
+/// its internal history covers the onset of speech
+const GATE_PREROLL_FRAMES: usize = 8;
+
+/// Snapshot of the assistant context captured when the wake word fires
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct ContextSnapshot {
+    /// Seconds since the Unix epoch
+    #[pyo3(get)]
+    pub timestamp: f64,
+    /// Output zone that was active at detection time
+    #[pyo3(get)]
+    pub output_zone: Option<String>,
+    /// Ambient noise level in dBFS, estimated from the frames preceding detection
+    #[pyo3(get)]
+    pub ambient_noise_db: f32,
+    /// Playback state reported by the host ("idle", "playing", "paused", ...)
+    #[pyo3(get)]
+    pub playback_state: String,
+}
+
+#[pymethods]
+impl ContextSnapshot {
+    fn __repr__(&self) -> String {
+        format!(
+            "ContextSnapshot(timestamp={:.3}, output_zone={:?}, ambient_noise_db={:.1}, playback_state={:?})",
+            self.timestamp, self.output_zone, self.ambient_noise_db, self.playback_state
+        )
+    }
+}
+
+/// Keyword paths as passed from Python: a single path or a list of paths
+#[derive(FromPyObject)]
+pub enum KeywordPaths {
+    Single(String),
+    Many(Vec<String>),
+}
+
+impl KeywordPaths {
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            KeywordPaths::Single(path) => vec![path],
+            KeywordPaths::Many(paths) => paths,
+        }
+    }
+}
+
+create_exception!(
+    jenna_rust,
+    WakeWordLicenseError,
+    LicenseError,
+    "Raised when the wake word engine rejects the access key or license."
+);
+
+/// Environment variable consulted when no access key is passed explicitly
+const ACCESS_KEY_ENV: &str = "PV_ACCESS_KEY";
+
+/// Built-in Porcupine keywords by name
+const BUILTIN_KEYWORDS: &[(&str, BuiltinKeywords)] = &[
+    ("alexa", BuiltinKeywords::Alexa),
+    ("americano", BuiltinKeywords::Americano),
+    ("blueberry", BuiltinKeywords::Blueberry),
+    ("bumblebee", BuiltinKeywords::Bumblebee),
+    ("computer", BuiltinKeywords::Computer),
+    ("grapefruit", BuiltinKeywords::Grapefruit),
+    ("grasshopper", BuiltinKeywords::Grasshopper),
+    ("hey google", BuiltinKeywords::HeyGoogle),
+    ("hey siri", BuiltinKeywords::HeySiri),
+    ("jarvis", BuiltinKeywords::Jarvis),
+    ("ok google", BuiltinKeywords::OkGoogle),
+    ("picovoice", BuiltinKeywords::Picovoice),
+    ("porcupine", BuiltinKeywords::Porcupine),
+    ("terminator", BuiltinKeywords::Terminator),
+];
+
+/// Look up a built-in keyword by name (case-insensitive, "_" or "-" may replace spaces)
+pub fn builtin_keyword(name: &str) -> PyResult<BuiltinKeywords> {
+    let normalized = name.to_ascii_lowercase().replace(['_', '-'], " ");
+    BUILTIN_KEYWORDS
+        .iter()
+        .find(|(keyword, _)| *keyword == normalized)
+        .map(|(_, keyword)| *keyword)
+        .ok_or_else(|| PyValueError::new_err(format!("Unknown built-in keyword: {}", name)))
+}
+
+/// Where the loaded keywords come from
+#[derive(Clone)]
+pub enum KeywordSource {
+    Paths(Vec<String>),
+    Builtin(Vec<BuiltinKeywords>),
+}
+
+impl KeywordSource {
+    pub fn len(&self) -> usize {
+        match self {
+            KeywordSource::Paths(paths) => paths.len(),
+            KeywordSource::Builtin(keywords) => keywords.len(),
+        }
+    }
+}
+
+/// Map an engine initialization error to a Python exception
+/// 
+/// Access key and activation failures become `WakeWordLicenseError` so the
+/// host can prompt for a new key instead of reporting a generic failure.
+fn engine_init_error(message: String) -> PyErr {
+    let lower = message.to_lowercase();
+    if ["accesskey", "access key", "activation", "license", "licence"].iter().any(|p| lower.contains(p)) {
+        WakeWordLicenseError::new_err(format!("Porcupine rejected the access key: {}", message))
+    } else {
+        JennaError::new_err(format!("Failed to initialize Porcupine: {}", message))
+    }
+}
+
+/// Build a Porcupine instance for the given keywords
+pub fn build_porcupine(
+    access_key: &str,
+    source: &KeywordSource,
+    sensitivities: &[f32],
+    model_path: Option<&str>,
+) -> PyResult<Porcupine> {
+    try_build_porcupine(access_key, source, sensitivities, model_path).map_err(engine_init_error)
+}
+
+/// Build a Porcupine instance, returning Porcupine's own error message on failure
+pub fn try_build_porcupine(
+    access_key: &str,
+    source: &KeywordSource,
+    sensitivities: &[f32],
+    model_path: Option<&str>,
+) -> Result<Porcupine, String> {
+    let mut builder = match source {
+        KeywordSource::Paths(paths) => PorcupineBuilder::new_with_keyword_paths(access_key, paths),
+        KeywordSource::Builtin(keywords) => PorcupineBuilder::new_with_keywords(access_key, keywords),
+    };
+    builder.sensitivities(sensitivities);
+    if let Some(model_path) = model_path {
+        builder.model_path(model_path);
+    }
+    
+    builder.init().map_err(|err| err.to_string())
+}
+
+/// Porcupine backend
+pub struct PorcupineEngine {
+    porcupine: Porcupine,
+}
+
+impl PorcupineEngine {
+    pub fn new(porcupine: Porcupine) -> Self {
+        PorcupineEngine { porcupine }
+    }
+}
+
+impl WakeWordEngine for PorcupineEngine {
+    fn frame_length(&self) -> usize {
+        self.porcupine.frame_length() as usize
+    }
+    
+    fn sample_rate(&self) -> u32 {
+        self.porcupine.sample_rate()
+    }
+    
+    fn process(&mut self, frame: &[i16]) -> Result<EngineOutput, String> {
+        let index = self.porcupine.process(frame).map_err(|err| err.to_string())?;
+        Ok(EngineOutput {
+            keyword: if index >= 0 { Some(index as usize) } else { None },
+            score: None,
+        })
+    }
+    
+    fn name(&self) -> &'static str {
+        "porcupine"
+    }
+}
+
+/// Available wake word backends
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EngineKind {
+    Porcupine,
+    OpenWakeWord,
+}
+
+impl EngineKind {
+    pub fn from_name(name: &str) -> PyResult<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "porcupine" => Ok(EngineKind::Porcupine),
+            "openwakeword" | "oww" | "onnx" => Ok(EngineKind::OpenWakeWord),
+            _ => Err(PyValueError::new_err(format!("Unknown wake word engine: {}", name)))
+        }
+    }
+}
+
+/// Structured result of a wake word detection
+#[pyclass]
+#[derive(Clone, Debug)]
+pub struct WakeWordDetection {
+    /// Name of the keyword that fired
+    #[pyo3(get)]
+    pub keyword: String,
+    /// Index of the keyword in the loaded keyword list
+    #[pyo3(get)]
+    pub index: usize,
+    /// Engine score in [0, 1], if the engine exposes one
+    #[pyo3(get)]
+    pub confidence: Option<f32>,
+    /// Stream position of the end of the triggering frame, in seconds since initialization
+    #[pyo3(get)]
+    pub stream_time: f64,
+    /// Wall-clock time of the detection, in seconds since the Unix epoch
+    #[pyo3(get)]
+    pub timestamp: f64,
+    /// Context captured when the keyword fired; None for detections in files
+    #[pyo3(get)]
+    pub context: Option<ContextSnapshot>,
+}
+
+#[pymethods]
+impl WakeWordDetection {
+    fn __repr__(&self) -> String {
+        format!(
+            "WakeWordDetection(keyword={:?}, index={}, confidence={:?}, stream_time={:.3}, timestamp={:.3})",
+            self.keyword, self.index, self.confidence, self.stream_time, self.timestamp
+        )
+    }
+}
+
+pub fn unix_time() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Conversation state used for wake-word-free follow-up turns
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FollowUpState {
+    /// Speech must start with the wake word
+    Idle,
+    /// The assistant is handling a turn; the window opens when it ends
+    Engaged,
+    /// Speech is addressed to the assistant until the deadline
+    Window(Instant),
+}
+
+/// Longest follow-up window accepted, in seconds
+const MAX_FOLLOW_UP_WINDOW_S: f64 = 86400.0;
+
+/// Follow-up mode timers; disabled while `window` is None
+struct FollowUp {
+    window: Option<Duration>,
+    state: FollowUpState,
+}
+
+impl FollowUp {
+    fn new() -> Self {
+        FollowUp { window: None, state: FollowUpState::Idle }
+    }
+    
+    /// Expire an elapsed window and return the current state
+    fn current(&mut self) -> FollowUpState {
+        if let FollowUpState::Window(deadline) = self.state {
+            if Instant::now() >= deadline {
+                self.state = FollowUpState::Idle;
+            }
+        }
+        self.state
+    }
+    
+    fn on_detection(&mut self) {
+        if self.window.is_some() {
+            self.state = FollowUpState::Engaged;
+        }
+    }
+    
+    fn end_interaction(&mut self) {
+        self.state = match self.window {
+            Some(window) => FollowUpState::Window(Instant::now() + window),
+            None => FollowUpState::Idle,
+        };
+    }
+}
+
+/// Window used for the rolling detection rate
+const STATS_RATE_WINDOW: Duration = Duration::from_secs(3600);
+
+/// Number of rejection scores kept for diagnostics
+const STATS_REJECTION_HISTORY: usize = 100;
+
+/// Number of detections kept in the recent history
+const STATS_DETECTION_HISTORY: usize = 20;
+
+/// Detection counters and recent history
+struct DetectionStats {
+    started: Instant,
+    frames_processed: u64,
+    total_detections: u64,
+    keyword_counts: Vec<u64>,
+    detection_times: VecDeque<Instant>,
+    recent_detections: VecDeque<WakeWordDetection>,
+    rejection_scores: VecDeque<f32>,
+}
+
+impl DetectionStats {
+    fn new() -> Self {
+        DetectionStats {
+            started: Instant::now(),
+            frames_processed: 0,
+            total_detections: 0,
+            keyword_counts: Vec::new(),
+            detection_times: VecDeque::new(),
+            recent_detections: VecDeque::with_capacity(STATS_DETECTION_HISTORY),
+            rejection_scores: VecDeque::with_capacity(STATS_REJECTION_HISTORY),
+        }
+    }
+    
+    fn record_detection(&mut self, detection: &WakeWordDetection) {
+        self.total_detections += 1;
+        if self.keyword_counts.len() <= detection.index {
+            self.keyword_counts.resize(detection.index + 1, 0);
+        }
+        self.keyword_counts[detection.index] += 1;
+        
+        self.detection_times.push_back(Instant::now());
+        self.prune();
+        
+        if self.recent_detections.len() == STATS_DETECTION_HISTORY {
+            self.recent_detections.pop_front();
+        }
+        self.recent_detections.push_back(detection.clone());
+    }
+    
+    fn record_rejection(&mut self, score: f32) {
+        if self.rejection_scores.len() == STATS_REJECTION_HISTORY {
+            self.rejection_scores.pop_front();
+        }
+        self.rejection_scores.push_back(score);
+    }
+    
+    /// Drop detection times that have left the rate window
+    fn prune(&mut self) {
+        let now = Instant::now();
+        while let Some(&oldest) = self.detection_times.front() {
+            if now.duration_since(oldest) <= STATS_RATE_WINDOW {
+                break;
+            }
+            self.detection_times.pop_front();
+        }
+    }
+    
+    /// Detections per hour over the rate window (or the uptime, if shorter)
+    fn detection_rate_per_hour(&mut self) -> f64 {
+        self.prune();
+        let span = self.started.elapsed().min(STATS_RATE_WINDOW).as_secs_f64();
+        if span > 0.0 {
+            self.detection_times.len() as f64 * 3600.0 / span
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Detector state shared between Python calls and the listening thread
+pub struct DetectorState {
+    engine: Option<Box<dyn WakeWordEngine>>,
+    keyword_names: Vec<String>,
+    samples_processed: u64,
+    ambient_noise_db: f32,
+    output_zone: Option<String>,
+    playback_state: String,
+    last_context: Option<ContextSnapshot>,
+    gate_threshold_dbfs: Option<f32>,
+    gate_hangover: u32,
+    gate_preroll: VecDeque<Vec<i16>>,
+    frames_gated: u64,
+    follow_up: FollowUp,
+    stats: DetectionStats,
+}
+
+impl DetectorState {
+    fn new() -> Self {
+        DetectorState {
+            engine: None,
+            keyword_names: Vec::new(),
+            samples_processed: 0,
+            ambient_noise_db: SILENCE_DBFS,
+            output_zone: None,
+            playback_state: "idle".to_string(),
+            last_context: None,
+            gate_threshold_dbfs: None,
+            gate_hangover: 0,
+            gate_preroll: VecDeque::with_capacity(GATE_PREROLL_FRAMES),
+            frames_gated: 0,
+            follow_up: FollowUp::new(),
+            stats: DetectionStats::new(),
+        }
+    }
+    
+    /// Decide whether a frame should skip the engine
+    /// 
+    /// Loud frames re-arm a hangover counter; the gate only closes once the
+    /// hangover has run out.
+    fn gate_closed(&mut self, level: f32) -> bool {
+        let threshold = match self.gate_threshold_dbfs {
+            Some(threshold) => threshold,
+            None => return false,
+        };
+        
+        if level >= threshold {
+            self.gate_hangover = GATE_HANGOVER_FRAMES;
+            false
+        } else if self.gate_hangover > 0 {
+            self.gate_hangover -= 1;
+            false
+        } else {
+            true
+        }
+    }
+    
+    fn engine(&self) -> Result<&dyn WakeWordEngine, String> {
+        self.engine.as_deref().ok_or_else(|| "Wake word engine not initialized".to_string())
+    }
+    
+    /// Frame length and sample rate expected by the engine
+    pub fn frame_format(&self) -> Result<(usize, u32), String> {
+        let engine = self.engine()?;
+        Ok((engine.frame_length(), engine.sample_rate()))
+    }
+    
+    /// Names of the loaded keywords, in index order
+    pub fn keyword_names(&self) -> &[String] {
+        &self.keyword_names
+    }
+    
+    pub fn set_playback_state(&mut self, state: &str) {
+        if self.playback_state != state {
+            self.playback_state = state.to_string();
+        }
+    }
+    
+    /// Mark the current turn as finished, opening the follow-up window if enabled
+    pub fn end_interaction(&mut self) {
+        self.follow_up.end_interaction();
+    }
+    
+    /// Start a wake-word-free turn if the follow-up window is open
+    pub fn begin_follow_up(&mut self) -> bool {
+        match self.follow_up.current() {
+            FollowUpState::Window(_) => {
+                self.follow_up.state = FollowUpState::Engaged;
+                true
+            }
+            FollowUpState::Engaged => true,
+            FollowUpState::Idle => false,
+        }
+    }
+    
+    /// Run the engine on a frame and return a detection if a keyword fired
+    pub fn process_frame(&mut self, audio_frame: &[i16]) -> Result<Option<WakeWordDetection>, String> {
+        let engine = self.engine.as_mut().ok_or_else(|| "Wake word engine not initialized".to_string())?;
+        if audio_frame.len() != engine.frame_length() {
+            return Err(format!(
+                "Audio frame size ({}) doesn't match required size ({})", 
+                audio_frame.len(), 
+                engine.frame_length()
+            ));
+        }
+        
+        let level = frame_dbfs(audio_frame);
+        if self.gate_closed(level) {
+            if self.gate_preroll.len() == GATE_PREROLL_FRAMES {
+                self.gate_preroll.pop_front();
+            }
+            self.gate_preroll.push_back(audio_frame.to_vec());
+            self.frames_gated += 1;
+            self.stats.frames_processed += 1;
+            self.samples_processed += audio_frame.len() as u64;
+            self.track_noise(audio_frame);
+            return Ok(None);
+        }
+        
+        // Replay the audio leading up to the gate opening; the engine keeps a
+        // sliding history and would otherwise splice stale audio onto this frame
+        let engine = self.engine.as_mut().ok_or_else(|| "Wake word engine not initialized".to_string())?;
+        let mut keyword = None;
+        let mut score = None;
+        let mut best_rejected: Option<f32> = None;
+        for frame in self.gate_preroll.drain(..).chain(std::iter::once(audio_frame.to_vec())) {
+            let output = engine.process(&frame).map_err(|err| format!("Processing error: {}", err))?;
+            if output.keyword.is_some() {
+                keyword = output.keyword;
+                score = output.score;
+            } else if let Some(rejected) = output.score {
+                best_rejected = Some(best_rejected.map_or(rejected, |best| best.max(rejected)));
+            }
+        }
+        let sample_rate = engine.sample_rate();
+        self.samples_processed += audio_frame.len() as u64;
+        self.stats.frames_processed += 1;
+        
+        match keyword {
+            Some(index) => {
+                let context = self.capture_context();
+                self.follow_up.on_detection();
+                let detection = WakeWordDetection {
+                    keyword: self.keyword_names.get(index).cloned().unwrap_or_default(),
+                    index,
+                    confidence: score,
+                    stream_time: self.samples_processed as f64 / sample_rate as f64,
+                    timestamp: unix_time(),
+                    context: Some(context),
+                };
+                self.stats.record_detection(&detection);
+                tracing::info!(keyword = %detection.keyword, confidence = ?detection.confidence, "Wake word detected");
+                events::post(Event::new("wake_word.detected", "wake_word", EventData::WakeWord(detection.clone())));
+                Ok(Some(detection))
+            }
+            None => {
+                if let Some(rejected) = best_rejected {
+                    self.stats.record_rejection(rejected);
+                }
+                self.track_noise(audio_frame);
+                Ok(None)
+            }
+        }
+    }
+    
+    /// Update the ambient noise estimate with a non-triggering frame
+    fn track_noise(&mut self, frame: &[i16]) {
+        let level = frame_dbfs(frame);
+        self.ambient_noise_db += NOISE_SMOOTHING * (level - self.ambient_noise_db);
+    }
+    
+    /// Capture the current context at detection time
+    fn capture_context(&mut self) -> ContextSnapshot {
+        let context = ContextSnapshot {
+            timestamp: unix_time(),
+            output_zone: self.output_zone.clone(),
+            ambient_noise_db: self.ambient_noise_db,
+            playback_state: self.playback_state.clone(),
+        };
+        self.last_context = Some(context.clone());
+        context
+    }
+}
+
+/// Background capture thread owned by a listening detector
+struct Listener {
+    stop: Arc<AtomicBool>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Resource for Listener {
+    fn shutdown(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let handle = self.handle.lock().unwrap().take();
+        lifecycle::join_threads(handle);
+    }
+}
+
+/// Capture, resample and frame audio, invoking `callback` on every detection
+#[tracing::instrument(name = "wake_listener", skip_all, fields(device = ?device_name))]
+fn run_listener(
+    state: Arc<Mutex<DetectorState>>,
+    device_name: Option<String>,
+    stall: StallPolicy,
+    callback: &PyObject,
+    stop: Arc<AtomicBool>,
+) -> Result<(), String> {
+    let (frame_length, engine_rate) = state.lock().unwrap().frame_format()?;
+    
+    let mut capture = CaptureSource::open(device_name, engine_rate, stall).map_err(|err| err.to_string())?;
+    let mut pending: Vec<i16> = Vec::with_capacity(frame_length * 2);
+    
+    while !stop.load(Ordering::Relaxed) {
+        let resampled = match capture.recv(Duration::from_millis(100)).map_err(|err| err.to_string())? {
+            Captured::Audio(samples) => samples,
+            Captured::Idle => continue,
+            Captured::Closed => break,
+        };
+        
+        let captured = Instant::now();
+        let cpu_start = metrics::thread_cpu_time();
+        pending.extend(resampled.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+        
+        while pending.len() >= frame_length {
+            let frame: Vec<i16> = pending.drain(..frame_length).collect();
+            let detection = state.lock().unwrap().process_frame(&frame)?;
+            metrics::observe_latency("wake_decision", captured.elapsed());
+            
+            if let Some(detection) = detection {
+                Python::with_gil(|py| {
+                    if let Err(err) = callback.call1(py, (detection,)) {
+                        err.print(py);
+                    }
+                });
+            }
+        }
+        metrics::set_buffer("listener_pending", pending.len());
+        metrics::add_cpu_time("wake_listener", metrics::thread_cpu_time().saturating_sub(cpu_start));
+    }
+    
+    drop(capture);
+    Ok(())
+}
+
+/// Run an engine over a WAV file, returning its detections and duration in seconds
+/// 
+/// `stream_time` of each detection is the offset into the file.
+fn detect_file(
+    engine: &mut dyn WakeWordEngine,
+    keyword_names: &[String],
+    path: &Path,
+) -> Result<(Vec<WakeWordDetection>, f64), String> {
+    let (samples, file_rate) = read_wav_mono(path).map_err(|err| err.to_string())?;
+    let engine_rate = engine.sample_rate();
+    let frame_length = engine.frame_length();
+    
+    let mut resampler = MonoResampler::new(file_rate, engine_rate).map_err(|err| err.to_string())?;
+    let mut resampled = resampler.push(&samples).map_err(|err| err.to_string())?;
+    // Flush the resampler tail and pad the last partial frame with silence
+    let tail = resampler.push(&vec![0.0; 2048]).map_err(|err| err.to_string())?;
+    resampled.extend_from_slice(&tail);
+    let padded = (resampled.len() + frame_length - 1) / frame_length * frame_length;
+    resampled.resize(padded, 0.0);
+    
+    // Prime with a second of silence so history from a previous file cannot leak in
+    let silence = vec![0i16; frame_length];
+    for _ in 0..(engine_rate as usize + frame_length - 1) / frame_length {
+        engine.process(&silence)?;
+    }
+    
+    let mut detections = Vec::new();
+    for (i, frame) in resampled.chunks(frame_length).enumerate() {
+        let frame: Vec<i16> = frame.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).collect();
+        let output = engine.process(&frame)?;
+        if let Some(index) = output.keyword {
+            detections.push(WakeWordDetection {
+                keyword: keyword_names.get(index).cloned().unwrap_or_default(),
+                index,
+                confidence: output.score,
+                stream_time: ((i + 1) * frame_length) as f64 / engine_rate as f64,
+                timestamp: unix_time(),
+                context: None,
+            });
+        }
+    }
+    
+    Ok((detections, samples.len() as f64 / file_rate as f64))
+}
+
+/// List the WAV files in a directory, sorted by name
+fn wav_files(directory: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(directory)
+        .map_err(|err| format!("Cannot read {}: {}", directory.display(), err))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .map(|ext| ext.eq_ignore_ascii_case("wav"))
+                .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Wake word detector with a pluggable engine (Porcupine or openWakeWord)
+/// 
+/// Several keywords can be loaded at once, each with its own sensitivity;
+/// `process_keyword` reports which one fired.
 #[pyclass]
 pub struct WakeWordDetector {
-    porcupine: Arc<Mutex<Option<Porcupine>>>,
+    state: Arc<Mutex<DetectorState>>,
+    engine_kind: EngineKind,
+    access_key: Option<String>,
+    model_path: Option<String>,
+    keyword_source: Option<KeywordSource>,
     sensitivity: f32,
+    sensitivities: Vec<f32>,
     is_active: bool,
+    listener: Option<Arc<Listener>>,
+}
+
+impl WakeWordDetector {
+    /// Resolve the access key from the constructor or the environment
+    fn resolve_access_key(&self) -> PyResult<String> {
+        self.access_key
+            .clone()
+            .or_else(|| std::env::var(ACCESS_KEY_ENV).ok())
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| WakeWordLicenseError::new_err(format!(
+                "A Porcupine access key is required; pass access_key or set {}", ACCESS_KEY_ENV
+            )))
+    }
+    
+    /// Validate the keyword configuration and swap in a freshly built engine
+    fn load_keywords(
+        &mut self,
+        source: KeywordSource,
+        sensitivities: Option<Vec<f32>>,
+        keyword_names: Vec<String>,
+        model_path: Option<String>,
+    ) -> PyResult<()> {
+        let count = source.len();
+        if count == 0 {
+            return Err(PyValueError::new_err("At least one keyword is required"));
+        }
+        
+        let sensitivities = sensitivities.unwrap_or_else(|| vec![self.sensitivity; count]);
+        if sensitivities.len() != count {
+            return Err(PyValueError::new_err(format!(
+                "Got {} sensitivities for {} keywords", sensitivities.len(), count
+            )));
+        }
+        if sensitivities.iter().any(|s| !(0.0..=1.0).contains(s)) {
+            return Err(PyValueError::new_err("Sensitivity must be between 0.0 and 1.0"));
+        }
+        if keyword_names.len() != count {
+            return Err(PyValueError::new_err(format!(
+                "Got {} keyword names for {} keywords", keyword_names.len(), count
+            )));
+        }
+        
+        if let Some(model_path) = &model_path {
+            if !Path::new(model_path).exists() {
+                return Err(ModelError::new_err(format!("Model file not found: {}", model_path)));
+            }
+        }
+        if self.engine_kind == EngineKind::OpenWakeWord && model_path.is_none() {
+            return Err(PyValueError::new_err("The openWakeWord engine requires a model directory"));
+        }
+        if let KeywordSource::Paths(paths) = &source {
+            for keyword_path in paths {
+                if !Path::new(keyword_path).exists() {
+                    return Err(ModelError::new_err(format!("Keyword file not found: {}", keyword_path)));
+                }
+            }
+        }
+        
+        let engine = self.build_engine(&source, &sensitivities, model_path.as_deref())?;
+        
+        // Swap under the lock so a running listener sees either the old or
+        // the new engine, never a half-configured one
+        let mut state = self.state.lock().unwrap();
+        if state.engine.is_none() {
+            state.samples_processed = 0;
+        }
+        if state.keyword_names != keyword_names {
+            state.stats.keyword_counts.clear();
+        }
+        state.engine = Some(engine);
+        state.keyword_names = keyword_names;
+        state.gate_preroll.clear();
+        self.keyword_source = Some(source);
+        self.model_path = model_path;
+        self.sensitivities = sensitivities;
+        self.is_active = true;
+        Ok(())
+    }
+    
+    /// Build a new engine instance for the selected backend
+    fn build_engine(
+        &self,
+        source: &KeywordSource,
+        sensitivities: &[f32],
+        model_path: Option<&str>,
+    ) -> PyResult<Box<dyn WakeWordEngine>> {
+        match (self.engine_kind, source) {
+            (EngineKind::Porcupine, _) => {
+                let access_key = self.resolve_access_key()?;
+                let porcupine = build_porcupine(&access_key, source, sensitivities, model_path)?;
+                Ok(Box::new(PorcupineEngine { porcupine }))
+            }
+            (EngineKind::OpenWakeWord, KeywordSource::Paths(paths)) => {
+                let model_dir = Path::new(model_path.unwrap_or_default());
+                Ok(Box::new(OpenWakeWordEngine::new(model_dir, paths, sensitivities).map_err(ModelError::new_err)?))
+            }
+            (EngineKind::OpenWakeWord, KeywordSource::Builtin(_)) => {
+                Err(PyValueError::new_err("Built-in keywords are only available with the Porcupine engine"))
+            }
+        }
+    }
+    
+    /// Build a separate engine for offline evaluation, leaving the live one untouched
+    fn build_offline_engine(&self, sensitivities: Option<Vec<f32>>) -> PyResult<Box<dyn WakeWordEngine>> {
+        let source = self
+            .keyword_source
+            .as_ref()
+            .ok_or_else(|| NotInitializedError::new_err("Wake word engine not initialized"))?;
+        let sensitivities = sensitivities.unwrap_or_else(|| self.sensitivities.clone());
+        if sensitivities.len() != source.len() {
+            return Err(PyValueError::new_err(format!(
+                "Got {} sensitivities for {} keywords", sensitivities.len(), source.len()
+            )));
+        }
+        
+        self.build_engine(source, &sensitivities, self.model_path.as_deref())
+    }
+    
+    /// Run the engine on a frame without holding the GIL
+    fn detect(&mut self, py: Python, audio_frame: Vec<i16>) -> PyResult<Option<WakeWordDetection>> {
+        let state = self.state.clone();
+        py.allow_threads(|| state.lock().unwrap().process_frame(&audio_frame))
+            .map_err(PyValueError::new_err)
+    }
+    
+    /// Stop the listening thread, if any, and wait for it to exit
+    fn stop_listener(&mut self, py: Python) {
+        if let Some(listener) = self.listener.take() {
+            // The thread may need the GIL to finish a callback
+            py.allow_threads(|| listener.shutdown());
+        }
+    }
+    
+    /// State shared with threads that drive the detector from Rust
+    pub fn shared_state(&self) -> Arc<Mutex<DetectorState>> {
+        Arc::clone(&self.state)
+    }
+    
+    pub fn listening(&self) -> bool {
+        self.listener
+            .as_ref()
+            .and_then(|l| l.handle.lock().unwrap().as_ref().map(|h| !h.is_finished()))
+            .unwrap_or(false)
+    }
 }
 
 #[pymethods]
 impl WakeWordDetector {
     /// Create a new wake word detector
+    /// 
+    /// `access_key` is the Picovoice console key; when omitted the
+    /// `PV_ACCESS_KEY` environment variable is used. `engine` selects the
+    /// backend: "porcupine" (default) or "openwakeword". When `keyword_path`
+    /// is given the detector is initialized from it and `model_path` right away.
     #[new]
-    fn new(model_path: Option<String>, keyword_path: Option<String>, sensitivity: Option<f32>) -> PyResult<Self> {
+    #[pyo3(signature = (model_path=None, keyword_path=None, sensitivity=None, access_key=None, engine="porcupine"))]
+    fn new(
+        model_path: Option<String>,
+        keyword_path: Option<String>,
+        sensitivity: Option<f32>,
+        access_key: Option<String>,
+        engine: &str,
+    ) -> PyResult<Self> {
         let sensitivity = sensitivity.unwrap_or(0.5);
+        if model_path.is_some() && keyword_path.is_none() {
+            return Err(PyValueError::new_err("model_path requires keyword_path"));
+        }
         
-        Ok(WakeWordDetector {
-            porcupine: Arc::new(Mutex::new(None)),
+        let mut detector = WakeWordDetector {
+            state: Arc::new(Mutex::new(DetectorState::new())),
+            engine_kind: EngineKind::from_name(engine)?,
+            access_key,
+            model_path: None,
+            keyword_source: None,
             sensitivity,
+            sensitivities: Vec::new(),
             is_active: false,
-        })
+            listener: None,
+        };
+        if let Some(keyword_path) = keyword_path {
+            detector.initialize(model_path, KeywordPaths::Single(keyword_path), None, None)?;
+        }
+        Ok(detector)
+    }
+    
+    /// Create and initialize a detector from a `JennaConfig`
+    /// 
+    /// Uses the built-in `keywords` when set, otherwise `keyword_paths`.
+    #[staticmethod]
+    pub fn from_config(config: PyRef<JennaConfig>) -> PyResult<Self> {
+        config.check().map_err(PyValueError::new_err)?;
+        let mut detector = WakeWordDetector::new(
+            None,
+            None,
+            Some(config.sensitivity),
+            config.access_key.clone(),
+            &config.wake_engine,
+        )?;
+        let sensitivities = (!config.sensitivities.is_empty()).then(|| config.sensitivities.clone());
+        if config.keywords.is_empty() {
+            let paths = KeywordPaths::Many(config.keyword_paths.clone());
+            detector.initialize(config.wake_model_path.clone(), paths, sensitivities, None)?;
+        } else {
+            detector.initialize_builtin(config.keywords.clone(), sensitivities, config.wake_model_path.clone())?;
+        }
+        Ok(detector)
     }
     
     /// Initialize the wake word detector with the given model and keyword files
-    fn initialize(&mut self, model_path: String, keyword_path: String) -> PyResult<()> {
-        let model_path = Path::new(&model_path);
-        let keyword_path = Path::new(&keyword_path);
+    /// 
+    /// `keyword_paths` may be a single path or a list. `sensitivities` gives one
+    /// value per keyword (defaulting to the detector sensitivity) and
+    /// `keyword_names` overrides the names derived from the file names.
+    /// Raises `WakeWordLicenseError` if the access key is missing or rejected.
+    #[pyo3(signature = (model_path, keyword_paths, sensitivities=None, keyword_names=None))]
+    fn initialize(
+        &mut self,
+        model_path: Option<String>,
+        keyword_paths: KeywordPaths,
+        sensitivities: Option<Vec<f32>>,
+        keyword_names: Option<Vec<String>>,
+    ) -> PyResult<()> {
+        let keyword_paths = keyword_paths.into_vec();
+        let keyword_names = keyword_names
+            .unwrap_or_else(|| keyword_paths.iter().map(|p| keyword_name_from_path(p)).collect());
         
-        if !model_path.exists() {
-            return Err(PyValueError::new_err(format!("Model file not found: {}", model_path.display())));
+        self.load_keywords(KeywordSource::Paths(keyword_paths), sensitivities, keyword_names, model_path)
+    }
+    
+    /// Swap in new model and keyword files while the detector keeps running
+    /// 
+    /// The new engine is fully built before it replaces the current one, so
+    /// a running listener never misses frames and keeps the old wake words
+    /// if loading fails. Arguments are as for `initialize`.
+    #[pyo3(signature = (model_path, keyword_paths, sensitivities=None, keyword_names=None))]
+    fn reload(
+        &mut self,
+        py: Python,
+        model_path: Option<String>,
+        keyword_paths: KeywordPaths,
+        sensitivities: Option<Vec<f32>>,
+        keyword_names: Option<Vec<String>>,
+    ) -> PyResult<()> {
+        if self.keyword_source.is_none() {
+            return Err(NotInitializedError::new_err("Wake word engine not initialized; call initialize first"));
         }
         
-        if !keyword_path.exists() {
-            return Err(PyValueError::new_err(format!("Keyword file not found: {}", keyword_path.display())));
+        let result = self.initialize(model_path, keyword_paths, sensitivities, keyword_names);
+        if let Err(err) = &result {
+            tracing::warn!("Wake word reload failed, keeping previous keywords: {}", err.value(py));
         }
+        result
+    }
+    
+    /// Initialize the detector from built-in keywords such as "porcupine" or "computer"
+    /// 
+    /// No keyword files are needed; see `get_builtin_keywords` for the list.
+    #[pyo3(signature = (keywords, sensitivities=None, model_path=None))]
+    fn initialize_builtin(
+        &mut self,
+        keywords: Vec<String>,
+        sensitivities: Option<Vec<f32>>,
+        model_path: Option<String>,
+    ) -> PyResult<()> {
+        let builtin = keywords
+            .iter()
+            .map(|name| builtin_keyword(name))
+            .collect::<PyResult<Vec<_>>>()?;
+        let keyword_names = keywords.iter().map(|name| name.to_ascii_lowercase()).collect();
         
-        match PorcupineBuilder::new_with_keyword_paths(
-            model_path.to_str().unwrap(),
-            &[keyword_path.to_str().unwrap()],
-            &[self.sensitivity]
-        ).build() {
-            Ok(porcupine) => {
-                let mut guard = self.porcupine.lock().unwrap();
-                *guard = Some(porcupine);
-                self.is_active = true;
-                Ok(())
-            },
-            Err(err) => Err(PyValueError::new_err(format!("Failed to initialize Porcupine: {}", err)))
-        }
+        self.load_keywords(KeywordSource::Builtin(builtin), sensitivities, keyword_names, model_path)
+    }
+    
+    /// Get the names of the built-in keywords
+    #[staticmethod]
+    fn get_builtin_keywords() -> Vec<String> {
+        BUILTIN_KEYWORDS.iter().map(|(name, _)| name.to_string()).collect()
+    }
+    
+    /// Set the access key used for subsequent initialization
+    fn set_access_key(&mut self, access_key: String) -> PyResult<()> {
+        self.access_key = Some(access_key);
+        Ok(())
     }
     
     /// Process audio frame and check for wake word
-    fn process(&self, audio_frame: Vec<i16>) -> PyResult<bool> {
-        let guard = self.porcupine.lock().unwrap();
-        
-        match &*guard {
-            Some(porcupine) => {
-                if audio_frame.len() != porcupine.frame_length() {
-                    return Err(PyValueError::new_err(
-                        format!(
-                            "Audio frame size ({}) doesn't match required size ({})", 
-                            audio_frame.len(), 
-                            porcupine.frame_length()
-                        )
-                    ));
+    /// 
+    /// When the wake word fires, a context snapshot is captured; it is on
+    /// the detection and can also be read with `get_last_context`.
+    fn process(&mut self, py: Python, audio_frame: Vec<i16>) -> PyResult<bool> {
+        Ok(self.detect(py, audio_frame)?.is_some())
+    }
+    
+    /// Process audio frame and return `(index, name)` of the keyword that fired, if any
+    fn process_keyword(&mut self, py: Python, audio_frame: Vec<i16>) -> PyResult<Option<(usize, String)>> {
+        let detection = self.detect(py, audio_frame)?;
+        Ok(detection.map(|d| (d.index, d.keyword)))
+    }
+    
+    /// Process audio frame and return a `WakeWordDetection` with keyword,
+    /// confidence and timestamps if a keyword fired
+    fn process_detailed(&mut self, py: Python, audio_frame: Vec<i16>) -> PyResult<Option<WakeWordDetection>> {
+        self.detect(py, audio_frame)
+    }
+    
+    /// Run detection over a WAV file and return every detection
+    /// 
+    /// The file is downmixed and resampled to the engine rate. A separate
+    /// engine is built with the current keywords (and `sensitivities`, if
+    /// given), so the live detector state is not disturbed. `stream_time`
+    /// of each detection is its offset into the file in seconds.
+    #[pyo3(signature = (path, sensitivities=None))]
+    fn process_file(&self, py: Python, path: String, sensitivities: Option<Vec<f32>>) -> PyResult<Vec<WakeWordDetection>> {
+        let mut engine = self.build_offline_engine(sensitivities)?;
+        let keyword_names = self.state.lock().unwrap().keyword_names.clone();
+        
+        py.allow_threads(|| detect_file(engine.as_mut(), &keyword_names, Path::new(&path)))
+            .map(|(detections, _)| detections)
+            .map_err(PyValueError::new_err)
+    }
+    
+    /// Evaluate detection accuracy against a labeled directory of WAV files
+    /// 
+    /// `directory` must contain a `positive` subdirectory (each file holds
+    /// the wake word) and a `negative` subdirectory (no file should
+    /// trigger). Returns a dict with false reject/accept counts and rates,
+    /// false accepts per hour of negative audio, and the offending files.
+    #[pyo3(signature = (directory, sensitivities=None))]
+    fn evaluate_directory(&self, py: Python, directory: String, sensitivities: Option<Vec<f32>>) -> PyResult<Py<PyDict>> {
+        let mut engine = self.build_offline_engine(sensitivities.clone())?;
+        let keyword_names = self.state.lock().unwrap().keyword_names.clone();
+        let root = Path::new(&directory);
+        let positives = wav_files(&root.join("positive")).map_err(PyValueError::new_err)?;
+        let negatives = wav_files(&root.join("negative")).map_err(PyValueError::new_err)?;
+        
+        let result = py.allow_threads(|| -> Result<_, String> {
+            let mut false_rejects = Vec::new();
+            let mut false_accepts = Vec::new();
+            let mut false_accept_count = 0usize;
+            let mut negative_seconds = 0.0;
+            
+            for path in &positives {
+                let (detections, _) = detect_file(engine.as_mut(), &keyword_names, path)?;
+                if detections.is_empty() {
+                    false_rejects.push(path.display().to_string());
                 }
-                
-                match porcupine.process(&audio_frame) {
-                    Ok(keyword_index) => Ok(keyword_index >= 0),
-                    Err(err) => Err(PyValueError::new_err(format!("Processing error: {}", err)))
+            }
+            for path in &negatives {
+                let (detections, duration) = detect_file(engine.as_mut(), &keyword_names, path)?;
+                negative_seconds += duration;
+                if !detections.is_empty() {
+                    false_accept_count += detections.len();
+                    false_accepts.push(path.display().to_string());
                 }
-            },
-            None => Err(PyValueError::new_err("Porcupine not initialized"))
+            }
+            Ok((false_rejects, false_accepts, false_accept_count, negative_seconds))
+        }).map_err(PyValueError::new_err)?;
+        let (false_rejects, false_accepts, false_accept_count, negative_seconds) = result;
+        
+        let rate = |count: usize, total: usize| if total > 0 { count as f64 / total as f64 } else { 0.0 };
+        let dict = PyDict::new(py);
+        dict.set_item("positive_files", positives.len())?;
+        dict.set_item("negative_files", negatives.len())?;
+        dict.set_item("false_reject_count", false_rejects.len())?;
+        dict.set_item("false_reject_rate", rate(false_rejects.len(), positives.len()))?;
+        dict.set_item("false_accept_count", false_accept_count)?;
+        dict.set_item("false_accept_rate", rate(false_accepts.len(), negatives.len()))?;
+        dict.set_item(
+            "false_accepts_per_hour",
+            if negative_seconds > 0.0 { false_accept_count as f64 * 3600.0 / negative_seconds } else { 0.0 },
+        )?;
+        dict.set_item("false_rejects", false_rejects)?;
+        dict.set_item("false_accepts", false_accepts)?;
+        dict.set_item("sensitivities", sensitivities.unwrap_or_else(|| self.sensitivities.clone()))?;
+        Ok(dict.into())
+    }
+    
+    /// Get the names of the loaded keywords, in index order
+    fn get_keyword_names(&self) -> Vec<String> {
+        self.state.lock().unwrap().keyword_names.clone()
+    }
+    
+    /// Get the per-keyword sensitivities, in index order
+    fn get_sensitivities(&self) -> Vec<f32> {
+        self.sensitivities.clone()
+    }
+    
+    /// Get the context snapshot captured at the last detection
+    fn get_last_context(&self) -> Option<ContextSnapshot> {
+        self.state.lock().unwrap().last_context.clone()
+    }
+    
+    /// Set the output zone that is currently active
+    fn set_output_zone(&mut self, zone: Option<String>) -> PyResult<()> {
+        self.state.lock().unwrap().output_zone = zone;
+        Ok(())
+    }
+    
+    /// Set the current playback state ("idle", "playing", "paused", ...)
+    fn set_playback_state(&mut self, state: String) -> PyResult<()> {
+        self.state.lock().unwrap().playback_state = state;
+        Ok(())
+    }
+    
+    /// Skip the engine for frames quieter than `threshold_dbfs` (None disables)
+    /// 
+    /// Saves CPU in a silent room. The gate stays open briefly after speech
+    /// and replays the last few skipped frames when it reopens, so the
+    /// engine always sees a contiguous lead-in to the keyword.
+    #[pyo3(signature = (threshold_dbfs=None))]
+    fn set_energy_gate(&mut self, threshold_dbfs: Option<f32>) -> PyResult<()> {
+        if let Some(threshold) = threshold_dbfs {
+            check_gate_threshold(threshold).map_err(PyValueError::new_err)?;
         }
+        
+        let mut state = self.state.lock().unwrap();
+        state.gate_threshold_dbfs = threshold_dbfs;
+        state.gate_hangover = 0;
+        state.gate_preroll.clear();
+        Ok(())
     }
     
-    /// Get the required frame length for audio processing
-    fn get_frame_length(&self) -> PyResult<usize> {
-        let guard = self.porcupine.lock().unwrap();
+    /// Get the energy gate threshold in dBFS, if enabled
+    fn get_energy_gate(&self) -> Option<f32> {
+        self.state.lock().unwrap().gate_threshold_dbfs
+    }
+    
+    /// Get the number of frames that skipped the engine because of the energy gate
+    fn get_gated_frames(&self) -> u64 {
+        self.state.lock().unwrap().frames_gated
+    }
+    
+    /// Enable follow-up mode with a window of `seconds`, or disable it with None
+    /// 
+    /// After a detection the detector is "engaged". When the pipeline calls
+    /// `end_interaction` (the assistant finished responding) a follow-up
+    /// window opens during which `is_addressed` is true and speech can be
+    /// handled without the wake word.
+    #[pyo3(signature = (seconds=None))]
+    fn set_follow_up_window(&mut self, seconds: Option<f64>) -> PyResult<()> {
+        let window = match seconds {
+            Some(seconds) if !seconds.is_finite() || seconds <= 0.0 || seconds > MAX_FOLLOW_UP_WINDOW_S => {
+                return Err(PyValueError::new_err("Follow-up window must be positive and at most a day"));
+            }
+            Some(seconds) => Some(Duration::from_secs_f64(seconds)),
+            None => None,
+        };
+        
+        let mut state = self.state.lock().unwrap();
+        state.follow_up.window = window;
+        if window.is_none() {
+            state.follow_up.state = FollowUpState::Idle;
+        }
+        Ok(())
+    }
+    
+    /// Get the follow-up window in seconds, if enabled
+    fn get_follow_up_window(&self) -> Option<f64> {
+        self.state.lock().unwrap().follow_up.window.map(|w| w.as_secs_f64())
+    }
+    
+    /// Mark the current turn as finished and open the follow-up window
+    fn end_interaction(&mut self) -> PyResult<()> {
+        self.state.lock().unwrap().end_interaction();
+        Ok(())
+    }
+    
+    /// Accept speech heard during the follow-up window as a new turn
+    /// 
+    /// Returns False if the window has already closed, in which case the
+    /// wake word is required again.
+    fn begin_follow_up(&mut self) -> bool {
+        self.state.lock().unwrap().begin_follow_up()
+    }
+    
+    /// Close the follow-up window immediately
+    fn cancel_follow_up(&mut self) -> PyResult<()> {
+        self.state.lock().unwrap().follow_up.state = FollowUpState::Idle;
+        Ok(())
+    }
+    
+    /// Check whether speech is currently addressed to the assistant without the wake word
+    fn is_addressed(&self) -> bool {
+        self.state.lock().unwrap().follow_up.current() != FollowUpState::Idle
+    }
+    
+    /// Get the follow-up state: "idle", "engaged" or "follow_up"
+    fn get_follow_up_state(&self) -> &'static str {
+        match self.state.lock().unwrap().follow_up.current() {
+            FollowUpState::Idle => "idle",
+            FollowUpState::Engaged => "engaged",
+            FollowUpState::Window(_) => "follow_up",
+        }
+    }
+    
+    /// Get the seconds left in the follow-up window (0.0 when it is not open)
+    fn get_follow_up_remaining(&self) -> f64 {
+        match self.state.lock().unwrap().follow_up.current() {
+            FollowUpState::Window(deadline) => deadline.saturating_duration_since(Instant::now()).as_secs_f64(),
+            _ => 0.0,
+        }
+    }
+    
+    /// Get detection statistics as a dict
+    /// 
+    /// Includes total and per-keyword detection counts, the rolling
+    /// detection rate over the last hour, recent detections, and the highest
+    /// engine score of recent frames that did not trigger (engines that do
+    /// not report scores, like Porcupine, leave this empty). Useful to tell
+    /// a detector that never fires from one that fires at random.
+    fn get_stats(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let mut state = self.state.lock().unwrap();
+        let rate = state.stats.detection_rate_per_hour();
+        let stats = &state.stats;
         
-        match &*guard {
-            Some(porcupine) => Ok(porcupine.frame_length()),
-            None => Err(PyValueError::new_err("Porcupine not initialized"))
+        let per_keyword = PyDict::new(py);
+        for (index, name) in state.keyword_names.iter().enumerate() {
+            per_keyword.set_item(name, stats.keyword_counts.get(index).copied().unwrap_or(0))?;
         }
+        
+        let dict = PyDict::new(py);
+        dict.set_item("uptime_seconds", stats.started.elapsed().as_secs_f64())?;
+        dict.set_item("frames_processed", stats.frames_processed)?;
+        dict.set_item("frames_gated", state.frames_gated)?;
+        dict.set_item("total_detections", stats.total_detections)?;
+        dict.set_item("keyword_counts", per_keyword)?;
+        dict.set_item("detections_last_hour", stats.detection_times.len())?;
+        dict.set_item("detection_rate_per_hour", rate)?;
+        let recent = stats
+            .recent_detections
+            .iter()
+            .map(|detection| Py::new(py, detection.clone()))
+            .collect::<PyResult<Vec<_>>>()?;
+        dict.set_item("recent_detections", recent)?;
+        dict.set_item("recent_rejection_scores", stats.rejection_scores.iter().copied().collect::<Vec<_>>())?;
+        dict.set_item("ambient_noise_db", state.ambient_noise_db)?;
+        Ok(dict.into())
+    }
+    
+    /// Reset detection statistics
+    fn reset_stats(&mut self) -> PyResult<()> {
+        let mut state = self.state.lock().unwrap();
+        state.stats = DetectionStats::new();
+        state.frames_gated = 0;
+        Ok(())
+    }
+    
+    /// Get the current ambient noise estimate in dBFS
+    fn get_ambient_noise_db(&self) -> f32 {
+        self.state.lock().unwrap().ambient_noise_db
+    }
+    
+    /// Get the required frame length for audio processing
+    fn get_frame_length(&self) -> PyResult<usize> {
+        let guard = self.state.lock().unwrap();
+        guard.engine().map(|engine| engine.frame_length()).map_err(NotInitializedError::new_err)
     }
     
     /// Get the required sample rate for audio processing
     fn get_sample_rate(&self) -> PyResult<u32> {
-        let guard = self.porcupine.lock().unwrap();
+        let guard = self.state.lock().unwrap();
+        guard.engine().map(|engine| engine.sample_rate()).map_err(NotInitializedError::new_err)
+    }
+    
+    /// Start listening on a capture device in a background thread
+    /// 
+    /// The detector owns the stream, downmixes and resamples to the engine
+    /// rate, frames the audio internally and calls `callback(detection)` with
+    /// a `WakeWordDetection` whenever a keyword fires. Pass `queue.put` as
+    /// the callback to receive detections through a queue instead. `device`
+    /// is an input device name, or None for the default device.
+    /// 
+    /// If the capture callback delivers nothing for `stall_timeout` seconds
+    /// (0 disables the check), an "audio.stalled" event is posted on the
+    /// event bus and, with `restart_on_stall`, the stream is reopened.
+    #[pyo3(signature = (device, callback, stall_timeout=2.0, restart_on_stall=true))]
+    fn start_listening(
+        &mut self,
+        py: Python,
+        device: Option<String>,
+        callback: PyObject,
+        stall_timeout: f32,
+        restart_on_stall: bool,
+    ) -> PyResult<()> {
+        if self.is_listening() {
+            return Err(PyValueError::new_err("Detector is already listening"));
+        }
+        let stall = StallPolicy::new(stall_timeout, restart_on_stall).map_err(PyValueError::new_err)?;
+        // Reap a listener that exited on its own (e.g. the device went away)
+        self.stop_listener(py);
+        self.state.lock().unwrap().engine().map_err(NotInitializedError::new_err)?;
+        if !callback.as_ref(py).is_callable() {
+            return Err(PyValueError::new_err("Callback must be callable"));
+        }
+        
+        let state = self.state.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let handle = thread::Builder::new()
+            .name("jenna-wake-word".to_string())
+            .spawn(move || {
+                panics::supervise(
+                    "Wake word listener",
+                    || run_listener(state.clone(), device.clone(), stall, &callback, thread_stop.clone()),
+                    |_, restart| {
+                        state.clear_poison();
+                        restart.map_or(false, |delay| panics::wait_unless_stopped(&thread_stop, delay))
+                    },
+                );
+            })
+            .map_err(|err| PyValueError::new_err(format!("Failed to start listener thread: {}", err)))?;
         
-        match &*guard {
-            Some(porcupine) => Ok(porcupine.sample_rate()),
-            None => Err(PyValueError::new_err("Porcupine not initialized"))
+        let listener = Arc::new(Listener { stop, handle: Mutex::new(Some(handle)) });
+        lifecycle::register(&listener);
+        self.listener = Some(listener);
+        Ok(())
+    }
+    
+    /// Stop listening and close the capture stream
+    fn stop_listening(&mut self, py: Python) -> PyResult<()> {
+        self.stop_listener(py);
+        Ok(())
+    }
+    
+    /// Check whether the background listener is running
+    fn is_listening(&self) -> bool {
+        self.listening()
+    }
+    
+    /// Get the name of the selected engine backend
+    fn get_engine(&self) -> &'static str {
+        match self.engine_kind {
+            EngineKind::Porcupine => "porcupine",
+            EngineKind::OpenWakeWord => "openwakeword",
         }
     }
     
@@ -105,20 +1354,57 @@ impl WakeWordDetector {
     }
     
     /// Set the sensitivity of the wake word detector
-    fn set_sensitivity(&mut self, sensitivity: f32) -> PyResult<()> {
+    /// 
+    /// Applies to all loaded keywords and to keywords loaded later without
+    /// explicit sensitivities. If keywords are loaded the engine is rebuilt
+    /// so the new value takes effect immediately.
+    pub fn set_sensitivity(&mut self, sensitivity: f32) -> PyResult<()> {
         if sensitivity < 0.0 || sensitivity > 1.0 {
             return Err(PyValueError::new_err("Sensitivity must be between 0.0 and 1.0"));
         }
         
         self.sensitivity = sensitivity;
+        if self.keyword_source.is_some() {
+            let count = self.sensitivities.len();
+            self.reinitialize(Some(vec![sensitivity; count]))?;
+        }
         Ok(())
     }
     
-    /// Release resources
-    fn release(&mut self) -> PyResult<()> {
-        let mut guard = self.porcupine.lock().unwrap();
-        *guard = None;
+    /// Rebuild the engine with the current keyword configuration
+    /// 
+    /// `sensitivities` optionally replaces the per-keyword values. The new
+    /// engine is built before it replaces the old one, so a running listener
+    /// keeps detecting with the previous settings if the rebuild fails.
+    #[pyo3(signature = (sensitivities=None))]
+    fn reinitialize(&mut self, sensitivities: Option<Vec<f32>>) -> PyResult<()> {
+        let source = self
+            .keyword_source
+            .clone()
+            .ok_or_else(|| NotInitializedError::new_err("Wake word engine not initialized"))?;
+        let sensitivities = sensitivities.unwrap_or_else(|| self.sensitivities.clone());
+        let keyword_names = self.state.lock().unwrap().keyword_names.clone();
+        let model_path = self.model_path.clone();
+        
+        self.load_keywords(source, Some(sensitivities), keyword_names, model_path)
+    }
+    
+    /// Stop listening and free the engine; safe to call more than once
+    fn release(&mut self, py: Python) -> PyResult<()> {
+        self.stop_listener(py);
+        let mut guard = self.state.lock().unwrap();
+        guard.engine = None;
         self.is_active = false;
         Ok(())
     }
+}
+
+impl Drop for WakeWordDetector {
+    fn drop(&mut self) {
+        // Signal without joining (see `VoicePipeline`'s Drop); the listener
+        // closes its stream and drops its reference to the engine on exit
+        if let Some(listener) = &self.listener {
+            listener.stop.store(true, Ordering::Relaxed);
+        }
+    }
 }
\ No newline at end of file
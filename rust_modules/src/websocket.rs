@@ -0,0 +1,663 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio_tungstenite::tungstenite::Message;
+
+use jenna_core::audio_class::AudioClass;
+use jenna_core::protocol::{ClientMessage, Codec, ServerMessage};
+
+use crate::assistant::{Assistant, Phase};
+use crate::audio::MonoResampler;
+use crate::errors::NotInitializedError;
+use crate::events::{self, Event, EventData};
+use crate::lifecycle::{self, Resource};
+use crate::pipeline::{DictationRequest, Endpointing, FrameEnv, FrameProcessor, PipelineEvent};
+use crate::speech::{SharedEngine, SpeechRecognizer, TextToSpeech};
+use crate::wake_word::{DetectorState, WakeWordDetector};
+
+/// Default port for the audio streaming server
+pub const DEFAULT_PORT: u16 = 8765;
+
+/// Samples per binary message when streaming synthesized speech
+const TTS_CHUNK_SAMPLES: usize = 4096;
+
+/// Largest Opus frame: 120 ms at 48 kHz
+const MAX_OPUS_FRAME: usize = 5760;
+
+/// Longest the server waits for in-flight work when stopping
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A text message for the client
+fn text_message(message: ServerMessage) -> Message {
+    // Server messages only contain strings, numbers and lists
+    Message::Text(serde_json::to_string(&message).unwrap_or_default())
+}
+
+fn error_message(message: impl Into<String>) -> Message {
+    text_message(ServerMessage::Error { message: message.into() })
+}
+
+/// JSON form of a pipeline event
+fn event_message(event: &PipelineEvent) -> Message {
+    text_message(ServerMessage::Event {
+        kind: event.kind.clone(),
+        text: event.text.clone(),
+        keyword: event.keyword.clone(),
+        confidence: event.confidence,
+        timestamp: event.timestamp,
+    })
+}
+
+/// Decodes a client's binary messages into mono audio at the engine rate
+struct AudioDecoder {
+    codec: Codec,
+    channels: usize,
+    opus: Option<opus::Decoder>,
+    resampler: MonoResampler,
+}
+
+impl AudioDecoder {
+    fn new(codec: Codec, sample_rate: u32, channels: u32, engine_rate: u32) -> Result<Self, String> {
+        if sample_rate == 0 || !(1..=2).contains(&channels) {
+            return Err(format!("Unsupported audio format: {} Hz, {} channels", sample_rate, channels));
+        }
+        let opus = match codec {
+            Codec::Opus => {
+                let layout = if channels == 2 { opus::Channels::Stereo } else { opus::Channels::Mono };
+                Some(opus::Decoder::new(sample_rate, layout).map_err(|err| format!("Invalid Opus stream: {}", err))?)
+            }
+            Codec::Pcm => None,
+        };
+        Ok(AudioDecoder {
+            codec,
+            channels: channels as usize,
+            opus,
+            resampler: MonoResampler::new(sample_rate, engine_rate).map_err(|err| err.to_string())?,
+        })
+    }
+    
+    fn decode(&mut self, data: &[u8]) -> Result<Vec<f32>, String> {
+        let interleaved = match (self.codec, self.opus.as_mut()) {
+            (Codec::Opus, Some(decoder)) => {
+                let mut output = vec![0.0f32; MAX_OPUS_FRAME * self.channels];
+                let samples = decoder
+                    .decode_float(data, &mut output, false)
+                    .map_err(|err| format!("Opus decode failed: {}", err))?;
+                output.truncate(samples * self.channels);
+                output
+            }
+            _ => data
+                .chunks_exact(2)
+                .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0)
+                .collect(),
+        };
+        let mono: Vec<f32> = match self.channels {
+            1 => interleaved,
+            channels => interleaved
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+                .collect(),
+        };
+        self.resampler.push(&mono).map_err(|err| err.to_string())
+    }
+}
+
+/// Pipeline environment of one streaming client
+/// 
+/// The client plays TTS audio itself, so playback state comes from its
+/// `playback` messages and barge-in is reported back as an "interrupt"
+/// message rather than by clearing a local queue.
+struct ClientEnv {
+    client: u64,
+    outgoing: UnboundedSender<Message>,
+    trigger: Arc<AtomicBool>,
+    playing: Arc<AtomicBool>,
+    callback: Option<Arc<PyObject>>,
+}
+
+impl FrameEnv for ClientEnv {
+    fn playing(&mut self) -> bool {
+        self.playing.load(Ordering::Relaxed)
+    }
+    
+    fn interrupt_playback(&mut self) {
+        self.playing.store(false, Ordering::Relaxed);
+        let _ = self.outgoing.send(text_message(ServerMessage::Interrupt));
+    }
+    
+    fn take_trigger(&mut self) -> bool {
+        self.trigger.swap(false, Ordering::Relaxed)
+    }
+    
+    fn take_dictation_request(&mut self) -> Option<DictationRequest> {
+        None
+    }
+    
+    fn begin_follow_up(&mut self, detector: &mut DetectorState) -> bool {
+        detector.begin_follow_up()
+    }
+    
+    fn assistant_phase(&mut self) -> Option<Phase> {
+        None
+    }
+    
+    fn assistant(&self) -> Option<&Assistant> {
+        None
+    }
+    
+    fn set_listening(&mut self, _listening: bool) {}
+    
+    fn set_dictating(&mut self, _dictating: bool) {}
+    
+    fn vad_boundary(&mut self, _kind: &'static str) {}
+    
+    fn audio_class_changed(&mut self, class: AudioClass) {
+        let source = format!("websocket:{}", self.client);
+        events::post(Event::new("audio.class", &source, EventData::Text(class.name().to_string())));
+    }
+    
+    fn presence_changed(&mut self, present: bool) {
+        let source = format!("websocket:{}", self.client);
+        let state = if present { "present" } else { "absent" };
+        events::post(Event::new("presence.changed", &source, EventData::Text(state.to_string())));
+    }
+    
+    fn voice_activity(&mut self, _speaking: bool, _seconds: f32) {}
+    
+    fn emit(&mut self, event: PipelineEvent) {
+        let _ = self.outgoing.send(event_message(&event));
+        events::post(Event::new("websocket.event", &format!("websocket:{}", self.client), EventData::Pipeline(event.clone())));
+        if let Some(callback) = &self.callback {
+            Python::with_gil(|py| {
+                if let Err(err) = callback.call1(py, (self.client, event)) {
+                    err.print(py);
+                }
+            });
+        }
+    }
+}
+
+/// Feed a client's audio through wake word detection and recognition until its stream ends
+fn run_client_pipeline(
+    input: mpsc::Receiver<Vec<u8>>,
+    mut decoder: AudioDecoder,
+    mut processor: FrameProcessor,
+    mut env: ClientEnv,
+) {
+    let frame_length = processor.frame_length;
+    let mut pending: Vec<i16> = Vec::with_capacity(frame_length * 2);
+    for data in input {
+        let captured = Instant::now();
+        let audio = match decoder.decode(&data) {
+            Ok(audio) => audio,
+            Err(err) => {
+                let _ = env.outgoing.send(error_message(err));
+                continue;
+            }
+        };
+        pending.extend(audio.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+        
+        while pending.len() >= frame_length {
+            let frame: Vec<i16> = pending.drain(..frame_length).collect();
+            if let Err(err) = processor.process(&frame, captured, &mut env) {
+                tracing::error!(client = env.client, "Streaming pipeline stopped: {}", err);
+                env.emit(PipelineEvent::error(err));
+                return;
+            }
+        }
+    }
+}
+
+/// An audio stream in progress: the client's pipeline thread and its flags
+struct Stream {
+    input: mpsc::Sender<Vec<u8>>,
+    trigger: Arc<AtomicBool>,
+    playing: Arc<AtomicBool>,
+}
+
+/// State shared by the server object, its runtime and the client pipeline threads
+struct Shared {
+    detector: Arc<Mutex<DetectorState>>,
+    recognizer: SharedEngine,
+    tts: Option<Py<TextToSpeech>>,
+    endpointing: Endpointing,
+    callback: Option<Arc<PyObject>>,
+    clients: Mutex<HashMap<u64, UnboundedSender<Message>>>,
+    /// Client currently streaming audio; the engines serve one stream at a time
+    streaming: Mutex<Option<u64>>,
+    next_client: AtomicU64,
+    threads: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Shared {
+    /// Claim the engines for `client` and start its pipeline thread
+    fn start_stream(&self, client: u64, outgoing: &UnboundedSender<Message>, codec: Codec, sample_rate: u32, channels: u32) -> Result<Stream, String> {
+        let mut streaming = self.streaming.lock().unwrap();
+        if streaming.map_or(false, |owner| owner != client) {
+            return Err("Another client is already streaming audio".to_string());
+        }
+        let processor = FrameProcessor::new(self.detector.clone(), self.recognizer.clone(), self.endpointing, true)?;
+        let decoder = AudioDecoder::new(codec, sample_rate, channels, processor.sample_rate)?;
+        let (input, receiver) = mpsc::channel();
+        let trigger = Arc::new(AtomicBool::new(false));
+        let playing = Arc::new(AtomicBool::new(false));
+        let env = ClientEnv {
+            client,
+            outgoing: outgoing.clone(),
+            trigger: trigger.clone(),
+            playing: playing.clone(),
+            callback: self.callback.clone(),
+        };
+        
+        let handle = thread::Builder::new()
+            .name("jenna-ws-pipeline".to_string())
+            .spawn(move || run_client_pipeline(receiver, decoder, processor, env))
+            .map_err(|err| format!("Failed to start pipeline thread: {}", err))?;
+        let mut threads = self.threads.lock().unwrap();
+        threads.retain(|handle| !handle.is_finished());
+        threads.push(handle);
+        
+        *streaming = Some(client);
+        tracing::debug!(client, sample_rate, channels, ?codec, "Client started streaming");
+        Ok(Stream { input, trigger, playing })
+    }
+    
+    /// Release the engines if `client` holds them
+    fn end_stream(&self, client: u64) {
+        let mut streaming = self.streaming.lock().unwrap();
+        if *streaming == Some(client) {
+            *streaming = None;
+        }
+    }
+    
+    /// Synthesize `text` and queue it for the given clients as PCM messages
+    fn say(&self, text: &str, clients: &[UnboundedSender<Message>]) -> Result<(), String> {
+        let tts = self.tts.as_ref().ok_or_else(|| "No text-to-speech engine configured".to_string())?;
+        let (pcm, sample_rate) = Python::with_gil(|py| tts.borrow(py).render(py, text).map_err(|err| err.to_string()))?;
+        
+        let mut messages = vec![text_message(ServerMessage::TtsStart {
+            text: text.to_string(),
+            sample_rate,
+            channels: 1,
+            format: "pcm_s16le".to_string(),
+        })];
+        messages.extend(pcm.chunks(TTS_CHUNK_SAMPLES).map(|chunk| Message::Binary(chunk.iter().flat_map(|s| s.to_le_bytes()).collect())));
+        messages.push(text_message(ServerMessage::TtsEnd));
+        for client in clients {
+            for message in &messages {
+                let _ = client.send(message.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serve one WebSocket client until it disconnects or the runtime stops
+async fn handle_client(socket: tokio::net::TcpStream, peer: SocketAddr, shared: Arc<Shared>) {
+    let websocket = match tokio_tungstenite::accept_async(socket).await {
+        Ok(websocket) => websocket,
+        Err(err) => {
+            tracing::debug!(%peer, error = %err, "WebSocket handshake failed");
+            return;
+        }
+    };
+    let client = shared.next_client.fetch_add(1, Ordering::Relaxed);
+    let (mut sink, mut source) = websocket.split();
+    let (outgoing, mut queued) = unbounded_channel::<Message>();
+    shared.clients.lock().unwrap().insert(client, outgoing.clone());
+    tracing::debug!(client, %peer, "WebSocket client connected");
+    
+    let writer = tokio::spawn(async move {
+        while let Some(message) = queued.recv().await {
+            if sink.send(message).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+    
+    let (frame_length, sample_rate) = shared.detector.lock().unwrap().frame_format().unwrap_or_default();
+    let _ = outgoing.send(text_message(ServerMessage::Ready {
+        client,
+        sample_rate,
+        frame_length,
+        tts: shared.tts.is_some(),
+    }));
+    
+    let mut stream: Option<Stream> = None;
+    while let Some(message) = source.next().await {
+        let message = match message {
+            Ok(message) => message,
+            Err(err) => {
+                tracing::debug!(client, error = %err, "WebSocket read failed");
+                break;
+            }
+        };
+        match message {
+            Message::Binary(data) => match &stream {
+                Some(active) => {
+                    if active.input.send(data).is_err() {
+                        // The pipeline thread stopped after an error it already reported
+                        stream = None;
+                        shared.end_stream(client);
+                    }
+                }
+                None => {
+                    let _ = outgoing.send(error_message("Send a start message before streaming audio"));
+                }
+            },
+            Message::Text(text) => match serde_json::from_str::<ClientMessage>(&text) {
+                Ok(ClientMessage::Start { sample_rate, channels, codec }) => {
+                    stream = None;
+                    shared.end_stream(client);
+                    match shared.start_stream(client, &outgoing, codec, sample_rate, channels) {
+                        Ok(started) => {
+                            stream = Some(started);
+                            let _ = outgoing.send(text_message(ServerMessage::Started));
+                        }
+                        Err(err) => {
+                            let _ = outgoing.send(error_message(err));
+                        }
+                    }
+                }
+                Ok(ClientMessage::Stop) => {
+                    stream = None;
+                    shared.end_stream(client);
+                    let _ = outgoing.send(text_message(ServerMessage::Stopped));
+                }
+                Ok(ClientMessage::Trigger) => match &stream {
+                    Some(active) => active.trigger.store(true, Ordering::Relaxed),
+                    None => {
+                        let _ = outgoing.send(error_message("Not streaming"));
+                    }
+                },
+                Ok(ClientMessage::Playback { playing }) => {
+                    if let Some(active) = &stream {
+                        active.playing.store(playing, Ordering::Relaxed);
+                    }
+                }
+                Ok(ClientMessage::Say { text }) => {
+                    let shared = shared.clone();
+                    let outgoing = outgoing.clone();
+                    // Synthesis takes the GIL and a while; keep the socket responsive
+                    tokio::task::spawn_blocking(move || {
+                        if let Err(err) = shared.say(&text, std::slice::from_ref(&outgoing)) {
+                            let _ = outgoing.send(error_message(err));
+                        }
+                    });
+                }
+                Ok(ClientMessage::Ping) => {
+                    let _ = outgoing.send(text_message(ServerMessage::Pong));
+                }
+                Err(err) => {
+                    let _ = outgoing.send(error_message(format!("Invalid message: {}", err)));
+                }
+            },
+            Message::Close(_) => break,
+            // Pings are answered by tungstenite
+            _ => {}
+        }
+    }
+    
+    drop(stream);
+    shared.end_stream(client);
+    shared.clients.lock().unwrap().remove(&client);
+    drop(outgoing);
+    let _ = writer.await;
+    tracing::debug!(client, "WebSocket client disconnected");
+}
+
+/// A running server: its runtime and the state the connections share
+struct Server {
+    address: SocketAddr,
+    runtime: Mutex<Option<Runtime>>,
+    shared: Arc<Shared>,
+}
+
+impl Resource for Server {
+    /// Close every connection and wait for the client pipeline threads
+    fn shutdown(&self) {
+        if let Some(runtime) = self.runtime.lock().unwrap().take() {
+            // Dropping the connection tasks closes the pipeline threads' inputs
+            runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+        }
+        self.shared.clients.lock().unwrap().clear();
+        *self.shared.streaming.lock().unwrap() = None;
+        let threads = std::mem::take(&mut *self.shared.threads.lock().unwrap());
+        lifecycle::join_threads(threads);
+    }
+}
+
+/// WebSocket server that runs thin clients' audio through the voice pipeline
+/// 
+/// Browser or satellite clients connect over WebSocket and exchange JSON
+/// text messages and binary audio:
+/// 
+/// - The server greets each client with `{"type": "ready", "client": id, ...}`.
+/// - `{"type": "start", "sample_rate": 16000, "channels": 1, "codec": "pcm"}`
+///   starts a stream. Binary messages that follow carry 16-bit little-endian
+///   PCM, or one raw Opus packet each with `"codec": "opus"`. Audio is
+///   resampled to the engine rate and runs through wake word detection,
+///   VAD and recognition exactly like a local `VoicePipeline`; its events
+///   come back as `{"type": "event", "kind": ..., "text": ...}`.
+/// - `{"type": "stop"}` ends the stream, `{"type": "trigger"}` starts a turn
+///   without the wake word, and `{"type": "playback", "playing": bool}`
+///   tells the server the client is playing audio, so the wake word can
+///   interrupt it (`{"type": "interrupt"}` is sent back).
+/// - `{"type": "say", "text": ...}`, like `say()` from Python, streams TTS
+///   audio as `tts_start`, binary PCM messages and `tts_end`.
+/// 
+/// `callback(client, event)` receives each `PipelineEvent` with the id of
+/// the client that produced it, so the application can answer with `say`.
+/// The detector and recognizer are shared, so only one client streams at a
+/// time, and they should not be used elsewhere while the server runs.
+#[pyclass]
+pub struct WebSocketServer {
+    detector: Arc<Mutex<DetectorState>>,
+    recognizer: SharedEngine,
+    tts: Option<Py<TextToSpeech>>,
+    host: String,
+    port: u16,
+    endpointing: Endpointing,
+    server: Option<Arc<Server>>,
+}
+
+impl WebSocketServer {
+    fn running(&self) -> Option<&Arc<Server>> {
+        self.server.as_ref().filter(|server| server.runtime.lock().unwrap().is_some())
+    }
+}
+
+#[pymethods]
+impl WebSocketServer {
+    /// Create a server from an initialized detector and recognizer
+    /// 
+    /// The endpointing arguments match `VoicePipeline`. Without `tts`,
+    /// `say` requests are answered with an error.
+    #[new]
+    #[pyo3(signature = (
+        detector,
+        recognizer,
+        tts=None,
+        host="0.0.0.0",
+        port=DEFAULT_PORT,
+        vad_threshold_db=12.0,
+        silence_timeout=0.8,
+        no_speech_timeout=5.0,
+        max_utterance=15.0
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        detector: PyRef<WakeWordDetector>,
+        recognizer: PyRef<SpeechRecognizer>,
+        tts: Option<Py<TextToSpeech>>,
+        host: &str,
+        port: u16,
+        vad_threshold_db: f32,
+        silence_timeout: f32,
+        no_speech_timeout: f32,
+        max_utterance: f32,
+    ) -> PyResult<Self> {
+        let state = detector.shared_state();
+        let (_, engine_rate) = state.lock().unwrap().frame_format().map_err(NotInitializedError::new_err)?;
+        let (engine, recognizer_rate) = recognizer.shared_engine()?;
+        if engine_rate != recognizer_rate {
+            return Err(PyValueError::new_err(format!(
+                "Recognizer sample rate ({} Hz) must match the wake word engine ({} Hz)",
+                recognizer_rate, engine_rate
+            )));
+        }
+        if [silence_timeout, no_speech_timeout, max_utterance].iter().any(|&t| t <= 0.0) {
+            return Err(PyValueError::new_err("Timeouts must be positive"));
+        }
+        
+        Ok(WebSocketServer {
+            detector: state,
+            recognizer: engine,
+            tts,
+            host: host.to_string(),
+            port,
+            endpointing: Endpointing { vad_threshold_db, silence_timeout, no_speech_timeout, max_utterance },
+            server: None,
+        })
+    }
+    
+    /// Bind the port and start accepting clients, returning the bound port
+    /// 
+    /// `callback(client, event)` is called from a worker thread for every
+    /// pipeline event. Port 0 picks a free port.
+    #[pyo3(signature = (callback=None))]
+    fn start(&mut self, py: Python, callback: Option<PyObject>) -> PyResult<u16> {
+        if self.running().is_some() {
+            return Err(PyValueError::new_err("WebSocket server is already running"));
+        }
+        let address = format!("{}:{}", self.host, self.port);
+        let listener = std::net::TcpListener::bind(&address)
+            .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+            .map_err(|err| PyValueError::new_err(format!("Failed to bind {}: {}", address, err)))?;
+        let local = listener.local_addr().map_err(|err| PyValueError::new_err(err.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("jenna-ws")
+            .enable_all()
+            .build()
+            .map_err(|err| PyValueError::new_err(format!("Failed to start WebSocket runtime: {}", err)))?;
+        
+        let shared = Arc::new(Shared {
+            detector: self.detector.clone(),
+            recognizer: self.recognizer.clone(),
+            tts: self.tts.as_ref().map(|tts| tts.clone_ref(py)),
+            endpointing: self.endpointing,
+            callback: callback.map(Arc::new),
+            clients: Mutex::new(HashMap::new()),
+            streaming: Mutex::new(None),
+            next_client: AtomicU64::new(1),
+            threads: Mutex::new(Vec::new()),
+        });
+        let listener = {
+            let _context = runtime.enter();
+            tokio::net::TcpListener::from_std(listener).map_err(|err| PyValueError::new_err(err.to_string()))?
+        };
+        let accepting = shared.clone();
+        runtime.spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((socket, peer)) => {
+                        let _ = socket.set_nodelay(true);
+                        tokio::spawn(handle_client(socket, peer, accepting.clone()));
+                    }
+                    Err(err) => {
+                        tracing::warn!(error = %err, "WebSocket accept failed");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                }
+            }
+        });
+        
+        tracing::info!(address = %local, "WebSocket server listening");
+        let server = Arc::new(Server { address: local, runtime: Mutex::new(Some(runtime)), shared });
+        lifecycle::register(&server);
+        self.server = Some(server);
+        Ok(local.port())
+    }
+    
+    /// Disconnect every client and stop listening
+    fn stop(&mut self, py: Python) {
+        if let Some(server) = self.server.take() {
+            // Pipeline threads may need the GIL to finish a callback
+            py.allow_threads(|| server.shutdown());
+        }
+    }
+    
+    fn is_running(&self) -> bool {
+        self.running().is_some()
+    }
+    
+    /// Port the server is bound to, or the configured port before `start`
+    #[getter]
+    fn get_port(&self) -> u16 {
+        self.running().map_or(self.port, |server| server.address.port())
+    }
+    
+    /// Ids of the connected clients
+    #[getter]
+    fn get_clients(&self) -> Vec<u64> {
+        let mut clients: Vec<u64> = self
+            .running()
+            .map(|server| server.shared.clients.lock().unwrap().keys().copied().collect())
+            .unwrap_or_default();
+        clients.sort_unstable();
+        clients
+    }
+    
+    /// Synthesize `text` and stream it to `client`, or to every client when None
+    /// 
+    /// Returns the number of clients the audio was queued for.
+    #[pyo3(signature = (text, client=None))]
+    fn say(&self, py: Python, text: &str, client: Option<u64>) -> PyResult<usize> {
+        let server = self.running().ok_or_else(|| PyValueError::new_err("WebSocket server is not running"))?;
+        let targets: Vec<UnboundedSender<Message>> = {
+            let clients = server.shared.clients.lock().unwrap();
+            match client {
+                Some(id) => vec![clients
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| PyValueError::new_err(format!("No client with id {}", id)))?],
+                None => clients.values().cloned().collect(),
+            }
+        };
+        let shared = server.shared.clone();
+        py.allow_threads(|| shared.say(text, &targets)).map_err(PyValueError::new_err)?;
+        Ok(targets.len())
+    }
+    
+    fn __repr__(&self) -> String {
+        format!(
+            "WebSocketServer(host='{}', port={}, running={}, clients={})",
+            self.host,
+            self.get_port(),
+            self.is_running(),
+            self.get_clients().len()
+        )
+    }
+}
+
+impl Drop for WebSocketServer {
+    fn drop(&mut self) {
+        // Shut the runtime down in the background (see `VoicePipeline`'s Drop):
+        // a pipeline thread may be waiting for the GIL to deliver a callback
+        if let Some(server) = self.server.take() {
+            if let Some(runtime) = server.runtime.lock().unwrap().take() {
+                runtime.shutdown_background();
+            }
+        }
+    }
+}
@@ -0,0 +1,751 @@
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::audio::{resample_mono, MonoResampler};
+use crate::lifecycle::{self, Resource};
+use crate::speech::{SharedEngine, SpeechRecognizer, TextToSpeech};
+use crate::wake_word::{DetectorState, WakeWordDetector};
+
+/// Protocol version reported in event headers
+const PROTOCOL_VERSION: &str = "1.5.2";
+
+/// Default port used by Wyoming services
+pub const DEFAULT_PORT: u16 = 10300;
+
+/// Sample rate expected by `SttEngine::transcribe`
+const RECOGNITION_SAMPLE_RATE: u32 = 16000;
+
+/// Samples per `audio-chunk` event when streaming synthesized speech
+const SAMPLES_PER_CHUNK: usize = 1024;
+
+/// Longest header or data line accepted from a client
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+/// Largest payload accepted in a single event
+const MAX_PAYLOAD_BYTES: usize = 16 * 1024 * 1024;
+
+/// Longest recording buffered for one transcription, in seconds
+const MAX_TRANSCRIBE_SECONDS: usize = 300;
+
+/// How often the accept loop checks the stop flag
+const ACCEPT_POLL: Duration = Duration::from_millis(100);
+
+/// Header line of a Wyoming event
+/// 
+/// Events are a JSON line, optionally followed by `data_length` bytes of
+/// additional JSON data (merged into `data`) and `payload_length` bytes of
+/// binary payload.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Header {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<Map<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    payload_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+}
+
+/// A decoded Wyoming event
+#[derive(Debug, Default)]
+struct WyomingEvent {
+    kind: String,
+    data: Map<String, Value>,
+    payload: Vec<u8>,
+}
+
+impl WyomingEvent {
+    fn new(kind: &str, data: Value) -> Self {
+        let data = match data {
+            Value::Object(map) => map,
+            _ => Map::new(),
+        };
+        WyomingEvent { kind: kind.to_string(), data, payload: Vec::new() }
+    }
+    
+    fn with_payload(mut self, payload: Vec<u8>) -> Self {
+        self.payload = payload;
+        self
+    }
+    
+    fn error(text: impl Into<String>) -> Self {
+        WyomingEvent::new("error", json!({ "text": text.into() }))
+    }
+    
+    fn str_field(&self, key: &str) -> Option<&str> {
+        self.data.get(key).and_then(Value::as_str)
+    }
+    
+    fn u32_field(&self, key: &str) -> Option<u32> {
+        self.data.get(key).and_then(Value::as_u64).and_then(|v| u32::try_from(v).ok())
+    }
+}
+
+/// Read one event, returning None when the client closed the connection
+fn read_event(reader: &mut impl BufRead) -> io::Result<Option<WyomingEvent>> {
+    let mut line = Vec::new();
+    loop {
+        let read = reader.by_ref().take((MAX_HEADER_BYTES + 1) as u64).read_until(b'\n', &mut line)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        if line.len() > MAX_HEADER_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Event header too long"));
+        }
+        // Tolerate blank keep-alive lines
+        if line.iter().any(|b| !b.is_ascii_whitespace()) {
+            break;
+        }
+        line.clear();
+    }
+    
+    let header: Header = serde_json::from_slice(&line)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid event header: {}", err)))?;
+    let mut data = header.data.unwrap_or_default();
+    if let Some(length) = header.data_length.filter(|&l| l > 0) {
+        if length > MAX_HEADER_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Event data too long"));
+        }
+        let mut extra = vec![0u8; length];
+        reader.read_exact(&mut extra)?;
+        let extra: Map<String, Value> = serde_json::from_slice(&extra)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid event data: {}", err)))?;
+        data.extend(extra);
+    }
+    let mut payload = Vec::new();
+    if let Some(length) = header.payload_length.filter(|&l| l > 0) {
+        if length > MAX_PAYLOAD_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Event payload too large"));
+        }
+        payload.resize(length, 0);
+        reader.read_exact(&mut payload)?;
+    }
+    Ok(Some(WyomingEvent { kind: header.kind, data, payload }))
+}
+
+/// Write one event with its data inline in the header and the payload after it
+fn write_event(writer: &mut impl Write, event: &WyomingEvent) -> io::Result<()> {
+    let header = Header {
+        kind: event.kind.clone(),
+        data: (!event.data.is_empty()).then(|| event.data.clone()),
+        data_length: None,
+        payload_length: (!event.payload.is_empty()).then_some(event.payload.len()),
+        version: Some(PROTOCOL_VERSION.to_string()),
+    };
+    let mut line = serde_json::to_vec(&header).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.write_all(&event.payload)?;
+    writer.flush()
+}
+
+/// Format of the PCM carried by `audio-start` and `audio-chunk` events
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AudioFormat {
+    rate: u32,
+    width: u32,
+    channels: u32,
+}
+
+impl AudioFormat {
+    fn from_event(event: &WyomingEvent) -> Result<Self, String> {
+        let format = AudioFormat {
+            rate: event.u32_field("rate").unwrap_or(RECOGNITION_SAMPLE_RATE),
+            width: event.u32_field("width").unwrap_or(2),
+            channels: event.u32_field("channels").unwrap_or(1),
+        };
+        if format.rate == 0 || format.channels == 0 || ![1, 2, 4].contains(&format.width) {
+            return Err(format!(
+                "Unsupported audio format: {} Hz, {} bytes per sample, {} channels",
+                format.rate, format.width, format.channels
+            ));
+        }
+        Ok(format)
+    }
+    
+    /// Decode little-endian PCM and mix it down to mono in [-1, 1]
+    fn to_mono(&self, payload: &[u8]) -> Vec<f32> {
+        let width = self.width as usize;
+        let channels = self.channels as usize;
+        let samples = payload.chunks_exact(width).map(|bytes| match bytes {
+            [b] => (*b as f32 - 128.0) / 128.0,
+            [a, b] => i16::from_le_bytes([*a, *b]) as f32 / 32768.0,
+            [a, b, c, d] => i32::from_le_bytes([*a, *b, *c, *d]) as f32 / 2_147_483_648.0,
+            _ => 0.0,
+        });
+        if channels == 1 {
+            return samples.collect();
+        }
+        let interleaved: Vec<f32> = samples.collect();
+        interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    }
+}
+
+/// Engines exposed by a running server, resolved when it starts
+struct Services {
+    recognizer: Option<SharedEngine>,
+    tts: Option<Py<TextToSpeech>>,
+    detector: Option<Arc<Mutex<DetectorState>>>,
+    /// Response to `describe`, built once at startup
+    info: Value,
+}
+
+fn attribution() -> Value {
+    json!({ "name": "Jenna", "url": "https://github.com/Mainali1/Jenna-VA" })
+}
+
+/// What a connection does with the audio it receives
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// No `transcribe` or `detect` seen yet
+    Idle,
+    Transcribe,
+    Detect,
+}
+
+/// Per-connection wake word stream: resampled audio waiting to fill a frame
+struct WakeStream {
+    resampler: Option<(u32, MonoResampler)>,
+    pending: Vec<i16>,
+    names: Option<Vec<String>>,
+    detected: bool,
+}
+
+/// State of one client connection
+struct Connection<'a> {
+    services: &'a Services,
+    mode: Mode,
+    format: Option<AudioFormat>,
+    /// Mono audio at `format.rate` buffered for transcription
+    recording: Vec<f32>,
+    wake: WakeStream,
+}
+
+impl<'a> Connection<'a> {
+    fn new(services: &'a Services) -> Self {
+        Connection {
+            services,
+            mode: Mode::Idle,
+            format: None,
+            recording: Vec::new(),
+            wake: WakeStream { resampler: None, pending: Vec::new(), names: None, detected: false },
+        }
+    }
+    
+    /// Mode used for audio when the client did not send `transcribe` or `detect`
+    /// 
+    /// Speech-to-text wins when both engines are available, matching the
+    /// single-service Wyoming servers Home Assistant usually talks to.
+    fn audio_mode(&self) -> Mode {
+        match self.mode {
+            Mode::Idle if self.services.recognizer.is_some() => Mode::Transcribe,
+            Mode::Idle if self.services.detector.is_some() => Mode::Detect,
+            mode => mode,
+        }
+    }
+    
+    /// Handle one event, writing any responses
+    fn handle(&mut self, event: WyomingEvent, out: &mut impl Write) -> io::Result<()> {
+        let responses = match event.kind.as_str() {
+            "describe" => vec![WyomingEvent::new("info", self.services.info.clone())],
+            "ping" => vec![WyomingEvent::new("pong", json!({ "text": event.str_field("text") }))],
+            "transcribe" => self.begin(Mode::Transcribe, &event),
+            "detect" => self.begin(Mode::Detect, &event),
+            "audio-start" => self.audio_start(&event),
+            "audio-chunk" => self.audio_chunk(&event),
+            "audio-stop" => self.audio_stop(),
+            "synthesize" => return self.synthesize(&event, out),
+            // Unknown events are ignored so newer clients keep working
+            _ => Vec::new(),
+        };
+        for response in &responses {
+            write_event(out, response)?;
+        }
+        Ok(())
+    }
+    
+    fn begin(&mut self, mode: Mode, event: &WyomingEvent) -> Vec<WyomingEvent> {
+        let available = match mode {
+            Mode::Transcribe => self.services.recognizer.is_some(),
+            _ => self.services.detector.is_some(),
+        };
+        if !available {
+            let service = if mode == Mode::Transcribe { "Speech recognition" } else { "Wake word detection" };
+            return vec![WyomingEvent::error(format!("{} is not served here", service))];
+        }
+        self.mode = mode;
+        if mode == Mode::Detect {
+            self.wake.names = event.data.get("names").and_then(Value::as_array).map(|names| {
+                names.iter().filter_map(Value::as_str).map(str::to_string).collect()
+            });
+        }
+        Vec::new()
+    }
+    
+    fn audio_start(&mut self, event: &WyomingEvent) -> Vec<WyomingEvent> {
+        match AudioFormat::from_event(event) {
+            Ok(format) => {
+                self.format = Some(format);
+                self.recording.clear();
+                self.wake.pending.clear();
+                self.wake.resampler = None;
+                self.wake.detected = false;
+                Vec::new()
+            }
+            Err(err) => vec![WyomingEvent::error(err)],
+        }
+    }
+    
+    fn audio_chunk(&mut self, event: &WyomingEvent) -> Vec<WyomingEvent> {
+        // Chunks carry their own format; it wins over the one from `audio-start`
+        let format = match AudioFormat::from_event(event) {
+            Ok(format) => format,
+            Err(err) => return vec![WyomingEvent::error(err)],
+        };
+        if self.format.map_or(false, |f| f != format) {
+            self.recording.clear();
+            self.wake.resampler = None;
+        }
+        self.format = Some(format);
+        let audio = format.to_mono(&event.payload);
+        
+        match self.audio_mode() {
+            Mode::Transcribe => {
+                if self.recording.len() + audio.len() > MAX_TRANSCRIBE_SECONDS * format.rate as usize {
+                    self.recording.clear();
+                    return vec![WyomingEvent::error(format!(
+                        "Recording exceeds {} seconds", MAX_TRANSCRIBE_SECONDS
+                    ))];
+                }
+                self.recording.extend(audio);
+                Vec::new()
+            }
+            Mode::Detect => self.detect(&audio, format.rate).unwrap_or_else(|err| vec![WyomingEvent::error(err)]),
+            Mode::Idle => Vec::new(),
+        }
+    }
+    
+    fn audio_stop(&mut self) -> Vec<WyomingEvent> {
+        let responses = match self.audio_mode() {
+            Mode::Transcribe => match self.transcribe() {
+                Ok(text) => vec![WyomingEvent::new("transcript", json!({ "text": text }))],
+                Err(err) => vec![WyomingEvent::error(err)],
+            },
+            Mode::Detect if !self.wake.detected => vec![WyomingEvent::new("not-detected", json!({}))],
+            _ => Vec::new(),
+        };
+        self.recording.clear();
+        self.wake.pending.clear();
+        self.wake.resampler = None;
+        self.wake.detected = false;
+        // Each `transcribe` or `detect` covers one audio stream
+        self.mode = Mode::Idle;
+        responses
+    }
+    
+    fn transcribe(&mut self) -> Result<String, String> {
+        let engine = self.services.recognizer.as_ref().ok_or("Speech recognition is not served here")?;
+        let rate = self.format.map_or(RECOGNITION_SAMPLE_RATE, |f| f.rate);
+        let audio = resample_mono(&self.recording, rate, RECOGNITION_SAMPLE_RATE).map_err(|err| err.to_string())?;
+        
+        let guard = engine.lock().unwrap();
+        let engine = guard.as_deref().ok_or_else(|| "Speech recognizer not initialized".to_string())?;
+        let result = engine.transcribe(&audio)?;
+        tracing::debug!(seconds = audio.len() as f64 / RECOGNITION_SAMPLE_RATE as f64, "Wyoming transcription");
+        Ok(result.text)
+    }
+    
+    /// Feed audio to the wake word engine, reporting the first detection of the stream
+    fn detect(&mut self, audio: &[f32], rate: u32) -> Result<Vec<WyomingEvent>, String> {
+        let state = self.services.detector.as_ref().ok_or("Wake word detection is not served here")?;
+        let (frame_length, engine_rate) = state.lock().unwrap().frame_format()?;
+        
+        let mut resampler = match self.wake.resampler.take() {
+            Some((r, resampler)) if r == rate => resampler,
+            _ => MonoResampler::new(rate, engine_rate).map_err(|err| err.to_string())?,
+        };
+        let resampled = resampler.push(audio).map_err(|err| err.to_string())?;
+        self.wake.resampler = Some((rate, resampler));
+        self.wake
+            .pending
+            .extend(resampled.iter().map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16));
+        
+        let mut responses = Vec::new();
+        let mut offset = 0;
+        while self.wake.pending.len() - offset >= frame_length {
+            let frame = &self.wake.pending[offset..offset + frame_length];
+            offset += frame_length;
+            let detection = match state.lock().unwrap().process_frame(frame)? {
+                Some(detection) => detection,
+                None => continue,
+            };
+            let wanted = self.wake.names.as_ref().map_or(true, |names| {
+                names.is_empty() || names.iter().any(|n| n.eq_ignore_ascii_case(&detection.keyword))
+            });
+            if wanted && !self.wake.detected {
+                self.wake.detected = true;
+                responses.push(WyomingEvent::new(
+                    "detection",
+                    json!({ "name": detection.keyword, "timestamp": (detection.stream_time * 1000.0) as u64 }),
+                ));
+            }
+        }
+        self.wake.pending.drain(..offset);
+        Ok(responses)
+    }
+    
+    /// Synthesize the requested text and stream it back as audio events
+    fn synthesize(&mut self, event: &WyomingEvent, out: &mut impl Write) -> io::Result<()> {
+        let tts = match self.services.tts.as_ref() {
+            Some(tts) => tts,
+            None => return write_event(out, &WyomingEvent::error("Text-to-speech is not served here")),
+        };
+        let text = event.str_field("text").unwrap_or_default();
+        let rendered = Python::with_gil(|py| -> PyResult<(Vec<i16>, u32)> { tts.borrow(py).render(py, text) });
+        let (pcm, rate) = match rendered {
+            Ok(rendered) => rendered,
+            Err(err) => return write_event(out, &WyomingEvent::error(err.to_string())),
+        };
+        
+        let format = json!({ "rate": rate, "width": 2, "channels": 1 });
+        write_event(out, &WyomingEvent::new("audio-start", format.clone()))?;
+        for chunk in pcm.chunks(SAMPLES_PER_CHUNK) {
+            let bytes = chunk.iter().flat_map(|s| s.to_le_bytes()).collect();
+            write_event(out, &WyomingEvent::new("audio-chunk", format.clone()).with_payload(bytes))?;
+        }
+        write_event(out, &WyomingEvent::new("audio-stop", json!({})))
+    }
+}
+
+/// Serve one client until it disconnects or the server stops
+fn serve_connection(stream: TcpStream, services: &Services, stop: &AtomicBool) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = io::BufWriter::new(stream);
+    let mut connection = Connection::new(services);
+    while !stop.load(Ordering::Relaxed) {
+        match read_event(&mut reader)? {
+            Some(event) => connection.handle(event, &mut writer)?,
+            None => break,
+        }
+    }
+    Ok(())
+}
+
+/// Listener thread, connection threads and their shared stop flag
+struct Server {
+    address: SocketAddr,
+    stop: AtomicBool,
+    accept: Mutex<Option<JoinHandle<()>>>,
+    connections: Mutex<Vec<(TcpStream, JoinHandle<()>)>>,
+}
+
+impl Resource for Server {
+    /// Stop accepting clients and close every open connection
+    fn shutdown(&self) {
+        self.signal();
+        let accept = self.accept.lock().unwrap().take();
+        lifecycle::join_threads(accept);
+        let handles: Vec<_> = self.connections.lock().unwrap().drain(..).map(|(_, handle)| handle).collect();
+        lifecycle::join_threads(handles);
+    }
+}
+
+impl Server {
+    /// Set the stop flag and close client sockets without waiting for the threads
+    fn signal(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for (stream, _) in self.connections.lock().unwrap().iter() {
+            // Unblocks the connection thread's pending read
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+    
+    fn spawn(listener: TcpListener, services: Services) -> io::Result<Arc<Server>> {
+        let address = listener.local_addr()?;
+        listener.set_nonblocking(true)?;
+        let server = Arc::new(Server {
+            address,
+            stop: AtomicBool::new(false),
+            accept: Mutex::new(None),
+            connections: Mutex::new(Vec::new()),
+        });
+        let services = Arc::new(services);
+        
+        let shared = Arc::clone(&server);
+        let handle = thread::Builder::new().name("jenna-wyoming".to_string()).spawn(move || {
+            while !shared.stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, peer)) => shared.add_connection(stream, peer, &services),
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL),
+                    Err(err) => {
+                        tracing::warn!(error = %err, "Wyoming accept failed");
+                        thread::sleep(ACCEPT_POLL);
+                    }
+                }
+            }
+        })?;
+        *server.accept.lock().unwrap() = Some(handle);
+        Ok(server)
+    }
+    
+    fn add_connection(self: &Arc<Self>, stream: TcpStream, peer: SocketAddr, services: &Arc<Services>) {
+        let spawned = stream.set_nonblocking(false).and_then(|_| stream.try_clone()).and_then(|control| {
+            let services = Arc::clone(services);
+            let server = Arc::clone(self);
+            let _ = stream.set_nodelay(true);
+            let handle = thread::Builder::new().name("jenna-wyoming-client".to_string()).spawn(move || {
+                tracing::debug!(%peer, "Wyoming client connected");
+                match serve_connection(stream, &services, &server.stop) {
+                    Ok(()) => tracing::debug!(%peer, "Wyoming client disconnected"),
+                    Err(err) if server.stop.load(Ordering::Relaxed) => {
+                        tracing::debug!(%peer, error = %err, "Wyoming connection closed on shutdown")
+                    }
+                    Err(err) => tracing::warn!(%peer, error = %err, "Wyoming connection failed"),
+                }
+            })?;
+            Ok((control, handle))
+        });
+        
+        match spawned {
+            Ok(connection) => {
+                let mut connections = self.connections.lock().unwrap();
+                // Forget connections whose threads have already exited
+                connections.retain(|(_, handle)| !handle.is_finished());
+                connections.push(connection);
+            }
+            Err(err) => tracing::warn!(%peer, error = %err, "Failed to start Wyoming connection"),
+        }
+    }
+}
+
+/// Wyoming protocol server exposing Jenna's engines to Home Assistant
+/// 
+/// Serves any combination of a `SpeechRecognizer` (asr), `TextToSpeech`
+/// (tts) and `WakeWordDetector` (wake) over TCP, so Home Assistant's
+/// Wyoming integration can use them as remote voice services. Each client
+/// gets its own thread; audio is converted from whatever rate, width and
+/// channel count the client sends. Recognition uses offline transcription,
+/// so it does not disturb the recognizer's live stream, but the wake word
+/// engine is shared: don't serve a detector that is also listening or part
+/// of a running pipeline, and expect one wake word stream at a time.
+#[pyclass]
+pub struct WyomingServer {
+    recognizer: Option<Py<SpeechRecognizer>>,
+    tts: Option<Py<TextToSpeech>>,
+    detector: Option<Py<WakeWordDetector>>,
+    host: String,
+    port: u16,
+    language: String,
+    server: Option<Arc<Server>>,
+}
+
+impl WyomingServer {
+    /// Resolve the engines and build the `describe` response
+    fn services(&self, py: Python) -> PyResult<Services> {
+        let version = env!("CARGO_PKG_VERSION");
+        let languages = json!([self.language]);
+        let mut info = Map::new();
+        
+        let recognizer = match &self.recognizer {
+            Some(recognizer) => {
+                let (engine, _) = recognizer.borrow(py).shared_engine()?;
+                let name = engine.lock().unwrap().as_deref().map_or("stt", |e| e.name());
+                info.insert("asr".to_string(), json!([{
+                    "name": "jenna-stt",
+                    "description": "Jenna speech recognition",
+                    "attribution": attribution(),
+                    "installed": true,
+                    "version": version,
+                    "models": [{
+                        "name": name,
+                        "description": format!("Jenna {} model", name),
+                        "attribution": attribution(),
+                        "installed": true,
+                        "version": version,
+                        "languages": languages,
+                    }],
+                }]));
+                Some(engine)
+            }
+            None => None,
+        };
+        
+        if let Some(tts) = &self.tts {
+            let (voice, language) = tts.borrow(py).voice_info();
+            let language = language.map_or_else(|| languages.clone(), |l| json!([l]));
+            info.insert("tts".to_string(), json!([{
+                "name": "jenna-tts",
+                "description": "Jenna text-to-speech",
+                "attribution": attribution(),
+                "installed": true,
+                "version": version,
+                "voices": [{
+                    "name": voice,
+                    "description": voice,
+                    "attribution": attribution(),
+                    "installed": true,
+                    "version": version,
+                    "languages": language,
+                }],
+            }]));
+        }
+        
+        let detector = match &self.detector {
+            Some(detector) => {
+                let state = detector.borrow(py).shared_state();
+                let models: Vec<Value> = {
+                    let state = state.lock().unwrap();
+                    state.frame_format().map_err(PyValueError::new_err)?;
+                    state
+                        .keyword_names()
+                        .iter()
+                        .map(|name| json!({
+                            "name": name,
+                            "description": name,
+                            "phrase": name,
+                            "attribution": attribution(),
+                            "installed": true,
+                            "version": version,
+                            "languages": languages,
+                        }))
+                        .collect()
+                };
+                info.insert("wake".to_string(), json!([{
+                    "name": "jenna-wake",
+                    "description": "Jenna wake word detection",
+                    "attribution": attribution(),
+                    "installed": true,
+                    "version": version,
+                    "models": models,
+                }]));
+                Some(state)
+            }
+            None => None,
+        };
+        
+        Ok(Services { recognizer, tts: self.tts.as_ref().map(|t| t.clone_ref(py)), detector, info: Value::Object(info) })
+    }
+    
+    fn running(&self) -> Option<&Arc<Server>> {
+        self.server.as_ref().filter(|server| !server.stop.load(Ordering::Relaxed))
+    }
+}
+
+#[pymethods]
+impl WyomingServer {
+    /// Create a server for the given engines; at least one is required
+    /// 
+    /// `language` is the language code advertised for speech recognition
+    /// and wake words (voices advertise their own). Port 0 picks a free port.
+    #[new]
+    #[pyo3(signature = (recognizer=None, tts=None, detector=None, host="0.0.0.0", port=DEFAULT_PORT, language="en"))]
+    fn new(
+        recognizer: Option<Py<SpeechRecognizer>>,
+        tts: Option<Py<TextToSpeech>>,
+        detector: Option<Py<WakeWordDetector>>,
+        host: &str,
+        port: u16,
+        language: &str,
+    ) -> PyResult<Self> {
+        if recognizer.is_none() && tts.is_none() && detector.is_none() {
+            return Err(PyValueError::new_err("A Wyoming server needs a recognizer, a voice or a wake word detector"));
+        }
+        Ok(WyomingServer {
+            recognizer,
+            tts,
+            detector,
+            host: host.to_string(),
+            port,
+            language: language.to_string(),
+            server: None,
+        })
+    }
+    
+    /// Bind the port and start serving, returning the bound port
+    /// 
+    /// The engines must be initialized. Fails if the server is already running.
+    fn start(&mut self, py: Python) -> PyResult<u16> {
+        if self.running().is_some() {
+            return Err(PyValueError::new_err("Wyoming server is already running"));
+        }
+        let services = self.services(py)?;
+        let address = format!("{}:{}", self.host, self.port);
+        let listener = TcpListener::bind(&address)
+            .map_err(|err| PyValueError::new_err(format!("Failed to bind {}: {}", address, err)))?;
+        let server = Server::spawn(listener, services)
+            .map_err(|err| PyValueError::new_err(format!("Failed to start Wyoming server: {}", err)))?;
+        
+        tracing::info!(address = %server.address, "Wyoming server listening");
+        lifecycle::register(&server);
+        let port = server.address.port();
+        self.server = Some(server);
+        Ok(port)
+    }
+    
+    /// Stop serving and close every client connection
+    fn stop(&mut self, py: Python) {
+        if let Some(server) = self.server.take() {
+            py.allow_threads(|| server.shutdown());
+        }
+    }
+    
+    fn is_running(&self) -> bool {
+        self.running().is_some()
+    }
+    
+    /// Port the server is bound to, or the configured port before `start`
+    #[getter]
+    fn get_port(&self) -> u16 {
+        self.running().map_or(self.port, |server| server.address.port())
+    }
+    
+    /// Number of connected clients
+    #[getter]
+    fn get_connections(&self) -> usize {
+        self.running().map_or(0, |server| {
+            server.connections.lock().unwrap().iter().filter(|(_, handle)| !handle.is_finished()).count()
+        })
+    }
+    
+    fn __repr__(&self) -> String {
+        let services: Vec<&str> = [
+            self.recognizer.as_ref().map(|_| "asr"),
+            self.tts.as_ref().map(|_| "tts"),
+            self.detector.as_ref().map(|_| "wake"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        format!(
+            "WyomingServer(host='{}', port={}, services=[{}], running={})",
+            self.host,
+            self.get_port(),
+            services.join(", "),
+            self.is_running()
+        )
+    }
+}
+
+impl Drop for WyomingServer {
+    fn drop(&mut self) {
+        // Signal without joining (see `VoicePipeline`'s Drop): a client
+        // thread may be waiting for the GIL to synthesize speech
+        if let Some(server) = &self.server {
+            server.signal();
+        }
+    }
+}